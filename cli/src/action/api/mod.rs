@@ -188,6 +188,43 @@ impl SplinterRestClient {
             })
     }
 
+    /// Creates a backup of the Splinter node and returns the backup archive.
+    #[cfg(feature = "backup")]
+    pub fn create_backup(&self) -> Result<Vec<u8>, CliError> {
+        Client::new()
+            .post(&format!("{}/admin/backup", self.url))
+            .header("Authorization", &self.auth)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to create backup: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.bytes().map(|bytes| bytes.to_vec()).map_err(|err| {
+                        CliError::ActionError(format!(
+                            "Request was successful, but failed to read backup archive: {}",
+                            err
+                        ))
+                    })
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Backup request failed with status code '{}', but error response \
+                                 was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to create backup: {}",
+                        message
+                    )))
+                }
+            })
+    }
+
     /// Lists all REST API permissions for a Splinter node.
     pub fn list_permissions(&self) -> Result<Vec<Permission>, CliError> {
         Client::new()
@@ -0,0 +1,152 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::action::api::{ServerError, SplinterRestClient};
+use crate::error::CliError;
+
+// The API keys protocol version supported by the current CLI
+const CLI_API_KEYS_PROTOCOL_VERSION: &str = "1";
+
+impl SplinterRestClient {
+    /// Submits a request to create an API key, returning the key and its token.
+    pub fn create_api_key(
+        &self,
+        display_name: &str,
+        scopes: Vec<String>,
+        expires_at: Option<u64>,
+    ) -> Result<ClientCreateApiKeyResponse, CliError> {
+        Client::new()
+            .post(&format!("{}/api-keys", self.url))
+            .header("SplinterProtocolVersion", CLI_API_KEYS_PROTOCOL_VERSION)
+            .header("Authorization", &self.auth)
+            .json(&ClientCreateApiKeyPayload {
+                display_name,
+                scopes,
+                expires_at,
+            })
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to create API key: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.json::<ClientCreateApiKeyResponse>().map_err(|_| {
+                        CliError::ActionError(
+                            "Create API key request succeeded, but response was not valid"
+                                .to_string(),
+                        )
+                    })
+                } else {
+                    Err(request_failed(
+                        res,
+                        "Create API key",
+                        "Failed to create API key",
+                    ))
+                }
+            })
+    }
+
+    /// Submits a request to list the node's API keys.
+    pub fn list_api_keys(&self) -> Result<ClientApiKeyListResponse, CliError> {
+        Client::new()
+            .get(&format!("{}/api-keys", self.url))
+            .header("SplinterProtocolVersion", CLI_API_KEYS_PROTOCOL_VERSION)
+            .header("Authorization", &self.auth)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to list API keys: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.json::<ClientApiKeyListResponse>().map_err(|_| {
+                        CliError::ActionError(
+                            "List API keys request succeeded, but response was not valid"
+                                .to_string(),
+                        )
+                    })
+                } else {
+                    Err(request_failed(
+                        res,
+                        "List API keys",
+                        "Failed to list API keys",
+                    ))
+                }
+            })
+    }
+
+    /// Submits a request to revoke an API key.
+    pub fn revoke_api_key(&self, id: &str) -> Result<(), CliError> {
+        Client::new()
+            .delete(&format!("{}/api-keys/{}", self.url, id))
+            .header("SplinterProtocolVersion", CLI_API_KEYS_PROTOCOL_VERSION)
+            .header("Authorization", &self.auth)
+            .send()
+            .map_err(|err| {
+                CliError::ActionError(format!("Failed to revoke API key {}: {}", id, err))
+            })
+            .and_then(|res| {
+                if res.status().is_success() {
+                    Ok(())
+                } else {
+                    Err(request_failed(
+                        res,
+                        "Revoke API key",
+                        &format!("Failed to revoke API key {}", id),
+                    ))
+                }
+            })
+    }
+}
+
+/// Converts the error response of a failed request into a `CliError`.
+fn request_failed(res: reqwest::blocking::Response, request: &str, failure: &str) -> CliError {
+    let status = res.status();
+    match res.json::<ServerError>() {
+        Ok(err) => CliError::ActionError(format!("{}: {}", failure, err.message)),
+        Err(_) => CliError::ActionError(format!(
+            "{} request failed with status code '{}', but error response was not valid",
+            request, status
+        )),
+    }
+}
+
+#[derive(Serialize)]
+struct ClientCreateApiKeyPayload<'a> {
+    display_name: &'a str,
+    scopes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClientCreateApiKeyResponse {
+    pub data: ClientApiKey,
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClientApiKeyListResponse {
+    pub data: Vec<ClientApiKey>,
+}
+
+/// An API key, specific to the client to allow for deserializing the response data.
+#[derive(Debug, Deserialize)]
+pub struct ClientApiKey {
+    pub id: String,
+    pub display_name: String,
+    pub scopes: Vec<String>,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+}
@@ -0,0 +1,186 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Actions for handling API key subcommands.
+
+mod api;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::ArgMatches;
+
+use crate::error::CliError;
+use crate::signing::{create_cylinder_jwt_auth, load_signer};
+
+use super::api::{SplinterRestClient, SplinterRestClientBuilder};
+use super::{print_table, Action, DEFAULT_SPLINTER_REST_API_URL, SPLINTER_REST_API_URL_ENV};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+/// The action responsible for creating an API key.
+///
+/// The specific args for this action:
+///
+/// * name: a human-readable name for the key
+/// * scopes: the permissions the key is limited to; if none are given, the key is not limited
+/// * expires_in: the number of days until the key expires; if not given, the key does not expire
+/// * format: specifies the output format; one of "human" or "json"
+pub struct CreateApiKeyAction;
+
+impl Action for CreateApiKeyAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let name = args
+            .value_of("name")
+            .ok_or_else(|| CliError::ActionError("A name must be specified".into()))?;
+        let scopes = args
+            .values_of("scopes")
+            .map(|values| values.map(String::from).collect())
+            .unwrap_or_default();
+        let expires_at = args
+            .value_of("expires_in")
+            .map(|days| {
+                let days = days
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|days| *days > 0)
+                    .ok_or_else(|| {
+                        CliError::ActionError("'expires-in' must be a positive integer".into())
+                    })?;
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_err(|err| CliError::ActionError(err.to_string()))?;
+                Ok(now.as_secs() + days * SECONDS_PER_DAY)
+            })
+            .transpose()?;
+
+        let created = new_client(args)?.create_api_key(name, scopes, expires_at)?;
+
+        match args.value_of("format").unwrap_or("human") {
+            "json" => println!(
+                "\n {}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "id": created.data.id,
+                    "token": created.token,
+                }))
+                .map_err(|err| {
+                    CliError::ActionError(format!("Cannot format API key into json: {}", err))
+                })?
+            ),
+            _ => {
+                println!("ID: {}", created.data.id);
+                println!("Token: {}", created.token);
+                println!();
+                println!("The token will not be shown again. Clients authenticate with the header");
+                println!("\"Authorization: Bearer ApiKey:<token>\".");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The action responsible for listing the API keys of a node.
+///
+/// The specific args for this action:
+///
+/// * format: specifies the output format; one of "human", "json" or "csv"
+pub struct ListApiKeysAction;
+
+impl Action for ListApiKeysAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let format = args.value_of("format").unwrap_or("human");
+
+        let api_keys = new_client(args)?.list_api_keys()?;
+
+        let data = std::iter::once(vec![
+            "ID".to_string(),
+            "NAME".to_string(),
+            "SCOPES".to_string(),
+            "CREATED".to_string(),
+            "EXPIRES".to_string(),
+        ])
+        .chain(api_keys.data.into_iter().map(|api_key| {
+            vec![
+                api_key.id,
+                api_key.display_name,
+                if api_key.scopes.is_empty() {
+                    "*".to_string()
+                } else {
+                    api_key.scopes.join(" ")
+                },
+                api_key.created_at.to_string(),
+                api_key
+                    .expires_at
+                    .map(|expires_at| expires_at.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ]
+        }));
+
+        match format {
+            "csv" => {
+                for row in data {
+                    println!("{}", row.join(","))
+                }
+            }
+            "json" => println!(
+                "\n {}",
+                serde_json::to_string_pretty(&data.collect::<Vec<_>>()).map_err(|err| {
+                    CliError::ActionError(format!("Cannot format API keys into json: {}", err))
+                })?
+            ),
+            _ => print_table(data.collect()),
+        }
+
+        Ok(())
+    }
+}
+
+/// The action responsible for revoking an API key.
+///
+/// The specific args for this action:
+///
+/// * id: the ID of the API key to revoke
+pub struct RevokeApiKeyAction;
+
+impl Action for RevokeApiKeyAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let id = args
+            .value_of("id")
+            .ok_or_else(|| CliError::ActionError("An API key ID must be specified".into()))?;
+
+        new_client(args)?.revoke_api_key(id)
+    }
+}
+
+/// Creates a REST client from the common `url` and `private_key_file` args.
+fn new_client(args: &ArgMatches) -> Result<SplinterRestClient, CliError> {
+    let url = args
+        .value_of("url")
+        .map(ToOwned::to_owned)
+        .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+        .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+    let signer = load_signer(args.value_of("private_key_file"))?;
+
+    SplinterRestClientBuilder::new()
+        .with_url(url)
+        .with_auth(create_cylinder_jwt_auth(signer)?)
+        .build()
+}
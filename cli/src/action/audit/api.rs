@@ -0,0 +1,92 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use reqwest::blocking::Client;
+use serde::Deserialize;
+
+use crate::action::api::{ServerError, SplinterRestClient};
+use crate::error::CliError;
+
+// The audit protocol version supported by the current CLI
+const CLI_AUDIT_PROTOCOL_VERSION: &str = "1";
+
+impl SplinterRestClient {
+    /// Submits a request to list the node's audit log entries, newest first.
+    pub fn list_audit_entries(
+        &self,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<ClientAuditEntryListResponse, CliError> {
+        let mut query = vec![];
+        if let Some(offset) = offset {
+            query.push(("offset", offset));
+        }
+        if let Some(limit) = limit {
+            query.push(("limit", limit));
+        }
+
+        Client::new()
+            .get(&format!("{}/audit", self.url))
+            .query(&query)
+            .header("SplinterProtocolVersion", CLI_AUDIT_PROTOCOL_VERSION)
+            .header("Authorization", &self.auth)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to list audit entries: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.json::<ClientAuditEntryListResponse>().map_err(|_| {
+                        CliError::ActionError(
+                            "List audit entries request succeeded, but response was not valid"
+                                .to_string(),
+                        )
+                    })
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "List audit entries request failed with status code '{}', but \
+                                error response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to list audit entries: {}",
+                        message
+                    )))
+                }
+            })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClientAuditEntryListResponse {
+    pub data: Vec<ClientAuditEntry>,
+}
+
+/// An audit log entry, specific to the client to allow for deserializing the response data.
+#[derive(Debug, Deserialize)]
+pub struct ClientAuditEntry {
+    pub timestamp: u64,
+    pub identity: Option<String>,
+    pub method: String,
+    pub endpoint: String,
+    pub result: String,
+    pub status_code: u16,
+    pub action: Option<String>,
+    pub circuit_id: Option<String>,
+}
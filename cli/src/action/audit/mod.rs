@@ -0,0 +1,109 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Actions for handling audit log subcommands.
+
+mod api;
+
+use clap::ArgMatches;
+
+use crate::error::CliError;
+use crate::signing::{create_cylinder_jwt_auth, load_signer};
+
+use super::api::SplinterRestClientBuilder;
+use super::{print_table, Action, DEFAULT_SPLINTER_REST_API_URL, SPLINTER_REST_API_URL_ENV};
+
+/// The action responsible for listing the audit log entries of a node, newest first.
+///
+/// The specific args for this action:
+///
+/// * url: specifies the URL of the splinter node to be queried; falls back to the environment
+///   variable SPLINTER_REST_API_URL
+/// * format: specifies the output format; one of "human", "json" or "csv"
+/// * offset: the number of entries to skip
+/// * limit: the maximum number of entries to list
+pub struct ListAuditEntriesAction;
+
+impl Action for ListAuditEntriesAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let format = args.value_of("format").unwrap_or("human");
+        let url = args
+            .value_of("url")
+            .map(ToOwned::to_owned)
+            .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+        let offset = parse_usize_arg(args, "offset")?;
+        let limit = parse_usize_arg(args, "limit")?;
+
+        let signer = load_signer(args.value_of("private_key_file"))?;
+
+        let entries = SplinterRestClientBuilder::new()
+            .with_url(url)
+            .with_auth(create_cylinder_jwt_auth(signer)?)
+            .build()?
+            .list_audit_entries(offset, limit)?;
+
+        let data = std::iter::once(vec![
+            "TIMESTAMP".to_string(),
+            "IDENTITY".to_string(),
+            "METHOD".to_string(),
+            "ENDPOINT".to_string(),
+            "RESULT".to_string(),
+            "STATUS".to_string(),
+            "ACTION".to_string(),
+            "CIRCUIT".to_string(),
+        ])
+        .chain(entries.data.into_iter().map(|entry| {
+            vec![
+                entry.timestamp.to_string(),
+                entry.identity.unwrap_or_else(|| "-".to_string()),
+                entry.method,
+                entry.endpoint,
+                entry.result,
+                entry.status_code.to_string(),
+                entry.action.unwrap_or_else(|| "-".to_string()),
+                entry.circuit_id.unwrap_or_else(|| "-".to_string()),
+            ]
+        }));
+
+        match format {
+            "csv" => {
+                for row in data {
+                    println!("{}", row.join(","))
+                }
+            }
+            "json" => println!(
+                "\n {}",
+                serde_json::to_string_pretty(&data.collect::<Vec<_>>()).map_err(|err| {
+                    CliError::ActionError(format!("Cannot format audit entries into json: {}", err))
+                })?
+            ),
+            _ => print_table(data.collect()),
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_usize_arg(args: &ArgMatches, name: &str) -> Result<Option<usize>, CliError> {
+    args.value_of(name)
+        .map(|value| {
+            value.parse::<usize>().map_err(|_| {
+                CliError::ActionError(format!("'{}' must be a non-negative integer", name))
+            })
+        })
+        .transpose()
+}
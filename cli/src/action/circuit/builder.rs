@@ -14,6 +14,10 @@
 
 #[cfg(feature = "challenge-authorization")]
 use splinter::admin::messages::AuthorizationType;
+#[cfg(feature = "circuit-durability")]
+use splinter::admin::messages::DurabilityType;
+#[cfg(feature = "circuit-relay")]
+use splinter::admin::messages::RouteType;
 use splinter::admin::messages::{
     BuilderError, CircuitStatus, CreateCircuit, CreateCircuitBuilder, SplinterNode,
     SplinterNodeBuilder, SplinterServiceBuilder,
@@ -32,6 +36,10 @@ pub struct CreateCircuitMessageBuilder {
     management_type: Option<String>,
     #[cfg(feature = "challenge-authorization")]
     authorization_type: Option<AuthorizationType>,
+    #[cfg(feature = "circuit-durability")]
+    durability: Option<DurabilityType>,
+    #[cfg(feature = "circuit-relay")]
+    route_type: Option<RouteType>,
     application_metadata: Vec<u8>,
     comments: Option<String>,
     display_name: Option<String>,
@@ -48,6 +56,10 @@ impl CreateCircuitMessageBuilder {
             management_type: None,
             #[cfg(feature = "challenge-authorization")]
             authorization_type: None,
+            #[cfg(feature = "circuit-durability")]
+            durability: None,
+            #[cfg(feature = "circuit-relay")]
+            route_type: None,
             application_metadata: vec![],
             comments: None,
             display_name: None,
@@ -246,6 +258,40 @@ impl CreateCircuitMessageBuilder {
         Ok(())
     }
 
+    #[cfg(feature = "circuit-durability")]
+    pub fn set_durability(&mut self, durability: &str) -> Result<(), CliError> {
+        let durability_type = match durability {
+            "none" => DurabilityType::NoDurability,
+            "store-and-forward" => DurabilityType::StoreAndForward,
+            _ => {
+                return Err(CliError::ActionError(format!(
+                    "Invalid durability type {}",
+                    durability
+                )))
+            }
+        };
+
+        self.durability = Some(durability_type);
+        Ok(())
+    }
+
+    #[cfg(feature = "circuit-relay")]
+    pub fn set_route_type(&mut self, route_type: &str) -> Result<(), CliError> {
+        let route_type = match route_type {
+            "any" => RouteType::Any,
+            "relay" => RouteType::Relay,
+            _ => {
+                return Err(CliError::ActionError(format!(
+                    "Invalid route type {}",
+                    route_type
+                )))
+            }
+        };
+
+        self.route_type = Some(route_type);
+        Ok(())
+    }
+
     pub fn set_application_metadata(&mut self, application_metadata: &[u8]) {
         self.application_metadata = application_metadata.into();
     }
@@ -349,6 +395,18 @@ impl CreateCircuitMessageBuilder {
             None => create_circuit_builder,
         };
 
+        #[cfg(feature = "circuit-durability")]
+        let create_circuit_builder = match self.durability {
+            Some(durability) => create_circuit_builder.with_durability(&durability),
+            None => create_circuit_builder,
+        };
+
+        #[cfg(feature = "circuit-relay")]
+        let create_circuit_builder = match self.route_type {
+            Some(route_type) => create_circuit_builder.with_routes(&route_type),
+            None => create_circuit_builder,
+        };
+
         let create_circuit = create_circuit_builder.build().map_err(|err| {
             CliError::ActionError(format!(
                 "Failed to build circuit: {}",
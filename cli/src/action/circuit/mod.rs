@@ -138,6 +138,16 @@ impl Action for CircuitProposeAction {
             None => (),
         }
 
+        #[cfg(feature = "circuit-durability")]
+        if let Some(durability) = args.value_of("durability") {
+            builder.set_durability(durability)?;
+        }
+
+        #[cfg(feature = "circuit-relay")]
+        if let Some(route_type) = args.value_of("route_type") {
+            builder.set_route_type(route_type)?;
+        }
+
         if let Some(management_type) = args.value_of("management_type") {
             builder.set_management_type(management_type);
         }
@@ -933,6 +943,240 @@ fn request_proposal_removal(
     }
 }
 
+#[cfg(feature = "circuit-update")]
+struct CircuitUpdateRoster {
+    circuit_id: String,
+    add_services: Vec<SplinterService>,
+    remove_services: Vec<String>,
+}
+
+#[cfg(feature = "circuit-update")]
+pub struct CircuitUpdateRosterAction;
+
+#[cfg(feature = "circuit-update")]
+impl Action for CircuitUpdateRosterAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+        let (url, signer, circuit_id) = parse_circuit_update_args(args)?;
+
+        let service_types = args
+            .values_of("service_type")
+            .map(|service_types| {
+                service_types
+                    .map(parse_service_type_argument)
+                    .collect::<Result<Vec<(String, String)>, CliError>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let service_arguments = args
+            .values_of("service_argument")
+            .map(|service_arguments| {
+                service_arguments
+                    .map(parse_service_argument)
+                    .collect::<Result<Vec<(String, (String, String))>, CliError>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut add_services = vec![];
+        if let Some(services) = args.values_of("add_service") {
+            for service in services {
+                let (service_id, allowed_nodes) = parse_service(service)?;
+                let service_type = service_types
+                    .iter()
+                    .find(|(service_id_match, _)| service_id_match == &service_id)
+                    .map(|(_, service_type)| service_type.to_string())
+                    .ok_or_else(|| {
+                        CliError::ActionError(format!(
+                            "Missing service type for service '{}'",
+                            service_id
+                        ))
+                    })?;
+                let arguments = service_arguments
+                    .iter()
+                    .filter(|(service_id_match, _)| service_id_match == &service_id)
+                    .map(|(_, argument)| argument.clone())
+                    .collect();
+
+                add_services.push(SplinterService {
+                    service_id,
+                    service_type,
+                    allowed_nodes,
+                    arguments,
+                });
+            }
+        }
+
+        let remove_services = args
+            .values_of("remove_service")
+            .map(|services| services.map(String::from).collect())
+            .unwrap_or_default();
+
+        let circuit_update_roster = CircuitUpdateRoster {
+            circuit_id: circuit_id.into(),
+            add_services,
+            remove_services,
+        };
+
+        propose_circuit_update(&url, signer, circuit_id, circuit_update_roster)
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+struct CircuitUpdateAddNode {
+    circuit_id: String,
+    node: SplinterNode,
+}
+
+#[cfg(feature = "circuit-update")]
+pub struct CircuitUpdateAddNodeAction;
+
+#[cfg(feature = "circuit-update")]
+impl Action for CircuitUpdateAddNodeAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+        let (url, signer, circuit_id) = parse_circuit_update_args(args)?;
+
+        let (node_id, endpoints) = parse_node_argument(
+            args.value_of("node")
+                .ok_or_else(|| CliError::ActionError("'node' argument is required".into()))?,
+        )?;
+
+        #[cfg(feature = "challenge-authorization")]
+        let public_key = args
+            .value_of("node_public_key")
+            .map(builder::parse_hex)
+            .transpose()?;
+
+        let circuit_update_add_node = CircuitUpdateAddNode {
+            circuit_id: circuit_id.into(),
+            node: SplinterNode {
+                node_id,
+                endpoints,
+                #[cfg(feature = "challenge-authorization")]
+                public_key,
+            },
+        };
+
+        propose_circuit_update(&url, signer, circuit_id, circuit_update_add_node)
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+struct CircuitUpdateRemoveNode {
+    circuit_id: String,
+    node_id: String,
+}
+
+#[cfg(feature = "circuit-update")]
+pub struct CircuitUpdateRemoveNodeAction;
+
+#[cfg(feature = "circuit-update")]
+impl Action for CircuitUpdateRemoveNodeAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+        let (url, signer, circuit_id) = parse_circuit_update_args(args)?;
+
+        let node_id = args
+            .value_of("node_id")
+            .ok_or_else(|| CliError::ActionError("'node-id' argument is required".into()))?;
+
+        let circuit_update_remove_node = CircuitUpdateRemoveNode {
+            circuit_id: circuit_id.into(),
+            node_id: node_id.into(),
+        };
+
+        propose_circuit_update(&url, signer, circuit_id, circuit_update_remove_node)
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+struct CircuitUpdateApplicationMetadata {
+    circuit_id: String,
+    application_metadata: Vec<u8>,
+}
+
+#[cfg(feature = "circuit-update")]
+pub struct CircuitUpdateApplicationMetadataAction;
+
+#[cfg(feature = "circuit-update")]
+impl Action for CircuitUpdateApplicationMetadataAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+        let (url, signer, circuit_id) = parse_circuit_update_args(args)?;
+
+        let application_metadata = args
+            .value_of("metadata")
+            .ok_or_else(|| CliError::ActionError("'metadata' argument is required".into()))?;
+
+        let circuit_update_application_metadata = CircuitUpdateApplicationMetadata {
+            circuit_id: circuit_id.into(),
+            application_metadata: application_metadata.as_bytes().to_vec(),
+        };
+
+        propose_circuit_update(
+            &url,
+            signer,
+            circuit_id,
+            circuit_update_application_metadata,
+        )
+    }
+}
+
+/// Returns the REST API URL, the signer and the ID of the circuit to update, which are common to
+/// the arguments of every circuit update action
+#[cfg(feature = "circuit-update")]
+fn parse_circuit_update_args<'a>(
+    args: &'a ArgMatches,
+) -> Result<(String, Box<dyn Signer>, &'a str), CliError> {
+    let url = args
+        .value_of("url")
+        .map(ToOwned::to_owned)
+        .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+        .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+    let signer = load_signer(args.value_of("private_key_file"))?;
+
+    let circuit_id = args
+        .value_of("circuit_id")
+        .ok_or_else(|| CliError::ActionError("'circuit-id' argument is required".into()))?;
+
+    Ok((url, signer, circuit_id))
+}
+
+/// Submits a signed circuit update request, once it has been verified that the circuit being
+/// updated exists
+#[cfg(feature = "circuit-update")]
+fn propose_circuit_update<M, A>(
+    url: &str,
+    signer: Box<dyn Signer>,
+    circuit_id: &str,
+    circuit_update_request: A,
+) -> Result<(), CliError>
+where
+    M: protobuf::Message + payload::ApplyToEnvelope,
+    A: payload::CircuitAction<M>,
+{
+    let client = SplinterRestClientBuilder::new()
+        .with_url(url.to_string())
+        .with_auth(create_cylinder_jwt_auth(signer.clone())?)
+        .build()?;
+
+    let requester_node = client.get_node_status()?.node_id;
+    let circuit = client.fetch_circuit(circuit_id)?;
+
+    if circuit.is_some() {
+        let signed_payload = make_signed_payload(&requester_node, signer, circuit_update_request)?;
+        client.submit_admin_payload(signed_payload)
+    } else {
+        Err(CliError::ActionError(format!(
+            "Circuit '{}' does not exist",
+            circuit_id
+        )))
+    }
+}
+
 pub struct CircuitListAction;
 
 impl Action for CircuitListAction {
@@ -23,11 +23,21 @@ use splinter::protos::admin::{
     CircuitManagementPayload_Action as Action, CircuitManagementPayload_Header as Header,
     CircuitProposalVote, CircuitProposalVote_Vote, CircuitPurgeRequest,
 };
+#[cfg(feature = "circuit-update")]
+use splinter::protos::admin::{
+    CircuitUpdateAddNodeRequest, CircuitUpdateApplicationMetadataRequest,
+    CircuitUpdateRemoveNodeRequest, CircuitUpdateRosterRequest, SplinterService,
+};
 
 use crate::error::CliError;
 
 use super::RemoveProposal;
 use super::{AbandonedCircuit, CircuitDisband, CircuitPurge};
+#[cfg(feature = "circuit-update")]
+use super::{
+    CircuitUpdateAddNode, CircuitUpdateApplicationMetadata, CircuitUpdateRemoveNode,
+    CircuitUpdateRoster,
+};
 use super::{CircuitVote, Vote};
 
 /// A circuit action that has a type and can be converted into a protobuf-serializable struct.
@@ -211,3 +221,102 @@ impl ApplyToEnvelope for ProposalRemoveRequest {
         circuit_management_payload.set_proposal_remove_request(self);
     }
 }
+
+#[cfg(feature = "circuit-update")]
+impl CircuitAction<CircuitUpdateRosterRequest> for CircuitUpdateRoster {
+    fn action_type(&self) -> Action {
+        Action::CIRCUIT_UPDATE_ROSTER_REQUEST
+    }
+
+    fn into_proto(self) -> Result<CircuitUpdateRosterRequest, CliError> {
+        let mut update_roster_request = CircuitUpdateRosterRequest::new();
+        update_roster_request.set_circuit_id(self.circuit_id);
+        update_roster_request.set_add_services(
+            self.add_services
+                .into_iter()
+                .map(|service| service.into_proto())
+                .collect(),
+        );
+        update_roster_request.set_remove_services(
+            self.remove_services
+                .into_iter()
+                .map(|service_id| {
+                    let mut service = SplinterService::new();
+                    service.set_service_id(service_id);
+                    service
+                })
+                .collect(),
+        );
+        Ok(update_roster_request)
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+impl ApplyToEnvelope for CircuitUpdateRosterRequest {
+    fn apply(self, circuit_management_payload: &mut CircuitManagementPayload) {
+        circuit_management_payload.set_circuit_update_roster_request(self);
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+impl CircuitAction<CircuitUpdateAddNodeRequest> for CircuitUpdateAddNode {
+    fn action_type(&self) -> Action {
+        Action::CIRCUIT_UPDATE_ADD_NODE
+    }
+
+    fn into_proto(self) -> Result<CircuitUpdateAddNodeRequest, CliError> {
+        let mut add_node_request = CircuitUpdateAddNodeRequest::new();
+        add_node_request.set_circuit_id(self.circuit_id);
+        add_node_request.set_node(self.node.into_proto());
+        Ok(add_node_request)
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+impl ApplyToEnvelope for CircuitUpdateAddNodeRequest {
+    fn apply(self, circuit_management_payload: &mut CircuitManagementPayload) {
+        circuit_management_payload.set_circuit_update_add_node(self);
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+impl CircuitAction<CircuitUpdateRemoveNodeRequest> for CircuitUpdateRemoveNode {
+    fn action_type(&self) -> Action {
+        Action::CIRCUIT_UPDATE_REMOVE_NODE
+    }
+
+    fn into_proto(self) -> Result<CircuitUpdateRemoveNodeRequest, CliError> {
+        let mut remove_node_request = CircuitUpdateRemoveNodeRequest::new();
+        remove_node_request.set_circuit_id(self.circuit_id);
+        remove_node_request.set_node_id(self.node_id);
+        Ok(remove_node_request)
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+impl ApplyToEnvelope for CircuitUpdateRemoveNodeRequest {
+    fn apply(self, circuit_management_payload: &mut CircuitManagementPayload) {
+        circuit_management_payload.set_circuit_update_remove_node(self);
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+impl CircuitAction<CircuitUpdateApplicationMetadataRequest> for CircuitUpdateApplicationMetadata {
+    fn action_type(&self) -> Action {
+        Action::CIRCUIT_UPDATE_APPLICATION_METADATA_REQUEST
+    }
+
+    fn into_proto(self) -> Result<CircuitUpdateApplicationMetadataRequest, CliError> {
+        let mut application_metadata_request = CircuitUpdateApplicationMetadataRequest::new();
+        application_metadata_request.set_circuit_id(self.circuit_id);
+        application_metadata_request.set_application_metedata(self.application_metadata);
+        Ok(application_metadata_request)
+    }
+}
+
+#[cfg(feature = "circuit-update")]
+impl ApplyToEnvelope for CircuitUpdateApplicationMetadataRequest {
+    fn apply(self, circuit_management_payload: &mut CircuitManagementPayload) {
+        circuit_management_payload.set_circuit_update_application_metadata_request(self);
+    }
+}
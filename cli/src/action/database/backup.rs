@@ -0,0 +1,134 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the actions for backing up and restoring a Splinter node
+
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use clap::ArgMatches;
+use splinter::backup::{create_backup, restore_backup};
+
+use crate::error::CliError;
+use crate::signing::{create_cylinder_jwt_auth, load_signer};
+use crate::Action;
+
+use super::super::{
+    api::SplinterRestClientBuilder, DEFAULT_SPLINTER_REST_API_URL, SPLINTER_REST_API_URL_ENV,
+};
+use super::migrate_data::store_factory;
+use super::SplinterEnvironment;
+
+pub struct BackupAction;
+
+impl Action for BackupAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let output = args
+            .value_of("output")
+            .ok_or_else(|| CliError::ActionError("'output' argument is required".into()))?;
+
+        let archive = match args.value_of("connect") {
+            // Offline backup of a stopped node
+            Some(url) => {
+                let store_factory = store_factory(url)?;
+                let state_dir = state_dir(args);
+                info!(
+                    "Creating backup of database {} and state directory {}",
+                    url,
+                    state_dir.display()
+                );
+
+                let mut archive = vec![];
+                create_backup(&*store_factory, &state_dir, &mut archive).map_err(|err| {
+                    CliError::ActionError(format!("Unable to create backup: {}", err))
+                })?;
+                archive
+            }
+            // Online backup of a running node
+            None => {
+                let url = args
+                    .value_of("url")
+                    .map(ToOwned::to_owned)
+                    .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+                    .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+                let signer = load_signer(args.value_of("private_key_file"))?;
+                info!("Creating backup of node {}", url);
+
+                SplinterRestClientBuilder::new()
+                    .with_url(url)
+                    .with_auth(create_cylinder_jwt_auth(signer)?)
+                    .build()?
+                    .create_backup()?
+            }
+        };
+
+        fs::write(output, archive).map_err(|err| {
+            CliError::ActionError(format!("Unable to write backup to {}: {}", output, err))
+        })?;
+        info!("Wrote backup to {}", output);
+
+        Ok(())
+    }
+}
+
+pub struct RestoreAction;
+
+impl Action for RestoreAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let input = args
+            .value_of("input")
+            .ok_or_else(|| CliError::ActionError("'input' argument is required".into()))?;
+        let url = match args.value_of("connect") {
+            Some(url) => url.to_owned(),
+            None => super::get_default_database()?,
+        };
+        let state_dir = state_dir(args);
+
+        let file = File::open(input).map_err(|err| {
+            CliError::ActionError(format!("Unable to open backup {}: {}", input, err))
+        })?;
+        let store_factory = store_factory(&url)?;
+
+        let manifest = restore_backup(BufReader::new(file), &*store_factory, &state_dir)
+            .map_err(|err| CliError::ActionError(format!("Unable to restore backup: {}", err)))?;
+
+        for (name, count) in manifest.counts() {
+            info!("Restored {} {}", count, name);
+        }
+        info!(
+            "Restored {} state file(s) to {}",
+            manifest.state_files().len(),
+            state_dir.display()
+        );
+        info!(
+            "Successfully restored backup created by Splinter {} to {}",
+            manifest.splinter_version(),
+            url
+        );
+
+        Ok(())
+    }
+}
+
+/// Returns the state directory argument, or the node's default state directory
+fn state_dir(args: &ArgMatches<'_>) -> PathBuf {
+    args.value_of("state_dir")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| SplinterEnvironment::load().get_state_path())
+}
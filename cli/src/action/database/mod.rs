@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "backup")]
+mod backup;
+#[cfg(feature = "migrate-data")]
+mod migrate_data;
 #[cfg(feature = "sqlite")]
 mod sqlite;
 
@@ -27,6 +31,10 @@ use diesel::{connection::Connection as _, pg::PgConnection};
 
 use splinter::migrations::run_postgres_migrations;
 
+#[cfg(feature = "backup")]
+pub use self::backup::{BackupAction, RestoreAction};
+#[cfg(feature = "migrate-data")]
+pub use self::migrate_data::MigrateDataAction;
 #[cfg(feature = "sqlite")]
 use self::sqlite::{get_default_database, sqlite_migrations};
 #[cfg(feature = "upgrade")]
@@ -15,6 +15,10 @@
 
 pub mod admin;
 mod api;
+#[cfg(feature = "api-keys")]
+pub mod api_key;
+#[cfg(feature = "audit")]
+pub mod audit;
 pub mod certs;
 pub mod circuit;
 #[cfg(feature = "database")]
@@ -16,11 +16,14 @@ use std::fmt;
 
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
+use splinter::registry::Node;
 use std::collections::HashMap;
 
 use crate::action::api::{ServerError, SplinterRestClient};
 use crate::error::CliError;
 
+const PAGING_LIMIT: u32 = 100;
+
 impl SplinterRestClient {
     /// Adds a new node to the registry.
     pub fn add_node(&self, node: &RegistryNode) -> Result<(), CliError> {
@@ -55,6 +58,124 @@ impl SplinterRestClient {
             })
     }
 
+    /// Lists all of the nodes in the node's local read-write registry, following the paging links
+    /// until every node has been retrieved. Nodes from read-only registries are not included.
+    pub fn list_writable_nodes(&self) -> Result<Vec<RegistryNode>, CliError> {
+        let mut nodes = vec![];
+        let mut link = format!("/registry/nodes?writable=true&limit={}", PAGING_LIMIT);
+
+        loop {
+            let request = Client::new()
+                .get(&format!("{}{}", self.url, link))
+                .header("Authorization", &self.auth);
+
+            let page = request
+                .send()
+                .map_err(|err| CliError::ActionError(format!("Failed to list nodes: {}", err)))
+                .and_then(|res| {
+                    let status = res.status();
+                    if status.is_success() {
+                        res.json::<RegistryNodeListSlice>().map_err(|_| {
+                            CliError::ActionError(
+                                "Request was successful, but received an invalid response".into(),
+                            )
+                        })
+                    } else {
+                        let message = res
+                            .json::<ServerError>()
+                            .map_err(|_| {
+                                CliError::ActionError(format!(
+                                    "Registry list nodes request failed with status code '{}', \
+                                     but error response was not valid",
+                                    status
+                                ))
+                            })?
+                            .message;
+
+                        Err(CliError::ActionError(format!(
+                            "Failed to list nodes: {}",
+                            message
+                        )))
+                    }
+                })?;
+
+            let received = page.data.len();
+            nodes.extend(page.data);
+
+            if received == 0 || page.paging.offset + page.paging.limit >= page.paging.total {
+                break;
+            }
+            link = page.paging.next;
+        }
+
+        Ok(nodes)
+    }
+
+    /// Replaces the node with the same identity in the registry.
+    pub fn update_node(&self, node: &RegistryNode) -> Result<(), CliError> {
+        let request = Client::new()
+            .put(&format!("{}/registry/nodes/{}", self.url, &node.identity))
+            .json(&node)
+            .header("Authorization", &self.auth);
+
+        request
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to update node: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    Ok(())
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Registry update node request failed with status code '{}', but error response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to update node: {}",
+                        message
+                    )))
+                }
+            })
+    }
+
+    /// Removes the node with the given identity from the registry.
+    pub fn delete_node(&self, identity: &str) -> Result<(), CliError> {
+        let request = Client::new()
+            .delete(&format!("{}/registry/nodes/{}", self.url, identity))
+            .header("Authorization", &self.auth);
+
+        request
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to delete node: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    Ok(())
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Registry delete node request failed with status code '{}', but error response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to delete node: {}",
+                        message
+                    )))
+                }
+            })
+    }
+
     /// Retrieves the node with the given identity from the registry.
     pub fn get_node(&self, identity: &str) -> Result<Option<RegistryNode>, CliError> {
         let request = Client::new()
@@ -92,7 +213,7 @@ impl SplinterRestClient {
 }
 
 #[cfg(feature = "registry")]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct RegistryNode {
     pub identity: String,
     pub endpoints: Vec<String>,
@@ -101,6 +222,35 @@ pub struct RegistryNode {
     pub metadata: HashMap<String, String>,
 }
 
+#[cfg(feature = "registry")]
+impl From<Node> for RegistryNode {
+    fn from(node: Node) -> Self {
+        RegistryNode {
+            identity: node.identity().to_string(),
+            endpoints: node.endpoints().to_vec(),
+            display_name: node.display_name().to_string(),
+            keys: node.keys().to_vec(),
+            metadata: node.metadata().clone(),
+        }
+    }
+}
+
+#[cfg(feature = "registry")]
+#[derive(Debug, Deserialize)]
+struct RegistryNodeListSlice {
+    data: Vec<RegistryNode>,
+    paging: Paging,
+}
+
+#[cfg(feature = "registry")]
+#[derive(Debug, Deserialize)]
+struct Paging {
+    offset: usize,
+    limit: usize,
+    total: usize,
+    next: String,
+}
+
 #[cfg(feature = "registry")]
 impl fmt::Display for RegistryNode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
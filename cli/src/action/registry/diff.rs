@@ -0,0 +1,239 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comparison of a registry document against the nodes in a registry.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::api::RegistryNode;
+
+/// A difference between a node in the registry and the same node in a registry document.
+#[derive(Debug, PartialEq)]
+pub enum NodeChange {
+    /// The node is in the document, but not in the registry.
+    Added(RegistryNode),
+    /// The node is in the registry, but not in the document.
+    Removed(RegistryNode),
+    /// The node is in both, but with different values.
+    Changed {
+        previous: RegistryNode,
+        node: RegistryNode,
+    },
+}
+
+impl NodeChange {
+    pub fn identity(&self) -> &str {
+        match self {
+            NodeChange::Added(node) => &node.identity,
+            NodeChange::Removed(node) => &node.identity,
+            NodeChange::Changed { node, .. } => &node.identity,
+        }
+    }
+}
+
+impl fmt::Display for NodeChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeChange::Added(node) => write!(f, "+ {}", node.identity),
+            NodeChange::Removed(node) => write!(f, "- {}", node.identity),
+            NodeChange::Changed { previous, node } => {
+                write!(f, "~ {}", node.identity)?;
+
+                if previous.display_name != node.display_name {
+                    write!(
+                        f,
+                        "\n    display name: {} -> {}",
+                        previous.display_name, node.display_name
+                    )?;
+                }
+
+                write_list_diff(f, "endpoints", &previous.endpoints, &node.endpoints)?;
+                write_list_diff(f, "keys", &previous.keys, &node.keys)?;
+
+                let previous_metadata: BTreeMap<_, _> = previous.metadata.iter().collect();
+                let metadata: BTreeMap<_, _> = node.metadata.iter().collect();
+                if previous_metadata != metadata {
+                    write!(f, "\n    metadata:")?;
+                    for (key, value) in &previous_metadata {
+                        match metadata.get(key) {
+                            None => write!(f, "\n      - {}: {}", key, value)?,
+                            Some(new_value) if new_value != value => {
+                                write!(f, "\n      ~ {}: {} -> {}", key, value, new_value)?
+                            }
+                            _ => (),
+                        }
+                    }
+                    for (key, value) in &metadata {
+                        if !previous_metadata.contains_key(key) {
+                            write!(f, "\n      + {}: {}", key, value)?;
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Writes the entries that were removed from and added to a list of node values, if the list was
+/// changed.
+fn write_list_diff(
+    f: &mut fmt::Formatter,
+    name: &str,
+    previous: &[String],
+    current: &[String],
+) -> fmt::Result {
+    if previous == current {
+        return Ok(());
+    }
+
+    write!(f, "\n    {}:", name)?;
+    for value in previous.iter().filter(|value| !current.contains(value)) {
+        write!(f, "\n      - {}", value)?;
+    }
+    for value in current.iter().filter(|value| !previous.contains(value)) {
+        write!(f, "\n      + {}", value)?;
+    }
+    // Only the order of the entries changed
+    if previous.iter().all(|value| current.contains(value))
+        && current.iter().all(|value| previous.contains(value))
+    {
+        write!(f, "\n      (reordered)")?;
+    }
+
+    Ok(())
+}
+
+/// Compares the nodes in the registry with the nodes in a registry document, returning the
+/// changes that would make the registry match the document, sorted by node identity.
+pub fn diff_nodes(
+    registry_nodes: &[RegistryNode],
+    document_nodes: &[RegistryNode],
+) -> Vec<NodeChange> {
+    let mut registry_by_id: BTreeMap<&str, &RegistryNode> = registry_nodes
+        .iter()
+        .map(|node| (node.identity.as_str(), node))
+        .collect();
+
+    let mut changes = document_nodes
+        .iter()
+        .filter_map(|node| match registry_by_id.remove(node.identity.as_str()) {
+            None => Some(NodeChange::Added(node.clone())),
+            Some(previous) if previous != node => Some(NodeChange::Changed {
+                previous: previous.clone(),
+                node: node.clone(),
+            }),
+            Some(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    changes.extend(
+        registry_by_id
+            .into_iter()
+            .map(|(_, node)| NodeChange::Removed(node.clone())),
+    );
+
+    changes.sort_by(|a, b| a.identity().cmp(b.identity()));
+    changes
+}
+
+/// Returns the identities that appear more than once in the list of nodes.
+pub fn duplicate_identities(nodes: &[RegistryNode]) -> Vec<&str> {
+    let mut seen = BTreeSet::new();
+    let duplicates: BTreeSet<&str> = nodes
+        .iter()
+        .map(|node| node.identity.as_str())
+        .filter(|identity| !seen.insert(*identity))
+        .collect();
+    duplicates.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    fn new_node(identity: &str, endpoint: &str, key: &str) -> RegistryNode {
+        RegistryNode {
+            identity: identity.into(),
+            endpoints: vec![endpoint.into()],
+            display_name: identity.into(),
+            keys: vec![key.into()],
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Verify that nodes only in the document are added, nodes only in the registry are removed,
+    /// and nodes with different values are changed, with the changes sorted by identity.
+    #[test]
+    fn diff_added_removed_changed() {
+        let node_a = new_node("node-a", "tcps://a:8044", "aaaa");
+        let node_b = new_node("node-b", "tcps://b:8044", "bbbb");
+        let node_c = new_node("node-c", "tcps://c:8044", "cccc");
+        let mut changed_b = node_b.clone();
+        changed_b.keys = vec!["dddd".into()];
+        changed_b
+            .metadata
+            .insert("company".into(), "Cargill".into());
+
+        let changes = diff_nodes(
+            &[node_b.clone(), node_a.clone()],
+            &[node_c.clone(), changed_b.clone()],
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                NodeChange::Removed(node_a),
+                NodeChange::Changed {
+                    previous: node_b,
+                    node: changed_b,
+                },
+                NodeChange::Added(node_c),
+            ]
+        );
+
+        assert_eq!(
+            changes[1].to_string(),
+            "~ node-b\n    keys:\n      - bbbb\n      + dddd\n    metadata:\n      + company: Cargill"
+        );
+    }
+
+    /// Verify that identical node lists produce no changes.
+    #[test]
+    fn diff_no_changes() {
+        let nodes = vec![
+            new_node("node-a", "tcps://a:8044", "aaaa"),
+            new_node("node-b", "tcps://b:8044", "bbbb"),
+        ];
+
+        assert!(diff_nodes(&nodes, &nodes).is_empty());
+    }
+
+    /// Verify that identities used by more than one node are reported once each.
+    #[test]
+    fn duplicates() {
+        let nodes = vec![
+            new_node("node-a", "tcps://a:8044", "aaaa"),
+            new_node("node-b", "tcps://b:8044", "bbbb"),
+            new_node("node-a", "tcps://c:8044", "cccc"),
+            new_node("node-a", "tcps://d:8044", "dddd"),
+        ];
+
+        assert_eq!(duplicate_identities(&nodes), vec!["node-a"]);
+    }
+}
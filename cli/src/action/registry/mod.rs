@@ -14,11 +14,15 @@
 
 #[cfg(feature = "registry")]
 mod api;
+#[cfg(feature = "registry")]
+mod diff;
 
 use clap::ArgMatches;
 use splinter::registry::{Node, YamlNode};
 #[cfg(feature = "registry")]
 use std::collections::HashMap;
+#[cfg(feature = "registry")]
+use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
@@ -26,8 +30,12 @@ use std::path::Path;
 use crate::error::CliError;
 #[cfg(feature = "registry")]
 use crate::registry::api::RegistryNode;
+#[cfg(feature = "registry")]
+use crate::registry::diff::{diff_nodes, duplicate_identities, NodeChange};
 use crate::signing::{create_cylinder_jwt_auth, load_signer};
 
+#[cfg(feature = "registry")]
+use super::api::SplinterRestClient;
 use super::api::SplinterRestClientBuilder;
 use super::{msg_from_io_error, Action, DEFAULT_SPLINTER_REST_API_URL, SPLINTER_REST_API_URL_ENV};
 
@@ -263,6 +271,254 @@ impl Action for RegistryAddAction {
     }
 }
 
+#[cfg(feature = "registry")]
+pub struct RegistryExportAction;
+
+#[cfg(feature = "registry")]
+impl Action for RegistryExportAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let client = create_client(args)?;
+        let mut nodes = client.list_writable_nodes()?;
+        nodes.sort_by(|a, b| a.identity.cmp(&b.identity));
+
+        let document = match args.value_of("format").unwrap_or("yaml") {
+            "json" => serde_json::to_string_pretty(&nodes).map_err(|err| {
+                CliError::ActionError(format!("Cannot format node list into json: {}", err))
+            })?,
+            _ => serde_yaml::to_string(&nodes).map_err(|err| {
+                CliError::ActionError(format!("Cannot format node list into yaml: {}", err))
+            })?,
+        };
+
+        match args.value_of("file") {
+            Some(output_file) => {
+                let mut file = File::create(output_file).map_err(|err| {
+                    CliError::ActionError(format!(
+                        "Failed to create or overwrite '{}': {}",
+                        output_file,
+                        msg_from_io_error(err)
+                    ))
+                })?;
+                writeln!(file, "{}", document).map_err(|err| {
+                    CliError::ActionError(format!(
+                        "Failed to write to file '{}': {}",
+                        output_file,
+                        msg_from_io_error(err)
+                    ))
+                })?;
+
+                info!("Exported {} nodes to '{}'", nodes.len(), output_file);
+            }
+            None => println!("{}", document),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "registry")]
+pub struct RegistryDiffAction;
+
+#[cfg(feature = "registry")]
+impl Action for RegistryDiffAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let document_nodes =
+            read_registry_document(args.value_of("file").ok_or_else(|| {
+                CliError::ActionError("A registry file must be specified".into())
+            })?)?;
+
+        let client = create_client(args)?;
+        let changes = diff_nodes(&client.list_writable_nodes()?, &document_nodes);
+
+        if changes.is_empty() {
+            info!("No differences found");
+        }
+        for change in &changes {
+            info!("{}", change);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "registry")]
+pub struct RegistryImportAction;
+
+#[cfg(feature = "registry")]
+impl Action for RegistryImportAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let document_nodes =
+            read_registry_document(args.value_of("file").ok_or_else(|| {
+                CliError::ActionError("A registry file must be specified".into())
+            })?)?;
+
+        let client = create_client(args)?;
+        let prune = args.is_present("prune");
+
+        let mut changes = diff_nodes(&client.list_writable_nodes()?, &document_nodes);
+        if !prune {
+            changes.retain(|change| match change {
+                NodeChange::Removed(node) => {
+                    info!(
+                        "Node '{}' is not in the registry file; use '--prune' to remove it",
+                        node.identity
+                    );
+                    false
+                }
+                _ => true,
+            });
+        }
+
+        if changes.is_empty() {
+            info!("Registry is up to date");
+            return Ok(());
+        }
+        for change in &changes {
+            info!("{}", change);
+        }
+
+        if args.is_present("dry_run") {
+            return Ok(());
+        }
+
+        // Removals and updates are applied before additions, so that endpoints released by one
+        // node may be taken by a new node in the same import
+        changes.sort_by_key(|change| match change {
+            NodeChange::Removed(_) => 0,
+            NodeChange::Changed { .. } => 1,
+            NodeChange::Added(_) => 2,
+        });
+
+        let mut applied = vec![];
+        for change in changes {
+            let result = match &change {
+                NodeChange::Added(node) => client.add_node(node),
+                NodeChange::Removed(node) => client.delete_node(&node.identity),
+                NodeChange::Changed { node, .. } => client.update_node(node),
+            };
+
+            if let Err(err) = result {
+                error!(
+                    "Failed to import node '{}'; reverting {} applied changes",
+                    change.identity(),
+                    applied.len()
+                );
+                return match rollback(&client, applied) {
+                    Ok(()) => Err(err),
+                    Err(rollback_err) => Err(CliError::ActionError(format!(
+                        "{}; reverting the applied changes also failed, the registry may be \
+                         partially imported: {}",
+                        err, rollback_err
+                    ))),
+                };
+            }
+
+            applied.push(change);
+        }
+
+        info!("Imported {} changes", applied.len());
+
+        Ok(())
+    }
+}
+
+/// Reverts the applied changes in the reverse order they were made. This is best effort: changes
+/// made by others since the import started are not detected and may be overwritten.
+#[cfg(feature = "registry")]
+fn rollback(client: &SplinterRestClient, applied: Vec<NodeChange>) -> Result<(), CliError> {
+    for change in applied.into_iter().rev() {
+        match &change {
+            NodeChange::Added(node) => client.delete_node(&node.identity),
+            NodeChange::Removed(node) => client.add_node(node),
+            NodeChange::Changed { previous, .. } => client.update_node(previous),
+        }?;
+    }
+    Ok(())
+}
+
+/// Creates a REST API client from the `url` and `private_key_file` arguments.
+#[cfg(feature = "registry")]
+fn create_client(args: &ArgMatches) -> Result<SplinterRestClient, CliError> {
+    let url = args
+        .value_of("url")
+        .map(ToOwned::to_owned)
+        .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+        .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+    let signer = load_signer(args.value_of("private_key_file"))?;
+
+    SplinterRestClientBuilder::new()
+        .with_url(url)
+        .with_auth(create_cylinder_jwt_auth(signer)?)
+        .build()
+}
+
+/// Reads and validates a registry document, a list of nodes in either YAML or JSON. Files with
+/// a `.json` extension are read as JSON; all others are read as YAML.
+#[cfg(feature = "registry")]
+fn read_registry_document(file_name: &str) -> Result<Vec<RegistryNode>, CliError> {
+    let file = File::open(file_name).map_err(|err| {
+        CliError::EnvironmentError(format!(
+            "Failed to open '{}': {}",
+            file_name,
+            msg_from_io_error(err)
+        ))
+    })?;
+
+    let is_json = Path::new(file_name)
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
+    let yaml_nodes: Vec<YamlNode> = if is_json {
+        serde_json::from_reader(file).map_err(|err| {
+            CliError::ActionError(format!(
+                "Failed to read registry file '{}': Not a valid JSON list of nodes: {}",
+                file_name, err
+            ))
+        })?
+    } else {
+        serde_yaml::from_reader(file).map_err(|err| {
+            CliError::ActionError(format!(
+                "Failed to read registry file '{}': Not a valid YAML sequence of nodes: {}",
+                file_name, err
+            ))
+        })?
+    };
+
+    let nodes = yaml_nodes
+        .into_iter()
+        .map(|yaml_node| {
+            let identity = yaml_node.identity().to_string();
+            Node::try_from(yaml_node)
+                .map(RegistryNode::from)
+                .map_err(|err| {
+                    CliError::ActionError(format!(
+                        "Invalid node '{}' in registry file '{}': {}",
+                        identity, file_name, err
+                    ))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let duplicates = duplicate_identities(&nodes);
+    if !duplicates.is_empty() {
+        return Err(CliError::ActionError(format!(
+            "Registry file '{}' contains duplicate nodes: {}",
+            file_name,
+            duplicates.join(", ")
+        )));
+    }
+
+    Ok(nodes)
+}
+
 #[cfg(feature = "registry")]
 fn parse_metadata(metadata: &str) -> Result<(String, String), CliError> {
     let mut parts = metadata.splitn(2, ':');
@@ -263,6 +263,30 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
             .help("Authorization type for the circuit"),
     );
 
+    #[cfg(feature = "circuit-durability")]
+    let propose_circuit = propose_circuit.arg(
+        Arg::with_name("durability")
+            .long("durability")
+            .possible_values(&["none", "store-and-forward"])
+            .takes_value(true)
+            .help(
+                "How direct messages to other nodes are delivered; store-and-forward messages \
+                 are stored until the recipient's node acknowledges them",
+            ),
+    );
+
+    #[cfg(feature = "circuit-relay")]
+    let propose_circuit = propose_circuit.arg(
+        Arg::with_name("route_type")
+            .long("route-type")
+            .possible_values(&["any", "relay"])
+            .takes_value(true)
+            .help(
+                "How messages are routed between nodes; relay circuits forward messages through \
+                 another member node when the recipient's node is not connected",
+            ),
+    );
+
     #[cfg(feature = "challenge-authorization")]
     let propose_circuit = propose_circuit.arg(
         Arg::with_name("node_public_key")
@@ -595,6 +619,188 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
             ),
     );
 
+    #[cfg(feature = "circuit-update")]
+    let add_node_subcommand = SubCommand::with_name("add-node")
+        .about("Propose to add a node to an existing circuit")
+        .arg(
+            Arg::with_name("url")
+                .short("U")
+                .long("url")
+                .takes_value(true)
+                .help("URL of Splinter Daemon"),
+        )
+        .arg(
+            Arg::with_name("private_key_file")
+                .value_name("private-key-file")
+                .short("k")
+                .long("key")
+                .takes_value(true)
+                .help("Path to private key file"),
+        )
+        .arg(
+            Arg::with_name("node")
+                .long("node")
+                .takes_value(true)
+                .required(true)
+                .help(
+                    "Node to add to the circuit \
+                     (<node_id>::<endpoint1>,<endpoint2>)",
+                ),
+        )
+        .arg(
+            Arg::with_name("circuit_id")
+                .value_name("circuit-id")
+                .takes_value(true)
+                .required(true)
+                .help("ID of the circuit to be updated"),
+        );
+
+    #[cfg(all(feature = "circuit-update", feature = "challenge-authorization"))]
+    let add_node_subcommand = add_node_subcommand.arg(
+        Arg::with_name("node_public_key")
+            .long("node-public-key")
+            .takes_value(true)
+            .help("Public key of the node that will be used in challenge authorization"),
+    );
+
+    #[cfg(feature = "circuit-update")]
+    let circuit_command = circuit_command
+        .subcommand(
+            SubCommand::with_name("update-roster")
+                .about("Propose to add or remove services of an existing circuit")
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of Splinter Daemon"),
+                )
+                .arg(
+                    Arg::with_name("private_key_file")
+                        .value_name("private-key-file")
+                        .short("k")
+                        .long("key")
+                        .takes_value(true)
+                        .help("Path to private key file"),
+                )
+                .arg(
+                    Arg::with_name("add_service")
+                        .long("add-service")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required_unless("remove_service")
+                        .help(
+                            "Service ID and allowed node of a service to add \
+                             (<service-id>::<allowed_node>)",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("service_type")
+                        .long("service-type")
+                        .takes_value(true)
+                        .multiple(true)
+                        .requires("add_service")
+                        .help(
+                            "Service type of a service to add \
+                             (<service_id>::<service_type>)",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("service_argument")
+                        .long("service-arg")
+                        .takes_value(true)
+                        .multiple(true)
+                        .requires("add_service")
+                        .help(
+                            "Pass arguments to a service to add \
+                             (<service_id>::<key>=<value>)",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("remove_service")
+                        .long("remove-service")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required_unless("add_service")
+                        .help("ID of a service to remove"),
+                )
+                .arg(
+                    Arg::with_name("circuit_id")
+                        .value_name("circuit-id")
+                        .takes_value(true)
+                        .required(true)
+                        .help("ID of the circuit to be updated"),
+                ),
+        )
+        .subcommand(add_node_subcommand)
+        .subcommand(
+            SubCommand::with_name("remove-node")
+                .about("Propose to remove a node from an existing circuit")
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of Splinter Daemon"),
+                )
+                .arg(
+                    Arg::with_name("private_key_file")
+                        .value_name("private-key-file")
+                        .short("k")
+                        .long("key")
+                        .takes_value(true)
+                        .help("Path to private key file"),
+                )
+                .arg(
+                    Arg::with_name("circuit_id")
+                        .value_name("circuit-id")
+                        .takes_value(true)
+                        .required(true)
+                        .help("ID of the circuit to be updated"),
+                )
+                .arg(
+                    Arg::with_name("node_id")
+                        .value_name("node-id")
+                        .takes_value(true)
+                        .required(true)
+                        .help("ID of the node to remove from the circuit"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("update-metadata")
+                .about("Propose to replace the application metadata of an existing circuit")
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of Splinter Daemon"),
+                )
+                .arg(
+                    Arg::with_name("private_key_file")
+                        .value_name("private-key-file")
+                        .short("k")
+                        .long("key")
+                        .takes_value(true)
+                        .help("Path to private key file"),
+                )
+                .arg(
+                    Arg::with_name("metadata")
+                        .long("metadata")
+                        .value_name("application_metadata")
+                        .takes_value(true)
+                        .required(true)
+                        .help("New application metadata of the circuit"),
+                )
+                .arg(
+                    Arg::with_name("circuit_id")
+                        .value_name("circuit-id")
+                        .takes_value(true)
+                        .required(true)
+                        .help("ID of the circuit to be updated"),
+                ),
+        );
+
     #[cfg(not(feature = "https-certs"))]
     let cert_generate_subcommand = SubCommand::with_name("generate")
         .long_about(
@@ -898,6 +1104,101 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
             ),
     );
 
+    #[cfg(feature = "registry")]
+    let registry_command = registry_command
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("Export the nodes in the registry to a YAML or JSON file")
+                .arg(
+                    Arg::with_name("file")
+                        .long("file")
+                        .takes_value(true)
+                        .help("Path of file to write the nodes to; defaults to stdout"),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .short("F")
+                        .long("format")
+                        .help("Output format")
+                        .possible_values(&["yaml", "json"])
+                        .default_value("yaml")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("private_key_file")
+                        .value_name("private-key-file")
+                        .short("k")
+                        .long("key")
+                        .takes_value(true)
+                        .help("Name or path of private key to be used for REST API authorization"),
+                )
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of the splinter REST API"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("Show the differences between a registry file and the registry")
+                .arg(
+                    Arg::with_name("file")
+                        .required(true)
+                        .help("Path of YAML or JSON registry file to compare"),
+                )
+                .arg(
+                    Arg::with_name("private_key_file")
+                        .value_name("private-key-file")
+                        .short("k")
+                        .long("key")
+                        .takes_value(true)
+                        .help("Name or path of private key to be used for REST API authorization"),
+                )
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of the splinter REST API"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("import")
+                .about("Apply a registry file to the registry")
+                .arg(
+                    Arg::with_name("dry_run")
+                        .long("dry-run")
+                        .help("Show the expected changes without applying them"),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .required(true)
+                        .help("Path of YAML or JSON registry file to import"),
+                )
+                .arg(
+                    Arg::with_name("prune")
+                        .long("prune")
+                        .help("Remove nodes from the registry that are not in the file"),
+                )
+                .arg(
+                    Arg::with_name("private_key_file")
+                        .value_name("private-key-file")
+                        .short("k")
+                        .long("key")
+                        .takes_value(true)
+                        .help("Name or path of private key to be used for REST API authorization"),
+                )
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of the splinter REST API"),
+                ),
+        );
+
     app = app.subcommand(registry_command);
 
     #[cfg(feature = "health")]
@@ -933,21 +1234,133 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
 
     #[cfg(feature = "database")]
     {
-        app = app.subcommand(
-            SubCommand::with_name("database")
-                .about("Database commands")
-                .setting(AppSettings::SubcommandRequiredElseHelp)
-                .subcommand(
-                    SubCommand::with_name("migrate")
-                        .about("Runs database migrations Splinter")
-                        .arg(
-                            Arg::with_name("connect")
-                                .short("C")
-                                .takes_value(true)
-                                .help("Database connection URI"),
+        let database_command = SubCommand::with_name("database")
+            .about("Database commands")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(
+                SubCommand::with_name("migrate")
+                    .about("Runs database migrations Splinter")
+                    .arg(
+                        Arg::with_name("connect")
+                            .short("C")
+                            .takes_value(true)
+                            .help("Database connection URI"),
+                    ),
+            );
+
+        #[cfg(feature = "migrate-data")]
+        let database_command = database_command.subcommand(
+            SubCommand::with_name("migrate-data")
+                .about("Copies all Splinter data from one database to another")
+                .arg(
+                    Arg::with_name("from")
+                        .long("from")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Connection URI of the database to copy data from"),
+                )
+                .arg(
+                    Arg::with_name("to")
+                        .long("to")
+                        .takes_value(true)
+                        .required(true)
+                        .help(
+                            "Connection URI of the database to copy data to; migrations must \
+                             have been run and it must not contain any data",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("resume")
+                        .long("resume")
+                        .help(
+                            "Continue a previous migration that failed partway through; data \
+                             that was already copied is skipped",
                         ),
                 ),
-        )
+        );
+
+        #[cfg(feature = "backup")]
+        let database_command = database_command
+            .subcommand(
+                SubCommand::with_name("backup")
+                    .about("Creates a backup archive of a Splinter node")
+                    .arg(
+                        Arg::with_name("output")
+                            .short("o")
+                            .long("output")
+                            .takes_value(true)
+                            .required(true)
+                            .help("Path of the backup archive to create"),
+                    )
+                    .arg(
+                        Arg::with_name("url")
+                            .short("U")
+                            .long("url")
+                            .takes_value(true)
+                            .conflicts_with_all(&["connect", "state_dir"])
+                            .help("URL of the Splinter daemon REST API"),
+                    )
+                    .arg(
+                        Arg::with_name("private_key_file")
+                            .value_name("private-key-file")
+                            .short("k")
+                            .long("key")
+                            .takes_value(true)
+                            .conflicts_with_all(&["connect", "state_dir"])
+                            .help("Name or path of private key"),
+                    )
+                    .arg(
+                        Arg::with_name("connect")
+                            .short("C")
+                            .long("connect")
+                            .takes_value(true)
+                            .help(
+                                "Database connection URI of a stopped node to back up directly, \
+                                 instead of using the REST API",
+                            ),
+                    )
+                    .arg(
+                        Arg::with_name("state_dir")
+                            .value_name("state-dir")
+                            .long("state-dir")
+                            .short("S")
+                            .takes_value(true)
+                            .requires("connect")
+                            .help("State directory of the stopped node to back up"),
+                    ),
+            )
+            .subcommand(
+                SubCommand::with_name("restore")
+                    .about("Restores a backup archive onto a fresh Splinter node")
+                    .arg(
+                        Arg::with_name("input")
+                            .short("i")
+                            .long("input")
+                            .takes_value(true)
+                            .required(true)
+                            .help("Path of the backup archive to restore"),
+                    )
+                    .arg(
+                        Arg::with_name("connect")
+                            .short("C")
+                            .long("connect")
+                            .takes_value(true)
+                            .help(
+                                "Connection URI of the database to restore to; migrations must \
+                                 have been run and it must not contain any data",
+                            ),
+                    )
+                    .arg(
+                        Arg::with_name("state_dir")
+                            .value_name("state-dir")
+                            .long("state-dir")
+                            .short("S")
+                            .takes_value(true)
+                            .help("State directory to restore the service state to"),
+                    ),
+            );
+
+        app = app.subcommand(database_command)
     }
 
     #[cfg(feature = "upgrade")]
@@ -1553,6 +1966,169 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
             ),
     );
 
+    #[cfg(feature = "api-keys")]
+    {
+        app = app.subcommand(
+            SubCommand::with_name("apikey")
+                .about("Splinter API key commands")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .about("Create an API key for a machine client")
+                        .arg(
+                            Arg::with_name("name")
+                                .long("name")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Human-readable name of the API key"),
+                        )
+                        .arg(
+                            Arg::with_name("scopes")
+                                .long("scope")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .help(
+                                    "Permission the API key is limited to; may be specified \
+                                    multiple times. If not specified, the key is not limited",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("expires_in")
+                                .long("expires-in")
+                                .value_name("days")
+                                .takes_value(true)
+                                .help("Number of days until the API key expires"),
+                        )
+                        .arg(
+                            Arg::with_name("format")
+                                .short("F")
+                                .long("format")
+                                .help("Output format")
+                                .possible_values(&["human", "json"])
+                                .default_value("human")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("url")
+                                .short("U")
+                                .long("url")
+                                .help("URL of the Splinter daemon REST API")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("private_key_file")
+                                .value_name("private-key-file")
+                                .short("k")
+                                .long("key")
+                                .takes_value(true)
+                                .help("Name or path of private key"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List the API keys of a node")
+                        .arg(
+                            Arg::with_name("format")
+                                .short("F")
+                                .long("format")
+                                .help("Output format")
+                                .possible_values(&["human", "csv", "json"])
+                                .default_value("human")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("url")
+                                .short("U")
+                                .long("url")
+                                .help("URL of the Splinter daemon REST API")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("private_key_file")
+                                .value_name("private-key-file")
+                                .short("k")
+                                .long("key")
+                                .takes_value(true)
+                                .help("Name or path of private key"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("revoke")
+                        .about("Revoke an API key")
+                        .arg(
+                            Arg::with_name("id")
+                                .takes_value(true)
+                                .required(true)
+                                .help("ID of the API key to revoke"),
+                        )
+                        .arg(
+                            Arg::with_name("url")
+                                .short("U")
+                                .long("url")
+                                .help("URL of the Splinter daemon REST API")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("private_key_file")
+                                .value_name("private-key-file")
+                                .short("k")
+                                .long("key")
+                                .takes_value(true)
+                                .help("Name or path of private key"),
+                        ),
+                ),
+        );
+    }
+
+    #[cfg(feature = "audit")]
+    {
+        app = app.subcommand(
+            SubCommand::with_name("audit")
+                .about("Splinter audit log commands")
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List the audit log entries of REST API requests, newest first")
+                        .arg(
+                            Arg::with_name("format")
+                                .short("F")
+                                .long("format")
+                                .help("Output format")
+                                .possible_values(&["human", "csv", "json"])
+                                .default_value("human")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("url")
+                                .short("U")
+                                .long("url")
+                                .help("URL of the Splinter daemon REST API")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("private_key_file")
+                                .value_name("private-key-file")
+                                .short("k")
+                                .long("key")
+                                .takes_value(true)
+                                .help("Name or path of private key"),
+                        )
+                        .arg(
+                            Arg::with_name("offset")
+                                .long("offset")
+                                .takes_value(true)
+                                .help("Number of entries to skip"),
+                        )
+                        .arg(
+                            Arg::with_name("limit")
+                                .long("limit")
+                                .takes_value(true)
+                                .help("Maximum number of entries to list"),
+                        ),
+                ),
+        );
+    }
+
     #[cfg(feature = "user-list")]
     {
         app = app.subcommand(
@@ -1646,6 +2222,16 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
     let circuit_command =
         circuit_command.with_command("remove-proposal", circuit::RemoveProposalAction);
 
+    #[cfg(feature = "circuit-update")]
+    let circuit_command = circuit_command
+        .with_command("update-roster", circuit::CircuitUpdateRosterAction)
+        .with_command("add-node", circuit::CircuitUpdateAddNodeAction)
+        .with_command("remove-node", circuit::CircuitUpdateRemoveNodeAction)
+        .with_command(
+            "update-metadata",
+            circuit::CircuitUpdateApplicationMetadataAction,
+        );
+
     #[cfg(feature = "circuit-template")]
     let circuit_command = circuit_command.with_command(
         "template",
@@ -1661,7 +2247,11 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
         SubcommandActions::new().with_command("build", registry::RegistryGenerateAction);
 
     #[cfg(feature = "registry")]
-    let registry_command = registry_command.with_command("add", registry::RegistryAddAction);
+    let registry_command = registry_command
+        .with_command("add", registry::RegistryAddAction)
+        .with_command("diff", registry::RegistryDiffAction)
+        .with_command("export", registry::RegistryExportAction)
+        .with_command("import", registry::RegistryImportAction);
 
     subcommands = subcommands.with_command("registry", registry_command);
 
@@ -1677,10 +2267,19 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
     #[cfg(feature = "database")]
     {
         use action::database;
-        subcommands = subcommands.with_command(
-            "database",
-            SubcommandActions::new().with_command("migrate", database::MigrateAction),
-        )
+        let database_command =
+            SubcommandActions::new().with_command("migrate", database::MigrateAction);
+
+        #[cfg(feature = "migrate-data")]
+        let database_command =
+            database_command.with_command("migrate-data", database::MigrateDataAction);
+
+        #[cfg(feature = "backup")]
+        let database_command = database_command
+            .with_command("backup", database::BackupAction)
+            .with_command("restore", database::RestoreAction);
+
+        subcommands = subcommands.with_command("database", database_command)
     }
 
     #[cfg(feature = "upgrade")]
@@ -1726,6 +2325,27 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
 
     subcommands = subcommands.with_command("permissions", permissions::ListAction);
 
+    #[cfg(feature = "api-keys")]
+    {
+        use action::api_key;
+        subcommands = subcommands.with_command(
+            "apikey",
+            SubcommandActions::new()
+                .with_command("create", api_key::CreateApiKeyAction)
+                .with_command("list", api_key::ListApiKeysAction)
+                .with_command("revoke", api_key::RevokeApiKeyAction),
+        )
+    }
+
+    #[cfg(feature = "audit")]
+    {
+        use action::audit;
+        subcommands = subcommands.with_command(
+            "audit",
+            SubcommandActions::new().with_command("list", audit::ListAuditEntriesAction),
+        )
+    }
+
     #[cfg(feature = "user-list")]
     {
         use action::user;
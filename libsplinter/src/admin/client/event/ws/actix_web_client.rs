@@ -359,6 +359,7 @@ impl From<messages::CircuitProposal> for ProposalSlice {
             UpdateRoster => "UpdateRoster",
             AddNode => "AddNode",
             RemoveNode => "RemoveNode",
+            UpdateApplicationMetadata => "UpdateApplicationMetadata",
             Disband => "Disband",
         }
         .to_owned();
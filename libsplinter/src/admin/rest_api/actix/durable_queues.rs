@@ -0,0 +1,168 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the `GET /admin/durable-queues` endpoint for listing the queues of
+//! unacknowledged direct messages sent on store-and-forward circuits.
+
+use actix_web::{error::BlockingError, web, Error, HttpRequest, HttpResponse};
+use futures::Future;
+
+#[cfg(feature = "authorization")]
+use crate::admin::rest_api::CIRCUIT_READ_PERMISSION;
+use crate::circuit::durable::DurableMessageStore;
+use crate::protocol;
+use crate::rest_api::{
+    actix_web_1::{Method, ProtocolVersionRangeGuard, Resource},
+    ErrorResponse,
+};
+
+use super::super::resources::v2::durable_queues::{
+    DurableQueueResponse, ListDurableQueuesResponse,
+};
+
+pub fn make_list_durable_queues_resource(store: Box<dyn DurableMessageStore>) -> Resource {
+    let resource =
+        Resource::build("/admin/durable-queues").add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::ADMIN_LIST_DURABLE_QUEUES_MIN,
+            protocol::ADMIN_PROTOCOL_VERSION,
+        ));
+    #[cfg(feature = "authorization")]
+    {
+        resource.add_method(Method::Get, CIRCUIT_READ_PERMISSION, move |r, _| {
+            list_durable_queues(r, web::Data::new(store.clone()))
+        })
+    }
+    #[cfg(not(feature = "authorization"))]
+    {
+        resource.add_method(Method::Get, move |r, _| {
+            list_durable_queues(r, web::Data::new(store.clone()))
+        })
+    }
+}
+
+fn list_durable_queues(
+    _: HttpRequest,
+    store: web::Data<Box<dyn DurableMessageStore>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    Box::new(
+        web::block(move || store.list_queues().map_err(|err| err.to_string())).then(
+            |res| match res {
+                Ok(queues) => Ok(HttpResponse::Ok().json(ListDurableQueuesResponse {
+                    data: queues.iter().map(DurableQueueResponse::from).collect(),
+                })),
+                Err(BlockingError::Error(err)) => {
+                    error!("Unable to list durable queues: {}", err);
+                    Ok(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+                }
+                Err(_) => {
+                    error!("Unable to list durable queues: the blocking operation was canceled");
+                    Ok(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+                }
+            },
+        ),
+    )
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{
+        r2d2::{ConnectionManager as DieselConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+    use reqwest::{blocking::Client, StatusCode, Url};
+    use serde_json::Value as JsonValue;
+
+    use crate::circuit::durable::DieselDurableMessageStore;
+    use crate::migrations::run_sqlite_migrations;
+    use crate::rest_api::actix_web_1::{RestApiBuilder, RestApiShutdownHandle};
+
+    #[test]
+    /// Tests a GET /admin/durable-queues request returns the depth of each queue.
+    fn test_list_durable_queues_ok() {
+        let store = create_store();
+        for _ in 0..2 {
+            store
+                .add_message("node-1", "abcde-01234", b"payload".to_vec())
+                .expect("Unable to add message");
+        }
+
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_list_durable_queues_resource(store)]);
+
+        let url = Url::parse(&format!("http://{}/admin/durable-queues", bind_url))
+            .expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header("SplinterProtocolVersion", protocol::ADMIN_PROTOCOL_VERSION)
+            .send()
+            .expect("Failed to perform request");
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: JsonValue = resp.json().expect("Failed to deserialize body");
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "data": [{
+                    "node_id": "node-1",
+                    "queue_depth": 2,
+                    "oldest_sequence_number": 1,
+                    "last_sent_sequence_number": 2,
+                }]
+            })
+        );
+
+        shutdown_handle
+            .shutdown()
+            .expect("unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn create_store() -> Box<dyn DurableMessageStore> {
+        let connection_manager = DieselConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        Box::new(DieselDurableMessageStore::new(pool))
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        #[cfg(not(feature = "https-bind"))]
+        let bind = "127.0.0.1:0";
+        #[cfg(feature = "https-bind")]
+        let bind = crate::rest_api::BindConfig::Http("127.0.0.1:0".into());
+
+        let result = RestApiBuilder::new()
+            .with_bind(bind)
+            .add_resources(resources.clone())
+            .build_insecure()
+            .expect("Failed to build REST API")
+            .run_insecure();
+        match result {
+            Ok((shutdown_handle, join_handle)) => {
+                let port = shutdown_handle.port_numbers()[0];
+                (shutdown_handle, join_handle, format!("127.0.0.1:{}", port))
+            }
+            Err(err) => panic!("Failed to run REST API: {}", err),
+        }
+    }
+}
@@ -14,6 +14,8 @@
 
 pub(super) mod circuits;
 pub(super) mod circuits_circuit_id;
+#[cfg(feature = "circuit-durability")]
+pub(super) mod durable_queues;
 pub(super) mod proposals;
 pub(super) mod proposals_circuit_id;
 pub(super) mod submit;
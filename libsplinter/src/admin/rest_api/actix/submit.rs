@@ -14,12 +14,18 @@
 
 use actix_web::HttpResponse;
 use futures::{Future, IntoFuture};
+#[cfg(feature = "audit")]
+use protobuf::Message;
 
 #[cfg(feature = "authorization")]
 use crate::admin::rest_api::CIRCUIT_WRITE_PERMISSION;
 use crate::admin::service::{AdminCommands, AdminServiceError};
+#[cfg(feature = "audit")]
+use crate::audit::AuditDetails;
 use crate::protocol;
 use crate::protos::admin::CircuitManagementPayload;
+#[cfg(feature = "audit")]
+use crate::protos::admin::{CircuitManagementPayload_Action, CircuitManagementPayload_Header};
 use crate::rest_api::actix_web_1::{into_protobuf, Method, ProtocolVersionRangeGuard, Resource};
 use crate::service::ServiceError;
 
@@ -32,37 +38,49 @@ pub fn make_submit_route<A: AdminCommands + Clone + 'static>(admin_commands: A)
 
     #[cfg(feature = "authorization")]
     {
-        resource.add_method(Method::Post, CIRCUIT_WRITE_PERMISSION, move |_, payload| {
-            let admin_commands = admin_commands.clone();
-            Box::new(
-                into_protobuf::<CircuitManagementPayload>(payload).and_then(move |payload| {
-                    match admin_commands.submit_circuit_change(payload) {
-                        Ok(()) => HttpResponse::Accepted().finish().into_future(),
-                        Err(AdminServiceError::ServiceError(
-                            ServiceError::UnableToHandleMessage(err),
-                        )) => {
-                            debug!("{}", err);
-                            HttpResponse::BadRequest()
+        resource.add_method(
+            Method::Post,
+            CIRCUIT_WRITE_PERMISSION,
+            move |request, payload| {
+                let admin_commands = admin_commands.clone();
+                #[cfg(not(feature = "audit"))]
+                let _ = request;
+                Box::new(into_protobuf::<CircuitManagementPayload>(payload).and_then(
+                    move |payload| {
+                        #[cfg(feature = "audit")]
+                        {
+                            if let Some(details) = audit_details(&payload) {
+                                request.extensions_mut().insert(details);
+                            }
+                        }
+                        match admin_commands.submit_circuit_change(payload) {
+                            Ok(()) => HttpResponse::Accepted().finish().into_future(),
+                            Err(AdminServiceError::ServiceError(
+                                ServiceError::UnableToHandleMessage(err),
+                            )) => {
+                                debug!("{}", err);
+                                HttpResponse::BadRequest()
+                                    .json(json!({
+                                        "message": format!("Unable to handle message: {}", err)
+                                    }))
+                                    .into_future()
+                            }
+                            Err(AdminServiceError::ServiceError(
+                                ServiceError::InvalidMessageFormat(err),
+                            )) => HttpResponse::BadRequest()
                                 .json(json!({
-                                    "message": format!("Unable to handle message: {}", err)
+                                    "message": format!("Failed to parse payload: {}", err)
                                 }))
-                                .into_future()
+                                .into_future(),
+                            Err(err) => {
+                                error!("{}", err);
+                                HttpResponse::InternalServerError().finish().into_future()
+                            }
                         }
-                        Err(AdminServiceError::ServiceError(
-                            ServiceError::InvalidMessageFormat(err),
-                        )) => HttpResponse::BadRequest()
-                            .json(json!({
-                                "message": format!("Failed to parse payload: {}", err)
-                            }))
-                            .into_future(),
-                        Err(err) => {
-                            error!("{}", err);
-                            HttpResponse::InternalServerError().finish().into_future()
-                        }
-                    }
-                }),
-            )
-        })
+                    },
+                ))
+            },
+        )
     }
     #[cfg(not(feature = "authorization"))]
     {
@@ -99,3 +117,43 @@ pub fn make_submit_route<A: AdminCommands + Clone + 'static>(admin_commands: A)
         })
     }
 }
+
+/// Extracts the admin action, and the circuit it applies to, from the submitted payload so that
+/// they may be included in the request's audit entry.
+#[cfg(feature = "audit")]
+fn audit_details(payload: &CircuitManagementPayload) -> Option<AuditDetails> {
+    let header = CircuitManagementPayload_Header::parse_from_bytes(payload.get_header()).ok()?;
+    let circuit_id = match header.get_action() {
+        CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST => payload
+            .get_circuit_create_request()
+            .get_circuit()
+            .get_circuit_id(),
+        CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_VOTE => {
+            payload.get_circuit_proposal_vote().get_circuit_id()
+        }
+        CircuitManagementPayload_Action::CIRCUIT_DISBAND_REQUEST => {
+            payload.get_circuit_disband_request().get_circuit_id()
+        }
+        CircuitManagementPayload_Action::CIRCUIT_PURGE_REQUEST => {
+            payload.get_circuit_purge_request().get_circuit_id()
+        }
+        CircuitManagementPayload_Action::CIRCUIT_ABANDON => {
+            payload.get_circuit_abandon().get_circuit_id()
+        }
+        CircuitManagementPayload_Action::PROPOSAL_REMOVE_REQUEST => {
+            payload.get_proposal_remove_request().get_circuit_id()
+        }
+        _ => "",
+    };
+    let circuit_id = if circuit_id.is_empty() {
+        None
+    } else {
+        Some(circuit_id.to_string())
+    };
+
+    Some(AuditDetails::new(
+        format!("{:?}", header.get_action()),
+        circuit_id,
+        payload.write_to_bytes().unwrap_or_default(),
+    ))
+}
@@ -24,6 +24,8 @@ mod resources;
 
 use crate::admin::service::AdminService;
 use crate::admin::store::AdminServiceStore;
+#[cfg(feature = "circuit-durability")]
+use crate::circuit::durable::DurableMessageStore;
 use crate::rest_api::actix_web_1::{Resource, RestResourceProvider};
 #[cfg(all(feature = "authorization", feature = "rest-api-actix"))]
 use crate::rest_api::auth::authorization::Permission;
@@ -127,3 +129,46 @@ impl RestResourceProvider for CircuitResourceProvider {
         resources
     }
 }
+
+/// Provides the REST API [`Resource`](crate::rest_api::Resource) definitions for inspecting the
+/// queues of direct messages sent on store-and-forward circuits.
+///
+/// The following endpoints are provided:
+///
+/// * `GET /admin/durable-queues` - List the number of unacknowledged messages queued for each
+///   node
+///
+/// These endpoints are only available if the following REST API backend feature is enabled:
+///
+/// * `rest-api-actix`
+#[cfg(feature = "circuit-durability")]
+#[derive(Clone)]
+pub struct DurableQueueResourceProvider {
+    store: Box<dyn DurableMessageStore>,
+}
+
+#[cfg(feature = "circuit-durability")]
+impl DurableQueueResourceProvider {
+    pub fn new(store: Box<dyn DurableMessageStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[cfg(feature = "circuit-durability")]
+impl RestResourceProvider for DurableQueueResourceProvider {
+    fn resources(&self) -> Vec<Resource> {
+        // Allowing unused_mut because resources must be mutable if feature rest-api-actix is
+        // enabled
+        #[allow(unused_mut)]
+        let mut resources = Vec::new();
+
+        #[cfg(feature = "rest-api-actix")]
+        {
+            resources.push(actix::durable_queues::make_list_durable_queues_resource(
+                self.store.clone(),
+            ));
+        }
+
+        resources
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::circuit::durable::DurableQueueStatus;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub(crate) struct ListDurableQueuesResponse<'a> {
+    pub data: Vec<DurableQueueResponse<'a>>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub(crate) struct DurableQueueResponse<'a> {
+    pub node_id: &'a str,
+    pub queue_depth: u64,
+    pub oldest_sequence_number: Option<u64>,
+    pub last_sent_sequence_number: u64,
+}
+
+impl<'a> From<&'a DurableQueueStatus> for DurableQueueResponse<'a> {
+    fn from(status: &'a DurableQueueStatus) -> Self {
+        Self {
+            node_id: status.node_id(),
+            queue_depth: status.depth(),
+            oldest_sequence_number: status.oldest_sequence_number(),
+            last_sent_sequence_number: status.last_sent_sequence_number(),
+        }
+    }
+}
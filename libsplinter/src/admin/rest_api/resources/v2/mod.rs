@@ -15,5 +15,7 @@
 //! Defines the REST API resources for protocol version 2
 pub(in super::super) mod circuits;
 pub(in super::super) mod circuits_circuit_id;
+#[cfg(feature = "circuit-durability")]
+pub(in super::super) mod durable_queues;
 pub(in super::super) mod proposals;
 pub(in super::super) mod proposals_circuit_id;
@@ -48,6 +48,7 @@ impl<'a> TryFrom<&'a CircuitProposal> for ProposalResponse<'a> {
             ProposalType::UpdateRoster => "UpdateRoster",
             ProposalType::AddNode => "AddNode",
             ProposalType::RemoveNode => "RemoveNode",
+            ProposalType::UpdateApplicationMetadata => "UpdateApplicationMetadata",
             ProposalType::Disband => "Disband",
         };
 
@@ -76,18 +76,34 @@ impl CreateCircuit {
 
         let durability = match proto.get_durability() {
             admin::Circuit_DurabilityType::NO_DURABILITY => DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-durability")]
+            admin::Circuit_DurabilityType::STORE_AND_FORWARD => DurabilityType::StoreAndForward,
             admin::Circuit_DurabilityType::UNSET_DURABILITY_TYPE => {
                 return Err(MarshallingError::UnsetField(
                     "Unset durability type".to_string(),
                 ));
             }
+            #[cfg(not(feature = "circuit-durability"))]
+            _ => {
+                return Err(MarshallingError::UnsetField(
+                    "Unsupported durability type".to_string(),
+                ));
+            }
         };
 
         let routes = match proto.get_routes() {
             admin::Circuit_RouteType::ANY_ROUTE => RouteType::Any,
+            #[cfg(feature = "circuit-relay")]
+            admin::Circuit_RouteType::RELAY_ROUTE => RouteType::Relay,
             admin::Circuit_RouteType::UNSET_ROUTE_TYPE => {
                 return Err(MarshallingError::UnsetField("Unset route type".to_string()));
             }
+            #[cfg(not(feature = "circuit-relay"))]
+            _ => {
+                return Err(MarshallingError::UnsetField(
+                    "Unsupported route type".to_string(),
+                ));
+            }
         };
 
         let circuit_status = match proto.get_circuit_status() {
@@ -197,10 +213,16 @@ impl CreateCircuit {
             DurabilityType::NoDurability => {
                 circuit.set_durability(admin::Circuit_DurabilityType::NO_DURABILITY);
             }
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::StoreAndForward => {
+                circuit.set_durability(admin::Circuit_DurabilityType::STORE_AND_FORWARD);
+            }
         };
 
         match self.routes {
             RouteType::Any => circuit.set_routes(admin::Circuit_RouteType::ANY_ROUTE),
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Relay => circuit.set_routes(admin::Circuit_RouteType::RELAY_ROUTE),
         };
 
         if self.circuit_version > UNSET_CIRCUIT_VERSION {
@@ -281,10 +303,16 @@ impl TryInto<admin::Circuit> for CreateCircuit {
             DurabilityType::NoDurability => {
                 circuit.set_durability(admin::Circuit_DurabilityType::NO_DURABILITY);
             }
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::StoreAndForward => {
+                circuit.set_durability(admin::Circuit_DurabilityType::STORE_AND_FORWARD);
+            }
         };
 
         match self.routes {
             RouteType::Any => circuit.set_routes(admin::Circuit_RouteType::ANY_ROUTE),
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Relay => circuit.set_routes(admin::Circuit_RouteType::RELAY_ROUTE),
         };
 
         match self.circuit_status {
@@ -355,12 +383,16 @@ impl From<&store::PersistenceType> for PersistenceType {
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum DurabilityType {
     NoDurability,
+    #[cfg(feature = "circuit-durability")]
+    StoreAndForward,
 }
 
 impl From<&store::DurabilityType> for DurabilityType {
     fn from(store_enum: &store::DurabilityType) -> Self {
         match *store_enum {
             store::DurabilityType::NoDurability => DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-durability")]
+            store::DurabilityType::StoreAndForward => DurabilityType::StoreAndForward,
         }
     }
 }
@@ -368,6 +400,8 @@ impl From<&store::DurabilityType> for DurabilityType {
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum RouteType {
     Any,
+    #[cfg(feature = "circuit-relay")]
+    Relay,
 }
 
 impl Default for RouteType {
@@ -380,6 +414,8 @@ impl From<&store::RouteType> for RouteType {
     fn from(store_enum: &store::RouteType) -> Self {
         match *store_enum {
             store::RouteType::Any => RouteType::Any,
+            #[cfg(feature = "circuit-relay")]
+            store::RouteType::Relay => RouteType::Relay,
         }
     }
 }
@@ -535,6 +571,9 @@ impl CircuitProposal {
             admin::CircuitProposal_ProposalType::UPDATE_ROSTER => ProposalType::UpdateRoster,
             admin::CircuitProposal_ProposalType::ADD_NODE => ProposalType::AddNode,
             admin::CircuitProposal_ProposalType::REMOVE_NODE => ProposalType::RemoveNode,
+            admin::CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA => {
+                ProposalType::UpdateApplicationMetadata
+            }
             admin::CircuitProposal_ProposalType::DISBAND => ProposalType::Disband,
             admin::CircuitProposal_ProposalType::UNSET_PROPOSAL_TYPE => {
                 return Err(MarshallingError::UnsetField(
@@ -566,6 +605,9 @@ impl CircuitProposal {
             ProposalType::UpdateRoster => admin::CircuitProposal_ProposalType::UPDATE_ROSTER,
             ProposalType::AddNode => admin::CircuitProposal_ProposalType::ADD_NODE,
             ProposalType::RemoveNode => admin::CircuitProposal_ProposalType::REMOVE_NODE,
+            ProposalType::UpdateApplicationMetadata => {
+                admin::CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA
+            }
             ProposalType::Disband => admin::CircuitProposal_ProposalType::DISBAND,
         };
 
@@ -597,6 +639,9 @@ impl From<store::CircuitProposal> for CircuitProposal {
             store::ProposalType::UpdateRoster => ProposalType::UpdateRoster,
             store::ProposalType::AddNode => ProposalType::AddNode,
             store::ProposalType::RemoveNode => ProposalType::RemoveNode,
+            store::ProposalType::UpdateApplicationMetadata => {
+                ProposalType::UpdateApplicationMetadata
+            }
             store::ProposalType::Disband => ProposalType::Disband,
         };
 
@@ -676,6 +721,7 @@ pub enum ProposalType {
     UpdateRoster,
     AddNode,
     RemoveNode,
+    UpdateApplicationMetadata,
     Disband,
 }
 
@@ -204,6 +204,14 @@ impl AdminService {
         AdminServiceProposals::new(&self.admin_service_shared)
     }
 
+    /// Returns a handle that can pause the admin service, e.g. while a backup is created.
+    #[cfg(feature = "backup")]
+    pub fn quiescer(&self) -> AdminServiceQuiescer {
+        AdminServiceQuiescer {
+            shared: Arc::clone(&self.admin_service_shared),
+        }
+    }
+
     /// On restart of a splinter node, all services that this node should run on the existing
     /// circuits should be initialized using the service orchestrator. This may not include all
     /// services if they are not supported locally. It is expected that some services will be
@@ -269,6 +277,23 @@ impl AdminService {
             })?
             .routing_table_writer();
 
+        // Members of active circuits already have peer refs, so a proposal to update one of
+        // these circuits only requires peer refs for the members being added
+        #[cfg(feature = "circuit-update")]
+        let active_circuit_members = active_circuits
+            .iter()
+            .map(|circuit| {
+                (
+                    circuit.circuit_id().to_string(),
+                    circuit
+                        .members()
+                        .iter()
+                        .map(|member| member.node_id().to_string())
+                        .collect::<Vec<String>>(),
+                )
+            })
+            .collect::<HashMap<String, Vec<String>>>();
+
         for circuit in active_circuits {
             // restart all peer in the circuit
             for member in circuit.members().iter() {
@@ -361,6 +386,10 @@ impl AdminService {
                             .collect(),
                         #[cfg(feature = "challenge-authorization")]
                         circuit.authorization_type().into(),
+                        #[cfg(feature = "circuit-durability")]
+                        circuit.durability().into(),
+                        #[cfg(feature = "circuit-relay")]
+                        circuit.routes().into(),
                     ),
                     routing_members,
                 )
@@ -446,6 +475,27 @@ impl AdminService {
         for proposal in proposals {
             // connect to all peers in the circuit proposal
             for member in proposal.circuit().members().iter() {
+                #[cfg(feature = "circuit-update")]
+                {
+                    let is_update = matches!(
+                        proposal.proposal_type(),
+                        store::ProposalType::UpdateRoster
+                            | store::ProposalType::AddNode
+                            | store::ProposalType::RemoveNode
+                            | store::ProposalType::UpdateApplicationMetadata
+                    );
+                    if is_update
+                        && active_circuit_members
+                            .get(proposal.circuit_id())
+                            .map(|members| {
+                                members.iter().any(|node_id| node_id == member.node_id())
+                            })
+                            .unwrap_or(false)
+                    {
+                        continue;
+                    }
+                }
+
                 if member.node_id() != self.node_id {
                     // Need to manually get peer token here because we also need the endpoints
                     // for requesting the peer ref
@@ -706,6 +756,12 @@ impl Service for AdminService {
                     proposal,
                     circuit_payload.clone(),
                     message_context.sender.to_string(),
+                    #[cfg(feature = "circuit-update")]
+                    if proposed_circuit.has_circuit() {
+                        Some(proposed_circuit.get_circuit().clone())
+                    } else {
+                        None
+                    },
                 )
             }
             AdminMessage_Type::MEMBER_READY => {
@@ -827,6 +883,33 @@ fn handle_peer_manager_notification(
     }
 }
 
+/// Pauses the admin service so that its state is not modified.
+#[cfg(feature = "backup")]
+#[derive(Clone)]
+pub struct AdminServiceQuiescer {
+    shared: Arc<Mutex<AdminServiceShared>>,
+}
+
+#[cfg(feature = "backup")]
+impl AdminServiceQuiescer {
+    /// Waits for the admin service to finish its current operation and pauses it until the
+    /// returned guard is dropped.
+    pub fn quiesce(&self) -> Result<AdminServiceQuiesceGuard<'_>, AdminServiceError> {
+        let guard = self
+            .shared
+            .lock()
+            .map_err(|_| AdminServiceError::general_error("Admin shared lock was lock poisoned"))?;
+
+        Ok(AdminServiceQuiesceGuard { _guard: guard })
+    }
+}
+
+/// Keeps the admin service paused until dropped; see [`AdminServiceQuiescer::quiesce`].
+#[cfg(feature = "backup")]
+pub struct AdminServiceQuiesceGuard<'a> {
+    _guard: std::sync::MutexGuard<'a, AdminServiceShared>,
+}
+
 #[derive(Clone)]
 struct AdminServiceCommands {
     shared: Arc<Mutex<AdminServiceShared>>,
@@ -27,6 +27,8 @@ use crate::admin::store::{
     CircuitProposal as StoreProposal, CircuitStatus as StoreCircuitStatus, ProposalType,
     ProposedCircuit, Service as StoreService, Vote, VoteRecordBuilder,
 };
+#[cfg(feature = "circuit-update")]
+use crate::admin::store::{ProposedNode, ProposedService};
 use crate::admin::token::ListPeerAuthorizationTokens;
 use crate::circuit::routing::{self, RoutingTableWriter};
 use crate::consensus::{Proposal, ProposalId, ProposalUpdate};
@@ -45,6 +47,8 @@ use crate::protos::admin::{
     Circuit_PersistenceType, Circuit_RouteType, MemberReady, RemovedProposal,
     ServiceProtocolVersionRequest, SplinterNode, SplinterService,
 };
+#[cfg(feature = "circuit-update")]
+use crate::protos::admin::SplinterService_Argument;
 use crate::service::error::ServiceError;
 #[cfg(feature = "service-arg-validation")]
 use crate::service::validation::ServiceArgValidator;
@@ -146,6 +150,10 @@ pub struct AdminServiceShared {
     event_store: Box<dyn AdminServiceStore>,
     #[cfg(feature = "challenge-authorization")]
     public_keys: Vec<Vec<u8>>,
+    // the current definitions of circuits this node has been asked to join, which are required to
+    // build the proposal to add this node as the circuit is not yet in the admin store
+    #[cfg(feature = "circuit-update")]
+    joining_circuits: HashMap<String, StoreCircuit>,
 }
 
 impl AdminServiceShared {
@@ -193,6 +201,8 @@ impl AdminServiceShared {
             event_store: admin_service_event_store,
             #[cfg(feature = "challenge-authorization")]
             public_keys,
+            #[cfg(feature = "circuit-update")]
+            joining_circuits: HashMap::new(),
         }
     }
 
@@ -328,8 +338,27 @@ impl AdminServiceShared {
                     .circuit_management_type
                     .clone();
 
+                // The definition of a circuit this node is joining is only required until the
+                // request to add this node has been committed as a proposal
+                #[cfg(feature = "circuit-update")]
+                self.joining_circuits.remove(circuit_id);
+
                 match self.check_approved(&circuit_proposal) {
                     CircuitProposalStatus::Accepted => {
+                        #[cfg(feature = "circuit-update")]
+                        {
+                            let is_update =
+                                ProposalType::try_from(&circuit_proposal.get_proposal_type())
+                                    .map(|proposal_type| is_update_proposal_type(&proposal_type))
+                                    .unwrap_or(false);
+                            if is_update {
+                                return self.commit_circuit_update(
+                                    circuit_proposal,
+                                    circuit_proposal_context.signer_public_key,
+                                );
+                            }
+                        }
+
                         let status = circuit_proposal.get_circuit_proposal().get_circuit_status();
                         // Verifying if the circuit proposal is associated with a disband request.
                         // If the status is set to `DISBANDED`, the proposal is associated with
@@ -435,6 +464,10 @@ impl AdminServiceShared {
                                     .collect(),
                                 #[cfg(feature = "challenge-authorization")]
                                 circuit.authorization_type().into(),
+                                #[cfg(feature = "circuit-durability")]
+                                circuit.durability().into(),
+                                #[cfg(feature = "circuit-relay")]
+                                circuit.routes().into(),
                             );
 
                             let routing_members = circuit_proposal
@@ -563,6 +596,29 @@ impl AdminServiceShared {
                                 );
                                 Ok(())
                             }
+                            #[cfg(feature = "circuit-update")]
+                            CircuitManagementPayload_Action::CIRCUIT_UPDATE_ROSTER_REQUEST
+                            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_ADD_NODE
+                            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_REMOVE_NODE
+                            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_APPLICATION_METADATA_REQUEST => {
+                                self.add_proposal(circuit_proposal.clone())?;
+                                #[cfg(feature = "admin-service-count")]
+                                self.update_metrics()?;
+                                // notify registered application authorization handlers of the
+                                // committed circuit update proposal
+                                let event = messages::AdminServiceEvent::ProposalSubmitted(
+                                    messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                                        .map_err(AdminSharedError::InvalidMessageFormat)?,
+                                );
+                                self.send_event(&mgmt_type, event);
+
+                                info!(
+                                    "committed changes for new circuit proposal to update \
+                                       circuit {}",
+                                    circuit_id
+                                );
+                                Ok(())
+                            }
                             _ => Err(AdminSharedError::UnknownAction(format!(
                                 "Received unknown action: {:?}",
                                 action
@@ -575,15 +631,7 @@ impl AdminServiceShared {
                         #[cfg(feature = "admin-service-count")]
                         self.update_metrics()?;
                         if let Some(proposal) = proposal {
-                            self.remove_peer_refs(proposal.circuit().list_tokens().map_err(
-                                |err| {
-                                    AdminSharedError::SplinterStateError(format!(
-                                        "Unable to remove peer refs for proposal {}: {}",
-                                        proposal.circuit_id(),
-                                        err
-                                    ))
-                                },
-                            )?);
+                            self.remove_proposal_peer_refs(&proposal)?;
                         }
                         let circuit_proposal_proto =
                             messages::CircuitProposal::from_proto(circuit_proposal.clone())
@@ -605,10 +653,16 @@ impl AdminServiceShared {
 
     pub fn rollback(&mut self) -> Result<(), AdminSharedError> {
         match self.pending_changes.take() {
-            Some(circuit_proposal_context) => info!(
-                "discarded change for {}",
-                circuit_proposal_context.circuit_proposal.get_circuit_id()
-            ),
+            Some(circuit_proposal_context) => {
+                #[cfg(feature = "circuit-update")]
+                self.joining_circuits
+                    .remove(circuit_proposal_context.circuit_proposal.get_circuit_id());
+
+                info!(
+                    "discarded change for {}",
+                    circuit_proposal_context.circuit_proposal.get_circuit_id()
+                )
+            }
             None => debug!("no changes to rollback"),
         }
 
@@ -710,6 +764,24 @@ impl AdminServiceShared {
                 for member in circuit_proposal.circuit().members() {
                     verifiers.push(admin_service_id(member.node_id()));
                 }
+                // Votes for a circuit update must also reach the members of the current circuit,
+                // including any node that is being removed from the circuit
+                #[cfg(feature = "circuit-update")]
+                {
+                    if is_update_proposal_type(circuit_proposal.proposal_type()) {
+                        if let Some(circuit) = self
+                            .admin_store
+                            .get_circuit(circuit_proposal.circuit_id())?
+                        {
+                            for member in circuit.members() {
+                                let verifier = admin_service_id(member.node_id());
+                                if !verifiers.contains(&verifier) {
+                                    verifiers.push(verifier);
+                                }
+                            }
+                        }
+                    }
+                }
                 let signer_public_key = header.get_requester();
 
                 self.validate_circuit_vote(
@@ -842,6 +914,82 @@ impl AdminServiceShared {
 
                 Ok((expected_hash, circuit_proposal))
             }
+            #[cfg(feature = "circuit-update")]
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_ROSTER_REQUEST
+            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_ADD_NODE
+            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_REMOVE_NODE
+            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_APPLICATION_METADATA_REQUEST => {
+                debug!("Circuit update request being processed");
+                let (current_circuit, circuit_proposal) = self.make_update_circuit_proposal(
+                    &circuit_payload,
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                )?;
+                let proposed_circuit = StoreCircuit::try_from(
+                    circuit_proposal.get_circuit_proposal(),
+                )
+                .map_err(|err| {
+                    AdminSharedError::SplinterStateError(format!(
+                        "Unable to convert proto Circuit to store Circuit: {}",
+                        err
+                    ))
+                })?;
+
+                // Both the current and the updated members of the circuit must agree on the
+                // update, so that nodes being removed are also notified of the change
+                let mut verifiers = vec![];
+                let mut protocol = ADMIN_SERVICE_PROTOCOL_VERSION;
+                for member in current_circuit
+                    .members()
+                    .iter()
+                    .chain(proposed_circuit.members().iter())
+                {
+                    let verifier = admin_service_id(member.node_id());
+                    if verifiers.contains(&verifier) {
+                        continue;
+                    }
+                    // Figure out what protocol version should be used for this proposal
+                    if let Some(protocol_version) = self.service_protocols.get(&verifier) {
+                        if protocol_version < &protocol {
+                            protocol = *protocol_version
+                        }
+                    }
+                    verifiers.push(verifier);
+                }
+
+                self.validate_update_circuit(
+                    &current_circuit,
+                    circuit_proposal.get_circuit_proposal(),
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                    protocol,
+                )
+                .map_err(|err| {
+                    match self.list_update_peers(Some(&current_circuit), &proposed_circuit) {
+                        Ok(peers) => self
+                            .remove_peer_refs(peers.into_iter().map(|(token, _)| token).collect()),
+                        Err(err) => {
+                            error!(
+                                "Unable to remove peer refs for proposal {}: {}",
+                                circuit_proposal.get_circuit_id(),
+                                err
+                            );
+                        }
+                    };
+
+                    err
+                })?;
+
+                let expected_hash = sha256(&circuit_proposal)?;
+                self.pending_changes = Some(CircuitProposalContext {
+                    circuit_proposal: circuit_proposal.clone(),
+                    signer_public_key: header.get_requester().to_vec(),
+                    action: header.get_action(),
+                });
+                self.current_consensus_verifiers = verifiers;
+
+                Ok((expected_hash, circuit_proposal))
+            }
             CircuitManagementPayload_Action::ACTION_UNSET => Err(
                 AdminSharedError::ValidationFailed("Action must be set".to_string()),
             ),
@@ -973,6 +1121,48 @@ impl AdminServiceShared {
         self.check_connected_peers_payload_disband(&members, payload, message_sender)
     }
 
+    /// Once a local circuit update request has been validated, the admin service may now proceed
+    /// to connecting to any nodes being added to the circuit and to communicating with the remote
+    /// circuit members to propose the update.
+    #[cfg(feature = "circuit-update")]
+    pub fn propose_update(
+        &mut self,
+        payload: CircuitManagementPayload,
+        current_circuit: &StoreCircuit,
+        circuit_proposal: &CircuitProposal,
+        message_sender: String,
+    ) -> Result<(), ServiceError> {
+        debug!(
+            "received circuit update request {}",
+            circuit_proposal.get_circuit_id()
+        );
+        let proposed_circuit = StoreCircuit::try_from(circuit_proposal.get_circuit_proposal())
+            .map_err(|err| {
+                ServiceError::UnableToHandleMessage(Box::new(AdminSharedError::ValidationFailed(
+                    format!("Unable to convert proto Circuit to store Circuit: {}", err),
+                )))
+            })?;
+
+        let new_peers = self
+            .list_update_peers(Some(current_circuit), &proposed_circuit)
+            .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+        let mut members = vec![];
+        for (token, _) in list_member_peers(current_circuit)
+            .and_then(|mut peers| {
+                peers.extend(list_member_peers(&proposed_circuit)?);
+                Ok(peers)
+            })
+            .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?
+        {
+            if !members.contains(&token) {
+                members.push(token);
+            }
+        }
+
+        self.check_connected_peers_payload_update(&new_peers, &members, payload, message_sender)
+    }
+
     #[cfg(feature = "admin-service-count")]
     pub fn update_metrics(&self) -> Result<(), AdminSharedError> {
         // initialize circuit and proposal metrics
@@ -1170,14 +1360,8 @@ impl AdminServiceShared {
             self.update_metrics()
                 .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
 
-            self.remove_peer_refs(proposal.circuit().list_tokens().map_err(|err| {
-                ServiceError::UnableToHandleMessage(Box::new(AdminSharedError::SplinterStateError(
-                    format!(
-                        "Unable to remove peer refs for proposal: {}: {}",
-                        circuit_id, err
-                    ),
-                )))
-            })?);
+            self.remove_proposal_peer_refs(&proposal)
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
             Ok(())
         } else {
             Err(ServiceError::UnableToHandleMessage(Box::new(
@@ -1366,6 +1550,95 @@ impl AdminServiceShared {
         Ok(())
     }
 
+    /// Add peer refs for the nodes being added to a circuit and verify all members of the current
+    /// and updated circuit are using a valid protocol version. If all members have agreed on a
+    /// protocol version, the update payload is moved into the `pending_circuit_payloads` list for
+    /// further processing. Otherwise, this payload is added to the `unpeered_payloads` list if any
+    /// new nodes are not yet peered, or to the `pending_protocol_payloads` list to await the
+    /// remaining nodes' protocol agreement.
+    #[cfg(feature = "circuit-update")]
+    fn check_connected_peers_payload_update(
+        &mut self,
+        new_peers: &[(PeerAuthorizationToken, Vec<String>)],
+        members: &[PeerAuthorizationToken],
+        payload: CircuitManagementPayload,
+        message_sender: String,
+    ) -> Result<(), ServiceError> {
+        let mut missing_protocol_ids = vec![];
+        let mut pending_peers = vec![];
+        let mut added_peers: Vec<PeerAuthorizationToken> = vec![];
+        for (node_id, endpoints) in new_peers {
+            debug!("Referencing node {:?}", node_id);
+            let peer_ref = self
+                .peer_connector
+                .add_peer_ref(node_id.clone(), endpoints.to_vec())
+                .map_err(|err| {
+                    // remove all peer refs added for this proposal
+                    self.remove_peer_refs(added_peers.to_vec());
+
+                    ServiceError::UnableToHandleMessage(Box::new(err))
+                })?;
+
+            self.add_peer_ref(peer_ref);
+            added_peers.push(node_id.clone());
+
+            // if we have a protocol the connection exists for the peer already
+            if self
+                .service_protocols
+                .get(&admin_service_id(&node_id.id_as_string()))
+                .is_none()
+            {
+                pending_peers.push(node_id.clone());
+            }
+        }
+
+        for node in members {
+            if !self.is_local_node(node)
+                && self
+                    .service_protocols
+                    .get(&admin_service_id(&node.id_as_string()))
+                    .is_none()
+            {
+                // new peers will request the protocol once they are connected
+                if !pending_peers.contains(node) {
+                    self.send_protocol_request(node)?;
+                }
+                missing_protocol_ids.push(admin_service_id(&node.id_as_string()))
+            }
+        }
+
+        if missing_protocol_ids.is_empty() {
+            self.pending_circuit_payloads.push_back(payload);
+        } else if !pending_peers.is_empty() {
+            debug!(
+                "Members {:?} added; awaiting peering and service protocol agreement before \
+                proceeding",
+                &missing_protocol_ids
+            );
+            self.unpeered_payloads.push(PendingPayload {
+                unpeered_ids: pending_peers,
+                missing_protocol_ids,
+                payload_type: PayloadType::Circuit(payload),
+                members: members.to_vec(),
+                message_sender,
+            });
+        } else {
+            debug!(
+                "Members {:?} added; awaiting service protocol agreement before proceeding",
+                &missing_protocol_ids
+            );
+            self.pending_protocol_payloads.push(PendingPayload {
+                unpeered_ids: vec![],
+                missing_protocol_ids,
+                payload_type: PayloadType::Circuit(payload),
+                members: members.to_vec(),
+                message_sender,
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn submit(&mut self, payload: CircuitManagementPayload) -> Result<(), ServiceError> {
         debug!("Payload submitted: {:?}", payload);
 
@@ -1450,6 +1723,33 @@ impl AdminServiceShared {
                     "local".to_string(),
                 )
             }
+            #[cfg(feature = "circuit-update")]
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_ROSTER_REQUEST
+            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_ADD_NODE
+            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_REMOVE_NODE
+            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_APPLICATION_METADATA_REQUEST => {
+                let signer_public_key = header.get_requester();
+                let requester_node_id = header.get_requester_node_id();
+                let (current_circuit, circuit_proposal) = self
+                    .make_update_circuit_proposal(&payload, signer_public_key, requester_node_id)
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                self.validate_update_circuit(
+                    &current_circuit,
+                    circuit_proposal.get_circuit_proposal(),
+                    signer_public_key,
+                    requester_node_id,
+                    ADMIN_SERVICE_PROTOCOL_VERSION,
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                self.propose_update(
+                    payload,
+                    &current_circuit,
+                    &circuit_proposal,
+                    "local".to_string(),
+                )
+            }
             CircuitManagementPayload_Action::CIRCUIT_PURGE_REQUEST => {
                 let signer_public_key = header.get_requester();
                 let requester_node_id = header.get_requester_node_id();
@@ -1506,6 +1806,7 @@ impl AdminServiceShared {
         proposal: Proposal,
         payload: CircuitManagementPayload,
         message_sender: String,
+        #[cfg(feature = "circuit-update")] circuit: Option<Circuit>,
     ) -> Result<(), ServiceError> {
         let mut missing_protocol_ids = vec![];
         let mut pending_peers = vec![];
@@ -1636,6 +1937,94 @@ impl AdminServiceShared {
             }
         }
 
+        // If the payload is to update a circuit, PeerRefs for any nodes being added must be
+        // added. If this node is the one being added, the circuit is not yet in the admin store,
+        // so the current definition of the circuit sent along with the proposal is kept until the
+        // proposal is committed.
+        #[cfg(feature = "circuit-update")]
+        if let Some(circuit_id) = update_request_circuit_id(&payload) {
+            if let Some(circuit) = circuit {
+                if self
+                    .admin_store
+                    .get_circuit(circuit_id)
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?
+                    .is_none()
+                {
+                    let store_circuit = StoreCircuit::try_from(&circuit).map_err(|err| {
+                        ServiceError::UnableToHandleMessage(Box::new(
+                            AdminSharedError::ValidationFailed(format!(
+                                "Unable to convert proto Circuit to store Circuit: {}",
+                                err
+                            )),
+                        ))
+                    })?;
+                    self.joining_circuits
+                        .insert(circuit_id.to_string(), store_circuit);
+                }
+            }
+
+            let header: CircuitManagementPayload_Header =
+                Message::parse_from_bytes(payload.get_header())?;
+            let (current_circuit, circuit_proposal) = self
+                .make_update_circuit_proposal(
+                    &payload,
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+            let proposed_circuit = StoreCircuit::try_from(circuit_proposal.get_circuit_proposal())
+                .map_err(|err| {
+                    ServiceError::UnableToHandleMessage(Box::new(
+                        AdminSharedError::ValidationFailed(format!(
+                            "Unable to convert proto Circuit to store Circuit: {}",
+                            err
+                        )),
+                    ))
+                })?;
+
+            let new_peers = self
+                .list_update_peers(Some(&current_circuit), &proposed_circuit)
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+            for (node_id, endpoints) in &new_peers {
+                debug!("Referencing node {:?}", node_id);
+                let peer_ref = self
+                    .peer_connector
+                    .add_peer_ref(node_id.clone(), endpoints.to_vec())
+                    .map_err(|err| {
+                        // remove all peer refs added for this proposal
+                        self.remove_peer_refs(added_peers.to_vec());
+
+                        ServiceError::UnableToHandleMessage(Box::new(err))
+                    })?;
+
+                self.add_peer_ref(peer_ref);
+                added_peers.push(node_id.clone());
+            }
+
+            for (node_id, _) in list_member_peers(&current_circuit)
+                .and_then(|mut peers| {
+                    peers.extend(list_member_peers(&proposed_circuit)?);
+                    Ok(peers)
+                })
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?
+            {
+                if pending_members.contains(&node_id) {
+                    continue;
+                }
+                // Verify each member has an agreed upon protocol version with this node
+                if !self.is_local_node(&node_id)
+                    && self
+                        .service_protocols
+                        .get(&admin_service_id(&node_id.id_as_string()))
+                        .is_none()
+                {
+                    pending_peers.push(node_id.clone());
+                    missing_protocol_ids.push(admin_service_id(&node_id.id_as_string()))
+                }
+                pending_members.push(node_id);
+            }
+        }
+
         if missing_protocol_ids.is_empty() {
             self.add_pending_consensus_proposal(proposal.id.clone(), (proposal.clone(), payload));
             self.proposal_sender
@@ -2508,6 +2897,22 @@ impl AdminServiceShared {
             )));
         }
 
+        // Nodes being removed from a circuit receive the votes for the update, but are not
+        // permitted to vote on it
+        #[cfg(feature = "circuit-update")]
+        if is_update_proposal_type(circuit_proposal.proposal_type())
+            && !circuit_proposal
+                .circuit()
+                .members()
+                .iter()
+                .any(|member| member.node_id() == node_id)
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Received vote from {} which is not a member of the updated circuit {}",
+                node_id, proposal_vote.circuit_id
+            )));
+        }
+
         self.key_permission_manager
             .is_permitted(signer_public_key, VOTER_ROLE)
             .map_err(|_| {
@@ -2972,6 +3377,10 @@ impl AdminServiceShared {
         if let Some(display_name) = store_circuit.display_name() {
             create_circuit_builder = create_circuit_builder.with_display_name(&display_name);
         }
+        if let Some(application_metadata) = store_circuit.application_metadata() {
+            create_circuit_builder =
+                create_circuit_builder.with_application_metadata(&application_metadata);
+        }
 
         let proposed_circuit: Circuit = create_circuit_builder
             .build()
@@ -3006,54 +3415,9 @@ impl AdminServiceShared {
         &self,
         store_circuit: &StoreCircuit,
     ) -> Result<(Circuit, StoreCircuit), AdminSharedError> {
-        // Collecting the endpoints of the nodes apart of the circuit being abandoned
-        let circuit_members = store_circuit
-            .members()
-            .iter()
-            .map(|circuit_node| {
-                let mut node = SplinterNode::new();
-                node.set_node_id(circuit_node.node_id().to_string());
-                node.set_endpoints(RepeatedField::from_vec(circuit_node.endpoints().to_vec()));
-                #[cfg(feature = "challenge-authorization")]
-                {
-                    if let Some(public_key) = circuit_node.public_key() {
-                        node.set_public_key(public_key.clone());
-                    }
-                }
-                node
-            })
-            .collect::<Vec<SplinterNode>>();
-
-        let services: Vec<SplinterService> = store_circuit
-            .roster()
-            .iter()
-            .map(|store_service| {
-                let mut service = SplinterService::new();
-                service.set_service_id(store_service.service_id().to_string());
-                service.set_service_type(store_service.service_type().to_string());
-                service.set_allowed_nodes(RepeatedField::from_vec(vec![store_service
-                    .node_id()
-                    .to_string()]));
-                service
-            })
-            .collect::<Vec<SplinterService>>();
-        let mut circuit = Circuit::new();
-        circuit.set_circuit_id(store_circuit.circuit_id().to_string());
-        circuit.set_roster(RepeatedField::from_vec(services));
-        circuit.set_members(RepeatedField::from_vec(circuit_members));
-        circuit.set_authorization_type(Circuit_AuthorizationType::from(
-            store_circuit.authorization_type(),
-        ));
-        circuit.set_persistence(Circuit_PersistenceType::from(store_circuit.persistence()));
-        circuit.set_durability(Circuit_DurabilityType::from(store_circuit.durability()));
-        circuit.set_routes(Circuit_RouteType::from(store_circuit.routes()));
-        circuit.set_circuit_management_type(store_circuit.circuit_management_type().to_string());
-        if let Some(display) = store_circuit.display_name() {
-            circuit.set_display_name(display.to_string());
-        }
-        circuit.set_circuit_version(store_circuit.circuit_version());
-        circuit.set_circuit_status(Circuit_CircuitStatus::from(store_circuit.circuit_status()));
+        let circuit = store_circuit_to_proto(store_circuit);
 
+        let application_metadata = store_circuit.application_metadata().clone();
         // Creating the `Abandoned` StoreCircuit
         let mut store_circuit = StoreCircuitBuilder::new()
             .with_circuit_id(store_circuit.circuit_id())
@@ -3069,6 +3433,9 @@ impl AdminServiceShared {
         if let Some(display_name) = store_circuit.display_name() {
             store_circuit = store_circuit.with_display_name(&display_name);
         }
+        if let Some(application_metadata) = application_metadata {
+            store_circuit = store_circuit.with_application_metadata(&application_metadata);
+        }
 
         Ok((
             circuit,
@@ -3081,6 +3448,549 @@ impl AdminServiceShared {
         ))
     }
 
+    /// Returns the current definition of a circuit, if the circuit exists
+    #[cfg(feature = "circuit-update")]
+    pub fn get_circuit_definition(
+        &self,
+        circuit_id: &str,
+    ) -> Result<Option<Circuit>, AdminSharedError> {
+        Ok(self
+            .admin_store
+            .get_circuit(circuit_id)?
+            .as_ref()
+            .map(store_circuit_to_proto))
+    }
+
+    /// Returns the current definition of the circuit being updated. If this node is being added
+    /// to the circuit, the circuit is not in the admin store and the definition that was sent
+    /// along with the proposal is used instead.
+    #[cfg(feature = "circuit-update")]
+    fn get_circuit_to_update(&self, circuit_id: &str) -> Result<StoreCircuit, AdminSharedError> {
+        let stored_circuit = self.admin_store.get_circuit(circuit_id).map_err(|err| {
+            AdminSharedError::ValidationFailed(format!(
+                "error occurred when trying to get circuit {}",
+                err
+            ))
+        })?;
+
+        stored_circuit
+            .or_else(|| self.joining_circuits.get(circuit_id).cloned())
+            .ok_or_else(|| {
+                AdminSharedError::ValidationFailed(format!(
+                    "Received update request for a circuit that does not exist: circuit id {}",
+                    circuit_id
+                ))
+            })
+    }
+
+    /// Makes the `CircuitProposal` associated with a circuit update request by applying the
+    /// requested change to the current definition of the circuit. The updated circuit has its
+    /// `circuit_version` incremented. The current definition of the circuit is returned along with
+    /// the proposal.
+    #[cfg(feature = "circuit-update")]
+    fn make_update_circuit_proposal(
+        &self,
+        payload: &CircuitManagementPayload,
+        requester: &[u8],
+        requester_node_id: &str,
+    ) -> Result<(StoreCircuit, CircuitProposal), AdminSharedError> {
+        let circuit_id = update_request_circuit_id(payload).ok_or_else(|| {
+            AdminSharedError::ValidationFailed(
+                "CircuitManagementPayload does not contain a circuit update request".to_string(),
+            )
+        })?;
+        let current_circuit = self.get_circuit_to_update(circuit_id)?;
+
+        let mut roster = current_circuit.roster().to_vec();
+        let mut members = current_circuit.members().to_vec();
+        let mut application_metadata = current_circuit.application_metadata().clone();
+
+        let proposal_type = if payload.has_circuit_update_roster_request() {
+            let roster_request = payload.get_circuit_update_roster_request();
+            for service in roster_request.get_remove_services() {
+                let index = roster
+                    .iter()
+                    .position(|existing| existing.service_id() == service.get_service_id())
+                    .ok_or_else(|| {
+                        AdminSharedError::ValidationFailed(format!(
+                            "Unable to remove service {}, service is not in circuit {}",
+                            service.get_service_id(),
+                            circuit_id
+                        ))
+                    })?;
+                roster.remove(index);
+            }
+
+            for service in roster_request.get_add_services() {
+                if roster
+                    .iter()
+                    .any(|existing| existing.service_id() == service.get_service_id())
+                {
+                    return Err(AdminSharedError::ValidationFailed(format!(
+                        "Unable to add service {}, service is already in circuit {}",
+                        service.get_service_id(),
+                        circuit_id
+                    )));
+                }
+                let proposed_service =
+                    ProposedService::from_proto(service.clone()).map_err(|err| {
+                        AdminSharedError::ValidationFailed(format!(
+                            "Invalid service {}: {}",
+                            service.get_service_id(),
+                            err
+                        ))
+                    })?;
+                roster.push(StoreService::from(proposed_service));
+            }
+
+            CircuitProposal_ProposalType::UPDATE_ROSTER
+        } else if payload.has_circuit_update_add_node() {
+            let node = payload.get_circuit_update_add_node().get_node();
+            if members
+                .iter()
+                .any(|member| member.node_id() == node.get_node_id())
+            {
+                return Err(AdminSharedError::ValidationFailed(format!(
+                    "Unable to add node {}, node is already a member of circuit {}",
+                    node.get_node_id(),
+                    circuit_id
+                )));
+            }
+            members.push(CircuitNode::from(ProposedNode::from_proto(node.clone())));
+
+            CircuitProposal_ProposalType::ADD_NODE
+        } else if payload.has_circuit_update_remove_node() {
+            let node_id = payload.get_circuit_update_remove_node().get_node_id();
+            if node_id == requester_node_id {
+                return Err(AdminSharedError::ValidationFailed(format!(
+                    "Node {} cannot propose its own removal from circuit {}, the circuit should \
+                     be abandoned instead",
+                    node_id, circuit_id
+                )));
+            }
+            let index = members
+                .iter()
+                .position(|member| member.node_id() == node_id)
+                .ok_or_else(|| {
+                    AdminSharedError::ValidationFailed(format!(
+                        "Unable to remove node {}, node is not a member of circuit {}",
+                        node_id, circuit_id
+                    ))
+                })?;
+            members.remove(index);
+            // The services run by the removed node are removed along with it
+            roster.retain(|service| service.node_id() != node_id);
+
+            CircuitProposal_ProposalType::REMOVE_NODE
+        } else {
+            application_metadata = Some(
+                payload
+                    .get_circuit_update_application_metadata_request()
+                    .get_application_metedata()
+                    .to_vec(),
+            );
+
+            CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA
+        };
+
+        let mut builder = StoreCircuitBuilder::new()
+            .with_circuit_id(current_circuit.circuit_id())
+            .with_roster(&roster)
+            .with_members(&members)
+            .with_authorization_type(current_circuit.authorization_type())
+            .with_persistence(current_circuit.persistence())
+            .with_durability(current_circuit.durability())
+            .with_routes(current_circuit.routes())
+            .with_circuit_management_type(current_circuit.circuit_management_type())
+            .with_circuit_version(current_circuit.circuit_version() + 1)
+            .with_circuit_status(&StoreCircuitStatus::Active);
+        if let Some(display_name) = current_circuit.display_name() {
+            builder = builder.with_display_name(&display_name);
+        }
+        if let Some(application_metadata) = application_metadata {
+            if !application_metadata.is_empty() {
+                builder = builder.with_application_metadata(&application_metadata);
+            }
+        }
+
+        let proposed_circuit = store_circuit_to_proto(&builder.build().map_err(|err| {
+            AdminSharedError::ValidationFailed(format!(
+                "error occurred when trying to build circuit {}",
+                err
+            ))
+        })?);
+
+        let mut circuit_proposal = CircuitProposal::new();
+        circuit_proposal.set_proposal_type(proposal_type);
+        circuit_proposal.set_circuit_id(circuit_id.to_string());
+        circuit_proposal.set_circuit_hash(sha256(&proposed_circuit)?);
+        circuit_proposal.set_circuit_proposal(proposed_circuit);
+        circuit_proposal.set_requester(requester.to_vec());
+        circuit_proposal.set_requester_node_id(requester_node_id.to_string());
+
+        Ok((current_circuit, circuit_proposal))
+    }
+
+    /// Returns the peers, along with their endpoints, that must be connected to for a circuit
+    /// update in addition to the peers of the current circuit. If this node is a member of the
+    /// current circuit, these are the nodes being added. Otherwise, this node is being added to
+    /// the circuit and all other members of the updated circuit are returned.
+    #[cfg(feature = "circuit-update")]
+    fn list_update_peers(
+        &self,
+        current_circuit: Option<&StoreCircuit>,
+        proposed_circuit: &StoreCircuit,
+    ) -> Result<Vec<(PeerAuthorizationToken, Vec<String>)>, AdminSharedError> {
+        let current_peers: Vec<PeerAuthorizationToken> = match current_circuit {
+            Some(circuit)
+                if circuit
+                    .members()
+                    .iter()
+                    .any(|member| member.node_id() == self.node_id) =>
+            {
+                list_member_peers(circuit)?
+                    .into_iter()
+                    .map(|(token, _)| token)
+                    .collect()
+            }
+            _ => vec![],
+        };
+
+        Ok(list_member_peers(proposed_circuit)?
+            .into_iter()
+            .filter(|(token, _)| !self.is_local_node(token) && !current_peers.contains(token))
+            .collect())
+    }
+
+    /// Returns the peers that are no longer required once a circuit update has been committed. If
+    /// this node remains a member of the circuit, these are the nodes that have been removed.
+    /// Otherwise, this node has been removed from the circuit and all other members of the
+    /// current circuit are returned.
+    #[cfg(feature = "circuit-update")]
+    fn list_removed_peers(
+        &self,
+        current_circuit: &StoreCircuit,
+        proposed_circuit: &StoreCircuit,
+    ) -> Result<Vec<PeerAuthorizationToken>, AdminSharedError> {
+        let proposed_peers: Vec<PeerAuthorizationToken> = if proposed_circuit
+            .members()
+            .iter()
+            .any(|member| member.node_id() == self.node_id)
+        {
+            list_member_peers(proposed_circuit)?
+                .into_iter()
+                .map(|(token, _)| token)
+                .collect()
+        } else {
+            vec![]
+        };
+
+        Ok(list_member_peers(current_circuit)?
+            .into_iter()
+            .map(|(token, _)| token)
+            .filter(|token| !self.is_local_node(token) && !proposed_peers.contains(token))
+            .collect())
+    }
+
+    /// Removes the peer refs that were added for a proposal. For a circuit update proposal, only
+    /// the peer refs added for the update are removed, as the remaining refs belong to the
+    /// current circuit.
+    fn remove_proposal_peer_refs(
+        &mut self,
+        proposal: &StoreProposal,
+    ) -> Result<(), AdminSharedError> {
+        #[cfg(feature = "circuit-update")]
+        {
+            if is_update_proposal_type(proposal.proposal_type()) {
+                let current_circuit = self.admin_store.get_circuit(proposal.circuit_id())?;
+                let proposed_circuit = StoreCircuit::from(proposal.circuit().clone());
+                let peers = self
+                    .list_update_peers(current_circuit.as_ref(), &proposed_circuit)?
+                    .into_iter()
+                    .map(|(token, _)| token)
+                    .collect();
+                self.remove_peer_refs(peers);
+                return Ok(());
+            }
+        }
+
+        self.remove_peer_refs(proposal.circuit().list_tokens().map_err(|err| {
+            AdminSharedError::SplinterStateError(format!(
+                "Unable to remove peer refs for proposal {}: {}",
+                proposal.circuit_id(),
+                err
+            ))
+        })?);
+
+        Ok(())
+    }
+
+    /// Validates a circuit update request using the following:
+    ///
+    /// - Validate the protocol version used by the submitter node. Currently, updating a circuit
+    ///   is only available to nodes using `ADMIN_SERVICE_PROTOCOL_VERSION` 2.
+    /// - Validate the requester is authorized to propose a change for the requesting node
+    /// - Validate the signer's public key is authorized for the requesting node
+    /// - Validate a `CircuitProposal` with the same ID is not present
+    /// - Validate the circuit being updated has a `circuit_status` of `Active`, a
+    ///   `circuit_version` of at least 2 and that the requesting node is one of its members
+    /// - Validate the updated circuit, if this node is a member of the updated circuit
+    #[cfg(feature = "circuit-update")]
+    fn validate_update_circuit(
+        &self,
+        current_circuit: &StoreCircuit,
+        circuit: &Circuit,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+        protocol: u32,
+    ) -> Result<(), AdminSharedError> {
+        if protocol != ADMIN_SERVICE_PROTOCOL_VERSION {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Circuit-Update is not available for protocol version {}",
+                protocol
+            )));
+        }
+
+        if requester_node_id.is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "requester_node_id is empty".to_string(),
+            ));
+        }
+
+        self.validate_key(signer_public_key)?;
+
+        if !self
+            .key_verifier
+            .is_permitted(requester_node_id, signer_public_key)?
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "{} is not registered for the requester node {}",
+                to_hex(signer_public_key),
+                requester_node_id,
+            )));
+        }
+
+        self.key_permission_manager
+            .is_permitted(signer_public_key, PROPOSER_ROLE)
+            .map_err(|_| {
+                AdminSharedError::ValidationFailed(format!(
+                    "{} is not permitted to update circuits for node {}",
+                    to_hex(signer_public_key),
+                    requester_node_id
+                ))
+            })?;
+
+        if self.has_proposal(circuit.get_circuit_id())? {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Ignoring duplicate proposal for circuit {}",
+                circuit.get_circuit_id()
+            )));
+        }
+
+        if current_circuit.circuit_status() != &StoreCircuitStatus::Active {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Attempting to update an inactive circuit {}",
+                circuit.get_circuit_id()
+            )));
+        }
+
+        if current_circuit.circuit_version() < CIRCUIT_PROTOCOL_VERSION {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Attempting to update a circuit with schema version {}, must be at least {}",
+                current_circuit.circuit_version(),
+                CIRCUIT_PROTOCOL_VERSION,
+            )));
+        }
+
+        if !current_circuit
+            .members()
+            .iter()
+            .any(|member| member.node_id() == requester_node_id)
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Requester node {} is not a member of circuit {}",
+                requester_node_id,
+                circuit.get_circuit_id()
+            )));
+        }
+
+        // A node that is being removed from the circuit does not validate the updated circuit, as
+        // it is no longer one of the circuit's members
+        if circuit
+            .get_members()
+            .iter()
+            .any(|member| member.get_node_id() == self.node_id)
+        {
+            self.validate_circuit(circuit)?;
+        }
+
+        Ok(())
+    }
+
+    /// Commits an accepted circuit update proposal.
+    ///
+    /// The updated circuit replaces the current circuit in the admin store and the routing table.
+    /// Unless only the application metadata has changed, this node's services are stopped and
+    /// are initialized again once all members of the updated circuit are ready. If this node has
+    /// been removed from the circuit, the circuit is instead marked as `Abandoned` for this node.
+    /// If this node has been added to the circuit, the proposal is upgraded to a circuit.
+    #[cfg(feature = "circuit-update")]
+    fn commit_circuit_update(
+        &mut self,
+        circuit_proposal: CircuitProposal,
+        signer_public_key: Vec<u8>,
+    ) -> Result<(), AdminSharedError> {
+        let circuit_id = circuit_proposal.get_circuit_id().to_string();
+        let mgmt_type = circuit_proposal
+            .get_circuit_proposal()
+            .get_circuit_management_type()
+            .to_string();
+        let proposed_circuit = StoreCircuit::try_from(circuit_proposal.get_circuit_proposal())
+            .map_err(|err| {
+                AdminSharedError::SplinterStateError(format!(
+                    "Unable to convert proto Circuit to store Circuit: {}",
+                    err
+                ))
+            })?;
+        let metadata_only = circuit_proposal.get_proposal_type()
+            == CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA;
+        let is_member = proposed_circuit
+            .members()
+            .iter()
+            .any(|member| member.node_id() == self.node_id);
+
+        match self.admin_store.get_circuit(&circuit_id)? {
+            Some(current_circuit) => {
+                if !metadata_only {
+                    self.stop_services(&store_circuit_to_proto(&current_circuit))?;
+                }
+                self.routing_table_writer
+                    .remove_circuit(&circuit_id)
+                    .map_err(|_| {
+                        AdminSharedError::SplinterStateError(format!(
+                            "Unable to remove circuit from routing table: {}",
+                            circuit_id
+                        ))
+                    })?;
+
+                let updated_circuit = if is_member {
+                    proposed_circuit.clone()
+                } else {
+                    // This node has been removed from the circuit
+                    let (_, abandoned_circuit) = self.make_abandoned_circuit(&current_circuit)?;
+                    abandoned_circuit
+                };
+                self.admin_store
+                    .update_circuit(updated_circuit)
+                    .map_err(|_| {
+                        AdminSharedError::SplinterStateError(format!(
+                            "Unable to update circuit {}",
+                            circuit_id
+                        ))
+                    })
+                    .and_then(|_| self.remove_proposal(&circuit_id))?;
+
+                let removed_peers = self.list_removed_peers(&current_circuit, &proposed_circuit)?;
+                self.remove_peer_refs(removed_peers);
+            }
+            // This node has been added to the circuit
+            None => self.admin_store.upgrade_proposal_to_circuit(&circuit_id)?,
+        }
+
+        #[cfg(feature = "admin-service-count")]
+        self.update_metrics()?;
+
+        // send message about circuit update proposal being accepted
+        let circuit_proposal_proto =
+            messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                .map_err(AdminSharedError::InvalidMessageFormat)?;
+        let event = messages::AdminServiceEvent::ProposalAccepted((
+            circuit_proposal_proto,
+            signer_public_key,
+        ));
+        self.send_event(&mgmt_type, event);
+
+        if !is_member {
+            info!("removed from circuit {}", circuit_id);
+            return Ok(());
+        }
+
+        let routing_circuit = routing::Circuit::new(
+            proposed_circuit.circuit_id().to_string(),
+            proposed_circuit
+                .roster()
+                .iter()
+                .map(|service| {
+                    routing::Service::new(
+                        service.service_id().to_string(),
+                        service.service_type().to_string(),
+                        service.node_id().to_string(),
+                        service.arguments().to_vec(),
+                    )
+                })
+                .collect(),
+            proposed_circuit
+                .members()
+                .iter()
+                .map(|node| node.node_id().to_string())
+                .collect(),
+            #[cfg(feature = "challenge-authorization")]
+            proposed_circuit.authorization_type().into(),
+            #[cfg(feature = "circuit-durability")]
+            proposed_circuit.durability().into(),
+            #[cfg(feature = "circuit-relay")]
+            proposed_circuit.routes().into(),
+        );
+
+        let routing_members = proposed_circuit
+            .members()
+            .iter()
+            .map(|node| {
+                routing::CircuitNode::new(
+                    node.node_id().to_string(),
+                    node.endpoints().to_vec(),
+                    #[cfg(feature = "challenge-authorization")]
+                    node.public_key().clone(),
+                )
+            })
+            .collect::<Vec<routing::CircuitNode>>();
+
+        self.routing_table_writer
+            .add_circuit(circuit_id.clone(), routing_circuit, routing_members)
+            .map_err(|_| {
+                AdminSharedError::SplinterStateError(format!(
+                    "Unable to add updated circuit to routing table: {}",
+                    circuit_id
+                ))
+            })?;
+
+        if !metadata_only {
+            // send MEMBER_READY message to all other members' admin services
+            if let Some(ref network_sender) = self.network_sender {
+                let mut member_ready = MemberReady::new();
+                member_ready.set_circuit_id(circuit_id.clone());
+                member_ready.set_member_node_id(self.node_id.clone());
+                let mut msg = AdminMessage::new();
+                msg.set_message_type(AdminMessage_Type::MEMBER_READY);
+                msg.set_member_ready(member_ready);
+
+                let envelope_bytes = msg.write_to_bytes().map_err(MarshallingError::from)?;
+                for member in proposed_circuit.members().iter() {
+                    if member.node_id() != self.node_id {
+                        network_sender
+                            .send(&admin_service_id(member.node_id()), &envelope_bytes)?;
+                    }
+                }
+            }
+
+            // add circuit as pending service initialization
+            self.add_uninitialized_circuit(circuit_proposal)?;
+        }
+
+        info!("committed update to circuit {}", circuit_id);
+        Ok(())
+    }
+
     /// Initialize all services that this node should run on the created circuit using the service
     /// orchestrator. This may not include all services if they are not supported locally. It is
     /// expected that some services will be started externally.
@@ -3355,6 +4265,133 @@ impl AdminServiceShared {
     }
 }
 
+/// Returns the ID of the circuit being updated, if the payload contains a circuit update request
+#[cfg(feature = "circuit-update")]
+fn update_request_circuit_id(payload: &CircuitManagementPayload) -> Option<&str> {
+    if payload.has_circuit_update_roster_request() {
+        Some(payload.get_circuit_update_roster_request().get_circuit_id())
+    } else if payload.has_circuit_update_add_node() {
+        Some(payload.get_circuit_update_add_node().get_circuit_id())
+    } else if payload.has_circuit_update_remove_node() {
+        Some(payload.get_circuit_update_remove_node().get_circuit_id())
+    } else if payload.has_circuit_update_application_metadata_request() {
+        Some(
+            payload
+                .get_circuit_update_application_metadata_request()
+                .get_circuit_id(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Returns whether the proposal type is for an update to an existing circuit
+#[cfg(feature = "circuit-update")]
+fn is_update_proposal_type(proposal_type: &ProposalType) -> bool {
+    matches!(
+        proposal_type,
+        ProposalType::UpdateRoster
+            | ProposalType::AddNode
+            | ProposalType::RemoveNode
+            | ProposalType::UpdateApplicationMetadata
+    )
+}
+
+/// Returns the peer authorization token and endpoints of each member of the circuit
+#[cfg(feature = "circuit-update")]
+fn list_member_peers(
+    circuit: &StoreCircuit,
+) -> Result<Vec<(PeerAuthorizationToken, Vec<String>)>, AdminSharedError> {
+    let tokens = circuit.list_tokens().map_err(|err| {
+        AdminSharedError::SplinterStateError(format!(
+            "Unable to get peer tokens for members of circuit {}: {}",
+            circuit.circuit_id(),
+            err
+        ))
+    })?;
+
+    Ok(tokens
+        .into_iter()
+        .zip(
+            circuit
+                .members()
+                .iter()
+                .map(|member| member.endpoints().to_vec()),
+        )
+        .collect())
+}
+
+/// Converts a `StoreCircuit` into its protobuf representation, including the application metadata
+/// and, with the `circuit-update` feature, the service arguments
+fn store_circuit_to_proto(store_circuit: &StoreCircuit) -> Circuit {
+    let members = store_circuit
+        .members()
+        .iter()
+        .map(|circuit_node| {
+            let mut node = SplinterNode::new();
+            node.set_node_id(circuit_node.node_id().to_string());
+            node.set_endpoints(RepeatedField::from_vec(circuit_node.endpoints().to_vec()));
+            #[cfg(feature = "challenge-authorization")]
+            {
+                if let Some(public_key) = circuit_node.public_key() {
+                    node.set_public_key(public_key.clone());
+                }
+            }
+            node
+        })
+        .collect::<Vec<SplinterNode>>();
+
+    let services = store_circuit
+        .roster()
+        .iter()
+        .map(|store_service| {
+            let mut service = SplinterService::new();
+            service.set_service_id(store_service.service_id().to_string());
+            service.set_service_type(store_service.service_type().to_string());
+            service.set_allowed_nodes(RepeatedField::from_vec(vec![store_service
+                .node_id()
+                .to_string()]));
+            // Circuit updates are proposed from the stored definition, so it must be complete
+            #[cfg(feature = "circuit-update")]
+            service.set_arguments(RepeatedField::from_vec(
+                store_service
+                    .arguments()
+                    .iter()
+                    .map(|(key, value)| {
+                        let mut argument = SplinterService_Argument::new();
+                        argument.set_key(key.to_string());
+                        argument.set_value(value.to_string());
+                        argument
+                    })
+                    .collect(),
+            ));
+            service
+        })
+        .collect::<Vec<SplinterService>>();
+
+    let mut circuit = Circuit::new();
+    circuit.set_circuit_id(store_circuit.circuit_id().to_string());
+    circuit.set_roster(RepeatedField::from_vec(services));
+    circuit.set_members(RepeatedField::from_vec(members));
+    circuit.set_authorization_type(Circuit_AuthorizationType::from(
+        store_circuit.authorization_type(),
+    ));
+    circuit.set_persistence(Circuit_PersistenceType::from(store_circuit.persistence()));
+    circuit.set_durability(Circuit_DurabilityType::from(store_circuit.durability()));
+    circuit.set_routes(Circuit_RouteType::from(store_circuit.routes()));
+    circuit.set_circuit_management_type(store_circuit.circuit_management_type().to_string());
+    if let Some(display_name) = store_circuit.display_name() {
+        circuit.set_display_name(display_name.to_string());
+    }
+    if let Some(application_metadata) = store_circuit.application_metadata() {
+        circuit.set_application_metadata(application_metadata.to_vec());
+    }
+    circuit.set_circuit_version(store_circuit.circuit_version());
+    circuit.set_circuit_status(Circuit_CircuitStatus::from(store_circuit.circuit_status()));
+
+    circuit
+}
+
 #[cfg(all(test, feature = "sqlite"))]
 mod tests {
     use super::*;
@@ -6827,6 +7864,394 @@ mod tests {
         shutdown(mesh, cm, pm);
     }
 
+    /// Tests that a valid circuit update request is validated successfully.
+    ///
+    /// 1. Set up `AdminServiceShared`
+    /// 2. Create the `Active` circuit being updated
+    /// 3. Call `validate_update_circuit` with the current circuit, a valid updated circuit and
+    ///    valid requester info
+    /// 4. Validate the call to `validate_update_circuit` returns successfully
+    ///
+    /// This test verifies the `validate_update_circuit` returns successfully when given a valid
+    /// request to update an existing circuit.
+    #[cfg(feature = "circuit-update")]
+    #[test]
+    fn test_validate_update_circuit_valid() {
+        let store = setup_admin_service_store();
+        let event_store = store.clone_boxed();
+
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let signature_verifier = Secp256k1Context::new().new_verifier();
+
+        let table = RoutingTable::default();
+        let writer: Box<dyn RoutingTableWriter> = Box::new(table.clone());
+
+        let shared = AdminServiceShared::new(
+            "node_a".into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            store,
+            signature_verifier,
+            Box::new(MockAdminKeyVerifier::default()),
+            Box::new(AllowAllKeyPermissionManager),
+            writer,
+            event_store,
+            #[cfg(feature = "challenge-authorization")]
+            vec![],
+        );
+
+        let current_circuit = store_circuit(CIRCUIT_PROTOCOL_VERSION, StoreCircuitStatus::Active);
+
+        if let Err(err) = shared.validate_update_circuit(
+            &current_circuit,
+            &setup_test_circuit(),
+            PUB_KEY,
+            "node_a",
+            ADMIN_SERVICE_PROTOCOL_VERSION,
+        ) {
+            panic!("Should have been valid: {}", err);
+        }
+
+        shutdown(mesh, cm, pm);
+    }
+
+    /// Tests that a circuit update request is invalid if the circuit being updated is not
+    /// `Active`.
+    ///
+    /// 1. Set up `AdminServiceShared`
+    /// 2. Create the `Disbanded` circuit being updated
+    /// 3. Call `validate_update_circuit` with the current circuit, a valid updated circuit and
+    ///    valid requester info
+    /// 4. Validate the call to `validate_update_circuit` returns an error
+    ///
+    /// This test verifies the `validate_update_circuit` returns an error when the circuit being
+    /// updated has already been disbanded.
+    #[cfg(feature = "circuit-update")]
+    #[test]
+    fn test_validate_update_circuit_inactive_circuit() {
+        let store = setup_admin_service_store();
+        let event_store = store.clone_boxed();
+
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let signature_verifier = Secp256k1Context::new().new_verifier();
+
+        let table = RoutingTable::default();
+        let writer: Box<dyn RoutingTableWriter> = Box::new(table.clone());
+
+        let shared = AdminServiceShared::new(
+            "node_a".into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            store,
+            signature_verifier,
+            Box::new(MockAdminKeyVerifier::default()),
+            Box::new(AllowAllKeyPermissionManager),
+            writer,
+            event_store,
+            #[cfg(feature = "challenge-authorization")]
+            vec![],
+        );
+
+        let current_circuit =
+            store_circuit(CIRCUIT_PROTOCOL_VERSION, StoreCircuitStatus::Disbanded);
+
+        if let Ok(()) = shared.validate_update_circuit(
+            &current_circuit,
+            &setup_test_circuit(),
+            PUB_KEY,
+            "node_a",
+            ADMIN_SERVICE_PROTOCOL_VERSION,
+        ) {
+            panic!("Should have been invalid because the circuit being updated is disbanded");
+        }
+
+        shutdown(mesh, cm, pm);
+    }
+
+    /// Tests that a circuit update request is invalid if the requesting node is not a member of
+    /// the circuit being updated.
+    ///
+    /// 1. Set up `AdminServiceShared`
+    /// 2. Create the `Active` circuit being updated
+    /// 3. Call `validate_update_circuit` with the current circuit, a valid updated circuit and a
+    ///    requester node ID that is not a member of the circuit
+    /// 4. Validate the call to `validate_update_circuit` returns an error
+    ///
+    /// This test verifies the `validate_update_circuit` returns an error when the requesting node
+    /// is not a member of the circuit being updated.
+    #[cfg(feature = "circuit-update")]
+    #[test]
+    fn test_validate_update_circuit_requester_not_member() {
+        let store = setup_admin_service_store();
+        let event_store = store.clone_boxed();
+
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let signature_verifier = Secp256k1Context::new().new_verifier();
+
+        let table = RoutingTable::default();
+        let writer: Box<dyn RoutingTableWriter> = Box::new(table.clone());
+
+        let shared = AdminServiceShared::new(
+            "node_a".into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            store,
+            signature_verifier,
+            Box::new(MockAdminKeyVerifier::default()),
+            Box::new(AllowAllKeyPermissionManager),
+            writer,
+            event_store,
+            #[cfg(feature = "challenge-authorization")]
+            vec![],
+        );
+
+        let current_circuit = store_circuit(CIRCUIT_PROTOCOL_VERSION, StoreCircuitStatus::Active);
+
+        if let Ok(()) = shared.validate_update_circuit(
+            &current_circuit,
+            &setup_test_circuit(),
+            PUB_KEY,
+            "node_c",
+            ADMIN_SERVICE_PROTOCOL_VERSION,
+        ) {
+            panic!("Should have been invalid because the requester is not a circuit member");
+        }
+
+        shutdown(mesh, cm, pm);
+    }
+
+    /// Tests that the proposal for a circuit update request is made by applying the requested
+    /// change to the current definition of the circuit.
+    ///
+    /// 1. Set up `AdminServiceShared`
+    /// 2. Add an `Active` circuit to the admin store
+    /// 3. Call `make_update_circuit_proposal` with a request to update the application metadata
+    /// 4. Validate the proposed circuit has the new application metadata and an incremented
+    ///    `circuit_version`
+    /// 5. Call `make_update_circuit_proposal` with a request to remove `node_b`
+    /// 6. Validate the proposed circuit no longer contains `node_b` or its services
+    /// 7. Call `make_update_circuit_proposal` with a request from `node_a` to remove itself
+    /// 8. Validate the call to `make_update_circuit_proposal` returns an error
+    #[cfg(feature = "circuit-update")]
+    #[test]
+    fn test_make_update_circuit_proposal() {
+        let store = setup_admin_service_store();
+        let event_store = store.clone_boxed();
+
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let signature_verifier = Secp256k1Context::new().new_verifier();
+
+        let table = RoutingTable::default();
+        let writer: Box<dyn RoutingTableWriter> = Box::new(table.clone());
+
+        let shared = AdminServiceShared::new(
+            "node_a".into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            store,
+            signature_verifier,
+            Box::new(MockAdminKeyVerifier::default()),
+            Box::new(AllowAllKeyPermissionManager),
+            writer,
+            event_store,
+            #[cfg(feature = "challenge-authorization")]
+            vec![],
+        );
+
+        shared
+            .admin_store
+            .add_circuit(
+                store_circuit(CIRCUIT_PROTOCOL_VERSION, StoreCircuitStatus::Active),
+                store_circuit_nodes(),
+            )
+            .expect("unable to add circuit to store");
+
+        let mut metadata_request = admin::CircuitUpdateApplicationMetadataRequest::new();
+        metadata_request.set_circuit_id("01234-ABCDE".to_string());
+        metadata_request.set_application_metedata(b"updated_data".to_vec());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_circuit_update_application_metadata_request(metadata_request);
+
+        let (current_circuit, proposal) = shared
+            .make_update_circuit_proposal(&payload, PUB_KEY, "node_a")
+            .expect("Unable to make update proposal");
+
+        assert_eq!(current_circuit.circuit_version(), CIRCUIT_PROTOCOL_VERSION);
+        assert_eq!(
+            proposal.get_proposal_type(),
+            CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA
+        );
+        assert_eq!(
+            proposal.get_circuit_proposal().get_application_metadata(),
+            b"updated_data"
+        );
+        assert_eq!(
+            proposal.get_circuit_proposal().get_circuit_version(),
+            CIRCUIT_PROTOCOL_VERSION + 1
+        );
+        assert_eq!(
+            proposal.get_circuit_hash(),
+            sha256(proposal.get_circuit_proposal()).expect("Unable to hash circuit")
+        );
+
+        let mut remove_node = admin::CircuitUpdateRemoveNodeRequest::new();
+        remove_node.set_circuit_id("01234-ABCDE".to_string());
+        remove_node.set_node_id("node_b".to_string());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_circuit_update_remove_node(remove_node);
+
+        let (_, proposal) = shared
+            .make_update_circuit_proposal(&payload, PUB_KEY, "node_a")
+            .expect("Unable to make update proposal");
+
+        assert_eq!(
+            proposal.get_proposal_type(),
+            CircuitProposal_ProposalType::REMOVE_NODE
+        );
+        let proposed_circuit = proposal.get_circuit_proposal();
+        assert_eq!(
+            proposed_circuit
+                .get_members()
+                .iter()
+                .map(|member| member.get_node_id())
+                .collect::<Vec<_>>(),
+            vec!["node_a"]
+        );
+        assert_eq!(
+            proposed_circuit
+                .get_roster()
+                .iter()
+                .map(|service| service.get_service_id())
+                .collect::<Vec<_>>(),
+            vec!["0123"]
+        );
+
+        let mut remove_node = admin::CircuitUpdateRemoveNodeRequest::new();
+        remove_node.set_circuit_id("01234-ABCDE".to_string());
+        remove_node.set_node_id("node_a".to_string());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_circuit_update_remove_node(remove_node);
+
+        assert!(
+            shared
+                .make_update_circuit_proposal(&payload, PUB_KEY, "node_a")
+                .is_err(),
+            "Should have been invalid because a node cannot propose its own removal"
+        );
+
+        shutdown(mesh, cm, pm);
+    }
+
+    /// Tests that committing an accepted circuit update proposal replaces the circuit in the
+    /// admin store and the routing table.
+    ///
+    /// 1. Set up `AdminServiceShared`
+    /// 2. Add an `Active` circuit to the admin store
+    /// 3. Make and add a proposal to update the circuit's application metadata
+    /// 4. Call `commit_circuit_update` with the proposal
+    /// 5. Validate the circuit in the admin store has been updated and the proposal removed
+    /// 6. Validate the updated circuit is in the routing table
+    #[cfg(feature = "circuit-update")]
+    #[test]
+    fn test_commit_circuit_update() {
+        use crate::circuit::routing::RoutingTableReader;
+
+        let store = setup_admin_service_store();
+        let event_store = store.clone_boxed();
+
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let signature_verifier = Secp256k1Context::new().new_verifier();
+
+        let table = RoutingTable::default();
+        let writer: Box<dyn RoutingTableWriter> = Box::new(table.clone());
+
+        let mut shared = AdminServiceShared::new(
+            "node_a".into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            store,
+            signature_verifier,
+            Box::new(MockAdminKeyVerifier::default()),
+            Box::new(AllowAllKeyPermissionManager),
+            writer,
+            event_store,
+            #[cfg(feature = "challenge-authorization")]
+            vec![],
+        );
+
+        shared
+            .admin_store
+            .add_circuit(
+                store_circuit(CIRCUIT_PROTOCOL_VERSION, StoreCircuitStatus::Active),
+                store_circuit_nodes(),
+            )
+            .expect("unable to add circuit to store");
+
+        let mut metadata_request = admin::CircuitUpdateApplicationMetadataRequest::new();
+        metadata_request.set_circuit_id("01234-ABCDE".to_string());
+        metadata_request.set_application_metedata(b"updated_data".to_vec());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_circuit_update_application_metadata_request(metadata_request);
+
+        let (_, proposal) = shared
+            .make_update_circuit_proposal(&payload, PUB_KEY, "node_a")
+            .expect("Unable to make update proposal");
+        shared
+            .add_proposal(proposal.clone())
+            .expect("Unable to add proposal");
+
+        if let Err(err) = shared.commit_circuit_update(proposal, PUB_KEY.to_vec()) {
+            panic!("Should have committed the circuit update: {}", err);
+        }
+
+        let updated_circuit = shared
+            .admin_store
+            .get_circuit("01234-ABCDE")
+            .expect("Unable to get circuit")
+            .expect("Circuit should exist");
+        assert_eq!(
+            updated_circuit.application_metadata(),
+            &Some(b"updated_data".to_vec())
+        );
+        assert_eq!(
+            updated_circuit.circuit_version(),
+            CIRCUIT_PROTOCOL_VERSION + 1
+        );
+        assert!(shared
+            .admin_store
+            .get_proposal("01234-ABCDE")
+            .expect("Unable to get proposal")
+            .is_none());
+
+        let routing_circuit = table
+            .get_circuit("01234-ABCDE")
+            .expect("Unable to get circuit from routing table")
+            .expect("Circuit should be in the routing table");
+        assert_eq!(routing_circuit.members(), &["node_a", "node_b"]);
+
+        shutdown(mesh, cm, pm);
+    }
+
     pub fn setup_test_circuit() -> Circuit {
         let mut service_a = SplinterService::new();
         service_a.set_service_id("0123".to_string());
@@ -39,6 +39,7 @@ pub struct Circuit {
     display_name: Option<String>,
     circuit_version: i32,
     circuit_status: CircuitStatus,
+    application_metadata: Option<Vec<u8>>,
 }
 
 impl Circuit {
@@ -96,6 +97,11 @@ impl Circuit {
     pub fn circuit_status(&self) -> &CircuitStatus {
         &self.circuit_status
     }
+
+    /// Returns the application metadata for the circuit
+    pub fn application_metadata(&self) -> &Option<Vec<u8>> {
+        &self.application_metadata
+    }
 }
 
 impl TryFrom<&admin::Circuit> for Circuit {
@@ -146,6 +152,9 @@ impl TryFrom<&admin::Circuit> for Circuit {
         if !proto.get_display_name().is_empty() {
             builder = builder.with_display_name(proto.get_display_name());
         }
+        if !proto.get_application_metadata().is_empty() {
+            builder = builder.with_application_metadata(proto.get_application_metadata());
+        }
 
         builder.build()
     }
@@ -243,12 +252,17 @@ impl From<&PersistenceType> for admin::Circuit_PersistenceType {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DurabilityType {
     NoDurability,
+    /// Direct messages are stored until the recipient node acknowledges them
+    #[cfg(feature = "circuit-durability")]
+    StoreAndForward,
 }
 
 impl From<&messages::DurabilityType> for DurabilityType {
     fn from(message_enum: &messages::DurabilityType) -> Self {
         match *message_enum {
             messages::DurabilityType::NoDurability => DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-durability")]
+            messages::DurabilityType::StoreAndForward => DurabilityType::StoreAndForward,
         }
     }
 }
@@ -259,9 +273,15 @@ impl TryFrom<&admin::Circuit_DurabilityType> for DurabilityType {
     fn try_from(proto: &admin::Circuit_DurabilityType) -> Result<Self, Self::Error> {
         match *proto {
             admin::Circuit_DurabilityType::NO_DURABILITY => Ok(DurabilityType::NoDurability),
+            #[cfg(feature = "circuit-durability")]
+            admin::Circuit_DurabilityType::STORE_AND_FORWARD => Ok(DurabilityType::StoreAndForward),
             admin::Circuit_DurabilityType::UNSET_DURABILITY_TYPE => Err(
                 InvalidStateError::with_message("DurabilityType is unset".to_string()),
             ),
+            #[cfg(not(feature = "circuit-durability"))]
+            _ => Err(InvalidStateError::with_message(
+                "DurabilityType is not supported".to_string(),
+            )),
         }
     }
 }
@@ -270,6 +290,8 @@ impl From<&DurabilityType> for admin::Circuit_DurabilityType {
     fn from(durability: &DurabilityType) -> Self {
         match *durability {
             DurabilityType::NoDurability => admin::Circuit_DurabilityType::NO_DURABILITY,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::StoreAndForward => admin::Circuit_DurabilityType::STORE_AND_FORWARD,
         }
     }
 }
@@ -278,6 +300,10 @@ impl From<&DurabilityType> for admin::Circuit_DurabilityType {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RouteType {
     Any,
+    /// Messages to a member node that is not directly connected are relayed through another
+    /// member node
+    #[cfg(feature = "circuit-relay")]
+    Relay,
 }
 
 impl Default for RouteType {
@@ -290,6 +316,8 @@ impl From<&messages::RouteType> for RouteType {
     fn from(message_enum: &messages::RouteType) -> Self {
         match *message_enum {
             messages::RouteType::Any => RouteType::Any,
+            #[cfg(feature = "circuit-relay")]
+            messages::RouteType::Relay => RouteType::Relay,
         }
     }
 }
@@ -300,9 +328,15 @@ impl TryFrom<&admin::Circuit_RouteType> for RouteType {
     fn try_from(proto: &admin::Circuit_RouteType) -> Result<Self, Self::Error> {
         match *proto {
             admin::Circuit_RouteType::ANY_ROUTE => Ok(RouteType::Any),
+            #[cfg(feature = "circuit-relay")]
+            admin::Circuit_RouteType::RELAY_ROUTE => Ok(RouteType::Relay),
             admin::Circuit_RouteType::UNSET_ROUTE_TYPE => Err(InvalidStateError::with_message(
                 "RouteType is unset".to_string(),
             )),
+            #[cfg(not(feature = "circuit-relay"))]
+            _ => Err(InvalidStateError::with_message(
+                "RouteType is not supported".to_string(),
+            )),
         }
     }
 }
@@ -311,6 +345,8 @@ impl From<&RouteType> for admin::Circuit_RouteType {
     fn from(route: &RouteType) -> Self {
         match *route {
             RouteType::Any => admin::Circuit_RouteType::ANY_ROUTE,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Relay => admin::Circuit_RouteType::RELAY_ROUTE,
         }
     }
 }
@@ -379,6 +415,7 @@ pub struct CircuitBuilder {
     display_name: Option<String>,
     circuit_version: Option<i32>,
     circuit_status: Option<CircuitStatus>,
+    application_metadata: Option<Vec<u8>>,
 }
 
 impl CircuitBuilder {
@@ -442,6 +479,11 @@ impl CircuitBuilder {
         self.circuit_status.clone()
     }
 
+    /// Returns the application metadata in the builder
+    pub fn application_metadata(&self) -> Option<Vec<u8>> {
+        self.application_metadata.clone()
+    }
+
     /// Sets the circuit ID
     ///
     /// # Arguments
@@ -557,6 +599,16 @@ impl CircuitBuilder {
         self
     }
 
+    /// Sets the application metadata for the circuit
+    ///
+    /// # Arguments
+    ///
+    ///  * `application_metadata` - The opaque application metadata for the circuit
+    pub fn with_application_metadata(mut self, application_metadata: &[u8]) -> CircuitBuilder {
+        self.application_metadata = Some(application_metadata.into());
+        self
+    }
+
     /// Builds a `Circuit`
     ///
     /// Returns an error if the circuit ID, roster, members or circuit management
@@ -606,6 +658,8 @@ impl CircuitBuilder {
 
         let circuit_status = self.circuit_status.unwrap_or_default();
 
+        let application_metadata = self.application_metadata;
+
         let circuit = Circuit {
             id: circuit_id,
             roster,
@@ -618,6 +672,7 @@ impl CircuitBuilder {
             display_name,
             circuit_version,
             circuit_status,
+            application_metadata,
         };
 
         Ok(circuit)
@@ -638,6 +693,27 @@ impl From<ProposedCircuit> for Circuit {
             display_name: circuit.display_name().clone(),
             circuit_version: circuit.circuit_version(),
             circuit_status: circuit.circuit_status().clone(),
+            application_metadata: circuit.application_metadata().clone(),
+        }
+    }
+}
+
+#[cfg(feature = "circuit-durability")]
+impl From<&DurabilityType> for routing::DurabilityType {
+    fn from(durability: &DurabilityType) -> Self {
+        match durability {
+            DurabilityType::NoDurability => routing::DurabilityType::NoDurability,
+            DurabilityType::StoreAndForward => routing::DurabilityType::StoreAndForward,
+        }
+    }
+}
+
+#[cfg(feature = "circuit-relay")]
+impl From<&RouteType> for routing::RouteType {
+    fn from(route_type: &RouteType) -> Self {
+        match route_type {
+            RouteType::Any => routing::RouteType::Any,
+            RouteType::Relay => routing::RouteType::Relay,
         }
     }
 }
@@ -87,6 +87,9 @@ impl CircuitProposal {
             admin::CircuitProposal_ProposalType::UPDATE_ROSTER => ProposalType::UpdateRoster,
             admin::CircuitProposal_ProposalType::ADD_NODE => ProposalType::AddNode,
             admin::CircuitProposal_ProposalType::REMOVE_NODE => ProposalType::RemoveNode,
+            admin::CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA => {
+                ProposalType::UpdateApplicationMetadata
+            }
             admin::CircuitProposal_ProposalType::DISBAND => ProposalType::Disband,
             admin::CircuitProposal_ProposalType::UNSET_PROPOSAL_TYPE => {
                 return Err(InvalidStateError::with_message(
@@ -118,6 +121,9 @@ impl CircuitProposal {
             ProposalType::UpdateRoster => admin::CircuitProposal_ProposalType::UPDATE_ROSTER,
             ProposalType::AddNode => admin::CircuitProposal_ProposalType::ADD_NODE,
             ProposalType::RemoveNode => admin::CircuitProposal_ProposalType::REMOVE_NODE,
+            ProposalType::UpdateApplicationMetadata => {
+                admin::CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA
+            }
             ProposalType::Disband => admin::CircuitProposal_ProposalType::DISBAND,
         };
 
@@ -497,6 +503,7 @@ pub enum ProposalType {
     UpdateRoster,
     AddNode,
     RemoveNode,
+    UpdateApplicationMetadata,
     Disband,
 }
 
@@ -507,6 +514,9 @@ impl From<&messages::ProposalType> for ProposalType {
             messages::ProposalType::UpdateRoster => ProposalType::UpdateRoster,
             messages::ProposalType::AddNode => ProposalType::AddNode,
             messages::ProposalType::RemoveNode => ProposalType::RemoveNode,
+            messages::ProposalType::UpdateApplicationMetadata => {
+                ProposalType::UpdateApplicationMetadata
+            }
             messages::ProposalType::Disband => ProposalType::Disband,
         }
     }
@@ -523,6 +533,9 @@ impl TryFrom<&admin::CircuitProposal_ProposalType> for ProposalType {
             admin::CircuitProposal_ProposalType::UPDATE_ROSTER => Ok(ProposalType::UpdateRoster),
             admin::CircuitProposal_ProposalType::ADD_NODE => Ok(ProposalType::AddNode),
             admin::CircuitProposal_ProposalType::REMOVE_NODE => Ok(ProposalType::RemoveNode),
+            admin::CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA => {
+                Ok(ProposalType::UpdateApplicationMetadata)
+            }
             admin::CircuitProposal_ProposalType::DISBAND => Ok(ProposalType::Disband),
             admin::CircuitProposal_ProposalType::UNSET_PROPOSAL_TYPE => Err(
                 InvalidStateError::with_message("ProposalType is unset".to_string()),
@@ -1012,6 +1012,209 @@ pub mod tests {
         assert_eq!(None, fetched_circuit);
     }
 
+    /// Verify that a circuit can be updated in the store
+    ///
+    /// 1. Run sqlite migrations
+    /// 2. Create DieselAdminServiceStore
+    /// 3. Create a circuit and nodes
+    /// 4. Add circuit to store
+    /// 5. Update the circuit with an additional member, an additional service and new
+    ///    application metadata
+    /// 6. Validate the fetched circuit matches the updated circuit
+    /// 7. Validate the endpoints of the added member were stored
+    /// 8. Validate updating a circuit that does not exist returns an error
+    #[test]
+    fn test_update_circuit() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselAdminServiceStore::new(pool);
+
+        let circuit = create_circuit("WBKLF-BBBBB", CircuitStatus::Active);
+        let nodes = create_nodes();
+
+        store
+            .add_circuit(circuit.clone(), nodes)
+            .expect("Unable to add circuit");
+
+        let mut members = circuit.members().to_vec();
+        members.push(
+            CircuitNodeBuilder::default()
+                .with_node_id("gumbo-node-000".into())
+                .with_endpoints(&vec!["tcps://splinterd-node-gumbo:8044".into()])
+                .build()
+                .expect("Unable to build node"),
+        );
+        let mut roster = circuit.roster().to_vec();
+        roster.push(
+            ServiceBuilder::default()
+                .with_service_id("a002")
+                .with_service_type("scabbard")
+                .with_node_id("gumbo-node-000")
+                .with_arguments(&vec![("peer_services".into(), "[\"a000\"]".into())])
+                .build()
+                .expect("Unable to build service"),
+        );
+
+        let updated_circuit = CircuitBuilder::default()
+            .with_circuit_id("WBKLF-BBBBB")
+            .with_roster(&roster)
+            .with_members(&members)
+            .with_circuit_management_type("gameroom")
+            .with_display_name("test_display")
+            .with_application_metadata(b"updated_metadata")
+            .with_circuit_version(4)
+            .with_circuit_status(&CircuitStatus::Active)
+            .build()
+            .expect("Unable to build circuit");
+
+        store
+            .update_circuit(updated_circuit.clone())
+            .expect("Unable to update circuit");
+
+        let fetched_circuit = store
+            .get_circuit("WBKLF-BBBBB")
+            .expect("Unable to get circuit")
+            .expect("Got None when expecting circuit");
+
+        assert_eq!(updated_circuit, fetched_circuit);
+
+        let fetched_node = store
+            .get_node("gumbo-node-000")
+            .expect("Unable to get node")
+            .expect("Got None when expecting node");
+
+        assert_eq!(
+            fetched_node.endpoints(),
+            &["tcps://splinterd-node-gumbo:8044".to_string()]
+        );
+
+        assert!(store
+            .update_circuit(create_circuit("WBKLF-AAAAA", CircuitStatus::Active))
+            .is_err());
+    }
+
+    /// Verify that several members can be added to and removed from a circuit, and that the
+    /// endpoints of a removed member are only deleted if it is not a member of another circuit
+    ///
+    /// 1. Run sqlite migrations
+    /// 2. Create DieselAdminServiceStore
+    /// 3. Add a circuit with the members `bubba-node-000` and `acme-node-000`
+    /// 4. Add a second circuit with the same members
+    /// 5. Update the first circuit with the additional members `gumbo-node-000` and
+    ///    `zydeco-node-000`
+    /// 6. Validate the fetched circuit has all four members, and the added members' endpoints
+    ///    were stored
+    /// 7. Update the first circuit to remove `bubba-node-000` and `zydeco-node-000`
+    /// 8. Validate the fetched circuit only has the remaining members
+    /// 9. Validate the endpoints of `zydeco-node-000` were deleted, while the endpoints of
+    ///    `bubba-node-000`, which is still a member of the second circuit, were kept
+    #[test]
+    fn test_update_circuit_add_and_remove_members() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselAdminServiceStore::new(pool.clone());
+
+        store
+            .add_circuit(
+                create_circuit("WBKLF-BBBBB", CircuitStatus::Active),
+                create_nodes(),
+            )
+            .expect("Unable to add circuit");
+        store
+            .add_circuit(
+                create_circuit("WBKLF-CCCCC", CircuitStatus::Active),
+                create_nodes(),
+            )
+            .expect("Unable to add circuit");
+
+        let circuit = create_circuit("WBKLF-BBBBB", CircuitStatus::Active);
+        let mut members = circuit.members().to_vec();
+        members.push(
+            CircuitNodeBuilder::default()
+                .with_node_id("gumbo-node-000".into())
+                .with_endpoints(&vec!["tcps://splinterd-node-gumbo:8044".into()])
+                .build()
+                .expect("Unable to build node"),
+        );
+        members.push(
+            CircuitNodeBuilder::default()
+                .with_node_id("zydeco-node-000".into())
+                .with_endpoints(&vec!["tcps://splinterd-node-zydeco:8044".into()])
+                .build()
+                .expect("Unable to build node"),
+        );
+
+        let added_members_circuit = CircuitBuilder::default()
+            .with_circuit_id("WBKLF-BBBBB")
+            .with_roster(circuit.roster())
+            .with_members(&members)
+            .with_circuit_management_type("gameroom")
+            .with_display_name("test_display")
+            .with_circuit_version(4)
+            .with_circuit_status(&CircuitStatus::Active)
+            .build()
+            .expect("Unable to build circuit");
+
+        store
+            .update_circuit(added_members_circuit.clone())
+            .expect("Unable to update circuit");
+
+        assert_eq!(
+            added_members_circuit,
+            store
+                .get_circuit("WBKLF-BBBBB")
+                .expect("Unable to get circuit")
+                .expect("Got None when expecting circuit")
+        );
+        assert_eq!(
+            node_endpoints(&pool, "zydeco-node-000"),
+            vec!["tcps://splinterd-node-zydeco:8044".to_string()]
+        );
+
+        let remaining_members = members
+            .into_iter()
+            .filter(|node| node.node_id() == "acme-node-000" || node.node_id() == "gumbo-node-000")
+            .collect::<Vec<_>>();
+        let removed_members_circuit = CircuitBuilder::default()
+            .with_circuit_id("WBKLF-BBBBB")
+            .with_roster(
+                &circuit
+                    .roster()
+                    .iter()
+                    .filter(|service| service.node_id() == "acme-node-000")
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            )
+            .with_members(&remaining_members)
+            .with_circuit_management_type("gameroom")
+            .with_display_name("test_display")
+            .with_circuit_version(5)
+            .with_circuit_status(&CircuitStatus::Active)
+            .build()
+            .expect("Unable to build circuit");
+
+        store
+            .update_circuit(removed_members_circuit.clone())
+            .expect("Unable to update circuit");
+
+        assert_eq!(
+            removed_members_circuit,
+            store
+                .get_circuit("WBKLF-BBBBB")
+                .expect("Unable to get circuit")
+                .expect("Got None when expecting circuit")
+        );
+        assert!(node_endpoints(&pool, "zydeco-node-000").is_empty());
+        assert_eq!(
+            node_endpoints(&pool, "bubba-node-000"),
+            vec!["tcps://splinterd-node-bubba:8044".to_string()]
+        );
+        assert_eq!(
+            node_endpoints(&pool, "gumbo-node-000"),
+            vec!["tcps://splinterd-node-gumbo:8044".to_string()]
+        );
+    }
+
     /// Verify that a service can be fetched from the store
     ///
     /// 1. Run sqlite migrations
@@ -1578,6 +1781,7 @@ pub mod tests {
             .with_display_name("test_display")
             .with_circuit_version(3)
             .with_circuit_status(&status)
+            .with_application_metadata(b"test")
             .build()
             .expect("Unable to build circuit")
     }
@@ -1706,6 +1910,20 @@ pub mod tests {
             .build().expect("Unable to build proposals")
     }
 
+    fn node_endpoints(
+        pool: &Pool<ConnectionManager<SqliteConnection>>,
+        node_id: &str,
+    ) -> Vec<String> {
+        use crate::admin::store::diesel::schema::node_endpoint;
+        use diesel::prelude::*;
+
+        node_endpoint::table
+            .filter(node_endpoint::node_id.eq(node_id))
+            .select(node_endpoint::endpoint)
+            .load::<String>(&*pool.get().expect("Unable to get connection"))
+            .expect("Unable to load node endpoints")
+    }
+
     fn create_nodes() -> Vec<CircuitNode> {
         vec![
             CircuitNodeBuilder::default()
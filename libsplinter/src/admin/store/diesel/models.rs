@@ -417,6 +417,7 @@ pub struct CircuitModel {
     pub display_name: Option<String>,
     pub circuit_version: i32,
     pub circuit_status: CircuitStatusModel,
+    pub application_metadata: Option<Vec<u8>>,
 }
 
 impl From<&Circuit> for CircuitModel {
@@ -431,6 +432,7 @@ impl From<&Circuit> for CircuitModel {
             display_name: circuit.display_name().clone(),
             circuit_version: circuit.circuit_version(),
             circuit_status: CircuitStatusModel::from(circuit.circuit_status()),
+            application_metadata: circuit.application_metadata().clone(),
         }
     }
 }
@@ -943,6 +945,7 @@ impl TryFrom<String> for ProposalType {
             "UpdateRoster" => Ok(ProposalType::UpdateRoster),
             "AddNode" => Ok(ProposalType::AddNode),
             "RemoveNode" => Ok(ProposalType::RemoveNode),
+            "UpdateApplicationMetadata" => Ok(ProposalType::UpdateApplicationMetadata),
             "Disband" => Ok(ProposalType::Disband),
             _ => Err(AdminServiceStoreError::InvalidStateError(
                 InvalidStateError::with_message("Unable to convert string to ProposalType".into()),
@@ -958,6 +961,7 @@ impl From<&ProposalType> for String {
             ProposalType::UpdateRoster => String::from("UpdateRoster"),
             ProposalType::AddNode => String::from("AddNode"),
             ProposalType::RemoveNode => String::from("RemoveNode"),
+            ProposalType::UpdateApplicationMetadata => String::from("UpdateApplicationMetadata"),
             ProposalType::Disband => String::from("Disband"),
         }
     }
@@ -970,6 +974,9 @@ impl From<&messages::ProposalType> for String {
             messages::ProposalType::UpdateRoster => String::from("UpdateRoster"),
             messages::ProposalType::AddNode => String::from("AddNode"),
             messages::ProposalType::RemoveNode => String::from("RemoveNode"),
+            messages::ProposalType::UpdateApplicationMetadata => {
+                String::from("UpdateApplicationMetadata")
+            }
             messages::ProposalType::Disband => String::from("Disband"),
         }
     }
@@ -1046,6 +1053,8 @@ impl TryFrom<String> for DurabilityType {
     fn try_from(variant: String) -> Result<Self, Self::Error> {
         match variant.as_ref() {
             "NoDurability" => Ok(DurabilityType::NoDurability),
+            #[cfg(feature = "circuit-durability")]
+            "StoreAndForward" => Ok(DurabilityType::StoreAndForward),
             _ => Err(AdminServiceStoreError::InvalidStateError(
                 InvalidStateError::with_message(
                     "Unable to convert string to DurabilityType".into(),
@@ -1059,6 +1068,8 @@ impl From<&DurabilityType> for String {
     fn from(variant: &DurabilityType) -> Self {
         match variant {
             DurabilityType::NoDurability => String::from("NoDurability"),
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::StoreAndForward => String::from("StoreAndForward"),
         }
     }
 }
@@ -1067,6 +1078,8 @@ impl From<&messages::DurabilityType> for String {
     fn from(variant: &messages::DurabilityType) -> Self {
         match variant {
             messages::DurabilityType::NoDurability => String::from("NoDurability"),
+            #[cfg(feature = "circuit-durability")]
+            messages::DurabilityType::StoreAndForward => String::from("StoreAndForward"),
         }
     }
 }
@@ -1076,6 +1089,8 @@ impl TryFrom<String> for RouteType {
     fn try_from(variant: String) -> Result<Self, Self::Error> {
         match variant.as_ref() {
             "Any" => Ok(RouteType::Any),
+            #[cfg(feature = "circuit-relay")]
+            "Relay" => Ok(RouteType::Relay),
             _ => Err(AdminServiceStoreError::InvalidStateError(
                 InvalidStateError::with_message("Unable to convert string to RouteType".into()),
             )),
@@ -1087,6 +1102,8 @@ impl From<&RouteType> for String {
     fn from(variant: &RouteType) -> Self {
         match variant {
             RouteType::Any => String::from("Any"),
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Relay => String::from("Relay"),
         }
     }
 }
@@ -1095,6 +1112,8 @@ impl From<&messages::RouteType> for String {
     fn from(variant: &messages::RouteType) -> Self {
         match variant {
             messages::RouteType::Any => String::from("Any"),
+            #[cfg(feature = "circuit-relay")]
+            messages::RouteType::Relay => String::from("Relay"),
         }
     }
 }
@@ -133,6 +133,11 @@ where
                 builder = builder.with_display_name(&display_name);
             }
 
+            // if application metadata is set, add to builder
+            if let Some(application_metadata) = circuit.application_metadata {
+                builder = builder.with_application_metadata(&application_metadata);
+            }
+
             Ok(Some(
                 builder
                     .build()
@@ -257,6 +257,10 @@ where
                     if let Some(display_name) = &model.display_name {
                         circuit_builder = circuit_builder.with_display_name(&display_name);
                     }
+                    if let Some(application_metadata) = &model.application_metadata {
+                        circuit_builder =
+                            circuit_builder.with_application_metadata(&application_metadata);
+                    }
                     if let Some(members) = circuit_members.get_mut(&model.circuit_id) {
                         members.sort_by_key(|node| node.position);
 
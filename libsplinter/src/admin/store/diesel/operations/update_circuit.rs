@@ -23,8 +23,10 @@ use diesel::{
 use super::AdminServiceStoreOperations;
 use crate::admin::store::{
     diesel::{
-        models::{CircuitMemberModel, CircuitModel, ServiceArgumentModel, ServiceModel},
-        schema::{circuit, circuit_member, service, service_argument},
+        models::{
+            CircuitMemberModel, CircuitModel, NodeEndpointModel, ServiceArgumentModel, ServiceModel,
+        },
+        schema::{circuit, circuit_member, node_endpoint, service, service_argument},
     },
     error::AdminServiceStoreError,
     Circuit,
@@ -61,9 +63,18 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
                     circuit::durability.eq(circuit_model.durability),
                     circuit::routes.eq(circuit_model.routes),
                     circuit::circuit_management_type.eq(circuit_model.circuit_management_type),
+                    circuit::display_name.eq(circuit_model.display_name),
+                    circuit::circuit_version.eq(circuit_model.circuit_version),
                     circuit::circuit_status.eq(circuit_model.circuit_status),
+                    circuit::application_metadata.eq(circuit_model.application_metadata),
                 ))
                 .execute(self.conn)?;
+            // Collect the current members, so that the `node_endpoint` entries of any member
+            // removed by this update can be deleted
+            let previous_members = circuit_member::table
+                .filter(circuit_member::circuit_id.eq(circuit.circuit_id()))
+                .select(circuit_member::node_id)
+                .load::<String>(self.conn)?;
             // Delete existing data associated with the `Circuit`
             delete(service::table.filter(service::circuit_id.eq(circuit.circuit_id())))
                 .execute(self.conn)?;
@@ -89,6 +100,46 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
             insert_into(circuit_member::table)
                 .values(circuit_member)
                 .execute(self.conn)?;
+            // Insert the endpoints of any members that do not already have associated
+            // `node_endpoint` entries, such as a node added to the circuit by this update
+            for node in circuit.members() {
+                if let Some(0) = node_endpoint::table
+                    .filter(node_endpoint::node_id.eq(node.node_id()))
+                    .count()
+                    .first(self.conn)
+                    .optional()?
+                {
+                    insert_into(node_endpoint::table)
+                        .values(
+                            node.endpoints()
+                                .iter()
+                                .map(|endpoint| NodeEndpointModel {
+                                    node_id: node.node_id().into(),
+                                    endpoint: endpoint.into(),
+                                })
+                                .collect::<Vec<NodeEndpointModel>>(),
+                        )
+                        .execute(self.conn)?;
+                }
+            }
+            // Delete the `node_endpoint` entries of removed members, unless the node is still a
+            // member of another circuit
+            for node_id in previous_members.iter().filter(|node_id| {
+                !circuit
+                    .members()
+                    .iter()
+                    .any(|node| node.node_id() == node_id.as_str())
+            }) {
+                if let Some(0) = circuit_member::table
+                    .filter(circuit_member::node_id.eq(node_id))
+                    .count()
+                    .first(self.conn)
+                    .optional()?
+                {
+                    delete(node_endpoint::table.filter(node_endpoint::node_id.eq(node_id)))
+                        .execute(self.conn)?;
+                }
+            }
             Ok(())
         })
     }
@@ -120,9 +171,18 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
                     circuit::durability.eq(circuit_model.durability),
                     circuit::routes.eq(circuit_model.routes),
                     circuit::circuit_management_type.eq(circuit_model.circuit_management_type),
+                    circuit::display_name.eq(circuit_model.display_name),
+                    circuit::circuit_version.eq(circuit_model.circuit_version),
                     circuit::circuit_status.eq(circuit_model.circuit_status),
+                    circuit::application_metadata.eq(circuit_model.application_metadata),
                 ))
                 .execute(self.conn)?;
+            // Collect the current members, so that the `node_endpoint` entries of any member
+            // removed by this update can be deleted
+            let previous_members = circuit_member::table
+                .filter(circuit_member::circuit_id.eq(circuit.circuit_id()))
+                .select(circuit_member::node_id)
+                .load::<String>(self.conn)?;
             // Delete existing data associated with the `Circuit`
             delete(service::table.filter(service::circuit_id.eq(circuit.circuit_id())))
                 .execute(self.conn)?;
@@ -148,6 +208,46 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
             insert_into(circuit_member::table)
                 .values(circuit_member)
                 .execute(self.conn)?;
+            // Insert the endpoints of any members that do not already have associated
+            // `node_endpoint` entries, such as a node added to the circuit by this update
+            for node in circuit.members() {
+                if let Some(0) = node_endpoint::table
+                    .filter(node_endpoint::node_id.eq(node.node_id()))
+                    .count()
+                    .first(self.conn)
+                    .optional()?
+                {
+                    insert_into(node_endpoint::table)
+                        .values(
+                            node.endpoints()
+                                .iter()
+                                .map(|endpoint| NodeEndpointModel {
+                                    node_id: node.node_id().into(),
+                                    endpoint: endpoint.into(),
+                                })
+                                .collect::<Vec<NodeEndpointModel>>(),
+                        )
+                        .execute(self.conn)?;
+                }
+            }
+            // Delete the `node_endpoint` entries of removed members, unless the node is still a
+            // member of another circuit
+            for node_id in previous_members.iter().filter(|node_id| {
+                !circuit
+                    .members()
+                    .iter()
+                    .any(|node| node.node_id() == node_id.as_str())
+            }) {
+                if let Some(0) = circuit_member::table
+                    .filter(circuit_member::node_id.eq(node_id))
+                    .count()
+                    .first(self.conn)
+                    .optional()?
+                {
+                    delete(node_endpoint::table.filter(node_endpoint::node_id.eq(node_id)))
+                        .execute(self.conn)?;
+                }
+            }
             Ok(())
         })
     }
@@ -134,20 +134,36 @@ impl ProposedCircuit {
 
         let durability = match proto.get_durability() {
             admin::Circuit_DurabilityType::NO_DURABILITY => DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-durability")]
+            admin::Circuit_DurabilityType::STORE_AND_FORWARD => DurabilityType::StoreAndForward,
             admin::Circuit_DurabilityType::UNSET_DURABILITY_TYPE => {
                 return Err(InvalidStateError::with_message(
                     "unable to build, missing field: `durability type`".to_string(),
                 ));
             }
+            #[cfg(not(feature = "circuit-durability"))]
+            _ => {
+                return Err(InvalidStateError::with_message(
+                    "unable to build, unsupported durability type".to_string(),
+                ));
+            }
         };
 
         let routes = match proto.get_routes() {
             admin::Circuit_RouteType::ANY_ROUTE => RouteType::Any,
+            #[cfg(feature = "circuit-relay")]
+            admin::Circuit_RouteType::RELAY_ROUTE => RouteType::Relay,
             admin::Circuit_RouteType::UNSET_ROUTE_TYPE => {
                 return Err(InvalidStateError::with_message(
                     "unable to build, missing field: `route type`".to_string(),
                 ));
             }
+            #[cfg(not(feature = "circuit-relay"))]
+            _ => {
+                return Err(InvalidStateError::with_message(
+                    "unable to build, unsupported route type".to_string(),
+                ));
+            }
         };
 
         let circuit_status = match proto.get_circuit_status() {
@@ -266,10 +282,16 @@ impl ProposedCircuit {
             DurabilityType::NoDurability => {
                 circuit.set_durability(admin::Circuit_DurabilityType::NO_DURABILITY);
             }
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::StoreAndForward => {
+                circuit.set_durability(admin::Circuit_DurabilityType::STORE_AND_FORWARD);
+            }
         };
 
         match self.routes {
             RouteType::Any => circuit.set_routes(admin::Circuit_RouteType::ANY_ROUTE),
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Relay => circuit.set_routes(admin::Circuit_RouteType::RELAY_ROUTE),
         };
 
         // If the circuit version is equal to the `CIRCUIT_PROTOCOL_VERSION`, the `circuit_status`
@@ -768,11 +768,39 @@ impl AdminServiceStore for YamlAdminServiceStore {
                 ))
             })?;
 
-            if state
+            if let Some(existing_circuit) = state
                 .circuit_state
                 .circuits
-                .contains_key(circuit.circuit_id())
+                .get(circuit.circuit_id())
+                .cloned()
             {
+                // replace the services of the circuit, as the roster may have changed
+                for service in existing_circuit.roster() {
+                    let service_id = ServiceId::new(
+                        service.service_id().to_string(),
+                        circuit.circuit_id().to_string(),
+                    );
+                    state.service_directory.remove(&service_id);
+                }
+
+                for service in circuit.roster() {
+                    let service_id = ServiceId::new(
+                        service.service_id().to_string(),
+                        circuit.circuit_id().to_string(),
+                    );
+
+                    state.service_directory.insert(service_id, service.clone());
+                }
+
+                for node in circuit.members() {
+                    if !state.circuit_state.nodes.contains_key(node.node_id()) {
+                        state
+                            .circuit_state
+                            .nodes
+                            .insert(node.node_id().to_string(), node.clone());
+                    }
+                }
+
                 state
                     .circuit_state
                     .circuits
@@ -1142,6 +1170,8 @@ struct YamlCircuit {
     circuit_version: i32,
     #[serde(default = "default_circuit_status")]
     circuit_status: YamlCircuitStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    application_metadata: Option<String>,
 }
 
 impl TryFrom<YamlCircuit> for Circuit {
@@ -1182,6 +1212,16 @@ impl TryFrom<YamlCircuit> for Circuit {
             builder = builder.with_display_name(display_name);
         }
 
+        if let Some(application_metadata) = &circuit.application_metadata {
+            builder = builder.with_application_metadata(&parse_hex(application_metadata).map_err(
+                |_| {
+                    InvalidStateError::with_message(
+                        "Circuit application metadata is not valid hex".to_string(),
+                    )
+                },
+            )?)
+        }
+
         builder.build()
     }
 }
@@ -1208,6 +1248,10 @@ impl From<Circuit> for YamlCircuit {
             display_name: circuit.display_name().clone(),
             circuit_version: circuit.circuit_version(),
             circuit_status: circuit.circuit_status().clone().into(),
+            application_metadata: circuit
+                .application_metadata()
+                .as_ref()
+                .map(|app_metadata| to_hex(&app_metadata)),
         }
     }
 }
@@ -1405,6 +1449,7 @@ pub enum YamlProposalType {
     UpdateRoster,
     AddNode,
     RemoveNode,
+    UpdateApplicationMetadata,
     Disband,
 }
 
@@ -1415,6 +1460,7 @@ impl From<YamlProposalType> for ProposalType {
             YamlProposalType::UpdateRoster => ProposalType::UpdateRoster,
             YamlProposalType::AddNode => ProposalType::AddNode,
             YamlProposalType::RemoveNode => ProposalType::RemoveNode,
+            YamlProposalType::UpdateApplicationMetadata => ProposalType::UpdateApplicationMetadata,
             YamlProposalType::Disband => ProposalType::Disband,
         }
     }
@@ -1427,6 +1473,7 @@ impl From<ProposalType> for YamlProposalType {
             ProposalType::UpdateRoster => YamlProposalType::UpdateRoster,
             ProposalType::AddNode => YamlProposalType::AddNode,
             ProposalType::RemoveNode => YamlProposalType::RemoveNode,
+            ProposalType::UpdateApplicationMetadata => YamlProposalType::UpdateApplicationMetadata,
             ProposalType::Disband => YamlProposalType::Disband,
         }
     }
@@ -1698,12 +1745,16 @@ impl From<YamlPersistenceType> for PersistenceType {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum YamlDurabilityType {
     NoDurability,
+    #[cfg(feature = "circuit-durability")]
+    StoreAndForward,
 }
 
 impl From<DurabilityType> for YamlDurabilityType {
     fn from(durability_type: DurabilityType) -> Self {
         match durability_type {
             DurabilityType::NoDurability => YamlDurabilityType::NoDurability,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::StoreAndForward => YamlDurabilityType::StoreAndForward,
         }
     }
 }
@@ -1712,6 +1763,8 @@ impl From<YamlDurabilityType> for DurabilityType {
     fn from(yaml_durability_type: YamlDurabilityType) -> Self {
         match yaml_durability_type {
             YamlDurabilityType::NoDurability => DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-durability")]
+            YamlDurabilityType::StoreAndForward => DurabilityType::StoreAndForward,
         }
     }
 }
@@ -1720,12 +1773,16 @@ impl From<YamlDurabilityType> for DurabilityType {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum YamlRouteType {
     Any,
+    #[cfg(feature = "circuit-relay")]
+    Relay,
 }
 
 impl From<RouteType> for YamlRouteType {
     fn from(route_type: RouteType) -> Self {
         match route_type {
             RouteType::Any => YamlRouteType::Any,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Relay => YamlRouteType::Relay,
         }
     }
 }
@@ -1734,6 +1791,8 @@ impl From<YamlRouteType> for RouteType {
     fn from(yaml_route_type: YamlRouteType) -> Self {
         match yaml_route_type {
             YamlRouteType::Any => RouteType::Any,
+            #[cfg(feature = "circuit-relay")]
+            YamlRouteType::Relay => RouteType::Relay,
         }
     }
 }
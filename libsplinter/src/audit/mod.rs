@@ -0,0 +1,58 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides an audit log of the requests made to the Splinter REST API.
+//!
+//! An [`AuditEntry`] is recorded for every request that requires authentication, whether or not
+//! the request was allowed. Each entry records the client's identity, the permission that was
+//! checked, the endpoint, the result and the time of the request. Requests that submit a
+//! `CircuitManagementPayload` to the admin service also record the payload's action, the ID of
+//! the circuit it applies to and the payload itself.
+
+#[cfg(feature = "rest-api-actix")]
+pub mod rest_api;
+pub mod store;
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub use store::diesel::DieselAuditStore;
+pub use store::memory::MemoryAuditStore;
+pub use store::{AuditEntry, AuditEntryBuilder, AuditResult, AuditStore, AuditStoreError};
+
+/// Details of the action performed by a request, which are recorded in the request's audit entry.
+///
+/// A REST API handler adds the details to the request's extensions; the authorization middleware
+/// reads them once the handler has responded.
+#[derive(Clone, Debug)]
+pub struct AuditDetails {
+    action: String,
+    circuit_id: Option<String>,
+    payload: Vec<u8>,
+}
+
+impl AuditDetails {
+    /// Creates new `AuditDetails`
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The name of the action, such as `CIRCUIT_PROPOSAL_VOTE`
+    /// * `circuit_id` - The ID of the circuit the action applies to, if any
+    /// * `payload` - The serialized payload that was submitted
+    pub fn new(action: String, circuit_id: Option<String>, payload: Vec<u8>) -> Self {
+        Self {
+            action,
+            circuit_id,
+            payload,
+        }
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoints:
+//!
+//! * `GET /audit` for listing audit entries
+
+use crate::actix_web::{error::BlockingError, web, Error, HttpRequest, HttpResponse};
+use crate::audit::{
+    rest_api::{
+        resources::{AuditEntryResponse, ListAuditEntriesResponse, PagingQuery},
+        AUDIT_READ_PERMISSION,
+    },
+    store::AuditStore,
+};
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::{
+    actix_web_1::{Method, ProtocolVersionRangeGuard, Resource},
+    paging::get_response_paging_info,
+    ErrorResponse,
+};
+
+pub fn make_audit_entries_resource(audit_store: Box<dyn AuditStore>) -> Resource {
+    Resource::build("/audit")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::AUDIT_LIST_ENTRIES_MIN,
+            protocol::AUDIT_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, AUDIT_READ_PERMISSION, move |r, _| {
+            list_entries(r, audit_store.clone())
+        })
+}
+
+fn list_entries(
+    req: HttpRequest,
+    audit_store: Box<dyn AuditStore>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let web::Query(paging_query): web::Query<PagingQuery> =
+        match web::Query::from_query(req.query_string()) {
+            Ok(paging_query) => paging_query,
+            Err(_) => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request("Invalid query"))
+                        .into_future(),
+                )
+            }
+        };
+
+    let link = format!("{}?", req.uri().path());
+
+    Box::new(
+        web::block(move || {
+            let total = audit_store.count_entries().map_err(|err| err.to_string())?;
+            let entries = audit_store
+                .list_entries(paging_query.offset, paging_query.limit)
+                .map_err(|err| err.to_string())?;
+
+            Ok((entries, link, paging_query, total))
+        })
+        .then(|res: Result<_, BlockingError<String>>| match res {
+            Ok((entries, link, paging_query, total)) => {
+                Ok(HttpResponse::Ok().json(ListAuditEntriesResponse {
+                    data: entries.iter().map(AuditEntryResponse::from).collect(),
+                    paging: get_response_paging_info(
+                        Some(paging_query.limit),
+                        Some(paging_query.offset),
+                        &link,
+                        total,
+                    ),
+                }))
+            }
+            Err(err) => {
+                error!("Unable to list audit entries: {}", err);
+                Ok(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+            }
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::{blocking::Client, StatusCode, Url};
+
+    use crate::audit::{AuditDetails, AuditEntryBuilder, AuditResult, MemoryAuditStore};
+    use crate::rest_api::actix_web_1::{RestApiBuilder, RestApiShutdownHandle};
+
+    #[derive(Debug, Deserialize)]
+    struct TestAuditEntry {
+        identity: Option<String>,
+        endpoint: String,
+        result: String,
+        status_code: u16,
+        action: Option<String>,
+        circuit_id: Option<String>,
+        payload: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct TestListAuditEntriesResponse {
+        data: Vec<TestAuditEntry>,
+    }
+
+    /// Tests a GET /audit request which returns a page of audit entries, newest first.
+    ///
+    /// 1. Add three audit entries to the store, one with action details
+    /// 2. Perform a GET against /audit with a limit of 2
+    /// 3. Verify that the two newest entries are returned, newest first
+    /// 4. Perform a GET against /audit with an offset of 2
+    /// 5. Verify that the oldest entry is returned with its action details
+    #[test]
+    fn test_list_audit_entries() {
+        let audit_store = MemoryAuditStore::new();
+
+        audit_store
+            .add_entry(
+                AuditEntryBuilder::new()
+                    .with_identity("key:0123".into())
+                    .with_method("POST".into())
+                    .with_endpoint("/admin/submit".into())
+                    .with_permission_id("circuit.write".into())
+                    .with_result(AuditResult::Allowed)
+                    .with_status_code(202)
+                    .with_details(AuditDetails::new(
+                        "CIRCUIT_DISBAND_REQUEST".into(),
+                        Some("abcde-12345".into()),
+                        vec![0x0a, 0xff],
+                    ))
+                    .build()
+                    .expect("Unable to build entry"),
+            )
+            .expect("Unable to add entry");
+        for endpoint in &["/admin/circuits", "/authorization/roles"] {
+            audit_store
+                .add_entry(
+                    AuditEntryBuilder::new()
+                        .with_method("GET".into())
+                        .with_endpoint(endpoint.to_string())
+                        .with_result(AuditResult::Unauthenticated)
+                        .with_status_code(401)
+                        .build()
+                        .expect("Unable to build entry"),
+                )
+                .expect("Unable to add entry");
+        }
+
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_audit_entries_resource(Box::new(audit_store))]);
+
+        let resp = get_entries(&bind_url, "limit=2");
+        assert_eq!(
+            resp.data
+                .iter()
+                .map(|entry| entry.endpoint.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/authorization/roles", "/admin/circuits"]
+        );
+        assert!(resp.data[0].identity.is_none());
+        assert_eq!(&resp.data[0].result, "unauthenticated");
+        assert_eq!(resp.data[0].status_code, 401);
+
+        let resp = get_entries(&bind_url, "offset=2");
+        assert_eq!(resp.data.len(), 1);
+        let entry = &resp.data[0];
+        assert_eq!(entry.identity.as_deref(), Some("key:0123"));
+        assert_eq!(&entry.result, "allowed");
+        assert_eq!(entry.action.as_deref(), Some("CIRCUIT_DISBAND_REQUEST"));
+        assert_eq!(entry.circuit_id.as_deref(), Some("abcde-12345"));
+        assert_eq!(entry.payload.as_deref(), Some("0aff"));
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn get_entries(bind_url: &str, query: &str) -> TestListAuditEntriesResponse {
+        let url = Url::parse(&format!("http://{}/audit?{}", bind_url, query))
+            .expect("Failed to parse URL");
+
+        let resp = Client::new()
+            .get(url)
+            .header("SplinterProtocolVersion", protocol::AUDIT_PROTOCOL_VERSION)
+            .send()
+            .expect("Failed to perform request");
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        resp.json::<TestListAuditEntriesResponse>()
+            .expect("Failed to deserialize body")
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        #[cfg(not(feature = "https-bind"))]
+        let bind = "127.0.0.1:0";
+        #[cfg(feature = "https-bind")]
+        let bind = crate::rest_api::BindConfig::Http("127.0.0.1:0".into());
+
+        let result = RestApiBuilder::new()
+            .with_bind(bind)
+            .add_resources(resources.clone())
+            .build_insecure()
+            .expect("Failed to build REST API")
+            .run_insecure();
+        match result {
+            Ok((shutdown_handle, join_handle)) => {
+                let port = shutdown_handle.port_numbers()[0];
+                (shutdown_handle, join_handle, format!("127.0.0.1:{}", port))
+            }
+            Err(err) => panic!("Failed to run REST API: {}", err),
+        }
+    }
+}
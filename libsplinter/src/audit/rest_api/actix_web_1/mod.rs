@@ -0,0 +1,39 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod entries;
+
+use crate::audit::store::AuditStore;
+use crate::rest_api::{Resource, RestResourceProvider};
+
+/// Provides the following REST API endpoint for the audit log:
+///
+/// * `GET /audit` - Get a page of audit entries, newest first
+pub struct AuditResourceProvider {
+    audit_store: Box<dyn AuditStore>,
+}
+
+impl AuditResourceProvider {
+    pub fn new(audit_store: Box<dyn AuditStore>) -> Self {
+        Self { audit_store }
+    }
+}
+
+impl RestResourceProvider for AuditResourceProvider {
+    fn resources(&self) -> Vec<Resource> {
+        vec![entries::make_audit_entries_resource(
+            self.audit_store.clone(),
+        )]
+    }
+}
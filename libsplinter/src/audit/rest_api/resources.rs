@@ -0,0 +1,80 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines structures used in the audit log REST API.
+
+use std::time::SystemTime;
+
+use crate::audit::store::AuditEntry;
+use crate::hex::to_hex;
+use crate::rest_api::paging::{Paging, DEFAULT_LIMIT, DEFAULT_OFFSET};
+
+#[derive(Serialize)]
+pub(super) struct ListAuditEntriesResponse<'a> {
+    pub data: Vec<AuditEntryResponse<'a>>,
+    pub paging: Paging,
+}
+
+#[derive(Serialize)]
+pub(super) struct AuditEntryResponse<'a> {
+    /// The time of the request, in seconds since the Unix epoch
+    pub timestamp: u64,
+    pub identity: Option<&'a str>,
+    pub method: &'a str,
+    pub endpoint: &'a str,
+    pub permission_id: Option<&'a str>,
+    pub result: &'static str,
+    pub status_code: u16,
+    pub action: Option<&'a str>,
+    pub circuit_id: Option<&'a str>,
+    /// The hex-encoded payload submitted with the request's action
+    pub payload: Option<String>,
+}
+
+impl<'a> From<&'a AuditEntry> for AuditEntryResponse<'a> {
+    fn from(entry: &'a AuditEntry) -> Self {
+        Self {
+            timestamp: entry
+                .timestamp()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0),
+            identity: entry.identity(),
+            method: entry.method(),
+            endpoint: entry.endpoint(),
+            permission_id: entry.permission_id(),
+            result: entry.result().as_str(),
+            status_code: entry.status_code(),
+            action: entry.action(),
+            circuit_id: entry.circuit_id(),
+            payload: entry.payload().map(to_hex),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(super) struct PagingQuery {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    #[serde(default = "default_offset")]
+    pub offset: usize,
+}
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+fn default_offset() -> usize {
+    DEFAULT_OFFSET
+}
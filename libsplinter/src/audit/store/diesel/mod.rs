@@ -0,0 +1,203 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Database-backed implementation of the [AuditStore], powered by [diesel].
+
+pub(in crate::audit) mod models;
+mod operations;
+pub(in crate::audit) mod schema;
+
+use diesel::r2d2::{ConnectionManager, Pool};
+
+use super::{AuditEntry, AuditStore, AuditStoreError};
+
+use operations::{
+    add_entry::AuditStoreAddEntry as _, count_entries::AuditStoreCountEntries as _,
+    list_entries::AuditStoreListEntries as _, AuditStoreOperations,
+};
+
+/// Manages adding and listing audit entries in the database
+pub struct DieselAuditStore<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C: diesel::Connection> DieselAuditStore<C> {
+    /// Creates a new DieselAuditStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        DieselAuditStore { connection_pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl AuditStore for DieselAuditStore<diesel::pg::PgConnection> {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        let connection = self.connection_pool.get()?;
+        AuditStoreOperations::new(&*connection).add_entry(entry)
+    }
+
+    fn list_entries(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, AuditStoreError> {
+        let connection = self.connection_pool.get()?;
+        AuditStoreOperations::new(&*connection).list_entries(offset, limit)
+    }
+
+    fn count_entries(&self) -> Result<usize, AuditStoreError> {
+        let connection = self.connection_pool.get()?;
+        AuditStoreOperations::new(&*connection).count_entries()
+    }
+
+    fn clone_box(&self) -> Box<dyn AuditStore> {
+        Box::new(Self {
+            connection_pool: self.connection_pool.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl AuditStore for DieselAuditStore<diesel::sqlite::SqliteConnection> {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        let connection = self.connection_pool.get()?;
+        AuditStoreOperations::new(&*connection).add_entry(entry)
+    }
+
+    fn list_entries(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, AuditStoreError> {
+        let connection = self.connection_pool.get()?;
+        AuditStoreOperations::new(&*connection).list_entries(offset, limit)
+    }
+
+    fn count_entries(&self) -> Result<usize, AuditStoreError> {
+        let connection = self.connection_pool.get()?;
+        AuditStoreOperations::new(&*connection).count_entries()
+    }
+
+    fn clone_box(&self) -> Box<dyn AuditStore> {
+        Box::new(Self {
+            connection_pool: self.connection_pool.clone(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+pub mod tests {
+    use super::*;
+
+    use std::time::{Duration, SystemTime};
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    use crate::audit::{store::AuditEntryBuilder, AuditDetails, AuditResult};
+    use crate::migrations::run_sqlite_migrations;
+
+    /// Verify that a SQLite-backed `DieselAuditStore` correctly supports adding, counting and
+    /// listing audit entries.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselAuditStore`.
+    /// 3. Add an allowed entry with action details, a denied entry and an unauthenticated entry.
+    /// 4. Verify that `count_entries` returns the number of entries.
+    /// 5. Verify that `list_entries` returns the entries newest first.
+    /// 6. Verify that `list_entries` returns the requested page of entries.
+    #[test]
+    fn sqlite_add_and_list_entries() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselAuditStore::new(pool);
+
+        let allowed = AuditEntryBuilder::new()
+            .with_timestamp(SystemTime::UNIX_EPOCH + Duration::from_secs(100))
+            .with_identity("key:0123".into())
+            .with_method("POST".into())
+            .with_endpoint("/admin/submit".into())
+            .with_permission_id("circuit.write".into())
+            .with_result(AuditResult::Allowed)
+            .with_status_code(202)
+            .with_details(AuditDetails::new(
+                "CIRCUIT_PROPOSAL_VOTE".into(),
+                Some("abcde-12345".into()),
+                vec![1, 2, 3],
+            ))
+            .build()
+            .expect("Unable to build entry");
+        let denied = AuditEntryBuilder::new()
+            .with_timestamp(SystemTime::UNIX_EPOCH + Duration::from_secs(200))
+            .with_identity("user:user1".into())
+            .with_method("DELETE".into())
+            .with_endpoint("/authorization/roles/admin".into())
+            .with_permission_id("authorization.rbac.write".into())
+            .with_result(AuditResult::Denied)
+            .with_status_code(401)
+            .build()
+            .expect("Unable to build entry");
+        let unauthenticated = AuditEntryBuilder::new()
+            .with_timestamp(SystemTime::UNIX_EPOCH + Duration::from_secs(200))
+            .with_method("GET".into())
+            .with_endpoint("/admin/circuits".into())
+            .with_permission_id("circuit.read".into())
+            .with_result(AuditResult::Unauthenticated)
+            .with_status_code(401)
+            .build()
+            .expect("Unable to build entry");
+
+        store
+            .add_entry(allowed.clone())
+            .expect("Unable to add entry");
+        store
+            .add_entry(denied.clone())
+            .expect("Unable to add entry");
+        store
+            .add_entry(unauthenticated.clone())
+            .expect("Unable to add entry");
+
+        assert_eq!(store.count_entries().expect("Unable to count entries"), 3);
+
+        assert_eq!(
+            store.list_entries(0, 10).expect("Unable to list entries"),
+            vec![unauthenticated, denied.clone(), allowed]
+        );
+        assert_eq!(
+            store.list_entries(1, 1).expect("Unable to list entries"),
+            vec![denied]
+        );
+    }
+
+    /// Creates a connection pool for an in-memory SQLite database with only a single connection
+    /// available. Each connection is backed by a different in-memory SQLite database, so limiting
+    /// the pool to a single connection insures that the same DB is used for all operations.
+    fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+}
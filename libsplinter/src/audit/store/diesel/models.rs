@@ -0,0 +1,99 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime};
+
+use crate::audit::store::{AuditEntry, AuditStoreError};
+use crate::error::InternalError;
+
+use super::schema::audit_entries;
+
+#[derive(Queryable, Identifiable, PartialEq, Debug)]
+#[table_name = "audit_entries"]
+#[primary_key(id)]
+pub struct AuditEntryModel {
+    pub id: i64,
+    pub timestamp: i64,
+    pub identity: Option<String>,
+    pub method: String,
+    pub endpoint: String,
+    pub permission_id: Option<String>,
+    pub result: String,
+    pub status_code: i32,
+    pub action: Option<String>,
+    pub circuit_id: Option<String>,
+    pub payload: Option<Vec<u8>>,
+}
+
+#[derive(Insertable, PartialEq, Debug)]
+#[table_name = "audit_entries"]
+pub struct NewAuditEntryModel {
+    pub timestamp: i64,
+    pub identity: Option<String>,
+    pub method: String,
+    pub endpoint: String,
+    pub permission_id: Option<String>,
+    pub result: String,
+    pub status_code: i32,
+    pub action: Option<String>,
+    pub circuit_id: Option<String>,
+    pub payload: Option<Vec<u8>>,
+}
+
+impl TryFrom<AuditEntry> for NewAuditEntryModel {
+    type Error = AuditStoreError;
+
+    fn try_from(entry: AuditEntry) -> Result<Self, Self::Error> {
+        let timestamp = entry
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|err| AuditStoreError::Internal(InternalError::from_source(Box::new(err))))?
+            .as_secs() as i64;
+
+        Ok(NewAuditEntryModel {
+            timestamp,
+            identity: entry.identity,
+            method: entry.method,
+            endpoint: entry.endpoint,
+            permission_id: entry.permission_id,
+            result: entry.result.as_str().to_string(),
+            status_code: i32::from(entry.status_code),
+            action: entry.action,
+            circuit_id: entry.circuit_id,
+            payload: entry.payload,
+        })
+    }
+}
+
+impl TryFrom<AuditEntryModel> for AuditEntry {
+    type Error = AuditStoreError;
+
+    fn try_from(model: AuditEntryModel) -> Result<Self, Self::Error> {
+        Ok(AuditEntry {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(model.timestamp as u64),
+            identity: model.identity,
+            method: model.method,
+            endpoint: model.endpoint,
+            permission_id: model.permission_id,
+            result: model.result.parse().map_err(|err| {
+                AuditStoreError::Internal(InternalError::from_source(Box::new(err)))
+            })?,
+            status_code: model.status_code as u16,
+            action: model.action,
+            circuit_id: model.circuit_id,
+            payload: model.payload,
+        })
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+
+use super::AuditStoreOperations;
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::audit::store::{
+    diesel::{models::NewAuditEntryModel, schema::audit_entries},
+    AuditEntry, AuditStoreError,
+};
+
+pub trait AuditStoreAddEntry {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AuditStoreAddEntry for AuditStoreOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        insert_into(audit_entries::table)
+            .values(NewAuditEntryModel::try_from(entry)?)
+            .execute(self.conn)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AuditStoreAddEntry for AuditStoreOperations<'a, diesel::pg::PgConnection> {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        insert_into(audit_entries::table)
+            .values(NewAuditEntryModel::try_from(entry)?)
+            .execute(self.conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+
+use super::AuditStoreOperations;
+
+use diesel::{dsl::count_star, prelude::*};
+
+use crate::audit::store::{diesel::schema::audit_entries, AuditStoreError};
+use crate::error::InternalError;
+
+pub trait AuditStoreCountEntries {
+    fn count_entries(&self) -> Result<usize, AuditStoreError>;
+}
+
+impl<'a, C> AuditStoreCountEntries for AuditStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn count_entries(&self) -> Result<usize, AuditStoreError> {
+        let count = audit_entries::table
+            .select(count_star())
+            .first::<i64>(self.conn)?;
+
+        usize::try_from(count)
+            .map_err(|err| AuditStoreError::Internal(InternalError::from_source(Box::new(err))))
+    }
+}
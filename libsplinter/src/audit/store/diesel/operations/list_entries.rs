@@ -0,0 +1,68 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+
+use super::AuditStoreOperations;
+
+use diesel::prelude::*;
+
+use crate::audit::store::{
+    diesel::{models::AuditEntryModel, schema::audit_entries},
+    AuditEntry, AuditStoreError,
+};
+use crate::error::InvalidArgumentError;
+
+pub trait AuditStoreListEntries {
+    fn list_entries(&self, offset: usize, limit: usize)
+        -> Result<Vec<AuditEntry>, AuditStoreError>;
+}
+
+impl<'a, C> AuditStoreListEntries for AuditStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    i32: diesel::deserialize::FromSql<diesel::sql_types::Integer, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    Vec<u8>: diesel::deserialize::FromSql<diesel::sql_types::Binary, C::Backend>,
+    i64: diesel::serialize::ToSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn list_entries(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, AuditStoreError> {
+        let offset = i64::try_from(offset).map_err(|_| {
+            AuditStoreError::InvalidArgument(InvalidArgumentError::new(
+                "offset".to_string(),
+                "Offset is too large".to_string(),
+            ))
+        })?;
+        let limit = i64::try_from(limit).map_err(|_| {
+            AuditStoreError::InvalidArgument(InvalidArgumentError::new(
+                "limit".to_string(),
+                "Limit is too large".to_string(),
+            ))
+        })?;
+
+        audit_entries::table
+            .order(audit_entries::id.desc())
+            .offset(offset)
+            .limit(limit)
+            .load::<AuditEntryModel>(self.conn)?
+            .into_iter()
+            .map(AuditEntry::try_from)
+            .collect()
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides [AuditStore] operations implemented for a diesel backend
+
+pub(super) mod add_entry;
+pub(super) mod count_entries;
+pub(super) mod list_entries;
+
+pub(super) struct AuditStoreOperations<'a, C> {
+    conn: &'a C,
+}
+
+impl<'a, C> AuditStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    pub fn new(conn: &'a C) -> Self {
+        AuditStoreOperations { conn }
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+table! {
+    audit_entries (id) {
+        id -> Int8,
+        timestamp -> BigInt,
+        identity -> Nullable<Text>,
+        method -> Text,
+        endpoint -> Text,
+        permission_id -> Nullable<Text>,
+        result -> Text,
+        status_code -> Integer,
+        action -> Nullable<Text>,
+        circuit_id -> Nullable<Text>,
+        payload -> Nullable<Binary>,
+    }
+}
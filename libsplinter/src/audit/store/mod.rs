@@ -0,0 +1,301 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines the audit entries recorded for REST API requests and the store they are kept in.
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub(in crate::audit) mod diesel;
+pub mod error;
+pub(in crate::audit) mod memory;
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use crate::error::{InvalidArgumentError, InvalidStateError};
+
+use super::AuditDetails;
+
+pub use error::AuditStoreError;
+
+/// The outcome of authorizing a REST API request.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuditResult {
+    /// The client was authenticated and permitted to make the request
+    Allowed,
+    /// The client was authenticated, but did not have the required permission
+    Denied,
+    /// The client's identity could not be determined
+    Unauthenticated,
+}
+
+impl AuditResult {
+    /// Returns the result's string representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditResult::Allowed => "allowed",
+            AuditResult::Denied => "denied",
+            AuditResult::Unauthenticated => "unauthenticated",
+        }
+    }
+}
+
+impl fmt::Display for AuditResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditResult {
+    type Err = InvalidArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allowed" => Ok(AuditResult::Allowed),
+            "denied" => Ok(AuditResult::Denied),
+            "unauthenticated" => Ok(AuditResult::Unauthenticated),
+            _ => Err(InvalidArgumentError::new(
+                "result".to_string(),
+                format!("Unknown audit result: {}", s),
+            )),
+        }
+    }
+}
+
+/// A record of a single REST API request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditEntry {
+    timestamp: SystemTime,
+    identity: Option<String>,
+    method: String,
+    endpoint: String,
+    permission_id: Option<String>,
+    result: AuditResult,
+    status_code: u16,
+    action: Option<String>,
+    circuit_id: Option<String>,
+    payload: Option<Vec<u8>>,
+}
+
+impl AuditEntry {
+    /// Returns the time the request was made.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// Returns the identity of the client that made the request, if it could be determined. The
+    /// identity is formatted as `<type>:<value>`, such as `key:<public key>` or `user:<user ID>`.
+    pub fn identity(&self) -> Option<&str> {
+        self.identity.as_deref()
+    }
+
+    /// Returns the HTTP method of the request.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Returns the endpoint that was requested.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Returns the ID of the permission that was checked for the request, if the endpoint
+    /// requires one.
+    pub fn permission_id(&self) -> Option<&str> {
+        self.permission_id.as_deref()
+    }
+
+    /// Returns the result of authorizing the request.
+    pub fn result(&self) -> AuditResult {
+        self.result
+    }
+
+    /// Returns the HTTP status code of the response.
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    /// Returns the name of the action the request performed, such as `CIRCUIT_PROPOSAL_VOTE`.
+    pub fn action(&self) -> Option<&str> {
+        self.action.as_deref()
+    }
+
+    /// Returns the ID of the circuit the request's action applies to.
+    pub fn circuit_id(&self) -> Option<&str> {
+        self.circuit_id.as_deref()
+    }
+
+    /// Returns the serialized payload that was submitted with the request's action.
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.payload.as_deref()
+    }
+}
+
+/// Builds a new `AuditEntry`.
+#[derive(Default)]
+pub struct AuditEntryBuilder {
+    timestamp: Option<SystemTime>,
+    identity: Option<String>,
+    method: Option<String>,
+    endpoint: Option<String>,
+    permission_id: Option<String>,
+    result: Option<AuditResult>,
+    status_code: Option<u16>,
+    details: Option<AuditDetails>,
+}
+
+impl AuditEntryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time the request was made; defaults to the current time.
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> AuditEntryBuilder {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn with_identity(mut self, identity: String) -> AuditEntryBuilder {
+        self.identity = Some(identity);
+        self
+    }
+
+    pub fn with_method(mut self, method: String) -> AuditEntryBuilder {
+        self.method = Some(method);
+        self
+    }
+
+    pub fn with_endpoint(mut self, endpoint: String) -> AuditEntryBuilder {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    pub fn with_permission_id(mut self, permission_id: String) -> AuditEntryBuilder {
+        self.permission_id = Some(permission_id);
+        self
+    }
+
+    pub fn with_result(mut self, result: AuditResult) -> AuditEntryBuilder {
+        self.result = Some(result);
+        self
+    }
+
+    pub fn with_status_code(mut self, status_code: u16) -> AuditEntryBuilder {
+        self.status_code = Some(status_code);
+        self
+    }
+
+    /// Sets the details of the action the request performed.
+    pub fn with_details(mut self, details: AuditDetails) -> AuditEntryBuilder {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn build(self) -> Result<AuditEntry, InvalidStateError> {
+        let (action, circuit_id, payload) = match self.details {
+            Some(details) => (
+                Some(details.action),
+                details.circuit_id,
+                Some(details.payload),
+            ),
+            None => (None, None, None),
+        };
+
+        Ok(AuditEntry {
+            timestamp: self.timestamp.unwrap_or_else(SystemTime::now),
+            identity: self.identity,
+            method: self.method.ok_or_else(|| {
+                InvalidStateError::with_message(
+                    "A method is required to build an AuditEntry".into(),
+                )
+            })?,
+            endpoint: self.endpoint.ok_or_else(|| {
+                InvalidStateError::with_message(
+                    "An endpoint is required to build an AuditEntry".into(),
+                )
+            })?,
+            permission_id: self.permission_id,
+            result: self.result.ok_or_else(|| {
+                InvalidStateError::with_message(
+                    "A result is required to build an AuditEntry".into(),
+                )
+            })?,
+            status_code: self.status_code.ok_or_else(|| {
+                InvalidStateError::with_message(
+                    "A status code is required to build an AuditEntry".into(),
+                )
+            })?,
+            action,
+            circuit_id,
+            payload,
+        })
+    }
+}
+
+/// Defines methods for recording and listing audit entries without defining a storage strategy.
+pub trait AuditStore: Sync + Send {
+    /// Adds an entry to the underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///  * `entry` - The audit entry to be added
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError>;
+
+    /// Lists a page of entries from the underlying storage, in the reverse of the order they were
+    /// added
+    ///
+    /// # Arguments
+    ///
+    ///  * `offset` - The number of entries to skip
+    ///  * `limit` - The maximum number of entries to return
+    fn list_entries(&self, offset: usize, limit: usize)
+        -> Result<Vec<AuditEntry>, AuditStoreError>;
+
+    /// Returns the total number of entries in the underlying storage
+    fn count_entries(&self) -> Result<usize, AuditStoreError>;
+
+    /// Clone into a boxed, dynamically dispatched store
+    fn clone_box(&self) -> Box<dyn AuditStore>;
+}
+
+impl Clone for Box<dyn AuditStore> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl<AS> AuditStore for Box<AS>
+where
+    AS: AuditStore + ?Sized,
+{
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        (**self).add_entry(entry)
+    }
+
+    fn list_entries(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, AuditStoreError> {
+        (**self).list_entries(offset, limit)
+    }
+
+    fn count_entries(&self) -> Result<usize, AuditStoreError> {
+        (**self).count_entries()
+    }
+
+    fn clone_box(&self) -> Box<dyn AuditStore> {
+        (**self).clone_box()
+    }
+}
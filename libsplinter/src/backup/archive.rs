@@ -0,0 +1,498 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Creates and restores backup archives.
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use diesel::{sqlite::SqliteConnection, Connection};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{InternalError, InvalidStateError};
+use crate::migrations::run_sqlite_migrations;
+use crate::store::{
+    create_store_factory,
+    migration::{migrate_data, migrated_stores},
+    ConnectionUri, StoreFactory,
+};
+
+use super::BackupError;
+
+/// The version of the backup archive format written by this library.
+pub const BACKUP_FORMAT_VERSION: u32 = 2;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const DATABASE_FILE: &str = "database.db";
+const STATE_DIR: &str = "state";
+const STATE_FILE_EXTENSION: &str = "lmdb";
+
+/// Describes the contents of a backup archive.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BackupManifest {
+    version: u32,
+    splinter_version: String,
+    created_at: u64,
+    // Archives written before the stores were listed fail the version check instead of failing
+    // to parse
+    #[serde(default)]
+    stores: Vec<String>,
+    counts: Vec<(String, usize)>,
+    state_files: Vec<String>,
+}
+
+impl BackupManifest {
+    /// Returns the format version of the archive.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the version of the Splinter library that created the archive.
+    pub fn splinter_version(&self) -> &str {
+        &self.splinter_version
+    }
+
+    /// Returns the time the archive was created, in seconds since the Unix epoch.
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Returns the names of the stores in the database snapshot.
+    pub fn stores(&self) -> &[String] {
+        &self.stores
+    }
+
+    /// Returns the name and number of entries for each kind of data in the database snapshot.
+    pub fn counts(&self) -> &[(String, usize)] {
+        &self.counts
+    }
+
+    /// Returns the names of the state files in the archive.
+    pub fn state_files(&self) -> &[String] {
+        &self.state_files
+    }
+}
+
+/// Writes a backup archive of the data in `store_factory` and the LMDB databases in `state_dir`
+/// to `writer`.
+///
+/// The caller is responsible for ensuring that the stores and state files are not modified while
+/// the backup is created.
+///
+/// # Errors
+///
+/// Returns an `Internal` error if the database snapshot cannot be created or the archive cannot
+/// be written.
+pub fn create_backup<W: Write>(
+    store_factory: &dyn StoreFactory,
+    state_dir: &Path,
+    writer: W,
+) -> Result<BackupManifest, BackupError> {
+    let work_dir = WorkDir::new()?;
+    let database_path = work_dir.path().join(DATABASE_FILE);
+
+    let report = {
+        let snapshot_factory = create_snapshot_factory(&database_path)?;
+        migrate_data(store_factory, &*snapshot_factory)?
+    };
+
+    let state_files = list_state_files(state_dir)?;
+
+    let manifest = BackupManifest {
+        version: BACKUP_FORMAT_VERSION,
+        splinter_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| InternalError::from_source(Box::new(err)))?
+            .as_secs(),
+        stores: migrated_stores()
+            .into_iter()
+            .map(|store| store.to_string())
+            .collect(),
+        counts: report
+            .counts()
+            .iter()
+            .map(|(name, count)| (name.to_string(), *count))
+            .collect(),
+        state_files: state_files.clone(),
+    };
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest)
+        .map_err(|err| InternalError::from_source(Box::new(err)))?;
+
+    let mut builder = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created_at);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST_FILE, &manifest_bytes[..])
+        .map_err(archive_error("Unable to add manifest to archive"))?;
+
+    builder
+        .append_path_with_name(&database_path, DATABASE_FILE)
+        .map_err(archive_error("Unable to add database snapshot to archive"))?;
+
+    for state_file in &state_files {
+        builder
+            .append_path_with_name(
+                state_dir.join(state_file),
+                Path::new(STATE_DIR).join(state_file),
+            )
+            .map_err(archive_error(&format!(
+                "Unable to add state file {} to archive",
+                state_file
+            )))?;
+    }
+
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(archive_error("Unable to finish archive"))?;
+
+    Ok(manifest)
+}
+
+/// Restores the backup archive read from `reader` into the stores of `store_factory` and the
+/// state directory `state_dir`.
+///
+/// The stores must be empty, apart from data seeded by the database migrations, and `state_dir`
+/// must not contain any LMDB databases. The node should not be running while it is restored.
+///
+/// # Errors
+///
+/// * Returns an `InvalidState` error if the archive is not a supported backup, if it does not
+///   contain every store of this node, or if the stores or state directory already contain data
+/// * Returns an `Internal` error if the archive cannot be read or the data cannot be restored
+pub fn restore_backup<R: Read>(
+    reader: R,
+    store_factory: &dyn StoreFactory,
+    state_dir: &Path,
+) -> Result<BackupManifest, BackupError> {
+    let work_dir = WorkDir::new()?;
+
+    tar::Archive::new(GzDecoder::new(reader))
+        .unpack(work_dir.path())
+        .map_err(archive_error("Unable to unpack archive"))?;
+
+    let manifest_file = File::open(work_dir.path().join(MANIFEST_FILE)).map_err(|_| {
+        BackupError::InvalidState(InvalidStateError::with_message(
+            "Archive does not contain a backup manifest".into(),
+        ))
+    })?;
+    let manifest: BackupManifest = serde_json::from_reader(manifest_file).map_err(|err| {
+        BackupError::InvalidState(InvalidStateError::with_message(format!(
+            "Backup manifest is invalid: {}",
+            err
+        )))
+    })?;
+    check_manifest(&manifest)?;
+
+    if state_dir.exists() {
+        if !list_state_files(state_dir)?.is_empty() {
+            return Err(BackupError::InvalidState(InvalidStateError::with_message(
+                format!(
+                    "State directory {} already contains state files",
+                    state_dir.display()
+                ),
+            )));
+        }
+    } else {
+        fs::create_dir_all(state_dir).map_err(|err| {
+            InternalError::from_source_with_prefix(
+                Box::new(err),
+                format!("Unable to create state directory {}", state_dir.display()),
+            )
+        })?;
+    }
+
+    {
+        let snapshot_factory = create_snapshot_factory(&work_dir.path().join(DATABASE_FILE))?;
+        migrate_data(&*snapshot_factory, store_factory)?;
+    }
+
+    for state_file in &manifest.state_files {
+        fs::copy(
+            work_dir.path().join(STATE_DIR).join(state_file),
+            state_dir.join(state_file),
+        )
+        .map_err(|err| {
+            InternalError::from_source_with_prefix(
+                Box::new(err),
+                format!("Unable to restore state file {}", state_file),
+            )
+        })?;
+    }
+
+    Ok(manifest)
+}
+
+/// Checks that the archive described by `manifest` can be restored by this node.
+///
+/// Every store of this node must be listed, as a store that is missing from the snapshot would
+/// otherwise be restored as empty.
+fn check_manifest(manifest: &BackupManifest) -> Result<(), BackupError> {
+    if manifest.version != BACKUP_FORMAT_VERSION {
+        return Err(BackupError::InvalidState(InvalidStateError::with_message(
+            format!(
+                "Unsupported backup format version {}; expected version {}",
+                manifest.version, BACKUP_FORMAT_VERSION
+            ),
+        )));
+    }
+
+    let missing_stores = migrated_stores()
+        .into_iter()
+        .filter(|store| !manifest.stores.iter().any(|listed| listed == store))
+        .collect::<Vec<_>>();
+    if !missing_stores.is_empty() {
+        return Err(BackupError::InvalidState(InvalidStateError::with_message(
+            format!(
+                "Backup does not contain the following stores: {}",
+                missing_stores.join(", ")
+            ),
+        )));
+    }
+
+    for state_file in &manifest.state_files {
+        if Path::new(state_file).file_name() != Some(state_file.as_ref()) {
+            return Err(BackupError::InvalidState(InvalidStateError::with_message(
+                format!("Backup contains an invalid state file name: {}", state_file),
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Opens the SQLite database at `path`, creating it if necessary, and brings its schema up to
+/// date.
+fn create_snapshot_factory(path: &Path) -> Result<Box<dyn StoreFactory>, InternalError> {
+    let path = path
+        .to_str()
+        .ok_or_else(|| InternalError::with_message("Snapshot path is not valid UTF-8".into()))?;
+
+    let connection = SqliteConnection::establish(path).map_err(|err| {
+        InternalError::from_source_with_prefix(
+            Box::new(err),
+            "Unable to open database snapshot".to_string(),
+        )
+    })?;
+    run_sqlite_migrations(&connection)?;
+
+    create_store_factory(ConnectionUri::Sqlite(path.to_string()))
+}
+
+/// Returns the sorted names of the LMDB databases in `state_dir`; lock files are skipped, as LMDB
+/// recreates them.
+fn list_state_files(state_dir: &Path) -> Result<Vec<String>, InternalError> {
+    let entries = fs::read_dir(state_dir).map_err(|err| {
+        InternalError::from_source_with_prefix(
+            Box::new(err),
+            format!("Unable to read state directory {}", state_dir.display()),
+        )
+    })?;
+
+    let mut state_files = vec![];
+    for entry in entries {
+        let path = entry
+            .map_err(|err| InternalError::from_source(Box::new(err)))?
+            .path();
+        if !path.is_file()
+            || path.extension().and_then(|ext| ext.to_str()) != Some(STATE_FILE_EXTENSION)
+        {
+            continue;
+        }
+        if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+            state_files.push(name.to_string());
+        }
+    }
+    state_files.sort();
+
+    Ok(state_files)
+}
+
+fn archive_error(message: &str) -> impl Fn(std::io::Error) -> BackupError + '_ {
+    move |err| {
+        BackupError::Internal(InternalError::from_source_with_prefix(
+            Box::new(err),
+            message.to_string(),
+        ))
+    }
+}
+
+/// A temporary directory that is removed when dropped.
+struct WorkDir {
+    path: PathBuf,
+}
+
+impl WorkDir {
+    fn new() -> Result<Self, InternalError> {
+        let path = std::env::temp_dir().join(format!("splinter-backup-{}", Uuid::new_v4()));
+        fs::create_dir_all(&path).map_err(|err| {
+            InternalError::from_source_with_prefix(
+                Box::new(err),
+                "Unable to create backup working directory".to_string(),
+            )
+        })?;
+        Ok(Self { path })
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_dir_all(&self.path) {
+            warn!(
+                "Unable to remove backup working directory {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "memory", feature = "registry"))]
+mod tests {
+    use super::*;
+
+    use tempdir::TempDir;
+
+    use crate::store::memory::MemoryStoreFactory;
+
+    /// Verify that a backup restores onto a fresh node.
+    ///
+    /// 1. Add a registry node to a `MemoryStoreFactory` and write a state file and a lock file
+    ///    to a state directory.
+    /// 2. Create a backup and verify the manifest lists the state file, but not the lock file.
+    /// 3. Restore the backup into a new `MemoryStoreFactory` and an empty state directory.
+    /// 4. Verify the registry node and the state file were restored.
+    #[test]
+    fn test_backup_and_restore() {
+        let source_dir = TempDir::new("source").expect("Unable to create temp dir");
+        let target_dir = TempDir::new("target").expect("Unable to create temp dir");
+
+        let source = MemoryStoreFactory::new().expect("Unable to create source factory");
+        source
+            .get_registry_store()
+            .add_node(create_node())
+            .expect("Unable to add node");
+        fs::write(
+            source_dir.path().join("circuit::service-state.lmdb"),
+            b"state",
+        )
+        .expect("Unable to write state file");
+        fs::write(
+            source_dir.path().join("circuit::service-state.lmdb-lock"),
+            b"lock",
+        )
+        .expect("Unable to write lock file");
+
+        let mut archive = vec![];
+        let manifest = create_backup(&source, source_dir.path(), &mut archive)
+            .expect("Unable to create backup");
+        assert_eq!(manifest.version(), BACKUP_FORMAT_VERSION);
+        assert!(manifest.stores().contains(&"registry".to_string()));
+        assert_eq!(
+            manifest.state_files(),
+            &["circuit::service-state.lmdb".to_string()]
+        );
+
+        let target = MemoryStoreFactory::new().expect("Unable to create target factory");
+        let restored = restore_backup(&archive[..], &target, target_dir.path())
+            .expect("Unable to restore backup");
+        assert_eq!(restored.state_files(), manifest.state_files());
+
+        assert!(target
+            .get_registry_store()
+            .get_node("node-000")
+            .expect("Unable to get node")
+            .is_some());
+        assert_eq!(
+            fs::read(target_dir.path().join("circuit::service-state.lmdb"))
+                .expect("Unable to read restored state file"),
+            b"state"
+        );
+    }
+
+    /// Verify that a backup is not restored into a state directory that contains state files,
+    /// and that no data is restored in that case.
+    #[test]
+    fn test_restore_state_dir_not_empty() {
+        let source_dir = TempDir::new("source").expect("Unable to create temp dir");
+        let target_dir = TempDir::new("target").expect("Unable to create temp dir");
+
+        let source = MemoryStoreFactory::new().expect("Unable to create source factory");
+        source
+            .get_registry_store()
+            .add_node(create_node())
+            .expect("Unable to add node");
+
+        let mut archive = vec![];
+        create_backup(&source, source_dir.path(), &mut archive).expect("Unable to create backup");
+
+        fs::write(target_dir.path().join("existing.lmdb"), b"state")
+            .expect("Unable to write state file");
+
+        let target = MemoryStoreFactory::new().expect("Unable to create target factory");
+        assert!(matches!(
+            restore_backup(&archive[..], &target, target_dir.path()),
+            Err(BackupError::InvalidState(_))
+        ));
+        assert!(target
+            .get_registry_store()
+            .get_node("node-000")
+            .expect("Unable to get node")
+            .is_none());
+    }
+
+    /// Verify that an archive whose manifest does not list every store of the node is refused.
+    #[test]
+    fn test_restore_missing_store() {
+        let manifest = BackupManifest {
+            version: BACKUP_FORMAT_VERSION,
+            splinter_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: 0,
+            stores: migrated_stores()
+                .into_iter()
+                .filter(|store| *store != "registry")
+                .map(|store| store.to_string())
+                .collect(),
+            counts: vec![],
+            state_files: vec![],
+        };
+
+        assert!(matches!(
+            check_manifest(&manifest),
+            Err(BackupError::InvalidState(_))
+        ));
+    }
+
+    fn create_node() -> crate::registry::Node {
+        crate::registry::Node::builder("node-000")
+            .with_endpoint("tcps://127.0.0.1:8044")
+            .with_display_name("Node 0")
+            .with_key("0123")
+            .build()
+            .expect("Unable to build node")
+    }
+}
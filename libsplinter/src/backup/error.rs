@@ -0,0 +1,55 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Errors for creating and restoring backups.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::error::{InternalError, InvalidStateError};
+use crate::store::migration::StoreMigrationError;
+
+/// Errors that may occur while creating or restoring a backup.
+#[derive(Debug)]
+pub enum BackupError {
+    Internal(InternalError),
+    InvalidState(InvalidStateError),
+}
+
+impl Error for BackupError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BackupError::Internal(err) => err.source(),
+            BackupError::InvalidState(err) => err.source(),
+        }
+    }
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BackupError::Internal(err) => f.write_str(&err.to_string()),
+            BackupError::InvalidState(err) => f.write_str(&err.to_string()),
+        }
+    }
+}
+
+impl From<StoreMigrationError> for BackupError {
+    fn from(err: StoreMigrationError) -> Self {
+        match err {
+            StoreMigrationError::Internal(err) => BackupError::Internal(err),
+            StoreMigrationError::InvalidState(err) => BackupError::InvalidState(err),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Coordination between commits and backups.
+
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Allows a backup to wait for in-progress commits to finish and to hold off new commits while
+/// the node's state is copied.
+///
+/// Components that commit state hold a [`CommitGuard`] for the duration of each commit. Quiescing
+/// the gate blocks until all such guards have been dropped, after which new commits block until
+/// the [`QuiesceGuard`] is dropped.
+#[derive(Clone, Default)]
+pub struct CommitGate {
+    lock: Arc<RwLock<()>>,
+}
+
+impl CommitGate {
+    /// Creates a new, open commit gate.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enters the gate for the duration of a commit, blocking while the gate is quiesced.
+    pub fn enter(&self) -> CommitGuard<'_> {
+        // The lock protects no data, so a poisoned lock is still safe to use
+        CommitGuard {
+            _guard: self.lock.read().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+
+    /// Quiesces the gate, blocking until all in-progress commits have finished. Commits are held
+    /// off until the returned guard is dropped.
+    pub fn quiesce(&self) -> QuiesceGuard<'_> {
+        QuiesceGuard {
+            _guard: self.lock.write().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+}
+
+/// Held by a component while it commits state; see [`CommitGate::enter`].
+pub struct CommitGuard<'a> {
+    _guard: RwLockReadGuard<'a, ()>,
+}
+
+/// Holds off commits until dropped; see [`CommitGate::quiesce`].
+pub struct QuiesceGuard<'a> {
+    _guard: RwLockWriteGuard<'a, ()>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc::channel;
+    use std::thread;
+    use std::time::Duration;
+
+    /// Verify that commits are held off while the gate is quiesced.
+    ///
+    /// 1. Quiesce the gate.
+    /// 2. Start a thread that enters the gate and then sends a message.
+    /// 3. Verify that no message is received while the gate is quiesced.
+    /// 4. Drop the quiesce guard and verify that the message is received.
+    #[test]
+    fn test_quiesce_holds_off_commits() {
+        let gate = CommitGate::new();
+        let quiesce_guard = gate.quiesce();
+
+        let (tx, rx) = channel();
+        let thread_gate = gate.clone();
+        let join_handle = thread::spawn(move || {
+            let _guard = thread_gate.enter();
+            tx.send(()).expect("Unable to send");
+        });
+
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+
+        drop(quiesce_guard);
+
+        rx.recv_timeout(Duration::from_secs(5))
+            .expect("Commit was not allowed after the gate was reopened");
+        join_handle.join().expect("Unable to join thread");
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Consistent backups of a node's database and service state.
+//!
+//! A backup is a gzipped tar archive that contains:
+//!
+//! * `manifest.json` - describes the archive, including its format version
+//! * `database.db` - a SQLite snapshot of every store, regardless of the node's database backend
+//! * `state/*.lmdb` - each LMDB database in the node's state directory, such as the scabbard
+//!   state and receipt databases
+//!
+//! To get a consistent snapshot of a running node, the caller should quiesce the components that
+//! write to the database and state directory (for example with a [`CommitGate`]) for the
+//! duration of [`create_backup`].
+
+mod archive;
+mod error;
+mod gate;
+
+pub use archive::{create_backup, restore_backup, BackupManifest, BACKUP_FORMAT_VERSION};
+pub use error::BackupError;
+pub use gate::{CommitGate, CommitGuard, QuiesceGuard};
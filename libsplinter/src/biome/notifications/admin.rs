@@ -0,0 +1,322 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Creates notifications for Biome users from admin service events.
+
+use std::collections::BTreeSet;
+
+use crate::admin::service::{AdminKeyVerifier, AdminServiceEventSubscriber, AdminSubscriberError};
+use crate::admin::store::{AdminServiceEvent, EventType};
+use crate::biome::key_management::store::KeyStore;
+use crate::hex::parse_hex;
+
+use super::store::{NotificationBuilder, NotificationStore};
+
+/// An admin service event subscriber that notifies Biome users about circuit proposals and
+/// circuits.
+///
+/// Notifications are created for the Biome users that have a key that is permitted to act as an
+/// admin on behalf of the local node. Each notification has the `circuit_id`, `event_type` and
+/// `management_type` properties, so applications can find the notifications for their circuits.
+pub struct AdminServiceNotifier {
+    node_id: String,
+    notification_store: Box<dyn NotificationStore>,
+    key_store: Box<dyn KeyStore>,
+    key_verifier: Box<dyn AdminKeyVerifier>,
+}
+
+impl AdminServiceNotifier {
+    /// Creates a new `AdminServiceNotifier`
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The ID of the local node
+    /// * `notification_store` - The store the notifications are added to
+    /// * `key_store` - The store of the Biome users' keys
+    /// * `key_verifier` - Checks which keys are permitted to act as an admin for the local node
+    pub fn new(
+        node_id: String,
+        notification_store: Box<dyn NotificationStore>,
+        key_store: Box<dyn KeyStore>,
+        key_verifier: Box<dyn AdminKeyVerifier>,
+    ) -> Self {
+        Self {
+            node_id,
+            notification_store,
+            key_store,
+            key_verifier,
+        }
+    }
+
+    /// Returns the IDs of the users with a key that is permitted to act as an admin for the local
+    /// node.
+    fn admin_users(&self) -> Result<BTreeSet<String>, AdminSubscriberError> {
+        let keys = self.key_store.list_keys(None).map_err(|err| {
+            AdminSubscriberError::UnableToHandleEvent(format!("Unable to list keys: {}", err))
+        })?;
+
+        let mut users = BTreeSet::new();
+        for key in keys {
+            let public_key = match parse_hex(&key.public_key) {
+                Ok(public_key) => public_key,
+                Err(err) => {
+                    debug!(
+                        "Ignoring invalid public key of user {}: {}",
+                        key.user_id, err
+                    );
+                    continue;
+                }
+            };
+
+            if self
+                .key_verifier
+                .is_permitted(&self.node_id, &public_key)
+                .map_err(|err| AdminSubscriberError::UnableToHandleEvent(err.to_string()))?
+            {
+                users.insert(key.user_id);
+            }
+        }
+
+        Ok(users)
+    }
+}
+
+impl AdminServiceEventSubscriber for AdminServiceNotifier {
+    fn handle_event(
+        &self,
+        admin_service_event: &AdminServiceEvent,
+    ) -> Result<(), AdminSubscriberError> {
+        let proposal = admin_service_event.proposal();
+        let circuit_id = proposal.circuit_id();
+
+        let (event_type, title, body) = match admin_service_event.event_type() {
+            EventType::ProposalSubmitted => {
+                // The requester's node does not vote on its own proposal
+                if proposal.requester_node_id() == self.node_id {
+                    return Ok(());
+                }
+                (
+                    "ProposalSubmitted",
+                    "Circuit proposal awaits your vote",
+                    format!(
+                        "Node {} proposed circuit {}, which needs a vote from node {}",
+                        proposal.requester_node_id(),
+                        circuit_id,
+                        self.node_id
+                    ),
+                )
+            }
+            EventType::ProposalRejected { .. } => (
+                "ProposalRejected",
+                "Circuit proposal rejected",
+                format!("The proposal for circuit {} was rejected", circuit_id),
+            ),
+            EventType::CircuitReady => (
+                "CircuitReady",
+                "Circuit ready",
+                format!("Circuit {} is ready", circuit_id),
+            ),
+            EventType::CircuitDisbanded => (
+                "CircuitDisbanded",
+                "Circuit disbanded",
+                format!("Circuit {} was disbanded", circuit_id),
+            ),
+            EventType::ProposalVote { .. } | EventType::ProposalAccepted { .. } => return Ok(()),
+        };
+
+        for user_id in self.admin_users()? {
+            let notification = NotificationBuilder::new()
+                .with_user_id(user_id)
+                .with_title(title.to_string())
+                .with_body(body.clone())
+                .with_property("circuit_id".to_string(), circuit_id.to_string())
+                .with_property("event_type".to_string(), event_type.to_string())
+                .with_property(
+                    "management_type".to_string(),
+                    proposal.circuit().circuit_management_type().to_string(),
+                )
+                .build()
+                .map_err(|err| AdminSubscriberError::UnableToHandleEvent(err.to_string()))?;
+
+            self.notification_store
+                .add_notification(notification)
+                .map_err(|err| {
+                    AdminSubscriberError::UnableToHandleEvent(format!(
+                        "Unable to add notification: {}",
+                        err
+                    ))
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    use crate::admin::service::AdminKeyVerifierError;
+    use crate::admin::store::{
+        AdminServiceEventBuilder, CircuitProposal, CircuitProposalBuilder, ProposalType,
+        ProposedCircuitBuilder, ProposedNodeBuilder,
+    };
+    use crate::biome::key_management::Key;
+    use crate::biome::{DieselKeyStore, DieselNotificationStore};
+    use crate::migrations::run_sqlite_migrations;
+
+    const ADMIN_KEY: &str = "0123";
+
+    /// Verify that a submitted proposal creates a notification for each user with an admin key
+    /// for the local node, and that a proposal from the local node does not.
+    ///
+    /// 1. Add an admin key for user1 and a key that is not permitted for user2.
+    /// 2. Handle a `ProposalSubmitted` event for a proposal from another node and verify that
+    ///    only user1 is notified, with the circuit's properties.
+    /// 3. Handle a `ProposalSubmitted` event for a proposal from the local node and verify that
+    ///    no notification is added.
+    #[test]
+    fn notify_proposal_submitted() {
+        let pool = create_connection_pool_and_migrate();
+
+        let key_store = DieselKeyStore::new(pool.clone());
+        key_store
+            .add_key(Key::new(ADMIN_KEY, "private", "user1", "admin"))
+            .expect("Unable to add key");
+        key_store
+            .add_key(Key::new("4567", "private", "user2", "other"))
+            .expect("Unable to add key");
+
+        let notification_store = DieselNotificationStore::new(pool);
+
+        let notifier = AdminServiceNotifier::new(
+            "bubba-node-000".into(),
+            notification_store.clone_box(),
+            Box::new(key_store),
+            Box::new(MockAdminKeyVerifier),
+        );
+
+        notifier
+            .handle_event(&create_event(1, "acme-node-000"))
+            .expect("Unable to handle event");
+
+        let notifications = notification_store
+            .list_notifications("user1")
+            .expect("Unable to list notifications");
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            notifications[0].title(),
+            "Circuit proposal awaits your vote"
+        );
+        assert!(notifications[0].unread());
+        assert_eq!(
+            notifications[0]
+                .properties()
+                .get("circuit_id")
+                .map(String::as_str),
+            Some("WBKLF-BBBBB")
+        );
+        assert_eq!(
+            notifications[0]
+                .properties()
+                .get("management_type")
+                .map(String::as_str),
+            Some("gameroom")
+        );
+        assert!(notification_store
+            .list_notifications("user2")
+            .expect("Unable to list notifications")
+            .is_empty());
+
+        notifier
+            .handle_event(&create_event(2, "bubba-node-000"))
+            .expect("Unable to handle event");
+        assert_eq!(
+            notification_store
+                .list_notifications("user1")
+                .expect("Unable to list notifications")
+                .len(),
+            1
+        );
+    }
+
+    struct MockAdminKeyVerifier;
+
+    impl AdminKeyVerifier for MockAdminKeyVerifier {
+        fn is_permitted(&self, _node_id: &str, key: &[u8]) -> Result<bool, AdminKeyVerifierError> {
+            Ok(key == parse_hex(ADMIN_KEY).expect("Invalid key").as_slice())
+        }
+    }
+
+    fn create_event(event_id: i64, requester_node_id: &str) -> AdminServiceEvent {
+        AdminServiceEventBuilder::new()
+            .with_event_id(event_id)
+            .with_event_type(&EventType::ProposalSubmitted)
+            .with_proposal(&create_proposal(requester_node_id))
+            .build()
+            .expect("Unable to build AdminServiceEvent")
+    }
+
+    fn create_proposal(requester_node_id: &str) -> CircuitProposal {
+        CircuitProposalBuilder::default()
+            .with_proposal_type(&ProposalType::Create)
+            .with_circuit_id("WBKLF-BBBBB")
+            .with_circuit_hash("7ddc426972710adc0b2ecd49e89a9dd805fb9206bf516079724c887bedbcdf1d")
+            .with_circuit(
+                &ProposedCircuitBuilder::default()
+                    .with_circuit_id("WBKLF-BBBBB")
+                    .with_roster(&[])
+                    .with_members(&[
+                        ProposedNodeBuilder::default()
+                            .with_node_id("acme-node-000")
+                            .with_endpoints(&["tcps://splinterd-node-acme:8044".into()])
+                            .build()
+                            .expect("Unable to build node"),
+                        ProposedNodeBuilder::default()
+                            .with_node_id("bubba-node-000")
+                            .with_endpoints(&["tcps://splinterd-node-bubba:8044".into()])
+                            .build()
+                            .expect("Unable to build node"),
+                    ])
+                    .with_circuit_management_type("gameroom")
+                    .build()
+                    .expect("Unable to build circuit"),
+            )
+            .with_requester(&parse_hex(ADMIN_KEY).expect("Invalid key"))
+            .with_requester_node_id(requester_node_id)
+            .build()
+            .expect("Unable to build proposal")
+    }
+
+    /// Creates a connection pool for an in-memory SQLite database with only a single connection
+    /// available. Each connection is backed by a different in-memory SQLite database, so limiting
+    /// the pool to a single connection insures that the same DB is used for all operations.
+    fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+}
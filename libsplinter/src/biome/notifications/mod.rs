@@ -17,4 +17,8 @@
 
 //! Provides an API for notifications.
 
+#[cfg(all(feature = "admin-service", feature = "biome-key-management"))]
+pub mod admin;
+#[cfg(feature = "rest-api")]
+pub mod rest_api;
 pub mod store;
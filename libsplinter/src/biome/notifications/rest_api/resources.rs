@@ -0,0 +1,48 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines structures used in the notifications REST API.
+
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use crate::biome::notifications::store::Notification;
+
+#[derive(Serialize)]
+pub(crate) struct ResponseNotification<'a> {
+    id: &'a str,
+    title: &'a str,
+    body: &'a str,
+    properties: &'a BTreeMap<String, String>,
+    /// The time the notification was created, in seconds since the Unix epoch
+    created: u64,
+    unread: bool,
+}
+
+impl<'a> From<&'a Notification> for ResponseNotification<'a> {
+    fn from(notification: &'a Notification) -> Self {
+        ResponseNotification {
+            id: notification.id(),
+            title: notification.title(),
+            body: notification.body(),
+            properties: notification.properties(),
+            created: notification
+                .created()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0),
+            unread: notification.unread(),
+        }
+    }
+}
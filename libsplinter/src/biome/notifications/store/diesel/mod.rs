@@ -1,19 +1,309 @@
-/*
- * Copyright 2018-2021 Cargill Incorporated
- *
- * Licensed under the Apache License, Version 2.0 (the "License");
- * you may not use this file except in compliance with the License.
- * You may obtain a copy of the License at
- *
- *     http://www.apache.org/licenses/LICENSE-2.0
- *
- * Unless required by applicable law or agreed to in writing, software
- * distributed under the License is distributed on an "AS IS" BASIS,
- * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
- * See the License for the specific language governing permissions and
- * limitations under the License.
- * -----------------------------------------------------------------------------
- */
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Database-backed implementation of the [NotificationStore], powered by [diesel].
 
 pub(in crate::biome) mod models;
-mod schema;
+mod operations;
+pub(in crate::biome) mod schema;
+
+use diesel::r2d2::{ConnectionManager, Pool};
+
+use super::{Notification, NotificationStore, NotificationStoreError};
+
+use operations::{
+    add_notification::NotificationStoreAddNotification as _,
+    get_notification::NotificationStoreGetNotification as _,
+    list_notifications::NotificationStoreListNotifications as _,
+    mark_notification_read::NotificationStoreMarkNotificationRead as _,
+    remove_notification::NotificationStoreRemoveNotification as _, NotificationStoreOperations,
+};
+
+/// Manages adding, updating, fetching and removing notifications from the database
+pub struct DieselNotificationStore<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C: diesel::Connection> DieselNotificationStore<C> {
+    /// Creates a new DieselNotificationStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        DieselNotificationStore { connection_pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl NotificationStore for DieselNotificationStore<diesel::pg::PgConnection> {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).add_notification(notification)
+    }
+
+    fn get_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<Option<Notification>, NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).get_notification(user_id, notification_id)
+    }
+
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<Notification>, NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).list_notifications(user_id)
+    }
+
+    fn list_all_notifications(&self) -> Result<Vec<Notification>, NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).list_all_notifications()
+    }
+
+    fn mark_notification_read(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection)
+            .mark_notification_read(user_id, notification_id)
+    }
+
+    fn remove_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).remove_notification(user_id, notification_id)
+    }
+
+    fn clone_box(&self) -> Box<dyn NotificationStore> {
+        Box::new(Self {
+            connection_pool: self.connection_pool.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl NotificationStore for DieselNotificationStore<diesel::sqlite::SqliteConnection> {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).add_notification(notification)
+    }
+
+    fn get_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<Option<Notification>, NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).get_notification(user_id, notification_id)
+    }
+
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<Notification>, NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).list_notifications(user_id)
+    }
+
+    fn list_all_notifications(&self) -> Result<Vec<Notification>, NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).list_all_notifications()
+    }
+
+    fn mark_notification_read(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection)
+            .mark_notification_read(user_id, notification_id)
+    }
+
+    fn remove_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let connection = self.connection_pool.get()?;
+        NotificationStoreOperations::new(&*connection).remove_notification(user_id, notification_id)
+    }
+
+    fn clone_box(&self) -> Box<dyn NotificationStore> {
+        Box::new(Self {
+            connection_pool: self.connection_pool.clone(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+pub mod tests {
+    use super::*;
+
+    use std::time::{Duration, SystemTime};
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    use crate::biome::notifications::store::NotificationBuilder;
+    use crate::migrations::run_sqlite_migrations;
+
+    /// Verify that a SQLite-backed `DieselNotificationStore` correctly supports adding, fetching
+    /// and listing notifications.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselNotificationStore`.
+    /// 3. Add notifications for two users.
+    /// 4. Verify that adding a notification with an existing ID fails.
+    /// 5. Verify that `list_notifications` returns only the user's notifications, newest first,
+    ///    with their properties.
+    /// 6. Verify that `get_notification` does not return another user's notification.
+    /// 7. Verify that `list_all_notifications` returns every user's notifications.
+    #[test]
+    fn sqlite_add_and_list_notifications() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselNotificationStore::new(pool);
+
+        let older = build_notification("older", "user1", 100);
+        let newer = build_notification("newer", "user1", 200);
+        let other = build_notification("other", "user2", 300);
+        store
+            .add_notification(older.clone())
+            .expect("Unable to add notification");
+        store
+            .add_notification(newer.clone())
+            .expect("Unable to add notification");
+        store
+            .add_notification(other.clone())
+            .expect("Unable to add notification");
+
+        assert!(store.add_notification(older.clone()).is_err());
+
+        assert_eq!(
+            store
+                .list_notifications("user1")
+                .expect("Unable to list notifications"),
+            vec![newer.clone(), older.clone()]
+        );
+
+        assert_eq!(
+            store
+                .get_notification("user2", "other")
+                .expect("Unable to get notification"),
+            Some(other.clone())
+        );
+        assert_eq!(
+            store
+                .get_notification("user1", "other")
+                .expect("Unable to get notification"),
+            None
+        );
+
+        assert_eq!(
+            store
+                .list_all_notifications()
+                .expect("Unable to list all notifications"),
+            vec![newer, older, other]
+        );
+    }
+
+    /// Verify that a SQLite-backed `DieselNotificationStore` correctly supports marking
+    /// notifications read and removing them.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselNotificationStore`.
+    /// 3. Add a notification.
+    /// 4. Verify that the notification cannot be marked read or removed by another user.
+    /// 5. Mark the notification read and verify that it is no longer unread.
+    /// 6. Remove the notification and verify that it can no longer be fetched.
+    #[test]
+    fn sqlite_mark_read_and_remove_notification() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselNotificationStore::new(pool);
+
+        store
+            .add_notification(build_notification("notification", "user1", 100))
+            .expect("Unable to add notification");
+
+        assert!(store
+            .mark_notification_read("user2", "notification")
+            .is_err());
+        assert!(store.remove_notification("user2", "notification").is_err());
+
+        store
+            .mark_notification_read("user1", "notification")
+            .expect("Unable to mark notification read");
+        assert!(!store
+            .get_notification("user1", "notification")
+            .expect("Unable to get notification")
+            .expect("Notification not found")
+            .unread());
+
+        store
+            .remove_notification("user1", "notification")
+            .expect("Unable to remove notification");
+        assert_eq!(
+            store
+                .get_notification("user1", "notification")
+                .expect("Unable to get notification"),
+            None
+        );
+        assert!(store
+            .list_notifications("user1")
+            .expect("Unable to list notifications")
+            .is_empty());
+    }
+
+    fn build_notification(id: &str, user_id: &str, created: u64) -> Notification {
+        NotificationBuilder::new()
+            .with_id(id.into())
+            .with_user_id(user_id.into())
+            .with_title("Circuit proposal".into())
+            .with_body("A circuit proposal awaits your vote".into())
+            .with_property("circuit_id".into(), "abcde-12345".into())
+            .with_property("management_type".into(), "gameroom".into())
+            .with_created(SystemTime::UNIX_EPOCH + Duration::from_secs(created))
+            .build()
+            .expect("Unable to build notification")
+    }
+
+    /// Creates a connection pool for an in-memory SQLite database with only a single connection
+    /// available. Each connection is backed by a different in-memory SQLite database, so limiting
+    /// the pool to a single connection insures that the same DB is used for all operations.
+    fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+}
@@ -1,47 +1,100 @@
-/*
- * Copyright 2018-2021 Cargill Incorporated
- *
- * Licensed under the Apache License, Version 2.0 (the "License");
- * you may not use this file except in compliance with the License.
- * You may obtain a copy of the License at
- *
- *     http://www.apache.org/licenses/LICENSE-2.0
- *
- * Unless required by applicable law or agreed to in writing, software
- * distributed under the License is distributed on an "AS IS" BASIS,
- * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
- * See the License for the specific language governing permissions and
- * limitations under the License.
- * -----------------------------------------------------------------------------
- */
-
-use std::time::SystemTime;
-
-use super::schema::{notification_properties, notifications, user_notifications};
-
-#[derive(Insertable, Queryable)]
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+use crate::biome::notifications::store::{Notification, NotificationStoreError};
+use crate::error::InternalError;
+
+use super::schema::{notification_properties, notifications};
+
+#[derive(Insertable, Queryable, Identifiable, PartialEq, Debug)]
 #[table_name = "notifications"]
-pub struct Notification {
+#[primary_key(id)]
+pub struct NotificationModel {
     pub id: String,
-    pub payload_title: String,
-    pub payload_body: String,
-    pub created: SystemTime,
-    pub recipients: Vec<String>,
-}
-
-#[derive(Insertable, Queryable)]
-#[table_name = "user_notifications"]
-pub struct UserNotification {
-    pub notification_id: String,
     pub user_id: String,
+    pub title: String,
+    pub body: String,
+    pub created: i64,
     pub unread: bool,
 }
 
-#[derive(Insertable, Queryable)]
+#[derive(Insertable, Queryable, PartialEq, Debug)]
 #[table_name = "notification_properties"]
-pub struct NotificationProperty {
-    pub id: i64,
+pub struct NotificationPropertyModel {
     pub notification_id: String,
     pub property: String,
     pub property_value: String,
 }
+
+impl NotificationModel {
+    /// Converts the model and the models of the notification's properties into a `Notification`
+    pub fn into_notification(self, properties: Vec<NotificationPropertyModel>) -> Notification {
+        Notification {
+            id: self.id,
+            user_id: self.user_id,
+            title: self.title,
+            body: self.body,
+            properties: properties
+                .into_iter()
+                .map(|property| (property.property, property.property_value))
+                .collect::<BTreeMap<_, _>>(),
+            created: SystemTime::UNIX_EPOCH + Duration::from_secs(self.created as u64),
+            unread: self.unread,
+        }
+    }
+}
+
+/// Converts a notification into the models for the notification and its properties
+pub fn notification_to_models(
+    notification: Notification,
+) -> Result<(NotificationModel, Vec<NotificationPropertyModel>), NotificationStoreError> {
+    let Notification {
+        id,
+        user_id,
+        title,
+        body,
+        properties,
+        created,
+        unread,
+    } = notification;
+
+    let created = created
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(|err| NotificationStoreError::Internal(InternalError::from_source(Box::new(err))))?
+        .as_secs() as i64;
+
+    let properties = properties
+        .into_iter()
+        .map(|(property, property_value)| NotificationPropertyModel {
+            notification_id: id.clone(),
+            property,
+            property_value,
+        })
+        .collect();
+
+    Ok((
+        NotificationModel {
+            id,
+            user_id,
+            title,
+            body,
+            created,
+            unread,
+        },
+        properties,
+    ))
+}
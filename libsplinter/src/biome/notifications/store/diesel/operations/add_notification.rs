@@ -0,0 +1,75 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::NotificationStoreOperations;
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::biome::notifications::store::{
+    diesel::{
+        models::notification_to_models,
+        schema::{notification_properties, notifications},
+    },
+    Notification, NotificationStoreError,
+};
+
+pub trait NotificationStoreAddNotification {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> NotificationStoreAddNotification
+    for NotificationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        let (notification, properties) = notification_to_models(notification)?;
+
+        self.conn.transaction::<_, NotificationStoreError, _>(|| {
+            insert_into(notifications::table)
+                .values(notification)
+                .execute(self.conn)?;
+
+            if !properties.is_empty() {
+                insert_into(notification_properties::table)
+                    .values(properties)
+                    .execute(self.conn)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> NotificationStoreAddNotification
+    for NotificationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        let (notification, properties) = notification_to_models(notification)?;
+
+        self.conn.transaction::<_, NotificationStoreError, _>(|| {
+            insert_into(notifications::table)
+                .values(notification)
+                .execute(self.conn)?;
+
+            if !properties.is_empty() {
+                insert_into(notification_properties::table)
+                    .values(properties)
+                    .execute(self.conn)?;
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::NotificationStoreOperations;
+
+use diesel::prelude::*;
+
+use crate::biome::notifications::store::{
+    diesel::{
+        models::{NotificationModel, NotificationPropertyModel},
+        schema::{notification_properties, notifications},
+    },
+    Notification, NotificationStoreError,
+};
+
+pub trait NotificationStoreGetNotification {
+    fn get_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<Option<Notification>, NotificationStoreError>;
+}
+
+impl<'a, C> NotificationStoreGetNotification for NotificationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    bool: diesel::deserialize::FromSql<diesel::sql_types::Bool, C::Backend>,
+{
+    fn get_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<Option<Notification>, NotificationStoreError> {
+        let notification = match notifications::table
+            .filter(notifications::id.eq(notification_id))
+            .filter(notifications::user_id.eq(user_id))
+            .first::<NotificationModel>(self.conn)
+            .optional()?
+        {
+            Some(notification) => notification,
+            None => return Ok(None),
+        };
+
+        let properties = notification_properties::table
+            .filter(notification_properties::notification_id.eq(notification_id))
+            .load::<NotificationPropertyModel>(self.conn)?;
+
+        Ok(Some(notification.into_notification(properties)))
+    }
+}
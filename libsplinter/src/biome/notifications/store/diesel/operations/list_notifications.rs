@@ -0,0 +1,107 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use super::NotificationStoreOperations;
+
+use diesel::prelude::*;
+
+use crate::biome::notifications::store::{
+    diesel::{
+        models::{NotificationModel, NotificationPropertyModel},
+        schema::{notification_properties, notifications},
+    },
+    Notification, NotificationStoreError,
+};
+
+pub trait NotificationStoreListNotifications {
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<Notification>, NotificationStoreError>;
+
+    fn list_all_notifications(&self) -> Result<Vec<Notification>, NotificationStoreError>;
+}
+
+impl<'a, C> NotificationStoreListNotifications for NotificationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    bool: diesel::deserialize::FromSql<diesel::sql_types::Bool, C::Backend>,
+{
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<Notification>, NotificationStoreError> {
+        let notifications = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .order((notifications::created.desc(), notifications::id.asc()))
+            .load::<NotificationModel>(self.conn)?;
+
+        let mut properties_by_id: HashMap<String, Vec<NotificationPropertyModel>> = HashMap::new();
+        for property in notification_properties::table
+            .inner_join(notifications::table)
+            .filter(notifications::user_id.eq(user_id))
+            .select(notification_properties::all_columns)
+            .load::<NotificationPropertyModel>(self.conn)?
+        {
+            properties_by_id
+                .entry(property.notification_id.clone())
+                .or_insert_with(Vec::new)
+                .push(property);
+        }
+
+        Ok(notifications
+            .into_iter()
+            .map(|notification| {
+                let properties = properties_by_id
+                    .remove(&notification.id)
+                    .unwrap_or_default();
+                notification.into_notification(properties)
+            })
+            .collect())
+    }
+
+    fn list_all_notifications(&self) -> Result<Vec<Notification>, NotificationStoreError> {
+        let notifications = notifications::table
+            .order((
+                notifications::user_id.asc(),
+                notifications::created.desc(),
+                notifications::id.asc(),
+            ))
+            .load::<NotificationModel>(self.conn)?;
+
+        let mut properties_by_id: HashMap<String, Vec<NotificationPropertyModel>> = HashMap::new();
+        for property in
+            notification_properties::table.load::<NotificationPropertyModel>(self.conn)?
+        {
+            properties_by_id
+                .entry(property.notification_id.clone())
+                .or_insert_with(Vec::new)
+                .push(property);
+        }
+
+        Ok(notifications
+            .into_iter()
+            .map(|notification| {
+                let properties = properties_by_id
+                    .remove(&notification.id)
+                    .unwrap_or_default();
+                notification.into_notification(properties)
+            })
+            .collect())
+    }
+}
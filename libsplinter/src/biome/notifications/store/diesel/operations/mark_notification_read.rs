@@ -0,0 +1,88 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::NotificationStoreOperations;
+
+use diesel::{dsl::update, prelude::*};
+
+use crate::biome::notifications::store::{diesel::schema::notifications, NotificationStoreError};
+use crate::error::InvalidArgumentError;
+
+pub trait NotificationStoreMarkNotificationRead {
+    fn mark_notification_read(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> NotificationStoreMarkNotificationRead
+    for NotificationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn mark_notification_read(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let updated = update(
+            notifications::table
+                .filter(notifications::id.eq(notification_id))
+                .filter(notifications::user_id.eq(user_id)),
+        )
+        .set(notifications::unread.eq(false))
+        .execute(self.conn)?;
+
+        if updated == 0 {
+            return Err(NotificationStoreError::InvalidArgument(
+                InvalidArgumentError::new(
+                    "notification_id".to_string(),
+                    "A notification with the given ID does not exist for the user".to_string(),
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> NotificationStoreMarkNotificationRead
+    for NotificationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn mark_notification_read(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let updated = update(
+            notifications::table
+                .filter(notifications::id.eq(notification_id))
+                .filter(notifications::user_id.eq(user_id)),
+        )
+        .set(notifications::unread.eq(false))
+        .execute(self.conn)?;
+
+        if updated == 0 {
+            return Err(NotificationStoreError::InvalidArgument(
+                InvalidArgumentError::new(
+                    "notification_id".to_string(),
+                    "A notification with the given ID does not exist for the user".to_string(),
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::NotificationStoreOperations;
+
+use diesel::{dsl::delete, prelude::*};
+
+use crate::biome::notifications::store::{
+    diesel::{
+        models::NotificationModel,
+        schema::{notification_properties, notifications},
+    },
+    NotificationStoreError,
+};
+use crate::error::InvalidArgumentError;
+
+pub trait NotificationStoreRemoveNotification {
+    fn remove_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError>;
+}
+
+impl<'a, C> NotificationStoreRemoveNotification for NotificationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    bool: diesel::deserialize::FromSql<diesel::sql_types::Bool, C::Backend>,
+{
+    fn remove_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        self.conn.transaction::<_, NotificationStoreError, _>(|| {
+            let notification = notifications::table
+                .filter(notifications::id.eq(notification_id))
+                .filter(notifications::user_id.eq(user_id))
+                .first::<NotificationModel>(self.conn)
+                .optional()?;
+            if notification.is_none() {
+                return Err(NotificationStoreError::InvalidArgument(
+                    InvalidArgumentError::new(
+                        "notification_id".to_string(),
+                        "A notification with the given ID does not exist for the user".to_string(),
+                    ),
+                ));
+            }
+
+            delete(
+                notification_properties::table
+                    .filter(notification_properties::notification_id.eq(notification_id)),
+            )
+            .execute(self.conn)?;
+
+            delete(notifications::table.filter(notifications::id.eq(notification_id)))
+                .execute(self.conn)?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A memory-backed implementation of the [NotificationStore]
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::error::{
+    ConstraintViolationError, ConstraintViolationType, InternalError, InvalidArgumentError,
+};
+
+use super::{error::NotificationStoreError, Notification, NotificationStore};
+
+#[derive(Default, Clone)]
+pub struct MemoryNotificationStore {
+    inner: Arc<Mutex<HashMap<String, Notification>>>,
+}
+
+impl MemoryNotificationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NotificationStore for MemoryNotificationStore {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        let mut inner = self.inner.lock().map_err(|_| {
+            NotificationStoreError::Internal(InternalError::with_message(
+                "Cannot access notification store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        if inner.contains_key(notification.id()) {
+            return Err(NotificationStoreError::ConstraintViolation(
+                ConstraintViolationError::with_violation_type(ConstraintViolationType::Unique),
+            ));
+        }
+
+        inner.insert(notification.id().to_string(), notification);
+        Ok(())
+    }
+
+    fn get_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<Option<Notification>, NotificationStoreError> {
+        let inner = self.inner.lock().map_err(|_| {
+            NotificationStoreError::Internal(InternalError::with_message(
+                "Cannot access notification store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        Ok(inner
+            .get(notification_id)
+            .filter(|notification| notification.user_id() == user_id)
+            .cloned())
+    }
+
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<Notification>, NotificationStoreError> {
+        let inner = self.inner.lock().map_err(|_| {
+            NotificationStoreError::Internal(InternalError::with_message(
+                "Cannot access notification store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        let mut notifications = inner
+            .values()
+            .filter(|notification| notification.user_id() == user_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        notifications.sort_by(|a, b| {
+            b.created()
+                .cmp(&a.created())
+                .then_with(|| a.id().cmp(b.id()))
+        });
+
+        Ok(notifications)
+    }
+
+    fn list_all_notifications(&self) -> Result<Vec<Notification>, NotificationStoreError> {
+        let inner = self.inner.lock().map_err(|_| {
+            NotificationStoreError::Internal(InternalError::with_message(
+                "Cannot access notification store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        let mut notifications = inner.values().cloned().collect::<Vec<_>>();
+        notifications.sort_by(|a, b| {
+            a.user_id()
+                .cmp(b.user_id())
+                .then_with(|| b.created().cmp(&a.created()))
+                .then_with(|| a.id().cmp(b.id()))
+        });
+
+        Ok(notifications)
+    }
+
+    fn mark_notification_read(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let mut inner = self.inner.lock().map_err(|_| {
+            NotificationStoreError::Internal(InternalError::with_message(
+                "Cannot access notification store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        match inner.get_mut(notification_id) {
+            Some(notification) if notification.user_id() == user_id => {
+                notification.unread = false;
+                Ok(())
+            }
+            _ => Err(NotificationStoreError::InvalidArgument(
+                InvalidArgumentError::new(
+                    "notification_id".to_string(),
+                    "A notification with the given ID does not exist for the user".to_string(),
+                ),
+            )),
+        }
+    }
+
+    fn remove_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let mut inner = self.inner.lock().map_err(|_| {
+            NotificationStoreError::Internal(InternalError::with_message(
+                "Cannot access notification store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        match inner.get(notification_id) {
+            Some(notification) if notification.user_id() == user_id => {
+                inner.remove(notification_id);
+                Ok(())
+            }
+            _ => Err(NotificationStoreError::InvalidArgument(
+                InvalidArgumentError::new(
+                    "notification_id".to_string(),
+                    "A notification with the given ID does not exist for the user".to_string(),
+                ),
+            )),
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn NotificationStore> {
+        Box::new(self.clone())
+    }
+}
@@ -14,5 +14,267 @@
 
 //! Defines a basic representation of a notification.
 
-#[cfg(feature = "diesel")]
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
 pub(in crate::biome) mod diesel;
+pub mod error;
+pub(in crate::biome) mod memory;
+
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use uuid::Uuid;
+
+use crate::error::InvalidStateError;
+
+pub use error::NotificationStoreError;
+
+/// A notification for a Biome user.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    id: String,
+    user_id: String,
+    title: String,
+    body: String,
+    properties: BTreeMap<String, String>,
+    created: SystemTime,
+    unread: bool,
+}
+
+impl Notification {
+    /// Returns the notification's ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the ID of the user the notification is for.
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Returns the notification's title.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the notification's body.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Returns the application-defined properties of the notification, such as the ID of the
+    /// circuit the notification is about.
+    pub fn properties(&self) -> &BTreeMap<String, String> {
+        &self.properties
+    }
+
+    /// Returns the time the notification was created.
+    pub fn created(&self) -> SystemTime {
+        self.created
+    }
+
+    /// Returns whether the notification has not been read by the user.
+    pub fn unread(&self) -> bool {
+        self.unread
+    }
+}
+
+/// Builds a new `Notification`.
+#[derive(Default)]
+pub struct NotificationBuilder {
+    id: Option<String>,
+    user_id: Option<String>,
+    title: Option<String>,
+    body: Option<String>,
+    properties: BTreeMap<String, String>,
+    created: Option<SystemTime>,
+    unread: Option<bool>,
+}
+
+impl NotificationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the notification's ID; a random ID is generated if one is not set.
+    pub fn with_id(mut self, id: String) -> NotificationBuilder {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn with_user_id(mut self, user_id: String) -> NotificationBuilder {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn with_title(mut self, title: String) -> NotificationBuilder {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn with_body(mut self, body: String) -> NotificationBuilder {
+        self.body = Some(body);
+        self
+    }
+
+    pub fn with_property(mut self, property: String, value: String) -> NotificationBuilder {
+        self.properties.insert(property, value);
+        self
+    }
+
+    pub fn with_properties(mut self, properties: BTreeMap<String, String>) -> NotificationBuilder {
+        self.properties = properties;
+        self
+    }
+
+    /// Sets the time the notification was created; defaults to the current time.
+    pub fn with_created(mut self, created: SystemTime) -> NotificationBuilder {
+        self.created = Some(created);
+        self
+    }
+
+    /// Sets whether the notification has been read; defaults to unread.
+    pub fn with_unread(mut self, unread: bool) -> NotificationBuilder {
+        self.unread = Some(unread);
+        self
+    }
+
+    pub fn build(self) -> Result<Notification, InvalidStateError> {
+        Ok(Notification {
+            id: self
+                .id
+                .unwrap_or_else(|| Uuid::new_v4().to_hyphenated().to_string()),
+            user_id: self.user_id.ok_or_else(|| {
+                InvalidStateError::with_message(
+                    "A user id is required to build a Notification".into(),
+                )
+            })?,
+            title: self.title.ok_or_else(|| {
+                InvalidStateError::with_message(
+                    "A title is required to build a Notification".into(),
+                )
+            })?,
+            body: self.body.unwrap_or_default(),
+            properties: self.properties,
+            created: self.created.unwrap_or_else(SystemTime::now),
+            unread: self.unread.unwrap_or(true),
+        })
+    }
+}
+
+/// Defines methods for adding, listing, updating and removing the notifications of Biome users
+/// without defining a storage strategy.
+pub trait NotificationStore: Sync + Send {
+    /// Adds a notification to the underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///  * `notification` - The notification to be added
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError>;
+
+    /// Fetches a user's notification from the underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The ID of the user the notification is for
+    ///  * `notification_id` - The ID of the notification
+    fn get_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<Option<Notification>, NotificationStoreError>;
+
+    /// Lists a user's notifications from the underlying storage, newest first
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The ID of the user whose notifications are listed
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<Notification>, NotificationStoreError>;
+
+    /// Lists the notifications of every user from the underlying storage, ordered by user and
+    /// then newest first
+    fn list_all_notifications(&self) -> Result<Vec<Notification>, NotificationStoreError>;
+
+    /// Marks a user's notification as read
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The ID of the user the notification is for
+    ///  * `notification_id` - The ID of the notification
+    fn mark_notification_read(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError>;
+
+    /// Removes a user's notification from the underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The ID of the user the notification is for
+    ///  * `notification_id` - The ID of the notification
+    fn remove_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError>;
+
+    /// Clone into a boxed, dynamically dispatched store
+    fn clone_box(&self) -> Box<dyn NotificationStore>;
+}
+
+impl Clone for Box<dyn NotificationStore> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl<NS> NotificationStore for Box<NS>
+where
+    NS: NotificationStore + ?Sized,
+{
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        (**self).add_notification(notification)
+    }
+
+    fn get_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<Option<Notification>, NotificationStoreError> {
+        (**self).get_notification(user_id, notification_id)
+    }
+
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<Notification>, NotificationStoreError> {
+        (**self).list_notifications(user_id)
+    }
+
+    fn list_all_notifications(&self) -> Result<Vec<Notification>, NotificationStoreError> {
+        (**self).list_all_notifications()
+    }
+
+    fn mark_notification_read(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        (**self).mark_notification_read(user_id, notification_id)
+    }
+
+    fn remove_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        (**self).remove_notification(user_id, notification_id)
+    }
+
+    fn clone_box(&self) -> Box<dyn NotificationStore> {
+        (**self).clone_box()
+    }
+}
@@ -20,14 +20,16 @@ pub(in crate::biome) mod schema;
 
 use diesel::r2d2::{ConnectionManager, Pool};
 
-#[cfg(feature = "oauth-user-list")]
+#[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
 use super::OAuthUserIter;
+#[cfg(feature = "store-migration")]
+use super::OAuthUserSessionIter;
 use super::{
     InsertableOAuthUserSession, OAuthUser, OAuthUserSession, OAuthUserSessionStore,
     OAuthUserSessionStoreError,
 };
 
-#[cfg(feature = "oauth-user-list")]
+#[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
 use operations::list_users::OAuthUserSessionStoreListUsers as _;
 use operations::{
     add_session::OAuthUserSessionStoreAddSession as _,
@@ -35,6 +37,12 @@ use operations::{
     remove_session::OAuthUserSessionStoreRemoveSession as _,
     update_session::OAuthUserSessionStoreUpdateSession as _, OAuthUserSessionStoreOperations,
 };
+#[cfg(feature = "store-migration")]
+use operations::{
+    add_user::OAuthUserSessionStoreAddUser as _,
+    import_session::OAuthUserSessionStoreImportSession as _,
+    list_sessions::OAuthUserSessionStoreListSessions as _,
+};
 
 /// A database-backed [OAuthUserSessionStore], powered by [diesel].
 pub struct DieselOAuthUserSessionStore<C: diesel::Connection + 'static> {
@@ -86,12 +94,30 @@ impl OAuthUserSessionStore for DieselOAuthUserSessionStore<diesel::sqlite::Sqlit
         OAuthUserSessionStoreOperations::new(&*connection).get_user(subject)
     }
 
-    #[cfg(feature = "oauth-user-list")]
+    #[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
     fn list_users(&self) -> Result<OAuthUserIter, OAuthUserSessionStoreError> {
         let connection = self.connection_pool.get()?;
         OAuthUserSessionStoreOperations::new(&*connection).list_users()
     }
 
+    #[cfg(feature = "store-migration")]
+    fn add_user(&self, user: OAuthUser) -> Result<(), OAuthUserSessionStoreError> {
+        let connection = self.connection_pool.get()?;
+        OAuthUserSessionStoreOperations::new(&*connection).add_user(user)
+    }
+
+    #[cfg(feature = "store-migration")]
+    fn list_sessions(&self) -> Result<OAuthUserSessionIter, OAuthUserSessionStoreError> {
+        let connection = self.connection_pool.get()?;
+        OAuthUserSessionStoreOperations::new(&*connection).list_sessions()
+    }
+
+    #[cfg(feature = "store-migration")]
+    fn import_session(&self, session: OAuthUserSession) -> Result<(), OAuthUserSessionStoreError> {
+        let connection = self.connection_pool.get()?;
+        OAuthUserSessionStoreOperations::new(&*connection).import_session(session)
+    }
+
     fn clone_box(&self) -> Box<dyn OAuthUserSessionStore> {
         Box::new(Self {
             connection_pool: self.connection_pool.clone(),
@@ -138,12 +164,30 @@ impl OAuthUserSessionStore for DieselOAuthUserSessionStore<diesel::pg::PgConnect
         OAuthUserSessionStoreOperations::new(&*connection).get_user(subject)
     }
 
-    #[cfg(feature = "oauth-user-list")]
+    #[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
     fn list_users(&self) -> Result<OAuthUserIter, OAuthUserSessionStoreError> {
         let connection = self.connection_pool.get()?;
         OAuthUserSessionStoreOperations::new(&*connection).list_users()
     }
 
+    #[cfg(feature = "store-migration")]
+    fn add_user(&self, user: OAuthUser) -> Result<(), OAuthUserSessionStoreError> {
+        let connection = self.connection_pool.get()?;
+        OAuthUserSessionStoreOperations::new(&*connection).add_user(user)
+    }
+
+    #[cfg(feature = "store-migration")]
+    fn list_sessions(&self) -> Result<OAuthUserSessionIter, OAuthUserSessionStoreError> {
+        let connection = self.connection_pool.get()?;
+        OAuthUserSessionStoreOperations::new(&*connection).list_sessions()
+    }
+
+    #[cfg(feature = "store-migration")]
+    fn import_session(&self, session: OAuthUserSession) -> Result<(), OAuthUserSessionStoreError> {
+        let connection = self.connection_pool.get()?;
+        OAuthUserSessionStoreOperations::new(&*connection).import_session(session)
+    }
+
     fn clone_box(&self) -> Box<dyn OAuthUserSessionStore> {
         Box::new(Self {
             connection_pool: self.connection_pool.clone(),
@@ -156,6 +200,8 @@ pub mod tests {
     use super::*;
 
     use crate::biome::oauth::store::InsertableOAuthUserSessionBuilder;
+    #[cfg(feature = "store-migration")]
+    use crate::biome::oauth::store::OAuthUserSessionBuilder;
     use crate::migrations::run_sqlite_migrations;
 
     use diesel::{
@@ -578,6 +624,82 @@ pub mod tests {
         assert_eq!(users.len(), 2);
     }
 
+    /// Verify that a SQLite-backed `DieselOAuthUserSessionStore` correctly supports copying users
+    /// and sessions into another store.
+    ///
+    /// 1. Create two `DieselOAuthUserSessionStore`s, each backed by its own in-memory database.
+    /// 2. Add an OAuth user session to the first store.
+    /// 3. Copy the users with `add_user` and the sessions returned by `list_sessions` with
+    ///    `import_session` into the second store.
+    /// 4. Verify that the copied user has the same Biome user ID and that the copied session has
+    ///    the same "last authenticated" value.
+    /// 5. Verify that importing the session again results in a ConstraintViolation.
+    /// 6. Verify that importing a session for an unknown user results in an InvalidState error.
+    #[cfg(feature = "store-migration")]
+    #[test]
+    fn sqlite_copy_users_and_sessions() {
+        let source = DieselOAuthUserSessionStore::new(create_connection_pool_and_migrate());
+        let destination = DieselOAuthUserSessionStore::new(create_connection_pool_and_migrate());
+
+        let session = InsertableOAuthUserSessionBuilder::new()
+            .with_splinter_access_token("splinter_access_token".into())
+            .with_subject("subject".into())
+            .with_oauth_access_token("oauth_access_token".into())
+            .build()
+            .expect("Unable to build session");
+        source.add_session(session).expect("Unable to add session");
+
+        for user in source.list_users().expect("Unable to list users") {
+            destination.add_user(user).expect("Unable to add user");
+        }
+        let sessions = source
+            .list_sessions()
+            .expect("Unable to list sessions")
+            .collect::<Vec<OAuthUserSession>>();
+        assert_eq!(sessions.len(), 1);
+        for session in sessions.clone() {
+            destination
+                .import_session(session)
+                .expect("Unable to import session");
+        }
+
+        let source_user = source
+            .get_user("subject")
+            .expect("Unable to get source user")
+            .expect("Source user not found");
+        let destination_user = destination
+            .get_user("subject")
+            .expect("Unable to get destination user")
+            .expect("Destination user not found");
+        assert_eq!(source_user.user_id(), destination_user.user_id());
+
+        let copied = destination
+            .get_session("splinter_access_token")
+            .expect("Unable to get session")
+            .expect("Session not found");
+        assert_eq!(
+            copied.last_authenticated(),
+            sessions[0].last_authenticated()
+        );
+
+        assert!(matches!(
+            destination.import_session(sessions[0].clone()),
+            Err(OAuthUserSessionStoreError::ConstraintViolation(_)),
+        ));
+
+        let unknown = OAuthUserSessionBuilder::new()
+            .with_splinter_access_token("other_token".into())
+            .with_user(OAuthUser::new("unknown".into()))
+            .with_oauth_access_token("oauth_access_token".into())
+            .with_last_authenticated(sessions[0].last_authenticated())
+            .build()
+            .expect("Unable to build session");
+        assert!(matches!(
+            destination.import_session(unknown),
+            Err(OAuthUserSessionStoreError::InvalidState(_)),
+        ));
+    }
+
     /// Creates a connection pool for an in-memory SQLite database with only a single connection
     /// available. Each connection is backed by a different in-memory SQLite database, so limiting
     /// the pool to a single connection insures that the same DB is used for all operations.
@@ -24,7 +24,7 @@ pub struct OAuthUserModel {
     pub user_id: String,
 }
 
-#[derive(Debug, PartialEq, Associations, Identifiable, Queryable)]
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
 #[table_name = "oauth_user_sessions"]
 #[belongs_to(OAuthUserModel, foreign_key = "subject")]
 #[primary_key(splinter_access_token)]
@@ -0,0 +1,52 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::biome::oauth::store::{
+    diesel::{models::OAuthUserModel, schema::oauth_users},
+    OAuthUser, OAuthUserSessionStoreError,
+};
+
+use super::OAuthUserSessionStoreOperations;
+
+pub trait OAuthUserSessionStoreAddUser {
+    fn add_user(&self, user: OAuthUser) -> Result<(), OAuthUserSessionStoreError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> OAuthUserSessionStoreAddUser
+    for OAuthUserSessionStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_user(&self, user: OAuthUser) -> Result<(), OAuthUserSessionStoreError> {
+        insert_into(oauth_users::table)
+            .values(OAuthUserModel::from(user))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(OAuthUserSessionStoreError::from)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> OAuthUserSessionStoreAddUser
+    for OAuthUserSessionStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_user(&self, user: OAuthUser) -> Result<(), OAuthUserSessionStoreError> {
+        insert_into(oauth_users::table)
+            .values(OAuthUserModel::from(user))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(OAuthUserSessionStoreError::from)
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+use std::time::UNIX_EPOCH;
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::biome::oauth::store::{
+    diesel::{
+        models::{OAuthUserModel, OAuthUserSessionModel},
+        schema::{oauth_user_sessions, oauth_users},
+    },
+    OAuthUserSession, OAuthUserSessionStoreError,
+};
+use crate::error::{
+    ConstraintViolationError, ConstraintViolationType, InternalError, InvalidStateError,
+};
+
+use super::OAuthUserSessionStoreOperations;
+
+pub trait OAuthUserSessionStoreImportSession {
+    fn import_session(&self, session: OAuthUserSession) -> Result<(), OAuthUserSessionStoreError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> OAuthUserSessionStoreImportSession
+    for OAuthUserSessionStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn import_session(&self, session: OAuthUserSession) -> Result<(), OAuthUserSessionStoreError> {
+        self.conn.transaction::<_, _, _>(|| {
+            // Check if a session already exists for the Splinter access token
+            if oauth_user_sessions::table
+                .find(session.splinter_access_token())
+                .first::<OAuthUserSessionModel>(self.conn)
+                .optional()?
+                .is_some()
+            {
+                return Err(OAuthUserSessionStoreError::ConstraintViolation(
+                    ConstraintViolationError::with_violation_type(ConstraintViolationType::Unique),
+                ));
+            }
+
+            // Unlike `add_session`, the user must already exist
+            if oauth_users::table
+                .find(session.user().subject())
+                .first::<OAuthUserModel>(self.conn)
+                .optional()?
+                .is_none()
+            {
+                return Err(OAuthUserSessionStoreError::InvalidState(
+                    InvalidStateError::with_message(
+                        "An OAuth user for the session's subject does not exist".to_string(),
+                    ),
+                ));
+            }
+
+            insert_into(oauth_user_sessions::table)
+                .values(session_model(session)?)
+                .execute(self.conn)
+                .map(|_| ())
+                .map_err(OAuthUserSessionStoreError::from)
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> OAuthUserSessionStoreImportSession
+    for OAuthUserSessionStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn import_session(&self, session: OAuthUserSession) -> Result<(), OAuthUserSessionStoreError> {
+        self.conn.transaction::<_, _, _>(|| {
+            // Check if a session already exists for the Splinter access token
+            if oauth_user_sessions::table
+                .find(session.splinter_access_token())
+                .first::<OAuthUserSessionModel>(self.conn)
+                .optional()?
+                .is_some()
+            {
+                return Err(OAuthUserSessionStoreError::ConstraintViolation(
+                    ConstraintViolationError::with_violation_type(ConstraintViolationType::Unique),
+                ));
+            }
+
+            // Unlike `add_session`, the user must already exist
+            if oauth_users::table
+                .find(session.user().subject())
+                .first::<OAuthUserModel>(self.conn)
+                .optional()?
+                .is_none()
+            {
+                return Err(OAuthUserSessionStoreError::InvalidState(
+                    InvalidStateError::with_message(
+                        "An OAuth user for the session's subject does not exist".to_string(),
+                    ),
+                ));
+            }
+
+            insert_into(oauth_user_sessions::table)
+                .values(session_model(session)?)
+                .execute(self.conn)
+                .map(|_| ())
+                .map_err(OAuthUserSessionStoreError::from)
+        })
+    }
+}
+
+/// Converts the session into a model, preserving its "last authenticated" timestamp
+fn session_model(
+    session: OAuthUserSession,
+) -> Result<OAuthUserSessionModel, OAuthUserSessionStoreError> {
+    let OAuthUserSession {
+        splinter_access_token,
+        user,
+        oauth_access_token,
+        oauth_refresh_token,
+        last_authenticated,
+    } = session;
+
+    let last_authenticated = last_authenticated
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| {
+            OAuthUserSessionStoreError::Internal(InternalError::from_source_with_message(
+                Box::new(err),
+                "'last_authenticated' timestamp is earlier than the UNIX epoch".to_string(),
+            ))
+        })?
+        .as_secs();
+    let last_authenticated = i64::try_from(last_authenticated).map_err(|err| {
+        OAuthUserSessionStoreError::Internal(InternalError::from_source_with_message(
+            Box::new(err),
+            "'last_authenticated' timestamp could not be converted from u64 to i64".to_string(),
+        ))
+    })?;
+
+    Ok(OAuthUserSessionModel {
+        splinter_access_token,
+        subject: user.subject,
+        oauth_access_token,
+        oauth_refresh_token,
+        last_authenticated,
+    })
+}
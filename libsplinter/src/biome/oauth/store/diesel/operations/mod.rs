@@ -15,9 +15,15 @@
 //! Provides [OAuthUserSessionStore] operations implemented for a diesel backend
 
 pub(super) mod add_session;
+#[cfg(feature = "store-migration")]
+pub(super) mod add_user;
 pub(super) mod get_session;
 pub(super) mod get_user;
-#[cfg(feature = "oauth-user-list")]
+#[cfg(feature = "store-migration")]
+pub(super) mod import_session;
+#[cfg(feature = "store-migration")]
+pub(super) mod list_sessions;
+#[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
 pub(super) mod list_users;
 pub(super) mod remove_session;
 pub(super) mod update_session;
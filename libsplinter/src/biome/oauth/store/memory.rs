@@ -23,8 +23,10 @@ use crate::error::{
     InvalidStateError,
 };
 
-#[cfg(feature = "oauth-user-list")]
+#[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
 use super::OAuthUserIter;
+#[cfg(feature = "store-migration")]
+use super::OAuthUserSessionIter;
 use super::{
     InsertableOAuthUserSession, OAuthUser, OAuthUserSession, OAuthUserSessionStore,
     OAuthUserSessionStoreError,
@@ -188,7 +190,7 @@ impl OAuthUserSessionStore for MemoryOAuthUserSessionStore {
             .cloned())
     }
 
-    #[cfg(feature = "oauth-user-list")]
+    #[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
     fn list_users(&self) -> Result<OAuthUserIter, OAuthUserSessionStoreError> {
         let internal = self.internal.lock().map_err(|_| {
             OAuthUserSessionStoreError::Internal(InternalError::with_message(
@@ -201,6 +203,117 @@ impl OAuthUserSessionStore for MemoryOAuthUserSessionStore {
         Ok(OAuthUserIter::new(users))
     }
 
+    #[cfg(feature = "store-migration")]
+    fn add_user(&self, user: OAuthUser) -> Result<(), OAuthUserSessionStoreError> {
+        let mut internal = self.internal.lock().map_err(|_| {
+            OAuthUserSessionStoreError::Internal(InternalError::with_message(
+                "Cannot access OAuth user session store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        if internal.users.contains_key(user.subject())
+            || internal
+                .users
+                .values()
+                .any(|existing| existing.user_id() == user.user_id())
+        {
+            return Err(OAuthUserSessionStoreError::ConstraintViolation(
+                ConstraintViolationError::with_violation_type(ConstraintViolationType::Unique),
+            ));
+        }
+
+        internal.users.insert(user.subject().to_string(), user);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "store-migration")]
+    fn list_sessions(&self) -> Result<OAuthUserSessionIter, OAuthUserSessionStoreError> {
+        let internal = self.internal.lock().map_err(|_| {
+            OAuthUserSessionStoreError::Internal(InternalError::with_message(
+                "Cannot access OAuth user session store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        let sessions = internal
+            .sessions
+            .values()
+            .cloned()
+            .map(|session| {
+                let InternalOAuthUserSession {
+                    splinter_access_token,
+                    subject,
+                    oauth_access_token,
+                    oauth_refresh_token,
+                    last_authenticated,
+                } = session;
+
+                let user = internal.users.get(&subject).cloned().ok_or_else(|| {
+                    OAuthUserSessionStoreError::Internal(InternalError::with_message(
+                        "Unknown session subject".to_string(),
+                    ))
+                })?;
+
+                Ok(OAuthUserSession {
+                    splinter_access_token,
+                    user,
+                    oauth_access_token,
+                    oauth_refresh_token,
+                    last_authenticated,
+                })
+            })
+            .collect::<Result<Vec<_>, OAuthUserSessionStoreError>>()?;
+
+        Ok(OAuthUserSessionIter::new(sessions))
+    }
+
+    #[cfg(feature = "store-migration")]
+    fn import_session(&self, session: OAuthUserSession) -> Result<(), OAuthUserSessionStoreError> {
+        let mut internal = self.internal.lock().map_err(|_| {
+            OAuthUserSessionStoreError::Internal(InternalError::with_message(
+                "Cannot access OAuth user session store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        if internal
+            .sessions
+            .contains_key(session.splinter_access_token())
+        {
+            return Err(OAuthUserSessionStoreError::ConstraintViolation(
+                ConstraintViolationError::with_violation_type(ConstraintViolationType::Unique),
+            ));
+        }
+
+        if !internal.users.contains_key(session.user().subject()) {
+            return Err(OAuthUserSessionStoreError::InvalidState(
+                InvalidStateError::with_message(
+                    "An OAuth user for the session's subject does not exist".to_string(),
+                ),
+            ));
+        }
+
+        let OAuthUserSession {
+            splinter_access_token,
+            user,
+            oauth_access_token,
+            oauth_refresh_token,
+            last_authenticated,
+        } = session;
+
+        internal.sessions.insert(
+            splinter_access_token.clone(),
+            InternalOAuthUserSession {
+                splinter_access_token,
+                subject: user.subject,
+                oauth_access_token,
+                oauth_refresh_token,
+                last_authenticated,
+            },
+        );
+
+        Ok(())
+    }
+
     fn clone_box(&self) -> Box<dyn OAuthUserSessionStore> {
         Box::new(self.clone())
     }
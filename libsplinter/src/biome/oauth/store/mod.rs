@@ -390,10 +390,37 @@ pub trait OAuthUserSessionStore: Send + Sync {
     /// exists
     fn get_user(&self, subject: &str) -> Result<Option<OAuthUser>, OAuthUserSessionStoreError>;
 
-    #[cfg(feature = "oauth-user-list")]
+    #[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
     /// Returns the list of OAuth users, including the Biome user ID if it exists
     fn list_users(&self) -> Result<OAuthUserIter, OAuthUserSessionStoreError>;
 
+    #[cfg(feature = "store-migration")]
+    /// Adds an OAuth user with its existing Biome user ID
+    ///
+    /// This is used when copying users between stores, where the Biome user ID must be preserved.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConstraintViolation` error if a user with the given subject or Biome user ID
+    /// already exists.
+    fn add_user(&self, user: OAuthUser) -> Result<(), OAuthUserSessionStoreError>;
+
+    #[cfg(feature = "store-migration")]
+    /// Returns the list of all OAuth sessions
+    fn list_sessions(&self) -> Result<OAuthUserSessionIter, OAuthUserSessionStoreError>;
+
+    #[cfg(feature = "store-migration")]
+    /// Adds an existing OAuth session, preserving its "last authenticated" value
+    ///
+    /// Unlike `add_session`, this will not create a user for the session's subject.
+    ///
+    /// # Errors
+    ///
+    /// * Returns a `ConstraintViolation` error if a session with the given `splinter_access_token`
+    ///   already exists.
+    /// * Returns an `InvalidState` error if there is no user for the session's subject.
+    fn import_session(&self, session: OAuthUserSession) -> Result<(), OAuthUserSessionStoreError>;
+
     /// Clone into a boxed, dynamically dispatched store
     fn clone_box(&self) -> Box<dyn OAuthUserSessionStore>;
 }
@@ -404,13 +431,13 @@ impl Clone for Box<dyn OAuthUserSessionStore> {
     }
 }
 
-#[cfg(feature = "oauth-user-list")]
+#[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
 /// An iterator over OauthUsers, with a well-known count of values.
 pub struct OAuthUserIter {
     inner: Box<dyn ExactSizeIterator<Item = OAuthUser>>,
 }
 
-#[cfg(feature = "oauth-user-list")]
+#[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
 impl OAuthUserIter {
     pub fn new(users: Vec<OAuthUser>) -> Self {
         Self {
@@ -419,7 +446,7 @@ impl OAuthUserIter {
     }
 }
 
-#[cfg(feature = "oauth-user-list")]
+#[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
 impl Iterator for OAuthUserIter {
     type Item = OAuthUser;
 
@@ -432,9 +459,44 @@ impl Iterator for OAuthUserIter {
     }
 }
 
-#[cfg(feature = "oauth-user-list")]
+#[cfg(any(feature = "oauth-user-list", feature = "store-migration"))]
 impl ExactSizeIterator for OAuthUserIter {
     fn len(&self) -> usize {
         self.inner.len()
     }
 }
+
+#[cfg(feature = "store-migration")]
+/// An iterator over OAuthUserSessions, with a well-known count of values.
+pub struct OAuthUserSessionIter {
+    inner: Box<dyn ExactSizeIterator<Item = OAuthUserSession>>,
+}
+
+#[cfg(feature = "store-migration")]
+impl OAuthUserSessionIter {
+    pub fn new(sessions: Vec<OAuthUserSession>) -> Self {
+        Self {
+            inner: Box::new(sessions.into_iter()),
+        }
+    }
+}
+
+#[cfg(feature = "store-migration")]
+impl Iterator for OAuthUserSessionIter {
+    type Item = OAuthUserSession;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.inner.len(), Some(self.inner.len()))
+    }
+}
+
+#[cfg(feature = "store-migration")]
+impl ExactSizeIterator for OAuthUserSessionIter {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
@@ -151,6 +151,10 @@ mod tests {
 
     #[cfg(feature = "challenge-authorization")]
     use crate::circuit::routing::AuthorizationType;
+    #[cfg(feature = "circuit-durability")]
+    use crate::circuit::routing::DurabilityType;
+    #[cfg(feature = "circuit-relay")]
+    use crate::circuit::routing::RouteType;
     use crate::circuit::routing::{
         memory::RoutingTable, Circuit, CircuitNode, RoutingTableWriter, Service,
     };
@@ -369,6 +373,10 @@ mod tests {
             vec!["123".into(), "345".into()],
             #[cfg(feature = "challenge-authorization")]
             AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Any,
         );
 
         (circuit, vec![node_123, node_345])
@@ -0,0 +1,428 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Diesel-backed DurableMessageStore implementation.
+
+mod models;
+mod operations;
+mod schema;
+
+use diesel::{
+    r2d2::{ConnectionManager, Pool},
+    result,
+};
+
+use crate::error::{ConstraintViolationError, ConstraintViolationType, InternalError};
+
+use super::{
+    DurableMessage, DurableMessageStore, DurableMessageStoreError, DurablePeer, DurableQueueStatus,
+};
+
+use operations::add_message::DurableMessageStoreAddMessageOperation as _;
+use operations::get_last_delivered::DurableMessageStoreGetLastDeliveredOperation as _;
+use operations::import_peer::DurableMessageStoreImportPeerOperation as _;
+use operations::list_messages::DurableMessageStoreListMessagesOperation as _;
+use operations::list_peers::DurableMessageStoreListPeersOperation as _;
+use operations::list_queues::DurableMessageStoreListQueuesOperation as _;
+use operations::remove_messages::DurableMessageStoreRemoveMessagesOperation as _;
+use operations::set_last_delivered::DurableMessageStoreSetLastDeliveredOperation as _;
+use operations::DurableMessageOperations;
+
+/// A Diesel-backed DurableMessageStore
+pub struct DieselDurableMessageStore<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C: diesel::Connection + 'static> DieselDurableMessageStore<C> {
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        Self { connection_pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl DurableMessageStore for DieselDurableMessageStore<diesel::pg::PgConnection> {
+    fn add_message(
+        &self,
+        recipient_node_id: &str,
+        circuit_id: &str,
+        payload: Vec<u8>,
+    ) -> Result<u64, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).add_message(
+            recipient_node_id,
+            circuit_id,
+            payload,
+        )
+    }
+
+    fn list_messages(
+        &self,
+        recipient_node_id: &str,
+    ) -> Result<Vec<DurableMessage>, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).list_messages(recipient_node_id)
+    }
+
+    fn remove_messages(
+        &self,
+        recipient_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<u64, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection)
+            .remove_messages(recipient_node_id, sequence_number)
+    }
+
+    fn list_queues(&self) -> Result<Vec<DurableQueueStatus>, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).list_queues()
+    }
+
+    fn get_last_delivered(&self, sender_node_id: &str) -> Result<u64, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).get_last_delivered(sender_node_id)
+    }
+
+    fn set_last_delivered(
+        &self,
+        sender_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<(), DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection)
+            .set_last_delivered(sender_node_id, sequence_number)
+    }
+
+    fn list_peers(&self) -> Result<Vec<DurablePeer>, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).list_peers()
+    }
+
+    fn import_peer(
+        &self,
+        peer: DurablePeer,
+        messages: Vec<DurableMessage>,
+    ) -> Result<(), DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).import_peer(peer, messages)
+    }
+
+    fn clone_box(&self) -> Box<dyn DurableMessageStore> {
+        Box::new(Self {
+            connection_pool: self.connection_pool.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl DurableMessageStore for DieselDurableMessageStore<diesel::sqlite::SqliteConnection> {
+    fn add_message(
+        &self,
+        recipient_node_id: &str,
+        circuit_id: &str,
+        payload: Vec<u8>,
+    ) -> Result<u64, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).add_message(
+            recipient_node_id,
+            circuit_id,
+            payload,
+        )
+    }
+
+    fn list_messages(
+        &self,
+        recipient_node_id: &str,
+    ) -> Result<Vec<DurableMessage>, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).list_messages(recipient_node_id)
+    }
+
+    fn remove_messages(
+        &self,
+        recipient_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<u64, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection)
+            .remove_messages(recipient_node_id, sequence_number)
+    }
+
+    fn list_queues(&self) -> Result<Vec<DurableQueueStatus>, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).list_queues()
+    }
+
+    fn get_last_delivered(&self, sender_node_id: &str) -> Result<u64, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).get_last_delivered(sender_node_id)
+    }
+
+    fn set_last_delivered(
+        &self,
+        sender_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<(), DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection)
+            .set_last_delivered(sender_node_id, sequence_number)
+    }
+
+    fn list_peers(&self) -> Result<Vec<DurablePeer>, DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).list_peers()
+    }
+
+    fn import_peer(
+        &self,
+        peer: DurablePeer,
+        messages: Vec<DurableMessage>,
+    ) -> Result<(), DurableMessageStoreError> {
+        let connection = self.connection_pool.get()?;
+        DurableMessageOperations::new(&*connection).import_peer(peer, messages)
+    }
+
+    fn clone_box(&self) -> Box<dyn DurableMessageStore> {
+        Box::new(Self {
+            connection_pool: self.connection_pool.clone(),
+        })
+    }
+}
+
+impl From<diesel::r2d2::PoolError> for DurableMessageStoreError {
+    fn from(err: diesel::r2d2::PoolError) -> Self {
+        DurableMessageStoreError::InternalError(InternalError::from_source(Box::new(err)))
+    }
+}
+
+impl From<result::Error> for DurableMessageStoreError {
+    fn from(err: result::Error) -> Self {
+        match err {
+            result::Error::DatabaseError(result::DatabaseErrorKind::UniqueViolation, _) => {
+                DurableMessageStoreError::ConstraintViolation(
+                    ConstraintViolationError::from_source_with_violation_type(
+                        ConstraintViolationType::Unique,
+                        Box::new(err),
+                    ),
+                )
+            }
+            _ => DurableMessageStoreError::InternalError(InternalError::from_source(Box::new(err))),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+pub mod tests {
+    use super::*;
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    use crate::migrations::run_sqlite_migrations;
+
+    /// Verify that messages are assigned increasing sequence numbers per recipient node and are
+    /// listed in order.
+    ///
+    /// 1. Add two messages for node-1 and one message for node-2
+    /// 2. Verify the sequence numbers start at 1 for each node
+    /// 3. Verify the messages for node-1 are listed in sequence order
+    #[test]
+    fn sqlite_add_and_list_messages() {
+        let store = DieselDurableMessageStore::new(create_connection_pool_and_migrate());
+
+        assert_eq!(
+            store
+                .add_message("node-1", "abcde-01234", b"first".to_vec())
+                .expect("Unable to add message"),
+            1
+        );
+        assert_eq!(
+            store
+                .add_message("node-1", "abcde-01234", b"second".to_vec())
+                .expect("Unable to add message"),
+            2
+        );
+        assert_eq!(
+            store
+                .add_message("node-2", "abcde-01234", b"other".to_vec())
+                .expect("Unable to add message"),
+            1
+        );
+
+        let messages = store
+            .list_messages("node-1")
+            .expect("Unable to list messages");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].sequence_number(), 1);
+        assert_eq!(messages[0].circuit_id(), "abcde-01234");
+        assert_eq!(messages[0].payload(), b"first");
+        assert_eq!(messages[1].sequence_number(), 2);
+        assert_eq!(messages[1].payload(), b"second");
+    }
+
+    /// Verify that acknowledged messages are removed from the queue, that the queue status
+    /// reflects the remaining messages, and that sequence numbers are not reused.
+    #[test]
+    fn sqlite_remove_messages_and_list_queues() {
+        let store = DieselDurableMessageStore::new(create_connection_pool_and_migrate());
+
+        for _ in 0..3 {
+            store
+                .add_message("node-1", "abcde-01234", b"payload".to_vec())
+                .expect("Unable to add message");
+        }
+
+        assert_eq!(
+            store
+                .remove_messages("node-1", 2)
+                .expect("Unable to remove messages"),
+            2
+        );
+
+        assert_eq!(
+            store.list_queues().expect("Unable to list queues"),
+            vec![DurableQueueStatus {
+                node_id: "node-1".into(),
+                depth: 1,
+                oldest_sequence_number: Some(3),
+                last_sent_sequence_number: 3,
+            }]
+        );
+
+        store
+            .remove_messages("node-1", 3)
+            .expect("Unable to remove messages");
+        assert_eq!(
+            store
+                .add_message("node-1", "abcde-01234", b"payload".to_vec())
+                .expect("Unable to add message"),
+            4
+        );
+    }
+
+    /// Verify that the last delivered sequence number defaults to 0 and can be updated.
+    #[test]
+    fn sqlite_last_delivered() {
+        let store = DieselDurableMessageStore::new(create_connection_pool_and_migrate());
+
+        assert_eq!(
+            store
+                .get_last_delivered("node-1")
+                .expect("Unable to get last delivered"),
+            0
+        );
+
+        store
+            .set_last_delivered("node-1", 1)
+            .expect("Unable to set last delivered");
+        store
+            .set_last_delivered("node-1", 2)
+            .expect("Unable to set last delivered");
+
+        assert_eq!(
+            store
+                .get_last_delivered("node-1")
+                .expect("Unable to get last delivered"),
+            2
+        );
+        // Nodes that have only sent messages do not have a queue
+        assert!(store
+            .list_queues()
+            .expect("Unable to list queues")
+            .is_empty());
+    }
+
+    /// Verify that a peer listed from one store can be imported into another, keeping its
+    /// sequence numbers and unacknowledged messages.
+    ///
+    /// 1. Add three messages for node-1, acknowledge the first, and set the last delivered
+    ///    sequence number for node-2
+    /// 2. Import every listed peer into a second store
+    /// 3. Verify the peers, messages and next sequence number in the second store
+    /// 4. Verify that importing an existing peer fails
+    #[test]
+    fn sqlite_list_and_import_peers() {
+        let from = DieselDurableMessageStore::new(create_connection_pool_and_migrate());
+        let to = DieselDurableMessageStore::new(create_connection_pool_and_migrate());
+
+        for _ in 0..3 {
+            from.add_message("node-1", "abcde-01234", b"payload".to_vec())
+                .expect("Unable to add message");
+        }
+        from.remove_messages("node-1", 1)
+            .expect("Unable to remove messages");
+        from.set_last_delivered("node-2", 5)
+            .expect("Unable to set last delivered");
+
+        let peers = from.list_peers().expect("Unable to list peers");
+        assert_eq!(
+            peers,
+            vec![
+                DurablePeer {
+                    node_id: "node-1".into(),
+                    last_sent_sequence_number: 3,
+                    last_delivered_sequence_number: 0,
+                },
+                DurablePeer {
+                    node_id: "node-2".into(),
+                    last_sent_sequence_number: 0,
+                    last_delivered_sequence_number: 5,
+                },
+            ]
+        );
+
+        for peer in peers.iter() {
+            let messages = from
+                .list_messages(peer.node_id())
+                .expect("Unable to list messages");
+            to.import_peer(peer.clone(), messages)
+                .expect("Unable to import peer");
+        }
+
+        assert_eq!(to.list_peers().expect("Unable to list peers"), peers);
+        assert_eq!(
+            to.list_messages("node-1").expect("Unable to list messages"),
+            from.list_messages("node-1")
+                .expect("Unable to list messages")
+        );
+        assert_eq!(
+            to.add_message("node-1", "abcde-01234", b"payload".to_vec())
+                .expect("Unable to add message"),
+            4
+        );
+
+        assert!(matches!(
+            to.import_peer(peers[1].clone(), vec![]),
+            Err(DurableMessageStoreError::ConstraintViolation(_))
+        ));
+    }
+
+    /// Creates a connection pool for an in-memory SQLite database with only a single connection
+    /// available. Each connection is backed by a different in-memory SQLite database, so limiting
+    /// the pool to a single connection insures that the same DB is used for all operations.
+    fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+}
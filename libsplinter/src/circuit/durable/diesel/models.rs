@@ -0,0 +1,33 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::schema::{circuit_durable_message, circuit_durable_peer};
+
+#[derive(Insertable, Queryable, Identifiable, AsChangeset, PartialEq, Debug)]
+#[table_name = "circuit_durable_peer"]
+#[primary_key(node_id)]
+pub struct CircuitDurablePeerModel {
+    pub node_id: String,
+    pub last_sent: i64,
+    pub last_delivered: i64,
+}
+
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "circuit_durable_message"]
+pub struct CircuitDurableMessageModel {
+    pub recipient_node_id: String,
+    pub sequence_number: i64,
+    pub circuit_id: String,
+    pub payload: Vec<u8>,
+}
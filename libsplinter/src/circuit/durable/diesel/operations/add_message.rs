@@ -0,0 +1,136 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "add message" operation for the `DieselDurableMessageStore`.
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::circuit::durable::{
+    diesel::{
+        models::{CircuitDurableMessageModel, CircuitDurablePeerModel},
+        schema::{circuit_durable_message, circuit_durable_peer},
+    },
+    DurableMessageStoreError,
+};
+
+use super::{from_db_sequence_number, DurableMessageOperations};
+
+pub(in crate::circuit::durable::diesel) trait DurableMessageStoreAddMessageOperation {
+    fn add_message(
+        &self,
+        recipient_node_id: &str,
+        circuit_id: &str,
+        payload: Vec<u8>,
+    ) -> Result<u64, DurableMessageStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> DurableMessageStoreAddMessageOperation
+    for DurableMessageOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_message(
+        &self,
+        recipient_node_id: &str,
+        circuit_id: &str,
+        payload: Vec<u8>,
+    ) -> Result<u64, DurableMessageStoreError> {
+        self.conn.transaction::<_, _, _>(|| {
+            let peer = circuit_durable_peer::table
+                .find(recipient_node_id)
+                .first::<CircuitDurablePeerModel>(self.conn)
+                .optional()?;
+
+            let sequence_number = match peer {
+                Some(mut peer) => {
+                    peer.last_sent += 1;
+                    diesel::update(circuit_durable_peer::table.find(recipient_node_id))
+                        .set(circuit_durable_peer::last_sent.eq(peer.last_sent))
+                        .execute(self.conn)?;
+                    peer.last_sent
+                }
+                None => {
+                    insert_into(circuit_durable_peer::table)
+                        .values(CircuitDurablePeerModel {
+                            node_id: recipient_node_id.to_string(),
+                            last_sent: 1,
+                            last_delivered: 0,
+                        })
+                        .execute(self.conn)?;
+                    1
+                }
+            };
+
+            insert_into(circuit_durable_message::table)
+                .values(CircuitDurableMessageModel {
+                    recipient_node_id: recipient_node_id.to_string(),
+                    sequence_number,
+                    circuit_id: circuit_id.to_string(),
+                    payload,
+                })
+                .execute(self.conn)?;
+
+            from_db_sequence_number(sequence_number)
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> DurableMessageStoreAddMessageOperation
+    for DurableMessageOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_message(
+        &self,
+        recipient_node_id: &str,
+        circuit_id: &str,
+        payload: Vec<u8>,
+    ) -> Result<u64, DurableMessageStoreError> {
+        self.conn.transaction::<_, _, _>(|| {
+            let peer = circuit_durable_peer::table
+                .find(recipient_node_id)
+                .first::<CircuitDurablePeerModel>(self.conn)
+                .optional()?;
+
+            let sequence_number = match peer {
+                Some(mut peer) => {
+                    peer.last_sent += 1;
+                    diesel::update(circuit_durable_peer::table.find(recipient_node_id))
+                        .set(circuit_durable_peer::last_sent.eq(peer.last_sent))
+                        .execute(self.conn)?;
+                    peer.last_sent
+                }
+                None => {
+                    insert_into(circuit_durable_peer::table)
+                        .values(CircuitDurablePeerModel {
+                            node_id: recipient_node_id.to_string(),
+                            last_sent: 1,
+                            last_delivered: 0,
+                        })
+                        .execute(self.conn)?;
+                    1
+                }
+            };
+
+            insert_into(circuit_durable_message::table)
+                .values(CircuitDurableMessageModel {
+                    recipient_node_id: recipient_node_id.to_string(),
+                    sequence_number,
+                    circuit_id: circuit_id.to_string(),
+                    payload,
+                })
+                .execute(self.conn)?;
+
+            from_db_sequence_number(sequence_number)
+        })
+    }
+}
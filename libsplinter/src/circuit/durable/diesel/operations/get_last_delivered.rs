@@ -0,0 +1,55 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "get last delivered" operation for the `DieselDurableMessageStore`.
+
+use diesel::prelude::*;
+
+use crate::circuit::durable::{diesel::schema::circuit_durable_peer, DurableMessageStoreError};
+
+use super::{from_db_sequence_number, DurableMessageOperations};
+
+pub(in crate::circuit::durable::diesel) trait DurableMessageStoreGetLastDeliveredOperation {
+    fn get_last_delivered(&self, sender_node_id: &str) -> Result<u64, DurableMessageStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> DurableMessageStoreGetLastDeliveredOperation
+    for DurableMessageOperations<'a, diesel::pg::PgConnection>
+{
+    fn get_last_delivered(&self, sender_node_id: &str) -> Result<u64, DurableMessageStoreError> {
+        circuit_durable_peer::table
+            .find(sender_node_id)
+            .select(circuit_durable_peer::last_delivered)
+            .first::<i64>(self.conn)
+            .optional()?
+            .map(from_db_sequence_number)
+            .unwrap_or(Ok(0))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> DurableMessageStoreGetLastDeliveredOperation
+    for DurableMessageOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn get_last_delivered(&self, sender_node_id: &str) -> Result<u64, DurableMessageStoreError> {
+        circuit_durable_peer::table
+            .find(sender_node_id)
+            .select(circuit_durable_peer::last_delivered)
+            .first::<i64>(self.conn)
+            .optional()?
+            .map(from_db_sequence_number)
+            .unwrap_or(Ok(0))
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "import peer" operation for the `DieselDurableMessageStore`.
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::circuit::durable::{
+    diesel::{
+        models::{CircuitDurableMessageModel, CircuitDurablePeerModel},
+        schema::{circuit_durable_message, circuit_durable_peer},
+    },
+    DurableMessage, DurableMessageStoreError, DurablePeer,
+};
+use crate::error::InternalError;
+
+use super::{to_db_sequence_number, DurableMessageOperations};
+
+pub(in crate::circuit::durable::diesel) trait DurableMessageStoreImportPeerOperation {
+    fn import_peer(
+        &self,
+        peer: DurablePeer,
+        messages: Vec<DurableMessage>,
+    ) -> Result<(), DurableMessageStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> DurableMessageStoreImportPeerOperation
+    for DurableMessageOperations<'a, diesel::pg::PgConnection>
+{
+    fn import_peer(
+        &self,
+        peer: DurablePeer,
+        messages: Vec<DurableMessage>,
+    ) -> Result<(), DurableMessageStoreError> {
+        let models = to_message_models(&peer, messages)?;
+        let peer = CircuitDurablePeerModel {
+            last_sent: to_db_sequence_number(peer.last_sent_sequence_number)?,
+            last_delivered: to_db_sequence_number(peer.last_delivered_sequence_number)?,
+            node_id: peer.node_id,
+        };
+
+        self.conn.transaction::<_, _, _>(|| {
+            insert_into(circuit_durable_peer::table)
+                .values(peer)
+                .execute(self.conn)?;
+
+            for model in models {
+                insert_into(circuit_durable_message::table)
+                    .values(model)
+                    .execute(self.conn)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> DurableMessageStoreImportPeerOperation
+    for DurableMessageOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn import_peer(
+        &self,
+        peer: DurablePeer,
+        messages: Vec<DurableMessage>,
+    ) -> Result<(), DurableMessageStoreError> {
+        let models = to_message_models(&peer, messages)?;
+        let peer = CircuitDurablePeerModel {
+            last_sent: to_db_sequence_number(peer.last_sent_sequence_number)?,
+            last_delivered: to_db_sequence_number(peer.last_delivered_sequence_number)?,
+            node_id: peer.node_id,
+        };
+
+        self.conn.transaction::<_, _, _>(|| {
+            insert_into(circuit_durable_peer::table)
+                .values(peer)
+                .execute(self.conn)?;
+
+            for model in models {
+                insert_into(circuit_durable_message::table)
+                    .values(model)
+                    .execute(self.conn)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Converts the messages of an imported peer, checking that each message is addressed to the
+/// peer and was queued before its last sent sequence number.
+fn to_message_models(
+    peer: &DurablePeer,
+    messages: Vec<DurableMessage>,
+) -> Result<Vec<CircuitDurableMessageModel>, DurableMessageStoreError> {
+    messages
+        .into_iter()
+        .map(|message| {
+            if message.recipient_node_id != peer.node_id
+                || message.sequence_number > peer.last_sent_sequence_number
+            {
+                return Err(DurableMessageStoreError::InternalError(
+                    InternalError::with_message(format!(
+                        "Message {} for {} does not belong to the queue of peer {}",
+                        message.sequence_number, message.recipient_node_id, peer.node_id
+                    )),
+                ));
+            }
+
+            Ok(CircuitDurableMessageModel {
+                sequence_number: to_db_sequence_number(message.sequence_number)?,
+                recipient_node_id: message.recipient_node_id,
+                circuit_id: message.circuit_id,
+                payload: message.payload,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,81 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "list messages" operation for the `DieselDurableMessageStore`.
+
+use diesel::prelude::*;
+
+use crate::circuit::durable::{
+    diesel::{models::CircuitDurableMessageModel, schema::circuit_durable_message},
+    DurableMessage, DurableMessageStoreError,
+};
+
+use super::{from_db_sequence_number, DurableMessageOperations};
+
+pub(in crate::circuit::durable::diesel) trait DurableMessageStoreListMessagesOperation {
+    fn list_messages(
+        &self,
+        recipient_node_id: &str,
+    ) -> Result<Vec<DurableMessage>, DurableMessageStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> DurableMessageStoreListMessagesOperation
+    for DurableMessageOperations<'a, diesel::pg::PgConnection>
+{
+    fn list_messages(
+        &self,
+        recipient_node_id: &str,
+    ) -> Result<Vec<DurableMessage>, DurableMessageStoreError> {
+        circuit_durable_message::table
+            .filter(circuit_durable_message::recipient_node_id.eq(recipient_node_id))
+            .order(circuit_durable_message::sequence_number.asc())
+            .load::<CircuitDurableMessageModel>(self.conn)?
+            .into_iter()
+            .map(|model| {
+                Ok(DurableMessage {
+                    recipient_node_id: model.recipient_node_id,
+                    sequence_number: from_db_sequence_number(model.sequence_number)?,
+                    circuit_id: model.circuit_id,
+                    payload: model.payload,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> DurableMessageStoreListMessagesOperation
+    for DurableMessageOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_messages(
+        &self,
+        recipient_node_id: &str,
+    ) -> Result<Vec<DurableMessage>, DurableMessageStoreError> {
+        circuit_durable_message::table
+            .filter(circuit_durable_message::recipient_node_id.eq(recipient_node_id))
+            .order(circuit_durable_message::sequence_number.asc())
+            .load::<CircuitDurableMessageModel>(self.conn)?
+            .into_iter()
+            .map(|model| {
+                Ok(DurableMessage {
+                    recipient_node_id: model.recipient_node_id,
+                    sequence_number: from_db_sequence_number(model.sequence_number)?,
+                    circuit_id: model.circuit_id,
+                    payload: model.payload,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "list peers" operation for the `DieselDurableMessageStore`.
+
+use diesel::prelude::*;
+
+use crate::circuit::durable::{
+    diesel::{models::CircuitDurablePeerModel, schema::circuit_durable_peer},
+    DurableMessageStoreError, DurablePeer,
+};
+
+use super::{from_db_sequence_number, DurableMessageOperations};
+
+pub(in crate::circuit::durable::diesel) trait DurableMessageStoreListPeersOperation {
+    fn list_peers(&self) -> Result<Vec<DurablePeer>, DurableMessageStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> DurableMessageStoreListPeersOperation
+    for DurableMessageOperations<'a, diesel::pg::PgConnection>
+{
+    fn list_peers(&self) -> Result<Vec<DurablePeer>, DurableMessageStoreError> {
+        circuit_durable_peer::table
+            .order(circuit_durable_peer::node_id.asc())
+            .load::<CircuitDurablePeerModel>(self.conn)?
+            .into_iter()
+            .map(|peer| {
+                Ok(DurablePeer {
+                    node_id: peer.node_id,
+                    last_sent_sequence_number: from_db_sequence_number(peer.last_sent)?,
+                    last_delivered_sequence_number: from_db_sequence_number(peer.last_delivered)?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> DurableMessageStoreListPeersOperation
+    for DurableMessageOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_peers(&self) -> Result<Vec<DurablePeer>, DurableMessageStoreError> {
+        circuit_durable_peer::table
+            .order(circuit_durable_peer::node_id.asc())
+            .load::<CircuitDurablePeerModel>(self.conn)?
+            .into_iter()
+            .map(|peer| {
+                Ok(DurablePeer {
+                    node_id: peer.node_id,
+                    last_sent_sequence_number: from_db_sequence_number(peer.last_sent)?,
+                    last_delivered_sequence_number: from_db_sequence_number(peer.last_delivered)?,
+                })
+            })
+            .collect()
+    }
+}
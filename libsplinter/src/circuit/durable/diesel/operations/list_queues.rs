@@ -0,0 +1,125 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "list queues" operation for the `DieselDurableMessageStore`.
+
+use std::collections::BTreeMap;
+
+use diesel::prelude::*;
+
+use crate::circuit::durable::{
+    diesel::{
+        models::CircuitDurablePeerModel,
+        schema::{circuit_durable_message, circuit_durable_peer},
+    },
+    DurableMessageStoreError, DurableQueueStatus,
+};
+
+use super::{from_db_sequence_number, DurableMessageOperations};
+
+pub(in crate::circuit::durable::diesel) trait DurableMessageStoreListQueuesOperation {
+    fn list_queues(&self) -> Result<Vec<DurableQueueStatus>, DurableMessageStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> DurableMessageStoreListQueuesOperation
+    for DurableMessageOperations<'a, diesel::pg::PgConnection>
+{
+    fn list_queues(&self) -> Result<Vec<DurableQueueStatus>, DurableMessageStoreError> {
+        self.conn.transaction::<_, _, _>(|| {
+            let peers = circuit_durable_peer::table
+                .filter(circuit_durable_peer::last_sent.gt(0))
+                .order(circuit_durable_peer::node_id.asc())
+                .load::<CircuitDurablePeerModel>(self.conn)?;
+
+            // Count the queued messages and find the oldest one for each node
+            let mut queued: BTreeMap<String, (u64, i64)> = BTreeMap::new();
+            for (node_id, sequence_number) in circuit_durable_message::table
+                .select((
+                    circuit_durable_message::recipient_node_id,
+                    circuit_durable_message::sequence_number,
+                ))
+                .load::<(String, i64)>(self.conn)?
+            {
+                let entry = queued.entry(node_id).or_insert((0, sequence_number));
+                entry.0 += 1;
+                if sequence_number < entry.1 {
+                    entry.1 = sequence_number;
+                }
+            }
+
+            peers
+                .into_iter()
+                .map(|peer| {
+                    let (depth, oldest_sequence_number) = match queued.get(&peer.node_id) {
+                        Some((depth, oldest)) => (*depth, Some(from_db_sequence_number(*oldest)?)),
+                        None => (0, None),
+                    };
+                    Ok(DurableQueueStatus {
+                        node_id: peer.node_id,
+                        depth,
+                        oldest_sequence_number,
+                        last_sent_sequence_number: from_db_sequence_number(peer.last_sent)?,
+                    })
+                })
+                .collect()
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> DurableMessageStoreListQueuesOperation
+    for DurableMessageOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_queues(&self) -> Result<Vec<DurableQueueStatus>, DurableMessageStoreError> {
+        self.conn.transaction::<_, _, _>(|| {
+            let peers = circuit_durable_peer::table
+                .filter(circuit_durable_peer::last_sent.gt(0))
+                .order(circuit_durable_peer::node_id.asc())
+                .load::<CircuitDurablePeerModel>(self.conn)?;
+
+            // Count the queued messages and find the oldest one for each node
+            let mut queued: BTreeMap<String, (u64, i64)> = BTreeMap::new();
+            for (node_id, sequence_number) in circuit_durable_message::table
+                .select((
+                    circuit_durable_message::recipient_node_id,
+                    circuit_durable_message::sequence_number,
+                ))
+                .load::<(String, i64)>(self.conn)?
+            {
+                let entry = queued.entry(node_id).or_insert((0, sequence_number));
+                entry.0 += 1;
+                if sequence_number < entry.1 {
+                    entry.1 = sequence_number;
+                }
+            }
+
+            peers
+                .into_iter()
+                .map(|peer| {
+                    let (depth, oldest_sequence_number) = match queued.get(&peer.node_id) {
+                        Some((depth, oldest)) => (*depth, Some(from_db_sequence_number(*oldest)?)),
+                        None => (0, None),
+                    };
+                    Ok(DurableQueueStatus {
+                        node_id: peer.node_id,
+                        depth,
+                        oldest_sequence_number,
+                        last_sent_sequence_number: from_db_sequence_number(peer.last_sent)?,
+                    })
+                })
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides DurableMessageOperations implemented for a diesel backend
+
+pub(super) mod add_message;
+pub(super) mod get_last_delivered;
+pub(super) mod import_peer;
+pub(super) mod list_messages;
+pub(super) mod list_peers;
+pub(super) mod list_queues;
+pub(super) mod remove_messages;
+pub(super) mod set_last_delivered;
+
+use std::convert::TryFrom;
+
+use crate::circuit::durable::DurableMessageStoreError;
+use crate::error::InternalError;
+
+pub(super) struct DurableMessageOperations<'a, C> {
+    conn: &'a C,
+}
+
+impl<'a, C> DurableMessageOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    pub fn new(conn: &'a C) -> Self {
+        DurableMessageOperations { conn }
+    }
+}
+
+/// Converts a sequence number to the signed integer type used by the database.
+fn to_db_sequence_number(sequence_number: u64) -> Result<i64, DurableMessageStoreError> {
+    i64::try_from(sequence_number).map_err(|_| {
+        DurableMessageStoreError::InternalError(InternalError::with_message(format!(
+            "Sequence number {} is too large to be stored",
+            sequence_number
+        )))
+    })
+}
+
+/// Converts a sequence number read from the database.
+fn from_db_sequence_number(sequence_number: i64) -> Result<u64, DurableMessageStoreError> {
+    u64::try_from(sequence_number).map_err(|_| {
+        DurableMessageStoreError::InternalError(InternalError::with_message(format!(
+            "Invalid sequence number {} in the database",
+            sequence_number
+        )))
+    })
+}
@@ -0,0 +1,71 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "remove messages" operation for the `DieselDurableMessageStore`.
+
+use diesel::{dsl::delete, prelude::*};
+
+use crate::circuit::durable::{diesel::schema::circuit_durable_message, DurableMessageStoreError};
+
+use super::{to_db_sequence_number, DurableMessageOperations};
+
+pub(in crate::circuit::durable::diesel) trait DurableMessageStoreRemoveMessagesOperation {
+    fn remove_messages(
+        &self,
+        recipient_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<u64, DurableMessageStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> DurableMessageStoreRemoveMessagesOperation
+    for DurableMessageOperations<'a, diesel::pg::PgConnection>
+{
+    fn remove_messages(
+        &self,
+        recipient_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<u64, DurableMessageStoreError> {
+        let sequence_number = to_db_sequence_number(sequence_number)?;
+        delete(
+            circuit_durable_message::table
+                .filter(circuit_durable_message::recipient_node_id.eq(recipient_node_id))
+                .filter(circuit_durable_message::sequence_number.le(sequence_number)),
+        )
+        .execute(self.conn)
+        .map(|count| count as u64)
+        .map_err(DurableMessageStoreError::from)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> DurableMessageStoreRemoveMessagesOperation
+    for DurableMessageOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn remove_messages(
+        &self,
+        recipient_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<u64, DurableMessageStoreError> {
+        let sequence_number = to_db_sequence_number(sequence_number)?;
+        delete(
+            circuit_durable_message::table
+                .filter(circuit_durable_message::recipient_node_id.eq(recipient_node_id))
+                .filter(circuit_durable_message::sequence_number.le(sequence_number)),
+        )
+        .execute(self.conn)
+        .map(|count| count as u64)
+        .map_err(DurableMessageStoreError::from)
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "set last delivered" operation for the `DieselDurableMessageStore`.
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::circuit::durable::{
+    diesel::{models::CircuitDurablePeerModel, schema::circuit_durable_peer},
+    DurableMessageStoreError,
+};
+
+use super::{to_db_sequence_number, DurableMessageOperations};
+
+pub(in crate::circuit::durable::diesel) trait DurableMessageStoreSetLastDeliveredOperation {
+    fn set_last_delivered(
+        &self,
+        sender_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<(), DurableMessageStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> DurableMessageStoreSetLastDeliveredOperation
+    for DurableMessageOperations<'a, diesel::pg::PgConnection>
+{
+    fn set_last_delivered(
+        &self,
+        sender_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<(), DurableMessageStoreError> {
+        let sequence_number = to_db_sequence_number(sequence_number)?;
+        self.conn.transaction::<_, _, _>(|| {
+            let updated = diesel::update(circuit_durable_peer::table.find(sender_node_id))
+                .set(circuit_durable_peer::last_delivered.eq(sequence_number))
+                .execute(self.conn)?;
+
+            if updated == 0 {
+                insert_into(circuit_durable_peer::table)
+                    .values(CircuitDurablePeerModel {
+                        node_id: sender_node_id.to_string(),
+                        last_sent: 0,
+                        last_delivered: sequence_number,
+                    })
+                    .execute(self.conn)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> DurableMessageStoreSetLastDeliveredOperation
+    for DurableMessageOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn set_last_delivered(
+        &self,
+        sender_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<(), DurableMessageStoreError> {
+        let sequence_number = to_db_sequence_number(sequence_number)?;
+        self.conn.transaction::<_, _, _>(|| {
+            let updated = diesel::update(circuit_durable_peer::table.find(sender_node_id))
+                .set(circuit_durable_peer::last_delivered.eq(sequence_number))
+                .execute(self.conn)?;
+
+            if updated == 0 {
+                insert_into(circuit_durable_peer::table)
+                    .values(CircuitDurablePeerModel {
+                        node_id: sender_node_id.to_string(),
+                        last_sent: 0,
+                        last_delivered: sequence_number,
+                    })
+                    .execute(self.conn)?;
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+table! {
+    circuit_durable_peer (node_id) {
+        node_id -> Text,
+        last_sent -> Int8,
+        last_delivered -> Int8,
+    }
+}
+
+table! {
+    circuit_durable_message (recipient_node_id, sequence_number) {
+        recipient_node_id -> Text,
+        sequence_number -> Int8,
+        circuit_id -> Text,
+        payload -> Binary,
+    }
+}
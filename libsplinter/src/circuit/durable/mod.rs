@@ -0,0 +1,250 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Store-and-forward delivery of circuit direct messages.
+//!
+//! Circuits with the `StoreAndForward` durability type persist the direct messages that are
+//! sent to services on other nodes until the recipient's node acknowledges them. Each message is
+//! assigned a sequence number that is unique for the recipient node, which is used by the
+//! recipient to deliver the messages in order and to discard duplicates.
+//!
+//! The [`DurableMessageStore`] tracks both sides of the exchange with a peer node: the messages
+//! that have been sent to it but not yet acknowledged, and the last sequence number that was
+//! delivered from it.
+
+#[cfg(feature = "diesel")]
+mod diesel;
+mod error;
+mod replay;
+
+#[cfg(feature = "diesel")]
+pub use self::diesel::DieselDurableMessageStore;
+pub use error::DurableMessageStoreError;
+pub use replay::DurableMessageReplayer;
+
+use protobuf::Message;
+
+use crate::circuit::handlers::create_message;
+use crate::circuit::routing::{AuthorizationType, CircuitNode, RoutingTableReader};
+use crate::error::InternalError;
+use crate::peer::PeerAuthorizationToken;
+use crate::protos::circuit::{CircuitDurableMessage, CircuitMessageType};
+
+/// A direct message that is waiting to be acknowledged by the recipient's node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DurableMessage {
+    recipient_node_id: String,
+    sequence_number: u64,
+    circuit_id: String,
+    payload: Vec<u8>,
+}
+
+impl DurableMessage {
+    /// Returns the ID of the node the message is being sent to
+    pub fn recipient_node_id(&self) -> &str {
+        &self.recipient_node_id
+    }
+
+    /// Returns the sequence number of the message for the recipient node
+    pub fn sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+
+    /// Returns the ID of the circuit the message was sent on
+    pub fn circuit_id(&self) -> &str {
+        &self.circuit_id
+    }
+
+    /// Returns the bytes of the `CircuitDirectMessage`
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+}
+
+/// The status of the queue of unacknowledged messages for a peer node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DurableQueueStatus {
+    node_id: String,
+    depth: u64,
+    oldest_sequence_number: Option<u64>,
+    last_sent_sequence_number: u64,
+}
+
+impl DurableQueueStatus {
+    /// Returns the ID of the node the messages are being sent to
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Returns the number of messages that have not been acknowledged
+    pub fn depth(&self) -> u64 {
+        self.depth
+    }
+
+    /// Returns the sequence number of the oldest unacknowledged message, if there is one
+    pub fn oldest_sequence_number(&self) -> Option<u64> {
+        self.oldest_sequence_number
+    }
+
+    /// Returns the sequence number of the last message that was queued for the node
+    pub fn last_sent_sequence_number(&self) -> u64 {
+        self.last_sent_sequence_number
+    }
+}
+
+/// The sequence numbers tracked for a node that messages have been sent to or delivered from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DurablePeer {
+    node_id: String,
+    last_sent_sequence_number: u64,
+    last_delivered_sequence_number: u64,
+}
+
+impl DurablePeer {
+    /// Returns the ID of the node
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Returns the sequence number of the last message that was queued for the node
+    pub fn last_sent_sequence_number(&self) -> u64 {
+        self.last_sent_sequence_number
+    }
+
+    /// Returns the sequence number of the last message that was delivered from the node
+    pub fn last_delivered_sequence_number(&self) -> u64 {
+        self.last_delivered_sequence_number
+    }
+}
+
+/// Persists the direct messages exchanged with peer nodes on store-and-forward circuits.
+pub trait DurableMessageStore: Send + Sync {
+    /// Adds a message to the queue for the given node, returning the sequence number that was
+    /// assigned to it. Sequence numbers start at 1 and increase by 1 for every message sent to the
+    /// node.
+    fn add_message(
+        &self,
+        recipient_node_id: &str,
+        circuit_id: &str,
+        payload: Vec<u8>,
+    ) -> Result<u64, DurableMessageStoreError>;
+
+    /// Lists the unacknowledged messages for the given node, in sequence number order.
+    fn list_messages(
+        &self,
+        recipient_node_id: &str,
+    ) -> Result<Vec<DurableMessage>, DurableMessageStoreError>;
+
+    /// Removes the messages for the given node with a sequence number less than or equal to the
+    /// given sequence number, returning the number of messages that were removed.
+    fn remove_messages(
+        &self,
+        recipient_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<u64, DurableMessageStoreError>;
+
+    /// Lists the status of the queue for every node that messages have been sent to.
+    fn list_queues(&self) -> Result<Vec<DurableQueueStatus>, DurableMessageStoreError>;
+
+    /// Returns the sequence number of the last message that was delivered from the given node, or
+    /// 0 if no messages have been delivered.
+    fn get_last_delivered(&self, sender_node_id: &str) -> Result<u64, DurableMessageStoreError>;
+
+    /// Sets the sequence number of the last message that was delivered from the given node.
+    fn set_last_delivered(
+        &self,
+        sender_node_id: &str,
+        sequence_number: u64,
+    ) -> Result<(), DurableMessageStoreError>;
+
+    /// Lists the sequence numbers of every node that messages have been sent to or delivered
+    /// from.
+    fn list_peers(&self) -> Result<Vec<DurablePeer>, DurableMessageStoreError>;
+
+    /// Adds a peer copied from another store, along with its unacknowledged messages, keeping
+    /// their sequence numbers. The peer and its messages are added in a single transaction.
+    ///
+    /// Returns a `ConstraintViolation` error if the peer already exists.
+    fn import_peer(
+        &self,
+        peer: DurablePeer,
+        messages: Vec<DurableMessage>,
+    ) -> Result<(), DurableMessageStoreError>;
+
+    /// Clone the store for dynamic dispatch.
+    fn clone_box(&self) -> Box<dyn DurableMessageStore>;
+}
+
+impl Clone for Box<dyn DurableMessageStore> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Creates the network message that carries a queued message to the recipient's node.
+///
+/// # Arguments
+///
+/// * `sender_node_id` - The ID of the local node
+/// * `receiver_node_id` - The ID of the node the message is queued for
+/// * `sequence_number` - The sequence number assigned to the message by the `DurableMessageStore`
+/// * `payload` - The bytes of the `CircuitDirectMessage`
+pub(crate) fn create_durable_message(
+    sender_node_id: &str,
+    receiver_node_id: &str,
+    sequence_number: u64,
+    payload: &[u8],
+) -> Result<Vec<u8>, protobuf::error::ProtobufError> {
+    let mut durable_message = CircuitDurableMessage::new();
+    durable_message.set_sender_node_id(sender_node_id.to_string());
+    durable_message.set_receiver_node_id(receiver_node_id.to_string());
+    durable_message.set_sequence_number(sequence_number);
+    durable_message.set_payload(payload.to_vec());
+
+    create_message(
+        durable_message.write_to_bytes()?,
+        CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+    )
+}
+
+/// Returns the IDs of the nodes in the routing table that the given peer is authorized as.
+///
+/// Durable messages and their acknowledgements name the node that sent them, so the handlers use
+/// this to check that the name matches the connection the message arrived on.
+pub(crate) fn peer_node_ids(
+    routing_table: &dyn RoutingTableReader,
+    peer: &PeerAuthorizationToken,
+) -> Result<Vec<String>, InternalError> {
+    Ok(routing_table
+        .list_nodes()
+        .map_err(|err| InternalError::from_source(Box::new(err)))?
+        .filter(|(_, node)| is_node_peer(node, peer))
+        .map(|(node_id, _)| node_id)
+        .collect())
+}
+
+/// Returns `true` if the given peer is authorized as the node, with any authorization type.
+pub(crate) fn is_node_peer(node: &CircuitNode, peer: &PeerAuthorizationToken) -> bool {
+    let auth_types = vec![
+        AuthorizationType::Trust,
+        #[cfg(feature = "challenge-authorization")]
+        AuthorizationType::Challenge,
+    ];
+
+    auth_types.iter().any(|auth_type| {
+        node.get_peer_auth_token(auth_type)
+            .map(|token| &token == peer)
+            .unwrap_or(false)
+    })
+}
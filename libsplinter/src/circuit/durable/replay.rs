@@ -0,0 +1,193 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resends queued durable messages when peer nodes reconnect.
+
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+
+use crate::circuit::routing::RoutingTableReader;
+use crate::error::InternalError;
+use crate::peer::interconnect::NetworkMessageSender;
+use crate::peer::{
+    PeerAuthorizationToken, PeerManagerConnector, PeerManagerNotification, SubscriberId,
+};
+use crate::threading::lifecycle::ShutdownHandle;
+
+use super::{create_durable_message, peer_node_ids, DurableMessageStore};
+
+enum ReplayMessage {
+    Notification(PeerManagerNotification),
+    Shutdown,
+}
+
+impl From<PeerManagerNotification> for ReplayMessage {
+    fn from(notification: PeerManagerNotification) -> Self {
+        ReplayMessage::Notification(notification)
+    }
+}
+
+/// Resends the unacknowledged messages queued for a node whenever the node's peer connects.
+///
+/// The messages are resent in sequence number order; the receiving node discards any messages it
+/// has already delivered.
+pub struct DurableMessageReplayer {
+    sender: Sender<ReplayMessage>,
+    join_handle: thread::JoinHandle<()>,
+    peer_connector: PeerManagerConnector,
+    subscriber_id: SubscriberId,
+}
+
+impl DurableMessageReplayer {
+    /// Starts the replayer's background thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The ID of the local node
+    /// * `durable_message_store` - The store of queued durable messages
+    /// * `routing_table` - The routing table, used to look up the peer for each node
+    /// * `network_sender` - The sender used to resend the messages
+    /// * `peer_connector` - The connector used to subscribe to peer notifications
+    pub fn start(
+        node_id: String,
+        durable_message_store: Box<dyn DurableMessageStore>,
+        routing_table: Box<dyn RoutingTableReader>,
+        network_sender: NetworkMessageSender,
+        peer_connector: PeerManagerConnector,
+    ) -> Result<Self, InternalError> {
+        let (sender, receiver) = channel();
+        let subscriber_id = peer_connector
+            .subscribe_sender(sender.clone())
+            .map_err(|err| InternalError::from_source(Box::new(err)))?;
+
+        let join_handle = thread::Builder::new()
+            .name("DurableMessageReplayer".into())
+            .spawn(move || loop {
+                match receiver.recv() {
+                    Ok(ReplayMessage::Notification(PeerManagerNotification::Connected {
+                        peer,
+                    })) => {
+                        if let Err(err) = replay_messages(
+                            &node_id,
+                            &*durable_message_store,
+                            &*routing_table,
+                            &network_sender,
+                            &peer,
+                        ) {
+                            error!("Unable to resend durable messages to {}: {}", peer, err);
+                        }
+                    }
+                    Ok(ReplayMessage::Notification(_)) => (),
+                    Ok(ReplayMessage::Shutdown) => break,
+                    Err(_) => {
+                        warn!(
+                            "Durable message replayer received an error while listening to peer \
+                            manager notifications, indicating remote thread has shutdown"
+                        );
+                        break;
+                    }
+                }
+            })
+            .map_err(|err| InternalError::from_source(Box::new(err)))?;
+
+        Ok(DurableMessageReplayer {
+            sender,
+            join_handle,
+            peer_connector,
+            subscriber_id,
+        })
+    }
+}
+
+impl ShutdownHandle for DurableMessageReplayer {
+    fn signal_shutdown(&mut self) {
+        if let Err(err) = self.peer_connector.unsubscribe(self.subscriber_id) {
+            warn!(
+                "Unable to unsubscribe durable message replayer from peer notifications: {}",
+                err
+            );
+        }
+
+        if self.sender.send(ReplayMessage::Shutdown).is_err() {
+            warn!("Durable message replayer is no longer running");
+        }
+    }
+
+    fn wait_for_shutdown(self) -> Result<(), InternalError> {
+        self.join_handle.join().map_err(|_| {
+            InternalError::with_message(
+                "Durable message replayer thread did not shutdown correctly".into(),
+            )
+        })
+    }
+}
+
+/// Resends the queued messages for the node that the connected peer is authorized as.
+fn replay_messages(
+    node_id: &str,
+    durable_message_store: &dyn DurableMessageStore,
+    routing_table: &dyn RoutingTableReader,
+    network_sender: &NetworkMessageSender,
+    peer: &PeerAuthorizationToken,
+) -> Result<(), InternalError> {
+    for peer_node_id in peer_node_ids(routing_table, peer)? {
+        let node = match routing_table
+            .get_node(&peer_node_id)
+            .map_err(|err| InternalError::from_source(Box::new(err)))?
+        {
+            Some(node) => node,
+            None => continue,
+        };
+
+        let messages = durable_message_store
+            .list_messages(&peer_node_id)
+            .map_err(|err| InternalError::from_source(Box::new(err)))?;
+
+        for message in messages {
+            // The peer token for a node depends on the authorization type of the circuit
+            let circuit = match routing_table
+                .get_circuit(message.circuit_id())
+                .map_err(|err| InternalError::from_source(Box::new(err)))?
+            {
+                Some(circuit) => circuit,
+                None => continue,
+            };
+            let token = node
+                .get_peer_auth_token(circuit.authorization_type())
+                .map_err(|err| InternalError::from_source(Box::new(err)))?;
+            if &token != peer {
+                continue;
+            }
+
+            let msg_bytes = create_durable_message(
+                node_id,
+                &peer_node_id,
+                message.sequence_number(),
+                message.payload(),
+            )
+            .map_err(|err| InternalError::from_source(Box::new(err)))?;
+
+            debug!(
+                "Resending durable message {} to {}",
+                message.sequence_number(),
+                peer_node_id
+            );
+            network_sender.send(token, msg_bytes).map_err(|_| {
+                InternalError::with_message("Peer interconnect is no longer running".into())
+            })?;
+        }
+    }
+
+    Ok(())
+}
@@ -265,6 +265,10 @@ mod tests {
 
     #[cfg(feature = "challenge-authorization")]
     use crate::circuit::routing::AuthorizationType;
+    #[cfg(feature = "circuit-durability")]
+    use crate::circuit::routing::DurabilityType;
+    #[cfg(feature = "circuit-relay")]
+    use crate::circuit::routing::RouteType;
     use crate::circuit::routing::{
         memory::RoutingTable, Circuit, CircuitNode, RoutingTableWriter, Service,
     };
@@ -318,6 +322,10 @@ mod tests {
             vec!["123".into(), "345".into()],
             #[cfg(feature = "challenge-authorization")]
             AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Any,
         );
 
         writer
@@ -415,6 +423,10 @@ mod tests {
             vec!["123".into(), "345".into()],
             #[cfg(feature = "challenge-authorization")]
             AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Any,
         );
 
         writer
@@ -512,6 +524,10 @@ mod tests {
             vec!["123".into(), "345".into()],
             #[cfg(feature = "challenge-authorization")]
             AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Any,
         );
 
         writer
@@ -12,9 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "circuit-relay")]
+use std::collections::HashMap;
+#[cfg(feature = "circuit-relay")]
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "circuit-durability")]
+use crate::circuit::durable::{create_durable_message, DurableMessageStore};
 use crate::circuit::handlers::create_message;
+#[cfg(any(feature = "circuit-durability", feature = "circuit-relay"))]
+use crate::circuit::routing::Circuit;
+#[cfg(feature = "circuit-durability")]
+use crate::circuit::routing::DurabilityType;
+#[cfg(feature = "circuit-relay")]
+use crate::circuit::routing::RouteType;
 use crate::circuit::routing::{RoutingTableReader, ServiceId};
 use crate::network::dispatch::{DispatchError, Handler, MessageContext, MessageSender, PeerId};
+#[cfg(feature = "circuit-relay")]
+use crate::peer::PeerManagerConnector;
 use crate::protos::circuit::{
     CircuitDirectMessage, CircuitError, CircuitError_Error, CircuitMessageType,
 };
@@ -25,6 +40,14 @@ use protobuf::Message;
 pub struct CircuitDirectMessageHandler {
     node_id: String,
     routing_table: Box<dyn RoutingTableReader>,
+    #[cfg(feature = "circuit-durability")]
+    durable_message_store: Option<Box<dyn DurableMessageStore>>,
+    #[cfg(feature = "circuit-relay")]
+    peer_connector: Option<PeerManagerConnector>,
+    /// The member node that last relayed a message from each node, which is therefore connected
+    /// to that node
+    #[cfg(feature = "circuit-relay")]
+    relay_routes: Arc<Mutex<HashMap<String, String>>>,
 }
 
 impl Handler for CircuitDirectMessageHandler {
@@ -74,12 +97,21 @@ impl Handler for CircuitDirectMessageHandler {
                 .get_circuit(circuit_name)
                 .map_err(|err| DispatchError::HandleError(err.to_string()))?
             {
+                // On relay circuits, messages from another node must have been sent or relayed
+                // by a member of the circuit
+                #[cfg(feature = "circuit-relay")]
+                let source_allowed =
+                    self.is_allowed_source(&circuit, msg_sender, context.source_peer_id())?;
+                #[cfg(not(feature = "circuit-relay"))]
+                let source_allowed = true;
+
                 // Check if the message sender is allowed on the circuit
                 // if the sender is not allowed on the circuit
-                if !circuit
-                    .roster()
-                    .iter()
-                    .any(|service| service.service_id() == msg_sender)
+                if !source_allowed
+                    || !circuit
+                        .roster()
+                        .iter()
+                        .any(|service| service.service_id() == msg_sender)
                 {
                     let mut error_message = CircuitError::new();
                     error_message.set_correlation_id(msg.get_correlation_id().to_string());
@@ -127,6 +159,34 @@ impl Handler for CircuitDirectMessageHandler {
                                 .map_err(|err| DispatchError::HandleError(err.to_string()))?
                                 .into();
 
+                            #[cfg(feature = "circuit-relay")]
+                            let node_peer_id =
+                                self.route_to_node(&circuit, &node_id, msg_sender, node_peer_id)?;
+
+                            // Durable messages name the recipient's node, so they may be sent
+                            // through a relaying node as well
+                            #[cfg(feature = "circuit-durability")]
+                            {
+                                if let Some(durable_msg_bytes) = self.queue_durable_message(
+                                    &circuit,
+                                    &node_id,
+                                    context.message_bytes(),
+                                )? {
+                                    // The message has been stored, so it will be resent when the
+                                    // node reconnects if it cannot be sent now
+                                    if let Err((recipient, _)) =
+                                        sender.send(node_peer_id, durable_msg_bytes)
+                                    {
+                                        debug!(
+                                            "Unable to send durable message to {}, it will be \
+                                            resent when the node reconnects",
+                                            recipient
+                                        );
+                                    }
+                                    return Ok(());
+                                }
+                            }
+
                             (network_msg_bytes, node_peer_id)
                         } else {
                             let peer_id: PeerId = match service.peer_id() {
@@ -209,7 +269,238 @@ impl CircuitDirectMessageHandler {
         CircuitDirectMessageHandler {
             node_id,
             routing_table,
+            #[cfg(feature = "circuit-durability")]
+            durable_message_store: None,
+            #[cfg(feature = "circuit-relay")]
+            peer_connector: None,
+            #[cfg(feature = "circuit-relay")]
+            relay_routes: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sets the peer connector used to check which nodes are connected when routing messages on
+    /// circuits with the `Relay` route type.
+    #[cfg(feature = "circuit-relay")]
+    pub fn with_peer_connector(mut self, peer_connector: PeerManagerConnector) -> Self {
+        self.peer_connector = Some(peer_connector);
+        self
+    }
+
+    /// Checks that a message on a relay circuit was received from either the sender service's
+    /// own connection or a member node of the circuit. If the message was relayed by another
+    /// member, that member is remembered as a relay for the sender's node.
+    #[cfg(feature = "circuit-relay")]
+    fn is_allowed_source(
+        &self,
+        circuit: &Circuit,
+        msg_sender: &str,
+        source_peer_id: &PeerId,
+    ) -> Result<bool, DispatchError> {
+        if circuit.route_type() != &RouteType::Relay {
+            return Ok(true);
+        }
+
+        let sender_id = ServiceId::new(circuit.circuit_id().to_string(), msg_sender.to_string());
+        match self
+            .routing_table
+            .get_service(&sender_id)
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?
+        {
+            // The sender is connected to this node
+            Some(service) if service.node_id() == self.node_id => Ok(true),
+            // The roster check will reject the message
+            None => Ok(true),
+            Some(service) => {
+                for member in circuit.members() {
+                    if let Some(node) = self
+                        .routing_table
+                        .get_node(member)
+                        .map_err(|err| DispatchError::HandleError(err.to_string()))?
+                    {
+                        let token = node
+                            .get_peer_auth_token(circuit.authorization_type())
+                            .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+                        if token == **source_peer_id {
+                            if member != service.node_id() {
+                                self.relay_routes
+                                    .lock()
+                                    .map_err(|_| {
+                                        DispatchError::HandleError(
+                                            "Relay routes lock was poisoned".into(),
+                                        )
+                                    })?
+                                    .insert(service.node_id().to_string(), member.to_string());
+                            }
+                            return Ok(true);
+                        }
+                    }
+                }
+
+                warn!(
+                    "Rejecting message on circuit {} from {}, which is not a member node",
+                    circuit.circuit_id(),
+                    source_peer_id
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    /// Returns the peer a message for the given node should be sent to.
+    ///
+    /// On circuits with the `Relay` route type, a message sent by a local service to a node that
+    /// is not connected is sent to another member node that is connected, which forwards it to the
+    /// recipient's node. A member that has relayed messages from the recipient's node is
+    /// preferred, as it is known to be connected to that node. Messages are relayed at most once,
+    /// so messages that were received from another node are always sent directly.
+    #[cfg(feature = "circuit-relay")]
+    fn route_to_node(
+        &self,
+        circuit: &Circuit,
+        node_id: &str,
+        msg_sender: &str,
+        node_peer_id: PeerId,
+    ) -> Result<PeerId, DispatchError> {
+        if circuit.route_type() != &RouteType::Relay {
+            return Ok(node_peer_id);
+        }
+
+        let peer_connector = match &self.peer_connector {
+            Some(peer_connector) => peer_connector,
+            None => return Ok(node_peer_id),
+        };
+
+        let connected_peers = peer_connector
+            .list_peers()
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+        if connected_peers.contains(&*node_peer_id) {
+            return Ok(node_peer_id);
+        }
+
+        let sender_id = ServiceId::new(circuit.circuit_id().to_string(), msg_sender.to_string());
+        let sent_by_local_service = self
+            .routing_table
+            .get_service(&sender_id)
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?
+            .map(|service| service.node_id() == self.node_id)
+            .unwrap_or(false);
+        if !sent_by_local_service {
+            warn!(
+                "Unable to relay message on circuit {} to node {}, which is not connected",
+                circuit.circuit_id(),
+                node_id
+            );
+            return Ok(node_peer_id);
+        }
+
+        let known_relay = self
+            .relay_routes
+            .lock()
+            .map_err(|_| DispatchError::HandleError("Relay routes lock was poisoned".into()))?
+            .get(node_id)
+            .cloned();
+
+        // Try the known relay first, followed by the other members in order
+        let candidates = known_relay
+            .iter()
+            .filter(|relay| circuit.members().contains(relay))
+            .chain(
+                circuit
+                    .members()
+                    .iter()
+                    .filter(|member| Some(*member) != known_relay.as_ref()),
+            );
+        for member in candidates {
+            if member == &self.node_id || member == node_id {
+                continue;
+            }
+
+            if let Some(node) = self
+                .routing_table
+                .get_node(member)
+                .map_err(|err| DispatchError::HandleError(err.to_string()))?
+            {
+                let token = node
+                    .get_peer_auth_token(circuit.authorization_type())
+                    .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+                if connected_peers.contains(&token) {
+                    if Some(member) == known_relay.as_ref() {
+                        debug!(
+                            "Relaying message on circuit {} to node {} through node {}",
+                            circuit.circuit_id(),
+                            node_id,
+                            member
+                        );
+                    } else {
+                        debug!(
+                            "Relaying message on circuit {} to node {} through node {}, which \
+                            is not known to be connected to node {}",
+                            circuit.circuit_id(),
+                            node_id,
+                            member,
+                            node_id
+                        );
+                    }
+                    return Ok(token.into());
+                }
+            }
         }
+
+        warn!(
+            "No connected member of circuit {} can relay messages to node {}",
+            circuit.circuit_id(),
+            node_id
+        );
+        Ok(node_peer_id)
+    }
+
+    /// Sets the store used to queue the direct messages sent on circuits with the
+    /// `StoreAndForward` durability type.
+    #[cfg(feature = "circuit-durability")]
+    pub fn with_durable_message_store(
+        mut self,
+        durable_message_store: Box<dyn DurableMessageStore>,
+    ) -> Self {
+        self.durable_message_store = Some(durable_message_store);
+        self
+    }
+
+    /// Stores a direct message that is being sent to another node if the circuit has the
+    /// `StoreAndForward` durability type, returning the durable message that should be sent
+    /// instead.
+    #[cfg(feature = "circuit-durability")]
+    fn queue_durable_message(
+        &self,
+        circuit: &Circuit,
+        node_id: &str,
+        msg_bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>, DispatchError> {
+        if circuit.durability() != &DurabilityType::StoreAndForward {
+            return Ok(None);
+        }
+
+        let store = match &self.durable_message_store {
+            Some(store) => store,
+            None => {
+                warn!(
+                    "No durable message store is configured, sending message on circuit {} \
+                    without durability",
+                    circuit.circuit_id()
+                );
+                return Ok(None);
+            }
+        };
+
+        let sequence_number = store
+            .add_message(node_id, circuit.circuit_id(), msg_bytes.to_vec())
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+
+        Ok(Some(create_durable_message(
+            &self.node_id,
+            node_id,
+            sequence_number,
+            msg_bytes,
+        )?))
     }
 }
 
@@ -220,13 +511,47 @@ mod tests {
     use std::collections::VecDeque;
     use std::sync::{Arc, Mutex};
 
+    #[cfg(all(
+        feature = "circuit-relay",
+        feature = "circuit-durability",
+        feature = "sqlite"
+    ))]
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    #[cfg(all(
+        feature = "circuit-relay",
+        feature = "circuit-durability",
+        feature = "sqlite"
+    ))]
+    use crate::circuit::durable::DieselDurableMessageStore;
     #[cfg(feature = "challenge-authorization")]
     use crate::circuit::routing::AuthorizationType;
+    #[cfg(feature = "circuit-durability")]
+    use crate::circuit::routing::DurabilityType;
+    #[cfg(feature = "circuit-relay")]
+    use crate::circuit::routing::RouteType;
     use crate::circuit::routing::{
         memory::RoutingTable, Circuit, CircuitNode, RoutingTableWriter, Service,
     };
+    #[cfg(all(
+        feature = "circuit-relay",
+        feature = "circuit-durability",
+        feature = "sqlite"
+    ))]
+    use crate::migrations::run_sqlite_migrations;
     use crate::network::dispatch::Dispatcher;
     use crate::peer::PeerAuthorizationToken;
+    #[cfg(feature = "circuit-relay")]
+    use crate::peer::{PeerManagerMessage, PeerManagerRequest};
+    #[cfg(all(
+        feature = "circuit-relay",
+        feature = "circuit-durability",
+        feature = "sqlite"
+    ))]
+    use crate::protos::circuit::CircuitDurableMessage;
     use crate::protos::circuit::CircuitMessage;
     use crate::protos::network::NetworkMessage;
 
@@ -278,6 +603,10 @@ mod tests {
             vec!["123".into(), "345".into()],
             #[cfg(feature = "challenge-authorization")]
             AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Any,
         );
 
         writer
@@ -374,6 +703,10 @@ mod tests {
             vec!["123".into(), "345".into()],
             #[cfg(feature = "challenge-authorization")]
             AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Any,
         );
 
         writer
@@ -463,6 +796,10 @@ mod tests {
             vec!["123".into(), "345".into()],
             #[cfg(feature = "challenge-authorization")]
             AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Any,
         );
 
         writer
@@ -553,6 +890,10 @@ mod tests {
             vec!["123".into(), "345".into()],
             #[cfg(feature = "challenge-authorization")]
             AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Any,
         );
 
         writer
@@ -651,6 +992,375 @@ mod tests {
         )
     }
 
+    // Test that a direct message sent by a local service on a relay circuit is sent to a
+    // connected member node when the recipient's node is not connected
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_circuit_direct_message_handler_relay() {
+        // Set up dispatcher and mock sender
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let reader = relay_routing_table();
+
+        // Only node 678 is connected to node 123
+        let peer_connector = mock_peer_connector(vec![PeerAuthorizationToken::from_peer_id("678")]);
+
+        // Add direct message handler to dispatcher
+        let handler = CircuitDirectMessageHandler::new("123".to_string(), reader)
+            .with_peer_connector(peer_connector);
+        dispatcher.set_handler(Box::new(handler));
+
+        // create dispatch message
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("abc".into());
+        direct_message.set_recipient("def".into());
+        direct_message.set_payload(b"test".to_vec());
+        direct_message.set_correlation_id("1234".into());
+        let direct_bytes = direct_message.write_to_bytes().unwrap();
+
+        // dispatch the message
+        dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("abc_network").into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_bytes.clone(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            PeerAuthorizationToken::from_peer_id("678"),
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+            |msg: CircuitDirectMessage| {
+                assert_eq!(msg.get_sender(), "abc");
+                assert_eq!(msg.get_circuit(), "alpha");
+                assert_eq!(msg.get_recipient(), "def");
+                assert_eq!(msg.get_payload().to_vec(), b"test".to_vec());
+                assert_eq!(msg.get_correlation_id(), "1234");
+            },
+        )
+    }
+
+    // Test that a direct message sent by a local service on a relay circuit is sent through the
+    // member node that relayed a message from the recipient's node, rather than the first
+    // connected member
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_circuit_direct_message_handler_relay_known_route() {
+        // Set up dispatcher and mock sender
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let reader = relay_routing_table();
+
+        // Nodes 678 and 901 are connected to node 123
+        let peer_connector = mock_peer_connector(vec![
+            PeerAuthorizationToken::from_peer_id("678"),
+            PeerAuthorizationToken::from_peer_id("901"),
+        ]);
+
+        // Add direct message handler to dispatcher
+        let handler = CircuitDirectMessageHandler::new("123".to_string(), reader)
+            .with_peer_connector(peer_connector);
+        dispatcher.set_handler(Box::new(handler));
+
+        // node 901 relays a message from def, on node 345, to abc
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("def".into());
+        direct_message.set_recipient("abc".into());
+        direct_message.set_payload(b"test".to_vec());
+        direct_message.set_correlation_id("1234".into());
+        dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("901").into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_message.write_to_bytes().unwrap(),
+            )
+            .unwrap();
+
+        let (id, _) = mock_sender.next_outbound().expect("No message was sent");
+        assert_eq!(
+            PeerAuthorizationToken::from(id),
+            PeerAuthorizationToken::from_peer_id("abc_network")
+        );
+
+        // abc replies to def
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("abc".into());
+        direct_message.set_recipient("def".into());
+        direct_message.set_payload(b"reply".to_vec());
+        direct_message.set_correlation_id("5678".into());
+        dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("abc_network").into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_message.write_to_bytes().unwrap(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            PeerAuthorizationToken::from_peer_id("901"),
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+            |msg: CircuitDirectMessage| {
+                assert_eq!(msg.get_sender(), "abc");
+                assert_eq!(msg.get_recipient(), "def");
+                assert_eq!(msg.get_payload().to_vec(), b"reply".to_vec());
+            },
+        )
+    }
+
+    // Test that a direct message on a relay circuit sent by a remote service is rejected if it
+    // was not received from a member node of the circuit
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_circuit_direct_message_handler_relay_non_member() {
+        // Set up dispatcher and mock sender
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let reader = relay_routing_table();
+
+        // Add direct message handler to dispatcher
+        let handler = CircuitDirectMessageHandler::new("123".to_string(), reader);
+        dispatcher.set_handler(Box::new(handler));
+
+        // create dispatch message
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("def".into());
+        direct_message.set_recipient("abc".into());
+        direct_message.set_payload(b"test".to_vec());
+        direct_message.set_correlation_id("1234".into());
+        let direct_bytes = direct_message.write_to_bytes().unwrap();
+
+        // dispatch the message from a node that is not a member of the circuit
+        dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("999").into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_bytes.clone(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            PeerAuthorizationToken::from_peer_id("999"),
+            CircuitMessageType::CIRCUIT_ERROR_MESSAGE,
+            |msg: CircuitError| {
+                assert_eq!(msg.get_service_id(), "def");
+                assert_eq!(
+                    msg.get_error(),
+                    CircuitError_Error::ERROR_SENDER_NOT_IN_CIRCUIT_ROSTER
+                );
+                assert_eq!(msg.get_correlation_id(), "1234");
+            },
+        );
+
+        // dispatch the message from the relaying member node
+        dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("678").into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_bytes.clone(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            PeerAuthorizationToken::from_peer_id("abc_network"),
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+            |msg: CircuitDirectMessage| {
+                assert_eq!(msg.get_sender(), "def");
+                assert_eq!(msg.get_recipient(), "abc");
+            },
+        )
+    }
+
+    // Test that a direct message sent by a local service on a relay circuit with store-and-forward
+    // durability is queued for the recipient's node, and sent as a durable message naming that
+    // node to a connected member node when the recipient's node is not connected
+    #[cfg(all(
+        feature = "circuit-relay",
+        feature = "circuit-durability",
+        feature = "sqlite"
+    ))]
+    #[test]
+    fn test_circuit_direct_message_handler_relay_durable() {
+        // Set up dispatcher and mock sender
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let reader = create_relay_routing_table(DurabilityType::StoreAndForward);
+        let store = create_durable_message_store();
+
+        // Only node 678 is connected to node 123
+        let peer_connector = mock_peer_connector(vec![PeerAuthorizationToken::from_peer_id("678")]);
+
+        // Add direct message handler to dispatcher
+        let handler = CircuitDirectMessageHandler::new("123".to_string(), reader)
+            .with_peer_connector(peer_connector)
+            .with_durable_message_store(store.clone());
+        dispatcher.set_handler(Box::new(handler));
+
+        // create dispatch message
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("abc".into());
+        direct_message.set_recipient("def".into());
+        direct_message.set_payload(b"test".to_vec());
+        direct_message.set_correlation_id("1234".into());
+
+        // dispatch the message
+        dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("abc_network").into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_message.write_to_bytes().unwrap(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            PeerAuthorizationToken::from_peer_id("678"),
+            CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+            |msg: CircuitDurableMessage| {
+                assert_eq!(msg.get_sender_node_id(), "123");
+                assert_eq!(msg.get_receiver_node_id(), "345");
+                assert_eq!(msg.get_sequence_number(), 1);
+                let direct_message =
+                    CircuitDirectMessage::parse_from_bytes(msg.get_payload()).unwrap();
+                assert_eq!(direct_message.get_recipient(), "def");
+            },
+        );
+        assert!(mock_sender.next_outbound().is_none());
+
+        assert_eq!(
+            store
+                .list_messages("345")
+                .expect("Unable to list messages")
+                .len(),
+            1
+        );
+    }
+
+    // Creates a routing table with the relay circuit "alpha", which has the members 123, 345, 678
+    // and 901, where service abc is connected to node 123 and service def is connected to node 345
+    #[cfg(feature = "circuit-relay")]
+    fn relay_routing_table() -> Box<dyn RoutingTableReader> {
+        create_relay_routing_table(
+            #[cfg(feature = "circuit-durability")]
+            DurabilityType::NoDurability,
+        )
+    }
+
+    // Creates the routing table returned by `relay_routing_table`, with the given durability
+    #[cfg(feature = "circuit-relay")]
+    fn create_relay_routing_table(
+        #[cfg(feature = "circuit-durability")] durability: DurabilityType,
+    ) -> Box<dyn RoutingTableReader> {
+        let table = RoutingTable::default();
+        let reader: Box<dyn RoutingTableReader> = Box::new(table.clone());
+        let mut writer: Box<dyn RoutingTableWriter> = Box::new(table);
+
+        let nodes = ["123", "345", "678", "901"]
+            .iter()
+            .enumerate()
+            .map(|(i, node_id)| {
+                CircuitNode::new(
+                    node_id.to_string(),
+                    vec![format!("123.0.0.1:{}", i)],
+                    #[cfg(feature = "challenge-authorization")]
+                    None,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut service_abc = Service::new(
+            "abc".to_string(),
+            "test".to_string(),
+            "123".to_string(),
+            vec![],
+        );
+        let mut service_def = Service::new(
+            "def".to_string(),
+            "test".to_string(),
+            "345".to_string(),
+            vec![],
+        );
+
+        service_abc.set_peer_id(PeerAuthorizationToken::from_peer_id("abc_network"));
+        service_def.set_peer_id(PeerAuthorizationToken::from_peer_id("def_network"));
+
+        let circuit = Circuit::new(
+            "alpha".into(),
+            vec![service_abc, service_def],
+            vec!["123".into(), "345".into(), "678".into(), "901".into()],
+            #[cfg(feature = "challenge-authorization")]
+            AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            durability,
+            RouteType::Relay,
+        );
+
+        writer
+            .add_circuit(circuit.circuit_id().into(), circuit, nodes)
+            .expect("Unable to add circuits");
+
+        reader
+    }
+
+    #[cfg(all(
+        feature = "circuit-relay",
+        feature = "circuit-durability",
+        feature = "sqlite"
+    ))]
+    fn create_durable_message_store() -> Box<dyn DurableMessageStore> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        Box::new(DieselDurableMessageStore::new(pool))
+    }
+
+    // Creates a peer connector that reports the given peers as connected
+    #[cfg(feature = "circuit-relay")]
+    fn mock_peer_connector(peers: Vec<PeerAuthorizationToken>) -> PeerManagerConnector {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            while let Ok(message) = receiver.recv() {
+                if let PeerManagerMessage::Request(PeerManagerRequest::ListPeers { sender }) =
+                    message
+                {
+                    sender
+                        .send(Ok(peers.clone()))
+                        .expect("Unable to send peer list");
+                }
+            }
+        });
+
+        PeerManagerConnector::new(sender)
+    }
+
     fn assert_network_message<M: protobuf::Message, F: Fn(M)>(
         message: Vec<u8>,
         recipient: PeerAuthorizationToken,
@@ -0,0 +1,957 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handlers for the messages exchanged between nodes on store-and-forward circuits.
+
+use protobuf::Message;
+
+use crate::circuit::durable::{create_durable_message, is_node_peer, DurableMessageStore};
+use crate::circuit::handlers::create_message;
+#[cfg(feature = "circuit-relay")]
+use crate::circuit::routing::{Circuit, RouteType};
+use crate::circuit::routing::{RoutingTableReader, ServiceId};
+use crate::network::dispatch::{DispatchError, Handler, MessageContext, MessageSender, PeerId};
+use crate::protos::circuit::{
+    CircuitDirectMessage, CircuitDurableMessage, CircuitDurableMessageAck, CircuitMessageType,
+};
+
+/// Checks that the peer a durable message or acknowledgement was received from is authorized as
+/// the given node.
+fn is_node(
+    routing_table: &dyn RoutingTableReader,
+    node_id: &str,
+    source_peer_id: &PeerId,
+) -> Result<bool, DispatchError> {
+    Ok(routing_table
+        .get_node(node_id)
+        .map_err(|err| DispatchError::HandleError(err.to_string()))?
+        .map(|node| is_node_peer(&node, source_peer_id))
+        .unwrap_or(false))
+}
+
+/// Checks that the node named in a durable message or acknowledgement is the node that the peer
+/// it was received from is authorized as, or, on circuits with the `Relay` route type, that the
+/// peer is another member of a circuit that both this node and the named node are members of.
+fn is_from_node(
+    routing_table: &dyn RoutingTableReader,
+    local_node_id: &str,
+    node_id: &str,
+    source_peer_id: &PeerId,
+) -> Result<bool, DispatchError> {
+    if is_node(routing_table, node_id, source_peer_id)? {
+        return Ok(true);
+    }
+
+    #[cfg(feature = "circuit-relay")]
+    for circuit in relay_circuits(routing_table, &[local_node_id, node_id])? {
+        for member in circuit.members() {
+            if member != local_node_id
+                && member != node_id
+                && is_node(routing_table, member, source_peer_id)?
+            {
+                return Ok(true);
+            }
+        }
+    }
+    #[cfg(not(feature = "circuit-relay"))]
+    let _ = local_node_id;
+
+    warn!(
+        "Rejecting durable message traffic for node {} from peer {}",
+        node_id, source_peer_id
+    );
+    Ok(false)
+}
+
+/// Returns the circuits with the `Relay` route type that all of the given nodes are members of.
+#[cfg(feature = "circuit-relay")]
+fn relay_circuits(
+    routing_table: &dyn RoutingTableReader,
+    node_ids: &[&str],
+) -> Result<Vec<Circuit>, DispatchError> {
+    Ok(routing_table
+        .list_circuits()
+        .map_err(|err| DispatchError::HandleError(err.to_string()))?
+        .map(|(_, circuit)| circuit)
+        .filter(|circuit| {
+            circuit.route_type() == &RouteType::Relay
+                && node_ids
+                    .iter()
+                    .all(|node_id| circuit.members().iter().any(|member| member == node_id))
+        })
+        .collect())
+}
+
+/// Forwards durable message traffic between two other members of a circuit with the `Relay`
+/// route type. Traffic is relayed at most once, so it is only forwarded if it was received
+/// directly from `origin_node_id`; otherwise, or if no such circuit exists, it is dropped.
+#[cfg(feature = "circuit-relay")]
+fn relay(
+    routing_table: &dyn RoutingTableReader,
+    local_node_id: &str,
+    origin_node_id: &str,
+    destination_node_id: &str,
+    source_peer_id: &PeerId,
+    network_msg_bytes: Vec<u8>,
+    sender: &dyn MessageSender<PeerId>,
+) -> Result<(), DispatchError> {
+    if is_node(routing_table, origin_node_id, source_peer_id)? {
+        let circuits = relay_circuits(
+            routing_table,
+            &[local_node_id, origin_node_id, destination_node_id],
+        )?;
+        let node = routing_table
+            .get_node(destination_node_id)
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+        if let (Some(circuit), Some(node)) = (circuits.first(), node) {
+            let peer_id: PeerId = node
+                .get_peer_auth_token(circuit.authorization_type())
+                .map_err(|err| DispatchError::HandleError(err.to_string()))?
+                .into();
+            debug!(
+                "Relaying durable message traffic from node {} to node {}",
+                origin_node_id, destination_node_id
+            );
+            // The sending node keeps the messages until they are acknowledged, so they will be
+            // resent if they cannot be relayed now
+            if let Err((recipient, _)) = sender.send(peer_id, network_msg_bytes) {
+                debug!(
+                    "Unable to relay durable message traffic to {}, it will be resent",
+                    recipient
+                );
+            }
+            return Ok(());
+        }
+    }
+
+    warn!(
+        "Dropping durable message traffic from node {} for node {} received from peer {}",
+        origin_node_id, destination_node_id, source_peer_id
+    );
+    Ok(())
+}
+
+/// Handles the durable messages sent by other nodes.
+///
+/// Messages are delivered to the local recipient service in sequence number order. A message
+/// that has already been delivered is acknowledged again, but not delivered. A message that
+/// arrives before the messages preceding it is dropped, and the sender is told which message was
+/// delivered last so that it can resend the rest.
+///
+/// On circuits with the `Relay` route type, messages for another node are forwarded to it, and
+/// the acknowledgements are sent back through the relaying node.
+pub struct CircuitDurableMessageHandler {
+    node_id: String,
+    routing_table: Box<dyn RoutingTableReader>,
+    durable_message_store: Box<dyn DurableMessageStore>,
+}
+
+impl CircuitDurableMessageHandler {
+    pub fn new(
+        node_id: String,
+        routing_table: Box<dyn RoutingTableReader>,
+        durable_message_store: Box<dyn DurableMessageStore>,
+    ) -> Self {
+        CircuitDurableMessageHandler {
+            node_id,
+            routing_table,
+            durable_message_store,
+        }
+    }
+
+    /// Sends the direct message to the recipient service, if it is connected to this node.
+    ///
+    /// Returns `false` if the message could not be delivered and should be resent later.
+    fn deliver(
+        &self,
+        direct_message: &CircuitDirectMessage,
+        payload: &[u8],
+        sender: &dyn MessageSender<PeerId>,
+    ) -> Result<bool, DispatchError> {
+        let service_id = ServiceId::new(
+            direct_message.get_circuit().to_string(),
+            direct_message.get_recipient().to_string(),
+        );
+
+        let service = match self
+            .routing_table
+            .get_service(&service_id)
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?
+        {
+            Some(service) if service.node_id() == self.node_id => service,
+            _ => {
+                // The circuit or service no longer exists on this node, so the message can never
+                // be delivered; it is acknowledged so that it is removed from the sender's queue.
+                warn!(
+                    "Dropping durable message for unknown service {}",
+                    service_id
+                );
+                return Ok(true);
+            }
+        };
+
+        let peer_id: PeerId = match service.peer_id() {
+            Some(peer_id) => peer_id.clone().into(),
+            None => {
+                debug!(
+                    "Service {} is not connected, durable message will be resent",
+                    service_id
+                );
+                return Ok(false);
+            }
+        };
+
+        let network_msg_bytes =
+            create_message(payload.to_vec(), CircuitMessageType::CIRCUIT_DIRECT_MESSAGE)?;
+        match sender.send(peer_id, network_msg_bytes) {
+            Ok(()) => Ok(true),
+            Err((recipient, _)) => {
+                debug!(
+                    "Unable to send durable message to {}, it will be resent",
+                    recipient
+                );
+                Ok(false)
+            }
+        }
+    }
+}
+
+impl Handler for CircuitDurableMessageHandler {
+    type Source = PeerId;
+    type MessageType = CircuitMessageType;
+    type Message = CircuitDurableMessage;
+
+    fn match_type(&self) -> Self::MessageType {
+        CircuitMessageType::CIRCUIT_DURABLE_MESSAGE
+    }
+
+    fn handle(
+        &self,
+        msg: Self::Message,
+        context: &MessageContext<Self::Source, Self::MessageType>,
+        sender: &dyn MessageSender<Self::Source>,
+    ) -> Result<(), DispatchError> {
+        let sender_node_id = msg.get_sender_node_id();
+        let receiver_node_id = msg.get_receiver_node_id();
+        let sequence_number = msg.get_sequence_number();
+        debug!(
+            "Handle Circuit Durable Message {} from {}",
+            sequence_number, sender_node_id
+        );
+
+        // Messages that do not name a receiver are for this node
+        if !receiver_node_id.is_empty() && receiver_node_id != self.node_id {
+            #[cfg(feature = "circuit-relay")]
+            return relay(
+                &*self.routing_table,
+                &self.node_id,
+                sender_node_id,
+                receiver_node_id,
+                context.source_peer_id(),
+                create_message(
+                    context.message_bytes().to_vec(),
+                    CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+                )?,
+                sender,
+            );
+            #[cfg(not(feature = "circuit-relay"))]
+            {
+                warn!(
+                    "Dropping durable message for node {} from node {}",
+                    receiver_node_id, sender_node_id
+                );
+                return Ok(());
+            }
+        }
+
+        if !is_from_node(
+            &*self.routing_table,
+            &self.node_id,
+            sender_node_id,
+            context.source_peer_id(),
+        )? {
+            return Ok(());
+        }
+
+        let last_delivered = self
+            .durable_message_store
+            .get_last_delivered(sender_node_id)
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+
+        let mut ack = CircuitDurableMessageAck::new();
+        ack.set_receiver_node_id(self.node_id.clone());
+        ack.set_sender_node_id(sender_node_id.to_string());
+
+        if sequence_number <= last_delivered {
+            // The message was delivered before, but the acknowledgement was lost
+            ack.set_sequence_number(sequence_number);
+        } else if sequence_number == last_delivered + 1 {
+            let direct_message = CircuitDirectMessage::parse_from_bytes(msg.get_payload())?;
+            if !self.deliver(&direct_message, msg.get_payload(), sender)? {
+                return Ok(());
+            }
+
+            self.durable_message_store
+                .set_last_delivered(sender_node_id, sequence_number)
+                .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+            ack.set_sequence_number(sequence_number);
+        } else {
+            ack.set_sequence_number(last_delivered);
+            ack.set_out_of_order(true);
+        }
+
+        let network_msg_bytes = create_message(
+            ack.write_to_bytes()?,
+            CircuitMessageType::CIRCUIT_DURABLE_MESSAGE_ACK,
+        )?;
+        sender
+            .send(context.source_peer_id().clone(), network_msg_bytes)
+            .map_err(|(recipient, payload)| {
+                DispatchError::NetworkSendError((recipient.into(), payload))
+            })
+    }
+}
+
+/// Handles the acknowledgements of durable messages sent by this node.
+///
+/// Acknowledged messages are removed from the queue for the receiving node. If the receiving node
+/// reports that a message arrived out of order, the remaining queued messages are resent.
+///
+/// On circuits with the `Relay` route type, acknowledgements for another node are forwarded to it.
+pub struct CircuitDurableMessageAckHandler {
+    node_id: String,
+    routing_table: Box<dyn RoutingTableReader>,
+    durable_message_store: Box<dyn DurableMessageStore>,
+}
+
+impl CircuitDurableMessageAckHandler {
+    pub fn new(
+        node_id: String,
+        routing_table: Box<dyn RoutingTableReader>,
+        durable_message_store: Box<dyn DurableMessageStore>,
+    ) -> Self {
+        CircuitDurableMessageAckHandler {
+            node_id,
+            routing_table,
+            durable_message_store,
+        }
+    }
+}
+
+impl Handler for CircuitDurableMessageAckHandler {
+    type Source = PeerId;
+    type MessageType = CircuitMessageType;
+    type Message = CircuitDurableMessageAck;
+
+    fn match_type(&self) -> Self::MessageType {
+        CircuitMessageType::CIRCUIT_DURABLE_MESSAGE_ACK
+    }
+
+    fn handle(
+        &self,
+        msg: Self::Message,
+        context: &MessageContext<Self::Source, Self::MessageType>,
+        sender: &dyn MessageSender<Self::Source>,
+    ) -> Result<(), DispatchError> {
+        let receiver_node_id = msg.get_receiver_node_id();
+        let sender_node_id = msg.get_sender_node_id();
+        debug!(
+            "Handle Circuit Durable Message Ack {} from {}",
+            msg.get_sequence_number(),
+            receiver_node_id
+        );
+
+        // Acknowledgements that do not name a sender are for this node
+        if !sender_node_id.is_empty() && sender_node_id != self.node_id {
+            #[cfg(feature = "circuit-relay")]
+            return relay(
+                &*self.routing_table,
+                &self.node_id,
+                receiver_node_id,
+                sender_node_id,
+                context.source_peer_id(),
+                create_message(
+                    context.message_bytes().to_vec(),
+                    CircuitMessageType::CIRCUIT_DURABLE_MESSAGE_ACK,
+                )?,
+                sender,
+            );
+            #[cfg(not(feature = "circuit-relay"))]
+            {
+                warn!(
+                    "Dropping durable message acknowledgement for node {} from node {}",
+                    sender_node_id, receiver_node_id
+                );
+                return Ok(());
+            }
+        }
+
+        if !is_from_node(
+            &*self.routing_table,
+            &self.node_id,
+            receiver_node_id,
+            context.source_peer_id(),
+        )? {
+            return Ok(());
+        }
+
+        self.durable_message_store
+            .remove_messages(receiver_node_id, msg.get_sequence_number())
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+
+        if !msg.get_out_of_order() {
+            return Ok(());
+        }
+
+        let messages = self
+            .durable_message_store
+            .list_messages(receiver_node_id)
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+
+        for message in messages {
+            let network_msg_bytes = create_durable_message(
+                &self.node_id,
+                receiver_node_id,
+                message.sequence_number(),
+                message.payload(),
+            )?;
+            sender
+                .send(context.source_peer_id().clone(), network_msg_bytes)
+                .map_err(|(recipient, payload)| {
+                    DispatchError::NetworkSendError((recipient.into(), payload))
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    use crate::circuit::durable::DieselDurableMessageStore;
+    #[cfg(feature = "challenge-authorization")]
+    use crate::circuit::routing::AuthorizationType;
+    #[cfg(feature = "circuit-relay")]
+    use crate::circuit::routing::RouteType;
+    use crate::circuit::routing::{
+        memory::RoutingTable, Circuit, CircuitNode, DurabilityType, RoutingTableWriter, Service,
+    };
+    use crate::migrations::run_sqlite_migrations;
+    use crate::network::dispatch::Dispatcher;
+    use crate::peer::PeerAuthorizationToken;
+    use crate::protos::circuit::CircuitMessage;
+    use crate::protos::network::NetworkMessage;
+
+    // Test that durable messages are delivered to the local service in order, that duplicates are
+    // acknowledged without being delivered again, and that a message received out of order is
+    // reported to the sender with the last delivered sequence number.
+    #[test]
+    fn test_circuit_durable_message_handler() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let reader = create_routing_table();
+        let store = create_store();
+        let handler = CircuitDurableMessageHandler::new("123".to_string(), reader, store);
+        dispatcher.set_handler(Box::new(handler));
+
+        let dispatch = |sequence_number: u64| {
+            dispatcher
+                .dispatch(
+                    PeerAuthorizationToken::from_peer_id("345").into(),
+                    &CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+                    create_durable_message_bytes(sequence_number),
+                )
+                .expect("Unable to dispatch durable message");
+        };
+
+        // The first message is delivered and acknowledged
+        dispatch(1);
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            PeerAuthorizationToken::from_peer_id("abc_network"),
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+            |msg: CircuitDirectMessage| {
+                assert_eq!(msg.get_recipient(), "abc");
+                assert_eq!(msg.get_payload().to_vec(), b"test".to_vec());
+            },
+        );
+        assert_ack(&mock_sender, 1, false);
+
+        // A duplicate is only acknowledged
+        dispatch(1);
+        assert_ack(&mock_sender, 1, false);
+        assert!(mock_sender.next_outbound().is_none());
+
+        // A gap is reported with the last delivered sequence number
+        dispatch(3);
+        assert_ack(&mock_sender, 1, true);
+        assert!(mock_sender.next_outbound().is_none());
+    }
+
+    // Test that an acknowledgement removes the acknowledged messages from the queue, and that the
+    // remaining messages are resent if the receiver reports a message was out of order.
+    #[test]
+    fn test_circuit_durable_message_ack_handler() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let store = create_store();
+        for _ in 0..3 {
+            store
+                .add_message("345", "alpha", b"direct message".to_vec())
+                .expect("Unable to add message");
+        }
+
+        let handler = CircuitDurableMessageAckHandler::new(
+            "123".to_string(),
+            create_routing_table(),
+            store.clone(),
+        );
+        dispatcher.set_handler(Box::new(handler));
+
+        let mut ack = CircuitDurableMessageAck::new();
+        ack.set_receiver_node_id("345".into());
+        ack.set_sequence_number(1);
+        ack.set_out_of_order(true);
+
+        dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("345").into(),
+                &CircuitMessageType::CIRCUIT_DURABLE_MESSAGE_ACK,
+                ack.write_to_bytes().expect("Unable to serialize ack"),
+            )
+            .expect("Unable to dispatch ack");
+
+        for expected_sequence_number in 2..=3 {
+            let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+            assert_network_message(
+                message,
+                id.into(),
+                PeerAuthorizationToken::from_peer_id("345"),
+                CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+                |msg: CircuitDurableMessage| {
+                    assert_eq!(msg.get_sender_node_id(), "123");
+                    assert_eq!(msg.get_sequence_number(), expected_sequence_number);
+                },
+            );
+        }
+        assert!(mock_sender.next_outbound().is_none());
+
+        assert_eq!(
+            store
+                .list_messages("345")
+                .expect("Unable to list messages")
+                .len(),
+            2
+        );
+    }
+
+    // Test that durable messages and acknowledgements naming a node other than the one the
+    // sending peer is authorized as are dropped.
+    #[test]
+    fn test_durable_message_from_wrong_peer() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let store = create_store();
+        store
+            .add_message("345", "alpha", b"direct message".to_vec())
+            .expect("Unable to add message");
+
+        dispatcher.set_handler(Box::new(CircuitDurableMessageHandler::new(
+            "123".to_string(),
+            create_routing_table(),
+            store.clone(),
+        )));
+        dispatcher.set_handler(Box::new(CircuitDurableMessageAckHandler::new(
+            "123".to_string(),
+            create_routing_table(),
+            store.clone(),
+        )));
+
+        dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("999").into(),
+                &CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+                create_durable_message_bytes(1),
+            )
+            .expect("Unable to dispatch durable message");
+        assert!(mock_sender.next_outbound().is_none());
+        assert_eq!(
+            store
+                .get_last_delivered("345")
+                .expect("Unable to get last delivered"),
+            0
+        );
+
+        let mut ack = CircuitDurableMessageAck::new();
+        ack.set_receiver_node_id("345".into());
+        ack.set_sequence_number(1);
+        dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("999").into(),
+                &CircuitMessageType::CIRCUIT_DURABLE_MESSAGE_ACK,
+                ack.write_to_bytes().expect("Unable to serialize ack"),
+            )
+            .expect("Unable to dispatch ack");
+        assert_eq!(
+            store
+                .list_messages("345")
+                .expect("Unable to list messages")
+                .len(),
+            1
+        );
+    }
+
+    // Test that on a relay circuit, a durable message from node 345 for node 123 is relayed by
+    // node 678, delivered by node 123 with an acknowledgement sent back through node 678, and that
+    // the relayed acknowledgement removes the message from the queue of node 345.
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_relayed_durable_message() {
+        let mock_sender = MockSender::new();
+
+        // node 678 relays the message from node 345 to node 123
+        let mut relay_dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+        relay_dispatcher.set_handler(Box::new(CircuitDurableMessageHandler::new(
+            "678".to_string(),
+            create_relay_routing_table(),
+            create_store(),
+        )));
+        relay_dispatcher.set_handler(Box::new(CircuitDurableMessageAckHandler::new(
+            "678".to_string(),
+            create_relay_routing_table(),
+            create_store(),
+        )));
+
+        let mut durable_message: CircuitDurableMessage =
+            Message::parse_from_bytes(&create_durable_message_bytes(1)).unwrap();
+        durable_message.set_receiver_node_id("123".into());
+        let durable_message_bytes = durable_message.write_to_bytes().unwrap();
+
+        relay_dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("345").into(),
+                &CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+                durable_message_bytes.clone(),
+            )
+            .expect("Unable to dispatch durable message");
+        let (id, message) = mock_sender.next_outbound().expect("No message was relayed");
+        assert_network_message(
+            message,
+            id.into(),
+            PeerAuthorizationToken::from_peer_id("123"),
+            CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+            |msg: CircuitDurableMessage| {
+                assert_eq!(msg.get_sender_node_id(), "345");
+                assert_eq!(msg.get_receiver_node_id(), "123");
+            },
+        );
+
+        // a message that was already relayed once is not relayed again
+        relay_dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("901").into(),
+                &CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+                durable_message_bytes.clone(),
+            )
+            .expect("Unable to dispatch durable message");
+        assert!(mock_sender.next_outbound().is_none());
+
+        // node 123 delivers the relayed message and acknowledges it through node 678
+        let mut receiver_dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+        receiver_dispatcher.set_handler(Box::new(CircuitDurableMessageHandler::new(
+            "123".to_string(),
+            create_relay_routing_table(),
+            create_store(),
+        )));
+        receiver_dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("678").into(),
+                &CircuitMessageType::CIRCUIT_DURABLE_MESSAGE,
+                durable_message_bytes,
+            )
+            .expect("Unable to dispatch durable message");
+
+        let (id, _) = mock_sender
+            .next_outbound()
+            .expect("No message was delivered");
+        assert_eq!(
+            PeerAuthorizationToken::from(id),
+            PeerAuthorizationToken::from_peer_id("abc_network")
+        );
+        let (id, message) = mock_sender.next_outbound().expect("No ack was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            PeerAuthorizationToken::from_peer_id("678"),
+            CircuitMessageType::CIRCUIT_DURABLE_MESSAGE_ACK,
+            |msg: CircuitDurableMessageAck| {
+                assert_eq!(msg.get_receiver_node_id(), "123");
+                assert_eq!(msg.get_sender_node_id(), "345");
+                assert_eq!(msg.get_sequence_number(), 1);
+            },
+        );
+
+        let mut ack = CircuitDurableMessageAck::new();
+        ack.set_receiver_node_id("123".into());
+        ack.set_sender_node_id("345".into());
+        ack.set_sequence_number(1);
+        let ack_bytes = ack.write_to_bytes().expect("Unable to serialize ack");
+
+        // node 678 relays the acknowledgement to node 345
+        relay_dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("123").into(),
+                &CircuitMessageType::CIRCUIT_DURABLE_MESSAGE_ACK,
+                ack_bytes.clone(),
+            )
+            .expect("Unable to dispatch ack");
+        let (id, _) = mock_sender.next_outbound().expect("No ack was relayed");
+        assert_eq!(
+            PeerAuthorizationToken::from(id),
+            PeerAuthorizationToken::from_peer_id("345")
+        );
+
+        // node 345 accepts the relayed acknowledgement
+        let store = create_store();
+        store
+            .add_message("123", "alpha", b"direct message".to_vec())
+            .expect("Unable to add message");
+        let mut sender_dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+        sender_dispatcher.set_handler(Box::new(CircuitDurableMessageAckHandler::new(
+            "345".to_string(),
+            create_relay_routing_table(),
+            store.clone(),
+        )));
+        sender_dispatcher
+            .dispatch(
+                PeerAuthorizationToken::from_peer_id("678").into(),
+                &CircuitMessageType::CIRCUIT_DURABLE_MESSAGE_ACK,
+                ack_bytes,
+            )
+            .expect("Unable to dispatch ack");
+        assert!(store
+            .list_messages("123")
+            .expect("Unable to list messages")
+            .is_empty());
+    }
+
+    fn create_routing_table() -> Box<dyn RoutingTableReader> {
+        let table = RoutingTable::default();
+        let reader: Box<dyn RoutingTableReader> = Box::new(table.clone());
+        let mut writer: Box<dyn RoutingTableWriter> = Box::new(table);
+
+        let node_123 = CircuitNode::new(
+            "123".to_string(),
+            vec!["123.0.0.1:0".to_string()],
+            #[cfg(feature = "challenge-authorization")]
+            None,
+        );
+        let node_345 = CircuitNode::new(
+            "345".to_string(),
+            vec!["123.0.0.1:1".to_string()],
+            #[cfg(feature = "challenge-authorization")]
+            None,
+        );
+
+        let mut service_abc = Service::new(
+            "abc".to_string(),
+            "test".to_string(),
+            "123".to_string(),
+            vec![],
+        );
+        let service_def = Service::new(
+            "def".to_string(),
+            "test".to_string(),
+            "345".to_string(),
+            vec![],
+        );
+        service_abc.set_peer_id(PeerAuthorizationToken::from_peer_id("abc_network"));
+
+        let circuit = Circuit::new(
+            "alpha".into(),
+            vec![service_abc, service_def],
+            vec!["123".into(), "345".into()],
+            #[cfg(feature = "challenge-authorization")]
+            AuthorizationType::Trust,
+            DurabilityType::StoreAndForward,
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Any,
+        );
+
+        writer
+            .add_circuit(
+                circuit.circuit_id().into(),
+                circuit,
+                vec![node_123, node_345],
+            )
+            .expect("Unable to add circuit");
+
+        reader
+    }
+
+    // Creates a routing table with the relay circuit "alpha", which has the members 123, 345, 678
+    // and 901, where service abc is connected to node 123 and service def is on node 345
+    #[cfg(feature = "circuit-relay")]
+    fn create_relay_routing_table() -> Box<dyn RoutingTableReader> {
+        let table = RoutingTable::default();
+        let reader: Box<dyn RoutingTableReader> = Box::new(table.clone());
+        let mut writer: Box<dyn RoutingTableWriter> = Box::new(table);
+
+        let nodes = ["123", "345", "678", "901"]
+            .iter()
+            .enumerate()
+            .map(|(i, node_id)| {
+                CircuitNode::new(
+                    node_id.to_string(),
+                    vec![format!("123.0.0.1:{}", i)],
+                    #[cfg(feature = "challenge-authorization")]
+                    None,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut service_abc = Service::new(
+            "abc".to_string(),
+            "test".to_string(),
+            "123".to_string(),
+            vec![],
+        );
+        let service_def = Service::new(
+            "def".to_string(),
+            "test".to_string(),
+            "345".to_string(),
+            vec![],
+        );
+        service_abc.set_peer_id(PeerAuthorizationToken::from_peer_id("abc_network"));
+
+        let circuit = Circuit::new(
+            "alpha".into(),
+            vec![service_abc, service_def],
+            vec!["123".into(), "345".into(), "678".into(), "901".into()],
+            #[cfg(feature = "challenge-authorization")]
+            AuthorizationType::Trust,
+            DurabilityType::StoreAndForward,
+            RouteType::Relay,
+        );
+
+        writer
+            .add_circuit(circuit.circuit_id().into(), circuit, nodes)
+            .expect("Unable to add circuit");
+
+        reader
+    }
+
+    fn create_store() -> Box<dyn DurableMessageStore> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        Box::new(DieselDurableMessageStore::new(pool))
+    }
+
+    fn create_durable_message_bytes(sequence_number: u64) -> Vec<u8> {
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("def".into());
+        direct_message.set_recipient("abc".into());
+        direct_message.set_payload(b"test".to_vec());
+
+        let mut durable_message = CircuitDurableMessage::new();
+        durable_message.set_sender_node_id("345".into());
+        durable_message.set_sequence_number(sequence_number);
+        durable_message.set_payload(direct_message.write_to_bytes().unwrap());
+        durable_message.write_to_bytes().unwrap()
+    }
+
+    fn assert_ack(mock_sender: &MockSender, sequence_number: u64, out_of_order: bool) {
+        let (id, message) = mock_sender.next_outbound().expect("No ack was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            PeerAuthorizationToken::from_peer_id("345"),
+            CircuitMessageType::CIRCUIT_DURABLE_MESSAGE_ACK,
+            |msg: CircuitDurableMessageAck| {
+                assert_eq!(msg.get_receiver_node_id(), "123");
+                assert_eq!(msg.get_sequence_number(), sequence_number);
+                assert_eq!(msg.get_out_of_order(), out_of_order);
+            },
+        );
+    }
+
+    fn assert_network_message<M: protobuf::Message, F: Fn(M)>(
+        message: Vec<u8>,
+        recipient: PeerAuthorizationToken,
+        expected_recipient: PeerAuthorizationToken,
+        expected_circuit_msg_type: CircuitMessageType,
+        detail_assertions: F,
+    ) {
+        assert_eq!(expected_recipient, recipient);
+
+        let network_msg: NetworkMessage = Message::parse_from_bytes(&message).unwrap();
+        let circuit_msg: CircuitMessage =
+            Message::parse_from_bytes(network_msg.get_payload()).unwrap();
+        assert_eq!(expected_circuit_msg_type, circuit_msg.get_message_type(),);
+        let circuit_msg: M = Message::parse_from_bytes(circuit_msg.get_payload()).unwrap();
+
+        detail_assertions(circuit_msg);
+    }
+
+    #[derive(Clone)]
+    struct MockSender {
+        outbound: Arc<Mutex<VecDeque<(PeerId, Vec<u8>)>>>,
+    }
+
+    impl MockSender {
+        fn new() -> Self {
+            Self {
+                outbound: Arc::new(Mutex::new(VecDeque::new())),
+            }
+        }
+
+        fn next_outbound(&self) -> Option<(PeerId, Vec<u8>)> {
+            self.outbound.lock().expect("lock was poisoned").pop_front()
+        }
+    }
+
+    impl MessageSender<PeerId> for MockSender {
+        fn send(&self, id: PeerId, message: Vec<u8>) -> Result<(), (PeerId, Vec<u8>)> {
+            self.outbound
+                .lock()
+                .expect("lock was poisoned")
+                .push_back((id, message));
+
+            Ok(())
+        }
+    }
+}
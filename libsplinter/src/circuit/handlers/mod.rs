@@ -16,6 +16,8 @@ mod admin_message;
 mod circuit_error;
 mod circuit_message;
 mod direct_message;
+#[cfg(feature = "circuit-durability")]
+mod durable_message;
 mod service_handlers;
 
 use protobuf::Message;
@@ -27,10 +29,12 @@ pub use self::admin_message::AdminDirectMessageHandler;
 pub use self::circuit_error::CircuitErrorHandler;
 pub use self::circuit_message::CircuitMessageHandler;
 pub use self::direct_message::CircuitDirectMessageHandler;
+#[cfg(feature = "circuit-durability")]
+pub use self::durable_message::{CircuitDurableMessageAckHandler, CircuitDurableMessageHandler};
 pub use self::service_handlers::ServiceConnectRequestHandler;
 pub use self::service_handlers::ServiceDisconnectRequestHandler;
 
-fn create_message(
+pub(crate) fn create_message(
     payload: Vec<u8>,
     circuit_message_type: CircuitMessageType,
 ) -> Result<Vec<u8>, protobuf::error::ProtobufError> {
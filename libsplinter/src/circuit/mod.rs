@@ -14,6 +14,8 @@
 
 #[cfg(feature = "service-network")]
 pub mod component;
+#[cfg(feature = "circuit-durability")]
+pub mod durable;
 pub mod handlers;
 pub mod routing;
 #[cfg(feature = "circuit-template")]
@@ -19,6 +19,10 @@ use super::{
     Circuit, CircuitNode, RoutingTable, RoutingTableReader, RoutingTableWriter, Service, ServiceId,
 };
 use crate::circuit::routing::AuthorizationType;
+#[cfg(feature = "circuit-durability")]
+use crate::circuit::routing::DurabilityType;
+#[cfg(feature = "circuit-relay")]
+use crate::circuit::routing::RouteType;
 
 extern crate test;
 
@@ -79,6 +83,10 @@ fn generate_circuits(num_circuits: i64, total_num_node: i64) -> (Vec<Circuit>, V
             roster,
             members,
             authorization_type: AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            durability: DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            route_type: RouteType::Any,
         };
         circuits.push(circuit);
     }
@@ -28,6 +28,10 @@ use std::sync::{Arc, RwLock};
 use super::error::RoutingTableReaderError;
 #[cfg(feature = "challenge-authorization")]
 use super::AuthorizationType;
+#[cfg(feature = "circuit-durability")]
+use super::DurabilityType;
+#[cfg(feature = "circuit-relay")]
+use super::RouteType;
 use super::{
     Circuit, CircuitIter, CircuitNode, CircuitNodeIter, RoutingTableReader, RoutingTableWriter,
     Service, ServiceId,
@@ -181,6 +185,10 @@ impl RoutingTableReader for RoutingTable {
                 vec![],
                 #[cfg(feature = "challenge-authorization")]
                 AuthorizationType::Trust,
+                #[cfg(feature = "circuit-durability")]
+                DurabilityType::NoDurability,
+                #[cfg(feature = "circuit-relay")]
+                RouteType::Any,
             )))
         } else {
             Ok(self
@@ -450,12 +458,20 @@ mod test {
             roster: circuit_roster0.clone(),
             members: circuit_members0.clone(),
             authorization_type: AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            durability: DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            route_type: RouteType::Any,
         };
         let circuit1 = Circuit {
             circuit_id: "345-def".to_string(),
             roster: circuit_roster1.clone(),
             members: circuit_members1.clone(),
             authorization_type: AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            durability: DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            route_type: RouteType::Any,
         };
 
         let mut expected_nodes = BTreeMap::new();
@@ -608,6 +624,10 @@ mod test {
             roster: vec![service0.clone(), service1.clone()],
             members: vec![node0.node_id.clone(), node1.node_id.clone()],
             authorization_type: AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            durability: DurabilityType::NoDurability,
+            #[cfg(feature = "circuit-relay")]
+            route_type: RouteType::Any,
         };
         let service_id0 = ServiceId::new(
             "012-abc".to_string(),
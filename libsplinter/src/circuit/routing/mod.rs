@@ -190,6 +190,10 @@ pub struct Circuit {
     roster: Vec<Service>,
     members: Vec<String>,
     authorization_type: AuthorizationType,
+    #[cfg(feature = "circuit-durability")]
+    durability: DurabilityType,
+    #[cfg(feature = "circuit-relay")]
+    route_type: RouteType,
 }
 
 impl Circuit {
@@ -200,11 +204,16 @@ impl Circuit {
     /// * `circuit_id` -  The unique ID for the circuit
     /// * `roster` - The list of services in the circuit
     /// * `members` - The list of node IDs for the members of a circuit
+    /// * `authorization_type` - How the nodes of the circuit are authorized
+    /// * `durability` - How direct messages are delivered to the other nodes of the circuit
+    /// * `route_type` - How direct messages are routed to the other nodes of the circuit
     pub fn new(
         circuit_id: String,
         roster: Vec<Service>,
         members: Vec<String>,
         #[cfg(feature = "challenge-authorization")] authorization_type: AuthorizationType,
+        #[cfg(feature = "circuit-durability")] durability: DurabilityType,
+        #[cfg(feature = "circuit-relay")] route_type: RouteType,
     ) -> Self {
         Circuit {
             circuit_id,
@@ -214,6 +223,10 @@ impl Circuit {
             authorization_type,
             #[cfg(not(feature = "challenge-authorization"))]
             authorization_type: AuthorizationType::Trust,
+            #[cfg(feature = "circuit-durability")]
+            durability,
+            #[cfg(feature = "circuit-relay")]
+            route_type,
         }
     }
 
@@ -236,6 +249,18 @@ impl Circuit {
     pub fn authorization_type(&self) -> &AuthorizationType {
         &self.authorization_type
     }
+
+    /// Returns the durability type
+    #[cfg(feature = "circuit-durability")]
+    pub fn durability(&self) -> &DurabilityType {
+        &self.durability
+    }
+
+    /// Returns the route type
+    #[cfg(feature = "circuit-relay")]
+    pub fn route_type(&self) -> &RouteType {
+        &self.route_type
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -245,6 +270,28 @@ pub enum AuthorizationType {
     Challenge,
 }
 
+/// How direct messages on a circuit are delivered to other nodes
+#[cfg(feature = "circuit-durability")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DurabilityType {
+    /// Messages are dropped if the recipient's node is not connected
+    NoDurability,
+    /// Messages are stored until the recipient's node acknowledges them, and resent in order when
+    /// the node reconnects
+    StoreAndForward,
+}
+
+/// How direct messages on a circuit are routed to other nodes
+#[cfg(feature = "circuit-relay")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RouteType {
+    /// Messages are sent directly to the recipient's node
+    Any,
+    /// Messages to a node that is not connected are relayed through another member node that is
+    /// connected
+    Relay,
+}
+
 /// The routing table representation of a node
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CircuitNode {
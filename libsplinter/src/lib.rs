@@ -68,6 +68,10 @@ macro_rules! mutex_lock_unwrap {
 
 #[cfg(feature = "admin-service")]
 pub mod admin;
+#[cfg(feature = "audit")]
+pub mod audit;
+#[cfg(feature = "backup")]
+pub mod backup;
 mod base62;
 #[cfg(feature = "biome")]
 pub mod biome;
@@ -0,0 +1,29 @@
+-- Copyright 2018-2021 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+-- Maps groups reported by an identity provider, such as OAuth group claims, to roles
+CREATE TABLE IF NOT EXISTS rbac_group_mappings (
+    group_id     TEXT    NOT NULL,
+    role_id      TEXT    NOT NULL,
+    PRIMARY KEY(group_id, role_id),
+    FOREIGN KEY (role_id) REFERENCES rbac_roles(id) ON DELETE CASCADE
+);
+
+-- The groups an identity was last reported to be a member of
+CREATE TABLE IF NOT EXISTS rbac_identity_groups (
+    identity     TEXT    NOT NULL,
+    group_id     TEXT    NOT NULL,
+    PRIMARY KEY(identity, group_id)
+);
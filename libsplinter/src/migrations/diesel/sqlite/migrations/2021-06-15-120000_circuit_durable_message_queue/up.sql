@@ -0,0 +1,31 @@
+---- Copyright 2018-2021 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+-- Sequence numbers of the durable messages exchanged with each peer node
+CREATE TABLE IF NOT EXISTS circuit_durable_peer (
+    node_id                   TEXT PRIMARY KEY,
+    last_sent                 INTEGER NOT NULL DEFAULT 0,
+    last_delivered            INTEGER NOT NULL DEFAULT 0
+);
+
+-- Durable messages that have not been acknowledged by the recipient's node
+CREATE TABLE IF NOT EXISTS circuit_durable_message (
+    recipient_node_id         TEXT NOT NULL,
+    sequence_number           INTEGER NOT NULL,
+    circuit_id                TEXT NOT NULL,
+    payload                   BINARY NOT NULL,
+    created_at                TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (recipient_node_id, sequence_number)
+);
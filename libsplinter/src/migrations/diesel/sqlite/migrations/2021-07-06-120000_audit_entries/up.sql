@@ -0,0 +1,29 @@
+-- Copyright 2018-2021 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+-- Records the requests made to the REST API and the result of authorizing them
+CREATE TABLE IF NOT EXISTS audit_entries (
+    id               INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp        BIGINT  NOT NULL,
+    identity         TEXT,
+    method           TEXT    NOT NULL,
+    endpoint         TEXT    NOT NULL,
+    permission_id    TEXT,
+    result           TEXT    NOT NULL,
+    status_code      INTEGER NOT NULL,
+    action           TEXT,
+    circuit_id       TEXT,
+    payload          BINARY
+);
@@ -0,0 +1,255 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::{InvalidArgumentError, InvalidStateError};
+#[cfg(feature = "oauth-profile")]
+use crate::oauth::AzureProfileProvider;
+use crate::oauth::{
+    builder::OAuthClientBuilder, error::OAuthClientBuildError, store::InflightOAuthRequestStore,
+    AzureSubjectProvider, OAuthClient,
+};
+
+/// The base URL of the Microsoft identity platform's endpoints
+const AZURE_LOGIN_URL: &str = "https://login.microsoftonline.com";
+/// The base URL of the Microsoft Graph API
+const GRAPH_URL: &str = "https://graph.microsoft.com";
+/// The tenants that allow users from more than one Azure AD tenant to sign in. Tokens from these
+/// tenants are not restricted to a single organization, so they are not accepted.
+const MULTI_TENANTS: &[&str] = &["common", "organizations", "consumers"];
+/// The scopes required to get OpenID user information, a refresh token, and access to the
+/// Microsoft Graph API
+const DEFAULT_SCOPES: &[&str] = &["openid", "profile", "email", "offline_access", "User.Read"];
+
+/// Builds a new `OAuthClient` for Azure Active Directory.
+///
+/// Unlike the generic OpenID builder, the Azure builder does not load a discovery document; the
+/// authorization and token URLs are derived from the tenant, which must be set explicitly so that
+/// only users of that tenant can sign in. Subjects, groups and profiles are read from the
+/// Microsoft Graph API.
+pub struct AzureOAuthClientBuilder {
+    tenant_id: Option<String>,
+    inner: OAuthClientBuilder,
+}
+
+impl AzureOAuthClientBuilder {
+    /// Constructs an Azure OAuthClient builder.
+    pub fn new() -> Self {
+        Self {
+            tenant_id: None,
+            inner: OAuthClientBuilder::new()
+                .with_scopes(DEFAULT_SCOPES.iter().map(ToString::to_string).collect()),
+        }
+    }
+
+    /// Sets the Azure AD tenant that users sign in with. This may be a tenant ID or a tenant
+    /// domain name; the multi-tenant `common`, `organizations` and `consumers` tenants are not
+    /// supported.
+    pub fn with_tenant_id(mut self, tenant_id: String) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
+    /// Sets the client ID for the OAuth2 provider.
+    pub fn with_client_id(self, client_id: String) -> Self {
+        Self {
+            tenant_id: self.tenant_id,
+            inner: self.inner.with_client_id(client_id),
+        }
+    }
+
+    /// Sets the client secret for the OAuth2 provider.
+    pub fn with_client_secret(self, client_secret: String) -> Self {
+        Self {
+            tenant_id: self.tenant_id,
+            inner: self.inner.with_client_secret(client_secret),
+        }
+    }
+
+    /// Sets the redirect URL for the OAuth2 provider.
+    pub fn with_redirect_url(self, redirect_url: String) -> Self {
+        Self {
+            tenant_id: self.tenant_id,
+            inner: self.inner.with_redirect_url(redirect_url),
+        }
+    }
+
+    /// Sets additional scopes to request from the OAuth2 provider, such as
+    /// `GroupMember.Read.All` for reading users' group memberships.
+    pub fn with_scopes(self, scopes: Vec<String>) -> Self {
+        Self {
+            tenant_id: self.tenant_id,
+            inner: self.inner.with_scopes(scopes),
+        }
+    }
+
+    /// Sets the in-flight request store in order to store values between requests to and from the
+    /// OAuth2 provider.
+    pub fn with_inflight_request_store(
+        self,
+        inflight_request_store: Box<dyn InflightOAuthRequestStore>,
+    ) -> Self {
+        Self {
+            tenant_id: self.tenant_id,
+            inner: self
+                .inner
+                .with_inflight_request_store(inflight_request_store),
+        }
+    }
+
+    /// Builds an OAuthClient.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OAuthClientBuildError`] if there are required fields missing, the tenant is
+    /// a multi-tenant tenant, or any URL's provided are invalid.
+    pub fn build(self) -> Result<OAuthClient, OAuthClientBuildError> {
+        let tenant_id = self.tenant_id.ok_or_else(|| {
+            InvalidStateError::with_message(
+                "A tenant ID is required to successfully build an Azure OAuthClient".into(),
+            )
+        })?;
+        if MULTI_TENANTS.contains(&tenant_id.to_lowercase().as_str()) {
+            return Err(InvalidArgumentError::new(
+                "tenant_id".into(),
+                format!(
+                    "'{}' allows users from any tenant to sign in; a single tenant is required",
+                    tenant_id
+                ),
+            )
+            .into());
+        }
+
+        // Allowing unused_mut because inner must be mutable if experimental feature
+        // oauth-profile is enabled, if feature is removed unused_mut notation can be removed
+        #[allow(unused_mut)]
+        let mut inner = self
+            .inner
+            .with_auth_url(format!(
+                "{}/{}/oauth2/v2.0/authorize",
+                AZURE_LOGIN_URL, tenant_id
+            ))
+            .with_token_url(format!(
+                "{}/{}/oauth2/v2.0/token",
+                AZURE_LOGIN_URL, tenant_id
+            ))
+            .with_subject_provider(Box::new(AzureSubjectProvider::new(GRAPH_URL.into())));
+
+        #[cfg(feature = "oauth-profile")]
+        {
+            inner =
+                inner.with_profile_provider(Box::new(AzureProfileProvider::new(GRAPH_URL.into())));
+        }
+
+        inner.build()
+    }
+}
+
+impl Default for AzureOAuthClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use url::Url;
+
+    use crate::oauth::store::MemoryInflightOAuthRequestStore;
+
+    const CLIENT_ID: &str = "client_id";
+    const CLIENT_SECRET: &str = "client_secret";
+    const EXTRA_SCOPE: &str = "GroupMember.Read.All";
+    const REDIRECT_URL: &str = "http://oauth/callback";
+    const TENANT_ID: &str = "contoso.onmicrosoft.com";
+
+    /// Verifies that the `AzureOAuthClientBuilder` builds an OAuth client for the configured
+    /// tenant with the default and additional scopes.
+    ///
+    /// 1. Create a builder and set all of its values
+    /// 2. Verify the values are correctly set for the builder
+    /// 3. Build the client
+    /// 4. Verify that the resulting client requests the default and additional scopes
+    /// 5. Verify that the authorization URL is the tenant's authorization endpoint
+    #[test]
+    fn tenant_client() {
+        let builder = AzureOAuthClientBuilder::new()
+            .with_tenant_id(TENANT_ID.into())
+            .with_client_id(CLIENT_ID.into())
+            .with_client_secret(CLIENT_SECRET.into())
+            .with_scopes(vec![EXTRA_SCOPE.into()])
+            .with_inflight_request_store(Box::new(MemoryInflightOAuthRequestStore::new()))
+            .with_redirect_url(REDIRECT_URL.into());
+
+        assert_eq!(builder.tenant_id, Some(TENANT_ID.into()));
+        assert_eq!(builder.inner.client_id, Some(CLIENT_ID.into()));
+        assert_eq!(builder.inner.client_secret, Some(CLIENT_SECRET.into()));
+        assert_eq!(builder.inner.redirect_url, Some(REDIRECT_URL.into()));
+        assert!(builder.inner.inflight_request_store.is_some());
+
+        let client = builder.build().expect("Failed to build Azure client");
+
+        assert_eq!(
+            client
+                .scopes
+                .iter()
+                .map(|scope| scope.as_str())
+                .collect::<HashSet<_>>(),
+            DEFAULT_SCOPES
+                .iter()
+                .cloned()
+                .chain(std::iter::once(EXTRA_SCOPE))
+                .collect::<HashSet<_>>(),
+        );
+
+        let generated_auth_url = Url::parse(
+            &client
+                .get_authorization_url("client_redirect_url".into())
+                .expect("Failed to generate auth URL"),
+        )
+        .expect("Failed to parse generated auth URL");
+        assert_eq!(
+            generated_auth_url.path(),
+            format!("/{}/oauth2/v2.0/authorize", TENANT_ID)
+        );
+    }
+
+    /// Verifies that the `AzureOAuthClientBuilder` fails to build if no tenant is set, or if the
+    /// tenant allows users from any tenant to sign in.
+    #[test]
+    fn single_tenant_required() {
+        let builder = || {
+            AzureOAuthClientBuilder::new()
+                .with_client_id(CLIENT_ID.into())
+                .with_client_secret(CLIENT_SECRET.into())
+                .with_inflight_request_store(Box::new(MemoryInflightOAuthRequestStore::new()))
+                .with_redirect_url(REDIRECT_URL.into())
+        };
+
+        assert!(matches!(
+            builder().build(),
+            Err(OAuthClientBuildError::InvalidStateError(_))
+        ));
+
+        for tenant in &["common", "organizations", "Consumers"] {
+            assert!(matches!(
+                builder().with_tenant_id(tenant.to_string()).build(),
+                Err(OAuthClientBuildError::InvalidArgumentError(_))
+            ));
+        }
+    }
+}
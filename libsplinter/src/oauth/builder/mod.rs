@@ -14,6 +14,8 @@
 
 //! Builders for [OAuthClient](crate::oauth::OAuthClient) structs.
 
+#[cfg(feature = "oauth-azure")]
+mod azure;
 mod github;
 mod openid;
 
@@ -24,6 +26,8 @@ use super::error::OAuthClientBuildError;
 use super::ProfileProvider;
 use super::{new_basic_client, store::InflightOAuthRequestStore, OAuthClient, SubjectProvider};
 
+#[cfg(feature = "oauth-azure")]
+pub use azure::AzureOAuthClientBuilder;
 pub use github::GithubOAuthClientBuilder;
 pub use openid::OpenIdOAuthClientBuilder;
 
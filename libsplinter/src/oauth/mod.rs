@@ -35,10 +35,16 @@ use crate::error::{InternalError, InvalidArgumentError};
 
 use store::InflightOAuthRequestStore;
 
+#[cfg(feature = "oauth-azure")]
+pub use builder::AzureOAuthClientBuilder;
 pub use builder::{GithubOAuthClientBuilder, OAuthClientBuilder, OpenIdOAuthClientBuilder};
 pub use error::OAuthClientBuildError;
+#[cfg(all(feature = "oauth-azure", feature = "oauth-profile"))]
+pub use profile::AzureProfileProvider;
 #[cfg(feature = "oauth-profile")]
 pub use profile::{GithubProfileProvider, OpenIdProfileProvider, ProfileProvider};
+#[cfg(feature = "oauth-azure")]
+pub use subject::AzureSubjectProvider;
 pub use subject::{GithubSubjectProvider, OpenIdSubjectProvider, SubjectProvider};
 
 /// An OAuth2 client for Splinter
@@ -184,6 +190,17 @@ impl OAuthClient {
             .get_subject(token_response.access_token().secret())?
             .ok_or_else(|| InternalError::with_message("subject not found".into()))?;
 
+        // Group membership is optional, so failing to read it should not prevent the login
+        let groups = self
+            .get_groups(token_response.access_token().secret())
+            .unwrap_or_else(|err| {
+                warn!(
+                    "Unable to get OAuth groups, continuing without groups: {}",
+                    err
+                );
+                vec![]
+            });
+
         let user_info = UserInfo {
             access_token: token_response.access_token().secret().into(),
             expires_in: token_response.expires_in(),
@@ -191,6 +208,7 @@ impl OAuthClient {
                 .refresh_token()
                 .map(|token| token.secret().into()),
             subject,
+            groups,
             #[cfg(feature = "oauth-profile")]
             profile,
         };
@@ -200,10 +218,25 @@ impl OAuthClient {
 
     /// Exchanges the given refresh token for an access token.
     pub fn exchange_refresh_token(&self, refresh_token: String) -> Result<String, InternalError> {
+        self.exchange_refresh_token_with_rotation(refresh_token)
+            .map(|(access_token, _)| access_token)
+    }
+
+    /// Exchanges the given refresh token for an access token and, if the provider rotates refresh
+    /// tokens (as Azure does), the refresh token that replaces the given one.
+    pub fn exchange_refresh_token_with_rotation(
+        &self,
+        refresh_token: String,
+    ) -> Result<(String, Option<String>), InternalError> {
         self.client
             .exchange_refresh_token(&RefreshToken::new(refresh_token))
             .request(http_client)
-            .map(|response| response.access_token().secret().into())
+            .map(|response| {
+                (
+                    response.access_token().secret().into(),
+                    response.refresh_token().map(|token| token.secret().into()),
+                )
+            })
             .map_err(|err| {
                 InternalError::with_message(format!(
                     "failed to make refresh token exchange request: {}",
@@ -217,6 +250,12 @@ impl OAuthClient {
     pub fn get_subject(&self, access_token: &str) -> Result<Option<String>, InternalError> {
         self.subject_provider.get_subject(access_token)
     }
+
+    /// Gets the IDs of the groups that the user the given access token is for is a member of.
+    /// Providers that do not report group membership will always return an empty list.
+    pub fn get_groups(&self, access_token: &str) -> Result<Vec<String>, InternalError> {
+        self.subject_provider.get_groups(access_token)
+    }
 }
 
 fn new_basic_client(
@@ -261,6 +300,8 @@ pub struct UserInfo {
     refresh_token: Option<String>,
     /// The user's subject identifier
     subject: String,
+    /// The IDs of the groups the user is a member of
+    groups: Vec<String>,
     /// The user's profile details
     #[cfg(feature = "oauth-profile")]
     profile: Profile,
@@ -289,6 +330,12 @@ impl UserInfo {
         &self.subject
     }
 
+    /// Gets the IDs of the groups the user is a member of. Not all providers report group
+    /// membership, so this may be empty for some providers.
+    pub fn groups(&self) -> &[String] {
+        &self.groups
+    }
+
     /// Gets the user's profile details
     #[cfg(feature = "oauth-profile")]
     pub fn profile(&self) -> &Profile {
@@ -307,7 +354,8 @@ impl std::fmt::Debug for UserInfo {
                 "refresh_token",
                 &self.refresh_token.as_deref().map(|_| "<Redacted>"),
             )
-            .field("subject", &self.subject);
+            .field("subject", &self.subject)
+            .field("groups", &self.groups);
 
         #[cfg(feature = "oauth-profile")]
         debug_struct.field("profile", &self.profile);
@@ -0,0 +1,271 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A profile provider that looks up Azure Active Directory profile information
+
+use base64::encode;
+use reqwest::{blocking::Client, StatusCode};
+
+use crate::error::InternalError;
+use crate::oauth::Profile;
+
+use super::openid::OpenIdProfileResponse;
+use super::ProfileProvider;
+
+/// Retrieves profile details from the Microsoft Graph API
+///
+/// The profile details are read from the Graph API's OpenID userinfo endpoint. Azure does not
+/// include a picture URL in the userinfo response, so the user's profile photo is downloaded from
+/// the Graph API and returned as base64-encoded image data.
+#[derive(Clone)]
+pub struct AzureProfileProvider {
+    graph_url: String,
+}
+
+impl AzureProfileProvider {
+    /// Creates a new `AzureProfileProvider`
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_url` - The base URL of the Microsoft Graph API (e.g.
+    ///   `https://graph.microsoft.com`)
+    pub fn new(graph_url: String) -> AzureProfileProvider {
+        AzureProfileProvider { graph_url }
+    }
+
+    /// Gets the user's profile photo as base64-encoded image data. Users are not required to have
+    /// a photo, so failing to get one is not an error.
+    fn get_picture(&self, access_token: &str) -> Result<Option<String>, InternalError> {
+        let response = match Client::builder()
+            .build()
+            .map_err(|err| InternalError::from_source(err.into()))?
+            .get(&format!("{}/v1.0/me/photo/$value", self.graph_url))
+            .header("Authorization", format!("Bearer {}", access_token))
+            .send()
+        {
+            Ok(response) => response,
+            Err(err) => {
+                warn!(
+                    "Failed to get user profile picture from Microsoft Graph API: {}",
+                    err
+                );
+                return Ok(None);
+            }
+        };
+
+        match response.status() {
+            status if status.is_success() => match response.bytes() {
+                Ok(image_data) => Ok(Some(encode(image_data.to_vec()))),
+                Err(_) => {
+                    warn!("Failed to get bytes from Microsoft Graph HTTP response");
+                    Ok(None)
+                }
+            },
+            StatusCode::NOT_FOUND => Ok(None),
+            status_code => {
+                warn!(
+                    "Microsoft Graph API profile picture request failed: {}",
+                    status_code
+                );
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl ProfileProvider for AzureProfileProvider {
+    fn get_profile(&self, access_token: &str) -> Result<Option<Profile>, InternalError> {
+        let response = Client::builder()
+            .build()
+            .map_err(|err| InternalError::from_source(err.into()))?
+            .get(&format!("{}/oidc/userinfo", self.graph_url))
+            .header("Authorization", format!("Bearer {}", access_token))
+            .send()
+            .map_err(|err| InternalError::from_source(err.into()))?;
+
+        if !response.status().is_success() {
+            match response.status() {
+                StatusCode::UNAUTHORIZED => return Ok(None),
+                status_code => {
+                    return Err(InternalError::with_message(format!(
+                        "Received unexpected response code: {}",
+                        status_code
+                    )))
+                }
+            }
+        }
+
+        let mut user_profile = response
+            .json::<OpenIdProfileResponse>()
+            .map_err(|_| InternalError::with_message("Received unexpected response body".into()))?;
+
+        user_profile.picture = self.get_picture(access_token)?;
+
+        Ok(Some(Profile::from(user_profile)))
+    }
+
+    fn clone_box(&self) -> Box<dyn ProfileProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "actix", feature = "actix-web", feature = "futures"))]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc::channel;
+    use std::thread::JoinHandle;
+
+    use actix::System;
+    use actix_web::{dev::Server, web, App, HttpRequest, HttpResponse, HttpServer};
+    use futures::Future;
+
+    const WITH_PHOTO_TOKEN: &str = "with_photo";
+    const WITHOUT_PHOTO_TOKEN: &str = "without_photo";
+    const SUB: &str = "sub";
+    const NAME: &str = "name";
+    const EMAIL: &str = "email";
+    const PHOTO: &[u8] = b"photo";
+
+    /// Verifies that the Azure profile provider returns the userinfo details along with the
+    /// user's base64-encoded profile photo.
+    ///
+    /// 1. Start the mock Graph API server
+    /// 2. Get the profile for a user with a profile photo
+    /// 3. Verify that the profile details and the encoded photo are correct
+    /// 4. Shutdown the Graph API server
+    #[test]
+    fn profile_with_photo() {
+        let (shutdown_handle, address) = run_mock_graph_server("profile_with_photo");
+
+        let profile = AzureProfileProvider::new(address)
+            .get_profile(WITH_PHOTO_TOKEN)
+            .expect("Failed to get profile")
+            .expect("Profile not found");
+
+        assert_eq!(&profile.subject, SUB);
+        assert_eq!(profile.name.as_deref(), Some(NAME));
+        assert_eq!(profile.email.as_deref(), Some(EMAIL));
+        assert_eq!(profile.picture, Some(encode(PHOTO)));
+
+        shutdown_handle.shutdown();
+    }
+
+    /// Verifies that the Azure profile provider returns the profile without a picture when the
+    /// user has no profile photo, and `Ok(None)` for an unknown token.
+    ///
+    /// 1. Start the mock Graph API server
+    /// 2. Get the profile for a user without a profile photo and verify that there is no picture
+    /// 3. Get the profile for an unknown token and verify that `None` is returned
+    /// 4. Shutdown the Graph API server
+    #[test]
+    fn profile_without_photo() {
+        let (shutdown_handle, address) = run_mock_graph_server("profile_without_photo");
+        let profile_provider = AzureProfileProvider::new(address);
+
+        let profile = profile_provider
+            .get_profile(WITHOUT_PHOTO_TOKEN)
+            .expect("Failed to get profile")
+            .expect("Profile not found");
+
+        assert_eq!(&profile.subject, SUB);
+        assert!(profile.picture.is_none());
+
+        assert!(profile_provider
+            .get_profile("unknown_token")
+            .expect("Failed to get profile")
+            .is_none());
+
+        shutdown_handle.shutdown();
+    }
+
+    /// Runs a mock Graph API server and returns its shutdown handle along with the address the
+    /// server is running on.
+    fn run_mock_graph_server(test_name: &str) -> (GraphServerShutdownHandle, String) {
+        let (tx, rx) = channel();
+
+        let instance_name = format!("Graph-Server-{}", test_name);
+        let join_handle = std::thread::Builder::new()
+            .name(instance_name.clone())
+            .spawn(move || {
+                let sys = System::new(instance_name);
+                let server = HttpServer::new(|| {
+                    App::new()
+                        .service(web::resource("/oidc/userinfo").to(userinfo_endpoint))
+                        .service(web::resource("/v1.0/me/photo/$value").to(photo_endpoint))
+                })
+                .bind("127.0.0.1:0")
+                .expect("Failed to bind Graph server");
+                let address = format!("http://127.0.0.1:{}", server.addrs()[0].port());
+                let server = server.disable_signals().system_exit().start();
+                tx.send((server, address)).expect("Failed to send server");
+                sys.run().expect("Graph server runtime failed");
+            })
+            .expect("Failed to spawn Graph server thread");
+
+        let (server, address) = rx.recv().expect("Failed to receive server");
+
+        (GraphServerShutdownHandle(server, join_handle), address)
+    }
+
+    /// Returns the bearer token of the request's authorization header
+    fn bearer_token(req: &HttpRequest) -> Option<&str> {
+        req.headers()
+            .get("Authorization")
+            .and_then(|auth| auth.to_str().ok())
+            .and_then(|auth_str| auth_str.strip_prefix("Bearer "))
+    }
+
+    /// The handler for the Graph API's userinfo endpoint.
+    fn userinfo_endpoint(req: HttpRequest) -> HttpResponse {
+        match bearer_token(&req) {
+            Some(token) if token == WITH_PHOTO_TOKEN || token == WITHOUT_PHOTO_TOKEN => {
+                HttpResponse::Ok()
+                    .content_type("application/json")
+                    .json(json!({
+                        "sub": SUB,
+                        "name": NAME,
+                        "email": EMAIL,
+                    }))
+            }
+            Some(_) => HttpResponse::Unauthorized().finish(),
+            None => HttpResponse::BadRequest().finish(),
+        }
+    }
+
+    /// The handler for the Graph API's profile photo endpoint.
+    fn photo_endpoint(req: HttpRequest) -> HttpResponse {
+        match bearer_token(&req) {
+            Some(token) if token == WITH_PHOTO_TOKEN => {
+                HttpResponse::Ok().content_type("image/jpeg").body(PHOTO)
+            }
+            Some(token) if token == WITHOUT_PHOTO_TOKEN => HttpResponse::NotFound().finish(),
+            Some(_) => HttpResponse::Unauthorized().finish(),
+            None => HttpResponse::BadRequest().finish(),
+        }
+    }
+
+    struct GraphServerShutdownHandle(Server, JoinHandle<()>);
+
+    impl GraphServerShutdownHandle {
+        pub fn shutdown(self) {
+            self.0
+                .stop(false)
+                .wait()
+                .expect("Failed to stop Graph server");
+            self.1.join().expect("Graph server thread failed");
+        }
+    }
+}
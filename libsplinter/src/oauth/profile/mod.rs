@@ -14,12 +14,16 @@
 
 //! APIs and implementations for fetching profile details from OAuth servers
 
+#[cfg(feature = "oauth-azure")]
+mod azure;
 mod github;
 mod openid;
 
 use crate::error::InternalError;
 use crate::oauth::Profile;
 
+#[cfg(feature = "oauth-azure")]
+pub use azure::AzureProfileProvider;
 pub use github::GithubProfileProvider;
 pub use openid::OpenIdProfileProvider;
 
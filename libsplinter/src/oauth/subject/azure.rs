@@ -0,0 +1,282 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A subject provider that looks up Azure Active Directory subject identifiers and groups
+
+use reqwest::{blocking::Client, StatusCode};
+
+use crate::error::InternalError;
+
+use super::SubjectProvider;
+
+/// Retrieves a subject identifier and group memberships from the Microsoft Graph API
+///
+/// The subject is the `sub` value of the Graph API's OpenID userinfo endpoint, which is the same
+/// value that is returned when Azure is used as a generic OpenID provider. Groups are the object
+/// IDs of the Azure AD groups the user is a member of, including transitive memberships. Reading
+/// group memberships requires the app to be granted the `GroupMember.Read.All` permission; if it
+/// has not been granted, the user is treated as having no groups.
+#[derive(Clone)]
+pub struct AzureSubjectProvider {
+    graph_url: String,
+}
+
+impl AzureSubjectProvider {
+    /// Creates a new `AzureSubjectProvider`
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_url` - The base URL of the Microsoft Graph API (e.g.
+    ///   `https://graph.microsoft.com`)
+    pub fn new(graph_url: String) -> AzureSubjectProvider {
+        AzureSubjectProvider { graph_url }
+    }
+}
+
+impl SubjectProvider for AzureSubjectProvider {
+    fn get_subject(&self, access_token: &str) -> Result<Option<String>, InternalError> {
+        let response = Client::builder()
+            .build()
+            .map_err(|err| InternalError::from_source(err.into()))?
+            .get(&format!("{}/oidc/userinfo", self.graph_url))
+            .header("Authorization", format!("Bearer {}", access_token))
+            .send()
+            .map_err(|err| InternalError::from_source(err.into()))?;
+
+        if !response.status().is_success() {
+            match response.status() {
+                StatusCode::UNAUTHORIZED => return Ok(None),
+                status_code => {
+                    return Err(InternalError::with_message(format!(
+                        "Received unexpected response code: {}",
+                        status_code
+                    )))
+                }
+            }
+        }
+
+        let subject = response
+            .json::<UserResponse>()
+            .map_err(|_| InternalError::with_message("Received unexpected response body".into()))?
+            .sub;
+
+        Ok(Some(subject))
+    }
+
+    fn get_groups(&self, access_token: &str) -> Result<Vec<String>, InternalError> {
+        let response = Client::builder()
+            .build()
+            .map_err(|err| InternalError::from_source(err.into()))?
+            .post(&format!("{}/v1.0/me/getMemberGroups", self.graph_url))
+            .header("Authorization", format!("Bearer {}", access_token))
+            .json(&json!({ "securityEnabledOnly": false }))
+            .send()
+            .map_err(|err| InternalError::from_source(err.into()))?;
+
+        if !response.status().is_success() {
+            match response.status() {
+                StatusCode::FORBIDDEN => {
+                    warn!(
+                        "Unable to read Azure group memberships; the app has not been granted \
+                         permission to read them"
+                    );
+                    return Ok(vec![]);
+                }
+                status_code => {
+                    return Err(InternalError::with_message(format!(
+                        "Received unexpected response code: {}",
+                        status_code
+                    )))
+                }
+            }
+        }
+
+        let groups = response
+            .json::<MemberGroupsResponse>()
+            .map_err(|_| InternalError::with_message("Received unexpected response body".into()))?
+            .value;
+
+        Ok(groups)
+    }
+
+    fn clone_box(&self) -> Box<dyn SubjectProvider> {
+        Box::new(self.clone())
+    }
+}
+
+/// Deserializes the userinfo response
+#[derive(Debug, Deserialize)]
+struct UserResponse {
+    sub: String,
+}
+
+/// Deserializes the getMemberGroups response
+#[derive(Debug, Deserialize)]
+struct MemberGroupsResponse {
+    value: Vec<String>,
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "actix", feature = "actix-web", feature = "futures"))]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc::channel;
+    use std::thread::JoinHandle;
+
+    use actix::System;
+    use actix_web::{dev::Server, web, App, HttpRequest, HttpResponse, HttpServer};
+    use futures::Future;
+
+    const ACCESS_TOKEN: &str = "access_token";
+    const FORBIDDEN_TOKEN: &str = "forbidden_token";
+    const SUBJECT_IDENTIFIER: &str = "AAAAAAAAAAAAAAAAAAAQEh-c1Zkltuwhd-12345";
+    const GROUP_1: &str = "fc4a3b5e-1b4c-4b8e-9d3a-6c0a4f3e2d11";
+    const GROUP_2: &str = "0b9e6f1c-7a2d-4e5f-8c3b-2d1e0f9a8b77";
+
+    /// Verifies that the `AzureSubjectProvider` `get_subject` method returns the `sub` value from
+    /// the Graph API's userinfo endpoint, and `None` for an invalid access token.
+    ///
+    /// 1. Start the mock Graph API server
+    /// 2. Call `get_subject` with a valid access token and verify the subject is returned
+    /// 3. Call `get_subject` with an invalid access token and verify `None` is returned
+    /// 4. Stop the mock Graph API server
+    #[test]
+    fn get_subject() {
+        let (shutdown_handle, address) = run_mock_graph_server("get_subject");
+        let subject_provider = AzureSubjectProvider::new(address);
+
+        assert_eq!(
+            subject_provider
+                .get_subject(ACCESS_TOKEN)
+                .expect("Failed to retrieve subject"),
+            Some(SUBJECT_IDENTIFIER.to_string())
+        );
+        assert!(subject_provider
+            .get_subject("invalid_token")
+            .expect("Failed to check invalid token")
+            .is_none());
+
+        shutdown_handle.shutdown();
+    }
+
+    /// Verifies that the `AzureSubjectProvider` `get_groups` method returns the group IDs from
+    /// the Graph API's getMemberGroups endpoint, and no groups if the app is not permitted to read
+    /// group memberships.
+    ///
+    /// 1. Start the mock Graph API server
+    /// 2. Call `get_groups` with a valid access token and verify the groups are returned
+    /// 3. Call `get_groups` with an access token that is forbidden from reading groups and verify
+    ///    that an empty list is returned
+    /// 4. Stop the mock Graph API server
+    #[test]
+    fn get_groups() {
+        let (shutdown_handle, address) = run_mock_graph_server("get_groups");
+        let subject_provider = AzureSubjectProvider::new(address);
+
+        assert_eq!(
+            subject_provider
+                .get_groups(ACCESS_TOKEN)
+                .expect("Failed to retrieve groups"),
+            vec![GROUP_1.to_string(), GROUP_2.to_string()]
+        );
+        assert!(subject_provider
+            .get_groups(FORBIDDEN_TOKEN)
+            .expect("Failed to retrieve groups with forbidden token")
+            .is_empty());
+
+        shutdown_handle.shutdown();
+    }
+
+    /// Runs a mock Graph API server and returns its shutdown handle along with the address the
+    /// server is running on.
+    fn run_mock_graph_server(test_name: &str) -> (GraphServerShutdownHandle, String) {
+        let (tx, rx) = channel();
+
+        let instance_name = format!("Graph-Server-{}", test_name);
+        let join_handle = std::thread::Builder::new()
+            .name(instance_name.clone())
+            .spawn(move || {
+                let sys = System::new(instance_name);
+                let server = HttpServer::new(|| {
+                    App::new()
+                        .service(web::resource("/oidc/userinfo").to(userinfo_endpoint))
+                        .service(
+                            web::resource("/v1.0/me/getMemberGroups").to(member_groups_endpoint),
+                        )
+                })
+                .bind("127.0.0.1:0")
+                .expect("Failed to bind Graph server");
+                let address = format!("http://127.0.0.1:{}", server.addrs()[0].port());
+                let server = server.disable_signals().system_exit().start();
+                tx.send((server, address)).expect("Failed to send server");
+                sys.run().expect("Graph server runtime failed");
+            })
+            .expect("Failed to spawn Graph server thread");
+
+        let (server, address) = rx.recv().expect("Failed to receive server");
+
+        (GraphServerShutdownHandle(server, join_handle), address)
+    }
+
+    /// Returns the value of the request's authorization header
+    fn authorization(request: &HttpRequest) -> String {
+        request
+            .headers()
+            .get("Authorization")
+            .expect("Invalid request, missing authorization header")
+            .to_str()
+            .expect("Unable to get authorization header value")
+            .to_string()
+    }
+
+    /// A handler for the Graph API's userinfo endpoint that returns the subject for the valid
+    /// access token and an "unauthorized" response otherwise.
+    fn userinfo_endpoint(request: HttpRequest) -> HttpResponse {
+        if authorization(&request) == format!("Bearer {}", ACCESS_TOKEN) {
+            HttpResponse::Ok()
+                .content_type("application/json")
+                .json(json!({ "sub": SUBJECT_IDENTIFIER }))
+        } else {
+            HttpResponse::Unauthorized().finish()
+        }
+    }
+
+    /// A handler for the Graph API's getMemberGroups endpoint that returns the groups for the
+    /// valid access token and a "forbidden" response for the forbidden access token.
+    fn member_groups_endpoint(request: HttpRequest) -> HttpResponse {
+        let authorization = authorization(&request);
+        if authorization == format!("Bearer {}", ACCESS_TOKEN) {
+            HttpResponse::Ok()
+                .content_type("application/json")
+                .json(json!({ "value": [GROUP_1, GROUP_2] }))
+        } else if authorization == format!("Bearer {}", FORBIDDEN_TOKEN) {
+            HttpResponse::Forbidden().finish()
+        } else {
+            HttpResponse::Unauthorized().finish()
+        }
+    }
+
+    struct GraphServerShutdownHandle(Server, JoinHandle<()>);
+
+    impl GraphServerShutdownHandle {
+        pub fn shutdown(self) {
+            self.0
+                .stop(false)
+                .wait()
+                .expect("Failed to stop Graph server");
+            self.1.join().expect("Graph server thread failed");
+        }
+    }
+}
@@ -14,11 +14,15 @@
 
 //! APIs and implementations for fetching subject identifiers from OAuth servers
 
+#[cfg(feature = "oauth-azure")]
+mod azure;
 mod github;
 mod openid;
 
 use crate::error::InternalError;
 
+#[cfg(feature = "oauth-azure")]
+pub use azure::AzureSubjectProvider;
 pub use github::GithubSubjectProvider;
 pub use openid::OpenIdSubjectProvider;
 
@@ -28,6 +32,13 @@ pub trait SubjectProvider: Send + Sync {
     /// `Ok(None)` if the access token could not be resolved to a subject.
     fn get_subject(&self, access_token: &str) -> Result<Option<String>, InternalError>;
 
+    /// Attempts to get the IDs of the groups that the account the given access token is for is a
+    /// member of. Providers that do not report group membership return an empty list, which is
+    /// the default.
+    fn get_groups(&self, _access_token: &str) -> Result<Vec<String>, InternalError> {
+        Ok(vec![])
+    }
+
     /// Clone implementation for `SubjectProvider`. The implementation of the `Clone` trait for
     /// `Box<dyn SubjectProvider>` calls this method.
     fn clone_box(&self) -> Box<dyn SubjectProvider>;
@@ -33,8 +33,10 @@ impl OpenIdSubjectProvider {
     }
 }
 
-impl SubjectProvider for OpenIdSubjectProvider {
-    fn get_subject(&self, access_token: &str) -> Result<Option<String>, InternalError> {
+impl OpenIdSubjectProvider {
+    /// Gets the user info for the given access token, or `None` if the access token is not
+    /// authorized.
+    fn get_user_info(&self, access_token: &str) -> Result<Option<UserResponse>, InternalError> {
         let response = Client::builder()
             .build()
             .map_err(|err| InternalError::from_source(err.into()))?
@@ -55,12 +57,33 @@ impl SubjectProvider for OpenIdSubjectProvider {
             }
         }
 
-        let subject = response
+        response
             .json::<UserResponse>()
-            .map_err(|_| InternalError::with_message("Received unexpected response body".into()))?
-            .sub;
+            .map(Some)
+            .map_err(|_| InternalError::with_message("Received unexpected response body".into()))
+    }
+}
+
+impl SubjectProvider for OpenIdSubjectProvider {
+    fn get_subject(&self, access_token: &str) -> Result<Option<String>, InternalError> {
+        Ok(self
+            .get_user_info(access_token)?
+            .map(|user_response| user_response.sub))
+    }
 
-        Ok(Some(subject))
+    /// Returns the values of the user info's `groups` and `roles` claims. Providers only include
+    /// these claims if they have been configured to do so.
+    fn get_groups(&self, access_token: &str) -> Result<Vec<String>, InternalError> {
+        Ok(self
+            .get_user_info(access_token)?
+            .map(|user_response| {
+                user_response
+                    .groups
+                    .into_iter()
+                    .chain(user_response.roles.into_iter())
+                    .collect()
+            })
+            .unwrap_or_default())
     }
 
     fn clone_box(&self) -> Box<dyn SubjectProvider> {
@@ -72,6 +95,10 @@ impl SubjectProvider for OpenIdSubjectProvider {
 #[derive(Debug, Deserialize)]
 struct UserResponse {
     sub: String,
+    #[serde(default)]
+    groups: Vec<String>,
+    #[serde(default)]
+    roles: Vec<String>,
 }
 
 #[cfg(test)]
@@ -178,6 +205,47 @@ mod tests {
         shutdown_handle.shutdown();
     }
 
+    /// Verifies that the `OpenIdSubjectProvider` `get_groups` method returns the values of the
+    /// `groups` and `roles` claims, and no groups if the claims are not present.
+    ///
+    /// 1. Start a mock Openid server whose user info includes `groups` and `roles` claims
+    /// 2. Call `get_groups` and verify that the values of both claims are returned
+    /// 3. Start a mock Openid server whose user info does not include the claims
+    /// 4. Call `get_groups` and verify that no groups are returned
+    /// 5. Stop the mock Openid servers
+    #[test]
+    fn get_groups() {
+        let (shutdown_handle, address) =
+            run_mock_openid_server("get_groups", groups_user_info_endpoint);
+        let subject_provider =
+            OpenIdSubjectProvider::new(format!("{}{}", address, USER_INFO_ENDPOINT));
+
+        assert_eq!(
+            subject_provider
+                .get_groups(ACCESS_TOKEN)
+                .expect("Failed to retrieve groups"),
+            vec![
+                "group-1".to_string(),
+                "group-2".to_string(),
+                "role-1".to_string()
+            ]
+        );
+
+        shutdown_handle.shutdown();
+
+        let (shutdown_handle, address) =
+            run_mock_openid_server("get_groups_no_claims", user_info_endpoint);
+        let subject_provider =
+            OpenIdSubjectProvider::new(format!("{}{}", address, USER_INFO_ENDPOINT));
+
+        assert!(subject_provider
+            .get_groups(ACCESS_TOKEN)
+            .expect("Failed to retrieve groups")
+            .is_empty());
+
+        shutdown_handle.shutdown();
+    }
+
     /// Runs a mock Openid server to mimick an Openid OAuth provider. Recieves a test name
     /// and a function for handling requests to the user_info endpoint. Returns its
     /// shutdown handle along with the address the server is running on.
@@ -262,6 +330,30 @@ mod tests {
         }
     }
 
+    /// A handler for the Openid server's user_info endpoint that returns user info with `groups`
+    /// and `roles` claims.
+    fn groups_user_info_endpoint(request: HttpRequest) -> HttpResponse {
+        match request.headers().get("Authorization") {
+            Some(auth_header) => {
+                let access_token = auth_header
+                    .to_str()
+                    .expect("Unable to get authorization header value");
+                if access_token == format!("Bearer {}", ACCESS_TOKEN) {
+                    HttpResponse::Ok()
+                        .content_type("application/json")
+                        .json(json!({
+                                "sub": SUBJECT_IDENTIFIER,
+                                "groups": ["group-1", "group-2"],
+                                "roles": ["role-1"],
+                        }))
+                } else {
+                    HttpResponse::Unauthorized().finish()
+                }
+            }
+            None => panic!("Invalid request, missing authorization header"),
+        }
+    }
+
     /// A handler for the Openid server's user_info endpoint. This handler simulates an http response
     /// with an unexpected response status from the Openid OAuth provider's user_info endpoint.
     fn bad_response_status_user_info_endpoint(_request: HttpRequest) -> HttpResponse {
@@ -20,7 +20,7 @@ use crate::collections::BiHashMap;
 
 use super::error::{
     PeerConnectionIdError, PeerListError, PeerLookupError, PeerManagerError, PeerRefAddError,
-    PeerRefRemoveError, PeerUnknownAddError,
+    PeerRefRemoveError, PeerUnknownAddError, PeerUpdateError,
 };
 use super::notification::{PeerManagerNotification, PeerNotificationIter, SubscriberId};
 use super::PeerAuthorizationToken;
@@ -183,6 +183,42 @@ impl PeerManagerConnector {
             .map_err(|err| PeerListError::ReceiveError(format!("{:?}", err)))?
     }
 
+    /// Requests that the endpoints of the peer reachable at any of the previous endpoints are
+    /// replaced, for example because the node moved to a new endpoint.
+    ///
+    /// If the peer is not connected, the new endpoints are tried right away. Nothing is changed
+    /// if no peer is reachable at the previous endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous_endpoints` - The endpoints the peer was known by
+    /// * `endpoints` - The new list of endpoints, in order of preference
+    pub fn update_peer_endpoints(
+        &self,
+        previous_endpoints: Vec<String>,
+        endpoints: Vec<String>,
+    ) -> Result<(), PeerUpdateError> {
+        let (sender, recv) = channel();
+
+        let message = PeerManagerMessage::Request(PeerManagerRequest::UpdatePeerEndpoints {
+            previous_endpoints,
+            endpoints,
+            sender,
+        });
+
+        match self.sender.send(message) {
+            Ok(()) => (),
+            Err(_) => {
+                return Err(PeerUpdateError(
+                    "Unable to send message to PeerManager, receiver dropped".to_string(),
+                ))
+            }
+        };
+
+        recv.recv()
+            .map_err(|err| PeerUpdateError(format!("{:?}", err)))?
+    }
+
     /// Requests the map of currently connected peers to connection IDs
     ///
     /// Returns a map of peer IDs to connection IDs
@@ -29,6 +29,8 @@ pub mod interconnect;
 mod notification;
 mod peer_map;
 mod peer_ref;
+#[cfg(feature = "registry-notifications")]
+mod registry;
 mod token;
 
 use std::cmp::min;
@@ -52,12 +54,14 @@ pub use self::connector::PeerManagerConnector;
 use self::connector::PeerRemover;
 use self::error::{
     PeerConnectionIdError, PeerListError, PeerLookupError, PeerManagerError, PeerRefAddError,
-    PeerRefRemoveError, PeerUnknownAddError,
+    PeerRefRemoveError, PeerUnknownAddError, PeerUpdateError,
 };
 pub use self::notification::{PeerManagerNotification, PeerNotificationIter, SubscriberId};
 use self::notification::{Subscriber, SubscriberMap};
 use self::peer_map::{PeerMap, PeerStatus};
 pub use self::peer_ref::{EndpointPeerRef, PeerRef};
+#[cfg(feature = "registry-notifications")]
+pub use self::registry::RegistryPeerUpdater;
 pub use self::token::PeerAuthorizationToken;
 
 /// Internal messages to drive management
@@ -100,6 +104,11 @@ pub(crate) enum PeerManagerRequest {
         endpoint: String,
         sender: Sender<Result<(), PeerRefRemoveError>>,
     },
+    UpdatePeerEndpoints {
+        previous_endpoints: Vec<String>,
+        endpoints: Vec<String>,
+        sender: Sender<Result<(), PeerUpdateError>>,
+    },
     ListPeers {
         sender: Sender<Result<Vec<PeerAuthorizationToken>, PeerListError>>,
     },
@@ -436,6 +445,23 @@ fn handle_request(
                 warn!("Connector dropped before receiving result of removing peer");
             }
         }
+        PeerManagerRequest::UpdatePeerEndpoints {
+            previous_endpoints,
+            endpoints,
+            sender,
+        } => {
+            if sender
+                .send(update_peer_endpoints(
+                    previous_endpoints,
+                    endpoints,
+                    connector,
+                    peers,
+                ))
+                .is_err()
+            {
+                warn!("Connector dropped before receiving result of updating peer endpoints");
+            }
+        }
         PeerManagerRequest::ListPeers { sender } => {
             if sender.send(Ok(peers.peer_ids())).is_err() {
                 warn!("Connector dropped before receiving result of list peers");
@@ -845,6 +871,74 @@ fn remove_peer_by_endpoint(
 // Allow clippy errors for too_many_arguments. The arguments are required
 // to avoid needing a lock in the PeerManager.
 #[allow(clippy::too_many_arguments)]
+/// Replaces the endpoints of the peer that is reachable at any of the previous endpoints.
+///
+/// A connected peer keeps its connection, and the new endpoints are used if it disconnects. If
+/// the peer is not connected, the connection to its old endpoint is dropped and the new endpoints
+/// are tried right away, instead of after the retries of the old endpoint have failed.
+fn update_peer_endpoints(
+    previous_endpoints: Vec<String>,
+    endpoints: Vec<String>,
+    connector: Connector,
+    peers: &mut PeerMap,
+) -> Result<(), PeerUpdateError> {
+    let mut peer_metadata = match previous_endpoints
+        .iter()
+        .find_map(|endpoint| peers.get_peer_from_endpoint(endpoint))
+        .cloned()
+    {
+        Some(peer_metadata) => peer_metadata,
+        None => return Ok(()),
+    };
+
+    if endpoints.is_empty() {
+        return Err(PeerUpdateError(format!(
+            "No endpoints provided for peer {}",
+            peer_metadata.id
+        )));
+    }
+
+    if peer_metadata.endpoints == endpoints {
+        return Ok(());
+    }
+
+    info!(
+        "Updating peer {} to endpoints {:?}",
+        peer_metadata.id, endpoints
+    );
+    peer_metadata.endpoints = endpoints;
+
+    if peer_metadata.status != PeerStatus::Connected {
+        if let PeerStatus::Disconnected { .. } = peer_metadata.status {
+            // stop the reconnection attempts to the old endpoint
+            if let Err(err) = connector.remove_connection(&peer_metadata.active_endpoint) {
+                error!("Unable to clean up old connection: {}", err);
+            }
+        }
+
+        info!(
+            "Attempting to find available endpoint for {}",
+            peer_metadata.id
+        );
+        for endpoint in peer_metadata.endpoints.iter() {
+            match connector.request_connection(&endpoint, &peer_metadata.connection_id) {
+                Ok(()) => {
+                    peer_metadata.active_endpoint = endpoint.to_string();
+                    break;
+                }
+                Err(err) => {
+                    log_connect_request_err(err, &peer_metadata.id, &endpoint);
+                }
+            }
+        }
+
+        peer_metadata.status = PeerStatus::Pending;
+        peer_metadata.last_connection_attempt = Instant::now();
+    }
+
+    peers.update_peer(peer_metadata)
+}
+
 fn handle_notifications(
     notification: ConnectionManagerNotification,
     unreferenced_peers: &mut UnreferencedPeerState,
@@ -2375,6 +2469,81 @@ pub mod tests {
         mesh1.wait_for_shutdown().expect("Unable to shutdown mesh");
     }
 
+    // Test that updating the endpoints of a peer that is not connected makes the peer manager
+    // connect to the new endpoints right away.
+    //
+    // 1. add test_peer with an endpoint that nothing is listening on
+    // 2. update the peer's endpoints to an endpoint with a listener
+    // 3. verify that a Connected notification is received, without waiting for a retry
+    #[test]
+    fn test_peer_manager_update_peer_endpoints() {
+        let mut transport = Box::new(InprocTransport::default());
+        let mut listener = transport.listen("inproc://moved").unwrap();
+
+        thread::spawn(move || {
+            listener.accept().unwrap();
+        });
+
+        let mut mesh = Mesh::new(512, 128);
+        let mut cm = ConnectionManager::builder()
+            .with_authorizer(Box::new(NoopAuthorizer::new("test_peer")))
+            .with_matrix_life_cycle(mesh.get_life_cycle())
+            .with_matrix_sender(mesh.get_sender())
+            .with_transport(transport.clone())
+            .start()
+            .expect("Unable to start Connection Manager");
+
+        let connector = cm.connector();
+        let mut peer_manager = PeerManager::builder()
+            .with_connector(connector)
+            .with_retry_interval(3600)
+            .with_identity("my_id".to_string())
+            .with_strict_ref_counts(true)
+            .start()
+            .expect("Cannot start peer_manager");
+        let peer_connector = peer_manager.connector();
+        let (tx, notification_rx): (
+            Sender<PeerManagerNotification>,
+            mpsc::Receiver<PeerManagerNotification>,
+        ) = channel();
+        peer_connector
+            .subscribe_sender(tx)
+            .expect("Unable to get subscriber");
+        let _peer_ref = peer_connector
+            .add_peer_ref(
+                PeerAuthorizationToken::from_peer_id("test_peer"),
+                vec!["inproc://original".to_string()],
+            )
+            .expect("Unable to add peer");
+
+        peer_connector
+            .update_peer_endpoints(
+                vec!["inproc://original".to_string()],
+                vec!["inproc://moved".to_string()],
+            )
+            .expect("Unable to update peer endpoints");
+
+        let notification = notification_rx
+            .recv_timeout(Duration::from_secs(60))
+            .expect("Unable to get new notifications");
+        assert!(
+            notification
+                == PeerManagerNotification::Connected {
+                    peer: PeerAuthorizationToken::from_peer_id("test_peer"),
+                }
+        );
+
+        peer_manager.signal_shutdown();
+        cm.signal_shutdown();
+        peer_manager
+            .wait_for_shutdown()
+            .expect("Unable to shutdown peer manager");
+        cm.wait_for_shutdown()
+            .expect("Unable to shutdown connection manager");
+        mesh.signal_shutdown();
+        mesh.wait_for_shutdown().expect("Unable to shutdown mesh");
+    }
+
     // Test that the PeerManager can be started and stopped
     #[test]
     fn test_peer_manager_shutdown() {
@@ -160,7 +160,14 @@ impl PeerMap {
     /// * `peer_metadata` - The updated peer metadata for the peer
     pub fn update_peer(&mut self, peer_metadata: PeerMetadata) -> Result<(), PeerUpdateError> {
         // Only valid if the peer already exists
-        if self.peers.contains_key(&peer_metadata.id) {
+        if let Some(previous) = self.peers.get(&peer_metadata.id) {
+            // Endpoints that were removed no longer lead to the peer
+            for endpoint in previous.endpoints.iter() {
+                if !peer_metadata.endpoints.contains(endpoint) {
+                    self.endpoints.remove(endpoint);
+                }
+            }
+
             for endpoint in peer_metadata.endpoints.iter() {
                 self.endpoints
                     .insert(endpoint.to_string(), peer_metadata.id.clone());
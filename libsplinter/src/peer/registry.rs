@@ -0,0 +1,57 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Updates the endpoints of peers when their nodes move in the registry.
+
+use crate::registry::{RegistryEvent, RegistryEventSubscriber, RegistrySubscriberError};
+
+use super::PeerManagerConnector;
+
+/// A `RegistryEventSubscriber` that passes the new endpoints of nodes in the registry to the
+/// `PeerManager`.
+///
+/// The peer is found by the node's previous endpoints, so this works for peers with any
+/// authorization type. A peer that is not connected is reconnected to the new endpoints right
+/// away.
+pub struct RegistryPeerUpdater {
+    connector: PeerManagerConnector,
+}
+
+impl RegistryPeerUpdater {
+    /// Creates a subscriber that updates peers through the given connector.
+    pub fn new(connector: PeerManagerConnector) -> Self {
+        RegistryPeerUpdater { connector }
+    }
+}
+
+impl RegistryEventSubscriber for RegistryPeerUpdater {
+    fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+        let (previous, node) = match event {
+            RegistryEvent::NodeUpdated { previous, node } if event.endpoints_changed() => {
+                (previous, node)
+            }
+            _ => return Ok(()),
+        };
+
+        self.connector
+            .update_peer_endpoints(previous.endpoints().to_vec(), node.endpoints().to_vec())
+            .map_err(|err| {
+                RegistrySubscriberError::UnableToHandleEvent(format!(
+                    "Unable to update endpoints of node {}: {}",
+                    node.identity(),
+                    err
+                ))
+            })
+    }
+}
@@ -36,6 +36,8 @@ pub(crate) const ADMIN_LIST_PROPOSALS_PROTOCOL_MIN: u32 = 1;
 pub(crate) const ADMIN_LIST_CIRCUITS_MIN: u32 = 1;
 #[cfg(all(feature = "rest-api-actix", feature = "admin-service"))]
 pub(crate) const ADMIN_FETCH_CIRCUIT_MIN: u32 = 1;
+#[cfg(all(feature = "rest-api-actix", feature = "circuit-durability"))]
+pub(crate) const ADMIN_LIST_DURABLE_QUEUES_MIN: u32 = 2;
 
 // Admin Service protocol versions
 pub const ADMIN_SERVICE_PROTOCOL_VERSION: u32 = 2;
@@ -46,6 +48,18 @@ pub(crate) const ADMIN_SERVICE_PROTOCOL_MIN: u32 = 1;
 // The currently supported circuit version
 pub const CIRCUIT_PROTOCOL_VERSION: i32 = 2;
 
+#[cfg(feature = "api-keys")]
+pub const API_KEYS_PROTOCOL_VERSION: u32 = 1;
+
+#[cfg(all(feature = "api-keys", feature = "rest-api-actix"))]
+pub(crate) const API_KEYS_MIN: u32 = 1;
+
+#[cfg(feature = "audit")]
+pub const AUDIT_PROTOCOL_VERSION: u32 = 1;
+
+#[cfg(all(feature = "audit", feature = "rest-api-actix"))]
+pub(crate) const AUDIT_LIST_ENTRIES_MIN: u32 = 1;
+
 #[cfg(feature = "authorization")]
 pub const AUTHORIZATION_PROTOCOL_VERSION: u32 = 1;
 
@@ -64,6 +78,11 @@ pub(crate) const AUTHORIZATION_RBAC_ROLES_MIN: u32 = 1;
 pub(crate) const AUTHORIZATION_RBAC_ROLE_MIN: u32 = 1;
 #[cfg(all(feature = "authorization-handler-rbac", feature = "rest-api-actix"))]
 pub(crate) const AUTHORIZATION_RBAC_ASSIGNMENTS_MIN: u32 = 1;
+#[cfg(all(
+    feature = "authorization-handler-rbac-groups",
+    feature = "rest-api-actix"
+))]
+pub(crate) const AUTHORIZATION_RBAC_GROUPS_MIN: u32 = 1;
 
 #[cfg(feature = "oauth")]
 pub const OAUTH_PROTOCOL_VERSION: u32 = 1;
@@ -84,6 +103,10 @@ pub const REGISTRY_PROTOCOL_VERSION: u32 = 1;
 pub(crate) const REGISTRY_LIST_NODES_MIN: u32 = 1;
 #[cfg(all(feature = "registry", feature = "rest-api-actix"))]
 pub(crate) const REGISTRY_FETCH_NODE_MIN: u32 = 1;
+#[cfg(all(feature = "registry-remote-signing", feature = "rest-api-actix"))]
+pub(crate) const REGISTRY_LIST_REMOTE_MIN: u32 = 1;
+#[cfg(all(feature = "registry-notifications", feature = "rest-api-actix"))]
+pub(crate) const REGISTRY_EVENTS_MIN: u32 = 1;
 
 #[cfg(any(
     feature = "biome-credentials",
@@ -107,6 +130,9 @@ pub(crate) const BIOME_VERIFY_PROTOCOL_MIN: u32 = 1;
 #[cfg(all(feature = "biome-key-management", feature = "rest-api",))]
 pub(crate) const BIOME_KEYS_PROTOCOL_MIN: u32 = 1;
 
+#[cfg(all(feature = "biome-notifications", feature = "rest-api",))]
+pub(crate) const BIOME_NOTIFICATIONS_PROTOCOL_MIN: u32 = 1;
+
 #[cfg(all(feature = "biome-profile", feature = "rest-api",))]
 pub(crate) const BIOME_FETCH_PROFILE_PROTOCOL_MIN: u32 = 1;
 #[cfg(all(feature = "biome-profile", feature = "rest-api",))]
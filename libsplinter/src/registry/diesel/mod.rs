@@ -26,9 +26,13 @@ mod schema;
 
 use diesel::r2d2::{ConnectionManager, Pool};
 
+#[cfg(feature = "registry-notifications")]
+use super::subscriber::RegistryEventSubscribers;
 use super::{
     MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader, RegistryWriter, RwRegistry,
 };
+#[cfg(feature = "registry-notifications")]
+use super::{RegistryEvent, RegistryEventSubscriber};
 
 use operations::add_node::RegistryAddNodeOperation as _;
 use operations::count_nodes::RegistryCountNodesOperation as _;
@@ -40,8 +44,15 @@ use operations::update_node::RegistryUpdateNodeOperation as _;
 use operations::RegistryOperations;
 
 /// A database-backed registry, powered by [`Diesel`](https://crates.io/crates/diesel).
+///
+/// If the experimental `registry-notifications` feature is enabled, subscribers are notified of
+/// the changes made through this registry and its clones. Changes made to the database by other
+/// `DieselRegistry` instances, including those in other processes, are not reported, so a
+/// process should create one registry and share clones of it; the store factories do this.
 pub struct DieselRegistry<C: diesel::Connection + 'static> {
     connection_pool: Pool<ConnectionManager<C>>,
+    #[cfg(feature = "registry-notifications")]
+    subscribers: RegistryEventSubscribers,
 }
 
 impl<C: diesel::Connection> DieselRegistry<C> {
@@ -51,7 +62,19 @@ impl<C: diesel::Connection> DieselRegistry<C> {
     ///
     ///  * `connection_pool`: connection pool for the database
     pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
-        DieselRegistry { connection_pool }
+        DieselRegistry {
+            connection_pool,
+            #[cfg(feature = "registry-notifications")]
+            subscribers: RegistryEventSubscribers::default(),
+        }
+    }
+
+    /// Sends the event to the subscribers, if the node was changed.
+    #[cfg(feature = "registry-notifications")]
+    fn notify(&self, previous: Option<Node>, current: Option<Node>) {
+        if let Some(event) = RegistryEvent::from_change(previous, current) {
+            self.subscribers.broadcast(&[event]);
+        }
     }
 }
 
@@ -60,6 +83,8 @@ impl Clone for DieselRegistry<diesel::pg::PgConnection> {
     fn clone(&self) -> Self {
         Self {
             connection_pool: self.connection_pool.clone(),
+            #[cfg(feature = "registry-notifications")]
+            subscribers: self.subscribers.clone(),
         }
     }
 }
@@ -69,6 +94,8 @@ impl Clone for DieselRegistry<diesel::sqlite::SqliteConnection> {
     fn clone(&self) -> Self {
         Self {
             connection_pool: self.connection_pool.clone(),
+            #[cfg(feature = "registry-notifications")]
+            subscribers: self.subscribers.clone(),
         }
     }
 }
@@ -99,35 +126,93 @@ where
     fn has_node(&self, identity: &str) -> Result<bool, RegistryError> {
         RegistryOperations::new(&*self.connection_pool.get()?).has_node(identity)
     }
+
+    #[cfg(feature = "registry-notifications")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        self.subscribers.add_subscriber(subscriber);
+        Ok(())
+    }
+
+    #[cfg(feature = "registry-notifications")]
+    fn prune_event_subscribers(&self) {
+        self.subscribers.prune();
+    }
 }
 
 #[cfg(feature = "postgres")]
 impl RegistryWriter for DieselRegistry<diesel::pg::PgConnection> {
     fn add_node(&self, node: Node) -> Result<(), RegistryError> {
-        RegistryOperations::new(&*self.connection_pool.get()?).add_node(node)
+        #[cfg(feature = "registry-notifications")]
+        let added = node.clone();
+
+        RegistryOperations::new(&*self.connection_pool.get()?).add_node(node)?;
+
+        #[cfg(feature = "registry-notifications")]
+        self.notify(None, Some(added));
+
+        Ok(())
     }
 
     fn update_node(&self, node: Node) -> Result<(), RegistryError> {
-        RegistryOperations::new(&*self.connection_pool.get()?).update_node(node)
+        #[cfg(feature = "registry-notifications")]
+        let (previous, updated) = (self.get_node(&node.identity)?, node.clone());
+
+        RegistryOperations::new(&*self.connection_pool.get()?).update_node(node)?;
+
+        #[cfg(feature = "registry-notifications")]
+        self.notify(previous, Some(updated));
+
+        Ok(())
     }
 
     fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
-        RegistryOperations::new(&*self.connection_pool.get()?).delete_node(identity)
+        let deleted =
+            RegistryOperations::new(&*self.connection_pool.get()?).delete_node(identity)?;
+
+        #[cfg(feature = "registry-notifications")]
+        self.notify(deleted.clone(), None);
+
+        Ok(deleted)
     }
 }
 
 #[cfg(feature = "sqlite")]
 impl RegistryWriter for DieselRegistry<diesel::sqlite::SqliteConnection> {
     fn add_node(&self, node: Node) -> Result<(), RegistryError> {
-        RegistryOperations::new(&*self.connection_pool.get()?).add_node(node)
+        #[cfg(feature = "registry-notifications")]
+        let added = node.clone();
+
+        RegistryOperations::new(&*self.connection_pool.get()?).add_node(node)?;
+
+        #[cfg(feature = "registry-notifications")]
+        self.notify(None, Some(added));
+
+        Ok(())
     }
 
     fn update_node(&self, node: Node) -> Result<(), RegistryError> {
-        RegistryOperations::new(&*self.connection_pool.get()?).update_node(node)
+        #[cfg(feature = "registry-notifications")]
+        let (previous, updated) = (self.get_node(&node.identity)?, node.clone());
+
+        RegistryOperations::new(&*self.connection_pool.get()?).update_node(node)?;
+
+        #[cfg(feature = "registry-notifications")]
+        self.notify(previous, Some(updated));
+
+        Ok(())
     }
 
     fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
-        RegistryOperations::new(&*self.connection_pool.get()?).delete_node(identity)
+        let deleted =
+            RegistryOperations::new(&*self.connection_pool.get()?).delete_node(identity)?;
+
+        #[cfg(feature = "registry-notifications")]
+        self.notify(deleted.clone(), None);
+
+        Ok(deleted)
     }
 }
 
@@ -693,6 +778,67 @@ pub mod tests {
         assert_eq!(count, 2);
     }
 
+    /// Verifies that a subscriber is notified of the nodes added, updated and deleted through
+    /// the registry
+    ///
+    /// 1. Setup sqlite database and add a subscriber
+    /// 2. Add node 1, update its keys, and delete it
+    /// 3. Verify that the subscriber received the matching events in order
+    #[cfg(feature = "registry-notifications")]
+    #[test]
+    fn test_event_subscriber() {
+        let pool = create_connection_pool_and_migrate();
+        let registry = DieselRegistry::new(pool);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        registry
+            .add_event_subscriber(Box::new(ChannelSubscriber(tx)))
+            .expect("Unable to add subscriber");
+
+        let mut updated_node = get_node_1();
+        updated_node.keys = vec!["bcde".into()];
+
+        registry
+            .add_node(get_node_1())
+            .expect("Unable to insert node");
+        registry
+            .update_node(updated_node.clone())
+            .expect("Unable to update node");
+        registry
+            .delete_node(&get_node_1().identity)
+            .expect("Unable to delete node");
+
+        let events: Vec<RegistryEvent> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                RegistryEvent::NodeAdded(get_node_1()),
+                RegistryEvent::NodeUpdated {
+                    previous: get_node_1(),
+                    node: updated_node.clone(),
+                },
+                RegistryEvent::NodeRemoved(updated_node),
+            ]
+        );
+        assert!(events[1].keys_changed());
+        assert!(!events[1].endpoints_changed());
+    }
+
+    #[cfg(feature = "registry-notifications")]
+    struct ChannelSubscriber(std::sync::mpsc::Sender<RegistryEvent>);
+
+    #[cfg(feature = "registry-notifications")]
+    impl RegistryEventSubscriber for ChannelSubscriber {
+        fn handle_event(
+            &self,
+            event: &RegistryEvent,
+        ) -> Result<(), crate::registry::RegistrySubscriberError> {
+            self.0
+                .send(event.clone())
+                .map_err(|_| crate::registry::RegistrySubscriberError::Unsubscribe)
+        }
+    }
+
     fn get_node_1() -> Node {
         Node::builder("Node-123")
             .with_endpoint("tcps://12.0.0.123:8431")
@@ -68,6 +68,33 @@ impl From<diesel::r2d2::PoolError> for RegistryError {
     }
 }
 
+/// Returned by a [`RegistryEventSubscriber`] that could not handle an event
+///
+/// [`RegistryEventSubscriber`]: trait.RegistryEventSubscriber.html
+#[cfg(feature = "registry-notifications")]
+#[derive(Debug)]
+pub enum RegistrySubscriberError {
+    /// The event could not be handled, but the subscriber should receive later events
+    UnableToHandleEvent(String),
+    /// The subscriber should no longer receive events
+    Unsubscribe,
+}
+
+#[cfg(feature = "registry-notifications")]
+impl Error for RegistrySubscriberError {}
+
+#[cfg(feature = "registry-notifications")]
+impl fmt::Display for RegistrySubscriberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistrySubscriberError::UnableToHandleEvent(msg) => {
+                write!(f, "Unable to handle event: {}", msg)
+            }
+            RegistrySubscriberError::Unsubscribe => f.write_str("Unsubscribe"),
+        }
+    }
+}
+
 /// Represents the reason that a node was found to be invalid
 #[derive(Debug)]
 pub enum InvalidNodeError {
@@ -31,16 +31,32 @@ mod diesel;
 mod error;
 #[cfg(feature = "rest-api")]
 mod rest_api;
+#[cfg(feature = "registry-notifications")]
+mod subscriber;
 mod unified;
 mod yaml;
 
 use std::collections::HashMap;
 use std::iter::ExactSizeIterator;
 
+#[cfg(feature = "registry-notifications")]
+use crate::error::InvalidStateError;
+
 #[cfg(feature = "diesel")]
 pub use self::diesel::DieselRegistry;
+#[cfg(feature = "registry-notifications")]
+pub use error::RegistrySubscriberError;
 pub use error::{InvalidNodeError, RegistryError};
+#[cfg(all(feature = "registry-remote-signing", feature = "rest-api"))]
+pub use rest_api::RemoteRegistryStatusResourceProvider;
+#[cfg(feature = "registry-notifications")]
+pub use subscriber::{RegistryEvent, RegistryEventSubscriber};
 pub use unified::UnifiedRegistry;
+#[cfg(feature = "registry-remote-signing")]
+pub use yaml::{
+    create_signed_feed, RemoteYamlRegistryBuilder, RemoteYamlRegistryStatus,
+    RemoteYamlRegistryStatusReader, RemoteYamlValidation,
+};
 pub use yaml::{LocalYamlRegistry, YamlNode};
 #[cfg(feature = "registry-remote")]
 pub use yaml::{RemoteYamlRegistry, RemoteYamlShutdownHandle};
@@ -279,6 +295,28 @@ pub trait RegistryReader: Send + Sync {
     fn has_node(&self, identity: &str) -> Result<bool, RegistryError> {
         self.get_node(identity).map(|opt| opt.is_some())
     }
+
+    /// Adds a subscriber that will be notified of each change to the nodes in the registry.
+    ///
+    /// Returns an `InvalidStateError` if the registry does not support notifications.
+    ///
+    /// # Arguments
+    ///
+    ///  * `subscriber` - The subscriber to notify.
+    #[cfg(feature = "registry-notifications")]
+    fn add_event_subscriber(
+        &self,
+        _subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        Err(RegistryError::InvalidStateError(
+            InvalidStateError::with_message("Registry does not support event subscriptions".into()),
+        ))
+    }
+
+    /// Removes the subscribers that have closed, such as those for websockets that were closed,
+    /// without waiting for the next event to be sent to them.
+    #[cfg(feature = "registry-notifications")]
+    fn prune_event_subscribers(&self) {}
 }
 
 /// Defines registry write capabilities.
@@ -318,6 +356,21 @@ pub trait RwRegistry: RegistryWriter + RegistryReader {
 
     /// Clone the `RwRegistry` as a `Box<dyn RegistryWriter>`.
     fn clone_box_as_writer(&self) -> Box<dyn RegistryWriter>;
+
+    /// Returns an iterator over the nodes that can be updated or deleted through this registry,
+    /// which are all of its nodes unless it also provides nodes from read-only sources.
+    ///
+    /// # Arguments
+    ///
+    ///  * `predicates` - A list of predicates to be applied to the resulting list. These are
+    ///  applied as an AND, from a query perspective. If the list is empty, it is the equivalent of
+    ///  no predicates (i.e. return all).
+    fn list_writable_nodes<'a, 'b: 'a>(
+        &'b self,
+        predicates: &'a [MetadataPredicate],
+    ) -> Result<NodeIter<'a>, RegistryError> {
+        self.list_nodes(predicates)
+    }
 }
 
 impl Clone for Box<dyn RwRegistry> {
@@ -348,6 +401,19 @@ where
     fn has_node(&self, identity: &str) -> Result<bool, RegistryError> {
         (**self).has_node(identity)
     }
+
+    #[cfg(feature = "registry-notifications")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        (**self).add_event_subscriber(subscriber)
+    }
+
+    #[cfg(feature = "registry-notifications")]
+    fn prune_event_subscribers(&self) {
+        (**self).prune_event_subscribers()
+    }
 }
 
 impl<NW> RegistryWriter for Box<NW>
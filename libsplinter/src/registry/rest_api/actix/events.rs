@@ -0,0 +1,113 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoints:
+//!
+//! * `GET /ws/registry/events` for subscribing to changes to the nodes in the registry
+
+use crate::actix_web::{web, Error, HttpRequest, HttpResponse};
+use crate::futures::{future::IntoFuture, Future};
+use crate::protocol;
+#[cfg(feature = "authorization")]
+use crate::registry::rest_api::REGISTRY_READ_PERMISSION;
+use crate::registry::{
+    rest_api::resources::events::RegistryEventResponse, RegistryEvent, RegistryEventSubscriber,
+    RegistryReader, RegistrySubscriberError, RwRegistry,
+};
+use crate::rest_api::{
+    actix_web_1::{
+        new_websocket_event_sender_with_close_handler, EventSender, Method,
+        ProtocolVersionRangeGuard, Request, Resource,
+    },
+    ErrorResponse,
+};
+
+pub fn make_events_resource(registry: Box<dyn RwRegistry>) -> Resource {
+    let resource =
+        Resource::build("/ws/registry/events").add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::REGISTRY_EVENTS_MIN,
+            protocol::REGISTRY_PROTOCOL_VERSION,
+        ));
+    #[cfg(feature = "authorization")]
+    {
+        resource.add_method(
+            Method::Get,
+            REGISTRY_READ_PERMISSION,
+            move |request, payload| subscribe(request, payload, &*registry),
+        )
+    }
+    #[cfg(not(feature = "authorization"))]
+    {
+        resource.add_method(Method::Get, move |request, payload| {
+            subscribe(request, payload, &*registry)
+        })
+    }
+}
+
+fn subscribe(
+    request: HttpRequest,
+    payload: web::Payload,
+    registry: &dyn RwRegistry,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let request = Request::from((request, payload));
+    // Drop the subscriber as soon as the websocket closes, rather than on the next event
+    let prune_registry = registry.clone_box_as_reader();
+    match new_websocket_event_sender_with_close_handler(
+        request,
+        Box::new(std::iter::empty()),
+        move || prune_registry.prune_event_subscribers(),
+    ) {
+        Ok((sender, res)) => {
+            if let Err(err) =
+                registry.add_event_subscriber(Box::new(WsRegistryEventSubscriber { sender }))
+            {
+                error!("Unable to add registry event subscriber: {}", err);
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+            debug!("Websocket response: {:?}", res);
+            Box::new(res.into_future())
+        }
+        Err(err) => {
+            debug!("Failed to create websocket: {:?}", err);
+            Box::new(
+                HttpResponse::InternalServerError()
+                    .json(ErrorResponse::internal_error())
+                    .into_future(),
+            )
+        }
+    }
+}
+
+struct WsRegistryEventSubscriber {
+    sender: EventSender<RegistryEventResponse>,
+}
+
+impl RegistryEventSubscriber for WsRegistryEventSubscriber {
+    fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+        self.sender
+            .send(RegistryEventResponse::from(event))
+            .map_err(|_| {
+                debug!("Dropping registry event and unsubscribing due to websocket being closed");
+                RegistrySubscriberError::Unsubscribe
+            })
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
@@ -50,7 +50,7 @@ pub fn make_nodes_resource(registry: Box<dyn RwRegistry>) -> Resource {
     {
         resource
             .add_method(Method::Get, REGISTRY_READ_PERMISSION, move |r, _| {
-                list_nodes(r, web::Data::new(registry.clone_box_as_reader()))
+                list_nodes(r, web::Data::new(registry.clone()))
             })
             .add_method(Method::Post, REGISTRY_WRITE_PERMISSION, move |_, p| {
                 add_node(p, web::Data::new(registry1.clone()))
@@ -60,7 +60,7 @@ pub fn make_nodes_resource(registry: Box<dyn RwRegistry>) -> Resource {
     {
         resource
             .add_method(Method::Get, move |r, _| {
-                list_nodes(r, web::Data::new(registry.clone_box_as_reader()))
+                list_nodes(r, web::Data::new(registry.clone()))
             })
             .add_method(Method::Post, move |_, p| {
                 add_node(p, web::Data::new(registry1.clone()))
@@ -70,7 +70,7 @@ pub fn make_nodes_resource(registry: Box<dyn RwRegistry>) -> Resource {
 
 fn list_nodes(
     req: HttpRequest,
-    registry: web::Data<Box<dyn RegistryReader>>,
+    registry: web::Data<Box<dyn RwRegistry>>,
 ) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
     let query: web::Query<HashMap<String, String>> =
         if let Ok(q) = web::Query::from_query(req.query_string()) {
@@ -139,6 +139,26 @@ fn list_nodes(
         None => None,
     };
 
+    let writable = match query.get("writable") {
+        Some(value) => match value.parse::<bool>() {
+            Ok(val) => {
+                link.push_str(&format!("writable={}&", val));
+                val
+            }
+            Err(err) => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Invalid writable value passed: {}. Error: {}",
+                            value, err
+                        )))
+                        .into_future(),
+                )
+            }
+        },
+        None => false,
+    };
+
     let predicates = match to_predicates(filters) {
         Ok(predicates) => predicates,
         Err(err) => {
@@ -157,22 +177,27 @@ fn list_nodes(
         registry,
         link,
         predicates,
+        writable,
         Some(offset),
         Some(limit),
     ))
 }
 
 fn query_list_nodes(
-    registry: web::Data<Box<dyn RegistryReader>>,
+    registry: web::Data<Box<dyn RwRegistry>>,
     link: String,
     filters: Vec<MetadataPredicate>,
+    writable: bool,
     offset: Option<usize>,
     limit: Option<usize>,
 ) -> impl Future<Item = HttpResponse, Error = Error> {
     web::block(move || {
-        let nodes = registry
-            .list_nodes(&filters)
-            .map_err(RegistryRestApiError::from)?;
+        let nodes = if writable {
+            registry.list_writable_nodes(&filters)
+        } else {
+            registry.list_nodes(&filters)
+        }
+        .map_err(RegistryRestApiError::from)?;
         let offset_value = offset.unwrap_or(0);
         let total = nodes.len();
         let limit_value = limit.unwrap_or_else(|| total as usize);
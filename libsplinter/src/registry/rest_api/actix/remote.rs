@@ -0,0 +1,72 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoints:
+//!
+//! * `GET /registry/remote` for listing the status of the remote registries
+
+use crate::actix_web::{web, Error, HttpResponse};
+use crate::futures::{future::IntoFuture, Future};
+use crate::protocol;
+#[cfg(feature = "authorization")]
+use crate::registry::rest_api::REGISTRY_READ_PERMISSION;
+use crate::registry::{
+    rest_api::resources::remote::{ListRemoteRegistriesResponse, RemoteRegistryResponse},
+    RegistryError, RemoteYamlRegistryStatus, RemoteYamlRegistryStatusReader,
+};
+use crate::rest_api::{
+    actix_web_1::{Method, ProtocolVersionRangeGuard, Resource},
+    ErrorResponse,
+};
+
+pub fn make_remote_resource(readers: Vec<RemoteYamlRegistryStatusReader>) -> Resource {
+    let resource =
+        Resource::build("/registry/remote").add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::REGISTRY_LIST_REMOTE_MIN,
+            protocol::REGISTRY_PROTOCOL_VERSION,
+        ));
+    #[cfg(feature = "authorization")]
+    {
+        resource.add_method(Method::Get, REGISTRY_READ_PERMISSION, move |_, _| {
+            list_remote_registries(web::Data::new(readers.clone()))
+        })
+    }
+    #[cfg(not(feature = "authorization"))]
+    {
+        resource.add_method(Method::Get, move |_, _| {
+            list_remote_registries(web::Data::new(readers.clone()))
+        })
+    }
+}
+
+fn list_remote_registries(
+    readers: web::Data<Vec<RemoteYamlRegistryStatusReader>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    Box::new(
+        match readers
+            .iter()
+            .map(RemoteYamlRegistryStatusReader::status)
+            .collect::<Result<Vec<RemoteYamlRegistryStatus>, RegistryError>>()
+        {
+            Ok(statuses) => HttpResponse::Ok().json(ListRemoteRegistriesResponse {
+                data: statuses.iter().map(RemoteRegistryResponse::from).collect(),
+            }),
+            Err(err) => {
+                error!("Unable to get remote registry status: {}", err);
+                HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+            }
+        }
+        .into_future(),
+    )
+}
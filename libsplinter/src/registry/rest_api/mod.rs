@@ -23,6 +23,8 @@ use crate::rest_api::actix_web_1::{Resource, RestResourceProvider};
 #[cfg(all(feature = "authorization", feature = "rest-api-actix"))]
 use crate::rest_api::auth::authorization::Permission;
 
+#[cfg(feature = "registry-remote-signing")]
+use super::RemoteYamlRegistryStatusReader;
 use super::RwRegistry;
 
 #[cfg(all(feature = "authorization", feature = "rest-api-actix"))]
@@ -46,6 +48,12 @@ const REGISTRY_WRITE_PERMISSION: Permission = Permission::Check {
 /// * `PUT /registry/nodes/{identity}` - Replace a node in the registry
 /// * `DELETE /registry/nodes/{identity}` - Delete a node from the registry
 ///
+/// If the experimental `registry-notifications` feature is enabled, the following endpoint is
+/// also provided:
+///
+/// * `GET /ws/registry/events` - Subscribe to changes to the nodes in the registry over a
+///   websocket
+///
 /// These endpoints are only available if the following REST API backend feature is enabled:
 ///
 /// * `rest-api-actix`
@@ -62,6 +70,44 @@ impl RestResourceProvider for dyn RwRegistry {
                 actix::nodes_identity::make_nodes_identity_resource(self.clone_box()),
                 actix::nodes::make_nodes_resource(self.clone_box()),
             ]);
+
+            #[cfg(feature = "registry-notifications")]
+            resources.push(actix::events::make_events_resource(self.clone_box()));
+        }
+
+        resources
+    }
+}
+
+/// Provides the `GET /registry/remote` endpoint, which lists the signed feed version and
+/// validation result of each of the given remote registries.
+///
+/// This endpoint is only available if the following REST API backend feature is enabled:
+///
+/// * `rest-api-actix`
+#[cfg(feature = "registry-remote-signing")]
+pub struct RemoteRegistryStatusResourceProvider {
+    readers: Vec<RemoteYamlRegistryStatusReader>,
+}
+
+#[cfg(feature = "registry-remote-signing")]
+impl RemoteRegistryStatusResourceProvider {
+    pub fn new(readers: Vec<RemoteYamlRegistryStatusReader>) -> Self {
+        Self { readers }
+    }
+}
+
+#[cfg(feature = "registry-remote-signing")]
+impl RestResourceProvider for RemoteRegistryStatusResourceProvider {
+    fn resources(&self) -> Vec<Resource> {
+        // Allowing unused_mut because resources must be mutable if feature rest-api-actix is
+        // enabled
+        #[allow(unused_mut)]
+        let mut resources = Vec::new();
+
+        #[cfg(feature = "rest-api-actix")]
+        {
+            resources.push(actix::remote::make_remote_resource(self.readers.clone()));
         }
 
         resources
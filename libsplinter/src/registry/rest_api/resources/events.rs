@@ -0,0 +1,71 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::registry::{Node, RegistryEvent};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RegistryEventResponse {
+    pub event_type: &'static str,
+    pub identity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<EventNodeResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<EventNodeResponse>,
+    pub endpoints_changed: bool,
+    pub keys_changed: bool,
+}
+
+impl From<&RegistryEvent> for RegistryEventResponse {
+    fn from(event: &RegistryEvent) -> Self {
+        let (event_type, node, previous) = match event {
+            RegistryEvent::NodeAdded(node) => ("node_added", Some(node.into()), None),
+            RegistryEvent::NodeRemoved(node) => ("node_removed", None, Some(node.into())),
+            RegistryEvent::NodeUpdated { previous, node } => {
+                ("node_updated", Some(node.into()), Some(previous.into()))
+            }
+        };
+
+        Self {
+            event_type,
+            identity: event.identity().to_string(),
+            node,
+            previous,
+            endpoints_changed: event.endpoints_changed(),
+            keys_changed: event.keys_changed(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventNodeResponse {
+    pub identity: String,
+    pub endpoints: Vec<String>,
+    pub display_name: String,
+    pub keys: Vec<String>,
+    pub metadata: HashMap<String, String>,
+}
+
+impl From<&Node> for EventNodeResponse {
+    fn from(node: &Node) -> Self {
+        Self {
+            identity: node.identity().to_string(),
+            endpoints: node.endpoints().to_vec(),
+            display_name: node.display_name().to_string(),
+            keys: node.keys().to_vec(),
+            metadata: node.metadata().clone(),
+        }
+    }
+}
@@ -12,5 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "registry-notifications")]
+pub(super) mod events;
 pub(super) mod nodes;
 pub(super) mod nodes_identity;
+#[cfg(feature = "registry-remote-signing")]
+pub(super) mod remote;
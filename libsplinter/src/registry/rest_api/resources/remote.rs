@@ -0,0 +1,51 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::registry::{RemoteYamlRegistryStatus, RemoteYamlValidation};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListRemoteRegistriesResponse<'a> {
+    pub data: Vec<RemoteRegistryResponse<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RemoteRegistryResponse<'a> {
+    pub url: &'a str,
+    pub signed: bool,
+    pub version: Option<u64>,
+    pub validation: &'static str,
+    pub message: String,
+}
+
+impl<'a> From<&'a RemoteYamlRegistryStatus> for RemoteRegistryResponse<'a> {
+    fn from(status: &'a RemoteYamlRegistryStatus) -> Self {
+        let validation = match status.validation() {
+            RemoteYamlValidation::Pending => "pending",
+            RemoteYamlValidation::Valid => "valid",
+            RemoteYamlValidation::Unavailable(_) => "unavailable",
+            RemoteYamlValidation::Invalid(_) => "invalid",
+            RemoteYamlValidation::InvalidSignature => "invalid_signature",
+            RemoteYamlValidation::Rollback { .. } => "rollback",
+            RemoteYamlValidation::Conflict { .. } => "conflict",
+        };
+
+        Self {
+            url: status.url(),
+            signed: status.signed(),
+            version: status.version(),
+            validation,
+            message: status.validation().to_string(),
+        }
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Notifications of changes to the nodes in a registry.
+//!
+//! Registries that support notifications accept a [`RegistryEventSubscriber`] through
+//! [`RegistryReader::add_event_subscriber`], and pass it a [`RegistryEvent`] each time a node is
+//! added, removed or changed.
+//!
+//! [`RegistryEvent`]: enum.RegistryEvent.html
+//! [`RegistryEventSubscriber`]: trait.RegistryEventSubscriber.html
+//! [`RegistryReader::add_event_subscriber`]: ../trait.RegistryReader.html#method.add_event_subscriber
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::{Node, RegistrySubscriberError};
+
+/// A change to a node in a registry.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegistryEvent {
+    /// A node was added to the registry.
+    NodeAdded(Node),
+    /// A node was removed from the registry.
+    NodeRemoved(Node),
+    /// An existing node was changed.
+    NodeUpdated {
+        /// The node before the change
+        previous: Node,
+        /// The node after the change
+        node: Node,
+    },
+}
+
+impl RegistryEvent {
+    /// Returns the event that describes the change from `previous` to `current`, where `None`
+    /// means the node is not in the registry. Returns `None` if the node was not changed.
+    pub(crate) fn from_change(previous: Option<Node>, current: Option<Node>) -> Option<Self> {
+        match (previous, current) {
+            (None, Some(node)) => Some(RegistryEvent::NodeAdded(node)),
+            (Some(node), None) => Some(RegistryEvent::NodeRemoved(node)),
+            (Some(previous), Some(node)) if previous != node => {
+                Some(RegistryEvent::NodeUpdated { previous, node })
+            }
+            _ => None,
+        }
+    }
+
+    /// The identity of the node that changed.
+    pub fn identity(&self) -> &str {
+        match self {
+            RegistryEvent::NodeAdded(node) => node.identity(),
+            RegistryEvent::NodeRemoved(node) => node.identity(),
+            RegistryEvent::NodeUpdated { node, .. } => node.identity(),
+        }
+    }
+
+    /// Whether the node's endpoints were changed by an update.
+    pub fn endpoints_changed(&self) -> bool {
+        match self {
+            RegistryEvent::NodeUpdated { previous, node } => previous.endpoints != node.endpoints,
+            _ => false,
+        }
+    }
+
+    /// Whether the node's keys were changed by an update.
+    pub fn keys_changed(&self) -> bool {
+        match self {
+            RegistryEvent::NodeUpdated { previous, node } => previous.keys != node.keys,
+            _ => false,
+        }
+    }
+}
+
+/// Receives the changes made to a registry.
+pub trait RegistryEventSubscriber: Send {
+    /// Handles a change to the registry.
+    ///
+    /// Returning `RegistrySubscriberError::Unsubscribe` removes the subscriber from the registry.
+    /// Subscribers are called after the change is made, and may read from the registry.
+    fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError>;
+
+    /// Returns `true` if the subscriber will not handle any more events, so that it can be removed
+    /// by `RegistryReader::prune_event_subscribers` before the next event is sent.
+    fn is_closed(&self) -> bool {
+        false
+    }
+}
+
+/// The subscribers of a registry, shared by each of its clones.
+#[derive(Clone, Default)]
+pub(crate) struct RegistryEventSubscribers {
+    subscribers: Arc<Mutex<Vec<Box<dyn RegistryEventSubscriber>>>>,
+}
+
+impl RegistryEventSubscribers {
+    pub fn add_subscriber(&self, subscriber: Box<dyn RegistryEventSubscriber>) {
+        match self.subscribers.lock() {
+            Ok(mut subscribers) => subscribers.push(subscriber),
+            Err(_) => error!("Registry subscribers lock poisoned; unable to add subscriber"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscribers
+            .lock()
+            .map(|subscribers| subscribers.is_empty())
+            .unwrap_or(true)
+    }
+
+    /// Drops the subscribers that are closed.
+    pub fn prune(&self) {
+        match self.subscribers.lock() {
+            Ok(mut subscribers) => subscribers.retain(|subscriber| !subscriber.is_closed()),
+            Err(_) => error!("Registry subscribers lock poisoned; unable to prune subscribers"),
+        }
+    }
+
+    /// Sends the events to each subscriber in order, dropping the subscribers that unsubscribe.
+    pub fn broadcast(&self, events: &[RegistryEvent]) {
+        if events.is_empty() {
+            return;
+        }
+
+        let mut subscribers = match self.subscribers.lock() {
+            Ok(subscribers) => subscribers,
+            Err(_) => {
+                error!("Registry subscribers lock poisoned; unable to send registry events");
+                return;
+            }
+        };
+
+        for event in events {
+            subscribers.retain(|subscriber| match subscriber.handle_event(event) {
+                Ok(()) => true,
+                Err(RegistrySubscriberError::Unsubscribe) => false,
+                Err(RegistrySubscriberError::UnableToHandleEvent(msg)) => {
+                    error!("Unable to send registry event: {}", msg);
+                    true
+                }
+            });
+        }
+    }
+}
+
+/// Compares two lists of nodes and returns the events that change `previous` into `current`.
+pub(crate) fn diff_nodes(previous: &[Node], current: &[Node]) -> Vec<RegistryEvent> {
+    let mut previous_by_id: HashMap<&str, &Node> = previous
+        .iter()
+        .map(|node| (node.identity.as_str(), node))
+        .collect();
+
+    let mut events: Vec<RegistryEvent> = current
+        .iter()
+        .filter_map(|node| {
+            RegistryEvent::from_change(
+                previous_by_id.remove(node.identity.as_str()).cloned(),
+                Some(node.clone()),
+            )
+        })
+        .collect();
+
+    // Anything left was not found in the current list; keep the previous order for these
+    events.extend(
+        previous
+            .iter()
+            .filter(|node| previous_by_id.contains_key(node.identity.as_str()))
+            .cloned()
+            .map(RegistryEvent::NodeRemoved),
+    );
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::{channel, Sender};
+
+    use super::*;
+
+    fn new_node(id: &str, endpoint: &str, key: &str) -> Node {
+        Node::builder(id)
+            .with_endpoint(endpoint)
+            .with_key(key)
+            .build()
+            .expect("Failed to build node")
+    }
+
+    /// Verify that the differences between two lists of nodes are reported as the matching
+    /// events, and that updates report which of the node's fields were changed.
+    #[test]
+    fn diff_nodes_events() {
+        let node1 = new_node("node1", "tcps://endpoint1:8044", "abcd");
+        let node2 = new_node("node2", "tcps://endpoint2:8044", "bcde");
+        let node3 = new_node("node3", "tcps://endpoint3:8044", "cdef");
+        let moved_node2 = new_node("node2", "tcps://endpoint4:8044", "bcde");
+
+        let events = diff_nodes(
+            &[node1.clone(), node2.clone()],
+            &[node1, moved_node2.clone(), node3.clone()],
+        );
+
+        assert_eq!(
+            events,
+            vec![
+                RegistryEvent::NodeUpdated {
+                    previous: node2.clone(),
+                    node: moved_node2,
+                },
+                RegistryEvent::NodeAdded(node3.clone()),
+            ]
+        );
+        assert!(events[0].endpoints_changed());
+        assert!(!events[0].keys_changed());
+
+        let events = diff_nodes(&[node2.clone(), node3], &[]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], RegistryEvent::NodeRemoved(node2));
+        assert_eq!(events[1].identity(), "node3");
+    }
+
+    /// Verify that subscribers receive each broadcast event and are removed when they
+    /// unsubscribe.
+    #[test]
+    fn broadcast_and_unsubscribe() {
+        let subscribers = RegistryEventSubscribers::default();
+        let (tx, rx) = channel();
+        subscribers.add_subscriber(Box::new(ChannelSubscriber(tx)));
+
+        let node = new_node("node1", "tcps://endpoint1:8044", "abcd");
+        subscribers.broadcast(&[RegistryEvent::NodeAdded(node.clone())]);
+        assert_eq!(
+            rx.try_recv().expect("Event not received"),
+            RegistryEvent::NodeAdded(node.clone())
+        );
+
+        // Dropping the receiver unsubscribes the subscriber on the next event
+        drop(rx);
+        subscribers.broadcast(&[RegistryEvent::NodeRemoved(node)]);
+        assert!(subscribers.is_empty());
+    }
+
+    /// Verify that pruning drops only the subscribers that are closed.
+    #[test]
+    fn prune_closed_subscribers() {
+        let subscribers = RegistryEventSubscribers::default();
+        let closed = Arc::new(AtomicBool::new(false));
+        subscribers.add_subscriber(Box::new(ClosableSubscriber(closed.clone())));
+
+        subscribers.prune();
+        assert!(!subscribers.is_empty());
+
+        closed.store(true, Ordering::SeqCst);
+        subscribers.prune();
+        assert!(subscribers.is_empty());
+    }
+
+    struct ChannelSubscriber(Sender<RegistryEvent>);
+
+    impl RegistryEventSubscriber for ChannelSubscriber {
+        fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+            self.0
+                .send(event.clone())
+                .map_err(|_| RegistrySubscriberError::Unsubscribe)
+        }
+    }
+
+    struct ClosableSubscriber(Arc<AtomicBool>);
+
+    impl RegistryEventSubscriber for ClosableSubscriber {
+        fn handle_event(&self, _event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+            Ok(())
+        }
+
+        fn is_closed(&self) -> bool {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+}
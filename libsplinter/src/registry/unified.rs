@@ -21,11 +21,19 @@
 //! [`RwRegistry`]: ../trait.RwRegistry.html
 
 use std::collections::HashMap;
+#[cfg(feature = "registry-notifications")]
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+#[cfg(feature = "registry-notifications")]
+use std::sync::Weak;
 
+#[cfg(feature = "registry-notifications")]
+use super::subscriber::RegistryEventSubscribers;
 use super::{
     MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader, RegistryWriter, RwRegistry,
 };
+#[cfg(feature = "registry-notifications")]
+use super::{RegistryEvent, RegistryEventSubscriber, RegistrySubscriberError};
 
 /// A registry with multiple sources.
 ///
@@ -61,6 +69,13 @@ use super::{
 /// If the same metadata key is set for the node in different registires, the value for that key
 /// from the highest-precedence registry will be used.
 ///
+/// # Notifications
+///
+/// If the experimental `registry-notifications` feature is enabled, subscribers are notified when
+/// the unified view of a node changes. Changes in a source registry that are hidden by a
+/// higher-precedence registry are not reported. Only source registries that support
+/// notifications are watched.
+///
 /// [`RegistryReader`]: ../trait.RegistryReader.html
 /// [`RegistryWriter`]: ../trait.RegistryWriter.html
 /// [`RwRegistry`]: ../trait.RwRegistry.html
@@ -72,6 +87,10 @@ use super::{
 pub struct UnifiedRegistry {
     internal_source: Arc<dyn RwRegistry>,
     external_sources: Vec<Arc<dyn RegistryReader>>,
+    #[cfg(feature = "registry-notifications")]
+    subscribers: RegistryEventSubscribers,
+    #[cfg(feature = "registry-notifications")]
+    watching_sources: Arc<AtomicBool>,
 }
 
 impl UnifiedRegistry {
@@ -84,6 +103,10 @@ impl UnifiedRegistry {
         Self {
             internal_source: internal_source.into(),
             external_sources: external_sources.into_iter().map(Arc::from).collect(),
+            #[cfg(feature = "registry-notifications")]
+            subscribers: RegistryEventSubscribers::default(),
+            #[cfg(feature = "registry-notifications")]
+            watching_sources: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -143,37 +166,21 @@ impl RegistryReader for UnifiedRegistry {
 
     fn get_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
         // Get node from all read-only sources
-        Ok(self
-            .external_sources
-            .iter()
-            .map(|registry| registry.get_node(identity))
-            // Reverse the sources, so lowest precedence is first
-            .rev()
-            // Get node from the internal source and add it to the end, since it has highest
-            // precedence
-            .chain(std::iter::once(self.internal_source.get_node(identity)))
-            // Log any errors from the `get_node` calls and ignore the failing registries
-            .filter_map(|res| {
-                res.map_err(|err| debug!("Failed to fetch node from source registry: {}", err))
-                    .ok()
-            })
-            // Merge metadata and get the highest-precedence definition of the node if it exists
-            .fold(None, |final_opt, fetch_opt| {
-                match fetch_opt {
-                    Some(mut node) => {
-                        // If the node was already found at a lower precedence, merge metadata
-                        if let Some(existing) = final_opt {
-                            // Overwrite the existing node's metadata with the new node's if they
-                            // share the same metadata keys
-                            let mut merged_metadata = existing.metadata;
-                            merged_metadata.extend(node.metadata);
-                            node.metadata = merged_metadata;
-                        }
-                        Some(node)
-                    }
-                    None => final_opt,
-                }
-            }))
+        Ok(merge_nodes(
+            self.external_sources
+                .iter()
+                .map(|registry| registry.get_node(identity))
+                // Reverse the sources, so lowest precedence is first
+                .rev()
+                // Get node from the internal source and add it to the end, since it has highest
+                // precedence
+                .chain(std::iter::once(self.internal_source.get_node(identity)))
+                // Log any errors from the `get_node` calls and ignore the failing registries
+                .filter_map(|res| {
+                    res.map_err(|err| debug!("Failed to fetch node from source registry: {}", err))
+                        .ok()
+                }),
+        ))
     }
 
     fn has_node(&self, identity: &str) -> Result<bool, RegistryError> {
@@ -197,6 +204,153 @@ impl RegistryReader for UnifiedRegistry {
                 })
             }))
     }
+
+    #[cfg(feature = "registry-notifications")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        self.subscribers.add_subscriber(subscriber);
+        if !self.watching_sources.swap(true, Ordering::SeqCst) {
+            self.watch_sources();
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "registry-notifications")]
+    fn prune_event_subscribers(&self) {
+        self.subscribers.prune();
+    }
+}
+
+#[cfg(feature = "registry-notifications")]
+impl UnifiedRegistry {
+    /// Subscribes to each source registry that supports notifications.
+    fn watch_sources(&self) {
+        let internal_source = Arc::downgrade(&self.internal_source);
+        let external_sources: Vec<Weak<dyn RegistryReader>> =
+            self.external_sources.iter().map(Arc::downgrade).collect();
+
+        let new_subscriber = |source_index| {
+            Box::new(SourceSubscriber {
+                source_index,
+                internal_source: internal_source.clone(),
+                external_sources: external_sources.clone(),
+                subscribers: self.subscribers.clone(),
+            })
+        };
+
+        if let Err(err) = self.internal_source.add_event_subscriber(new_subscriber(0)) {
+            debug!("Not watching internal source registry: {}", err);
+        }
+        for (index, source) in self.external_sources.iter().enumerate() {
+            if let Err(err) = source.add_event_subscriber(new_subscriber(index + 1)) {
+                debug!("Not watching read-only source registry: {}", err);
+            }
+        }
+    }
+}
+
+/// Receives the changes to one of the source registries of a `UnifiedRegistry` and sends the
+/// resulting changes to the unified view of the nodes to the `UnifiedRegistry`'s subscribers.
+///
+/// The sources are held as weak references, so the subscriber does not keep the sources alive.
+#[cfg(feature = "registry-notifications")]
+struct SourceSubscriber {
+    /// The precedence of the source that sent the events; 0 is the internal source, and the
+    /// read-only sources follow in order
+    source_index: usize,
+    internal_source: Weak<dyn RwRegistry>,
+    external_sources: Vec<Weak<dyn RegistryReader>>,
+    subscribers: RegistryEventSubscribers,
+}
+
+#[cfg(feature = "registry-notifications")]
+impl RegistryEventSubscriber for SourceSubscriber {
+    fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+        if self.subscribers.is_empty() {
+            return Ok(());
+        }
+
+        let (previous, current) = match event {
+            RegistryEvent::NodeAdded(node) => (None, Some(node.clone())),
+            RegistryEvent::NodeRemoved(node) => (Some(node.clone()), None),
+            RegistryEvent::NodeUpdated { previous, node } => {
+                (Some(previous.clone()), Some(node.clone()))
+            }
+        };
+
+        // Fetch the node from every other source, in descending order of precedence; the
+        // source that sent the event is filled in below with the node before and after the change
+        let internal_source = self
+            .internal_source
+            .upgrade()
+            .ok_or(RegistrySubscriberError::Unsubscribe)?;
+        let mut nodes = vec![None];
+        if self.source_index != 0 {
+            nodes[0] = fetch_source_node(&*internal_source, event.identity());
+        }
+        for (index, source) in self.external_sources.iter().enumerate() {
+            let source = source
+                .upgrade()
+                .ok_or(RegistrySubscriberError::Unsubscribe)?;
+            if index + 1 == self.source_index {
+                nodes.push(None);
+            } else {
+                nodes.push(fetch_source_node(&*source, event.identity()));
+            }
+        }
+
+        let unified_node = |source_node: Option<Node>| {
+            let mut nodes = nodes.clone();
+            nodes[self.source_index] = source_node;
+            merge_nodes(nodes.into_iter().rev())
+        };
+
+        if let Some(event) =
+            RegistryEvent::from_change(unified_node(previous), unified_node(current))
+        {
+            self.subscribers.broadcast(&[event]);
+        }
+
+        Ok(())
+    }
+}
+
+/// Fetches the node from a source registry, logging and ignoring any error.
+#[cfg(feature = "registry-notifications")]
+fn fetch_source_node<R>(source: &R, identity: &str) -> Option<Node>
+where
+    R: RegistryReader + ?Sized,
+{
+    source.get_node(identity).unwrap_or_else(|err| {
+        debug!("Failed to fetch node from source registry: {}", err);
+        None
+    })
+}
+
+/// Merges the definitions of a node from each source (in ascending order of precedence), giving
+/// the highest-precedence definition of the node with the merged metadata of all definitions.
+fn merge_nodes<I>(nodes: I) -> Option<Node>
+where
+    I: Iterator<Item = Option<Node>>,
+{
+    nodes.fold(None, |final_opt, fetch_opt| {
+        match fetch_opt {
+            Some(mut node) => {
+                // If the node was already found at a lower precedence, merge metadata
+                if let Some(existing) = final_opt {
+                    // Overwrite the existing node's metadata with the new node's if they share the
+                    // same metadata keys
+                    let mut merged_metadata = existing.metadata;
+                    merged_metadata.extend(node.metadata);
+                    node.metadata = merged_metadata;
+                }
+                Some(node)
+            }
+            None => final_opt,
+        }
+    })
 }
 
 impl RegistryWriter for UnifiedRegistry {
@@ -225,6 +379,15 @@ impl RwRegistry for UnifiedRegistry {
     fn clone_box_as_writer(&self) -> Box<dyn RegistryWriter> {
         Box::new(self.clone())
     }
+
+    /// Only the nodes of the internal source can be changed; the nodes of the read-only sources
+    /// are not included, even if the internal source has no node with the same identity.
+    fn list_writable_nodes<'a, 'b: 'a>(
+        &'b self,
+        predicates: &'a [MetadataPredicate],
+    ) -> Result<NodeIter<'a>, RegistryError> {
+        self.internal_source.list_nodes(predicates)
+    }
 }
 
 #[cfg(test)]
@@ -600,6 +763,35 @@ mod test {
         assert_eq!(None, nodes.next());
     }
 
+    /// Verify that only the nodes of the internal registry are listed as writable, without the
+    /// metadata merged in from read-only registries.
+    #[test]
+    fn list_writable_nodes() {
+        let node1_internal = new_node("node1", "endpoint1", &[("meta_a", "val_a")]);
+        let node1_read_only = new_node("node1", "endpoint3", &[("meta_b", "val_b")]);
+        let node2 = new_node("node2", "endpoint2", &[("meta_a", "val_a")]);
+
+        let writable = MemRegistry::default();
+        writable
+            .add_node(node1_internal.clone())
+            .expect("Unable to insert internal node1");
+
+        let readable = MemRegistry::default();
+        readable
+            .add_node(node1_read_only)
+            .expect("Unable to insert read-only node1");
+        readable.add_node(node2).expect("Unable to insert node2");
+
+        let unified = UnifiedRegistry::new(Box::new(writable), vec![Box::new(readable)]);
+
+        let mut nodes = unified
+            .list_writable_nodes(&[])
+            .expect("Unable to list writable nodes");
+
+        assert_eq!(Some(node1_internal), nodes.next());
+        assert_eq!(None, nodes.next());
+    }
+
     /// Verify that the `NodeRegistryWriter` implementation affects only the internal registry.
     #[test]
     fn write_nodes() {
@@ -659,6 +851,104 @@ mod test {
             .expect("Unable to check writeable for node1"));
     }
 
+    /// Verify that a subscriber is notified of the changes to the unified view of the nodes:
+    ///
+    /// 1. Adding a node to the internal source that overrides a read-only node is reported as an
+    ///    update with merged metadata
+    /// 2. Changing the endpoints of the read-only node while it is overridden is not reported
+    /// 3. Deleting the internal node is reported as an update back to the read-only node
+    /// 4. Removing the read-only node is reported as a removal
+    #[cfg(feature = "registry-notifications")]
+    #[test]
+    fn event_subscriber() {
+        use crate::registry::{LocalYamlRegistry, RegistryEvent, RegistrySubscriberError};
+
+        struct ChannelSubscriber(std::sync::mpsc::Sender<RegistryEvent>);
+
+        impl RegistryEventSubscriber for ChannelSubscriber {
+            fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+                self.0
+                    .send(event.clone())
+                    .map_err(|_| RegistrySubscriberError::Unsubscribe)
+            }
+        }
+
+        let temp_dir =
+            tempdir::TempDir::new("unified_event_subscriber").expect("Failed to create temp dir");
+        let path = |name: &str| {
+            temp_dir
+                .path()
+                .join(name)
+                .to_str()
+                .expect("Failed to get path")
+                .to_string()
+        };
+
+        let writeable = LocalYamlRegistry::new(&path("internal.yaml"))
+            .expect("Failed to create internal registry");
+        let readable = LocalYamlRegistry::new(&path("read_only.yaml"))
+            .expect("Failed to create read-only registry");
+
+        let node_read_only = new_node("node1", "endpoint1", &[("meta_a", "val_a")]);
+        readable
+            .add_node(node_read_only.clone())
+            .expect("Unable to insert read-only node");
+
+        let unified = UnifiedRegistry::new(
+            Box::new(writeable.clone()),
+            vec![Box::new(readable.clone())],
+        );
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        unified
+            .add_event_subscriber(Box::new(ChannelSubscriber(tx)))
+            .expect("Unable to add subscriber");
+
+        let node_internal = new_node("node1", "endpoint2", &[("meta_b", "val_b")]);
+        unified
+            .add_node(node_internal)
+            .expect("Unable to insert internal node");
+        let merged_node = new_node(
+            "node1",
+            "endpoint2",
+            &[("meta_a", "val_a"), ("meta_b", "val_b")],
+        );
+        let event = rx.try_recv().expect("Add event not received");
+        assert!(event.endpoints_changed());
+        assert_eq!(
+            event,
+            RegistryEvent::NodeUpdated {
+                previous: node_read_only,
+                node: merged_node.clone(),
+            }
+        );
+
+        let moved_read_only = new_node("node1", "endpoint3", &[("meta_a", "val_a")]);
+        readable
+            .update_node(moved_read_only.clone())
+            .expect("Unable to update read-only node");
+        assert!(rx.try_recv().is_err());
+
+        unified
+            .delete_node("node1")
+            .expect("Unable to delete internal node");
+        assert_eq!(
+            rx.try_recv().expect("Delete event not received"),
+            RegistryEvent::NodeUpdated {
+                previous: merged_node,
+                node: moved_read_only.clone(),
+            }
+        );
+
+        readable
+            .delete_node("node1")
+            .expect("Unable to delete read-only node");
+        assert_eq!(
+            rx.try_recv().expect("Remove event not received"),
+            RegistryEvent::NodeRemoved(moved_read_only)
+        );
+    }
+
     #[derive(Clone, Default)]
     struct MemRegistry {
         nodes: Arc<Mutex<HashMap<String, Node>>>,
@@ -27,10 +27,14 @@ use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
+#[cfg(feature = "registry-notifications")]
+use crate::registry::subscriber::{diff_nodes, RegistryEventSubscribers};
 use crate::registry::{
     error::InvalidNodeError, validate_nodes, MetadataPredicate, Node, NodeIter, RegistryError,
     RegistryReader, RegistryWriter, RwRegistry,
 };
+#[cfg(feature = "registry-notifications")]
+use crate::registry::{RegistryEvent, RegistryEventSubscriber};
 
 use crate::error::{InternalError, InvalidStateError};
 
@@ -51,10 +55,15 @@ use super::YamlNode;
 /// file already exists, the registry will attempt to load, parse, and validate it. If the backing
 /// file does not already exist, the registry will attempt to create it.
 ///
+/// If the experimental `registry-notifications` feature is enabled, subscribers are notified of
+/// the changes made through the registry and of the changes found when the file is reloaded.
+///
 /// [`Node`]: struct.Node.html
 #[derive(Clone)]
 pub struct LocalYamlRegistry {
     internal: Arc<Mutex<Internal>>,
+    #[cfg(feature = "registry-notifications")]
+    subscribers: RegistryEventSubscribers,
 }
 
 impl LocalYamlRegistry {
@@ -69,32 +78,42 @@ impl LocalYamlRegistry {
     pub fn new(file_path: &str) -> Result<LocalYamlRegistry, RegistryError> {
         Ok(LocalYamlRegistry {
             internal: Arc::new(Mutex::new(Internal::new(file_path)?)),
+            #[cfg(feature = "registry-notifications")]
+            subscribers: RegistryEventSubscribers::default(),
         })
     }
 
     /// Get all nodes in the registry.
     pub(super) fn get_nodes(&self) -> Result<Vec<Node>, RegistryError> {
-        Ok(self
-            .internal
-            .lock()
-            .map_err(|_| {
-                RegistryError::InternalError(InternalError::with_message(
-                    "YAML registry's internal lock poisoned".into(),
-                ))
-            })?
-            .get_nodes())
+        self.with_internal(Internal::get_nodes)
     }
 
     /// Write the given list of nodes to the backing YAML file.
     pub(super) fn write_nodes(&self, nodes: Vec<Node>) -> Result<(), RegistryError> {
-        self.internal
-            .lock()
-            .map_err(|_| {
-                RegistryError::InternalError(InternalError::with_message(
-                    "YAML registry's internal lock poisoned".into(),
-                ))
-            })?
-            .write_nodes(nodes)
+        self.with_internal(|internal| internal.write_nodes(nodes))?
+    }
+
+    /// Acquire the lock for the internal state and run `f` with it. Any changes to the nodes are
+    /// sent to the subscribers once the lock is released.
+    fn with_internal<T, F>(&self, f: F) -> Result<T, RegistryError>
+    where
+        F: FnOnce(&mut Internal) -> T,
+    {
+        let mut internal = self.internal.lock().map_err(|_| {
+            RegistryError::InternalError(InternalError::with_message(
+                "YAML registry's internal lock poisoned".into(),
+            ))
+        })?;
+        let result = f(&mut internal);
+
+        #[cfg(feature = "registry-notifications")]
+        {
+            let events = std::mem::take(&mut internal.pending_events);
+            drop(internal);
+            self.subscribers.broadcast(&events);
+        }
+
+        Ok(result)
     }
 }
 
@@ -130,6 +149,20 @@ impl RegistryReader for LocalYamlRegistry {
             .iter()
             .any(|node| node.identity == identity))
     }
+
+    #[cfg(feature = "registry-notifications")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        self.subscribers.add_subscriber(subscriber);
+        Ok(())
+    }
+
+    #[cfg(feature = "registry-notifications")]
+    fn prune_event_subscribers(&self) {
+        self.subscribers.prune();
+    }
 }
 
 impl RegistryWriter for LocalYamlRegistry {
@@ -191,6 +224,9 @@ struct Internal {
     file_path: String,
     cached_nodes: Vec<Node>,
     last_read: SystemTime,
+    /// Changes to the cached nodes that have not been sent to the subscribers yet
+    #[cfg(feature = "registry-notifications")]
+    pending_events: Vec<RegistryEvent>,
 }
 
 impl Internal {
@@ -199,6 +235,8 @@ impl Internal {
             file_path: file_path.into(),
             cached_nodes: vec![],
             last_read: SystemTime::UNIX_EPOCH,
+            #[cfg(feature = "registry-notifications")]
+            pending_events: vec![],
         };
 
         // If file already exists, read it; otherwise initialize it.
@@ -208,6 +246,10 @@ impl Internal {
             internal.write_nodes(vec![])?;
         }
 
+        // The initial contents of the file are not changes
+        #[cfg(feature = "registry-notifications")]
+        internal.pending_events.clear();
+
         Ok(internal)
     }
 
@@ -273,6 +315,9 @@ impl Internal {
             RegistryError::InvalidStateError(InvalidStateError::with_message(err.to_string()))
         })?;
 
+        #[cfg(feature = "registry-notifications")]
+        self.pending_events
+            .extend(diff_nodes(&self.cached_nodes, &nodes));
         self.cached_nodes = nodes;
         self.last_read = SystemTime::now();
 
@@ -320,6 +365,9 @@ impl Internal {
             ))
         })?;
 
+        #[cfg(feature = "registry-notifications")]
+        self.pending_events
+            .extend(diff_nodes(&self.cached_nodes, &nodes));
         self.cached_nodes = nodes;
         self.last_read = SystemTime::now();
 
@@ -1264,6 +1312,87 @@ mod test {
         assert_eq!(nodes, vec![get_node_1()]);
     }
 
+    ///
+    /// Verifies that a subscriber is notified of the nodes added, updated and deleted through
+    /// the registry, and of the changes found when the file is modified directly.
+    ///
+    #[cfg(feature = "registry-notifications")]
+    #[test]
+    fn test_event_subscriber() {
+        let temp_dir = TempDir::new("test_event_subscriber").expect("Failed to create temp dir");
+        let path = temp_dir
+            .path()
+            .join("registry.yaml")
+            .to_str()
+            .expect("Failed to get path")
+            .to_string();
+
+        write_to_file(&[get_node_1()], &path);
+
+        let registry = LocalYamlRegistry::new(&path).expect("Failed to create LocalYamlRegistry");
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        registry
+            .add_event_subscriber(Box::new(ChannelSubscriber(tx)))
+            .expect("Failed to add subscriber");
+
+        registry.add_node(get_node_2()).expect("Unable to add node");
+        assert_eq!(
+            rx.try_recv().expect("Add event not received"),
+            RegistryEvent::NodeAdded(get_node_2())
+        );
+
+        let mut moved_node = get_node_1();
+        moved_node.endpoints = vec!["tcps://12.0.0.123:8436".into()];
+        registry
+            .update_node(moved_node.clone())
+            .expect("Unable to update node");
+        let event = rx.try_recv().expect("Update event not received");
+        assert!(event.endpoints_changed());
+        assert_eq!(
+            event,
+            RegistryEvent::NodeUpdated {
+                previous: get_node_1(),
+                node: moved_node.clone(),
+            }
+        );
+
+        registry
+            .delete_node(&get_node_2().identity)
+            .expect("Unable to delete node");
+        assert_eq!(
+            rx.try_recv().expect("Delete event not received"),
+            RegistryEvent::NodeRemoved(get_node_2())
+        );
+
+        // Allow some time before writing the file to make sure the read time is earlier than the
+        // write time; the sytem clock may not be very precise.
+        std::thread::sleep(std::time::Duration::from_secs(1));
+
+        write_to_file(&[moved_node.clone(), get_node_3()], &path);
+        registry.get_nodes().expect("Failed to get nodes");
+        assert_eq!(
+            rx.try_recv().expect("Reload event not received"),
+            RegistryEvent::NodeAdded(get_node_3())
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[cfg(feature = "registry-notifications")]
+    struct ChannelSubscriber(std::sync::mpsc::Sender<RegistryEvent>);
+
+    #[cfg(feature = "registry-notifications")]
+    impl RegistryEventSubscriber for ChannelSubscriber {
+        fn handle_event(
+            &self,
+            event: &RegistryEvent,
+        ) -> Result<(), crate::registry::RegistrySubscriberError> {
+            self.0
+                .send(event.clone())
+                .map_err(|_| crate::registry::RegistrySubscriberError::Unsubscribe)
+        }
+    }
+
     fn get_node_1() -> Node {
         Node::builder("Node-123")
             .with_endpoint("tcps://12.0.0.123:8431")
@@ -26,6 +26,11 @@ pub use crate::registry::error::InvalidNodeError;
 use super::Node;
 
 pub use local::LocalYamlRegistry;
+#[cfg(feature = "registry-remote-signing")]
+pub use remote::{
+    create_signed_feed, RemoteYamlRegistryBuilder, RemoteYamlRegistryStatus,
+    RemoteYamlRegistryStatusReader, RemoteYamlValidation,
+};
 #[cfg(feature = "registry-remote")]
 pub use remote::{RemoteYamlRegistry, RemoteYamlShutdownHandle};
 
@@ -20,6 +20,11 @@
 //! [`RemoteYamlRegistry`]: struct.RemoteYamlRegistry.html
 //! [`RegistryReader`]: ../../trait.RegistryReader.html
 
+#[cfg(feature = "registry-remote-signing")]
+use std::fmt;
+#[cfg(feature = "registry-remote-signing")]
+use std::fs;
+
 use std::convert::TryFrom;
 use std::path::Path;
 use std::sync::{
@@ -29,14 +34,22 @@ use std::sync::{
 use std::thread;
 use std::time::{Duration, Instant};
 
+#[cfg(feature = "registry-remote-signing")]
+use cylinder::{secp256k1::Secp256k1Context, Context, PublicKey, Signature, Signer, Verifier};
 use openssl::hash::{hash, MessageDigest};
 
 use crate::error::{InternalError, InvalidStateError};
+#[cfg(feature = "registry-remote-signing")]
+use crate::hex::parse_hex;
 use crate::hex::to_hex;
+#[cfg(feature = "registry-notifications")]
+use crate::registry::subscriber::{diff_nodes, RegistryEventSubscribers};
 use crate::registry::{
     error::InvalidNodeError, validate_nodes, MetadataPredicate, Node, NodeIter, RegistryError,
     RegistryReader,
 };
+#[cfg(feature = "registry-notifications")]
+use crate::registry::{RegistryEvent, RegistryEventSubscriber};
 use crate::threading::lifecycle::ShutdownHandle;
 
 use super::{LocalYamlRegistry, YamlNode};
@@ -71,12 +84,27 @@ use super::{LocalYamlRegistry, YamlNode};
 /// and the previously cached registry values will continue to be used. The next time the registry
 /// is read, it will try again to refresh the cache.
 ///
+/// If the registry is built with a signing key (see [`RemoteYamlRegistryBuilder`]), the remote
+/// file must instead be a signed feed: a YAML mapping with a `payload` string, containing a YAML
+/// mapping of the feed's `version` and its `nodes`, and a hex-encoded `signature` of the payload
+/// by the signing key (see [`create_signed_feed`]). A feed is rejected if its signature is
+/// invalid, if its version is lower than the version of the cached feed (a rollback), or if it
+/// has the same version as the cached feed but different contents (a conflict). The version of
+/// the cached feed is saved alongside the cache, so rollbacks are also detected across restarts.
+/// The result of validating the most recently fetched file can be read with
+/// [`RemoteYamlRegistry::status`].
+///
+/// If the experimental `registry-notifications` feature is enabled, subscribers are notified of
+/// the changes found each time the cache is refreshed.
+///
 /// [`Node`]: struct.Node.html
 /// [`RegistryReader`]: trait.RegistryReader.html
 /// [`constructor`]: struct.RemoteYamlRegistry.html#method.new
 pub struct RemoteYamlRegistry {
     internal: Arc<Mutex<Internal>>,
     shutdown_handle: Option<RemoteYamlShutdownHandle>,
+    #[cfg(feature = "registry-notifications")]
+    subscribers: RegistryEventSubscribers,
 }
 
 impl RemoteYamlRegistry {
@@ -97,13 +125,35 @@ impl RemoteYamlRegistry {
         cache_dir: &str,
         automatic_refresh_period: Option<Duration>,
         forced_refresh_period: Option<Duration>,
+    ) -> Result<Self, RegistryError> {
+        Self::start(
+            url,
+            cache_dir,
+            automatic_refresh_period,
+            forced_refresh_period,
+            #[cfg(feature = "registry-remote-signing")]
+            None,
+        )
+    }
+
+    fn start(
+        url: &str,
+        cache_dir: &str,
+        automatic_refresh_period: Option<Duration>,
+        forced_refresh_period: Option<Duration>,
+        #[cfg(feature = "registry-remote-signing")] feed_verification: Option<FeedVerification>,
     ) -> Result<Self, RegistryError> {
         let internal = Arc::new(Mutex::new(Internal::new(
             url,
             cache_dir,
             forced_refresh_period,
+            #[cfg(feature = "registry-remote-signing")]
+            feed_verification,
         )?));
 
+        #[cfg(feature = "registry-notifications")]
+        let subscribers = RegistryEventSubscribers::default();
+
         let (running, join_handle) = {
             if let Some(refresh_period) = automatic_refresh_period {
                 let running = Arc::new(AtomicBool::new(true));
@@ -111,6 +161,8 @@ impl RemoteYamlRegistry {
                 let thread_internal = internal.clone();
                 let thread_url = url.to_string();
                 let thread_running = running.clone();
+                #[cfg(feature = "registry-notifications")]
+                let thread_subscribers = subscribers.clone();
                 let join_handle = thread::Builder::new()
                     .name(format!("Remote Registry Automatic Refresh: {}", url))
                     .spawn(move || {
@@ -119,6 +171,8 @@ impl RemoteYamlRegistry {
                             thread_internal,
                             &thread_url,
                             thread_running,
+                            #[cfg(feature = "registry-notifications")]
+                            thread_subscribers,
                         )
                     })
                     .map_err(|err| {
@@ -144,6 +198,8 @@ impl RemoteYamlRegistry {
         Ok(Self {
             internal,
             shutdown_handle: Some(shutdown_handle),
+            #[cfg(feature = "registry-notifications")]
+            subscribers,
         })
     }
 
@@ -151,16 +207,40 @@ impl RemoteYamlRegistry {
         self.shutdown_handle.take()
     }
 
+    /// Returns the registry's signed feed version and the result of validating the most recently
+    /// fetched remote file.
+    #[cfg(feature = "registry-remote-signing")]
+    pub fn status(&self) -> Result<RemoteYamlRegistryStatus, RegistryError> {
+        self.status_reader().status()
+    }
+
+    /// Returns a reader for the registry's status that remains usable after the registry has been
+    /// moved, e.g. into a `UnifiedRegistry`.
+    #[cfg(feature = "registry-remote-signing")]
+    pub fn status_reader(&self) -> RemoteYamlRegistryStatusReader {
+        RemoteYamlRegistryStatusReader {
+            internal: self.internal.clone(),
+        }
+    }
+
     /// Acquire the lock for the internal cache and get the nodes from it.
     fn get_nodes(&self) -> Result<Vec<Node>, RegistryError> {
-        self.internal
-            .lock()
-            .map_err(|_| {
-                RegistryError::InternalError(InternalError::with_message(
-                    "Internal lock poisoned".into(),
-                ))
-            })?
-            .get_nodes()
+        let mut internal = self.internal.lock().map_err(|_| {
+            RegistryError::InternalError(InternalError::with_message(
+                "Internal lock poisoned".into(),
+            ))
+        })?;
+        let nodes = internal.get_nodes();
+
+        // Send any changes from a refresh once the lock is released
+        #[cfg(feature = "registry-notifications")]
+        {
+            let events = std::mem::take(&mut internal.pending_events);
+            drop(internal);
+            self.subscribers.broadcast(&events);
+        }
+
+        nodes
     }
 }
 
@@ -188,6 +268,20 @@ impl RegistryReader for RemoteYamlRegistry {
             .filter(move |node| predicates.iter().all(|predicate| predicate.apply(node)))
             .count() as u32)
     }
+
+    #[cfg(feature = "registry-notifications")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        self.subscribers.add_subscriber(subscriber);
+        Ok(())
+    }
+
+    #[cfg(feature = "registry-notifications")]
+    fn prune_event_subscribers(&self) {
+        self.subscribers.prune();
+    }
 }
 
 /// Holds the internal state of the remote registry.
@@ -197,6 +291,17 @@ struct Internal {
     last_refresh_successful: bool,
     forced_refresh_period: Option<Duration>,
     next_forced_refresh: Option<Instant>,
+    #[cfg(feature = "registry-remote-signing")]
+    feed_verification: Option<FeedVerification>,
+    #[cfg(feature = "registry-remote-signing")]
+    feed_state_file: String,
+    #[cfg(feature = "registry-remote-signing")]
+    feed_state: Option<FeedState>,
+    #[cfg(feature = "registry-remote-signing")]
+    validation: RemoteYamlValidation,
+    /// Changes to the cached nodes that have not been sent to the subscribers yet
+    #[cfg(feature = "registry-notifications")]
+    pending_events: Vec<RegistryEvent>,
 }
 
 impl Internal {
@@ -205,17 +310,33 @@ impl Internal {
         url: &str,
         cache_dir: &str,
         forced_refresh_period: Option<Duration>,
+        #[cfg(feature = "registry-remote-signing")] feed_verification: Option<FeedVerification>,
     ) -> Result<Self, RegistryError> {
         let url = url.to_string();
 
         let cache = LocalYamlRegistry::new(&compute_cache_filename(&url, cache_dir)?)?;
 
+        #[cfg(feature = "registry-remote-signing")]
+        let feed_state_file = compute_feed_state_filename(&url, cache_dir)?;
+        #[cfg(feature = "registry-remote-signing")]
+        let feed_state = read_feed_state(&feed_state_file)?;
+
         let mut internal = Self {
             url,
             cache,
             last_refresh_successful: false,
             forced_refresh_period,
             next_forced_refresh: None,
+            #[cfg(feature = "registry-remote-signing")]
+            feed_verification,
+            #[cfg(feature = "registry-remote-signing")]
+            feed_state_file,
+            #[cfg(feature = "registry-remote-signing")]
+            feed_state,
+            #[cfg(feature = "registry-remote-signing")]
+            validation: RemoteYamlValidation::Pending,
+            #[cfg(feature = "registry-notifications")]
+            pending_events: vec![],
         };
 
         // If initial fetch/cache fails, it will be re-attempted on the next registry read, so just
@@ -227,13 +348,16 @@ impl Internal {
             );
         }
 
+        // There are no subscribers yet to send the initial changes to
+        #[cfg(feature = "registry-notifications")]
+        internal.pending_events.clear();
+
         Ok(internal)
     }
 
     /// Attempt to refresh the internal cache and update state accordingly.
     fn refresh_cache(&mut self) -> Result<(), RegistryError> {
-        fetch_nodes_from_remote(&self.url)
-            .and_then(|nodes| self.cache.write_nodes(nodes))
+        self.fetch_and_cache()
             .map_err(|err| {
                 self.last_refresh_successful = false;
                 err
@@ -258,6 +382,98 @@ impl Internal {
             })
     }
 
+    /// Fetch the remote file and write its nodes to the cache.
+    #[cfg(not(feature = "registry-remote-signing"))]
+    fn fetch_and_cache(&mut self) -> Result<(), RegistryError> {
+        fetch_nodes_from_remote(&self.url).and_then(|nodes| self.write_cache(nodes))
+    }
+
+    /// Write the nodes to the cache, recording the changes for the subscribers.
+    fn write_cache(&mut self, nodes: Vec<Node>) -> Result<(), RegistryError> {
+        #[cfg(feature = "registry-notifications")]
+        let events = diff_nodes(&self.cache.get_nodes()?, &nodes);
+
+        self.cache.write_nodes(nodes)?;
+
+        #[cfg(feature = "registry-notifications")]
+        self.pending_events.extend(events);
+
+        Ok(())
+    }
+
+    /// Fetch and validate the remote file, write its nodes to the cache and record the result of
+    /// the validation.
+    #[cfg(feature = "registry-remote-signing")]
+    fn fetch_and_cache(&mut self) -> Result<(), RegistryError> {
+        let bytes = match fetch_remote_file(&self.url) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                self.validation = RemoteYamlValidation::Unavailable(err.to_string());
+                return Err(err);
+            }
+        };
+
+        let feed = match validate_feed(&bytes, self.feed_verification.as_ref())
+            .and_then(|feed| self.check_feed_state(feed))
+        {
+            Ok(feed) => feed,
+            Err(validation) => {
+                let err = RegistryError::InvalidStateError(InvalidStateError::with_message(
+                    validation.to_string(),
+                ));
+                self.validation = validation;
+                return Err(err);
+            }
+        };
+
+        // The feed state is saved before the nodes, so a failure to write the nodes will be
+        // recovered by the next refresh of the same feed
+        if let Some(feed_state) = &feed.feed_state {
+            write_feed_state(&self.feed_state_file, feed_state)?;
+        }
+        self.write_cache(feed.nodes)?;
+
+        if feed.feed_state.is_some() {
+            self.feed_state = feed.feed_state;
+        }
+        self.validation = RemoteYamlValidation::Valid;
+
+        Ok(())
+    }
+
+    /// Reject a signed feed that would roll back or conflict with the cached feed.
+    #[cfg(feature = "registry-remote-signing")]
+    fn check_feed_state(&self, feed: ValidatedFeed) -> Result<ValidatedFeed, RemoteYamlValidation> {
+        if let (Some(current), Some(new)) = (&self.feed_state, &feed.feed_state) {
+            if new.version < current.version {
+                return Err(RemoteYamlValidation::Rollback {
+                    version: new.version,
+                    current_version: current.version,
+                });
+            }
+            if new.version == current.version && new.digest != current.digest {
+                return Err(RemoteYamlValidation::Conflict {
+                    version: new.version,
+                });
+            }
+        }
+
+        Ok(feed)
+    }
+
+    #[cfg(feature = "registry-remote-signing")]
+    fn status(&self) -> RemoteYamlRegistryStatus {
+        RemoteYamlRegistryStatus {
+            url: self.url.clone(),
+            signed: self.feed_verification.is_some(),
+            version: self
+                .feed_state
+                .as_ref()
+                .map(|feed_state| feed_state.version),
+            validation: self.validation.clone(),
+        }
+    }
+
     /// Attempt to refresh the internal cache if necessary and return the cache's contents.
     fn get_nodes(&mut self) -> Result<Vec<Node>, RegistryError> {
         // If the last attempt to refresh the cache wasn't successful, try again
@@ -295,16 +511,18 @@ impl Internal {
 // Derive the filename for the cache from a hash of the URL; this makes the location deterministic,
 // which allows the local cache to be used across restarts.
 fn compute_cache_filename(url: &str, cache_dir: &str) -> Result<String, RegistryError> {
-    let hash = hash(MessageDigest::sha256(), url.as_bytes())
-        .map(|digest| to_hex(&*digest))
-        .map_err(|err| {
-            RegistryError::InternalError(InternalError::from_source_with_message(
-                Box::new(err),
-                "Failed to hash URL for cache file".into(),
-            ))
-        })?;
+    let filename = format!("remote_registry_{}.yaml", hash_url(url)?);
+    Ok(Path::new(cache_dir)
+        .join(filename)
+        .to_str()
+        .expect("path built from &str cannot be invalid")
+        .to_string())
+}
 
-    let filename = format!("remote_registry_{}.yaml", hash);
+// Derive the filename for the signed feed state the same way as the cache's filename.
+#[cfg(feature = "registry-remote-signing")]
+fn compute_feed_state_filename(url: &str, cache_dir: &str) -> Result<String, RegistryError> {
+    let filename = format!("remote_registry_{}.feed.yaml", hash_url(url)?);
     Ok(Path::new(cache_dir)
         .join(filename)
         .to_str()
@@ -312,8 +530,25 @@ fn compute_cache_filename(url: &str, cache_dir: &str) -> Result<String, Registry
         .to_string())
 }
 
+fn hash_url(url: &str) -> Result<String, RegistryError> {
+    hash(MessageDigest::sha256(), url.as_bytes())
+        .map(|digest| to_hex(&*digest))
+        .map_err(|err| {
+            RegistryError::InternalError(InternalError::from_source_with_message(
+                Box::new(err),
+                "Failed to hash URL for cache file".into(),
+            ))
+        })
+}
+
 /// Fetch, parse, and validate the YAML registry file at the given URL.
+#[cfg(not(feature = "registry-remote-signing"))]
 fn fetch_nodes_from_remote(url: &str) -> Result<Vec<Node>, RegistryError> {
+    parse_nodes(&fetch_remote_file(url)?)
+}
+
+/// Fetch the contents of the remote registry file at the given URL.
+fn fetch_remote_file(url: &str) -> Result<Vec<u8>, RegistryError> {
     let bytes = reqwest::blocking::get(url)
         .and_then(|response| response.error_for_status())
         .map_err(|err| {
@@ -329,12 +564,22 @@ fn fetch_nodes_from_remote(url: &str) -> Result<Vec<Node>, RegistryError> {
                 "Failed to get bytes from remote registry file HTTP response".into(),
             ))
         })?;
-    let yaml_nodes: Vec<YamlNode> = serde_yaml::from_slice(&bytes).map_err(|_| {
+
+    Ok(bytes.to_vec())
+}
+
+/// Parse and validate a YAML sequence of nodes.
+fn parse_nodes(bytes: &[u8]) -> Result<Vec<Node>, RegistryError> {
+    let yaml_nodes: Vec<YamlNode> = serde_yaml::from_slice(bytes).map_err(|_| {
         RegistryError::InternalError(InternalError::with_message(
             "Failed to deserialize remote registry file: Not a valid YAML sequence of nodes".into(),
         ))
     })?;
 
+    into_valid_nodes(yaml_nodes)
+}
+
+fn into_valid_nodes(yaml_nodes: Vec<YamlNode>) -> Result<Vec<Node>, RegistryError> {
     let nodes: Vec<Node> = yaml_nodes
         .into_iter()
         .map(Node::try_from)
@@ -360,6 +605,7 @@ fn automatic_refresh_loop(
     internal: Arc<Mutex<Internal>>,
     url: &str,
     running: Arc<AtomicBool>,
+    #[cfg(feature = "registry-notifications")] subscribers: RegistryEventSubscribers,
 ) {
     loop {
         // Wait the `refresh_period`, checking for shutdown every second
@@ -400,7 +646,392 @@ fn automatic_refresh_loop(
                 }
             }
         }
+
+        #[cfg(feature = "registry-notifications")]
+        {
+            let events = std::mem::take(&mut internal.pending_events);
+            drop(internal);
+            subscribers.broadcast(&events);
+        }
+    }
+}
+
+/// Builds a [`RemoteYamlRegistry`] that may require the remote file to be a signed feed.
+#[cfg(feature = "registry-remote-signing")]
+#[derive(Default)]
+pub struct RemoteYamlRegistryBuilder {
+    url: Option<String>,
+    cache_dir: Option<String>,
+    automatic_refresh_period: Option<Duration>,
+    forced_refresh_period: Option<Duration>,
+    signing_key: Option<PublicKey>,
+    signature_verifier: Option<Box<dyn Verifier>>,
+}
+
+#[cfg(feature = "registry-remote-signing")]
+impl RemoteYamlRegistryBuilder {
+    /// Constructs a new builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the URL of the registry's backing YAML file. This is a required value.
+    pub fn with_url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    /// Sets the directory that the local registry cache will be stored in. This is a required
+    /// value.
+    pub fn with_cache_dir(mut self, cache_dir: String) -> Self {
+        self.cache_dir = Some(cache_dir);
+        self
+    }
+
+    /// Sets the amount of time between attempts to automatically fetch and cache the remote YAML
+    /// file in the background. If not set, background refreshes will be disabled.
+    pub fn with_automatic_refresh_period(mut self, automatic_refresh_period: Duration) -> Self {
+        self.automatic_refresh_period = Some(automatic_refresh_period);
+        self
+    }
+
+    /// Sets the amount of time since the last successful cache refresh before attempting to
+    /// refresh on every read operation. If not set, forced refreshes will be disabled.
+    pub fn with_forced_refresh_period(mut self, forced_refresh_period: Duration) -> Self {
+        self.forced_refresh_period = Some(forced_refresh_period);
+        self
+    }
+
+    /// Sets the public key that the remote file must be signed by. If set, the remote file must
+    /// be a signed feed.
+    pub fn with_signing_key(mut self, signing_key: PublicKey) -> Self {
+        self.signing_key = Some(signing_key);
+        self
+    }
+
+    /// Sets the verifier for the signed feed's signature. If not set, a secp256k1 verifier is
+    /// used.
+    pub fn with_signature_verifier(mut self, signature_verifier: Box<dyn Verifier>) -> Self {
+        self.signature_verifier = Some(signature_verifier);
+        self
+    }
+
+    /// Builds the `RemoteYamlRegistry`, attempting to fetch and cache the remote file
+    /// immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidStateError` if the URL or cache directory have not been set, and an
+    /// `InternalError` if the saved state of a signed feed can not be read.
+    pub fn build(self) -> Result<RemoteYamlRegistry, RegistryError> {
+        let url = self.url.ok_or_else(|| {
+            RegistryError::InvalidStateError(InvalidStateError::with_message(
+                "A remote registry requires a URL".into(),
+            ))
+        })?;
+        let cache_dir = self.cache_dir.ok_or_else(|| {
+            RegistryError::InvalidStateError(InvalidStateError::with_message(
+                "A remote registry requires a cache directory".into(),
+            ))
+        })?;
+
+        let signature_verifier = self.signature_verifier;
+        let feed_verification = self.signing_key.map(|public_key| FeedVerification {
+            verifier: signature_verifier.unwrap_or_else(|| Secp256k1Context::new().new_verifier()),
+            public_key,
+        });
+
+        RemoteYamlRegistry::start(
+            &url,
+            &cache_dir,
+            self.automatic_refresh_period,
+            self.forced_refresh_period,
+            feed_verification,
+        )
+    }
+}
+
+/// The result of validating the most recently fetched remote registry file.
+#[cfg(feature = "registry-remote-signing")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RemoteYamlValidation {
+    /// No remote file has been fetched yet
+    Pending,
+    /// The remote file was valid and has been cached
+    Valid,
+    /// The remote file could not be fetched
+    Unavailable(String),
+    /// The remote file is not a valid registry file or signed feed
+    Invalid(String),
+    /// The signed feed's signature was not made by the registry's signing key
+    InvalidSignature,
+    /// The signed feed's version is lower than the version of the cached feed
+    Rollback { version: u64, current_version: u64 },
+    /// The signed feed has the same version as the cached feed, but different contents
+    Conflict { version: u64 },
+}
+
+#[cfg(feature = "registry-remote-signing")]
+impl fmt::Display for RemoteYamlValidation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RemoteYamlValidation::Pending => f.write_str("Remote registry file not fetched yet"),
+            RemoteYamlValidation::Valid => f.write_str("Remote registry file is valid"),
+            RemoteYamlValidation::Unavailable(msg) => {
+                write!(f, "Remote registry file is unavailable: {}", msg)
+            }
+            RemoteYamlValidation::Invalid(msg) => {
+                write!(f, "Remote registry file is invalid: {}", msg)
+            }
+            RemoteYamlValidation::InvalidSignature => {
+                f.write_str("Remote registry feed signature is invalid")
+            }
+            RemoteYamlValidation::Rollback {
+                version,
+                current_version,
+            } => write!(
+                f,
+                "Remote registry feed version {} is lower than the current version {}",
+                version, current_version
+            ),
+            RemoteYamlValidation::Conflict { version } => write!(
+                f,
+                "Remote registry feed version {} does not match the cached feed with the same \
+                 version",
+                version
+            ),
+        }
+    }
+}
+
+/// The signed feed version and validation result of a [`RemoteYamlRegistry`].
+#[cfg(feature = "registry-remote-signing")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteYamlRegistryStatus {
+    url: String,
+    signed: bool,
+    version: Option<u64>,
+    validation: RemoteYamlValidation,
+}
+
+#[cfg(feature = "registry-remote-signing")]
+impl RemoteYamlRegistryStatus {
+    /// Returns the URL of the registry's backing file.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns whether the registry requires a signed feed.
+    pub fn signed(&self) -> bool {
+        self.signed
+    }
+
+    /// Returns the version of the cached signed feed, if any.
+    pub fn version(&self) -> Option<u64> {
+        self.version
+    }
+
+    /// Returns the result of validating the most recently fetched remote file.
+    pub fn validation(&self) -> &RemoteYamlValidation {
+        &self.validation
+    }
+}
+
+/// Reads the status of a [`RemoteYamlRegistry`]; see [`RemoteYamlRegistry::status_reader`].
+#[cfg(feature = "registry-remote-signing")]
+#[derive(Clone)]
+pub struct RemoteYamlRegistryStatusReader {
+    internal: Arc<Mutex<Internal>>,
+}
+
+#[cfg(feature = "registry-remote-signing")]
+impl RemoteYamlRegistryStatusReader {
+    /// Returns the registry's signed feed version and the result of validating the most recently
+    /// fetched remote file.
+    pub fn status(&self) -> Result<RemoteYamlRegistryStatus, RegistryError> {
+        Ok(self
+            .internal
+            .lock()
+            .map_err(|_| {
+                RegistryError::InternalError(InternalError::with_message(
+                    "Internal lock poisoned".into(),
+                ))
+            })?
+            .status())
+    }
+}
+
+/// Creates a signed feed of the given nodes, which can be served to a [`RemoteYamlRegistry`]
+/// with the signer's public key.
+///
+/// The `version` must be greater than the version of any feed previously served at the same URL.
+#[cfg(feature = "registry-remote-signing")]
+pub fn create_signed_feed(
+    version: u64,
+    nodes: Vec<Node>,
+    signer: &dyn Signer,
+) -> Result<Vec<u8>, RegistryError> {
+    let payload = serde_yaml::to_string(&YamlFeedPayload {
+        version,
+        nodes: nodes.into_iter().map(YamlNode::from).collect(),
+    })
+    .map_err(|err| {
+        RegistryError::InternalError(InternalError::from_source_with_message(
+            Box::new(err),
+            "Failed to serialize registry feed".into(),
+        ))
+    })?;
+    let signature = signer.sign(payload.as_bytes()).map_err(|err| {
+        RegistryError::InternalError(InternalError::from_source_with_message(
+            Box::new(err),
+            "Failed to sign registry feed".into(),
+        ))
+    })?;
+
+    serde_yaml::to_vec(&SignedYamlFeed {
+        payload,
+        signature: to_hex(&signature.take_bytes()),
+    })
+    .map_err(|err| {
+        RegistryError::InternalError(InternalError::from_source_with_message(
+            Box::new(err),
+            "Failed to serialize signed registry feed".into(),
+        ))
+    })
+}
+
+/// The key and verifier that a signed feed's signature is checked with.
+#[cfg(feature = "registry-remote-signing")]
+struct FeedVerification {
+    verifier: Box<dyn Verifier>,
+    public_key: PublicKey,
+}
+
+/// The version and payload digest of the most recently accepted signed feed.
+#[cfg(feature = "registry-remote-signing")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct FeedState {
+    version: u64,
+    digest: String,
+}
+
+/// YAML representation of a signed feed.
+#[cfg(feature = "registry-remote-signing")]
+#[derive(Serialize, Deserialize)]
+struct SignedYamlFeed {
+    payload: String,
+    signature: String,
+}
+
+/// YAML representation of a signed feed's payload.
+#[cfg(feature = "registry-remote-signing")]
+#[derive(Serialize, Deserialize)]
+struct YamlFeedPayload {
+    version: u64,
+    nodes: Vec<YamlNode>,
+}
+
+/// The nodes of a validated remote file, along with the feed's state if it was signed.
+#[cfg(feature = "registry-remote-signing")]
+struct ValidatedFeed {
+    nodes: Vec<Node>,
+    feed_state: Option<FeedState>,
+}
+
+/// Parse and validate the remote file; if `feed_verification` is provided, the file must be a
+/// feed signed by its key.
+#[cfg(feature = "registry-remote-signing")]
+fn validate_feed(
+    bytes: &[u8],
+    feed_verification: Option<&FeedVerification>,
+) -> Result<ValidatedFeed, RemoteYamlValidation> {
+    let feed_verification = match feed_verification {
+        Some(feed_verification) => feed_verification,
+        None => {
+            return parse_nodes(bytes)
+                .map(|nodes| ValidatedFeed {
+                    nodes,
+                    feed_state: None,
+                })
+                .map_err(|err| RemoteYamlValidation::Invalid(err.to_string()))
+        }
+    };
+
+    let feed: SignedYamlFeed = serde_yaml::from_slice(bytes)
+        .map_err(|_| RemoteYamlValidation::Invalid("Not a valid signed registry feed".into()))?;
+
+    let signature = parse_hex(&feed.signature)
+        .map(Signature::new)
+        .map_err(|_| RemoteYamlValidation::InvalidSignature)?;
+    match feed_verification.verifier.verify(
+        feed.payload.as_bytes(),
+        &signature,
+        &feed_verification.public_key,
+    ) {
+        Ok(true) => (),
+        Ok(false) => return Err(RemoteYamlValidation::InvalidSignature),
+        Err(err) => {
+            debug!("Unable to verify remote registry feed signature: {}", err);
+            return Err(RemoteYamlValidation::InvalidSignature);
+        }
     }
+
+    let payload: YamlFeedPayload = serde_yaml::from_str(&feed.payload).map_err(|_| {
+        RemoteYamlValidation::Invalid(
+            "Feed payload is not a valid version and sequence of nodes".into(),
+        )
+    })?;
+    let nodes = into_valid_nodes(payload.nodes)
+        .map_err(|err| RemoteYamlValidation::Invalid(err.to_string()))?;
+    let digest = hash(MessageDigest::sha256(), feed.payload.as_bytes())
+        .map(|digest| to_hex(&*digest))
+        .map_err(|err| RemoteYamlValidation::Invalid(err.to_string()))?;
+
+    Ok(ValidatedFeed {
+        nodes,
+        feed_state: Some(FeedState {
+            version: payload.version,
+            digest,
+        }),
+    })
+}
+
+/// Read the saved state of the signed feed, if it exists.
+#[cfg(feature = "registry-remote-signing")]
+fn read_feed_state(filename: &str) -> Result<Option<FeedState>, RegistryError> {
+    if !Path::new(filename).exists() {
+        return Ok(None);
+    }
+
+    let bytes = fs::read(filename).map_err(|err| {
+        RegistryError::InternalError(InternalError::from_source_with_message(
+            Box::new(err),
+            format!("Failed to read remote registry feed state {}", filename),
+        ))
+    })?;
+    serde_yaml::from_slice(&bytes).map(Some).map_err(|err| {
+        RegistryError::InternalError(InternalError::from_source_with_message(
+            Box::new(err),
+            format!("Failed to parse remote registry feed state {}", filename),
+        ))
+    })
+}
+
+/// Save the state of the signed feed.
+#[cfg(feature = "registry-remote-signing")]
+fn write_feed_state(filename: &str, feed_state: &FeedState) -> Result<(), RegistryError> {
+    let bytes = serde_yaml::to_vec(feed_state).map_err(|err| {
+        RegistryError::InternalError(InternalError::from_source_with_message(
+            Box::new(err),
+            "Failed to serialize remote registry feed state".into(),
+        ))
+    })?;
+    fs::write(filename, bytes).map_err(|err| {
+        RegistryError::InternalError(InternalError::from_source_with_message(
+            Box::new(err),
+            format!("Failed to write remote registry feed state {}", filename),
+        ))
+    })
 }
 
 /// Handle for signaling the `RemoteYamlRegistry` to shutdown.
@@ -1069,6 +1700,61 @@ mod tests {
         test_config.shutdown();
     }
 
+    /// Verifies that a subscriber is notified of the changes found when the cache is refreshed,
+    /// and not of the nodes that were already cached.
+    #[cfg(feature = "registry-notifications")]
+    #[test]
+    fn event_subscriber_refresh() {
+        let test_config = TestConfig::setup("event_subscriber_refresh", Some(mock_registry()));
+
+        let refresh_period = Duration::from_millis(10);
+        let mut remote_registry = RemoteYamlRegistry::new(
+            test_config.url(),
+            test_config.path(),
+            None,
+            Some(refresh_period),
+        )
+        .expect("Failed to create registry");
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        remote_registry
+            .add_event_subscriber(Box::new(ChannelSubscriber(tx)))
+            .expect("Failed to add subscriber");
+
+        let mut nodes = mock_registry();
+        let removed_node = nodes.pop().expect("Mock registry is empty");
+        let previous_node = nodes[0].clone();
+        nodes[0].endpoints = vec!["tcps://12.0.0.123:8436".into()];
+        test_config.update_registry(Some(nodes.clone()));
+
+        // Wait at least as long as the forced refresh period
+        std::thread::sleep(refresh_period);
+
+        verify_internal_cache(&test_config, &remote_registry, nodes.clone());
+
+        let events: Vec<RegistryEvent> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                RegistryEvent::NodeUpdated {
+                    previous: previous_node,
+                    node: nodes[0].clone(),
+                },
+                RegistryEvent::NodeRemoved(removed_node),
+            ]
+        );
+        assert!(events[0].endpoints_changed());
+
+        let mut shutdown_handle = remote_registry
+            .take_shutdown_handle()
+            .expect("Unable to get shutdown handle");
+        shutdown_handle.signal_shutdown();
+        shutdown_handle
+            .wait_for_shutdown()
+            .expect("Unable to shutdown remote registry");
+        test_config.shutdown();
+    }
+
     /// Verifies that any changes made to the remote file are fetched on restart if the remote file
     /// is available.
     #[test]
@@ -1148,6 +1834,194 @@ mod tests {
 
     // Restart, remote file not available
 
+    /// Verifies that a signed feed is verified and cached, and that its version is reported in the
+    /// registry's status.
+    #[cfg(feature = "registry-remote-signing")]
+    #[test]
+    fn signed_feed_valid() {
+        let signer = new_signer();
+        let test_config = FeedTestConfig::setup(
+            "signed_feed_valid",
+            Some(create_signed_feed(1, mock_registry(), &*signer).expect("Failed to sign feed")),
+        );
+
+        let remote_registry = signed_registry(&test_config, &*signer);
+
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+        let status = remote_registry.status().expect("Failed to get status");
+        assert!(status.signed());
+        assert_eq!(status.version(), Some(1));
+        assert_eq!(status.validation(), &RemoteYamlValidation::Valid);
+
+        shutdown_registry(remote_registry);
+        test_config.shutdown();
+    }
+
+    /// Verifies that a feed signed by a different key, or an unsigned registry file, is rejected
+    /// (not loaded).
+    #[cfg(feature = "registry-remote-signing")]
+    #[test]
+    fn signed_feed_invalid_signature() {
+        let signer = new_signer();
+        let other_signer = new_signer();
+        let test_config = FeedTestConfig::setup(
+            "signed_feed_invalid_signature",
+            Some(
+                create_signed_feed(1, mock_registry(), &*other_signer)
+                    .expect("Failed to sign feed"),
+            ),
+        );
+
+        let remote_registry = signed_registry(&test_config, &*signer);
+
+        assert!(remote_registry
+            .get_nodes()
+            .expect("Failed to get nodes")
+            .is_empty());
+        assert_eq!(
+            remote_registry
+                .status()
+                .expect("Failed to get status")
+                .validation(),
+            &RemoteYamlValidation::InvalidSignature
+        );
+
+        let yaml_registry: Vec<YamlNode> =
+            mock_registry().into_iter().map(YamlNode::from).collect();
+        test_config.update_feed(Some(
+            serde_yaml::to_vec(&yaml_registry).expect("Failed to serialize registry"),
+        ));
+        refresh(&remote_registry).expect_err("Unsigned registry file was accepted");
+        assert!(matches!(
+            remote_registry
+                .status()
+                .expect("Failed to get status")
+                .validation(),
+            RemoteYamlValidation::Invalid(_)
+        ));
+
+        shutdown_registry(remote_registry);
+        test_config.shutdown();
+    }
+
+    /// Verifies that a feed with a lower version than the cached feed is rejected, including after
+    /// a restart, and that the cached nodes continue to be used.
+    #[cfg(feature = "registry-remote-signing")]
+    #[test]
+    fn signed_feed_rollback() {
+        let signer = new_signer();
+        let test_config = FeedTestConfig::setup(
+            "signed_feed_rollback",
+            Some(create_signed_feed(2, mock_registry(), &*signer).expect("Failed to sign feed")),
+        );
+
+        let remote_registry = signed_registry(&test_config, &*signer);
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+
+        test_config.update_feed(Some(
+            create_signed_feed(1, vec![], &*signer).expect("Failed to sign feed"),
+        ));
+        refresh(&remote_registry).expect_err("Rollback was accepted");
+        assert_eq!(
+            remote_registry
+                .status()
+                .expect("Failed to get status")
+                .validation(),
+            &RemoteYamlValidation::Rollback {
+                version: 1,
+                current_version: 2
+            }
+        );
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+
+        // Verify that the rollback is still rejected after a restart
+        shutdown_registry(remote_registry);
+        let remote_registry = signed_registry(&test_config, &*signer);
+        let status = remote_registry.status().expect("Failed to get status");
+        assert_eq!(status.version(), Some(2));
+        assert!(matches!(
+            status.validation(),
+            RemoteYamlValidation::Rollback { .. }
+        ));
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+
+        shutdown_registry(remote_registry);
+        test_config.shutdown();
+    }
+
+    /// Verifies that a feed with the same version as the cached feed, but different contents, is
+    /// rejected, while the same feed and a newer feed are accepted.
+    #[cfg(feature = "registry-remote-signing")]
+    #[test]
+    fn signed_feed_conflict() {
+        let signer = new_signer();
+        let test_config = FeedTestConfig::setup(
+            "signed_feed_conflict",
+            Some(create_signed_feed(1, mock_registry(), &*signer).expect("Failed to sign feed")),
+        );
+
+        let remote_registry = signed_registry(&test_config, &*signer);
+        refresh(&remote_registry).expect("Failed to refresh with the same feed");
+
+        test_config.update_feed(Some(
+            create_signed_feed(1, vec![], &*signer).expect("Failed to sign feed"),
+        ));
+        refresh(&remote_registry).expect_err("Conflicting feed was accepted");
+        assert_eq!(
+            remote_registry
+                .status()
+                .expect("Failed to get status")
+                .validation(),
+            &RemoteYamlValidation::Conflict { version: 1 }
+        );
+
+        test_config.update_feed(Some(
+            create_signed_feed(2, vec![], &*signer).expect("Failed to sign feed"),
+        ));
+        refresh(&remote_registry).expect("Failed to refresh with a newer feed");
+        assert!(remote_registry
+            .get_nodes()
+            .expect("Failed to get nodes")
+            .is_empty());
+        assert_eq!(
+            remote_registry
+                .status()
+                .expect("Failed to get status")
+                .version(),
+            Some(2)
+        );
+
+        shutdown_registry(remote_registry);
+        test_config.shutdown();
+    }
+
+    #[cfg(feature = "registry-notifications")]
+    struct ChannelSubscriber(std::sync::mpsc::Sender<RegistryEvent>);
+
+    #[cfg(feature = "registry-notifications")]
+    impl RegistryEventSubscriber for ChannelSubscriber {
+        fn handle_event(
+            &self,
+            event: &RegistryEvent,
+        ) -> Result<(), crate::registry::RegistrySubscriberError> {
+            self.0
+                .send(event.clone())
+                .map_err(|_| crate::registry::RegistrySubscriberError::Unsubscribe)
+        }
+    }
+
     /// Creates a mock registry.
     fn mock_registry() -> Vec<Node> {
         vec![
@@ -1348,4 +2222,126 @@ mod tests {
             Err(err) => panic!("Failed to run REST API: {}", err),
         }
     }
+
+    #[cfg(feature = "registry-remote-signing")]
+    fn new_signer() -> Box<dyn Signer> {
+        let context = Secp256k1Context::new();
+        let private_key = context.new_random_private_key();
+        context.new_signer(private_key)
+    }
+
+    /// Creates a registry for the feed served by the `test_config` that requires the feed to be
+    /// signed by the `signer`.
+    #[cfg(feature = "registry-remote-signing")]
+    fn signed_registry(test_config: &FeedTestConfig, signer: &dyn Signer) -> RemoteYamlRegistry {
+        RemoteYamlRegistryBuilder::new()
+            .with_url(test_config.url().into())
+            .with_cache_dir(test_config.path().into())
+            .with_signing_key(signer.public_key().expect("Failed to get public key"))
+            .build()
+            .expect("Failed to create registry")
+    }
+
+    /// Refreshes the registry's cache immediately.
+    #[cfg(feature = "registry-remote-signing")]
+    fn refresh(remote_registry: &RemoteYamlRegistry) -> Result<(), RegistryError> {
+        remote_registry
+            .internal
+            .lock()
+            .expect("Internal lock poisoned")
+            .refresh_cache()
+    }
+
+    #[cfg(feature = "registry-remote-signing")]
+    fn shutdown_registry(mut remote_registry: RemoteYamlRegistry) {
+        let mut shutdown_handle = remote_registry
+            .take_shutdown_handle()
+            .expect("Unable to get shutdown handle");
+        shutdown_handle.signal_shutdown();
+        shutdown_handle
+            .wait_for_shutdown()
+            .expect("Unable to shutdown remote registry");
+    }
+
+    /// Simplifies signed feed tests by serving the raw feed and handling tear down.
+    #[cfg(feature = "registry-remote-signing")]
+    struct FeedTestConfig {
+        _temp_dir: TempDir,
+        temp_dir_path: String,
+        feed: Arc<Mutex<Option<Vec<u8>>>>,
+        feed_url: String,
+        rest_api_shutdown_handle: RestApiShutdownHandle,
+        rest_api_join_handle: std::thread::JoinHandle<()>,
+    }
+
+    #[cfg(feature = "registry-remote-signing")]
+    impl FeedTestConfig {
+        /// Setup for the test, serving the `feed` (if `Some`, otherwise the remote file won't be
+        /// available).
+        fn setup(test_name: &str, feed: Option<Vec<u8>>) -> Self {
+            let temp_dir = TempDir::new(test_name).expect("Failed to create temp dir");
+            let temp_dir_path = temp_dir
+                .path()
+                .to_str()
+                .expect("Failed to get path")
+                .to_string();
+
+            let feed = Arc::new(Mutex::new(feed));
+
+            let mut resource = Resource::build("/registry.yaml");
+            let resource_feed = feed.clone();
+            let handler = move |_: actix_web::HttpRequest, _: actix_web::web::Payload| {
+                Box::new(
+                    match &*resource_feed.lock().expect("Feed lock poisoned") {
+                        Some(feed) => HttpResponse::Ok().body(feed.clone()),
+                        None => HttpResponse::NotFound().finish(),
+                    }
+                    .into_future(),
+                )
+                    as Box<dyn futures::Future<Item = HttpResponse, Error = actix_web::Error>>
+            };
+            #[cfg(feature = "authorization")]
+            {
+                resource =
+                    resource.add_method(Method::Get, Permission::AllowUnauthenticated, handler);
+            }
+            #[cfg(not(feature = "authorization"))]
+            {
+                resource = resource.add_method(Method::Get, handler);
+            }
+            let (rest_api_shutdown_handle, rest_api_join_handle, url) =
+                run_rest_api_on_open_port(vec![resource]);
+
+            Self {
+                _temp_dir: temp_dir,
+                temp_dir_path,
+                feed,
+                feed_url: format!("http://{}/registry.yaml", url),
+                rest_api_shutdown_handle,
+                rest_api_join_handle,
+            }
+        }
+
+        fn path(&self) -> &str {
+            &self.temp_dir_path
+        }
+
+        fn url(&self) -> &str {
+            &self.feed_url
+        }
+
+        /// Updates the feed served by the REST API.
+        fn update_feed(&self, feed: Option<Vec<u8>>) {
+            *self.feed.lock().expect("Feed lock poisoned") = feed;
+        }
+
+        fn shutdown(self) {
+            self.rest_api_shutdown_handle
+                .shutdown()
+                .expect("Unable to shutdown rest api");
+            self.rest_api_join_handle
+                .join()
+                .expect("Unable to join rest api thread");
+        }
+    }
 }
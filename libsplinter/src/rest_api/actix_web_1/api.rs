@@ -15,13 +15,25 @@
 use std::sync::mpsc;
 use std::thread;
 
+#[cfg(feature = "https-client-auth")]
+use actix_http::HttpService;
+#[cfg(feature = "https-client-auth")]
+use actix_server::ssl::{OpensslAcceptor, SslError};
+#[cfg(feature = "https-client-auth")]
+use actix_service::NewService;
 use actix_web::{middleware, App, HttpServer};
 use futures::Future;
 
+#[cfg(feature = "audit")]
+use crate::audit::AuditStore;
 #[cfg(feature = "authorization")]
 use crate::rest_api::auth::authorization::{
     routes::AuthorizationResourceProvider, AuthorizationHandler, PermissionMap,
 };
+#[cfg(feature = "https-client-auth")]
+use crate::rest_api::auth::identity::client_cert::PeerCertificate;
+#[cfg(feature = "rest-api-rate-limit")]
+use crate::rest_api::auth::rate_limit::RateLimiter;
 use crate::rest_api::auth::{actix::Authorization, identity::IdentityProvider};
 #[cfg(feature = "rest-api-cors")]
 use crate::rest_api::cors::Cors;
@@ -57,6 +69,10 @@ pub struct RestApi {
     pub(super) identity_providers: Vec<Box<dyn IdentityProvider>>,
     #[cfg(feature = "authorization")]
     pub(super) authorization_handlers: Vec<Box<dyn AuthorizationHandler>>,
+    #[cfg(feature = "audit")]
+    pub(super) audit_store: Option<Box<dyn AuditStore>>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    pub(super) rate_limiter: Option<RateLimiter>,
 }
 
 impl RestApi {
@@ -86,6 +102,16 @@ impl RestApi {
             #[cfg(feature = "authorization")]
             self.authorization_handlers.to_owned(),
         );
+        #[cfg(feature = "audit")]
+        let authorization = match self.audit_store {
+            Some(audit_store) => authorization.with_audit_store(audit_store),
+            None => authorization,
+        };
+        #[cfg(feature = "rest-api-rate-limit")]
+        let authorization = match self.rate_limiter {
+            Some(rate_limiter) => authorization.with_rate_limiter(rate_limiter),
+            None => authorization,
+        };
 
         #[cfg(feature = "rest-api-cors")]
         let cors = match &whitelist {
@@ -93,12 +119,23 @@ impl RestApi {
             None => Cors::new_allow_any(),
         };
 
+        #[cfg(feature = "https-client-auth")]
+        let client_auth = matches!(
+            &self.bind,
+            BindConfig::Https {
+                client_ca_path: Some(_),
+                ..
+            }
+        );
+
         #[cfg(feature = "https-bind")]
         let bind_info = match self.bind {
             BindConfig::Https {
                 bind,
                 cert_path,
                 key_path,
+                #[cfg(feature = "https-client-auth")]
+                client_ca_path,
             } => {
                 let mut acceptor =
                     openssl::ssl::SslAcceptor::mozilla_modern(openssl::ssl::SslMethod::tls())?;
@@ -106,6 +143,14 @@ impl RestApi {
                 acceptor.set_certificate_chain_file(&cert_path)?;
                 acceptor.check_private_key()?;
 
+                // Request a certificate from clients without requiring one, so clients that do
+                // not have a certificate can still authenticate with the Authorization header
+                #[cfg(feature = "https-client-auth")]
+                if let Some(client_ca_path) = client_ca_path {
+                    acceptor.set_ca_file(&client_ca_path)?;
+                    acceptor.set_verify(openssl::ssl::SslVerifyMode::PEER);
+                }
+
                 (bind, Some(acceptor))
             }
             BindConfig::Http(bind) => (bind, None),
@@ -118,7 +163,7 @@ impl RestApi {
             .name("SplinterDRestApi".into())
             .spawn(move || {
                 let sys = actix::System::new("SplinterD-Rest-API");
-                let server = HttpServer::new(move || {
+                let app_factory = move || {
                     let app = App::new();
 
                     #[cfg(feature = "rest-api-cors")]
@@ -162,24 +207,74 @@ impl RestApi {
                     }
 
                     app
-                });
+                };
 
                 #[cfg(feature = "https-bind")]
                 let (bind_url, opt_acceptor) = bind_info;
                 #[cfg(not(feature = "https-bind"))]
                 let bind_url = bind_info;
 
-                #[cfg(feature = "https-bind")]
-                let server = if let Some(acceptor) = opt_acceptor {
-                    server.bind_ssl(&bind_url, acceptor)
+                #[cfg(feature = "https-client-auth")]
+                let (opt_acceptor, client_auth_acceptor) = if client_auth {
+                    (None, opt_acceptor)
                 } else {
-                    server.bind(&bind_url)
+                    (opt_acceptor, None)
                 };
 
-                #[cfg(not(feature = "https-bind"))]
-                let server = server.bind(&bind_url);
+                // actix-web's `HttpServer` does not expose the TLS session to the app, so when
+                // client certificates are accepted the server is built directly in order to add
+                // the client's certificate to each request's extensions.
+                #[cfg(feature = "https-client-auth")]
+                let client_auth_server =
+                    client_auth_acceptor.map(|acceptor| -> std::io::Result<_> {
+                        let app_factory = app_factory.clone();
+                        let listener = std::net::TcpListener::bind(bind_url.as_str())?;
+                        let port_numbers = vec![listener.local_addr()?.port()];
+                        let acceptor = OpensslAcceptor::new(acceptor.build());
+
+                        let server = actix_server::Server::build()
+                            .listen("splinter-rest-api", listener, move || {
+                                acceptor.clone().map_err(SslError::Ssl).and_then(
+                                    HttpService::build()
+                                        .on_connect(PeerCertificate::from_ssl_stream)
+                                        .finish(app_factory())
+                                        .map_err(SslError::Service)
+                                        .map_init_err(|_| ()),
+                                )
+                            })?
+                            .disable_signals()
+                            .system_exit()
+                            .start();
+
+                        Ok((server, port_numbers))
+                    });
+                #[cfg(not(feature = "https-client-auth"))]
+                let client_auth_server = None;
+
+                let server = match client_auth_server {
+                    Some(server) => server,
+                    None => {
+                        let server = HttpServer::new(app_factory);
+
+                        #[cfg(feature = "https-bind")]
+                        let server = if let Some(acceptor) = opt_acceptor {
+                            server.bind_ssl(&bind_url, acceptor)
+                        } else {
+                            server.bind(&bind_url)
+                        };
+
+                        #[cfg(not(feature = "https-bind"))]
+                        let server = server.bind(&bind_url);
+
+                        server.map(|server| {
+                            let port_numbers =
+                                server.addrs().iter().map(|addrs| addrs.port()).collect();
+                            (server.disable_signals().system_exit().start(), port_numbers)
+                        })
+                    }
+                };
 
-                let server = match server {
+                let (addr, port_numbers) = match server {
                     Ok(server) => server,
                     Err(err) => {
                         let error_msg = format!("Invalid REST API bind {}: {}", bind_url, err);
@@ -190,9 +285,6 @@ impl RestApi {
                         return;
                     }
                 };
-                let port_numbers = server.addrs().iter().map(|addrs| addrs.port()).collect();
-
-                let addr = server.disable_signals().system_exit().start();
 
                 if let Err(err) = tx.send(Ok((addr, port_numbers))) {
                     error!("Unable to send Server Addr: {}", err);
@@ -22,6 +22,12 @@ use crate::biome::credentials::rest_api::BiomeCredentialsRestResourceProvider;
 use crate::biome::OAuthUserSessionStore;
 #[cfg(all(feature = "oauth", feature = "biome-profile"))]
 use crate::biome::UserProfileStore;
+#[cfg(feature = "api-keys")]
+use crate::rest_api::auth::api_key::ApiKeyStore;
+#[cfg(all(feature = "oauth", feature = "authorization-handler-rbac-groups"))]
+use crate::rest_api::auth::authorization::rbac::store::RoleBasedAuthorizationStore;
+#[cfg(feature = "https-client-auth")]
+use crate::rest_api::auth::identity::client_cert::ClientCertificateIdentityMapping;
 #[cfg(feature = "oauth")]
 use crate::rest_api::OAuthConfig;
 use crate::rest_api::{auth::identity::IdentityProvider, RequestError};
@@ -30,6 +36,12 @@ use super::Resource;
 
 /// Configurations for the various authentication methods supported by the Splinter REST API.
 pub enum AuthConfig {
+    /// API key authentication
+    #[cfg(feature = "api-keys")]
+    ApiKey {
+        /// The store that API keys are kept in
+        api_key_store: Box<dyn ApiKeyStore>,
+    },
     /// Biome credentials authentication
     #[cfg(feature = "biome-credentials")]
     Biome {
@@ -37,6 +49,12 @@ pub enum AuthConfig {
         /// API
         biome_credentials_resource_provider: BiomeCredentialsRestResourceProvider,
     },
+    /// TLS client certificate authentication; requires the REST API to be bound with a client CA
+    #[cfg(feature = "https-client-auth")]
+    ClientCertificate {
+        /// The part of the client's certificate that is used as the client's identity
+        mapping: ClientCertificateIdentityMapping,
+    },
     /// Cylinder JWT authentication
     #[cfg(feature = "cylinder-jwt")]
     Cylinder {
@@ -53,6 +71,10 @@ pub enum AuthConfig {
         /// The Biome user profile store
         #[cfg(feature = "biome-profile")]
         user_profile_store: Box<dyn UserProfileStore>,
+        /// The store that OAuth users' groups are saved to, so that roles mapped to those groups
+        /// are granted to the users
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        role_based_authorization_store: Option<Box<dyn RoleBasedAuthorizationStore>>,
     },
     /// A custom authentication method
     Custom {
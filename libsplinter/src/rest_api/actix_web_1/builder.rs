@@ -17,13 +17,27 @@ use std::sync::Arc;
 #[cfg(feature = "cylinder-jwt")]
 use std::sync::Mutex;
 
+#[cfg(feature = "audit")]
+use crate::audit::AuditStore;
 use crate::error::InvalidStateError;
+#[cfg(feature = "oauth-azure")]
+use crate::oauth::AzureOAuthClientBuilder;
 #[cfg(feature = "oauth")]
 use crate::oauth::{GithubOAuthClientBuilder, OpenIdOAuthClientBuilder};
+#[cfg(feature = "api-keys")]
+use crate::rest_api::auth::api_key::{
+    rest_api::ApiKeyResourceProvider, ApiKeyScopeAuthorizationHandler,
+};
 #[cfg(feature = "authorization")]
 use crate::rest_api::auth::authorization::AuthorizationHandler;
+#[cfg(feature = "api-keys")]
+use crate::rest_api::auth::identity::api_key::ApiKeyIdentityProvider;
+#[cfg(feature = "https-client-auth")]
+use crate::rest_api::auth::identity::client_cert::ClientCertificateIdentityProvider;
 #[cfg(feature = "cylinder-jwt")]
 use crate::rest_api::auth::identity::cylinder::CylinderKeyIdentityProvider;
+#[cfg(feature = "rest-api-rate-limit")]
+use crate::rest_api::auth::rate_limit::RateLimiter;
 #[cfg(feature = "oauth")]
 use crate::rest_api::{
     auth::identity::oauth::OAuthUserIdentityProvider, OAuthConfig, OAuthResourceProvider,
@@ -31,7 +45,7 @@ use crate::rest_api::{
 use crate::rest_api::{auth::identity::IdentityProvider, BindConfig, RestApiServerError};
 
 use super::AuthConfig;
-#[cfg(any(feature = "biome-credentials", feature = "oauth"))]
+#[cfg(any(feature = "api-keys", feature = "biome-credentials", feature = "oauth"))]
 use super::RestResourceProvider;
 use super::{Resource, RestApi};
 
@@ -44,6 +58,10 @@ pub struct RestApiBuilder {
     auth_configs: Vec<AuthConfig>,
     #[cfg(feature = "authorization")]
     authorization_handlers: Vec<Box<dyn AuthorizationHandler>>,
+    #[cfg(feature = "audit")]
+    audit_store: Option<Box<dyn AuditStore>>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rate_limiter: Option<RateLimiter>,
 }
 
 impl Default for RestApiBuilder {
@@ -56,6 +74,10 @@ impl Default for RestApiBuilder {
             auth_configs: Vec::new(),
             #[cfg(feature = "authorization")]
             authorization_handlers: Vec::new(),
+            #[cfg(feature = "audit")]
+            audit_store: None,
+            #[cfg(feature = "rest-api-rate-limit")]
+            rate_limiter: None,
         }
     }
 }
@@ -107,6 +129,22 @@ impl RestApiBuilder {
         self
     }
 
+    /// Sets the store that an audit entry is recorded in for each request that requires
+    /// authentication.
+    #[cfg(feature = "audit")]
+    pub fn with_audit_store(mut self, audit_store: Box<dyn AuditStore>) -> Self {
+        self.audit_store = Some(audit_store);
+        self
+    }
+
+    /// Sets the rate limiter that requests made by authenticated clients are checked against.
+    /// Requests that exceed their rate limit are rejected with a `429 Too Many Requests` response.
+    #[cfg(feature = "rest-api-rate-limit")]
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
     // Allowing unused_mut because self must be mutable if feature `auth` is enabled
     #[allow(unused_mut)]
     pub fn build(mut self) -> Result<RestApi, RestApiServerError> {
@@ -129,6 +167,19 @@ impl RestApiBuilder {
 
             for auth_config in self.auth_configs.into_iter() {
                 match auth_config {
+                    #[cfg(feature = "api-keys")]
+                    AuthConfig::ApiKey { api_key_store } => {
+                        identity_providers
+                            .push(Box::new(ApiKeyIdentityProvider::new(api_key_store.clone())));
+                        // API keys must be limited to their scopes before any other handler can
+                        // allow the request
+                        self.authorization_handlers.insert(
+                            0,
+                            Box::new(ApiKeyScopeAuthorizationHandler::new(api_key_store.clone())),
+                        );
+                        self.resources
+                            .append(&mut ApiKeyResourceProvider::new(api_key_store).resources());
+                    }
                     #[cfg(feature = "biome-credentials")]
                     AuthConfig::Biome {
                         biome_credentials_resource_provider,
@@ -139,6 +190,11 @@ impl RestApiBuilder {
                         self.resources
                             .append(&mut biome_credentials_resource_provider.resources());
                     }
+                    #[cfg(feature = "https-client-auth")]
+                    AuthConfig::ClientCertificate { mapping } => {
+                        identity_providers
+                            .push(Box::new(ClientCertificateIdentityProvider::new(mapping)));
+                    }
                     #[cfg(feature = "cylinder-jwt")]
                     AuthConfig::Cylinder { verifier } => {
                         identity_providers.push(Box::new(CylinderKeyIdentityProvider::new(
@@ -151,6 +207,8 @@ impl RestApiBuilder {
                         oauth_user_session_store,
                         #[cfg(feature = "biome-profile")]
                         user_profile_store,
+                        #[cfg(feature = "authorization-handler-rbac-groups")]
+                        role_based_authorization_store,
                     } => {
                         if oauth_configured {
                             return Err(RestApiServerError::InvalidStateError(
@@ -161,6 +219,7 @@ impl RestApiBuilder {
                         }
 
                         let oauth_client = match oauth_config {
+                            #[cfg(not(feature = "oauth-azure"))]
                             OAuthConfig::Azure {
                                 client_id,
                                 client_secret,
@@ -174,6 +233,26 @@ impl RestApiBuilder {
                                 .with_redirect_url(redirect_url)
                                 .with_inflight_request_store(inflight_request_store)
                                 .build()?,
+                            #[cfg(feature = "oauth-azure")]
+                            OAuthConfig::Azure {
+                                client_id,
+                                client_secret,
+                                redirect_url,
+                                tenant_id,
+                                scopes,
+                                inflight_request_store,
+                            } => {
+                                let mut builder = AzureOAuthClientBuilder::new()
+                                    .with_tenant_id(tenant_id)
+                                    .with_client_id(client_id)
+                                    .with_client_secret(client_secret)
+                                    .with_redirect_url(redirect_url)
+                                    .with_inflight_request_store(inflight_request_store);
+                                if let Some(scopes) = scopes {
+                                    builder = builder.with_scopes(scopes);
+                                }
+                                builder.build()?
+                            }
                             OAuthConfig::GitHub {
                                 client_id,
                                 client_secret,
@@ -221,11 +300,19 @@ impl RestApiBuilder {
                             }
                         };
 
-                        identity_providers.push(Box::new(OAuthUserIdentityProvider::new(
+                        let oauth_identity_provider = OAuthUserIdentityProvider::new(
                             oauth_client.clone(),
                             oauth_user_session_store.clone(),
                             None,
-                        )));
+                        );
+                        #[cfg(feature = "authorization-handler-rbac-groups")]
+                        let oauth_identity_provider = match role_based_authorization_store {
+                            Some(store) => {
+                                oauth_identity_provider.with_role_based_authorization_store(store)
+                            }
+                            None => oauth_identity_provider,
+                        };
+                        identity_providers.push(Box::new(oauth_identity_provider));
                         self.resources.append(
                             &mut OAuthResourceProvider::new(
                                 oauth_client,
@@ -258,6 +345,10 @@ impl RestApiBuilder {
             identity_providers,
             #[cfg(feature = "authorization")]
             authorization_handlers: self.authorization_handlers,
+            #[cfg(feature = "audit")]
+            audit_store: self.audit_store,
+            #[cfg(feature = "rest-api-rate-limit")]
+            rate_limiter: self.rate_limiter,
         })
     }
 }
@@ -349,6 +440,10 @@ mod test {
                 identity_providers: vec![],
                 #[cfg(feature = "authorization")]
                 authorization_handlers: vec![],
+                #[cfg(feature = "audit")]
+                audit_store: None,
+                #[cfg(feature = "rest-api-rate-limit")]
+                rate_limiter: None,
             })
         }
     }
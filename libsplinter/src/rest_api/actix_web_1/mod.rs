@@ -28,4 +28,7 @@ pub use guard::{Continuation, ProtocolVersionRangeGuard, RequestGuard};
 pub use resource::{
     into_bytes, into_protobuf, HandlerFunction, Method, Resource, RestResourceProvider,
 };
-pub use websocket::{new_websocket_event_sender, EventSender, Request, Response};
+pub use websocket::{
+    new_websocket_event_sender, new_websocket_event_sender_with_close_handler, EventSender,
+    Request, Response,
+};
@@ -13,6 +13,10 @@
 // limitations under the License.
 
 use std::fmt::Debug;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 use std::time::Duration;
 
 use actix::prelude::*;
@@ -71,29 +75,58 @@ impl std::fmt::Debug for Response {
 pub fn new_websocket_event_sender<T: Serialize + Debug>(
     req: Request,
     initial_events: Box<dyn Iterator<Item = T> + Send>,
+) -> Result<(EventSender<T>, Response), ResponseError> {
+    start_event_sender(req, initial_events, None)
+}
+
+/// Starts a websocket in the same way as `new_websocket_event_sender`, and calls `on_close` once
+/// the websocket has stopped, so that its `EventSender` can be dropped right away.
+pub fn new_websocket_event_sender_with_close_handler<T, F>(
+    req: Request,
+    initial_events: Box<dyn Iterator<Item = T> + Send>,
+    on_close: F,
+) -> Result<(EventSender<T>, Response), ResponseError>
+where
+    T: Serialize + Debug,
+    F: FnOnce() + 'static,
+{
+    start_event_sender(req, initial_events, Some(Box::new(on_close)))
+}
+
+fn start_event_sender<T: Serialize + Debug>(
+    req: Request,
+    initial_events: Box<dyn Iterator<Item = T> + Send>,
+    on_close: Option<Box<dyn FnOnce()>>,
 ) -> Result<(EventSender<T>, Response), ResponseError> {
     let (sender, recv) = unbounded();
+    let closed = Arc::new(AtomicBool::new(false));
 
     let (request, payload) = req.into();
 
     let stream = iter_ok::<_, ()>(initial_events.map(MessageWrapper::Message)).chain(recv);
 
     let res = ws::start(
-        EventSenderWebSocket::new(Box::new(stream)),
+        EventSenderWebSocket::new(Box::new(stream), closed.clone(), on_close),
         &request,
         payload,
     )
     .map_err(ResponseError::from)?;
 
-    Ok((EventSender { sender }, Response::from(res)))
+    Ok((EventSender { sender, closed }, Response::from(res)))
 }
 
 #[derive(Clone)]
 pub struct EventSender<T: Serialize + Debug + 'static> {
     sender: UnboundedSender<MessageWrapper<T>>,
+    closed: Arc<AtomicBool>,
 }
 
 impl<T: Serialize + Debug + 'static> EventSender<T> {
+    /// Returns `true` if the websocket has stopped, so no more events can be sent.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
     pub fn send(&self, event: T) -> Result<(), EventSendError<T>> {
         trace!("Event sent: {:?}", &event);
         self.sender
@@ -134,12 +167,20 @@ pub struct EventSendError<T: Serialize + Debug + 'static>(pub T);
 
 struct EventSenderWebSocket<T: Serialize + Debug + 'static> {
     stream: Option<Box<dyn Stream<Item = MessageWrapper<T>, Error = ()>>>,
+    closed: Arc<AtomicBool>,
+    on_close: Option<Box<dyn FnOnce()>>,
 }
 
 impl<T: Serialize + Debug + 'static> EventSenderWebSocket<T> {
-    fn new(stream: Box<dyn Stream<Item = MessageWrapper<T>, Error = ()>>) -> Self {
+    fn new(
+        stream: Box<dyn Stream<Item = MessageWrapper<T>, Error = ()>>,
+        closed: Arc<AtomicBool>,
+        on_close: Option<Box<dyn FnOnce()>>,
+    ) -> Self {
         Self {
             stream: Some(stream),
+            closed,
+            on_close,
         }
     }
 }
@@ -195,6 +236,13 @@ impl<T: Serialize + Debug + 'static> Actor for EventSenderWebSocket<T> {
             warn!("Event dealer websocket was unexpectedly started twice; ignoring");
         }
     }
+
+    fn stopped(&mut self, _: &mut Self::Context) {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Some(on_close) = self.on_close.take() {
+            on_close();
+        }
+    }
 }
 
 impl<T: Serialize + Debug + 'static> StreamHandler<ws::Message, ws::ProtocolError>
@@ -38,6 +38,7 @@ impl RunnableRestApi {
                 bind,
                 cert_path,
                 key_path,
+                ..
             } => {
                 let mut acceptor =
                     openssl::ssl::SslAcceptor::mozilla_modern(openssl::ssl::SslMethod::tls())?;
@@ -27,12 +27,22 @@ use futures::{
     Future, IntoFuture, Poll,
 };
 
+#[cfg(feature = "audit")]
+use crate::audit::{AuditDetails, AuditEntryBuilder, AuditResult, AuditStore};
 use crate::rest_api::ErrorResponse;
 #[cfg(feature = "authorization")]
 use crate::rest_api::Method;
 
+#[cfg(any(feature = "audit", feature = "rest-api-rate-limit"))]
+use super::authorization::Permission;
 #[cfg(feature = "authorization")]
 use super::authorization::{AuthorizationHandler, PermissionMap};
+#[cfg(feature = "https-client-auth")]
+use super::identity::client_cert::PeerCertificate;
+#[cfg(feature = "audit")]
+use super::identity::Identity;
+#[cfg(feature = "rest-api-rate-limit")]
+use super::rate_limit::{RateLimitResult, RateLimiter};
 use super::{authorize, identity::IdentityProvider, AuthorizationResult};
 
 /// Wrapper for the authorization middleware
@@ -41,6 +51,10 @@ pub struct Authorization {
     identity_providers: Vec<Box<dyn IdentityProvider>>,
     #[cfg(feature = "authorization")]
     authorization_handlers: Vec<Box<dyn AuthorizationHandler>>,
+    #[cfg(feature = "audit")]
+    audit_store: Option<Box<dyn AuditStore>>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rate_limiter: Option<RateLimiter>,
 }
 
 impl Authorization {
@@ -54,8 +68,27 @@ impl Authorization {
             identity_providers,
             #[cfg(feature = "authorization")]
             authorization_handlers,
+            #[cfg(feature = "audit")]
+            audit_store: None,
+            #[cfg(feature = "rest-api-rate-limit")]
+            rate_limiter: None,
         }
     }
+
+    /// Sets the store that an audit entry is recorded in for each request that requires
+    /// authentication.
+    #[cfg(feature = "audit")]
+    pub fn with_audit_store(mut self, audit_store: Box<dyn AuditStore>) -> Self {
+        self.audit_store = Some(audit_store);
+        self
+    }
+
+    /// Sets the rate limiter that requests made by authenticated clients are checked against.
+    #[cfg(feature = "rest-api-rate-limit")]
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
 }
 
 impl<S, B> Transform<S> for Authorization
@@ -76,6 +109,10 @@ where
             identity_providers: self.identity_providers.clone(),
             #[cfg(feature = "authorization")]
             authorization_handlers: self.authorization_handlers.clone(),
+            #[cfg(feature = "audit")]
+            audit_store: self.audit_store.clone(),
+            #[cfg(feature = "rest-api-rate-limit")]
+            rate_limiter: self.rate_limiter.clone(),
             service,
         })
     }
@@ -86,6 +123,10 @@ pub struct AuthorizationMiddleware<S> {
     identity_providers: Vec<Box<dyn IdentityProvider>>,
     #[cfg(feature = "authorization")]
     authorization_handlers: Vec<Box<dyn AuthorizationHandler>>,
+    #[cfg(feature = "audit")]
+    audit_store: Option<Box<dyn AuditStore>>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rate_limiter: Option<RateLimiter>,
     service: S,
 }
 
@@ -175,11 +216,21 @@ where
             }
         };
 
+        #[cfg(feature = "https-client-auth")]
+        let peer_certificate = req.extensions().get::<PeerCertificate>().cloned();
+
+        #[cfg(feature = "audit")]
+        let mut audit_entry = None;
+
         match authorize(
             #[cfg(feature = "authorization")]
             &method,
             req.path(),
             auth_header,
+            #[cfg(feature = "https-client-auth")]
+            peer_certificate
+                .as_ref()
+                .and_then(PeerCertificate::certificate),
             #[cfg(feature = "authorization")]
             permission_map.get_ref(),
             &self.identity_providers,
@@ -188,6 +239,52 @@ where
         ) {
             AuthorizationResult::Authorized(identity) => {
                 debug!("Authenticated user {:?}", identity);
+                #[cfg(feature = "audit")]
+                {
+                    audit_entry = start_audit_entry(
+                        &self.audit_store,
+                        &method,
+                        req.path(),
+                        permission_map.get_ref(),
+                        Some(&identity),
+                        AuditResult::Allowed,
+                    );
+                }
+                #[cfg(feature = "rest-api-rate-limit")]
+                {
+                    if let Some(rate_limiter) = &self.rate_limiter {
+                        let permission_id = match permission_map
+                            .get_ref()
+                            .get_permission(&method, req.path())
+                        {
+                            Some(Permission::Check { permission_id, .. }) => Some(*permission_id),
+                            _ => None,
+                        };
+                        if let RateLimitResult::Limited { retry_after } =
+                            rate_limiter.check(&identity, permission_id)
+                        {
+                            debug!("Rate limit exceeded by {}", identity);
+                            #[cfg(feature = "audit")]
+                            if let Some((audit_store, builder)) = audit_entry.take() {
+                                record_audit_entry(&*audit_store, builder.with_status_code(429));
+                            }
+                            // Round up so that the client does not retry too early
+                            let retry_after_secs =
+                                retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+                            return Box::new(
+                                req.into_response(
+                                    HttpResponse::TooManyRequests()
+                                        .header(header::RETRY_AFTER, retry_after_secs.to_string())
+                                        .json(ErrorResponse::too_many_requests(
+                                            "Rate limit exceeded",
+                                        ))
+                                        .into_body(),
+                                )
+                                .into_future(),
+                            );
+                        }
+                    }
+                }
                 req.extensions_mut().insert(identity);
             }
             #[cfg(any(
@@ -196,7 +293,41 @@ where
                 feature = "oauth"
             ))]
             AuthorizationResult::NoAuthorizationNecessary => {}
+            #[cfg(feature = "authorization")]
+            #[cfg_attr(not(feature = "audit"), allow(unused_variables))]
+            AuthorizationResult::Forbidden(identity) => {
+                #[cfg(feature = "audit")]
+                if let Some((audit_store, builder)) = start_audit_entry(
+                    &self.audit_store,
+                    &method,
+                    req.path(),
+                    permission_map.get_ref(),
+                    Some(&identity),
+                    AuditResult::Denied,
+                ) {
+                    record_audit_entry(&*audit_store, builder.with_status_code(401));
+                }
+                return Box::new(
+                    req.into_response(
+                        HttpResponse::Unauthorized()
+                            .json(ErrorResponse::unauthorized())
+                            .into_body(),
+                    )
+                    .into_future(),
+                );
+            }
             AuthorizationResult::Unauthorized => {
+                #[cfg(feature = "audit")]
+                if let Some((audit_store, builder)) = start_audit_entry(
+                    &self.audit_store,
+                    &method,
+                    req.path(),
+                    permission_map.get_ref(),
+                    None,
+                    AuditResult::Unauthenticated,
+                ) {
+                    record_audit_entry(&*audit_store, builder.with_status_code(401));
+                }
                 return Box::new(
                     req.into_response(
                         HttpResponse::Unauthorized()
@@ -204,7 +335,7 @@ where
                             .into_body(),
                     )
                     .into_future(),
-                )
+                );
             }
             #[cfg(feature = "authorization")]
             AuthorizationResult::UnknownEndpoint => {
@@ -219,7 +350,19 @@ where
             }
         }
 
-        Box::new(self.service.call(req).and_then(|mut res| {
+        Box::new(self.service.call(req).and_then(move |mut res| {
+            #[cfg(feature = "audit")]
+            if let Some((audit_store, builder)) = audit_entry {
+                let builder = builder.with_status_code(res.status().as_u16());
+                // Handlers add the details of the action the request performed, if any, to the
+                // request's extensions
+                let builder = match res.request().extensions().get::<AuditDetails>() {
+                    Some(details) => builder.with_details(details.clone()),
+                    None => builder,
+                };
+                record_audit_entry(&*audit_store, builder);
+            }
+
             res.headers_mut().insert(
                 header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
                 HeaderValue::from_static("true"),
@@ -230,6 +373,50 @@ where
     }
 }
 
+/// Starts building the audit entry for a request if an audit store is configured. Returns the
+/// store along with the builder, which is completed once the response status is known.
+#[cfg(feature = "audit")]
+fn start_audit_entry(
+    audit_store: &Option<Box<dyn AuditStore>>,
+    method: &Method,
+    endpoint: &str,
+    permission_map: &PermissionMap,
+    identity: Option<&Identity>,
+    result: AuditResult,
+) -> Option<(Box<dyn AuditStore>, AuditEntryBuilder)> {
+    let audit_store = audit_store.as_ref()?;
+
+    let mut builder = AuditEntryBuilder::new()
+        .with_method(method.to_string())
+        .with_endpoint(endpoint.to_string())
+        .with_result(result);
+
+    if let Some(Permission::Check { permission_id, .. }) =
+        permission_map.get_permission(method, endpoint)
+    {
+        builder = builder.with_permission_id(permission_id.to_string());
+    }
+
+    if let Some(identity) = identity {
+        builder = builder.with_identity(identity.to_string());
+    }
+
+    Some((audit_store.clone(), builder))
+}
+
+/// Records an audit entry. Failing to record the entry is logged, but does not fail the request.
+#[cfg(feature = "audit")]
+fn record_audit_entry(audit_store: &dyn AuditStore, builder: AuditEntryBuilder) {
+    match builder.build() {
+        Ok(entry) => {
+            if let Err(err) = audit_store.add_entry(entry) {
+                error!("Failed to record audit entry: {}", err);
+            }
+        }
+        Err(err) => error!("Failed to build audit entry: {}", err),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -370,6 +557,186 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    /// Verifies that the authorization middleware rejects requests from an authenticated client
+    /// that exceed the configured rate limit with a `429 Too Many Requests` response and a
+    /// `Retry-After` header.
+    #[cfg(feature = "rest-api-rate-limit")]
+    #[test]
+    fn auth_middleware_rate_limited() {
+        use std::time::Duration;
+
+        use crate::rest_api::auth::rate_limit::{RateLimit, RateLimiterBuilder};
+
+        let auth_middleware =
+            Authorization::new(vec![Box::new(AlwaysAcceptIdentityProvider)], vec![])
+                .with_rate_limiter(
+                    RateLimiterBuilder::new()
+                        .with_default_limit(RateLimit::new(1, Duration::from_secs(60)).unwrap())
+                        .build(),
+                );
+
+        let mut permission_map = PermissionMap::default();
+        permission_map.add_permission(Method::Get, "/", Permission::AllowAuthenticated);
+
+        let mut service = test::init_service(
+            App::new()
+                .wrap(auth_middleware)
+                .data(permission_map)
+                .route("/", web::get().to(|| HttpResponse::Ok())),
+        );
+
+        let req = test::TestRequest::with_uri("/")
+            .header("Authorization", "test")
+            .to_request();
+        let resp = test::block_on(service.call(req)).unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::with_uri("/")
+            .header("Authorization", "test")
+            .to_request();
+        let resp = test::block_on(service.call(req)).unwrap();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            resp.headers()
+                .get(header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok()),
+            Some("60")
+        );
+    }
+
+    /// Verifies that the authorization middleware records an audit entry for each request that
+    /// requires authentication, including the details of the action added by the handler, and
+    /// does not record requests that do not require authentication.
+    ///
+    /// 1. Create the middleware with an audit store and an authorization handler that only
+    ///    allows the `test.read` permission
+    /// 2. Make an allowed request to an endpoint whose handler adds action details
+    /// 3. Make a request that is denied by the authorization handler
+    /// 4. Make a request without an authorization header
+    /// 5. Make a request to an endpoint that does not require authorization
+    /// 6. Verify that the three requests that required authentication were recorded, newest
+    ///    first, with the correct identity, permission, result and status code
+    #[cfg(feature = "audit")]
+    #[test]
+    fn auth_middleware_audit() {
+        use crate::audit::{AuditResult, MemoryAuditStore};
+        use crate::rest_api::auth::authorization::AuthorizationHandlerResult;
+
+        let audit_store = MemoryAuditStore::new();
+
+        let auth_middleware = Authorization::new(
+            vec![Box::new(AlwaysAcceptIdentityProvider)],
+            vec![Box::new(ReadOnlyAuthorizationHandler)],
+        )
+        .with_audit_store(Box::new(audit_store.clone()));
+
+        let mut permission_map = PermissionMap::default();
+        permission_map.add_permission(
+            Method::Get,
+            "/read",
+            Permission::Check {
+                permission_id: "test.read",
+                permission_display_name: "",
+                permission_description: "",
+            },
+        );
+        permission_map.add_permission(
+            Method::Post,
+            "/write",
+            Permission::Check {
+                permission_id: "test.write",
+                permission_display_name: "",
+                permission_description: "",
+            },
+        );
+        permission_map.add_permission(Method::Get, "/status", Permission::AllowUnauthenticated);
+
+        let mut service = test::init_service(
+            App::new()
+                .wrap(auth_middleware)
+                .data(permission_map)
+                .route(
+                    "/read",
+                    web::get().to(|req: HttpRequest| {
+                        req.extensions_mut().insert(AuditDetails::new(
+                            "TEST_ACTION".into(),
+                            Some("circuit".into()),
+                            vec![1],
+                        ));
+                        HttpResponse::Ok()
+                    }),
+                )
+                .route("/write", web::post().to(|| HttpResponse::Ok()))
+                .route("/status", web::get().to(|| HttpResponse::Ok())),
+        );
+
+        let req = test::TestRequest::with_uri("/read")
+            .header("Authorization", "test")
+            .to_request();
+        let resp = test::block_on(service.call(req)).unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri("/write")
+            .header("Authorization", "test")
+            .to_request();
+        let resp = test::block_on(service.call(req)).unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post().uri("/write").to_request();
+        let resp = test::block_on(service.call(req)).unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::with_uri("/status").to_request();
+        let resp = test::block_on(service.call(req)).unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let entries = audit_store
+            .list_entries(0, 10)
+            .expect("Failed to list audit entries");
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].identity(), None);
+        assert_eq!(entries[0].result(), AuditResult::Unauthenticated);
+
+        assert_eq!(entries[1].identity(), Some("custom:identity"));
+        assert_eq!(entries[1].method(), "POST");
+        assert_eq!(entries[1].endpoint(), "/write");
+        assert_eq!(entries[1].permission_id(), Some("test.write"));
+        assert_eq!(entries[1].result(), AuditResult::Denied);
+        assert_eq!(entries[1].status_code(), 401);
+
+        assert_eq!(entries[2].identity(), Some("custom:identity"));
+        assert_eq!(entries[2].permission_id(), Some("test.read"));
+        assert_eq!(entries[2].result(), AuditResult::Allowed);
+        assert_eq!(entries[2].status_code(), 200);
+        assert_eq!(entries[2].action(), Some("TEST_ACTION"));
+        assert_eq!(entries[2].circuit_id(), Some("circuit"));
+        assert_eq!(entries[2].payload(), Some(&[1][..]));
+
+        /// An authorization handler that only allows the `test.read` permission
+        #[derive(Clone)]
+        struct ReadOnlyAuthorizationHandler;
+
+        impl AuthorizationHandler for ReadOnlyAuthorizationHandler {
+            fn has_permission(
+                &self,
+                _identity: &Identity,
+                permission_id: &str,
+            ) -> Result<AuthorizationHandlerResult, InternalError> {
+                if permission_id == "test.read" {
+                    Ok(AuthorizationHandlerResult::Allow)
+                } else {
+                    Ok(AuthorizationHandlerResult::Deny)
+                }
+            }
+
+            fn clone_box(&self) -> Box<dyn AuthorizationHandler> {
+                Box::new(self.clone())
+            }
+        }
+    }
+
     /// An identity provider that always returns `Ok(Some(_))`
     #[derive(Clone)]
     struct AlwaysAcceptIdentityProvider;
@@ -0,0 +1,162 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An authorization handler that limits API keys to their scopes
+
+use std::time::SystemTime;
+
+use crate::error::InternalError;
+use crate::rest_api::auth::{
+    authorization::{AuthorizationHandler, AuthorizationHandlerResult},
+    identity::Identity,
+};
+
+use super::ApiKeyStore;
+
+/// An authorization handler that enforces the scopes of API keys.
+///
+/// If the identity is an API key that is limited to a set of permissions and the requested
+/// permission is not one of them, or if the key has expired, the handler denies the request.
+/// Otherwise it defers to the next handler in the chain, so a key must still be granted the
+/// permission by another handler, such as the role-based authorization handler. For this reason
+/// the handler should be placed before any handlers that allow permissions.
+pub struct ApiKeyScopeAuthorizationHandler {
+    api_key_store: Box<dyn ApiKeyStore>,
+}
+
+impl ApiKeyScopeAuthorizationHandler {
+    /// Constructs a new API key scope authorization handler with the given store.
+    pub fn new(api_key_store: Box<dyn ApiKeyStore>) -> Self {
+        Self { api_key_store }
+    }
+}
+
+impl AuthorizationHandler for ApiKeyScopeAuthorizationHandler {
+    fn has_permission(
+        &self,
+        identity: &Identity,
+        permission_id: &str,
+    ) -> Result<AuthorizationHandlerResult, InternalError> {
+        let key_id = match identity {
+            Identity::Key(key_id) => key_id,
+            _ => return Ok(AuthorizationHandlerResult::Continue),
+        };
+
+        let api_key = match self
+            .api_key_store
+            .get_api_key(key_id)
+            .map_err(|err| InternalError::from_source(Box::new(err)))?
+        {
+            Some(api_key) => api_key,
+            // Not an API key, but a public key
+            None => return Ok(AuthorizationHandlerResult::Continue),
+        };
+
+        if api_key.is_expired(SystemTime::now()) || !api_key.allows(permission_id) {
+            Ok(AuthorizationHandlerResult::Deny)
+        } else {
+            Ok(AuthorizationHandlerResult::Continue)
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn AuthorizationHandler> {
+        Box::new(ApiKeyScopeAuthorizationHandler {
+            api_key_store: self.api_key_store.clone_box(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::rest_api::auth::api_key::store::{ApiKeyBuilder, MemoryApiKeyStore};
+
+    /// Verify that the handler denies permissions outside of a scoped key's scopes and continues
+    /// for permissions within them.
+    #[test]
+    fn scoped_key() {
+        let handler = handler_with_key(vec!["circuit.read".into()], None);
+        let identity = Identity::Key("apikey-1".into());
+
+        assert!(matches!(
+            handler.has_permission(&identity, "circuit.read"),
+            Ok(AuthorizationHandlerResult::Continue)
+        ));
+        assert!(matches!(
+            handler.has_permission(&identity, "circuit.write"),
+            Ok(AuthorizationHandlerResult::Deny)
+        ));
+    }
+
+    /// Verify that the handler continues for any permission for a key without scopes.
+    #[test]
+    fn unscoped_key() {
+        let handler = handler_with_key(vec![], None);
+
+        assert!(matches!(
+            handler.has_permission(&Identity::Key("apikey-1".into()), "circuit.write"),
+            Ok(AuthorizationHandlerResult::Continue)
+        ));
+    }
+
+    /// Verify that the handler denies all permissions for an expired key.
+    #[test]
+    fn expired_key() {
+        let handler = handler_with_key(vec![], Some(SystemTime::now() - Duration::from_secs(1)));
+
+        assert!(matches!(
+            handler.has_permission(&Identity::Key("apikey-1".into()), "circuit.read"),
+            Ok(AuthorizationHandlerResult::Deny)
+        ));
+    }
+
+    /// Verify that the handler continues for identities that are not API keys.
+    #[test]
+    fn other_identities() {
+        let handler = handler_with_key(vec!["circuit.read".into()], None);
+
+        assert!(matches!(
+            handler.has_permission(&Identity::Key("0123".into()), "circuit.write"),
+            Ok(AuthorizationHandlerResult::Continue)
+        ));
+        assert!(matches!(
+            handler.has_permission(&Identity::User("apikey-1".into()), "circuit.write"),
+            Ok(AuthorizationHandlerResult::Continue)
+        ));
+    }
+
+    fn handler_with_key(
+        scopes: Vec<String>,
+        expires_at: Option<SystemTime>,
+    ) -> ApiKeyScopeAuthorizationHandler {
+        let store = MemoryApiKeyStore::new();
+
+        let mut builder = ApiKeyBuilder::new()
+            .with_id("apikey-1".into())
+            .with_display_name("ci".into())
+            .with_secret_hash("abcd".into())
+            .with_scopes(scopes);
+        if let Some(expires_at) = expires_at {
+            builder = builder.with_expires_at(expires_at);
+        }
+        store
+            .add_api_key(builder.build().expect("Unable to build key"))
+            .expect("Unable to add key");
+
+        ApiKeyScopeAuthorizationHandler::new(Box::new(store))
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Long-lived API keys for machine clients
+//!
+//! An API key allows a client, such as a CI bot or an integration service, to authenticate with
+//! the REST API without a signing key or an OAuth flow. The client sends the key's token in the
+//! `Authorization` header as `Bearer ApiKey:<token>`. Only a hash of the key's secret is stored,
+//! so the token is only available when the key is created.
+//!
+//! An API key is identified as [`Identity::Key`] with the key's ID, so that roles may be assigned
+//! to it in the same way as to a signing key. A key may also be limited to a set of permissions
+//! and may expire.
+//!
+//! [`Identity::Key`]: crate::rest_api::auth::identity::Identity::Key
+
+mod handler;
+#[cfg(feature = "rest-api-actix")]
+pub mod rest_api;
+pub mod store;
+
+use std::time::SystemTime;
+
+use openssl::hash::{hash, MessageDigest};
+use rand::{distributions::Alphanumeric, Rng};
+use uuid::Uuid;
+
+use crate::error::InternalError;
+use crate::hex::to_hex;
+
+pub use handler::ApiKeyScopeAuthorizationHandler;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub use store::DieselApiKeyStore;
+pub use store::{ApiKey, ApiKeyBuilder, ApiKeyStore, ApiKeyStoreError, MemoryApiKeyStore};
+
+/// The prefix of generated API key IDs, which distinguishes them from public keys
+const API_KEY_ID_PREFIX: &str = "apikey-";
+/// The length of generated API key secrets
+const API_KEY_SECRET_LENGTH: usize = 40;
+
+/// Generates a new API key with a random ID and secret.
+///
+/// Returns the key, which only contains a hash of the secret, along with the token that clients
+/// authenticate with. The token is of the form `<id>.<secret>`.
+///
+/// # Arguments
+///
+/// * `display_name` - A human-readable name for the key
+/// * `scopes` - The permissions the key is limited to; an empty list does not limit the key
+/// * `expires_at` - The time after which the key is no longer accepted, if any
+pub fn generate_api_key(
+    display_name: String,
+    scopes: Vec<String>,
+    expires_at: Option<SystemTime>,
+) -> Result<(ApiKey, String), InternalError> {
+    let id = format!("{}{}", API_KEY_ID_PREFIX, Uuid::new_v4().to_simple());
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(API_KEY_SECRET_LENGTH)
+        .collect();
+
+    let mut builder = ApiKeyBuilder::new()
+        .with_id(id.clone())
+        .with_display_name(display_name)
+        .with_secret_hash(hash_secret(&secret)?)
+        .with_scopes(scopes);
+    if let Some(expires_at) = expires_at {
+        builder = builder.with_expires_at(expires_at);
+    }
+    let api_key = builder
+        .build()
+        .map_err(|err| InternalError::from_source(Box::new(err)))?;
+
+    Ok((api_key, format!("{}.{}", id, secret)))
+}
+
+/// Splits an API key token into the key's ID and secret.
+pub(crate) fn parse_token(token: &str) -> Option<(&str, &str)> {
+    let mut parts = token.splitn(2, '.');
+    match (parts.next(), parts.next()) {
+        (Some(id), Some(secret)) if !id.is_empty() && !secret.is_empty() => Some((id, secret)),
+        _ => None,
+    }
+}
+
+/// Returns the hex-encoded SHA-256 hash of an API key secret.
+///
+/// The secret is randomly generated with enough entropy that a fast hash is sufficient.
+pub(crate) fn hash_secret(secret: &str) -> Result<String, InternalError> {
+    hash(MessageDigest::sha256(), secret.as_bytes())
+        .map(|digest| to_hex(&*digest))
+        .map_err(|err| InternalError::from_source(Box::new(err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that a generated API key's token contains the key's ID and a secret that matches
+    /// the key's secret hash.
+    #[test]
+    fn generate_and_parse_token() {
+        let (api_key, token) = generate_api_key("ci".into(), vec!["circuit.read".into()], None)
+            .expect("Failed to generate API key");
+
+        assert!(api_key.id().starts_with(API_KEY_ID_PREFIX));
+        assert_eq!(api_key.display_name(), "ci");
+        assert_eq!(api_key.scopes(), &["circuit.read".to_string()][..]);
+        assert_eq!(api_key.expires_at(), None);
+
+        let (id, secret) = parse_token(&token).expect("Failed to parse token");
+        assert_eq!(id, api_key.id());
+        assert_eq!(
+            hash_secret(secret).expect("Failed to hash secret"),
+            api_key.secret_hash()
+        );
+
+        assert!(parse_token("no-secret").is_none());
+        assert!(parse_token(".secret").is_none());
+        assert!(parse_token("id.").is_none());
+    }
+}
@@ -0,0 +1,374 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoints:
+//!
+//! * `GET /api-keys` for listing API keys
+//! * `POST /api-keys` for creating an API key
+//! * `DELETE /api-keys/{id}` for revoking an API key
+
+use std::time::{Duration, SystemTime};
+
+use crate::actix_web::{error::BlockingError, web, Error, HttpRequest, HttpResponse};
+use crate::futures::{stream::Stream, Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::{
+    actix_web_1::{Method, ProtocolVersionRangeGuard, Resource},
+    auth::api_key::{
+        generate_api_key,
+        rest_api::{
+            resources::{
+                ApiKeyResponse, CreateApiKeyPayload, CreateApiKeyResponse, ListApiKeysResponse,
+                PagingQuery,
+            },
+            API_KEYS_READ_PERMISSION, API_KEYS_WRITE_PERMISSION,
+        },
+        store::ApiKeyStore,
+    },
+    paging::get_response_paging_info,
+    ErrorResponse,
+};
+
+use super::error::SendableApiKeyStoreError;
+
+pub fn make_api_keys_resource(api_key_store: Box<dyn ApiKeyStore>) -> Resource {
+    let list_store = api_key_store.clone();
+    let post_store = api_key_store;
+    Resource::build("/api-keys")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::API_KEYS_MIN,
+            protocol::API_KEYS_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, API_KEYS_READ_PERMISSION, move |r, _| {
+            list_api_keys(r, list_store.clone())
+        })
+        .add_method(Method::Post, API_KEYS_WRITE_PERMISSION, move |_, p| {
+            create_api_key(p, post_store.clone())
+        })
+}
+
+pub fn make_api_key_resource(api_key_store: Box<dyn ApiKeyStore>) -> Resource {
+    Resource::build("/api-keys/{id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::API_KEYS_MIN,
+            protocol::API_KEYS_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Delete, API_KEYS_WRITE_PERMISSION, move |r, _| {
+            revoke_api_key(r, api_key_store.clone())
+        })
+}
+
+fn list_api_keys(
+    req: HttpRequest,
+    api_key_store: Box<dyn ApiKeyStore>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let web::Query(paging_query): web::Query<PagingQuery> =
+        match web::Query::from_query(req.query_string()) {
+            Ok(paging_query) => paging_query,
+            Err(_) => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request("Invalid query"))
+                        .into_future(),
+                )
+            }
+        };
+
+    let link = format!("{}?", req.uri().path());
+
+    Box::new(
+        web::block(move || {
+            let api_keys = api_key_store
+                .list_api_keys()
+                .map_err(SendableApiKeyStoreError::from)?;
+
+            let total = api_keys.len();
+            let api_keys = api_keys
+                .into_iter()
+                .skip(paging_query.offset)
+                .take(paging_query.limit)
+                .collect::<Vec<_>>();
+
+            Ok((api_keys, link, paging_query, total))
+        })
+        .then(
+            |res: Result<_, BlockingError<SendableApiKeyStoreError>>| match res {
+                Ok((api_keys, link, paging_query, total)) => {
+                    Ok(HttpResponse::Ok().json(ListApiKeysResponse {
+                        data: api_keys.iter().map(ApiKeyResponse::from).collect(),
+                        paging: get_response_paging_info(
+                            Some(paging_query.limit),
+                            Some(paging_query.offset),
+                            &link,
+                            total,
+                        ),
+                    }))
+                }
+                Err(err) => {
+                    error!("Unable to list API keys: {}", err);
+                    Ok(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+                }
+            },
+        ),
+    )
+}
+
+fn create_api_key(
+    payload: web::Payload,
+    api_key_store: Box<dyn ApiKeyStore>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    Box::new(
+        payload
+            .from_err::<Error>()
+            .fold(web::BytesMut::new(), move |mut body, chunk| {
+                body.extend_from_slice(&chunk);
+                Ok::<_, Error>(body)
+            })
+            .into_future()
+            .and_then(move |body| {
+                let payload = match serde_json::from_slice::<CreateApiKeyPayload>(&body) {
+                    Ok(payload) => payload,
+                    Err(err) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Invalid API key payload: {}",
+                                    err
+                                )))
+                                .into_future(),
+                        )
+                            as Box<dyn Future<Item = HttpResponse, Error = Error>>
+                    }
+                };
+
+                let expires_at = payload
+                    .expires_at
+                    .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+                if matches!(expires_at, Some(expires_at) if expires_at <= SystemTime::now()) {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(
+                                "Invalid API key payload: expires_at must be in the future",
+                            ))
+                            .into_future(),
+                    );
+                }
+
+                Box::new(
+                    web::block(move || {
+                        let (api_key, token) =
+                            generate_api_key(payload.display_name, payload.scopes, expires_at)
+                                .map_err(|err| {
+                                    SendableApiKeyStoreError::InternalError(err.reduce_to_string())
+                                })?;
+                        api_key_store
+                            .add_api_key(api_key.clone())
+                            .map_err(SendableApiKeyStoreError::from)?;
+
+                        Ok((api_key, token))
+                    })
+                    .then(|res| {
+                        Ok(match res {
+                            Ok((api_key, token)) => HttpResponse::Ok().json(CreateApiKeyResponse {
+                                data: ApiKeyResponse::from(&api_key),
+                                token,
+                            }),
+                            Err(BlockingError::Error(
+                                SendableApiKeyStoreError::ConstraintViolation(msg),
+                            )) => HttpResponse::Conflict().json(ErrorResponse::conflict(&msg)),
+                            Err(err) => {
+                                error!("Unable to create API key: {}", err);
+                                HttpResponse::InternalServerError()
+                                    .json(ErrorResponse::internal_error())
+                            }
+                        })
+                    }),
+                )
+            }),
+    )
+}
+
+fn revoke_api_key(
+    req: HttpRequest,
+    api_key_store: Box<dyn ApiKeyStore>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let id = req.match_info().get("id").unwrap_or("").to_string();
+    Box::new(
+        web::block(move || {
+            api_key_store
+                .remove_api_key(&id)
+                .map_err(SendableApiKeyStoreError::from)
+        })
+        .then(|res| {
+            Ok(match res {
+                Ok(()) => HttpResponse::Ok().finish(),
+                Err(BlockingError::Error(SendableApiKeyStoreError::NotFound(_))) => {
+                    HttpResponse::NotFound().json(ErrorResponse::not_found("API key not found"))
+                }
+                Err(err) => {
+                    error!("Unable to revoke API key: {}", err);
+                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                }
+            })
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::{blocking::Client, StatusCode, Url};
+
+    use crate::rest_api::actix_web_1::{RestApiBuilder, RestApiShutdownHandle};
+    use crate::rest_api::auth::api_key::{parse_token, store::MemoryApiKeyStore};
+
+    #[derive(Debug, Deserialize)]
+    struct TestApiKey {
+        id: String,
+        display_name: String,
+        scopes: Vec<String>,
+        expires_at: Option<u64>,
+    }
+
+    #[derive(Deserialize)]
+    struct TestCreateApiKeyResponse {
+        data: TestApiKey,
+        token: String,
+    }
+
+    #[derive(Deserialize)]
+    struct TestListApiKeysResponse {
+        data: Vec<TestApiKey>,
+    }
+
+    /// Tests creating, listing and revoking API keys.
+    ///
+    /// 1. Perform a POST against /api-keys with a name and scopes
+    /// 2. Verify that the response contains the new key and a token for it
+    /// 3. Verify that the key was added to the store with the hash of the token's secret
+    /// 4. Perform a GET against /api-keys and verify that the key is listed
+    /// 5. Perform a DELETE against /api-keys/{id} and verify that the key was removed
+    /// 6. Perform the DELETE again and verify that a 404 is returned
+    #[test]
+    fn test_create_list_and_revoke_api_keys() {
+        let api_key_store = MemoryApiKeyStore::new();
+
+        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
+            make_api_keys_resource(Box::new(api_key_store.clone())),
+            make_api_key_resource(Box::new(api_key_store.clone())),
+        ]);
+
+        let url =
+            Url::parse(&format!("http://{}/api-keys", bind_url)).expect("Failed to parse URL");
+        let resp = Client::new()
+            .post(url.clone())
+            .header(
+                "SplinterProtocolVersion",
+                protocol::API_KEYS_PROTOCOL_VERSION,
+            )
+            .json(&serde_json::json!({
+                "display_name": "ci",
+                "scopes": ["status.read", "circuit.read"],
+            }))
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let created = resp
+            .json::<TestCreateApiKeyResponse>()
+            .expect("Failed to deserialize body");
+        assert_eq!(&created.data.display_name, "ci");
+        assert_eq!(
+            created.data.scopes,
+            vec!["circuit.read".to_string(), "status.read".to_string()]
+        );
+        assert!(created.data.expires_at.is_none());
+
+        let (id, _) = parse_token(&created.token).expect("Failed to parse token");
+        assert_eq!(id, created.data.id);
+        assert!(api_key_store
+            .get_api_key(id)
+            .expect("Unable to get key")
+            .is_some());
+
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::API_KEYS_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let listed = resp
+            .json::<TestListApiKeysResponse>()
+            .expect("Failed to deserialize body");
+        assert_eq!(listed.data.len(), 1);
+        assert_eq!(listed.data[0].id, created.data.id);
+
+        let url = Url::parse(&format!("http://{}/api-keys/{}", bind_url, created.data.id))
+            .expect("Failed to parse URL");
+        let resp = Client::new()
+            .delete(url.clone())
+            .header(
+                "SplinterProtocolVersion",
+                protocol::API_KEYS_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(api_key_store
+            .get_api_key(&created.data.id)
+            .expect("Unable to get key")
+            .is_none());
+
+        let resp = Client::new()
+            .delete(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::API_KEYS_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        #[cfg(not(feature = "https-bind"))]
+        let bind = "127.0.0.1:0";
+        #[cfg(feature = "https-bind")]
+        let bind = crate::rest_api::BindConfig::Http("127.0.0.1:0".into());
+
+        let result = RestApiBuilder::new()
+            .with_bind(bind)
+            .add_resources(resources.clone())
+            .build_insecure()
+            .expect("Failed to build REST API")
+            .run_insecure();
+        match result {
+            Ok((shutdown_handle, join_handle)) => {
+                let port = shutdown_handle.port_numbers()[0];
+                (shutdown_handle, join_handle, format!("127.0.0.1:{}", port))
+            }
+            Err(err) => panic!("Failed to run REST API: {}", err),
+        }
+    }
+}
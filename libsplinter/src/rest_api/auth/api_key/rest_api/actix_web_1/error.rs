@@ -0,0 +1,59 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Thread-friendly version of the ApiKeyStoreError
+
+use std::error::Error;
+use std::fmt;
+
+use crate::error::ConstraintViolationType;
+use crate::rest_api::auth::api_key::store::ApiKeyStoreError;
+
+#[derive(Debug)]
+pub(super) enum SendableApiKeyStoreError {
+    ConstraintViolation(String),
+    InternalError(String),
+    NotFound(String),
+}
+
+impl Error for SendableApiKeyStoreError {}
+
+impl fmt::Display for SendableApiKeyStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SendableApiKeyStoreError::ConstraintViolation(msg) => f.write_str(msg),
+            SendableApiKeyStoreError::InternalError(msg) => f.write_str(msg),
+            SendableApiKeyStoreError::NotFound(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl From<ApiKeyStoreError> for SendableApiKeyStoreError {
+    fn from(err: ApiKeyStoreError) -> Self {
+        match err {
+            ApiKeyStoreError::ConstraintViolation(err)
+                if err.violation_type() == &ConstraintViolationType::NotFound =>
+            {
+                SendableApiKeyStoreError::NotFound(err.to_string())
+            }
+            ApiKeyStoreError::ConstraintViolation(err) => {
+                SendableApiKeyStoreError::ConstraintViolation(err.to_string())
+            }
+            ApiKeyStoreError::Internal(err) => {
+                SendableApiKeyStoreError::InternalError(err.reduce_to_string())
+            }
+            err => SendableApiKeyStoreError::InternalError(err.to_string()),
+        }
+    }
+}
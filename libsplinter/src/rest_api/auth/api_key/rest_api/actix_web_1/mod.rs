@@ -0,0 +1,43 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod api_keys;
+mod error;
+
+use crate::rest_api::auth::api_key::store::ApiKeyStore;
+use crate::rest_api::{Resource, RestResourceProvider};
+
+/// Provides the following REST API endpoints for API keys:
+///
+/// * `GET /api-keys` - Get a page of API keys, without their secrets
+/// * `POST /api-keys` - Create an API key; the response contains the key's token
+/// * `DELETE /api-keys/{id}` - Revoke an API key
+pub struct ApiKeyResourceProvider {
+    api_key_store: Box<dyn ApiKeyStore>,
+}
+
+impl ApiKeyResourceProvider {
+    pub fn new(api_key_store: Box<dyn ApiKeyStore>) -> Self {
+        Self { api_key_store }
+    }
+}
+
+impl RestResourceProvider for ApiKeyResourceProvider {
+    fn resources(&self) -> Vec<Resource> {
+        vec![
+            api_keys::make_api_keys_resource(self.api_key_store.clone()),
+            api_keys::make_api_key_resource(self.api_key_store.clone()),
+        ]
+    }
+}
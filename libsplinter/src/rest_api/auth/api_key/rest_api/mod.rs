@@ -0,0 +1,34 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! API key REST API endpoints
+
+mod actix_web_1;
+mod resources;
+
+use crate::rest_api::auth::authorization::Permission;
+
+pub use actix_web_1::ApiKeyResourceProvider;
+
+const API_KEYS_READ_PERMISSION: Permission = Permission::Check {
+    permission_id: "authorization.api_keys.read",
+    permission_display_name: "API keys read",
+    permission_description: "Allows the client to list API keys",
+};
+
+const API_KEYS_WRITE_PERMISSION: Permission = Permission::Check {
+    permission_id: "authorization.api_keys.write",
+    permission_display_name: "API keys write",
+    permission_description: "Allows the client to create and revoke API keys",
+};
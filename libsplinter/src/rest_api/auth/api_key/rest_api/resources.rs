@@ -0,0 +1,88 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines structures used in the API key REST API.
+
+use std::time::SystemTime;
+
+use crate::rest_api::auth::api_key::store::ApiKey;
+use crate::rest_api::paging::{Paging, DEFAULT_LIMIT, DEFAULT_OFFSET};
+
+#[derive(Serialize)]
+pub(super) struct ListApiKeysResponse<'a> {
+    pub data: Vec<ApiKeyResponse<'a>>,
+    pub paging: Paging,
+}
+
+/// An API key, without its secret
+#[derive(Serialize)]
+pub(super) struct ApiKeyResponse<'a> {
+    pub id: &'a str,
+    pub display_name: &'a str,
+    pub scopes: &'a [String],
+    /// The time the key was created, in seconds since the Unix epoch
+    pub created_at: u64,
+    /// The time the key expires, in seconds since the Unix epoch
+    pub expires_at: Option<u64>,
+}
+
+impl<'a> From<&'a ApiKey> for ApiKeyResponse<'a> {
+    fn from(api_key: &'a ApiKey) -> Self {
+        Self {
+            id: api_key.id(),
+            display_name: api_key.display_name(),
+            scopes: api_key.scopes(),
+            created_at: to_seconds(api_key.created_at()),
+            expires_at: api_key.expires_at().map(to_seconds),
+        }
+    }
+}
+
+/// The response to creating an API key, which is the only time the key's token is returned
+#[derive(Serialize)]
+pub(super) struct CreateApiKeyResponse<'a> {
+    pub data: ApiKeyResponse<'a>,
+    pub token: String,
+}
+
+#[derive(Deserialize)]
+pub(super) struct CreateApiKeyPayload {
+    pub display_name: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// The time the key expires, in seconds since the Unix epoch
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct PagingQuery {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    #[serde(default = "default_offset")]
+    pub offset: usize,
+}
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+fn default_offset() -> usize {
+    DEFAULT_OFFSET
+}
+
+fn to_seconds(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
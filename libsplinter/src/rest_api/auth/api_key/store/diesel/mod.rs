@@ -0,0 +1,221 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Database-backed implementation of the [ApiKeyStore], powered by [diesel].
+
+pub(in crate::rest_api::auth::api_key) mod models;
+mod operations;
+pub(in crate::rest_api::auth::api_key) mod schema;
+
+use diesel::r2d2::{ConnectionManager, Pool};
+
+use super::{ApiKey, ApiKeyStore, ApiKeyStoreError};
+
+use operations::{
+    add_api_key::ApiKeyStoreAddApiKey as _, get_api_key::ApiKeyStoreGetApiKey as _,
+    list_api_keys::ApiKeyStoreListApiKeys as _, remove_api_key::ApiKeyStoreRemoveApiKey as _,
+    ApiKeyStoreOperations,
+};
+
+/// Manages creating, fetching, listing and removing API keys in the database
+pub struct DieselApiKeyStore<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C: diesel::Connection> DieselApiKeyStore<C> {
+    /// Creates a new DieselApiKeyStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        DieselApiKeyStore { connection_pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl ApiKeyStore for DieselApiKeyStore<diesel::pg::PgConnection> {
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), ApiKeyStoreError> {
+        let connection = self.connection_pool.get()?;
+        ApiKeyStoreOperations::new(&*connection).add_api_key(api_key)
+    }
+
+    fn get_api_key(&self, id: &str) -> Result<Option<ApiKey>, ApiKeyStoreError> {
+        let connection = self.connection_pool.get()?;
+        ApiKeyStoreOperations::new(&*connection).get_api_key(id)
+    }
+
+    fn list_api_keys(&self) -> Result<Vec<ApiKey>, ApiKeyStoreError> {
+        let connection = self.connection_pool.get()?;
+        ApiKeyStoreOperations::new(&*connection).list_api_keys()
+    }
+
+    fn remove_api_key(&self, id: &str) -> Result<(), ApiKeyStoreError> {
+        let connection = self.connection_pool.get()?;
+        ApiKeyStoreOperations::new(&*connection).remove_api_key(id)
+    }
+
+    fn clone_box(&self) -> Box<dyn ApiKeyStore> {
+        Box::new(Self {
+            connection_pool: self.connection_pool.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl ApiKeyStore for DieselApiKeyStore<diesel::sqlite::SqliteConnection> {
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), ApiKeyStoreError> {
+        let connection = self.connection_pool.get()?;
+        ApiKeyStoreOperations::new(&*connection).add_api_key(api_key)
+    }
+
+    fn get_api_key(&self, id: &str) -> Result<Option<ApiKey>, ApiKeyStoreError> {
+        let connection = self.connection_pool.get()?;
+        ApiKeyStoreOperations::new(&*connection).get_api_key(id)
+    }
+
+    fn list_api_keys(&self) -> Result<Vec<ApiKey>, ApiKeyStoreError> {
+        let connection = self.connection_pool.get()?;
+        ApiKeyStoreOperations::new(&*connection).list_api_keys()
+    }
+
+    fn remove_api_key(&self, id: &str) -> Result<(), ApiKeyStoreError> {
+        let connection = self.connection_pool.get()?;
+        ApiKeyStoreOperations::new(&*connection).remove_api_key(id)
+    }
+
+    fn clone_box(&self) -> Box<dyn ApiKeyStore> {
+        Box::new(Self {
+            connection_pool: self.connection_pool.clone(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+pub mod tests {
+    use super::*;
+
+    use std::time::{Duration, SystemTime};
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    use crate::error::ConstraintViolationType;
+    use crate::migrations::run_sqlite_migrations;
+    use crate::rest_api::auth::api_key::store::ApiKeyBuilder;
+
+    /// Verify that a SQLite-backed `DieselApiKeyStore` correctly supports adding, fetching,
+    /// listing and removing API keys.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselApiKeyStore`.
+    /// 3. Add a scoped key that expires and an unscoped key that does not.
+    /// 4. Verify that adding a key with an existing ID fails with a unique constraint violation.
+    /// 5. Verify that `get_api_key` returns each key, and `None` for an unknown ID.
+    /// 6. Verify that `list_api_keys` returns both keys, ordered by ID.
+    /// 7. Remove the first key and verify that it is no longer returned.
+    /// 8. Verify that removing an unknown key fails with a not found constraint violation.
+    #[test]
+    fn sqlite_add_get_list_and_remove_api_keys() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselApiKeyStore::new(pool);
+
+        let scoped = ApiKeyBuilder::new()
+            .with_id("apikey-1".into())
+            .with_display_name("ci".into())
+            .with_secret_hash("abcd".into())
+            .with_scopes(vec!["circuit.read".into(), "status.read".into()])
+            .with_created_at(SystemTime::UNIX_EPOCH + Duration::from_secs(100))
+            .with_expires_at(SystemTime::UNIX_EPOCH + Duration::from_secs(200))
+            .build()
+            .expect("Unable to build key");
+        let unscoped = ApiKeyBuilder::new()
+            .with_id("apikey-2".into())
+            .with_display_name("integration".into())
+            .with_secret_hash("ef01".into())
+            .with_created_at(SystemTime::UNIX_EPOCH + Duration::from_secs(100))
+            .build()
+            .expect("Unable to build key");
+
+        store
+            .add_api_key(scoped.clone())
+            .expect("Unable to add key");
+        store
+            .add_api_key(unscoped.clone())
+            .expect("Unable to add key");
+
+        match store.add_api_key(scoped.clone()) {
+            Err(ApiKeyStoreError::ConstraintViolation(err)) => {
+                assert_eq!(err.violation_type(), &ConstraintViolationType::Unique)
+            }
+            res => panic!("Expected unique constraint violation, got {:?}", res),
+        }
+
+        assert_eq!(
+            store.get_api_key("apikey-1").expect("Unable to get key"),
+            Some(scoped.clone())
+        );
+        assert_eq!(
+            store.get_api_key("apikey-2").expect("Unable to get key"),
+            Some(unscoped.clone())
+        );
+        assert_eq!(
+            store.get_api_key("apikey-3").expect("Unable to get key"),
+            None
+        );
+
+        assert_eq!(
+            store.list_api_keys().expect("Unable to list keys"),
+            vec![scoped, unscoped.clone()]
+        );
+
+        store
+            .remove_api_key("apikey-1")
+            .expect("Unable to remove key");
+        assert_eq!(
+            store.get_api_key("apikey-1").expect("Unable to get key"),
+            None
+        );
+        assert_eq!(
+            store.list_api_keys().expect("Unable to list keys"),
+            vec![unscoped]
+        );
+
+        match store.remove_api_key("apikey-1") {
+            Err(ApiKeyStoreError::ConstraintViolation(err)) => {
+                assert_eq!(err.violation_type(), &ConstraintViolationType::NotFound)
+            }
+            res => panic!("Expected not found constraint violation, got {:?}", res),
+        }
+    }
+
+    /// Creates a connection pool for an in-memory SQLite database with only a single connection
+    /// available. Each connection is backed by a different in-memory SQLite database, so limiting
+    /// the pool to a single connection insures that the same DB is used for all operations.
+    fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+}
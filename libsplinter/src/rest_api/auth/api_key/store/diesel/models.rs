@@ -0,0 +1,97 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime};
+
+use crate::error::InternalError;
+use crate::rest_api::auth::api_key::store::{ApiKey, ApiKeyStoreError};
+
+use super::schema::{api_key_scopes, api_keys};
+
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
+#[table_name = "api_keys"]
+#[primary_key(id)]
+pub(super) struct ApiKeyModel {
+    pub id: String,
+    pub display_name: String,
+    pub secret_hash: String,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
+#[table_name = "api_key_scopes"]
+#[belongs_to(ApiKeyModel, foreign_key = "api_key_id")]
+#[primary_key(api_key_id, permission_id)]
+pub(super) struct ApiKeyScopeModel {
+    pub api_key_id: String,
+    pub permission_id: String,
+}
+
+impl TryFrom<&ApiKey> for ApiKeyModel {
+    type Error = ApiKeyStoreError;
+
+    fn try_from(api_key: &ApiKey) -> Result<Self, Self::Error> {
+        Ok(ApiKeyModel {
+            id: api_key.id.clone(),
+            display_name: api_key.display_name.clone(),
+            secret_hash: api_key.secret_hash.clone(),
+            created_at: to_seconds(api_key.created_at)?,
+            expires_at: api_key.expires_at.map(to_seconds).transpose()?,
+        })
+    }
+}
+
+impl From<&ApiKey> for Vec<ApiKeyScopeModel> {
+    fn from(api_key: &ApiKey) -> Self {
+        api_key
+            .scopes
+            .iter()
+            .map(|permission_id| ApiKeyScopeModel {
+                api_key_id: api_key.id.clone(),
+                permission_id: permission_id.clone(),
+            })
+            .collect()
+    }
+}
+
+impl From<(ApiKeyModel, Vec<ApiKeyScopeModel>)> for ApiKey {
+    fn from((model, scopes): (ApiKeyModel, Vec<ApiKeyScopeModel>)) -> Self {
+        let mut scopes = scopes
+            .into_iter()
+            .map(|scope| scope.permission_id)
+            .collect::<Vec<_>>();
+        scopes.sort();
+
+        ApiKey {
+            id: model.id,
+            display_name: model.display_name,
+            secret_hash: model.secret_hash,
+            scopes,
+            created_at: from_seconds(model.created_at),
+            expires_at: model.expires_at.map(from_seconds),
+        }
+    }
+}
+
+fn to_seconds(time: SystemTime) -> Result<i64, ApiKeyStoreError> {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .map_err(|err| ApiKeyStoreError::Internal(InternalError::from_source(Box::new(err))))
+}
+
+fn from_seconds(seconds: i64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(seconds as u64)
+}
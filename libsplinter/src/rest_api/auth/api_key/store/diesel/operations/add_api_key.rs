@@ -0,0 +1,71 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::rest_api::auth::api_key::store::{
+    diesel::{
+        models::{ApiKeyModel, ApiKeyScopeModel},
+        schema::{api_key_scopes, api_keys},
+    },
+    ApiKey, ApiKeyStoreError,
+};
+
+use super::ApiKeyStoreOperations;
+
+pub trait ApiKeyStoreAddApiKey {
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), ApiKeyStoreError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> ApiKeyStoreAddApiKey for ApiKeyStoreOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), ApiKeyStoreError> {
+        let model = ApiKeyModel::try_from(&api_key)?;
+        let scopes = Vec::<ApiKeyScopeModel>::from(&api_key);
+
+        self.conn.transaction::<_, _, _>(|| {
+            insert_into(api_keys::table)
+                .values(model)
+                .execute(self.conn)?;
+
+            insert_into(api_key_scopes::table)
+                .values(scopes)
+                .execute(self.conn)?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> ApiKeyStoreAddApiKey for ApiKeyStoreOperations<'a, diesel::pg::PgConnection> {
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), ApiKeyStoreError> {
+        let model = ApiKeyModel::try_from(&api_key)?;
+        let scopes = Vec::<ApiKeyScopeModel>::from(&api_key);
+
+        self.conn.transaction::<_, _, _>(|| {
+            insert_into(api_keys::table)
+                .values(model)
+                .execute(self.conn)?;
+
+            insert_into(api_key_scopes::table)
+                .values(scopes)
+                .execute(self.conn)?;
+
+            Ok(())
+        })
+    }
+}
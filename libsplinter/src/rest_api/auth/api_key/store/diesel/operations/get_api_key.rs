@@ -0,0 +1,50 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::rest_api::auth::api_key::store::{
+    diesel::{
+        models::{ApiKeyModel, ApiKeyScopeModel},
+        schema::api_keys,
+    },
+    ApiKey, ApiKeyStoreError,
+};
+
+use super::ApiKeyStoreOperations;
+
+pub trait ApiKeyStoreGetApiKey {
+    fn get_api_key(&self, id: &str) -> Result<Option<ApiKey>, ApiKeyStoreError>;
+}
+
+impl<'a, C> ApiKeyStoreGetApiKey for ApiKeyStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn get_api_key(&self, id: &str) -> Result<Option<ApiKey>, ApiKeyStoreError> {
+        self.conn.transaction::<_, _, _>(|| {
+            let api_keys = api_keys::table
+                .filter(api_keys::id.eq(id))
+                .load::<ApiKeyModel>(self.conn)?;
+
+            let scopes = ApiKeyScopeModel::belonging_to(&api_keys)
+                .load::<ApiKeyScopeModel>(self.conn)?
+                .grouped_by(&api_keys);
+
+            Ok(api_keys.into_iter().zip(scopes).next().map(ApiKey::from))
+        })
+    }
+}
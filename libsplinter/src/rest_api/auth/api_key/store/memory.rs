@@ -0,0 +1,90 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A memory-backed implementation of the [ApiKeyStore]
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use crate::error::{ConstraintViolationError, ConstraintViolationType, InternalError};
+
+use super::{error::ApiKeyStoreError, ApiKey, ApiKeyStore};
+
+#[derive(Default, Clone)]
+pub struct MemoryApiKeyStore {
+    inner: Arc<Mutex<BTreeMap<String, ApiKey>>>,
+}
+
+impl MemoryApiKeyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ApiKeyStore for MemoryApiKeyStore {
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), ApiKeyStoreError> {
+        let mut inner = self.inner.lock().map_err(|_| {
+            ApiKeyStoreError::Internal(InternalError::with_message(
+                "Cannot access API key store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        if inner.contains_key(api_key.id()) {
+            return Err(ApiKeyStoreError::ConstraintViolation(
+                ConstraintViolationError::with_violation_type(ConstraintViolationType::Unique),
+            ));
+        }
+
+        inner.insert(api_key.id().to_string(), api_key);
+        Ok(())
+    }
+
+    fn get_api_key(&self, id: &str) -> Result<Option<ApiKey>, ApiKeyStoreError> {
+        let inner = self.inner.lock().map_err(|_| {
+            ApiKeyStoreError::Internal(InternalError::with_message(
+                "Cannot access API key store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        Ok(inner.get(id).cloned())
+    }
+
+    fn list_api_keys(&self) -> Result<Vec<ApiKey>, ApiKeyStoreError> {
+        let inner = self.inner.lock().map_err(|_| {
+            ApiKeyStoreError::Internal(InternalError::with_message(
+                "Cannot access API key store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        Ok(inner.values().cloned().collect())
+    }
+
+    fn remove_api_key(&self, id: &str) -> Result<(), ApiKeyStoreError> {
+        let mut inner = self.inner.lock().map_err(|_| {
+            ApiKeyStoreError::Internal(InternalError::with_message(
+                "Cannot access API key store: mutex lock poisoned".to_string(),
+            ))
+        })?;
+
+        inner.remove(id).map(|_| ()).ok_or_else(|| {
+            ApiKeyStoreError::ConstraintViolation(ConstraintViolationError::with_violation_type(
+                ConstraintViolationType::NotFound,
+            ))
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn ApiKeyStore> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines API keys and the store they are kept in.
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub(in crate::rest_api::auth::api_key) mod diesel;
+pub mod error;
+pub(in crate::rest_api::auth::api_key) mod memory;
+
+use std::time::SystemTime;
+
+use crate::error::InvalidStateError;
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub use self::diesel::DieselApiKeyStore;
+pub use error::ApiKeyStoreError;
+pub use memory::MemoryApiKeyStore;
+
+/// A long-lived key that a client authenticates with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiKey {
+    id: String,
+    display_name: String,
+    secret_hash: String,
+    scopes: Vec<String>,
+    created_at: SystemTime,
+    expires_at: Option<SystemTime>,
+}
+
+impl ApiKey {
+    /// Returns the key's ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the key's human-readable name.
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    /// Returns the hex-encoded SHA-256 hash of the key's secret.
+    pub fn secret_hash(&self) -> &str {
+        &self.secret_hash
+    }
+
+    /// Returns the permissions the key is limited to. If empty, the key is not limited.
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Returns the time the key was created.
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+
+    /// Returns the time after which the key is no longer accepted, if any.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.expires_at
+    }
+
+    /// Returns whether the key has expired as of the given time.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        match self.expires_at {
+            Some(expires_at) => expires_at <= now,
+            None => false,
+        }
+    }
+
+    /// Returns whether the key may be used for the given permission.
+    pub fn allows(&self, permission_id: &str) -> bool {
+        self.scopes.is_empty() || self.scopes.iter().any(|scope| scope == permission_id)
+    }
+}
+
+/// Builds a new `ApiKey`.
+#[derive(Default)]
+pub struct ApiKeyBuilder {
+    id: Option<String>,
+    display_name: Option<String>,
+    secret_hash: Option<String>,
+    scopes: Vec<String>,
+    created_at: Option<SystemTime>,
+    expires_at: Option<SystemTime>,
+}
+
+impl ApiKeyBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_id(mut self, id: String) -> ApiKeyBuilder {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn with_display_name(mut self, display_name: String) -> ApiKeyBuilder {
+        self.display_name = Some(display_name);
+        self
+    }
+
+    pub fn with_secret_hash(mut self, secret_hash: String) -> ApiKeyBuilder {
+        self.secret_hash = Some(secret_hash);
+        self
+    }
+
+    /// Sets the permissions the key is limited to; the scopes are kept sorted and deduplicated.
+    pub fn with_scopes(mut self, mut scopes: Vec<String>) -> ApiKeyBuilder {
+        scopes.sort();
+        scopes.dedup();
+        self.scopes = scopes;
+        self
+    }
+
+    /// Sets the time the key was created; defaults to the current time.
+    pub fn with_created_at(mut self, created_at: SystemTime) -> ApiKeyBuilder {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    pub fn with_expires_at(mut self, expires_at: SystemTime) -> ApiKeyBuilder {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    pub fn build(self) -> Result<ApiKey, InvalidStateError> {
+        Ok(ApiKey {
+            id: self.id.ok_or_else(|| {
+                InvalidStateError::with_message("An id is required to build an ApiKey".into())
+            })?,
+            display_name: self.display_name.ok_or_else(|| {
+                InvalidStateError::with_message(
+                    "A display name is required to build an ApiKey".into(),
+                )
+            })?,
+            secret_hash: self.secret_hash.ok_or_else(|| {
+                InvalidStateError::with_message(
+                    "A secret hash is required to build an ApiKey".into(),
+                )
+            })?,
+            scopes: self.scopes,
+            created_at: self.created_at.unwrap_or_else(SystemTime::now),
+            expires_at: self.expires_at,
+        })
+    }
+}
+
+/// Defines methods for managing API keys without defining a storage strategy.
+pub trait ApiKeyStore: Sync + Send {
+    /// Adds an API key to the underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///  * `api_key` - The API key to be added
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConstraintViolation` error if a key with the same ID already exists.
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), ApiKeyStoreError>;
+
+    /// Fetches an API key from the underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///  * `id` - The ID of the API key
+    fn get_api_key(&self, id: &str) -> Result<Option<ApiKey>, ApiKeyStoreError>;
+
+    /// Lists all API keys in the underlying storage, ordered by ID
+    fn list_api_keys(&self) -> Result<Vec<ApiKey>, ApiKeyStoreError>;
+
+    /// Removes an API key from the underlying storage, revoking it
+    ///
+    /// # Arguments
+    ///
+    ///  * `id` - The ID of the API key
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConstraintViolation` error with a `NotFound` violation type if the key does not
+    /// exist.
+    fn remove_api_key(&self, id: &str) -> Result<(), ApiKeyStoreError>;
+
+    /// Clone into a boxed, dynamically dispatched store
+    fn clone_box(&self) -> Box<dyn ApiKeyStore>;
+}
+
+impl Clone for Box<dyn ApiKeyStore> {
+    fn clone(&self) -> Box<dyn ApiKeyStore> {
+        self.clone_box()
+    }
+}
+
+impl<AKS> ApiKeyStore for Box<AKS>
+where
+    AKS: ApiKeyStore + ?Sized,
+{
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), ApiKeyStoreError> {
+        (**self).add_api_key(api_key)
+    }
+
+    fn get_api_key(&self, id: &str) -> Result<Option<ApiKey>, ApiKeyStoreError> {
+        (**self).get_api_key(id)
+    }
+
+    fn list_api_keys(&self) -> Result<Vec<ApiKey>, ApiKeyStoreError> {
+        (**self).list_api_keys()
+    }
+
+    fn remove_api_key(&self, id: &str) -> Result<(), ApiKeyStoreError> {
+        (**self).remove_api_key(id)
+    }
+
+    fn clone_box(&self) -> Box<dyn ApiKeyStore> {
+        (**self).clone_box()
+    }
+}
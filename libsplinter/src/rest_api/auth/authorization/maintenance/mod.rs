@@ -120,6 +120,8 @@ impl AuthorizationHandler for MaintenanceModeAuthorizationHandler {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    use crate::rest_api::auth::authorization::rbac::store::GroupMapping;
     use crate::rest_api::auth::authorization::rbac::store::{
         Assignment, AssignmentBuilder, Role, RoleBasedAuthorizationStore,
         RoleBasedAuthorizationStoreError,
@@ -319,6 +321,64 @@ mod tests {
             unimplemented!()
         }
 
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn get_group_mapping(
+            &self,
+            _group_id: &str,
+        ) -> Result<Option<GroupMapping>, RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn list_group_mappings(
+            &self,
+        ) -> Result<Box<dyn ExactSizeIterator<Item = GroupMapping>>, RoleBasedAuthorizationStoreError>
+        {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn set_group_mapping(
+            &self,
+            _group_mapping: GroupMapping,
+        ) -> Result<(), RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn remove_group_mapping(
+            &self,
+            _group_id: &str,
+        ) -> Result<(), RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn get_group_assigned_roles(
+            &self,
+            _identity: &RBACIdentity,
+        ) -> Result<Box<dyn ExactSizeIterator<Item = Role>>, RoleBasedAuthorizationStoreError>
+        {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn get_identity_groups(
+            &self,
+            _identity: &RBACIdentity,
+        ) -> Result<Vec<String>, RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn set_identity_groups(
+            &self,
+            _identity: &RBACIdentity,
+            _groups: Vec<String>,
+        ) -> Result<(), RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
         fn clone_box(&self) -> Box<dyn RoleBasedAuthorizationStore> {
             Box::new(self.clone())
         }
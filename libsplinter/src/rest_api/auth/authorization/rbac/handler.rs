@@ -19,7 +19,7 @@ use crate::rest_api::auth::{
     identity::Identity,
 };
 
-use super::store::{RoleBasedAuthorizationStore, ADMIN_ROLE_ID};
+use super::store::{Role, RoleBasedAuthorizationStore, ADMIN_ROLE_ID};
 
 /// A Role-based authorization handler.
 ///
@@ -27,6 +27,9 @@ use super::store::{RoleBasedAuthorizationStore, ADMIN_ROLE_ID};
 /// it has been assigned.  If one of the identity's assigned roles contains the permission, then
 /// the identity is allowed access. If not, the handler defers to the next handler in the chain.
 ///
+/// With the `authorization-handler-rbac-groups` feature, the roles that are mapped to the groups
+/// the identity is a member of are also examined.
+///
 /// It currently does not deny any permissions.
 pub struct RoleBasedAuthorizationHandler {
     role_based_auth_store: Box<dyn RoleBasedAuthorizationStore>,
@@ -47,19 +50,38 @@ impl AuthorizationHandler for RoleBasedAuthorizationHandler {
         identity: &Identity,
         permission_id: &str,
     ) -> Result<AuthorizationHandlerResult, InternalError> {
-        match identity.into() {
-            Some(identity) => Ok(self
+        let identity = match identity.into() {
+            Some(identity) => identity,
+            None => return Ok(AuthorizationHandlerResult::Continue),
+        };
+
+        let allows_permission = |role: &Role| {
+            role.id() == ADMIN_ROLE_ID
+                || role.permissions().iter().any(|perm| perm == permission_id)
+        };
+
+        if self
+            .role_based_auth_store
+            .get_assigned_roles(&identity)
+            .map_err(|err| InternalError::from_source(Box::new(err)))?
+            .any(|role| allows_permission(&role))
+        {
+            return Ok(AuthorizationHandlerResult::Allow);
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        {
+            if self
                 .role_based_auth_store
-                .get_assigned_roles(&identity)
+                .get_group_assigned_roles(&identity)
                 .map_err(|err| InternalError::from_source(Box::new(err)))?
-                .find(|role| {
-                    role.id() == ADMIN_ROLE_ID
-                        || role.permissions().iter().any(|perm| perm == permission_id)
-                })
-                .map(|_| AuthorizationHandlerResult::Allow)
-                .unwrap_or(AuthorizationHandlerResult::Continue)),
-            None => Ok(AuthorizationHandlerResult::Continue),
+                .any(|role| allows_permission(&role))
+            {
+                return Ok(AuthorizationHandlerResult::Allow);
+            }
         }
+
+        Ok(AuthorizationHandlerResult::Continue)
     }
 
     fn clone_box(&self) -> Box<dyn AuthorizationHandler> {
@@ -73,6 +95,8 @@ impl AuthorizationHandler for RoleBasedAuthorizationHandler {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    use crate::rest_api::auth::authorization::rbac::store::GroupMappingBuilder;
     use crate::rest_api::auth::authorization::rbac::store::{
         AssignmentBuilder, DieselRoleBasedAuthorizationStore, Identity as StoreIdentity,
         RoleBuilder,
@@ -154,6 +178,66 @@ mod tests {
         assert!(matches!(result, AuthorizationHandlerResult::Continue));
     }
 
+    /// This test checks that an identity that is a member of a group that is mapped to a role
+    /// with the permission will return Allow when queried, and Continue once it is no longer a
+    /// member of the group.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    #[test]
+    fn allow_user_identity_with_group_mapping() {
+        let role_based_auth_store = create_role_based_authorization_store();
+
+        let role = RoleBuilder::new()
+            .with_id("test-role-1".into())
+            .with_display_name("Test Role 1".into())
+            .with_permissions(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+            .build()
+            .expect("Unable to build role");
+
+        role_based_auth_store
+            .add_role(role)
+            .expect("Unable to add role");
+
+        let group_mapping = GroupMappingBuilder::new()
+            .with_group_id("test-group".into())
+            .with_roles(vec!["test-role-1".to_string()])
+            .build()
+            .expect("Unable to build group mapping");
+
+        role_based_auth_store
+            .set_group_mapping(group_mapping)
+            .expect("Unable to set group mapping");
+
+        let store_identity = StoreIdentity::User("some-user-id".into());
+        role_based_auth_store
+            .set_identity_groups(&store_identity, vec!["test-group".into()])
+            .expect("Unable to set identity groups");
+
+        let handler = RoleBasedAuthorizationHandler::new(role_based_auth_store.clone_box());
+        let identity = Identity::User("some-user-id".into());
+
+        let result = handler
+            .has_permission(&identity, "a")
+            .expect("Should have returned an auth result");
+
+        assert!(matches!(result, AuthorizationHandlerResult::Allow));
+
+        let result = handler
+            .has_permission(&identity, "x")
+            .expect("Should have returned an auth result");
+
+        assert!(matches!(result, AuthorizationHandlerResult::Continue));
+
+        role_based_auth_store
+            .set_identity_groups(&store_identity, vec![])
+            .expect("Unable to set identity groups");
+
+        let result = handler
+            .has_permission(&identity, "a")
+            .expect("Should have returned an auth result");
+
+        assert!(matches!(result, AuthorizationHandlerResult::Continue));
+    }
+
     /// This test checks that an identity with an assigned role will return Allow when queried.
     fn test_allow_identity_with_assignment(identity: Identity, store_identity: StoreIdentity) {
         let role_based_auth_store = create_role_based_authorization_store();
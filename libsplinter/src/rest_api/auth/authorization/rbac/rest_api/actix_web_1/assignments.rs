@@ -374,6 +374,8 @@ mod tests {
     use serde_json::{to_value, Value as JsonValue};
 
     use crate::error::{ConstraintViolationError, ConstraintViolationType};
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    use crate::rest_api::auth::authorization::rbac::store::GroupMapping;
     use crate::rest_api::auth::authorization::rbac::store::{
         Assignment, AssignmentBuilder, Identity, Role, RoleBasedAuthorizationStoreError,
         RoleBuilder,
@@ -1489,6 +1491,64 @@ mod tests {
             Ok(())
         }
 
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn get_group_mapping(
+            &self,
+            _group_id: &str,
+        ) -> Result<Option<GroupMapping>, RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn list_group_mappings(
+            &self,
+        ) -> Result<Box<dyn ExactSizeIterator<Item = GroupMapping>>, RoleBasedAuthorizationStoreError>
+        {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn set_group_mapping(
+            &self,
+            _group_mapping: GroupMapping,
+        ) -> Result<(), RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn remove_group_mapping(
+            &self,
+            _group_id: &str,
+        ) -> Result<(), RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn get_group_assigned_roles(
+            &self,
+            _identity: &Identity,
+        ) -> Result<Box<dyn ExactSizeIterator<Item = Role>>, RoleBasedAuthorizationStoreError>
+        {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn get_identity_groups(
+            &self,
+            _identity: &Identity,
+        ) -> Result<Vec<String>, RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        fn set_identity_groups(
+            &self,
+            _identity: &Identity,
+            _groups: Vec<String>,
+        ) -> Result<(), RoleBasedAuthorizationStoreError> {
+            unimplemented!()
+        }
+
         fn clone_box(&self) -> Box<dyn RoleBasedAuthorizationStore> {
             Box::new(self.clone())
         }
@@ -0,0 +1,491 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::actix_web::{error::BlockingError, web, Error, HttpRequest, HttpResponse};
+use crate::futures::{Future, IntoFuture, Stream};
+use crate::protocol;
+use crate::rest_api::{
+    actix_web_1::{Method, ProtocolVersionRangeGuard, Resource},
+    auth::authorization::rbac::{
+        rest_api::{
+            resources::{
+                groups::{GroupMappingPayload, GroupMappingResponse, ListGroupMappingsResponse},
+                PagingQuery,
+            },
+            RBAC_READ_PERMISSION, RBAC_WRITE_PERMISSION,
+        },
+        store::RoleBasedAuthorizationStore,
+    },
+    paging::get_response_paging_info,
+    ErrorResponse,
+};
+
+use super::error::SendableRoleBasedAuthorizationStoreError;
+
+pub fn make_groups_resource(
+    role_based_auth_store: Box<dyn RoleBasedAuthorizationStore>,
+) -> Resource {
+    Resource::build("/authorization/groups")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::AUTHORIZATION_RBAC_GROUPS_MIN,
+            protocol::AUTHORIZATION_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, RBAC_READ_PERMISSION, move |r, _| {
+            list_group_mappings(r, web::Data::new(role_based_auth_store.clone()))
+        })
+}
+
+pub fn make_group_resource(
+    role_based_auth_store: Box<dyn RoleBasedAuthorizationStore>,
+) -> Resource {
+    let get_store = role_based_auth_store.clone();
+    let put_store = role_based_auth_store.clone();
+    let delete_store = role_based_auth_store;
+    Resource::build("/authorization/groups/{group_id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::AUTHORIZATION_RBAC_GROUPS_MIN,
+            protocol::AUTHORIZATION_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, RBAC_READ_PERMISSION, move |r, _| {
+            get_group_mapping(r, web::Data::new(get_store.clone()))
+        })
+        .add_method(Method::Put, RBAC_WRITE_PERMISSION, move |r, p| {
+            put_group_mapping(r, p, web::Data::new(put_store.clone()))
+        })
+        .add_method(Method::Delete, RBAC_WRITE_PERMISSION, move |r, _| {
+            delete_group_mapping(r, web::Data::new(delete_store.clone()))
+        })
+}
+
+fn list_group_mappings(
+    req: HttpRequest,
+    role_based_auth_store: web::Data<Box<dyn RoleBasedAuthorizationStore>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let web::Query(paging_query): web::Query<PagingQuery> =
+        match web::Query::from_query(req.query_string()) {
+            Ok(paging_query) => paging_query,
+            Err(_) => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request("Invalid query"))
+                        .into_future(),
+                )
+            }
+        };
+
+    let link = format!("{}?", req.uri().path());
+
+    Box::new(
+        web::block(move || {
+            let group_mappings = role_based_auth_store
+                .list_group_mappings()
+                .map_err(SendableRoleBasedAuthorizationStoreError::from)?;
+
+            let total = group_mappings.len();
+            let group_mappings = group_mappings
+                .skip(paging_query.offset)
+                .take(paging_query.limit)
+                .collect::<Vec<_>>();
+
+            Ok((group_mappings, link, paging_query, total))
+        })
+        .then(
+            |res: Result<_, BlockingError<SendableRoleBasedAuthorizationStoreError>>| match res {
+                Ok((group_mappings, link, paging_query, total)) => {
+                    Ok(HttpResponse::Ok().json(ListGroupMappingsResponse {
+                        data: group_mappings
+                            .iter()
+                            .map(GroupMappingResponse::from)
+                            .collect(),
+                        paging: get_response_paging_info(
+                            Some(paging_query.limit),
+                            Some(paging_query.offset),
+                            &link,
+                            total,
+                        ),
+                    }))
+                }
+                Err(err) => {
+                    error!("Unable to list group mappings: {}", err);
+                    Ok(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+                }
+            },
+        ),
+    )
+}
+
+fn get_group_mapping(
+    req: HttpRequest,
+    role_based_auth_store: web::Data<Box<dyn RoleBasedAuthorizationStore>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let group_id = req.match_info().get("group_id").unwrap_or("").to_string();
+    Box::new(
+        web::block(move || {
+            role_based_auth_store
+                .get_group_mapping(&group_id)
+                .map_err(SendableRoleBasedAuthorizationStoreError::from)
+        })
+        .then(|group_mapping_res| {
+            Ok(match group_mapping_res {
+                Ok(Some(group_mapping)) => HttpResponse::Ok().json(json!({
+                    "data": GroupMappingResponse::from(&group_mapping),
+                })),
+                Ok(None) => HttpResponse::NotFound()
+                    .json(ErrorResponse::not_found("Group mapping not found")),
+                Err(err) => {
+                    error!("Unable to get group mapping: {}", err);
+                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                }
+            })
+        }),
+    )
+}
+
+fn put_group_mapping(
+    req: HttpRequest,
+    payload: web::Payload,
+    role_based_auth_store: web::Data<Box<dyn RoleBasedAuthorizationStore>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let group_id = req.match_info().get("group_id").unwrap_or("").to_string();
+    Box::new(
+        payload
+            .from_err::<Error>()
+            .fold(web::BytesMut::new(), move |mut body, chunk| {
+                body.extend_from_slice(&chunk);
+                Ok::<_, Error>(body)
+            })
+            .into_future()
+            .and_then(move |body| {
+                let group_mapping_res = serde_json::from_slice::<GroupMappingPayload>(&body)
+                    .map_err(|err| err.to_string())
+                    .and_then(|group_mapping_payload| {
+                        group_mapping_payload
+                            .into_group_mapping(group_id)
+                            .map_err(|err| err.to_string())
+                    });
+
+                match group_mapping_res {
+                    Ok(group_mapping) => Box::new(
+                        web::block(move || {
+                            role_based_auth_store
+                                .set_group_mapping(group_mapping)
+                                .map_err(SendableRoleBasedAuthorizationStoreError::from)
+                        })
+                        .then(|res| {
+                            Ok(match res {
+                                Ok(_) => HttpResponse::Ok().finish(),
+                                Err(BlockingError::Error(
+                                    SendableRoleBasedAuthorizationStoreError::ConstraintViolation(
+                                        msg,
+                                    ),
+                                )) => HttpResponse::Conflict().json(ErrorResponse::conflict(&msg)),
+                                Err(err) => {
+                                    error!("Unable to set group mapping: {}", err);
+                                    HttpResponse::InternalServerError()
+                                        .json(ErrorResponse::internal_error())
+                                }
+                            })
+                        }),
+                    )
+                        as Box<dyn Future<Item = HttpResponse, Error = Error>>,
+                    Err(err) => Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Invalid group mapping payload: {}",
+                                err
+                            )))
+                            .into_future(),
+                    ),
+                }
+            }),
+    )
+}
+
+fn delete_group_mapping(
+    req: HttpRequest,
+    role_based_auth_store: web::Data<Box<dyn RoleBasedAuthorizationStore>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let group_id = req.match_info().get("group_id").unwrap_or("").to_string();
+    Box::new(
+        web::block(move || {
+            role_based_auth_store
+                .remove_group_mapping(&group_id)
+                .map_err(SendableRoleBasedAuthorizationStoreError::from)
+        })
+        .then(|res| {
+            Ok(match res {
+                Ok(()) => HttpResponse::Ok().finish(),
+                Err(BlockingError::Error(SendableRoleBasedAuthorizationStoreError::NotFound(
+                    _,
+                ))) => HttpResponse::NotFound()
+                    .json(ErrorResponse::not_found("Group mapping not found")),
+                Err(err) => {
+                    error!("Unable to delete group mapping: {}", err);
+                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                }
+            })
+        }),
+    )
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+    use reqwest::{blocking::Client, StatusCode, Url};
+    use serde_json::Value as JsonValue;
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::rest_api::actix_web_1::{RestApiBuilder, RestApiShutdownHandle};
+    use crate::rest_api::auth::authorization::rbac::store::{
+        DieselRoleBasedAuthorizationStore, RoleBuilder,
+    };
+    use crate::store::ForeignKeyCustomizer;
+
+    /// Tests a PUT /authorization/groups/{group_id} request followed by GET requests for the
+    /// group mapping and the list of group mappings.
+    /// 1. Adds a role to the store
+    /// 2. Puts a group mapping for the role and verifies the response is OK
+    /// 3. Gets the group mapping and verifies that it contains the role
+    /// 4. Lists the group mappings and verifies that the group mapping is the only one returned
+    #[test]
+    fn test_put_and_get_group_mapping_ok() {
+        let role_based_auth_store = create_role_based_authorization_store();
+        add_test_role(&*role_based_auth_store);
+
+        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
+            make_groups_resource(role_based_auth_store.clone()),
+            make_group_resource(role_based_auth_store),
+        ]);
+
+        let url = Url::parse(&format!(
+            "http://{}/authorization/groups/test-group",
+            bind_url
+        ))
+        .expect("Failed to parse URL");
+
+        let resp = Client::new()
+            .put(url.clone())
+            .header(
+                "SplinterProtocolVersion",
+                protocol::AUTHORIZATION_PROTOCOL_VERSION,
+            )
+            .json(&json!({ "roles": ["test-role"] }))
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::AUTHORIZATION_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: JsonValue = resp.json().expect("Failed to deserialize body");
+        assert_eq!(
+            json!({
+                "data": {
+                    "group_id": "test-group",
+                    "roles": ["test-role"],
+                }
+            }),
+            body
+        );
+
+        let url = Url::parse(&format!("http://{}/authorization/groups", bind_url))
+            .expect("Failed to parse URL");
+
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::AUTHORIZATION_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: JsonValue = resp.json().expect("Failed to deserialize body");
+        assert_eq!(
+            json!([{
+                "group_id": "test-group",
+                "roles": ["test-role"],
+            }]),
+            body["data"]
+        );
+        assert_eq!(json!(1), body["paging"]["total"]);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Tests that PUT /authorization/groups/{group_id} requests return a 400 for a payload without
+    /// any roles and a 409 for a payload with an unknown role.
+    #[test]
+    fn test_put_group_mapping_invalid() {
+        let role_based_auth_store = create_role_based_authorization_store();
+
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_group_resource(role_based_auth_store)]);
+
+        let url = Url::parse(&format!(
+            "http://{}/authorization/groups/test-group",
+            bind_url
+        ))
+        .expect("Failed to parse URL");
+
+        let resp = Client::new()
+            .put(url.clone())
+            .header(
+                "SplinterProtocolVersion",
+                protocol::AUTHORIZATION_PROTOCOL_VERSION,
+            )
+            .json(&json!({ "roles": [] }))
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = Client::new()
+            .put(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::AUTHORIZATION_PROTOCOL_VERSION,
+            )
+            .json(&json!({ "roles": ["unknown-role"] }))
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Tests a DELETE /authorization/groups/{group_id} request removes the group mapping, and that
+    /// deleting it again returns a 404.
+    #[test]
+    fn test_delete_group_mapping() {
+        let role_based_auth_store = create_role_based_authorization_store();
+        add_test_role(&*role_based_auth_store);
+        role_based_auth_store
+            .set_group_mapping(
+                GroupMappingPayload {
+                    roles: vec!["test-role".into()],
+                }
+                .into_group_mapping("test-group".into())
+                .expect("Unable to build group mapping"),
+            )
+            .expect("Unable to set group mapping");
+
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_group_resource(role_based_auth_store.clone())]);
+
+        let url = Url::parse(&format!(
+            "http://{}/authorization/groups/test-group",
+            bind_url
+        ))
+        .expect("Failed to parse URL");
+
+        let resp = Client::new()
+            .delete(url.clone())
+            .header(
+                "SplinterProtocolVersion",
+                protocol::AUTHORIZATION_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        assert!(role_based_auth_store
+            .get_group_mapping("test-group")
+            .expect("Unable to get group mapping")
+            .is_none());
+
+        let resp = Client::new()
+            .delete(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::AUTHORIZATION_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn add_test_role(role_based_auth_store: &dyn RoleBasedAuthorizationStore) {
+        role_based_auth_store
+            .add_role(
+                RoleBuilder::new()
+                    .with_id("test-role".into())
+                    .with_display_name("Test Role".into())
+                    .with_permissions(vec!["a".to_string()])
+                    .build()
+                    .expect("Unable to build role"),
+            )
+            .expect("Unable to add role");
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        #[cfg(not(feature = "https-bind"))]
+        let bind = "127.0.0.1:0";
+        #[cfg(feature = "https-bind")]
+        let bind = crate::rest_api::BindConfig::Http("127.0.0.1:0".into());
+
+        let result = RestApiBuilder::new()
+            .with_bind(bind)
+            .add_resources(resources.clone())
+            .build_insecure()
+            .expect("Failed to build REST API")
+            .run_insecure();
+        match result {
+            Ok((shutdown_handle, join_handle)) => {
+                let port = shutdown_handle.port_numbers()[0];
+                (shutdown_handle, join_handle, format!("127.0.0.1:{}", port))
+            }
+            Err(err) => panic!("Failed to run REST API: {}", err),
+        }
+    }
+
+    /// Creates a role-based authorization store backed by an in-memory SQLite database with only a
+    /// single connection available, so that the same database is used for all operations.
+    fn create_role_based_authorization_store() -> Box<dyn RoleBasedAuthorizationStore> {
+        let pool = Pool::builder()
+            .connection_customizer(Box::new(ForeignKeyCustomizer))
+            .max_size(1)
+            .build(ConnectionManager::<SqliteConnection>::new(":memory:"))
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        Box::new(DieselRoleBasedAuthorizationStore::new(pool))
+    }
+}
@@ -16,6 +16,8 @@
 
 mod assignments;
 mod error;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+mod groups;
 mod roles;
 
 use crate::rest_api::{
@@ -38,11 +40,24 @@ impl RoleBasedAuthorizationResourceProvider {
 
 impl RestResourceProvider for RoleBasedAuthorizationResourceProvider {
     fn resources(&self) -> Vec<Resource> {
-        vec![
+        #[allow(unused_mut)]
+        let mut resources = vec![
             roles::make_roles_resource(self.role_based_authorization_store.clone()),
             roles::make_role_resource(self.role_based_authorization_store.clone()),
             assignments::make_assignments_resource(self.role_based_authorization_store.clone()),
             assignments::make_assignment_resource(self.role_based_authorization_store.clone()),
-        ]
+        ];
+
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        {
+            resources.push(groups::make_groups_resource(
+                self.role_based_authorization_store.clone(),
+            ));
+            resources.push(groups::make_group_resource(
+                self.role_based_authorization_store.clone(),
+            ));
+        }
+
+        resources
     }
 }
@@ -0,0 +1,53 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::error::InvalidStateError;
+use crate::rest_api::{
+    auth::authorization::rbac::store::{GroupMapping, GroupMappingBuilder},
+    paging::Paging,
+};
+
+#[derive(Serialize)]
+pub struct ListGroupMappingsResponse<'a> {
+    pub data: Vec<GroupMappingResponse<'a>>,
+    pub paging: Paging,
+}
+
+#[derive(Serialize)]
+pub struct GroupMappingResponse<'a> {
+    group_id: &'a str,
+    roles: &'a [String],
+}
+
+impl<'a> From<&'a GroupMapping> for GroupMappingResponse<'a> {
+    fn from(group_mapping: &'a GroupMapping) -> Self {
+        Self {
+            group_id: group_mapping.group_id(),
+            roles: group_mapping.roles(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GroupMappingPayload {
+    pub roles: Vec<String>,
+}
+
+impl GroupMappingPayload {
+    pub fn into_group_mapping(self, group_id: String) -> Result<GroupMapping, InvalidStateError> {
+        GroupMappingBuilder::new()
+            .with_group_id(group_id)
+            .with_roles(self.roles)
+            .build()
+    }
+}
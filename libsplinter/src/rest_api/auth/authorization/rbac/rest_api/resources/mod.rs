@@ -15,6 +15,8 @@
 //! Web-framework-agnostic resources.
 
 pub mod assignments;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+pub mod groups;
 pub mod roles;
 
 use crate::rest_api::paging::{DEFAULT_LIMIT, DEFAULT_OFFSET};
@@ -24,6 +24,8 @@ use crate::error::{
 
 use diesel::r2d2::{ConnectionManager, Pool};
 
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use super::GroupMapping;
 use super::{
     Assignment, Identity, Role, RoleBasedAuthorizationStore, RoleBasedAuthorizationStoreError,
     RoleBuilder, ADMIN_ROLE_ID,
@@ -33,11 +35,25 @@ use operations::add_assignment::RoleBasedAuthorizationStoreAddAssignment as _;
 use operations::add_role::RoleBasedAuthorizationStoreAddRole as _;
 use operations::get_assigned_roles::RoleBasedAuthorizationStoreGetAssignedRoles as _;
 use operations::get_assignment::RoleBasedAuthorizationStoreGetAssignment as _;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use operations::get_group_assigned_roles::RoleBasedAuthorizationStoreGetGroupAssignedRoles as _;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use operations::get_group_mapping::RoleBasedAuthorizationStoreGetGroupMapping as _;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use operations::get_identity_groups::RoleBasedAuthorizationStoreGetIdentityGroups as _;
 use operations::get_role::RoleBasedAuthorizationStoreGetRole as _;
 use operations::list_assignments::RoleBasedAuthorizationStoreListAssignments as _;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use operations::list_group_mappings::RoleBasedAuthorizationStoreListGroupMappings as _;
 use operations::list_roles::RoleBasedAuthorizationStoreListRoles as _;
 use operations::remove_assignment::RoleBasedAuthorizationStoreRemoveAssignment as _;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use operations::remove_group_mapping::RoleBasedAuthorizationStoreRemoveGroupMapping as _;
 use operations::remove_role::RoleBasedAuthorizationStoreRemoveRole as _;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use operations::set_group_mapping::RoleBasedAuthorizationStoreSetGroupMapping as _;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use operations::set_identity_groups::RoleBasedAuthorizationStoreSetIdentityGroups as _;
 use operations::update_assignment::RoleBasedAuthorizationStoreUpdateAssignment as _;
 use operations::update_role::RoleBasedAuthorizationStoreUpdateRole as _;
 use operations::RoleBasedAuthorizationStoreOperations;
@@ -182,6 +198,85 @@ impl RoleBasedAuthorizationStore
         RoleBasedAuthorizationStoreOperations::new(&*connection).remove_assignment(identity)
     }
 
+    /// Returns the group mapping for the given group ID, if one exists.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn get_group_mapping(
+        &self,
+        group_id: &str,
+    ) -> Result<Option<GroupMapping>, RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).get_group_mapping(group_id)
+    }
+
+    /// Lists all group mappings.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn list_group_mappings(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = GroupMapping>>, RoleBasedAuthorizationStoreError>
+    {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).list_group_mappings()
+    }
+
+    /// Sets a group mapping, replacing the roles of an existing mapping for the same group.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConstraintViolation` error if any of the roles do not exist.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn set_group_mapping(
+        &self,
+        group_mapping: GroupMapping,
+    ) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).set_group_mapping(group_mapping)
+    }
+
+    /// Removes a group mapping.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConstraintViolation` error if the group mapping does not exist.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn remove_group_mapping(&self, group_id: &str) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).remove_group_mapping(group_id)
+    }
+
+    /// Returns the roles granted through the group mappings of the groups the given Identity is a
+    /// member of.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn get_group_assigned_roles(
+        &self,
+        identity: &Identity,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Role>>, RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).get_group_assigned_roles(identity)
+    }
+
+    /// Returns the IDs of the groups the given Identity is a member of.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn get_identity_groups(
+        &self,
+        identity: &Identity,
+    ) -> Result<Vec<String>, RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).get_identity_groups(identity)
+    }
+
+    /// Sets the IDs of the groups the given Identity is a member of, replacing any previous group
+    /// memberships.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn set_identity_groups(
+        &self,
+        identity: &Identity,
+        groups: Vec<String>,
+    ) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection)
+            .set_identity_groups(identity, groups)
+    }
+
     /// Clone into a boxed, dynamically dispatched store
     fn clone_box(&self) -> Box<dyn RoleBasedAuthorizationStore> {
         Box::new(DieselRoleBasedAuthorizationStore {
@@ -317,6 +412,85 @@ impl RoleBasedAuthorizationStore for DieselRoleBasedAuthorizationStore<diesel::p
         RoleBasedAuthorizationStoreOperations::new(&*connection).remove_assignment(identity)
     }
 
+    /// Returns the group mapping for the given group ID, if one exists.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn get_group_mapping(
+        &self,
+        group_id: &str,
+    ) -> Result<Option<GroupMapping>, RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).get_group_mapping(group_id)
+    }
+
+    /// Lists all group mappings.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn list_group_mappings(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = GroupMapping>>, RoleBasedAuthorizationStoreError>
+    {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).list_group_mappings()
+    }
+
+    /// Sets a group mapping, replacing the roles of an existing mapping for the same group.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConstraintViolation` error if any of the roles do not exist.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn set_group_mapping(
+        &self,
+        group_mapping: GroupMapping,
+    ) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).set_group_mapping(group_mapping)
+    }
+
+    /// Removes a group mapping.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConstraintViolation` error if the group mapping does not exist.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn remove_group_mapping(&self, group_id: &str) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).remove_group_mapping(group_id)
+    }
+
+    /// Returns the roles granted through the group mappings of the groups the given Identity is a
+    /// member of.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn get_group_assigned_roles(
+        &self,
+        identity: &Identity,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Role>>, RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).get_group_assigned_roles(identity)
+    }
+
+    /// Returns the IDs of the groups the given Identity is a member of.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn get_identity_groups(
+        &self,
+        identity: &Identity,
+    ) -> Result<Vec<String>, RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection).get_identity_groups(identity)
+    }
+
+    /// Sets the IDs of the groups the given Identity is a member of, replacing any previous group
+    /// memberships.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn set_identity_groups(
+        &self,
+        identity: &Identity,
+        groups: Vec<String>,
+    ) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let connection = self.connection_pool.get()?;
+        RoleBasedAuthorizationStoreOperations::new(&*connection)
+            .set_identity_groups(identity, groups)
+    }
+
     /// Clone into a boxed, dynamically dispatched store
     fn clone_box(&self) -> Box<dyn RoleBasedAuthorizationStore> {
         Box::new(DieselRoleBasedAuthorizationStore {
@@ -414,6 +588,23 @@ impl TryFrom<(models::IdentityModel, Vec<models::AssignmentModel>)> for Assignme
     }
 }
 
+#[cfg(feature = "authorization-handler-rbac-groups")]
+impl From<GroupMapping> for (String, Vec<models::GroupMappingModel>) {
+    fn from(group_mapping: GroupMapping) -> Self {
+        let (group_id, roles) = group_mapping.into_parts();
+
+        let models = roles
+            .into_iter()
+            .map(|role_id| models::GroupMappingModel {
+                group_id: group_id.clone(),
+                role_id,
+            })
+            .collect();
+
+        (group_id, models)
+    }
+}
+
 impl From<diesel::result::Error> for RoleBasedAuthorizationStoreError {
     fn from(err: diesel::result::Error) -> Self {
         match err {
@@ -455,6 +646,8 @@ impl From<diesel::r2d2::PoolError> for RoleBasedAuthorizationStoreError {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    use crate::rest_api::auth::authorization::rbac::store::GroupMappingBuilder;
     use crate::rest_api::auth::authorization::rbac::store::{AssignmentBuilder, RoleBuilder};
 
     use crate::migrations::run_sqlite_migrations;
@@ -1163,6 +1356,160 @@ mod tests {
         assert!(stored_assignment.roles().is_empty());
     }
 
+    /// This test verifies the following:
+    /// 1. Add two roles
+    /// 2. Set a group mapping for one role and verify it with the store API
+    /// 3. Set the group mapping again with both roles and verify that the roles were replaced
+    /// 4. List the group mappings and verify the mapping is returned
+    /// 5. Verify that a group mapping with an unknown role is rejected
+    /// 6. Remove the group mapping, verify it is gone, and verify that removing it again fails
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    #[test]
+    fn sqlite_set_and_remove_group_mapping() {
+        let pool = create_connection_pool_and_migrate();
+
+        let role_based_auth_store = DieselRoleBasedAuthorizationStore::new(pool);
+
+        for role_id in &["role-1", "role-2"] {
+            role_based_auth_store
+                .add_role(
+                    RoleBuilder::new()
+                        .with_id(role_id.to_string())
+                        .with_display_name(role_id.to_string())
+                        .with_permissions(vec!["a".to_string()])
+                        .build()
+                        .expect("Unable to build role"),
+                )
+                .expect("Unable to add role");
+        }
+
+        let group_mapping = GroupMappingBuilder::new()
+            .with_group_id("group-1".into())
+            .with_roles(vec!["role-1".into()])
+            .build()
+            .expect("Unable to build group mapping");
+        role_based_auth_store
+            .set_group_mapping(group_mapping.clone())
+            .expect("Unable to set group mapping");
+
+        assert_eq!(
+            Some(group_mapping),
+            role_based_auth_store
+                .get_group_mapping("group-1")
+                .expect("Unable to get group mapping")
+        );
+
+        let group_mapping = GroupMappingBuilder::new()
+            .with_group_id("group-1".into())
+            .with_roles(vec!["role-1".into(), "role-2".into()])
+            .build()
+            .expect("Unable to build group mapping");
+        role_based_auth_store
+            .set_group_mapping(group_mapping.clone())
+            .expect("Unable to replace group mapping");
+
+        assert_eq!(
+            vec![group_mapping],
+            role_based_auth_store
+                .list_group_mappings()
+                .expect("Unable to list group mappings")
+                .collect::<Vec<_>>()
+        );
+
+        let unknown_role_mapping = GroupMappingBuilder::new()
+            .with_group_id("group-2".into())
+            .with_roles(vec!["unknown-role".into()])
+            .build()
+            .expect("Unable to build group mapping");
+        assert!(matches!(
+            role_based_auth_store.set_group_mapping(unknown_role_mapping),
+            Err(RoleBasedAuthorizationStoreError::ConstraintViolation(_))
+        ));
+
+        role_based_auth_store
+            .remove_group_mapping("group-1")
+            .expect("Unable to remove group mapping");
+        assert!(role_based_auth_store
+            .get_group_mapping("group-1")
+            .expect("Unable to get group mapping")
+            .is_none());
+        assert!(matches!(
+            role_based_auth_store.remove_group_mapping("group-1"),
+            Err(RoleBasedAuthorizationStoreError::ConstraintViolation(_))
+        ));
+    }
+
+    /// This test verifies the following:
+    /// 1. Add a role and map a group to it
+    /// 2. Set the groups of a user, including the mapped group, and verify them
+    /// 3. Verify that the mapped role is returned as a group-assigned role for the user
+    /// 4. Replace the user's groups without the mapped group
+    /// 5. Verify that the user no longer has any group-assigned roles
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    #[test]
+    fn sqlite_get_group_assigned_roles() {
+        let pool = create_connection_pool_and_migrate();
+
+        let role_based_auth_store = DieselRoleBasedAuthorizationStore::new(pool);
+
+        let role = RoleBuilder::new()
+            .with_id("test-role".into())
+            .with_display_name("Test Role".into())
+            .with_permissions(vec!["a".to_string(), "b".to_string()])
+            .build()
+            .expect("Unable to build role");
+        role_based_auth_store
+            .add_role(role)
+            .expect("Unable to add role");
+
+        role_based_auth_store
+            .set_group_mapping(
+                GroupMappingBuilder::new()
+                    .with_group_id("mapped-group".into())
+                    .with_roles(vec!["test-role".into()])
+                    .build()
+                    .expect("Unable to build group mapping"),
+            )
+            .expect("Unable to set group mapping");
+
+        let identity = Identity::User("some-user-id".into());
+        role_based_auth_store
+            .set_identity_groups(&identity, vec!["other-group".into(), "mapped-group".into()])
+            .expect("Unable to set identity groups");
+
+        assert_eq!(
+            vec!["mapped-group".to_string(), "other-group".to_string()],
+            role_based_auth_store
+                .get_identity_groups(&identity)
+                .expect("Unable to get identity groups")
+        );
+        let mut group_assigned_roles = role_based_auth_store
+            .get_group_assigned_roles(&identity)
+            .expect("Unable to get group assigned roles");
+        assert_eq!(1, group_assigned_roles.len());
+
+        let stored_role = group_assigned_roles
+            .next()
+            .expect("has 1 item, but returned None");
+        assert_eq!("test-role", stored_role.id());
+        assert_eq!(
+            &["a".to_string(), "b".to_string()],
+            stored_role.permissions()
+        );
+
+        role_based_auth_store
+            .set_identity_groups(&identity, vec!["other-group".into()])
+            .expect("Unable to replace identity groups");
+
+        assert_eq!(
+            0,
+            role_based_auth_store
+                .get_group_assigned_roles(&identity)
+                .expect("Unable to get group assigned roles")
+                .len()
+        );
+    }
+
     /// This tests verifies that the `admin` role is present by default and cannot be removed or
     /// modified
     #[test]
@@ -36,6 +36,8 @@ use diesel::pg::Pg;
 use diesel::sqlite::Sqlite;
 
 use super::schema::{rbac_assignments, rbac_identities, rbac_role_permissions, rbac_roles};
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use super::schema::{rbac_group_mappings, rbac_identity_groups};
 
 #[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
 #[table_name = "rbac_roles"]
@@ -222,3 +224,21 @@ pub(super) struct AssignmentModel {
     pub identity: String,
     pub role_id: String,
 }
+
+#[cfg(feature = "authorization-handler-rbac-groups")]
+#[derive(Debug, PartialEq, Identifiable, Insertable, Queryable)]
+#[table_name = "rbac_group_mappings"]
+#[primary_key(group_id, role_id)]
+pub(super) struct GroupMappingModel {
+    pub group_id: String,
+    pub role_id: String,
+}
+
+#[cfg(feature = "authorization-handler-rbac-groups")]
+#[derive(Debug, PartialEq, Identifiable, Insertable, Queryable)]
+#[table_name = "rbac_identity_groups"]
+#[primary_key(identity, group_id)]
+pub(super) struct IdentityGroupModel {
+    pub identity: String,
+    pub group_id: String,
+}
@@ -0,0 +1,57 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use diesel::prelude::*;
+
+use crate::rest_api::auth::authorization::rbac::store::{
+    diesel::{models::GroupMappingModel, schema::rbac_group_mappings},
+    GroupMapping, RoleBasedAuthorizationStoreError,
+};
+
+use super::RoleBasedAuthorizationStoreOperations;
+
+pub trait RoleBasedAuthorizationStoreGetGroupMapping {
+    fn get_group_mapping(
+        &self,
+        group_id: &str,
+    ) -> Result<Option<GroupMapping>, RoleBasedAuthorizationStoreError>;
+}
+
+impl<'a, C> RoleBasedAuthorizationStoreGetGroupMapping
+    for RoleBasedAuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn get_group_mapping(
+        &self,
+        group_id: &str,
+    ) -> Result<Option<GroupMapping>, RoleBasedAuthorizationStoreError> {
+        let roles = rbac_group_mappings::table
+            .filter(rbac_group_mappings::group_id.eq(group_id))
+            .order(rbac_group_mappings::role_id)
+            .load::<GroupMappingModel>(self.conn)?
+            .into_iter()
+            .map(|model| model.role_id)
+            .collect::<Vec<_>>();
+
+        if roles.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(GroupMapping {
+            group_id: group_id.to_string(),
+            roles,
+        }))
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use diesel::prelude::*;
+
+use crate::rest_api::auth::authorization::rbac::store::{
+    diesel::schema::rbac_identity_groups, Identity, RoleBasedAuthorizationStoreError,
+};
+
+use super::RoleBasedAuthorizationStoreOperations;
+
+pub trait RoleBasedAuthorizationStoreGetIdentityGroups {
+    fn get_identity_groups(
+        &self,
+        identity: &Identity,
+    ) -> Result<Vec<String>, RoleBasedAuthorizationStoreError>;
+}
+
+impl<'a, C> RoleBasedAuthorizationStoreGetIdentityGroups
+    for RoleBasedAuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn get_identity_groups(
+        &self,
+        identity: &Identity,
+    ) -> Result<Vec<String>, RoleBasedAuthorizationStoreError> {
+        let search_identity = match identity {
+            Identity::Key(ref key) => key,
+            Identity::User(ref user_id) => user_id,
+        };
+
+        rbac_identity_groups::table
+            .filter(rbac_identity_groups::identity.eq(search_identity))
+            .select(rbac_identity_groups::group_id)
+            .order(rbac_identity_groups::group_id)
+            .load::<String>(self.conn)
+            .map_err(RoleBasedAuthorizationStoreError::from)
+    }
+}
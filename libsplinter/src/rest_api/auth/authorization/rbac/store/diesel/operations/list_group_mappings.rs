@@ -0,0 +1,56 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::BTreeMap;
+
+use diesel::prelude::*;
+
+use crate::rest_api::auth::authorization::rbac::store::{
+    diesel::{models::GroupMappingModel, schema::rbac_group_mappings},
+    GroupMapping, RoleBasedAuthorizationStoreError,
+};
+
+use super::RoleBasedAuthorizationStoreOperations;
+
+pub trait RoleBasedAuthorizationStoreListGroupMappings {
+    fn list_group_mappings(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = GroupMapping>>, RoleBasedAuthorizationStoreError>;
+}
+
+impl<'a, C> RoleBasedAuthorizationStoreListGroupMappings
+    for RoleBasedAuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_group_mappings(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = GroupMapping>>, RoleBasedAuthorizationStoreError>
+    {
+        let mut group_roles: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for GroupMappingModel { group_id, role_id } in rbac_group_mappings::table
+            .order((rbac_group_mappings::group_id, rbac_group_mappings::role_id))
+            .load::<GroupMappingModel>(self.conn)?
+        {
+            group_roles.entry(group_id).or_default().push(role_id);
+        }
+
+        let group_mappings = group_roles
+            .into_iter()
+            .map(|(group_id, roles)| GroupMapping { group_id, roles })
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(group_mappings.into_iter()))
+    }
+}
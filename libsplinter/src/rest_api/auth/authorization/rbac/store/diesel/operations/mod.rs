@@ -16,11 +16,25 @@ pub(super) mod add_assignment;
 pub(super) mod add_role;
 pub(super) mod get_assigned_roles;
 pub(super) mod get_assignment;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+pub(super) mod get_group_assigned_roles;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+pub(super) mod get_group_mapping;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+pub(super) mod get_identity_groups;
 pub(super) mod get_role;
 pub(super) mod list_assignments;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+pub(super) mod list_group_mappings;
 pub(super) mod list_roles;
 pub(super) mod remove_assignment;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+pub(super) mod remove_group_mapping;
 pub(super) mod remove_role;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+pub(super) mod set_group_mapping;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+pub(super) mod set_identity_groups;
 pub(super) mod update_assignment;
 pub(super) mod update_role;
 
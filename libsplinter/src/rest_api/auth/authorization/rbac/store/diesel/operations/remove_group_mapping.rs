@@ -0,0 +1,46 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use diesel::{dsl::delete, prelude::*};
+
+use crate::error::{ConstraintViolationError, ConstraintViolationType};
+use crate::rest_api::auth::authorization::rbac::store::{
+    diesel::schema::rbac_group_mappings, RoleBasedAuthorizationStoreError,
+};
+
+use super::RoleBasedAuthorizationStoreOperations;
+
+pub trait RoleBasedAuthorizationStoreRemoveGroupMapping {
+    fn remove_group_mapping(&self, group_id: &str) -> Result<(), RoleBasedAuthorizationStoreError>;
+}
+
+impl<'a, C> RoleBasedAuthorizationStoreRemoveGroupMapping
+    for RoleBasedAuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn remove_group_mapping(&self, group_id: &str) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let removed =
+            delete(rbac_group_mappings::table.filter(rbac_group_mappings::group_id.eq(group_id)))
+                .execute(self.conn)?;
+
+        if removed == 0 {
+            return Err(RoleBasedAuthorizationStoreError::ConstraintViolation(
+                ConstraintViolationError::with_violation_type(ConstraintViolationType::NotFound),
+            ));
+        }
+
+        Ok(())
+    }
+}
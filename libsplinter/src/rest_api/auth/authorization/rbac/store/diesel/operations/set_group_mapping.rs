@@ -0,0 +1,75 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use diesel::{
+    dsl::{delete, insert_into},
+    prelude::*,
+};
+
+use crate::rest_api::auth::authorization::rbac::store::{
+    diesel::{models::GroupMappingModel, schema::rbac_group_mappings},
+    GroupMapping, RoleBasedAuthorizationStoreError,
+};
+
+use super::RoleBasedAuthorizationStoreOperations;
+
+pub trait RoleBasedAuthorizationStoreSetGroupMapping {
+    fn set_group_mapping(
+        &self,
+        group_mapping: GroupMapping,
+    ) -> Result<(), RoleBasedAuthorizationStoreError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RoleBasedAuthorizationStoreSetGroupMapping
+    for RoleBasedAuthorizationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn set_group_mapping(
+        &self,
+        group_mapping: GroupMapping,
+    ) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let (group_id, models): (String, Vec<GroupMappingModel>) = group_mapping.into();
+        self.conn.transaction::<_, _, _>(|| {
+            delete(rbac_group_mappings::table.filter(rbac_group_mappings::group_id.eq(&group_id)))
+                .execute(self.conn)?;
+
+            insert_into(rbac_group_mappings::table)
+                .values(models)
+                .execute(self.conn)?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RoleBasedAuthorizationStoreSetGroupMapping
+    for RoleBasedAuthorizationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn set_group_mapping(
+        &self,
+        group_mapping: GroupMapping,
+    ) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let (group_id, models): (String, Vec<GroupMappingModel>) = group_mapping.into();
+        self.conn.transaction::<_, _, _>(|| {
+            delete(rbac_group_mappings::table.filter(rbac_group_mappings::group_id.eq(&group_id)))
+                .execute(self.conn)?;
+
+            insert_into(rbac_group_mappings::table)
+                .values(models)
+                .execute(self.conn)?;
+
+            Ok(())
+        })
+    }
+}
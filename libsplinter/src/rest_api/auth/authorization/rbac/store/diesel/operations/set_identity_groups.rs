@@ -0,0 +1,110 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use diesel::{
+    dsl::{delete, insert_into},
+    prelude::*,
+};
+
+use crate::rest_api::auth::authorization::rbac::store::{
+    diesel::{models::IdentityGroupModel, schema::rbac_identity_groups},
+    Identity, RoleBasedAuthorizationStoreError,
+};
+
+use super::RoleBasedAuthorizationStoreOperations;
+
+pub trait RoleBasedAuthorizationStoreSetIdentityGroups {
+    fn set_identity_groups(
+        &self,
+        identity: &Identity,
+        groups: Vec<String>,
+    ) -> Result<(), RoleBasedAuthorizationStoreError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RoleBasedAuthorizationStoreSetIdentityGroups
+    for RoleBasedAuthorizationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn set_identity_groups(
+        &self,
+        identity: &Identity,
+        groups: Vec<String>,
+    ) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let models = identity_group_models(identity, groups);
+        self.conn.transaction::<_, _, _>(|| {
+            delete(
+                rbac_identity_groups::table
+                    .filter(rbac_identity_groups::identity.eq(identity_id(identity))),
+            )
+            .execute(self.conn)?;
+
+            if !models.is_empty() {
+                insert_into(rbac_identity_groups::table)
+                    .values(models)
+                    .execute(self.conn)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RoleBasedAuthorizationStoreSetIdentityGroups
+    for RoleBasedAuthorizationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn set_identity_groups(
+        &self,
+        identity: &Identity,
+        groups: Vec<String>,
+    ) -> Result<(), RoleBasedAuthorizationStoreError> {
+        let models = identity_group_models(identity, groups);
+        self.conn.transaction::<_, _, _>(|| {
+            delete(
+                rbac_identity_groups::table
+                    .filter(rbac_identity_groups::identity.eq(identity_id(identity))),
+            )
+            .execute(self.conn)?;
+
+            if !models.is_empty() {
+                insert_into(rbac_identity_groups::table)
+                    .values(models)
+                    .execute(self.conn)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+fn identity_id(identity: &Identity) -> &str {
+    match identity {
+        Identity::Key(ref key) => key,
+        Identity::User(ref user_id) => user_id,
+    }
+}
+
+fn identity_group_models(identity: &Identity, mut groups: Vec<String>) -> Vec<IdentityGroupModel> {
+    // Identity providers may report a group more than once, such as when the same group is in
+    // both the groups and roles claims
+    groups.sort();
+    groups.dedup();
+
+    groups
+        .into_iter()
+        .map(|group_id| IdentityGroupModel {
+            identity: identity_id(identity).to_string(),
+            group_id,
+        })
+        .collect()
+}
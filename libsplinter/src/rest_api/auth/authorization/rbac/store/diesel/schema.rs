@@ -44,3 +44,19 @@ table! {
         role_id -> Text,
     }
 }
+
+#[cfg(feature = "authorization-handler-rbac-groups")]
+table! {
+    rbac_group_mappings (group_id, role_id) {
+        group_id -> Text,
+        role_id -> Text,
+    }
+}
+
+#[cfg(feature = "authorization-handler-rbac-groups")]
+table! {
+    rbac_identity_groups (identity, group_id) {
+        identity -> Text,
+        group_id -> Text,
+    }
+}
@@ -332,6 +332,85 @@ impl AssignmentUpdateBuilder {
     }
 }
 
+/// A mapping of an identity provider's group to a set of roles.
+///
+/// Identities that are members of the group, such as OAuth users whose group claims include the
+/// group, are granted the mapped roles.
+#[cfg(feature = "authorization-handler-rbac-groups")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupMapping {
+    group_id: String,
+    roles: Vec<String>,
+}
+
+#[cfg(feature = "authorization-handler-rbac-groups")]
+impl GroupMapping {
+    /// Returns the ID of the group, as reported by the identity provider.
+    pub fn group_id(&self) -> &str {
+        &self.group_id
+    }
+
+    /// Returns the IDs of the roles granted to members of the group.
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
+    /// Decompose this group mapping into a tuple of its parts.
+    pub fn into_parts(self) -> (String, Vec<String>) {
+        (self.group_id, self.roles)
+    }
+}
+
+/// Builds a new group mapping.
+#[cfg(feature = "authorization-handler-rbac-groups")]
+#[derive(Default)]
+pub struct GroupMappingBuilder {
+    group_id: Option<String>,
+    roles: Vec<String>,
+}
+
+#[cfg(feature = "authorization-handler-rbac-groups")]
+impl GroupMappingBuilder {
+    /// Constructs a new builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the ID of the group.
+    pub fn with_group_id(mut self, group_id: String) -> Self {
+        self.group_id = Some(group_id);
+        self
+    }
+
+    /// Sets the roles granted to members of the group.
+    pub fn with_roles(mut self, roles: Vec<String>) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    /// Builds a new group mapping.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidStateError`] under the following conditions:
+    /// * no group ID was provided
+    /// * no roles were provided
+    pub fn build(self) -> Result<GroupMapping, InvalidStateError> {
+        if self.roles.is_empty() {
+            return Err(InvalidStateError::with_message(
+                "A group mapping requires at least one role".into(),
+            ));
+        }
+
+        Ok(GroupMapping {
+            group_id: self.group_id.ok_or_else(|| {
+                InvalidStateError::with_message("A group mapping requires a group ID".into())
+            })?,
+            roles: self.roles,
+        })
+    }
+}
+
 /// Defines methods for CRUD operations on Role and assignment data.
 pub trait RoleBasedAuthorizationStore: Send + Sync {
     /// Returns the role for the given ID, if one exists.
@@ -411,6 +490,63 @@ pub trait RoleBasedAuthorizationStore: Send + Sync {
         identity: &Identity,
     ) -> Result<(), RoleBasedAuthorizationStoreError>;
 
+    /// Returns the group mapping for the given group ID, if one exists.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn get_group_mapping(
+        &self,
+        group_id: &str,
+    ) -> Result<Option<GroupMapping>, RoleBasedAuthorizationStoreError>;
+
+    /// Lists all group mappings.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn list_group_mappings(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = GroupMapping>>, RoleBasedAuthorizationStoreError>;
+
+    /// Sets a group mapping, replacing the roles of an existing mapping for the same group.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConstraintViolation` error if any of the roles do not exist.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn set_group_mapping(
+        &self,
+        group_mapping: GroupMapping,
+    ) -> Result<(), RoleBasedAuthorizationStoreError>;
+
+    /// Removes a group mapping.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConstraintViolation` error if the group mapping does not exist.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn remove_group_mapping(&self, group_id: &str) -> Result<(), RoleBasedAuthorizationStoreError>;
+
+    /// Returns the roles granted through the group mappings of the groups the given Identity is a
+    /// member of.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn get_group_assigned_roles(
+        &self,
+        identity: &Identity,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Role>>, RoleBasedAuthorizationStoreError>;
+
+    /// Returns the IDs of the groups the given Identity is a member of.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn get_identity_groups(
+        &self,
+        identity: &Identity,
+    ) -> Result<Vec<String>, RoleBasedAuthorizationStoreError>;
+
+    /// Sets the IDs of the groups the given Identity is a member of, replacing any previous group
+    /// memberships. This is updated by identity providers that resolve an identity's groups, such
+    /// as from OAuth group claims.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn set_identity_groups(
+        &self,
+        identity: &Identity,
+        groups: Vec<String>,
+    ) -> Result<(), RoleBasedAuthorizationStoreError>;
+
     /// Clone into a boxed, dynamically dispatched store
     fn clone_box(&self) -> Box<dyn RoleBasedAuthorizationStore>;
 }
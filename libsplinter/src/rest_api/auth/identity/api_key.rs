@@ -0,0 +1,156 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An identity provider that looks up API keys
+
+use std::time::SystemTime;
+
+use openssl::memcmp;
+
+use crate::error::InternalError;
+use crate::rest_api::auth::api_key::{hash_secret, parse_token, ApiKeyStore};
+use crate::rest_api::auth::{AuthorizationHeader, BearerToken};
+
+use super::{Identity, IdentityProvider};
+
+/// Looks up the API key that an API key token was issued for
+///
+/// This provider only accepts `AuthorizationHeader::Bearer(BearerToken::ApiKey(token))`
+/// authorizations. The inner token must be of the form `<id>.<secret>`, and must match an API key
+/// that has not expired. The resulting identity is `Identity::Key` with the API key's ID.
+pub struct ApiKeyIdentityProvider {
+    api_key_store: Box<dyn ApiKeyStore>,
+}
+
+impl ApiKeyIdentityProvider {
+    /// Creates a new API key identity provider
+    pub fn new(api_key_store: Box<dyn ApiKeyStore>) -> Self {
+        Self { api_key_store }
+    }
+}
+
+impl IdentityProvider for ApiKeyIdentityProvider {
+    fn get_identity(
+        &self,
+        authorization: &AuthorizationHeader,
+    ) -> Result<Option<Identity>, InternalError> {
+        let token = match authorization {
+            AuthorizationHeader::Bearer(BearerToken::ApiKey(token)) => token,
+            _ => return Ok(None),
+        };
+
+        let (id, secret) = match parse_token(token) {
+            Some(parts) => parts,
+            None => return Ok(None),
+        };
+
+        let api_key = match self
+            .api_key_store
+            .get_api_key(id)
+            .map_err(|err| InternalError::from_source(Box::new(err)))?
+        {
+            Some(api_key) => api_key,
+            None => return Ok(None),
+        };
+
+        if api_key.is_expired(SystemTime::now()) {
+            return Ok(None);
+        }
+
+        let secret_hash = hash_secret(secret)?;
+        if secret_hash.len() != api_key.secret_hash().len()
+            || !memcmp::eq(secret_hash.as_bytes(), api_key.secret_hash().as_bytes())
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(Identity::Key(api_key.id().into())))
+    }
+
+    fn clone_box(&self) -> Box<dyn IdentityProvider> {
+        Box::new(ApiKeyIdentityProvider {
+            api_key_store: self.api_key_store.clone_box(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::rest_api::auth::api_key::{generate_api_key, store::MemoryApiKeyStore};
+
+    /// Verifies that the provider returns the key's identity for a valid token, and no identity
+    /// for a token with the wrong secret, an unknown key or an expired key.
+    #[test]
+    fn get_identity() {
+        let store = MemoryApiKeyStore::new();
+
+        let (api_key, token) =
+            generate_api_key("ci".into(), vec![], None).expect("Unable to generate key");
+        store
+            .add_api_key(api_key.clone())
+            .expect("Unable to add key");
+
+        let (expired_key, expired_token) = generate_api_key(
+            "expired".into(),
+            vec![],
+            Some(SystemTime::now() - Duration::from_secs(1)),
+        )
+        .expect("Unable to generate key");
+        store.add_api_key(expired_key).expect("Unable to add key");
+
+        let (_, unknown_token) =
+            generate_api_key("unknown".into(), vec![], None).expect("Unable to generate key");
+
+        let provider = ApiKeyIdentityProvider::new(Box::new(store));
+
+        assert_eq!(
+            provider
+                .get_identity(&AuthorizationHeader::Bearer(BearerToken::ApiKey(token)))
+                .expect("Unable to get identity"),
+            Some(Identity::Key(api_key.id().into()))
+        );
+
+        let wrong_secret = format!("{}.{}", api_key.id(), "wrong");
+        assert_eq!(
+            provider
+                .get_identity(&AuthorizationHeader::Bearer(BearerToken::ApiKey(
+                    wrong_secret
+                )))
+                .expect("Unable to get identity"),
+            None
+        );
+
+        assert_eq!(
+            provider
+                .get_identity(&AuthorizationHeader::Bearer(BearerToken::ApiKey(
+                    unknown_token
+                )))
+                .expect("Unable to get identity"),
+            None
+        );
+
+        assert_eq!(
+            provider
+                .get_identity(&AuthorizationHeader::Bearer(BearerToken::ApiKey(
+                    expired_token
+                )))
+                .expect("Unable to get identity"),
+            None
+        );
+    }
+}
@@ -0,0 +1,340 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An identity provider that maps verified TLS client certificates to identities
+
+use std::str::FromStr;
+
+use openssl::bn::BigNumContext;
+use openssl::ec::PointConversionForm;
+use openssl::error::ErrorStack;
+use openssl::x509::{X509Ref, X509};
+
+use crate::error::{InternalError, InvalidArgumentError};
+use crate::hex::to_hex;
+use crate::rest_api::auth::AuthorizationHeader;
+
+use super::{Identity, IdentityProvider};
+
+/// The certificate that the client presented during the TLS handshake, if any
+///
+/// This is captured once per connection and added to the extensions of every request received on
+/// that connection.
+#[derive(Clone)]
+pub(crate) struct PeerCertificate(Option<X509>);
+
+impl PeerCertificate {
+    /// Gets the peer certificate of the given TLS stream
+    pub(crate) fn from_ssl_stream<T>(stream: &tokio_openssl::SslStream<T>) -> Self {
+        Self(stream.get_ref().ssl().peer_certificate())
+    }
+
+    /// Returns the certificate, if the client presented one
+    pub(crate) fn certificate(&self) -> Option<&X509Ref> {
+        self.0.as_deref()
+    }
+}
+
+/// The part of a client certificate that is used as the client's identity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClientCertificateIdentityMapping {
+    /// The certificate's public key is mapped to `Identity::Key`. EC public keys are encoded as a
+    /// hex compressed point, which matches the encoding of Cylinder public keys; all other keys
+    /// are encoded as the hex of their DER-encoded `SubjectPublicKeyInfo`.
+    PublicKey,
+    /// The certificate's subject is mapped to `Identity::User`, formatted as comma-separated
+    /// `<short name>=<value>` entries (for example, `CN=alice,O=Acme`). Values are escaped as
+    /// described in RFC 4514, so that a value cannot be mistaken for another entry.
+    Subject,
+}
+
+impl FromStr for ClientCertificateIdentityMapping {
+    type Err = InvalidArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "public-key" => Ok(ClientCertificateIdentityMapping::PublicKey),
+            "subject" => Ok(ClientCertificateIdentityMapping::Subject),
+            _ => Err(InvalidArgumentError::new(
+                "mapping".into(),
+                format!("'{}' is not one of 'public-key' or 'subject'", s),
+            )),
+        }
+    }
+}
+
+/// Maps the certificate a client presented during the TLS handshake to an identity
+///
+/// This provider does not accept any authorization headers; it only resolves identities from
+/// client certificates, which have already been verified against the REST API's client CAs by the
+/// time they reach this provider.
+#[derive(Clone)]
+pub struct ClientCertificateIdentityProvider {
+    mapping: ClientCertificateIdentityMapping,
+}
+
+impl ClientCertificateIdentityProvider {
+    /// Creates a new client certificate identity provider that uses the given mapping
+    pub fn new(mapping: ClientCertificateIdentityMapping) -> Self {
+        Self { mapping }
+    }
+}
+
+impl IdentityProvider for ClientCertificateIdentityProvider {
+    fn get_identity(
+        &self,
+        _authorization: &AuthorizationHeader,
+    ) -> Result<Option<Identity>, InternalError> {
+        Ok(None)
+    }
+
+    fn get_identity_from_certificate(
+        &self,
+        certificate: &X509Ref,
+    ) -> Result<Option<Identity>, InternalError> {
+        match self.mapping {
+            ClientCertificateIdentityMapping::PublicKey => {
+                public_key_identity(certificate).map(Some).map_err(|err| {
+                    InternalError::from_source_with_prefix(
+                        Box::new(err),
+                        "Unable to get public key of client certificate".into(),
+                    )
+                })
+            }
+            ClientCertificateIdentityMapping::Subject => {
+                subject_identity(certificate).map_err(|err| {
+                    InternalError::from_source_with_prefix(
+                        Box::new(err),
+                        "Unable to get subject of client certificate".into(),
+                    )
+                })
+            }
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn IdentityProvider> {
+        Box::new(self.clone())
+    }
+}
+
+fn public_key_identity(certificate: &X509Ref) -> Result<Identity, ErrorStack> {
+    let public_key = certificate.public_key()?;
+    let bytes = match public_key.ec_key() {
+        Ok(ec_key) => ec_key.public_key().to_bytes(
+            ec_key.group(),
+            PointConversionForm::COMPRESSED,
+            &mut BigNumContext::new()?,
+        )?,
+        Err(_) => public_key.public_key_to_der()?,
+    };
+
+    Ok(Identity::Key(to_hex(&bytes)))
+}
+
+fn subject_identity(certificate: &X509Ref) -> Result<Option<Identity>, ErrorStack> {
+    let entries = certificate
+        .subject_name()
+        .entries()
+        .map(|entry| {
+            Ok(format!(
+                "{}={}",
+                entry.object().nid().short_name()?,
+                escape_attribute_value(&entry.data().as_utf8()?)
+            ))
+        })
+        .collect::<Result<Vec<_>, ErrorStack>>()?;
+
+    if entries.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(Identity::User(entries.join(","))))
+    }
+}
+
+/// Escapes a distinguished name attribute value as described in RFC 4514, section 2.4.
+fn escape_attribute_value(value: &str) -> String {
+    let last_index = value.chars().count().saturating_sub(1);
+    let mut escaped = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            '"' | '+' | ',' | ';' | '<' | '>' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' ' if i == 0 || i == last_index => escaped.push_str("\\ "),
+            '#' if i == 0 => escaped.push_str("\\#"),
+            '\0' => escaped.push_str("\\00"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use openssl::asn1::Asn1Time;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::{PKey, Private};
+    use openssl::rsa::Rsa;
+    use openssl::x509::X509NameBuilder;
+
+    /// Verifies that the provider does not resolve an identity from an authorization header
+    #[test]
+    fn authorization_header_ignored() {
+        let provider =
+            ClientCertificateIdentityProvider::new(ClientCertificateIdentityMapping::Subject);
+
+        assert!(matches!(
+            provider.get_identity(&"Bearer token".parse().unwrap()),
+            Ok(None)
+        ));
+    }
+
+    /// Verifies that the `PublicKey` mapping encodes an EC public key as a hex compressed point
+    #[test]
+    fn public_key_mapping_ec() {
+        let group = EcGroup::from_curve_name(Nid::SECP256K1).expect("Failed to get curve");
+        let ec_key = EcKey::generate(&group).expect("Failed to generate key");
+        let expected_key = to_hex(
+            &ec_key
+                .public_key()
+                .to_bytes(
+                    &group,
+                    PointConversionForm::COMPRESSED,
+                    &mut BigNumContext::new().unwrap(),
+                )
+                .unwrap(),
+        );
+        let cert = make_cert(PKey::from_ec_key(ec_key).unwrap(), "client");
+
+        let provider =
+            ClientCertificateIdentityProvider::new(ClientCertificateIdentityMapping::PublicKey);
+
+        assert_eq!(
+            provider
+                .get_identity_from_certificate(&cert)
+                .expect("Failed to get identity"),
+            Some(Identity::Key(expected_key))
+        );
+    }
+
+    /// Verifies that the `PublicKey` mapping encodes a non-EC public key as the hex of its DER
+    /// encoding
+    #[test]
+    fn public_key_mapping_rsa() {
+        let privkey = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let expected_key = to_hex(&privkey.public_key_to_der().unwrap());
+        let cert = make_cert(privkey, "client");
+
+        let provider =
+            ClientCertificateIdentityProvider::new(ClientCertificateIdentityMapping::PublicKey);
+
+        assert_eq!(
+            provider
+                .get_identity_from_certificate(&cert)
+                .expect("Failed to get identity"),
+            Some(Identity::Key(expected_key))
+        );
+    }
+
+    /// Verifies that the `Subject` mapping formats the certificate's subject as the user ID
+    #[test]
+    fn subject_mapping() {
+        let privkey = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let cert = make_cert(privkey, "alice");
+
+        let provider =
+            ClientCertificateIdentityProvider::new(ClientCertificateIdentityMapping::Subject);
+
+        assert_eq!(
+            provider
+                .get_identity_from_certificate(&cert)
+                .expect("Failed to get identity"),
+            Some(Identity::User("CN=alice,O=Acme".into()))
+        );
+    }
+
+    /// Verifies that the `Subject` mapping escapes special characters in the subject's values, so
+    /// that a value containing a separator cannot impersonate another subject
+    #[test]
+    fn subject_mapping_escaped() {
+        let privkey = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let cert = make_cert(privkey, "alice,O=Other");
+
+        let provider =
+            ClientCertificateIdentityProvider::new(ClientCertificateIdentityMapping::Subject);
+
+        assert_eq!(
+            provider
+                .get_identity_from_certificate(&cert)
+                .expect("Failed to get identity"),
+            Some(Identity::User("CN=alice\\,O=Other,O=Acme".into()))
+        );
+
+        assert_eq!(escape_attribute_value("#a+b "), "\\#a\\+b\\ ");
+        assert_eq!(
+            escape_attribute_value(" \"q\";<x>\\"),
+            "\\ \\\"q\\\"\\;\\<x\\>\\\\"
+        );
+    }
+
+    /// Verifies that mappings are parsed from their string representations
+    #[test]
+    fn parse_mapping() {
+        assert_eq!(
+            "public-key"
+                .parse::<ClientCertificateIdentityMapping>()
+                .unwrap(),
+            ClientCertificateIdentityMapping::PublicKey
+        );
+        assert_eq!(
+            "subject"
+                .parse::<ClientCertificateIdentityMapping>()
+                .unwrap(),
+            ClientCertificateIdentityMapping::Subject
+        );
+        assert!("issuer"
+            .parse::<ClientCertificateIdentityMapping>()
+            .is_err());
+    }
+
+    // Make a self-signed certificate for the given key with the given common name
+    fn make_cert(privkey: PKey<Private>, common_name: &str) -> X509 {
+        let mut x509_name = X509NameBuilder::new().unwrap();
+        x509_name.append_entry_by_text("CN", common_name).unwrap();
+        x509_name.append_entry_by_text("O", "Acme").unwrap();
+        let x509_name = x509_name.build();
+
+        let mut cert_builder = X509::builder().unwrap();
+        cert_builder.set_version(2).unwrap();
+        cert_builder.set_subject_name(&x509_name).unwrap();
+        cert_builder.set_issuer_name(&x509_name).unwrap();
+        cert_builder.set_pubkey(&privkey).unwrap();
+        cert_builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        cert_builder
+            .set_not_after(&Asn1Time::days_from_now(365).unwrap())
+            .unwrap();
+        cert_builder
+            .sign(&privkey, MessageDigest::sha256())
+            .unwrap();
+
+        cert_builder.build()
+    }
+}
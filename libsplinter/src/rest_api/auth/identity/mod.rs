@@ -14,14 +14,24 @@
 
 //! Tools for identifying clients and users
 
+#[cfg(feature = "api-keys")]
+pub mod api_key;
 #[cfg(feature = "biome-credentials")]
 pub mod biome;
+#[cfg(feature = "https-client-auth")]
+pub mod client_cert;
 #[cfg(feature = "cylinder-jwt")]
 pub mod cylinder;
 #[cfg(feature = "oauth")]
 pub mod oauth;
 
-use crate::error::InternalError;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "https-client-auth")]
+use openssl::x509::X509Ref;
+
+use crate::error::{InternalError, InvalidArgumentError};
 
 use super::AuthorizationHeader;
 
@@ -36,6 +46,42 @@ pub enum Identity {
     User(String),
 }
 
+/// Displays the identity as `<type>:<value>`, where the type is one of `custom`, `key` or `user`.
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Identity::Custom(custom) => write!(f, "custom:{}", custom),
+            Identity::Key(key) => write!(f, "key:{}", key),
+            Identity::User(user_id) => write!(f, "user:{}", user_id),
+        }
+    }
+}
+
+/// Parses an identity of the form `<type>:<value>`, as displayed by the `Display` implementation.
+impl FromStr for Identity {
+    type Err = InvalidArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some("custom"), Some(custom)) if !custom.is_empty() => {
+                Ok(Identity::Custom(custom.into()))
+            }
+            (Some("key"), Some(key)) if !key.is_empty() => Ok(Identity::Key(key.into())),
+            (Some("user"), Some(user_id)) if !user_id.is_empty() => {
+                Ok(Identity::User(user_id.into()))
+            }
+            _ => Err(InvalidArgumentError::new(
+                "identity".into(),
+                format!(
+                    "'{}' is not of the form custom:<value>, key:<value> or user:<value>",
+                    s
+                ),
+            )),
+        }
+    }
+}
+
 /// A service that fetches identities from a backing provider
 pub trait IdentityProvider: Send + Sync {
     /// Attempts to get the identity that corresponds to the given authorization header. This method
@@ -46,6 +92,17 @@ pub trait IdentityProvider: Send + Sync {
         authorization: &AuthorizationHeader,
     ) -> Result<Option<Identity>, InternalError>;
 
+    /// Attempts to get the identity that corresponds to the certificate the client presented
+    /// during the TLS handshake. The certificate has already been verified against the REST API's
+    /// client CAs. By default, no identity is resolved from client certificates.
+    #[cfg(feature = "https-client-auth")]
+    fn get_identity_from_certificate(
+        &self,
+        _certificate: &X509Ref,
+    ) -> Result<Option<Identity>, InternalError> {
+        Ok(None)
+    }
+
     /// Clone implementation for `IdentityProvider`. The implementation of the `Clone` trait for
     /// `Box<dyn IdentityProvider>` calls this method.
     fn clone_box(&self) -> Box<dyn IdentityProvider>;
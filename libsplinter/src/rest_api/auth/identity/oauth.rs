@@ -14,11 +14,21 @@
 
 //! An identity provider backed by an OAuth server
 
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use std::collections::HashMap;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use std::time::Instant;
 
 use crate::biome::OAuthUserSessionStore;
 use crate::error::InternalError;
 use crate::oauth::OAuthClient;
+#[cfg(feature = "authorization-handler-rbac-groups")]
+use crate::rest_api::auth::authorization::rbac::store::{
+    Identity as RBACIdentity, RoleBasedAuthorizationStore,
+};
 use crate::rest_api::auth::{AuthorizationHeader, BearerToken};
 
 use super::{Identity, IdentityProvider};
@@ -41,6 +51,12 @@ const DEFAULT_REAUTHENTICATION_INTERVAL: Duration = Duration::from_secs(3600); /
 /// This identity provider will also use a session's refresh token (if it has one) to get a new
 /// OAuth access token for the session as needed.
 ///
+/// If a [RoleBasedAuthorizationStore] is provided, the groups that the OAuth server reports the
+/// user to be a member of are saved as the user's groups in the store, so that roles mapped to
+/// those groups are granted to the user. Groups are updated the first time a session is used after
+/// startup and whenever the session is re-authenticated. Sessions are only remembered as updated
+/// for one re-authentication interval, so sessions that are no longer used are forgotten.
+///
 /// This provider only accepts `AuthorizationHeader::Bearer(BearerToken::OAuth2(token))`
 /// authorizations, and the inner token must be a valid Splinter access token for an OAuth user.
 #[derive(Clone)]
@@ -48,6 +64,12 @@ pub struct OAuthUserIdentityProvider {
     oauth_client: OAuthClient,
     oauth_user_session_store: Box<dyn OAuthUserSessionStore>,
     reauthentication_interval: Duration,
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    role_based_authorization_store: Option<Box<dyn RoleBasedAuthorizationStore>>,
+    /// The Splinter access tokens of the sessions whose groups have been updated since startup,
+    /// with the time they were last updated
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    group_synced_sessions: Arc<Mutex<HashMap<String, Instant>>>,
 }
 
 impl OAuthUserIdentityProvider {
@@ -71,8 +93,90 @@ impl OAuthUserIdentityProvider {
             oauth_user_session_store,
             reauthentication_interval: reauthentication_interval
                 .unwrap_or(DEFAULT_REAUTHENTICATION_INTERVAL),
+            #[cfg(feature = "authorization-handler-rbac-groups")]
+            role_based_authorization_store: None,
+            #[cfg(feature = "authorization-handler-rbac-groups")]
+            group_synced_sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sets the store that users' OAuth groups will be saved to, so that the roles mapped to those
+    /// groups are granted to the users.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    pub fn with_role_based_authorization_store(
+        mut self,
+        role_based_authorization_store: Box<dyn RoleBasedAuthorizationStore>,
+    ) -> Self {
+        self.role_based_authorization_store = Some(role_based_authorization_store);
+        self
+    }
+
+    /// Saves the user's OAuth groups to the role-based authorization store, if one was provided.
+    /// Unless `force` is set, the groups are only updated if they have not been updated for the
+    /// session within the re-authentication interval.
+    ///
+    /// Failing to update the groups does not invalidate the session, so errors are logged rather
+    /// than returned.
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    fn sync_groups(&self, token: &str, user_id: &str, oauth_access_token: &str, force: bool) {
+        let store = match &self.role_based_authorization_store {
+            Some(store) => store,
+            None => return,
+        };
+
+        if !force
+            && self
+                .group_synced_sessions
+                .lock()
+                .map(|synced_sessions| {
+                    synced_sessions
+                        .get(token)
+                        .map(|synced| synced.elapsed() < self.reauthentication_interval)
+                        .unwrap_or(false)
+                })
+                .unwrap_or(false)
+        {
+            return;
+        }
+
+        let result = self
+            .oauth_client
+            .get_groups(oauth_access_token)
+            .and_then(|groups| {
+                store
+                    .set_identity_groups(&RBACIdentity::User(user_id.into()), groups)
+                    .map_err(|err| InternalError::from_source(Box::new(err)))
+            });
+
+        match result {
+            Ok(()) => {
+                if let Ok(mut synced_sessions) = self.group_synced_sessions.lock() {
+                    // Sessions that have not been used within the interval would be updated
+                    // again anyway, so they are forgotten
+                    let reauthentication_interval = self.reauthentication_interval;
+                    synced_sessions
+                        .retain(|_, synced| synced.elapsed() < reauthentication_interval);
+                    synced_sessions.insert(token.into(), Instant::now());
+                }
+            }
+            Err(err) => warn!(
+                "Failed to update OAuth groups for user {}: {}",
+                user_id, err
+            ),
         }
     }
+
+    /// Removes a session from the session store, along with its group update record.
+    fn remove_session(&self, token: &str) -> Result<(), InternalError> {
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        if let Ok(mut synced_sessions) = self.group_synced_sessions.lock() {
+            synced_sessions.remove(token);
+        }
+
+        self.oauth_user_session_store
+            .remove_session(token)
+            .map_err(|err| InternalError::from_source(err.into()))
+    }
 }
 
 impl IdentityProvider for OAuthUserIdentityProvider {
@@ -103,6 +207,8 @@ impl IdentityProvider for OAuthUserIdentityProvider {
         if time_since_authenticated >= self.reauthentication_interval {
             match self.oauth_client.get_subject(session.oauth_access_token()) {
                 Ok(Some(_)) => {
+                    #[cfg(feature = "authorization-handler-rbac-groups")]
+                    self.sync_groups(token, &user_id, session.oauth_access_token(), true);
                     let updated_session = session.into_update_builder().build();
                     self.oauth_user_session_store
                         .update_session(updated_session)
@@ -118,14 +224,19 @@ impl IdentityProvider for OAuthUserIdentityProvider {
                             // access token
                             match self
                                 .oauth_client
-                                .exchange_refresh_token(refresh_token.to_string())
+                                .exchange_refresh_token_with_rotation(refresh_token.to_string())
                             {
-                                Ok(access_token) => {
-                                    // Update the access token in the store
-                                    let updated_session = session
+                                Ok((access_token, rotated_refresh_token)) => {
+                                    // Update the access token in the store, along with the refresh
+                                    // token if the provider replaced it
+                                    let mut update_builder = session
                                         .into_update_builder()
-                                        .with_oauth_access_token(access_token.clone())
-                                        .build();
+                                        .with_oauth_access_token(access_token.clone());
+                                    if let Some(rotated_refresh_token) = rotated_refresh_token {
+                                        update_builder = update_builder
+                                            .with_oauth_refresh_token(Some(rotated_refresh_token));
+                                    }
+                                    let updated_session = update_builder.build();
                                     self.oauth_user_session_store
                                         .update_session(updated_session)
                                         .map_err(|err| InternalError::from_source(err.into()))?;
@@ -133,7 +244,11 @@ impl IdentityProvider for OAuthUserIdentityProvider {
                                     // get Ok(None) or Err(_)), something's wrong that can't be
                                     // handled here.
                                     match self.oauth_client.get_subject(&access_token)? {
-                                        Some(_) => Ok(Some(Identity::User(user_id))),
+                                        Some(_) => {
+                                            #[cfg(feature = "authorization-handler-rbac-groups")]
+                                            self.sync_groups(token, &user_id, &access_token, true);
+                                            Ok(Some(Identity::User(user_id)))
+                                        }
                                         None => Err(InternalError::with_message(
                                             "failed to authenticate user with new access token"
                                                 .into(),
@@ -144,9 +259,7 @@ impl IdentityProvider for OAuthUserIdentityProvider {
                                     // The refresh token didn't work; delete the session since it's
                                     // no longer valid
                                     debug!("Failed to exchange refresh token: {}", err);
-                                    self.oauth_user_session_store
-                                        .remove_session(token)
-                                        .map_err(|err| InternalError::from_source(err.into()))?;
+                                    self.remove_session(token)?;
                                     Ok(None)
                                 }
                             }
@@ -154,21 +267,19 @@ impl IdentityProvider for OAuthUserIdentityProvider {
                         None => {
                             // The access token didn't work and there's no refresh token for this
                             // session; delete the session since it's no longer valid.
-                            self.oauth_user_session_store
-                                .remove_session(token)
-                                .map_err(|err| InternalError::from_source(err.into()))?;
+                            self.remove_session(token)?;
                             Ok(None)
                         }
                     }
                 }
                 Err(err) => {
-                    self.oauth_user_session_store
-                        .remove_session(token)
-                        .map_err(|err| InternalError::from_source(err.into()))?;
+                    self.remove_session(token)?;
                     Err(err)
                 }
             }
         } else {
+            #[cfg(feature = "authorization-handler-rbac-groups")]
+            self.sync_groups(token, &user_id, session.oauth_access_token(), false);
             Ok(Some(Identity::User(user_id)))
         }
     }
@@ -563,6 +674,110 @@ mod tests {
         shutdown_handle.shutdown();
     }
 
+    /// Verifies that the `OAuthUserIdentityProvider` saves a user's OAuth groups to the
+    /// role-based authorization store the first time the session is used.
+    ///
+    /// 1. Create a new `OAuthUserSessionStore` and add a session to it
+    /// 2. Create a new `OAuthUserIdentityProvider` with the session store, an OAuth client that
+    ///    always reports the user to be a member of a group, a role-based authorization store, and
+    ///    the default re-authentication interval
+    /// 3. Call the `get_identity` method with the session's access token
+    /// 4. Verify that the group has been saved as the user's group in the role-based
+    ///    authorization store
+    #[cfg(all(feature = "authorization-handler-rbac-groups", feature = "sqlite"))]
+    #[test]
+    fn get_identity_syncs_groups() {
+        let session_store = Box::new(MemoryOAuthUserSessionStore::new());
+
+        let splinter_access_token = "splinter_access_token";
+        let session = InsertableOAuthUserSessionBuilder::new()
+            .with_splinter_access_token(splinter_access_token.into())
+            .with_subject("subject".into())
+            .with_oauth_access_token("oauth_access_token".into())
+            .build()
+            .expect("Failed to build session");
+        session_store
+            .add_session(session)
+            .expect("Failed to add session");
+
+        let role_based_authorization_store = create_role_based_authorization_store();
+
+        let identity_provider =
+            OAuthUserIdentityProvider::new(always_some_client(), session_store, None)
+                .with_role_based_authorization_store(role_based_authorization_store.clone_box());
+
+        let authorization_header =
+            AuthorizationHeader::Bearer(BearerToken::OAuth2(splinter_access_token.into()));
+        let user_id = match identity_provider
+            .get_identity(&authorization_header)
+            .expect("Failed to get identity")
+            .expect("Identity not found")
+        {
+            Identity::User(user_id) => user_id,
+            identity => panic!("Unexpected identity: {:?}", identity),
+        };
+
+        assert_eq!(
+            role_based_authorization_store
+                .get_identity_groups(&RBACIdentity::User(user_id))
+                .expect("Failed to get identity groups"),
+            vec!["group".to_string()]
+        );
+    }
+
+    /// Verifies that the `OAuthUserIdentityProvider` forgets the group updates of sessions that
+    /// have not been used within the re-authentication interval.
+    ///
+    /// 1. Create a new `OAuthUserIdentityProvider` with a session store that contains a session
+    ///    and a role-based authorization store
+    /// 2. Record a group update for another session that is older than the re-authentication
+    ///    interval
+    /// 3. Call the `get_identity` method with the session's access token
+    /// 4. Verify that only the used session's group update is remembered
+    #[cfg(all(feature = "authorization-handler-rbac-groups", feature = "sqlite"))]
+    #[test]
+    fn get_identity_forgets_stale_group_syncs() {
+        let session_store = Box::new(MemoryOAuthUserSessionStore::new());
+
+        let splinter_access_token = "splinter_access_token";
+        let session = InsertableOAuthUserSessionBuilder::new()
+            .with_splinter_access_token(splinter_access_token.into())
+            .with_subject("subject".into())
+            .with_oauth_access_token("oauth_access_token".into())
+            .build()
+            .expect("Failed to build session");
+        session_store
+            .add_session(session)
+            .expect("Failed to add session");
+
+        let identity_provider =
+            OAuthUserIdentityProvider::new(always_some_client(), session_store, None)
+                .with_role_based_authorization_store(create_role_based_authorization_store());
+
+        let stale = Instant::now()
+            .checked_sub(DEFAULT_REAUTHENTICATION_INTERVAL * 2)
+            .expect("Unable to create stale instant");
+        identity_provider
+            .group_synced_sessions
+            .lock()
+            .expect("Failed to lock synced sessions")
+            .insert("stale_access_token".into(), stale);
+
+        let authorization_header =
+            AuthorizationHeader::Bearer(BearerToken::OAuth2(splinter_access_token.into()));
+        identity_provider
+            .get_identity(&authorization_header)
+            .expect("Failed to get identity")
+            .expect("Identity not found");
+
+        let synced_sessions = identity_provider
+            .group_synced_sessions
+            .lock()
+            .expect("Failed to lock synced sessions");
+        assert_eq!(synced_sessions.len(), 1);
+        assert!(synced_sessions.contains_key(splinter_access_token));
+    }
+
     /// Returns a mock OAuth client that wraps an `AlwaysSomeSubjectProvider`
     fn always_some_client() -> OAuthClient {
         let client = OAuthClientBuilder::new()
@@ -590,6 +805,10 @@ mod tests {
             Ok(Some("subject".into()))
         }
 
+        fn get_groups(&self, _access_token: &str) -> Result<Vec<String>, InternalError> {
+            Ok(vec!["group".into()])
+        }
+
         fn clone_box(&self) -> Box<dyn SubjectProvider> {
             Box::new(self.clone())
         }
@@ -823,4 +1042,26 @@ mod tests {
             self.1.join().expect("OAuth server thread failed");
         }
     }
+
+    /// Creates a role-based authorization store backed by an in-memory SQLite database
+    #[cfg(all(feature = "authorization-handler-rbac-groups", feature = "sqlite"))]
+    fn create_role_based_authorization_store() -> Box<dyn RoleBasedAuthorizationStore> {
+        use diesel::{
+            r2d2::{ConnectionManager, Pool},
+            sqlite::SqliteConnection,
+        };
+
+        use crate::migrations::run_sqlite_migrations;
+        use crate::rest_api::auth::authorization::rbac::store::DieselRoleBasedAuthorizationStore;
+
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(ConnectionManager::<SqliteConnection>::new(":memory:"))
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        Box::new(DieselRoleBasedAuthorizationStore::new(pool))
+    }
 }
@@ -16,12 +16,19 @@
 
 #[cfg(feature = "rest-api-actix")]
 pub(crate) mod actix;
+#[cfg(feature = "api-keys")]
+pub mod api_key;
 #[cfg(feature = "authorization")]
 pub mod authorization;
 pub mod identity;
+#[cfg(feature = "rest-api-rate-limit")]
+pub mod rate_limit;
 
 use std::str::FromStr;
 
+#[cfg(feature = "https-client-auth")]
+use openssl::x509::X509Ref;
+
 use crate::error::InvalidArgumentError;
 
 #[cfg(feature = "authorization")]
@@ -42,6 +49,10 @@ enum AuthorizationResult {
         feature = "oauth"
     ))]
     NoAuthorizationNecessary,
+    /// The client was authenticated to the given identity, but does not have the required
+    /// permission
+    #[cfg(feature = "authorization")]
+    Forbidden(Identity),
     /// The authorization header is empty or invalid
     Unauthorized,
     /// The request endpoint is not defined
@@ -57,6 +68,8 @@ enum AuthorizationResult {
 /// * `method` - The HTTP method used for the request
 /// * `endpoint` - The endpoint that is being requested. Example: "/endpoint/path"
 /// * `auth_header` - The value of the Authorization HTTP header for the request
+/// * `client_certificate` - The verified certificate the client presented during the TLS
+///   handshake, if any; only used when the authorization header does not resolve to an identity
 /// * `identity_providers` - The identity providers that will be used to check the client's identity
 /// * `authorization_handlers` - The authorization handlers that will be used to check the client's
 ///   permissions
@@ -75,6 +88,7 @@ fn authorize(
     )))]
     _endpoint: &str,
     auth_header: Option<&str>,
+    #[cfg(feature = "https-client-auth")] client_certificate: Option<&X509Ref>,
     #[cfg(feature = "authorization")] permission_map: &PermissionMap,
     identity_providers: &[Box<dyn IdentityProvider>],
     #[cfg(feature = "authorization")] authorization_handlers: &[Box<dyn AuthorizationHandler>],
@@ -89,12 +103,22 @@ fn authorize(
 
         match *permission {
             Permission::AllowUnauthenticated => AuthorizationResult::NoAuthorizationNecessary,
-            Permission::AllowAuthenticated => match get_identity(auth_header, identity_providers) {
+            Permission::AllowAuthenticated => match get_identity(
+                auth_header,
+                #[cfg(feature = "https-client-auth")]
+                client_certificate,
+                identity_providers,
+            ) {
                 Some(identity) => AuthorizationResult::Authorized(identity),
                 None => AuthorizationResult::Unauthorized,
             },
             Permission::Check { permission_id, .. } => {
-                match get_identity(auth_header, identity_providers) {
+                match get_identity(
+                    auth_header,
+                    #[cfg(feature = "https-client-auth")]
+                    client_certificate,
+                    identity_providers,
+                ) {
                     Some(identity) => {
                         for handler in authorization_handlers {
                             match handler.has_permission(&identity, permission_id) {
@@ -102,14 +126,14 @@ fn authorize(
                                     return AuthorizationResult::Authorized(identity)
                                 }
                                 Ok(AuthorizationHandlerResult::Deny) => {
-                                    return AuthorizationResult::Unauthorized
+                                    return AuthorizationResult::Forbidden(identity)
                                 }
                                 Ok(AuthorizationHandlerResult::Continue) => {}
                                 Err(err) => error!("{}", err),
                             }
                         }
                         // No handler allowed the request, so deny by default
-                        AuthorizationResult::Unauthorized
+                        AuthorizationResult::Forbidden(identity)
                     }
                     None => AuthorizationResult::Unauthorized,
                 }
@@ -138,7 +162,12 @@ fn authorize(
             }
         }
 
-        match get_identity(auth_header, identity_providers) {
+        match get_identity(
+            auth_header,
+            #[cfg(feature = "https-client-auth")]
+            client_certificate,
+            identity_providers,
+        ) {
             Some(identity) => AuthorizationResult::Authorized(identity),
             None => AuthorizationResult::Unauthorized,
         }
@@ -147,15 +176,37 @@ fn authorize(
 
 fn get_identity(
     auth_header: Option<&str>,
+    #[cfg(feature = "https-client-auth")] client_certificate: Option<&X509Ref>,
     identity_providers: &[Box<dyn IdentityProvider>],
 ) -> Option<Identity> {
-    let authorization = auth_header?.parse().ok()?;
-    identity_providers.iter().find_map(|provider| {
-        provider.get_identity(&authorization).unwrap_or_else(|err| {
-            error!("{}", err);
-            None
-        })
-    })
+    let header_identity = auth_header
+        .and_then(|auth_header| auth_header.parse::<AuthorizationHeader>().ok())
+        .and_then(|authorization| {
+            identity_providers.iter().find_map(|provider| {
+                provider.get_identity(&authorization).unwrap_or_else(|err| {
+                    error!("{}", err);
+                    None
+                })
+            })
+        });
+    if header_identity.is_some() {
+        return header_identity;
+    }
+
+    // Fall back to the client's certificate if the header doesn't resolve to an identity
+    #[cfg(feature = "https-client-auth")]
+    if let Some(certificate) = client_certificate {
+        return identity_providers.iter().find_map(|provider| {
+            provider
+                .get_identity_from_certificate(certificate)
+                .unwrap_or_else(|err| {
+                    error!("{}", err);
+                    None
+                })
+        });
+    }
+
+    None
 }
 
 /// A parsed authorization header
@@ -188,6 +239,9 @@ impl FromStr for AuthorizationHeader {
 /// A bearer token of a specific type
 #[derive(PartialEq)]
 pub enum BearerToken {
+    #[cfg(feature = "api-keys")]
+    /// Contains an API key token
+    ApiKey(String),
     #[cfg(feature = "biome-credentials")]
     /// Contains a Biome JWT
     Biome(String),
@@ -211,10 +265,12 @@ impl FromStr for BearerToken {
     fn from_str(str: &str) -> Result<Self, Self::Err> {
         let mut parts = str.splitn(2, ':');
         match (parts.next(), parts.next()) {
-            // Allowing lint in case none of `biome-credentials`, `cylinder-jwt`, or `oauth` are
-            // used
+            // Allowing lint in case none of `api-keys`, `biome-credentials`, `cylinder-jwt`, or
+            // `oauth` are used
             #[allow(unused_variables, clippy::match_single_binding)]
             (Some(token_type), Some(token)) => match token_type {
+                #[cfg(feature = "api-keys")]
+                "ApiKey" => Ok(BearerToken::ApiKey(token.to_string())),
                 #[cfg(feature = "biome-credentials")]
                 "Biome" => Ok(BearerToken::Biome(token.to_string())),
                 #[cfg(feature = "cylinder-jwt")]
@@ -257,6 +313,12 @@ mod tests {
     /// Verfifies that the `BearerToken` enum is correctly parsed from strings
     #[test]
     fn parse_bearer_token() {
+        #[cfg(feature = "api-keys")]
+        assert!(matches!(
+            "ApiKey:test".parse(),
+            Ok(BearerToken::ApiKey(token)) if token == "test"
+        ));
+
         #[cfg(feature = "biome-credentials")]
         assert!(matches!(
             "Biome:test".parse(),
@@ -308,6 +370,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 #[cfg(feature = "authorization")]
                 &permission_map,
                 &[],
@@ -339,6 +403,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 #[cfg(feature = "authorization")]
                 &permission_map,
                 &[Box::new(AlwaysRejectIdentityProvider)],
@@ -371,6 +437,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 None,
+                #[cfg(feature = "https-client-auth")]
+                None,
                 #[cfg(feature = "authorization")]
                 &permission_map,
                 &[Box::new(AlwaysAcceptIdentityProvider)],
@@ -392,6 +460,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 None,
+                #[cfg(feature = "https-client-auth")]
+                None,
                 &Default::default(),
                 &[Box::new(AlwaysAcceptIdentityProvider)],
                 &[Box::new(AlwaysAllowAuthorizationHandler)],
@@ -421,6 +491,8 @@ mod tests {
                     &Method::Get,
                     "/test/endpoint",
                     None,
+                    #[cfg(feature = "https-client-auth")]
+                    None,
                     &permission_map,
                     &[Box::new(AlwaysRejectIdentityProvider)],
                     &[Box::new(AlwaysAllowAuthorizationHandler)],
@@ -434,6 +506,8 @@ mod tests {
                     &Method::Get,
                     "/test/endpoint",
                     Some("auth"),
+                    #[cfg(feature = "https-client-auth")]
+                    None,
                     &permission_map,
                     &[Box::new(AlwaysRejectIdentityProvider)],
                     &[Box::new(AlwaysAllowAuthorizationHandler)],
@@ -451,6 +525,8 @@ mod tests {
                     authorize(
                         "/biome/register",
                         None,
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -459,6 +535,8 @@ mod tests {
                     authorize(
                         "/biome/login",
                         None,
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -467,6 +545,8 @@ mod tests {
                     authorize(
                         "/biome/token",
                         None,
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -478,6 +558,8 @@ mod tests {
                     authorize(
                         "/oauth/login",
                         None,
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -486,6 +568,8 @@ mod tests {
                     authorize(
                         "/oauth/callback",
                         None,
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -499,6 +583,8 @@ mod tests {
                     authorize(
                         "/biome/register",
                         Some("auth"),
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -507,6 +593,8 @@ mod tests {
                     authorize(
                         "/biome/login",
                         Some("auth"),
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -515,6 +603,8 @@ mod tests {
                     authorize(
                         "/biome/token",
                         Some("auth"),
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -526,6 +616,8 @@ mod tests {
                     authorize(
                         "/oauth/login",
                         Some("auth"),
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -534,6 +626,8 @@ mod tests {
                     authorize(
                         "/oauth/callback",
                         Some("auth"),
+                        #[cfg(feature = "https-client-auth")]
+                        None,
                         &[Box::new(AlwaysRejectIdentityProvider)]
                     ),
                     AuthorizationResult::NoAuthorizationNecessary
@@ -571,6 +665,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 #[cfg(feature = "authorization")]
                 &permission_map,
                 &[Box::new(AlwaysAcceptIdentityProvider)],
@@ -610,6 +706,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 #[cfg(feature = "authorization")]
                 &permission_map,
                 &[
@@ -653,6 +751,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 #[cfg(feature = "authorization")]
                 &permission_map,
                 &[
@@ -666,7 +766,7 @@ mod tests {
         ));
     }
 
-    /// Verifies that the `authorize` function returns `AuthorizationResult::Unauthorized` when no
+    /// Verifies that the `authorize` function returns `AuthorizationResult::Forbidden` when no
     /// authorization handlers are specified, since this function should deny by default.
     #[cfg(feature = "authorization")]
     #[test]
@@ -690,15 +790,17 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 &permission_map,
                 &[Box::new(AlwaysAcceptIdentityProvider)],
                 &[],
             ),
-            AuthorizationResult::Unauthorized
+            AuthorizationResult::Forbidden(_)
         ));
     }
 
-    /// Verifies that the `authorize` function returns `AuthorizationResult::Unauthorized` when no
+    /// Verifies that the `authorize` function returns `AuthorizationResult::Forbidden` when no
     /// authorization handlers returns `Allow` or `Deny`. since this function should deny by
     /// default (must get an explicit `Allow` from an authorization handler).
     #[cfg(feature = "authorization")]
@@ -723,15 +825,17 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 &permission_map,
                 &[Box::new(AlwaysAcceptIdentityProvider)],
                 &[Box::new(AlwaysContinueAuthorizationHandler)],
             ),
-            AuthorizationResult::Unauthorized
+            AuthorizationResult::Forbidden(_)
         ));
     }
 
-    /// Verifies that the `authorize` function returns `AuthorizationResult::Unauthorized` when an
+    /// Verifies that the `authorize` function returns `AuthorizationResult::Forbidden` when an
     /// authorization handler returns `Deny`, even if an auth handler after it returns `Allow`.
     #[cfg(feature = "authorization")]
     #[test]
@@ -755,6 +859,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 &permission_map,
                 &[Box::new(AlwaysAcceptIdentityProvider)],
                 &[
@@ -762,7 +868,7 @@ mod tests {
                     Box::new(AlwaysAllowAuthorizationHandler),
                 ],
             ),
-            AuthorizationResult::Unauthorized
+            AuthorizationResult::Forbidden(_)
         ));
     }
 
@@ -797,6 +903,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 &permission_map,
                 &[Box::new(AlwaysAcceptIdentityProvider)],
                 &[
@@ -839,6 +947,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 &permission_map,
                 &[Box::new(AlwaysAcceptIdentityProvider)],
                 &[
@@ -881,6 +991,8 @@ mod tests {
                 &Method::Get,
                 "/test/endpoint",
                 Some("auth"),
+                #[cfg(feature = "https-client-auth")]
+                None,
                 &permission_map,
                 &[Box::new(AlwaysAcceptIdentityProvider)],
                 &[
@@ -892,6 +1004,89 @@ mod tests {
         ));
     }
 
+    /// Verifies that `authorize` falls back to the client's certificate when the authorization
+    /// header is missing or does not resolve to an identity, and that an identity resolved from the
+    /// header takes precedence over the certificate.
+    #[cfg(feature = "https-client-auth")]
+    #[test]
+    fn authorize_client_certificate() {
+        use openssl::hash::MessageDigest;
+        use openssl::pkey::PKey;
+        use openssl::rsa::Rsa;
+        use openssl::x509::X509;
+
+        let privkey = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut cert_builder = X509::builder().unwrap();
+        cert_builder.set_pubkey(&privkey).unwrap();
+        cert_builder
+            .sign(&privkey, MessageDigest::sha256())
+            .unwrap();
+        let cert = cert_builder.build();
+
+        #[cfg(feature = "authorization")]
+        let permission_map = {
+            let mut map = PermissionMap::new();
+            map.add_permission(
+                Method::Get,
+                "/test/endpoint",
+                Permission::AllowAuthenticated,
+            );
+            map
+        };
+
+        assert!(matches!(
+            authorize(
+                #[cfg(feature = "authorization")]
+                &Method::Get,
+                "/test/endpoint",
+                None,
+                Some(&cert),
+                #[cfg(feature = "authorization")]
+                &permission_map,
+                &[Box::new(CertificateIdentityProvider)],
+                #[cfg(feature = "authorization")]
+                &[],
+            ),
+            AuthorizationResult::Authorized(Identity::Custom(identity))
+                if identity == "certificate"
+        ));
+
+        assert!(matches!(
+            authorize(
+                #[cfg(feature = "authorization")]
+                &Method::Get,
+                "/test/endpoint",
+                Some("auth"),
+                Some(&cert),
+                #[cfg(feature = "authorization")]
+                &permission_map,
+                &[
+                    Box::new(AlwaysAcceptIdentityProvider),
+                    Box::new(CertificateIdentityProvider)
+                ],
+                #[cfg(feature = "authorization")]
+                &[],
+            ),
+            AuthorizationResult::Authorized(Identity::Custom(identity)) if identity == "identity"
+        ));
+
+        assert!(matches!(
+            authorize(
+                #[cfg(feature = "authorization")]
+                &Method::Get,
+                "/test/endpoint",
+                None,
+                None,
+                #[cfg(feature = "authorization")]
+                &permission_map,
+                &[Box::new(CertificateIdentityProvider)],
+                #[cfg(feature = "authorization")]
+                &[],
+            ),
+            AuthorizationResult::Unauthorized
+        ));
+    }
+
     /// An identity provider that always returns `Ok(Some(_))`
     #[derive(Clone)]
     struct AlwaysAcceptIdentityProvider;
@@ -909,6 +1104,32 @@ mod tests {
         }
     }
 
+    /// An identity provider that only resolves client certificates, always to the same identity
+    #[cfg(feature = "https-client-auth")]
+    #[derive(Clone)]
+    struct CertificateIdentityProvider;
+
+    #[cfg(feature = "https-client-auth")]
+    impl IdentityProvider for CertificateIdentityProvider {
+        fn get_identity(
+            &self,
+            _authorization: &AuthorizationHeader,
+        ) -> Result<Option<Identity>, InternalError> {
+            Ok(None)
+        }
+
+        fn get_identity_from_certificate(
+            &self,
+            _certificate: &X509Ref,
+        ) -> Result<Option<Identity>, InternalError> {
+            Ok(Some(Identity::Custom("certificate".into())))
+        }
+
+        fn clone_box(&self) -> Box<dyn IdentityProvider> {
+            Box::new(self.clone())
+        }
+    }
+
     /// An identity provider that always returns `Ok(None)`
     #[derive(Clone)]
     struct AlwaysRejectIdentityProvider;
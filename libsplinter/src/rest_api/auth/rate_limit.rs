@@ -0,0 +1,373 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rate limiting of REST API requests
+//!
+//! A [`RateLimiter`] tracks the requests made by each client [`Identity`] to each permission and
+//! rejects requests that exceed the configured [`RateLimit`]. The limit applied to a request is
+//! the first one configured for, in order:
+//!
+//! 1. the client's identity
+//! 2. the permission the endpoint requires
+//! 3. the default limit
+//!
+//! Requests that none of these apply to are not limited.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::InvalidArgumentError;
+
+use super::identity::Identity;
+
+/// The number of tracked request buckets above which idle buckets are removed
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// The number of requests allowed within a period of time.
+///
+/// A `RateLimit` may be parsed from a string of the form `<requests>/<seconds>`; for example,
+/// `100/60` allows 100 requests per minute.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+}
+
+impl RateLimit {
+    /// Creates a new rate limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidArgumentError` if either `requests` or `period` is zero.
+    pub fn new(requests: u32, period: Duration) -> Result<Self, InvalidArgumentError> {
+        if requests == 0 {
+            return Err(InvalidArgumentError::new(
+                "requests".into(),
+                "must be greater than 0".into(),
+            ));
+        }
+        if period == Duration::from_secs(0) {
+            return Err(InvalidArgumentError::new(
+                "period".into(),
+                "must be greater than 0".into(),
+            ));
+        }
+
+        Ok(Self { requests, period })
+    }
+
+    /// Returns the number of requests allowed within the period.
+    pub fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    /// Returns the period of time in which the requests are allowed.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+}
+
+impl fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.requests, self.period.as_secs())
+    }
+}
+
+impl FromStr for RateLimit {
+    type Err = InvalidArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '/');
+        let requests = parts
+            .next()
+            .and_then(|requests| requests.trim().parse::<u32>().ok());
+        let seconds = parts
+            .next()
+            .and_then(|seconds| seconds.trim().parse::<u64>().ok());
+
+        match (requests, seconds) {
+            (Some(requests), Some(seconds)) => Self::new(requests, Duration::from_secs(seconds)),
+            _ => Err(InvalidArgumentError::new(
+                "rate_limit".into(),
+                format!("'{}' is not of the form <requests>/<seconds>", s),
+            )),
+        }
+    }
+}
+
+/// The outcome of checking a request against a [`RateLimiter`]
+#[derive(Debug, PartialEq)]
+pub enum RateLimitResult {
+    /// The request is within the rate limit
+    Allowed,
+    /// The request exceeds the rate limit; the client may retry after the given duration
+    Limited { retry_after: Duration },
+}
+
+/// Builds a [`RateLimiter`]
+#[derive(Default)]
+pub struct RateLimiterBuilder {
+    default_limit: Option<RateLimit>,
+    permission_limits: HashMap<String, RateLimit>,
+    identity_limits: HashMap<String, RateLimit>,
+}
+
+impl RateLimiterBuilder {
+    /// Creates a new `RateLimiterBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the limit applied to requests that no other limit applies to.
+    pub fn with_default_limit(mut self, limit: RateLimit) -> Self {
+        self.default_limit = Some(limit);
+        self
+    }
+
+    /// Sets the limit applied to requests to endpoints that require the given permission.
+    pub fn with_permission_limit(mut self, permission_id: String, limit: RateLimit) -> Self {
+        self.permission_limits.insert(permission_id, limit);
+        self
+    }
+
+    /// Sets the limit applied to requests made by the given identity. This takes precedence over
+    /// both permission limits and the default limit.
+    pub fn with_identity_limit(mut self, identity: &Identity, limit: RateLimit) -> Self {
+        self.identity_limits.insert(identity.to_string(), limit);
+        self
+    }
+
+    /// Builds the `RateLimiter`.
+    pub fn build(self) -> RateLimiter {
+        RateLimiter {
+            default_limit: self.default_limit,
+            permission_limits: Arc::new(self.permission_limits),
+            identity_limits: Arc::new(self.identity_limits),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+/// Limits the rate of requests made by each identity to each permission.
+///
+/// Requests are limited using a token bucket per identity and permission: the bucket holds up to
+/// the limit's number of requests and is refilled evenly over the limit's period. Clones of a
+/// `RateLimiter` share the same buckets.
+#[derive(Clone)]
+pub struct RateLimiter {
+    default_limit: Option<RateLimit>,
+    permission_limits: Arc<HashMap<String, RateLimit>>,
+    identity_limits: Arc<HashMap<String, RateLimit>>,
+    buckets: Arc<Mutex<HashMap<(String, String), Bucket>>>,
+}
+
+impl RateLimiter {
+    /// Checks whether a request made by the given identity, to an endpoint that requires the given
+    /// permission, is within its rate limit. An allowed request is counted against the limit.
+    pub fn check(&self, identity: &Identity, permission_id: Option<&str>) -> RateLimitResult {
+        self.check_at(identity, permission_id, Instant::now())
+    }
+
+    fn check_at(
+        &self,
+        identity: &Identity,
+        permission_id: Option<&str>,
+        now: Instant,
+    ) -> RateLimitResult {
+        let identity = identity.to_string();
+        let limit = match self
+            .identity_limits
+            .get(&identity)
+            .or_else(|| permission_id.and_then(|id| self.permission_limits.get(id)))
+            .or_else(|| self.default_limit.as_ref())
+        {
+            Some(limit) => *limit,
+            None => return RateLimitResult::Allowed,
+        };
+
+        let mut buckets = match self.buckets.lock() {
+            Ok(buckets) => buckets,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| !bucket.is_full(now));
+        }
+
+        buckets
+            .entry((identity, permission_id.unwrap_or_default().to_string()))
+            .or_insert_with(|| Bucket::new(limit, now))
+            .take(now)
+    }
+}
+
+/// A token bucket that tracks the requests available to a single identity and permission
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: f64::from(limit.requests),
+            updated: now,
+        }
+    }
+
+    /// The number of requests that are added back to the bucket each second
+    fn refill_rate(&self) -> f64 {
+        f64::from(self.limit.requests) / self.limit.period.as_secs_f64()
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * self.refill_rate()).min(f64::from(self.limit.requests));
+        self.updated = now;
+    }
+
+    fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * self.refill_rate() >= f64::from(self.limit.requests)
+    }
+
+    fn take(&mut self, now: Instant) -> RateLimitResult {
+        self.refill(now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            RateLimitResult::Allowed
+        } else {
+            let wait = (1.0 - self.tokens) / self.refill_rate();
+            RateLimitResult::Limited {
+                retry_after: Duration::from_secs_f64(wait),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that rate limits are parsed from strings of the form `<requests>/<seconds>`, and
+    /// that invalid strings are rejected.
+    #[test]
+    fn parse_rate_limit() {
+        let limit: RateLimit = "100/60".parse().expect("Failed to parse limit");
+        assert_eq!(limit.requests(), 100);
+        assert_eq!(limit.period(), Duration::from_secs(60));
+        assert_eq!(limit.to_string(), "100/60");
+
+        assert!("100".parse::<RateLimit>().is_err());
+        assert!("0/60".parse::<RateLimit>().is_err());
+        assert!("100/0".parse::<RateLimit>().is_err());
+        assert!("a/b".parse::<RateLimit>().is_err());
+    }
+
+    /// Verifies that the rate limiter allows requests up to the limit, rejects further requests
+    /// with the time until a request is available, and allows requests again once the bucket has
+    /// been refilled.
+    #[test]
+    fn limit_requests() {
+        let limiter = RateLimiterBuilder::new()
+            .with_default_limit(RateLimit::new(2, Duration::from_secs(10)).unwrap())
+            .build();
+        let identity = Identity::Key("key".into());
+        let start = Instant::now();
+
+        assert_eq!(
+            limiter.check_at(&identity, Some("test.read"), start),
+            RateLimitResult::Allowed
+        );
+        assert_eq!(
+            limiter.check_at(&identity, Some("test.read"), start),
+            RateLimitResult::Allowed
+        );
+        assert_eq!(
+            limiter.check_at(&identity, Some("test.read"), start),
+            RateLimitResult::Limited {
+                retry_after: Duration::from_secs(5)
+            }
+        );
+
+        // Requests are counted separately for each identity and permission
+        assert_eq!(
+            limiter.check_at(&identity, Some("test.write"), start),
+            RateLimitResult::Allowed
+        );
+        assert_eq!(
+            limiter.check_at(&Identity::Key("other".into()), Some("test.read"), start),
+            RateLimitResult::Allowed
+        );
+
+        assert_eq!(
+            limiter.check_at(&identity, Some("test.read"), start + Duration::from_secs(5)),
+            RateLimitResult::Allowed
+        );
+    }
+
+    /// Verifies that identity limits take precedence over permission limits, which take
+    /// precedence over the default limit, and that requests are not limited when no limit
+    /// applies.
+    #[test]
+    fn limit_precedence() {
+        let limiter = RateLimiterBuilder::new()
+            .with_permission_limit(
+                "test.write".into(),
+                RateLimit::new(1, Duration::from_secs(60)).unwrap(),
+            )
+            .with_identity_limit(
+                &Identity::User("admin".into()),
+                RateLimit::new(3, Duration::from_secs(60)).unwrap(),
+            )
+            .build();
+        let now = Instant::now();
+
+        let user = Identity::User("user".into());
+        assert_eq!(
+            limiter.check_at(&user, Some("test.write"), now),
+            RateLimitResult::Allowed
+        );
+        assert!(matches!(
+            limiter.check_at(&user, Some("test.write"), now),
+            RateLimitResult::Limited { .. }
+        ));
+
+        let admin = Identity::User("admin".into());
+        for _ in 0..3 {
+            assert_eq!(
+                limiter.check_at(&admin, Some("test.write"), now),
+                RateLimitResult::Allowed
+            );
+        }
+        assert!(matches!(
+            limiter.check_at(&admin, Some("test.write"), now),
+            RateLimitResult::Limited { .. }
+        ));
+
+        for _ in 0..10 {
+            assert_eq!(
+                limiter.check_at(&user, Some("test.read"), now),
+                RateLimitResult::Allowed
+            );
+        }
+    }
+}
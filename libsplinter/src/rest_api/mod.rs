@@ -122,6 +122,10 @@ pub enum BindConfig {
         bind: String,
         cert_path: String,
         key_path: String,
+        /// Path to the CA certificate(s) used to verify client certificates. When set, clients
+        /// may present a certificate signed by one of these CAs during the TLS handshake.
+        #[cfg(feature = "https-client-auth")]
+        client_ca_path: Option<String>,
     },
     /// A insecure HTTP binding.
     Http(String),
@@ -148,7 +152,14 @@ pub enum OAuthConfig {
         /// The redirect URL that is configured for the Azure OAuth app
         redirect_url: String,
         /// The URL of the OpenID discovery document for the Azure OAuth app
+        #[cfg(not(feature = "oauth-azure"))]
         oauth_openid_url: String,
+        /// The Azure AD tenant that users sign in with
+        #[cfg(feature = "oauth-azure")]
+        tenant_id: String,
+        /// Additional scopes to request from Azure, such as `GroupMember.Read.All`
+        #[cfg(feature = "oauth-azure")]
+        scopes: Option<Vec<String>>,
         /// The store for in-flight requests
         inflight_request_store: Box<dyn InflightOAuthRequestStore>,
     },
@@ -19,6 +19,8 @@ use diesel::{
     sqlite::SqliteConnection,
 };
 
+#[cfg(feature = "audit")]
+use crate::audit::{AuditStore, MemoryAuditStore};
 #[cfg(feature = "oauth")]
 use crate::biome::MemoryOAuthUserSessionStore;
 #[cfg(feature = "biome-credentials")]
@@ -27,21 +29,31 @@ use crate::biome::{
 };
 #[cfg(feature = "biome-key-management")]
 use crate::biome::{KeyStore, MemoryKeyStore};
+#[cfg(feature = "biome-notifications")]
+use crate::biome::{MemoryNotificationStore, NotificationStore};
 #[cfg(feature = "biome-profile")]
 use crate::biome::{MemoryUserProfileStore, UserProfileStore};
 use crate::error::InternalError;
 #[cfg(feature = "oauth")]
 use crate::oauth::store::MemoryInflightOAuthRequestStore;
+#[cfg(feature = "api-keys")]
+use crate::rest_api::auth::api_key::{ApiKeyStore, MemoryApiKeyStore};
 
 use super::sqlite::ForeignKeyCustomizer;
 use super::StoreFactory;
 
 /// A `StoryFactory` backed by memory.
 pub struct MemoryStoreFactory {
+    #[cfg(feature = "api-keys")]
+    api_key_store: MemoryApiKeyStore,
+    #[cfg(feature = "audit")]
+    audit_store: MemoryAuditStore,
     #[cfg(feature = "biome-credentials")]
     biome_credentials_store: MemoryCredentialsStore,
     #[cfg(feature = "biome-key-management")]
     biome_key_store: MemoryKeyStore,
+    #[cfg(feature = "biome-notifications")]
+    biome_notification_store: MemoryNotificationStore,
     #[cfg(feature = "biome-credentials")]
     biome_refresh_token_store: MemoryRefreshTokenStore,
     #[cfg(feature = "oauth")]
@@ -50,6 +62,9 @@ pub struct MemoryStoreFactory {
     inflight_request_store: MemoryInflightOAuthRequestStore,
     #[cfg(feature = "biome-profile")]
     biome_profile_store: MemoryUserProfileStore,
+    // shared so that changes made through any registry store are reported to all subscribers
+    #[cfg(feature = "registry")]
+    registry: crate::registry::DieselRegistry<SqliteConnection>,
     // to be used for sqlite in memory implementations
     pool: Pool<ConnectionManager<SqliteConnection>>,
 }
@@ -88,10 +103,16 @@ impl MemoryStoreFactory {
         .map_err(|err| InternalError::from_source(Box::new(err)))?;
 
         Ok(Self {
+            #[cfg(feature = "api-keys")]
+            api_key_store: MemoryApiKeyStore::new(),
+            #[cfg(feature = "audit")]
+            audit_store: MemoryAuditStore::new(),
             #[cfg(feature = "biome-credentials")]
             biome_credentials_store,
             #[cfg(feature = "biome-key-management")]
             biome_key_store,
+            #[cfg(feature = "biome-notifications")]
+            biome_notification_store: MemoryNotificationStore::new(),
             #[cfg(feature = "biome-credentials")]
             biome_refresh_token_store: MemoryRefreshTokenStore::new(),
             #[cfg(feature = "oauth")]
@@ -100,12 +121,24 @@ impl MemoryStoreFactory {
             inflight_request_store,
             #[cfg(feature = "biome-profile")]
             biome_profile_store,
+            #[cfg(feature = "registry")]
+            registry: crate::registry::DieselRegistry::new(pool.clone()),
             pool,
         })
     }
 }
 
 impl StoreFactory for MemoryStoreFactory {
+    #[cfg(feature = "api-keys")]
+    fn get_api_key_store(&self) -> Box<dyn ApiKeyStore> {
+        Box::new(self.api_key_store.clone())
+    }
+
+    #[cfg(feature = "audit")]
+    fn get_audit_store(&self) -> Box<dyn AuditStore> {
+        Box::new(self.audit_store.clone())
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_credentials_store(&self) -> Box<dyn CredentialsStore> {
         Box::new(self.biome_credentials_store.clone())
@@ -116,6 +149,11 @@ impl StoreFactory for MemoryStoreFactory {
         Box::new(self.biome_key_store.clone())
     }
 
+    #[cfg(feature = "biome-notifications")]
+    fn get_biome_notification_store(&self) -> Box<dyn NotificationStore> {
+        Box::new(self.biome_notification_store.clone())
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_refresh_token_store(&self) -> Box<dyn RefreshTokenStore> {
         Box::new(self.biome_refresh_token_store.clone())
@@ -133,6 +171,13 @@ impl StoreFactory for MemoryStoreFactory {
         ))
     }
 
+    #[cfg(feature = "circuit-durability")]
+    fn get_durable_message_store(&self) -> Box<dyn crate::circuit::durable::DurableMessageStore> {
+        Box::new(crate::circuit::durable::DieselDurableMessageStore::new(
+            self.pool.clone(),
+        ))
+    }
+
     #[cfg(feature = "oauth")]
     fn get_oauth_inflight_request_store(
         &self,
@@ -142,7 +187,7 @@ impl StoreFactory for MemoryStoreFactory {
 
     #[cfg(feature = "registry")]
     fn get_registry_store(&self) -> Box<dyn crate::registry::RwRegistry> {
-        Box::new(crate::registry::DieselRegistry::new(self.pool.clone()))
+        Box::new(self.registry.clone())
     }
 
     #[cfg(feature = "authorization-handler-rbac")]
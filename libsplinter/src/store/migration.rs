@@ -0,0 +1,1637 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Copies the data of every store created by one `StoreFactory` into the stores created by
+//! another, e.g. to move a node from SQLite to PostgreSQL.
+//!
+//! In-flight OAuth requests are not copied, as they expire within minutes.
+//!
+//! The stores are not copied in a single transaction. If a migration fails partway through, it
+//! can be continued with [`resume_data_migration`], which skips the entries that were already
+//! copied.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::error::{InternalError, InvalidStateError};
+
+use super::StoreFactory;
+
+/// The number of entries copied for each kind of data.
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    counts: Vec<(&'static str, usize)>,
+}
+
+impl MigrationReport {
+    /// Returns the name and number of copied entries for each kind of data, in the order they
+    /// were copied.
+    pub fn counts(&self) -> &[(&'static str, usize)] {
+        &self.counts
+    }
+}
+
+/// Errors that may occur while migrating data between stores.
+#[derive(Debug)]
+pub enum StoreMigrationError {
+    Internal(InternalError),
+    InvalidState(InvalidStateError),
+}
+
+impl Error for StoreMigrationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StoreMigrationError::Internal(err) => err.source(),
+            StoreMigrationError::InvalidState(err) => err.source(),
+        }
+    }
+}
+
+impl fmt::Display for StoreMigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoreMigrationError::Internal(err) => f.write_str(&err.to_string()),
+            StoreMigrationError::InvalidState(err) => f.write_str(&err.to_string()),
+        }
+    }
+}
+
+/// Returns the names of the stores copied by [`migrate_data`], which depend on the enabled
+/// features.
+pub fn migrated_stores() -> Vec<&'static str> {
+    let mut stores = vec![];
+
+    #[cfg(feature = "admin-service")]
+    stores.push("admin service");
+    #[cfg(feature = "registry")]
+    stores.push("registry");
+    #[cfg(feature = "circuit-durability")]
+    stores.push("durable messages");
+    #[cfg(feature = "audit")]
+    stores.push("audit log");
+    #[cfg(feature = "biome-credentials")]
+    stores.extend(&["Biome credentials", "Biome refresh tokens"]);
+    #[cfg(feature = "biome-key-management")]
+    stores.push("Biome keys");
+    #[cfg(feature = "oauth")]
+    stores.push("OAuth user sessions");
+    #[cfg(feature = "biome-profile")]
+    stores.push("Biome profiles");
+    #[cfg(feature = "biome-notifications")]
+    stores.push("Biome notifications");
+    #[cfg(feature = "authorization-handler-rbac")]
+    stores.push("role-based authorization");
+    #[cfg(feature = "authorization-handler-rbac-groups")]
+    stores.push("role-based authorization group mappings");
+    #[cfg(feature = "api-keys")]
+    stores.push("API keys");
+
+    stores
+}
+
+/// Copies the data in every store of `from` into the corresponding store of `to`.
+///
+/// All destination stores must be empty, apart from data seeded by the database migrations. The
+/// number of entries in each destination store is checked against the source once it has been
+/// copied.
+///
+/// # Errors
+///
+/// * Returns an `InvalidState` error if a destination store already contains data, or if the
+///   number of entries in a destination store does not match the source after copying
+/// * Returns an `Internal` error if a store operation fails
+pub fn migrate_data(
+    from: &dyn StoreFactory,
+    to: &dyn StoreFactory,
+) -> Result<MigrationReport, StoreMigrationError> {
+    check_destination_empty(to)?;
+
+    copy_data(from, to, false)
+}
+
+/// Continues a migration from `from` into `to` that previously failed partway through.
+///
+/// The destination stores are not required to be empty; entries that already exist in the
+/// destination are skipped, and every other entry is copied as with [`migrate_data`]. The number
+/// of entries in each destination store is checked against the source once it has been copied.
+///
+/// The destination must only contain data copied from `from` by an earlier call to
+/// `migrate_data` or `resume_data_migration`.
+///
+/// # Errors
+///
+/// * Returns an `InvalidState` error if the number of entries in a destination store does not
+///   match the source after copying
+/// * Returns an `Internal` error if a store operation fails
+pub fn resume_data_migration(
+    from: &dyn StoreFactory,
+    to: &dyn StoreFactory,
+) -> Result<MigrationReport, StoreMigrationError> {
+    copy_data(from, to, true)
+}
+
+/// Copies the data of every store; if `resume` is true, entries that already exist in the
+/// destination are skipped.
+fn copy_data(
+    from: &dyn StoreFactory,
+    to: &dyn StoreFactory,
+    resume: bool,
+) -> Result<MigrationReport, StoreMigrationError> {
+    let mut report = MigrationReport::default();
+
+    #[cfg(feature = "admin-service")]
+    admin::migrate(
+        &*from.get_admin_service_store(),
+        &*to.get_admin_service_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "registry")]
+    registry::migrate(
+        &*from.get_registry_store(),
+        &*to.get_registry_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "circuit-durability")]
+    durable::migrate(
+        &*from.get_durable_message_store(),
+        &*to.get_durable_message_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "audit")]
+    audit::migrate(
+        &*from.get_audit_store(),
+        &*to.get_audit_store(),
+        resume,
+        &mut report,
+    )?;
+
+    // Keys and refresh tokens are copied after credentials, as stores may require their user to
+    // exist
+    #[cfg(feature = "biome-credentials")]
+    biome::migrate_credentials(
+        &*from.get_biome_credentials_store(),
+        &*to.get_biome_credentials_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "biome-credentials")]
+    biome::migrate_refresh_tokens(
+        &*from.get_biome_credentials_store(),
+        &*from.get_biome_refresh_token_store(),
+        &*to.get_biome_refresh_token_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "biome-key-management")]
+    biome::migrate_keys(
+        &*from.get_biome_key_store(),
+        &*to.get_biome_key_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "oauth")]
+    biome::migrate_oauth(
+        &*from.get_biome_oauth_user_session_store(),
+        &*to.get_biome_oauth_user_session_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "biome-profile")]
+    biome::migrate_profiles(
+        &*from.get_biome_user_profile_store(),
+        &*to.get_biome_user_profile_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "biome-notifications")]
+    biome::migrate_notifications(
+        &*from.get_biome_notification_store(),
+        &*to.get_biome_notification_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "authorization-handler-rbac")]
+    rbac::migrate(
+        &*from.get_role_based_authorization_store(),
+        &*to.get_role_based_authorization_store(),
+        resume,
+        &mut report,
+    )?;
+
+    #[cfg(feature = "api-keys")]
+    api_key::migrate(
+        &*from.get_api_key_store(),
+        &*to.get_api_key_store(),
+        resume,
+        &mut report,
+    )?;
+
+    Ok(report)
+}
+
+/// Verifies that every destination store is empty before anything is copied, so that a failed
+/// check does not leave the destination partially populated.
+fn check_destination_empty(to: &dyn StoreFactory) -> Result<(), StoreMigrationError> {
+    #[cfg(feature = "admin-service")]
+    admin::check_empty(&*to.get_admin_service_store())?;
+
+    #[cfg(feature = "registry")]
+    registry::check_empty(&*to.get_registry_store())?;
+
+    #[cfg(feature = "circuit-durability")]
+    durable::check_empty(&*to.get_durable_message_store())?;
+
+    #[cfg(feature = "audit")]
+    audit::check_empty(&*to.get_audit_store())?;
+
+    #[cfg(feature = "biome-credentials")]
+    biome::check_credentials_empty(&*to.get_biome_credentials_store())?;
+
+    // Refresh tokens belong to credentials users, so an empty credentials store has none
+
+    #[cfg(feature = "biome-key-management")]
+    biome::check_keys_empty(&*to.get_biome_key_store())?;
+
+    #[cfg(feature = "oauth")]
+    biome::check_oauth_empty(&*to.get_biome_oauth_user_session_store())?;
+
+    #[cfg(feature = "biome-profile")]
+    biome::check_profiles_empty(&*to.get_biome_user_profile_store())?;
+
+    #[cfg(feature = "biome-notifications")]
+    biome::check_notifications_empty(&*to.get_biome_notification_store())?;
+
+    #[cfg(feature = "authorization-handler-rbac")]
+    rbac::check_empty(&*to.get_role_based_authorization_store())?;
+
+    #[cfg(feature = "api-keys")]
+    api_key::check_empty(&*to.get_api_key_store())?;
+
+    Ok(())
+}
+
+/// Returns an `InvalidState` error if the destination already contains `count` entries.
+fn ensure_empty(name: &str, count: usize) -> Result<(), StoreMigrationError> {
+    if count == 0 {
+        Ok(())
+    } else {
+        Err(StoreMigrationError::InvalidState(
+            InvalidStateError::with_message(format!(
+                "Destination already contains {} {}",
+                count, name
+            )),
+        ))
+    }
+}
+
+/// Compares the number of entries in the source and destination, recording the count in the
+/// report if they match.
+fn verify_count(
+    name: &'static str,
+    source: usize,
+    destination: usize,
+    report: &mut MigrationReport,
+) -> Result<(), StoreMigrationError> {
+    if source != destination {
+        return Err(StoreMigrationError::InvalidState(
+            InvalidStateError::with_message(format!(
+                "Source contains {} {} but destination contains {}",
+                source, name, destination
+            )),
+        ));
+    }
+
+    report.counts.push((name, destination));
+
+    Ok(())
+}
+
+fn internal_error<E: Error + 'static>(prefix: &str) -> impl FnOnce(E) -> StoreMigrationError {
+    let prefix = prefix.to_string();
+    move |err| {
+        StoreMigrationError::Internal(InternalError::from_source_with_prefix(
+            Box::new(err),
+            prefix,
+        ))
+    }
+}
+
+#[cfg(feature = "admin-service")]
+mod admin {
+    use crate::admin::service::messages;
+    use crate::admin::store::{AdminServiceStore, CircuitPredicate, CircuitStatus};
+
+    use super::{ensure_empty, internal_error, verify_count, MigrationReport, StoreMigrationError};
+
+    /// Returns predicates that match circuits of any status; by default only active circuits are
+    /// listed.
+    fn all_circuits() -> Vec<CircuitPredicate> {
+        vec![
+            CircuitPredicate::CircuitStatus(CircuitStatus::Active),
+            CircuitPredicate::CircuitStatus(CircuitStatus::Disbanded),
+            CircuitPredicate::CircuitStatus(CircuitStatus::Abandoned),
+        ]
+    }
+
+    pub fn check_empty(to: &dyn AdminServiceStore) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "circuits",
+            to.list_circuits(&all_circuits())
+                .map_err(internal_error("Unable to list destination circuits"))?
+                .len(),
+        )?;
+        ensure_empty(
+            "circuit proposals",
+            to.list_proposals(&[])
+                .map_err(internal_error("Unable to list destination proposals"))?
+                .len(),
+        )?;
+        ensure_empty(
+            "admin service events",
+            to.list_events_since(0)
+                .map_err(internal_error("Unable to list destination events"))?
+                .len(),
+        )
+    }
+
+    pub fn migrate(
+        from: &dyn AdminServiceStore,
+        to: &dyn AdminServiceStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let circuits = from
+            .list_circuits(&all_circuits())
+            .map_err(internal_error("Unable to list source circuits"))?;
+        let circuit_count = circuits.len();
+        for circuit in circuits {
+            if resume
+                && to
+                    .get_circuit(circuit.circuit_id())
+                    .map_err(internal_error("Unable to get destination circuit"))?
+                    .is_some()
+            {
+                continue;
+            }
+            let nodes = circuit.members().to_vec();
+            to.add_circuit(circuit, nodes)
+                .map_err(internal_error("Unable to add circuit"))?;
+        }
+        verify_count(
+            "circuits",
+            circuit_count,
+            to.list_circuits(&all_circuits())
+                .map_err(internal_error("Unable to list destination circuits"))?
+                .len(),
+            report,
+        )?;
+
+        let proposals = from
+            .list_proposals(&[])
+            .map_err(internal_error("Unable to list source proposals"))?;
+        let proposal_count = proposals.len();
+        for proposal in proposals {
+            if resume
+                && to
+                    .get_proposal(proposal.circuit_id())
+                    .map_err(internal_error("Unable to get destination proposal"))?
+                    .is_some()
+            {
+                continue;
+            }
+            to.add_proposal(proposal)
+                .map_err(internal_error("Unable to add proposal"))?;
+        }
+        verify_count(
+            "circuit proposals",
+            proposal_count,
+            to.list_proposals(&[])
+                .map_err(internal_error("Unable to list destination proposals"))?
+                .len(),
+            report,
+        )?;
+
+        // Events are added in order, so that they keep their relative ordering in the
+        // destination. When resuming, the events already in the destination are the first events
+        // of the source.
+        let copied_events = if resume {
+            to.list_events_since(0)
+                .map_err(internal_error("Unable to list destination events"))?
+                .len()
+        } else {
+            0
+        };
+        let events = from
+            .list_events_since(0)
+            .map_err(internal_error("Unable to list source events"))?;
+        let event_count = events.len();
+        for event in events.skip(copied_events) {
+            to.add_event(messages::AdminServiceEvent::from(&event))
+                .map_err(internal_error("Unable to add event"))?;
+        }
+        verify_count(
+            "admin service events",
+            event_count,
+            to.list_events_since(0)
+                .map_err(internal_error("Unable to list destination events"))?
+                .len(),
+            report,
+        )
+    }
+}
+
+#[cfg(feature = "registry")]
+mod registry {
+    use crate::registry::RwRegistry;
+
+    use super::{ensure_empty, internal_error, verify_count, MigrationReport, StoreMigrationError};
+
+    pub fn check_empty(to: &dyn RwRegistry) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "registry nodes",
+            to.count_nodes(&[])
+                .map_err(internal_error("Unable to count destination registry nodes"))?
+                as usize,
+        )
+    }
+
+    pub fn migrate(
+        from: &dyn RwRegistry,
+        to: &dyn RwRegistry,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let nodes = from
+            .list_nodes(&[])
+            .map_err(internal_error("Unable to list source registry nodes"))?;
+        let node_count = nodes.len();
+        for node in nodes {
+            if resume
+                && to
+                    .has_node(node.identity())
+                    .map_err(internal_error("Unable to check destination registry node"))?
+            {
+                continue;
+            }
+            to.add_node(node)
+                .map_err(internal_error("Unable to add registry node"))?;
+        }
+        verify_count(
+            "registry nodes",
+            node_count,
+            to.count_nodes(&[])
+                .map_err(internal_error("Unable to count destination registry nodes"))?
+                as usize,
+            report,
+        )
+    }
+}
+
+#[cfg(feature = "circuit-durability")]
+mod durable {
+    use std::collections::HashSet;
+
+    use crate::circuit::durable::DurableMessageStore;
+
+    use super::{ensure_empty, internal_error, verify_count, MigrationReport, StoreMigrationError};
+
+    pub fn check_empty(to: &dyn DurableMessageStore) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "durable message peers",
+            to.list_peers()
+                .map_err(internal_error(
+                    "Unable to list destination durable message peers",
+                ))?
+                .len(),
+        )
+    }
+
+    /// Copies each peer together with its unacknowledged messages, keeping their sequence
+    /// numbers so that delivery continues where it left off.
+    pub fn migrate(
+        from: &dyn DurableMessageStore,
+        to: &dyn DurableMessageStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let copied: HashSet<String> = if resume {
+            to.list_peers()
+                .map_err(internal_error(
+                    "Unable to list destination durable message peers",
+                ))?
+                .into_iter()
+                .map(|peer| peer.node_id().to_string())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let peers = from.list_peers().map_err(internal_error(
+            "Unable to list source durable message peers",
+        ))?;
+        let peer_count = peers.len();
+        let mut message_count = 0;
+        for peer in peers {
+            let messages = from
+                .list_messages(peer.node_id())
+                .map_err(internal_error("Unable to list source durable messages"))?;
+            message_count += messages.len();
+            if copied.contains(peer.node_id()) {
+                continue;
+            }
+            to.import_peer(peer, messages)
+                .map_err(internal_error("Unable to import durable message peer"))?;
+        }
+
+        let peers = to.list_peers().map_err(internal_error(
+            "Unable to list destination durable message peers",
+        ))?;
+        let mut copied_message_count = 0;
+        for peer in peers.iter() {
+            copied_message_count += to
+                .list_messages(peer.node_id())
+                .map_err(internal_error(
+                    "Unable to list destination durable messages",
+                ))?
+                .len();
+        }
+        verify_count("durable message peers", peer_count, peers.len(), report)?;
+        verify_count(
+            "durable messages",
+            message_count,
+            copied_message_count,
+            report,
+        )
+    }
+}
+
+#[cfg(feature = "audit")]
+mod audit {
+    use crate::audit::AuditStore;
+
+    use super::{ensure_empty, internal_error, verify_count, MigrationReport, StoreMigrationError};
+
+    /// The number of audit entries read from the source at a time.
+    const PAGE_SIZE: usize = 1000;
+
+    pub fn check_empty(to: &dyn AuditStore) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "audit entries",
+            to.count_entries()
+                .map_err(internal_error("Unable to count destination audit entries"))?,
+        )
+    }
+
+    /// Copies the entries oldest first, so that they are listed in the same order in the
+    /// destination. When resuming, the entries already in the destination are the oldest entries
+    /// of the source.
+    pub fn migrate(
+        from: &dyn AuditStore,
+        to: &dyn AuditStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let entry_count = from
+            .count_entries()
+            .map_err(internal_error("Unable to count source audit entries"))?;
+        let copied = if resume {
+            to.count_entries()
+                .map_err(internal_error("Unable to count destination audit entries"))?
+        } else {
+            0
+        };
+
+        // Entries are listed newest first, so the uncopied entries are the first `remaining`
+        // entries of the listing
+        let mut remaining = entry_count.saturating_sub(copied);
+        while remaining > 0 {
+            let offset = remaining.saturating_sub(PAGE_SIZE);
+            let entries = from
+                .list_entries(offset, remaining - offset)
+                .map_err(internal_error("Unable to list source audit entries"))?;
+            for entry in entries.into_iter().rev() {
+                to.add_entry(entry)
+                    .map_err(internal_error("Unable to add audit entry"))?;
+            }
+            remaining = offset;
+        }
+
+        verify_count(
+            "audit entries",
+            entry_count,
+            to.count_entries()
+                .map_err(internal_error("Unable to count destination audit entries"))?,
+            report,
+        )
+    }
+}
+
+#[cfg(feature = "biome")]
+mod biome {
+    #[cfg(any(
+        feature = "biome-credentials",
+        feature = "biome-key-management",
+        feature = "biome-profile",
+        feature = "biome-notifications"
+    ))]
+    use std::collections::HashSet;
+
+    #[cfg(feature = "biome-credentials")]
+    use crate::biome::refresh_tokens::store::RefreshTokenError;
+    #[cfg(feature = "biome-key-management")]
+    use crate::biome::KeyStore;
+    #[cfg(feature = "biome-notifications")]
+    use crate::biome::NotificationStore;
+    #[cfg(feature = "oauth")]
+    use crate::biome::OAuthUserSessionStore;
+    #[cfg(feature = "biome-profile")]
+    use crate::biome::UserProfileStore;
+    #[cfg(feature = "biome-credentials")]
+    use crate::biome::{CredentialsStore, RefreshTokenStore};
+
+    #[cfg(any(
+        feature = "biome-credentials",
+        feature = "biome-key-management",
+        feature = "oauth",
+        feature = "biome-profile",
+        feature = "biome-notifications"
+    ))]
+    use super::{ensure_empty, internal_error, verify_count, MigrationReport, StoreMigrationError};
+
+    #[cfg(feature = "biome-credentials")]
+    pub fn check_credentials_empty(to: &dyn CredentialsStore) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "Biome credentials",
+            to.list_usernames()
+                .map_err(internal_error("Unable to list destination usernames"))?
+                .len(),
+        )
+    }
+
+    /// Copies each user's credentials, including the already-hashed password.
+    #[cfg(feature = "biome-credentials")]
+    pub fn migrate_credentials(
+        from: &dyn CredentialsStore,
+        to: &dyn CredentialsStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let copied = if resume {
+            to.list_usernames()
+                .map_err(internal_error("Unable to list destination usernames"))?
+                .into_iter()
+                .map(|username| username.user_id)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let usernames = from
+            .list_usernames()
+            .map_err(internal_error("Unable to list source usernames"))?;
+        let credentials_count = usernames.len();
+        for username in usernames {
+            if copied.contains(&username.user_id) {
+                continue;
+            }
+            let credentials = from
+                .fetch_credential_by_user_id(&username.user_id)
+                .map_err(internal_error("Unable to fetch source credentials"))?;
+            to.add_credentials(credentials)
+                .map_err(internal_error("Unable to add credentials"))?;
+        }
+        verify_count(
+            "Biome credentials",
+            credentials_count,
+            to.list_usernames()
+                .map_err(internal_error("Unable to list destination usernames"))?
+                .len(),
+            report,
+        )
+    }
+
+    /// Copies the refresh token of each credentials user that has one. The refresh token store
+    /// cannot be listed, so the users are taken from the source credentials store.
+    #[cfg(feature = "biome-credentials")]
+    pub fn migrate_refresh_tokens(
+        credentials: &dyn CredentialsStore,
+        from: &dyn RefreshTokenStore,
+        to: &dyn RefreshTokenStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let user_ids = credentials
+            .list_usernames()
+            .map_err(internal_error("Unable to list source usernames"))?
+            .into_iter()
+            .map(|username| username.user_id)
+            .collect::<Vec<_>>();
+
+        let mut token_count = 0;
+        for user_id in user_ids.iter() {
+            let token = match fetch_refresh_token(from, user_id)
+                .map_err(internal_error("Unable to fetch source refresh token"))?
+            {
+                Some(token) => token,
+                None => continue,
+            };
+            token_count += 1;
+            if resume
+                && fetch_refresh_token(to, user_id)
+                    .map_err(internal_error("Unable to fetch destination refresh token"))?
+                    .is_some()
+            {
+                continue;
+            }
+            to.add_token(user_id, &token)
+                .map_err(internal_error("Unable to add refresh token"))?;
+        }
+
+        let mut copied_count = 0;
+        for user_id in user_ids.iter() {
+            if fetch_refresh_token(to, user_id)
+                .map_err(internal_error("Unable to fetch destination refresh token"))?
+                .is_some()
+            {
+                copied_count += 1;
+            }
+        }
+        verify_count("Biome refresh tokens", token_count, copied_count, report)
+    }
+
+    /// Fetches a user's refresh token, returning `None` if the user does not have one.
+    #[cfg(feature = "biome-credentials")]
+    fn fetch_refresh_token(
+        store: &dyn RefreshTokenStore,
+        user_id: &str,
+    ) -> Result<Option<String>, RefreshTokenError> {
+        match store.fetch_token(user_id) {
+            Ok(token) => Ok(Some(token)),
+            Err(RefreshTokenError::NotFoundError(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[cfg(feature = "biome-key-management")]
+    pub fn check_keys_empty(to: &dyn KeyStore) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "Biome keys",
+            to.list_keys(None)
+                .map_err(internal_error("Unable to list destination keys"))?
+                .len(),
+        )
+    }
+
+    #[cfg(feature = "biome-key-management")]
+    pub fn migrate_keys(
+        from: &dyn KeyStore,
+        to: &dyn KeyStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let copied = if resume {
+            to.list_keys(None)
+                .map_err(internal_error("Unable to list destination keys"))?
+                .into_iter()
+                .map(|key| key.public_key)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let keys = from
+            .list_keys(None)
+            .map_err(internal_error("Unable to list source keys"))?;
+        let key_count = keys.len();
+        for key in keys {
+            if copied.contains(&key.public_key) {
+                continue;
+            }
+            to.add_key(key)
+                .map_err(internal_error("Unable to add key"))?;
+        }
+        verify_count(
+            "Biome keys",
+            key_count,
+            to.list_keys(None)
+                .map_err(internal_error("Unable to list destination keys"))?
+                .len(),
+            report,
+        )
+    }
+
+    #[cfg(feature = "oauth")]
+    pub fn check_oauth_empty(to: &dyn OAuthUserSessionStore) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "OAuth users",
+            to.list_users()
+                .map_err(internal_error("Unable to list destination OAuth users"))?
+                .len(),
+        )?;
+        ensure_empty(
+            "OAuth sessions",
+            to.list_sessions()
+                .map_err(internal_error("Unable to list destination OAuth sessions"))?
+                .len(),
+        )
+    }
+
+    /// Copies the OAuth users, keeping their Biome user IDs, followed by their sessions.
+    #[cfg(feature = "oauth")]
+    pub fn migrate_oauth(
+        from: &dyn OAuthUserSessionStore,
+        to: &dyn OAuthUserSessionStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let users = from
+            .list_users()
+            .map_err(internal_error("Unable to list source OAuth users"))?;
+        let user_count = users.len();
+        for user in users {
+            if resume
+                && to
+                    .get_user(user.subject())
+                    .map_err(internal_error("Unable to get destination OAuth user"))?
+                    .is_some()
+            {
+                continue;
+            }
+            to.add_user(user)
+                .map_err(internal_error("Unable to add OAuth user"))?;
+        }
+        verify_count(
+            "OAuth users",
+            user_count,
+            to.list_users()
+                .map_err(internal_error("Unable to list destination OAuth users"))?
+                .len(),
+            report,
+        )?;
+
+        let sessions = from
+            .list_sessions()
+            .map_err(internal_error("Unable to list source OAuth sessions"))?;
+        let session_count = sessions.len();
+        for session in sessions {
+            if resume
+                && to
+                    .get_session(session.splinter_access_token())
+                    .map_err(internal_error("Unable to get destination OAuth session"))?
+                    .is_some()
+            {
+                continue;
+            }
+            to.import_session(session)
+                .map_err(internal_error("Unable to add OAuth session"))?;
+        }
+        verify_count(
+            "OAuth sessions",
+            session_count,
+            to.list_sessions()
+                .map_err(internal_error("Unable to list destination OAuth sessions"))?
+                .len(),
+            report,
+        )
+    }
+
+    #[cfg(feature = "biome-profile")]
+    pub fn check_profiles_empty(to: &dyn UserProfileStore) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "Biome profiles",
+            to.list_profiles()
+                .map_err(internal_error("Unable to list destination profiles"))?
+                .map(|profiles| profiles.len())
+                .unwrap_or(0),
+        )
+    }
+
+    #[cfg(feature = "biome-profile")]
+    pub fn migrate_profiles(
+        from: &dyn UserProfileStore,
+        to: &dyn UserProfileStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let copied = if resume {
+            to.list_profiles()
+                .map_err(internal_error("Unable to list destination profiles"))?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|profile| profile.user_id().to_string())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let profiles = from
+            .list_profiles()
+            .map_err(internal_error("Unable to list source profiles"))?
+            .unwrap_or_default();
+        let profile_count = profiles.len();
+        for profile in profiles {
+            if copied.contains(profile.user_id()) {
+                continue;
+            }
+            to.add_profile(profile)
+                .map_err(internal_error("Unable to add profile"))?;
+        }
+        verify_count(
+            "Biome profiles",
+            profile_count,
+            to.list_profiles()
+                .map_err(internal_error("Unable to list destination profiles"))?
+                .map(|profiles| profiles.len())
+                .unwrap_or(0),
+            report,
+        )
+    }
+
+    #[cfg(feature = "biome-notifications")]
+    pub fn check_notifications_empty(
+        to: &dyn NotificationStore,
+    ) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "Biome notifications",
+            to.list_all_notifications()
+                .map_err(internal_error("Unable to list destination notifications"))?
+                .len(),
+        )
+    }
+
+    #[cfg(feature = "biome-notifications")]
+    pub fn migrate_notifications(
+        from: &dyn NotificationStore,
+        to: &dyn NotificationStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let copied = if resume {
+            to.list_all_notifications()
+                .map_err(internal_error("Unable to list destination notifications"))?
+                .into_iter()
+                .map(|notification| notification.id().to_string())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let notifications = from
+            .list_all_notifications()
+            .map_err(internal_error("Unable to list source notifications"))?;
+        let notification_count = notifications.len();
+        for notification in notifications {
+            if copied.contains(notification.id()) {
+                continue;
+            }
+            to.add_notification(notification)
+                .map_err(internal_error("Unable to add notification"))?;
+        }
+        verify_count(
+            "Biome notifications",
+            notification_count,
+            to.list_all_notifications()
+                .map_err(internal_error("Unable to list destination notifications"))?
+                .len(),
+            report,
+        )
+    }
+}
+
+#[cfg(feature = "authorization-handler-rbac")]
+mod rbac {
+    use crate::rest_api::auth::authorization::rbac::store::{
+        RoleBasedAuthorizationStore, ADMIN_ROLE_ID,
+    };
+
+    use super::{ensure_empty, internal_error, verify_count, MigrationReport, StoreMigrationError};
+
+    /// Checks that the destination holds no roles other than the admin role, which is seeded by
+    /// the database migrations, no assignments and no group mappings.
+    pub fn check_empty(to: &dyn RoleBasedAuthorizationStore) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "roles",
+            to.list_roles()
+                .map_err(internal_error("Unable to list destination roles"))?
+                .filter(|role| role.id() != ADMIN_ROLE_ID)
+                .count(),
+        )?;
+        ensure_empty(
+            "role assignments",
+            to.list_assignments()
+                .map_err(internal_error("Unable to list destination assignments"))?
+                .len(),
+        )?;
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        ensure_empty(
+            "group mappings",
+            to.list_group_mappings()
+                .map_err(internal_error("Unable to list destination group mappings"))?
+                .len(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Copies the roles, replacing any seeded role in the destination with the source's version,
+    /// followed by the assignments and group mappings.
+    pub fn migrate(
+        from: &dyn RoleBasedAuthorizationStore,
+        to: &dyn RoleBasedAuthorizationStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let roles = from
+            .list_roles()
+            .map_err(internal_error("Unable to list source roles"))?
+            .collect::<Vec<_>>();
+        for role in roles.iter().cloned() {
+            let exists = to
+                .get_role(role.id())
+                .map_err(internal_error("Unable to get destination role"))?
+                .is_some();
+            if exists {
+                to.update_role(role)
+                    .map_err(internal_error("Unable to update role"))?;
+            } else {
+                to.add_role(role)
+                    .map_err(internal_error("Unable to add role"))?;
+            }
+        }
+
+        // A seeded role that was removed from the source is removed from the destination as well
+        let seeded_roles = to
+            .list_roles()
+            .map_err(internal_error("Unable to list destination roles"))?
+            .filter(|seeded| !roles.iter().any(|role| role.id() == seeded.id()))
+            .collect::<Vec<_>>();
+        for role in seeded_roles {
+            to.remove_role(role.id())
+                .map_err(internal_error("Unable to remove role"))?;
+        }
+
+        verify_count(
+            "roles",
+            roles.len(),
+            to.list_roles()
+                .map_err(internal_error("Unable to list destination roles"))?
+                .len(),
+            report,
+        )?;
+
+        let assignments = from
+            .list_assignments()
+            .map_err(internal_error("Unable to list source assignments"))?;
+        let assignment_count = assignments.len();
+        for assignment in assignments {
+            if resume
+                && to
+                    .get_assignment(assignment.identity())
+                    .map_err(internal_error("Unable to get destination assignment"))?
+                    .is_some()
+            {
+                continue;
+            }
+            to.add_assignment(assignment)
+                .map_err(internal_error("Unable to add assignment"))?;
+        }
+        verify_count(
+            "role assignments",
+            assignment_count,
+            to.list_assignments()
+                .map_err(internal_error("Unable to list destination assignments"))?
+                .len(),
+            report,
+        )?;
+
+        // Setting a group mapping replaces an existing one, so mappings that were already copied
+        // can be set again when resuming
+        #[cfg(feature = "authorization-handler-rbac-groups")]
+        {
+            let group_mappings = from
+                .list_group_mappings()
+                .map_err(internal_error("Unable to list source group mappings"))?;
+            let group_mapping_count = group_mappings.len();
+            for group_mapping in group_mappings {
+                to.set_group_mapping(group_mapping)
+                    .map_err(internal_error("Unable to set group mapping"))?;
+            }
+            verify_count(
+                "group mappings",
+                group_mapping_count,
+                to.list_group_mappings()
+                    .map_err(internal_error("Unable to list destination group mappings"))?
+                    .len(),
+                report,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "api-keys")]
+mod api_key {
+    use std::collections::HashSet;
+
+    use crate::rest_api::auth::api_key::ApiKeyStore;
+
+    use super::{ensure_empty, internal_error, verify_count, MigrationReport, StoreMigrationError};
+
+    pub fn check_empty(to: &dyn ApiKeyStore) -> Result<(), StoreMigrationError> {
+        ensure_empty(
+            "API keys",
+            to.list_api_keys()
+                .map_err(internal_error("Unable to list destination API keys"))?
+                .len(),
+        )
+    }
+
+    pub fn migrate(
+        from: &dyn ApiKeyStore,
+        to: &dyn ApiKeyStore,
+        resume: bool,
+        report: &mut MigrationReport,
+    ) -> Result<(), StoreMigrationError> {
+        let copied: HashSet<String> = if resume {
+            to.list_api_keys()
+                .map_err(internal_error("Unable to list destination API keys"))?
+                .into_iter()
+                .map(|api_key| api_key.id().to_string())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let api_keys = from
+            .list_api_keys()
+            .map_err(internal_error("Unable to list source API keys"))?;
+        let api_key_count = api_keys.len();
+        for api_key in api_keys {
+            if copied.contains(api_key.id()) {
+                continue;
+            }
+            to.add_api_key(api_key)
+                .map_err(internal_error("Unable to add API key"))?;
+        }
+        verify_count(
+            "API keys",
+            api_key_count,
+            to.list_api_keys()
+                .map_err(internal_error("Unable to list destination API keys"))?
+                .len(),
+            report,
+        )
+    }
+}
+
+#[cfg(all(test, feature = "memory"))]
+mod tests {
+    use super::*;
+
+    use crate::store::memory::MemoryStoreFactory;
+
+    /// Verify that `migrate_data` copies the data of every store into empty destination stores.
+    ///
+    /// 1. Create a source and destination `MemoryStoreFactory`.
+    /// 2. Add a circuit, a registry node, durable messages, audit entries, Biome credentials with a
+    ///    refresh token, a key and a notification, an OAuth session, a role with an assignment and
+    ///    a group mapping and an API key to the source stores (depending on the enabled features).
+    /// 3. Migrate the data and verify that every entry exists in the destination stores.
+    /// 4. Verify that the report contains a count for the copied data.
+    #[test]
+    fn test_migrate_data() {
+        let from = MemoryStoreFactory::new().expect("Unable to create source factory");
+        let to = MemoryStoreFactory::new().expect("Unable to create destination factory");
+
+        #[cfg(feature = "admin-service")]
+        {
+            let circuit = create_circuit();
+            let nodes = circuit.members().to_vec();
+            from.get_admin_service_store()
+                .add_circuit(circuit, nodes)
+                .expect("Unable to add circuit");
+        }
+
+        #[cfg(feature = "registry")]
+        from.get_registry_store()
+            .add_node(create_node())
+            .expect("Unable to add node");
+
+        #[cfg(feature = "circuit-durability")]
+        {
+            let durable_store = from.get_durable_message_store();
+            for _ in 0..2 {
+                durable_store
+                    .add_message("node-001", "ABCDE-01234", b"payload".to_vec())
+                    .expect("Unable to add durable message");
+            }
+            durable_store
+                .remove_messages("node-001", 1)
+                .expect("Unable to remove durable message");
+        }
+
+        #[cfg(feature = "audit")]
+        {
+            use crate::audit::{AuditEntryBuilder, AuditResult};
+
+            let audit_store = from.get_audit_store();
+            for endpoint in &["/admin/circuits", "/registry/nodes"] {
+                audit_store
+                    .add_entry(
+                        AuditEntryBuilder::new()
+                            .with_method("GET".into())
+                            .with_endpoint(endpoint.to_string())
+                            .with_result(AuditResult::Allowed)
+                            .with_status_code(200)
+                            .build()
+                            .expect("Unable to build audit entry"),
+                    )
+                    .expect("Unable to add audit entry");
+            }
+        }
+
+        #[cfg(feature = "biome-credentials")]
+        from.get_biome_credentials_store()
+            .add_credentials(crate::biome::credentials::store::Credentials {
+                user_id: "user_id".into(),
+                username: "username".into(),
+                password: "hashed_password".into(),
+            })
+            .expect("Unable to add credentials");
+
+        #[cfg(feature = "biome-credentials")]
+        from.get_biome_refresh_token_store()
+            .add_token("user_id", "refresh_token")
+            .expect("Unable to add refresh token");
+
+        #[cfg(feature = "biome-key-management")]
+        from.get_biome_key_store()
+            .add_key(crate::biome::key_management::Key::new(
+                "public_key",
+                "encrypted_private_key",
+                "user_id",
+                "display_name",
+            ))
+            .expect("Unable to add key");
+
+        #[cfg(feature = "biome-notifications")]
+        from.get_biome_notification_store()
+            .add_notification(
+                crate::biome::notifications::store::NotificationBuilder::new()
+                    .with_id("notification_id".into())
+                    .with_user_id("user_id".into())
+                    .with_title("Circuit proposal".into())
+                    .with_property("circuit_id".into(), "ABCDE-01234".into())
+                    .build()
+                    .expect("Unable to build notification"),
+            )
+            .expect("Unable to add notification");
+
+        #[cfg(feature = "oauth")]
+        from.get_biome_oauth_user_session_store()
+            .add_session(
+                crate::biome::oauth::store::InsertableOAuthUserSessionBuilder::new()
+                    .with_splinter_access_token("splinter_access_token".into())
+                    .with_subject("subject".into())
+                    .with_oauth_access_token("oauth_access_token".into())
+                    .build()
+                    .expect("Unable to build session"),
+            )
+            .expect("Unable to add session");
+
+        #[cfg(feature = "authorization-handler-rbac")]
+        {
+            use crate::rest_api::auth::authorization::rbac::store::{
+                AssignmentBuilder, Identity, RoleBuilder,
+            };
+
+            let rbac_store = from.get_role_based_authorization_store();
+            rbac_store
+                .add_role(
+                    RoleBuilder::new()
+                        .with_id("circuit_reader".into())
+                        .with_display_name("Circuit reader".into())
+                        .with_permissions(vec!["circuit.read".into()])
+                        .build()
+                        .expect("Unable to build role"),
+                )
+                .expect("Unable to add role");
+            rbac_store
+                .add_assignment(
+                    AssignmentBuilder::new()
+                        .with_identity(Identity::Key("public_key".into()))
+                        .with_roles(vec!["circuit_reader".into()])
+                        .build()
+                        .expect("Unable to build assignment"),
+                )
+                .expect("Unable to add assignment");
+
+            #[cfg(feature = "authorization-handler-rbac-groups")]
+            rbac_store
+                .set_group_mapping(
+                    crate::rest_api::auth::authorization::rbac::store::GroupMappingBuilder::new()
+                        .with_group_id("readers".into())
+                        .with_roles(vec!["circuit_reader".into()])
+                        .build()
+                        .expect("Unable to build group mapping"),
+                )
+                .expect("Unable to set group mapping");
+        }
+
+        #[cfg(feature = "api-keys")]
+        from.get_api_key_store()
+            .add_api_key(
+                crate::rest_api::auth::api_key::ApiKeyBuilder::new()
+                    .with_id("api_key_id".into())
+                    .with_display_name("Deploy key".into())
+                    .with_secret_hash("secret_hash".into())
+                    .with_scopes(vec!["circuit.read".into()])
+                    .build()
+                    .expect("Unable to build API key"),
+            )
+            .expect("Unable to add API key");
+
+        let report = migrate_data(&from, &to).expect("Unable to migrate data");
+
+        #[cfg(feature = "admin-service")]
+        {
+            assert!(to
+                .get_admin_service_store()
+                .get_circuit("ABCDE-01234")
+                .expect("Unable to get circuit")
+                .is_some());
+            assert!(report.counts().contains(&("circuits", 1)));
+        }
+
+        #[cfg(feature = "registry")]
+        {
+            assert!(to
+                .get_registry_store()
+                .has_node("node-000")
+                .expect("Unable to check node"));
+            assert!(report.counts().contains(&("registry nodes", 1)));
+        }
+
+        #[cfg(feature = "circuit-durability")]
+        {
+            let durable_store = to.get_durable_message_store();
+            let messages = durable_store
+                .list_messages("node-001")
+                .expect("Unable to list durable messages");
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].sequence_number(), 2);
+            assert!(report.counts().contains(&("durable messages", 1)));
+        }
+
+        #[cfg(feature = "audit")]
+        {
+            let entries = to
+                .get_audit_store()
+                .list_entries(0, 10)
+                .expect("Unable to list audit entries");
+            assert_eq!(
+                entries
+                    .iter()
+                    .map(|entry| entry.endpoint())
+                    .collect::<Vec<_>>(),
+                vec!["/registry/nodes", "/admin/circuits"]
+            );
+        }
+
+        #[cfg(feature = "biome-credentials")]
+        {
+            let credentials = to
+                .get_biome_credentials_store()
+                .fetch_credential_by_user_id("user_id")
+                .expect("Unable to fetch credentials");
+            assert_eq!(credentials.password, "hashed_password");
+            assert_eq!(
+                to.get_biome_refresh_token_store()
+                    .fetch_token("user_id")
+                    .expect("Unable to fetch refresh token"),
+                "refresh_token"
+            );
+            assert!(report.counts().contains(&("Biome refresh tokens", 1)));
+        }
+
+        #[cfg(feature = "biome-key-management")]
+        assert_eq!(
+            to.get_biome_key_store()
+                .list_keys(Some("user_id"))
+                .expect("Unable to list keys")
+                .len(),
+            1
+        );
+
+        #[cfg(feature = "biome-notifications")]
+        {
+            let notification = to
+                .get_biome_notification_store()
+                .get_notification("user_id", "notification_id")
+                .expect("Unable to get notification")
+                .expect("Notification not found");
+            assert_eq!(
+                notification.properties().get("circuit_id"),
+                Some(&"ABCDE-01234".to_string())
+            );
+            assert!(report.counts().contains(&("Biome notifications", 1)));
+        }
+
+        #[cfg(feature = "oauth")]
+        {
+            let source_user = from
+                .get_biome_oauth_user_session_store()
+                .get_user("subject")
+                .expect("Unable to get source user")
+                .expect("Source user not found");
+            let session = to
+                .get_biome_oauth_user_session_store()
+                .get_session("splinter_access_token")
+                .expect("Unable to get session")
+                .expect("Session not found");
+            assert_eq!(session.user().user_id(), source_user.user_id());
+        }
+
+        #[cfg(feature = "authorization-handler-rbac")]
+        {
+            let rbac_store = to.get_role_based_authorization_store();
+            assert!(rbac_store
+                .get_role("circuit_reader")
+                .expect("Unable to get role")
+                .is_some());
+            assert_eq!(
+                rbac_store
+                    .list_assignments()
+                    .expect("Unable to list assignments")
+                    .len(),
+                1
+            );
+            #[cfg(feature = "authorization-handler-rbac-groups")]
+            assert_eq!(
+                rbac_store
+                    .get_group_mapping("readers")
+                    .expect("Unable to get group mapping")
+                    .expect("Group mapping not found")
+                    .roles(),
+                &["circuit_reader".to_string()]
+            );
+        }
+
+        #[cfg(feature = "api-keys")]
+        {
+            let api_key = to
+                .get_api_key_store()
+                .get_api_key("api_key_id")
+                .expect("Unable to get API key")
+                .expect("API key not found");
+            assert_eq!(api_key.secret_hash(), "secret_hash");
+            assert_eq!(api_key.scopes(), &["circuit.read".to_string()]);
+        }
+    }
+
+    /// Verify that `migrate_data` refuses to copy into a destination that already contains data,
+    /// and that nothing is copied in that case.
+    #[cfg(all(feature = "registry", feature = "biome-credentials"))]
+    #[test]
+    fn test_migrate_data_destination_not_empty() {
+        let from = MemoryStoreFactory::new().expect("Unable to create source factory");
+        let to = MemoryStoreFactory::new().expect("Unable to create destination factory");
+
+        from.get_biome_credentials_store()
+            .add_credentials(crate::biome::credentials::store::Credentials {
+                user_id: "user_id".into(),
+                username: "username".into(),
+                password: "hashed_password".into(),
+            })
+            .expect("Unable to add credentials");
+        to.get_registry_store()
+            .add_node(create_node())
+            .expect("Unable to add node");
+
+        assert!(matches!(
+            migrate_data(&from, &to),
+            Err(StoreMigrationError::InvalidState(_))
+        ));
+        assert!(to
+            .get_biome_credentials_store()
+            .list_usernames()
+            .expect("Unable to list usernames")
+            .is_empty());
+    }
+
+    /// Verify that `resume_data_migration` continues a migration into a destination that already
+    /// contains part of the source's data.
+    ///
+    /// 1. Add two registry nodes and two credentials users to the source stores.
+    /// 2. Copy one node and one user to the destination, as if a migration had failed partway.
+    /// 3. Verify that `migrate_data` refuses the destination, but `resume_data_migration` copies
+    ///    the remaining entries without duplicating the existing ones.
+    #[cfg(all(feature = "registry", feature = "biome-credentials"))]
+    #[test]
+    fn test_resume_data_migration() {
+        let from = MemoryStoreFactory::new().expect("Unable to create source factory");
+        let to = MemoryStoreFactory::new().expect("Unable to create destination factory");
+
+        let second_node = crate::registry::Node::builder("node-001")
+            .with_endpoint("tcps://127.0.0.1:8045")
+            .with_display_name("Node 1")
+            .with_key("4567")
+            .build()
+            .expect("Unable to build node");
+        let credentials = |user_id: &str| crate::biome::credentials::store::Credentials {
+            user_id: user_id.into(),
+            username: format!("{}_name", user_id),
+            password: "hashed_password".into(),
+        };
+
+        let registry = from.get_registry_store();
+        registry
+            .add_node(create_node())
+            .expect("Unable to add node");
+        registry.add_node(second_node).expect("Unable to add node");
+        let credentials_store = from.get_biome_credentials_store();
+        credentials_store
+            .add_credentials(credentials("user_0"))
+            .expect("Unable to add credentials");
+        credentials_store
+            .add_credentials(credentials("user_1"))
+            .expect("Unable to add credentials");
+
+        to.get_registry_store()
+            .add_node(create_node())
+            .expect("Unable to add node");
+        to.get_biome_credentials_store()
+            .add_credentials(credentials("user_0"))
+            .expect("Unable to add credentials");
+
+        assert!(matches!(
+            migrate_data(&from, &to),
+            Err(StoreMigrationError::InvalidState(_))
+        ));
+
+        let report = resume_data_migration(&from, &to).expect("Unable to resume migration");
+
+        assert!(report.counts().contains(&("registry nodes", 2)));
+        assert!(to
+            .get_registry_store()
+            .has_node("node-001")
+            .expect("Unable to check node"));
+        assert_eq!(
+            to.get_biome_credentials_store()
+                .list_usernames()
+                .expect("Unable to list usernames")
+                .len(),
+            2
+        );
+    }
+
+    #[cfg(feature = "registry")]
+    fn create_node() -> crate::registry::Node {
+        crate::registry::Node::builder("node-000")
+            .with_endpoint("tcps://127.0.0.1:8044")
+            .with_display_name("Node 0")
+            .with_key("0123")
+            .build()
+            .expect("Unable to build node")
+    }
+
+    #[cfg(feature = "admin-service")]
+    fn create_circuit() -> crate::admin::store::Circuit {
+        use crate::admin::store::{CircuitBuilder, CircuitNodeBuilder, ServiceBuilder};
+
+        CircuitBuilder::default()
+            .with_circuit_id("ABCDE-01234")
+            .with_roster(&[ServiceBuilder::default()
+                .with_service_id("a000")
+                .with_service_type("scabbard")
+                .with_node_id("node-000")
+                .build()
+                .expect("Unable to build service")])
+            .with_members(&[CircuitNodeBuilder::default()
+                .with_node_id("node-000")
+                .with_endpoints(&["tcps://127.0.0.1:8044".into()])
+                .build()
+                .expect("Unable to build node")])
+            .with_circuit_management_type("test")
+            .build()
+            .expect("Unable to build circuit")
+    }
+}
@@ -16,6 +16,8 @@
 //! backed by a single storage mechanism (e.g. database)
 #[cfg(feature = "memory")]
 pub mod memory;
+#[cfg(feature = "store-migration")]
+pub mod migration;
 #[cfg(feature = "postgres")]
 pub mod postgres;
 #[cfg(feature = "sqlite")]
@@ -34,6 +36,14 @@ use crate::error::{InternalError, InvalidArgumentError};
 
 /// An abstract factory for creating Splinter stores backed by the same storage
 pub trait StoreFactory {
+    /// Get a new `ApiKeyStore`
+    #[cfg(feature = "api-keys")]
+    fn get_api_key_store(&self) -> Box<dyn crate::rest_api::auth::api_key::ApiKeyStore>;
+
+    /// Get a new `AuditStore`
+    #[cfg(feature = "audit")]
+    fn get_audit_store(&self) -> Box<dyn crate::audit::AuditStore>;
+
     /// Get a new `CredentialsStore`
     #[cfg(feature = "biome-credentials")]
     fn get_biome_credentials_store(&self) -> Box<dyn crate::biome::CredentialsStore>;
@@ -42,6 +52,10 @@ pub trait StoreFactory {
     #[cfg(feature = "biome-key-management")]
     fn get_biome_key_store(&self) -> Box<dyn crate::biome::KeyStore>;
 
+    /// Get a new `NotificationStore`
+    #[cfg(feature = "biome-notifications")]
+    fn get_biome_notification_store(&self) -> Box<dyn crate::biome::NotificationStore>;
+
     /// Get a new `RefreshTokenStore`
     #[cfg(feature = "biome-credentials")]
     fn get_biome_refresh_token_store(&self) -> Box<dyn crate::biome::RefreshTokenStore>;
@@ -53,6 +67,10 @@ pub trait StoreFactory {
     #[cfg(feature = "admin-service")]
     fn get_admin_service_store(&self) -> Box<dyn crate::admin::store::AdminServiceStore>;
 
+    /// Get a new `DurableMessageStore`
+    #[cfg(feature = "circuit-durability")]
+    fn get_durable_message_store(&self) -> Box<dyn crate::circuit::durable::DurableMessageStore>;
+
     #[cfg(feature = "oauth")]
     fn get_oauth_inflight_request_store(
         &self,
@@ -23,18 +23,39 @@ use diesel::{
 use super::StoreFactory;
 
 /// A `StoreFactory` backed by a SQLite database.
+///
+/// The registry stores returned by the factory are clones of a single registry, so that changes
+/// made through any of them are reported to the subscribers of all of them.
 pub struct SqliteStoreFactory {
     pool: Pool<ConnectionManager<SqliteConnection>>,
+    #[cfg(feature = "registry")]
+    registry: crate::registry::DieselRegistry<SqliteConnection>,
 }
 
 impl SqliteStoreFactory {
     /// Create a new `SqliteStoreFactory`.
     pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
-        Self { pool }
+        Self {
+            #[cfg(feature = "registry")]
+            registry: crate::registry::DieselRegistry::new(pool.clone()),
+            pool,
+        }
     }
 }
 
 impl StoreFactory for SqliteStoreFactory {
+    #[cfg(feature = "api-keys")]
+    fn get_api_key_store(&self) -> Box<dyn crate::rest_api::auth::api_key::ApiKeyStore> {
+        Box::new(crate::rest_api::auth::api_key::DieselApiKeyStore::new(
+            self.pool.clone(),
+        ))
+    }
+
+    #[cfg(feature = "audit")]
+    fn get_audit_store(&self) -> Box<dyn crate::audit::AuditStore> {
+        Box::new(crate::audit::DieselAuditStore::new(self.pool.clone()))
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_credentials_store(&self) -> Box<dyn crate::biome::CredentialsStore> {
         Box::new(crate::biome::DieselCredentialsStore::new(self.pool.clone()))
@@ -45,6 +66,13 @@ impl StoreFactory for SqliteStoreFactory {
         Box::new(crate::biome::DieselKeyStore::new(self.pool.clone()))
     }
 
+    #[cfg(feature = "biome-notifications")]
+    fn get_biome_notification_store(&self) -> Box<dyn crate::biome::NotificationStore> {
+        Box::new(crate::biome::DieselNotificationStore::new(
+            self.pool.clone(),
+        ))
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_refresh_token_store(&self) -> Box<dyn crate::biome::RefreshTokenStore> {
         Box::new(crate::biome::DieselRefreshTokenStore::new(
@@ -66,6 +94,13 @@ impl StoreFactory for SqliteStoreFactory {
         ))
     }
 
+    #[cfg(feature = "circuit-durability")]
+    fn get_durable_message_store(&self) -> Box<dyn crate::circuit::durable::DurableMessageStore> {
+        Box::new(crate::circuit::durable::DieselDurableMessageStore::new(
+            self.pool.clone(),
+        ))
+    }
+
     #[cfg(feature = "oauth")]
     fn get_oauth_inflight_request_store(
         &self,
@@ -77,7 +112,7 @@ impl StoreFactory for SqliteStoreFactory {
 
     #[cfg(feature = "registry")]
     fn get_registry_store(&self) -> Box<dyn crate::registry::RwRegistry> {
-        Box::new(crate::registry::DieselRegistry::new(self.pool.clone()))
+        Box::new(self.registry.clone())
     }
 
     #[cfg(feature = "authorization-handler-rbac")]
@@ -39,6 +39,8 @@ mod error;
 pub mod inproc;
 pub(crate) mod matrix;
 pub mod multi;
+#[cfg(feature = "quic-transport")]
+pub mod quic;
 #[deprecated(since = "0.3.14", note = "please use splinter::transport::socket")]
 pub mod raw;
 pub mod socket;
@@ -80,6 +82,56 @@ pub trait Connection: Send {
 
     /// Returns a `mio::event::Evented` for this connection which can be used for polling.
     fn evented(&self) -> &dyn Evented;
+
+    /// Returns the number of message bytes sent and received over this connection, if the
+    /// connection type tracks them.
+    #[cfg(feature = "transport-compression")]
+    fn stats(&self) -> Option<ConnectionStats> {
+        None
+    }
+}
+
+/// Counts of the message bytes sent and received over a connection.
+///
+/// Raw counts are the sizes of the messages passed to `send` and returned by `recv`. Compressed
+/// counts are the sizes of the message data written to and read from the connection, after
+/// compression and excluding any framing; they equal the raw counts for uncompressed messages.
+#[cfg(feature = "transport-compression")]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ConnectionStats {
+    raw_bytes_sent: u64,
+    compressed_bytes_sent: u64,
+    raw_bytes_received: u64,
+    compressed_bytes_received: u64,
+}
+
+#[cfg(feature = "transport-compression")]
+impl ConnectionStats {
+    pub fn raw_bytes_sent(&self) -> u64 {
+        self.raw_bytes_sent
+    }
+
+    pub fn compressed_bytes_sent(&self) -> u64 {
+        self.compressed_bytes_sent
+    }
+
+    pub fn raw_bytes_received(&self) -> u64 {
+        self.raw_bytes_received
+    }
+
+    pub fn compressed_bytes_received(&self) -> u64 {
+        self.compressed_bytes_received
+    }
+
+    pub(crate) fn record_sent(&mut self, raw: usize, compressed: usize) {
+        self.raw_bytes_sent += raw as u64;
+        self.compressed_bytes_sent += compressed as u64;
+    }
+
+    pub(crate) fn record_received(&mut self, raw: usize, compressed: usize) {
+        self.raw_bytes_received += raw as u64;
+        self.compressed_bytes_received += compressed as u64;
+    }
 }
 
 pub trait Listener: Send {
@@ -90,6 +90,23 @@ mod tests {
         assert!(!transport.accepts("foo://127.0.0.1:0"));
     }
 
+    /// Test that the MultiTransport routes `unix://` endpoints to the Unix domain socket
+    /// transport, with the standard transport tests.
+    #[cfg(feature = "unix-transport")]
+    #[test]
+    fn test_transport_unix_listener() {
+        let temp_dir = tempdir::TempDir::new("test_multi_unix").unwrap();
+        let raw_transport = Box::new(socket::TcpTransport::default());
+        let unix_transport = Box::new(socket::UnixTransport::default());
+
+        let transport = MultiTransport::new(vec![raw_transport, unix_transport]);
+        assert!(transport.accepts("unix:///tmp/splinter.sock"));
+        tests::test_transport(
+            transport,
+            &format!("unix://{}", temp_dir.path().join("multi.sock").display()),
+        );
+    }
+
     /// Test MultiTransport using a raw transport for the listening endpoint, with the standard
     /// transport tests.
     #[test]
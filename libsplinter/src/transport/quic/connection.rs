@@ -0,0 +1,324 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use mio::{Evented, Ready, Registration, SetReadiness};
+use mio_extras::channel::{self, Sender};
+
+use crate::transport::{Connection, DisconnectError, RecvError, SendError};
+
+use super::driver::LENGTH_PREFIX_SIZE;
+
+/// The size of the largest message that may be sent or received on a connection.
+pub(super) const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Once this many bytes of received messages are queued, the driver stops reading from the
+/// stream until some are received, so that QUIC flow control pushes back on the remote endpoint.
+const MAX_INBOUND_QUEUE_SIZE: usize = 8 * 1024 * 1024;
+
+/// Once this many bytes of messages are waiting to be written to the stream, `send` returns
+/// `SendError::WouldBlock` and the connection stops being writable.
+const MAX_OUTBOUND_QUEUE_SIZE: usize = 8 * 1024 * 1024;
+
+/// Commands sent from a `QuicConnection` to the thread driving the underlying QUIC connection.
+pub(super) enum Command {
+    Send(Vec<u8>),
+    /// Wakes the driver after reading from the stream was paused for a full inbound queue
+    Resume,
+    Disconnect,
+}
+
+/// The state shared between a `QuicConnection` and the thread driving it.
+///
+/// Messages received by the driver are queued here until they are read by `recv`, and the size of
+/// the messages sent but not yet written to the stream is tracked here to bound the driver's write
+/// buffer. The readiness of the connection's `Registration` is always updated while the lock is
+/// held, so that the driver cannot race with `recv` or `send` changing the readiness.
+pub(super) struct Shared {
+    state: Mutex<State>,
+    set_readiness: SetReadiness,
+}
+
+struct State {
+    messages: VecDeque<Vec<u8>>,
+    inbound_bytes: usize,
+    outbound_bytes: usize,
+    read_paused: bool,
+    closed: bool,
+}
+
+impl State {
+    fn readiness(&self) -> Ready {
+        let mut ready = Ready::empty();
+        // Readable when closed, so that a poller will call recv and find out the connection is gone
+        if !self.messages.is_empty() || self.closed {
+            ready |= Ready::readable();
+        }
+        if self.outbound_bytes < MAX_OUTBOUND_QUEUE_SIZE || self.closed {
+            ready |= Ready::writable();
+        }
+        ready
+    }
+}
+
+impl Shared {
+    fn new(set_readiness: SetReadiness) -> Self {
+        Shared {
+            state: Mutex::new(State {
+                messages: VecDeque::new(),
+                inbound_bytes: 0,
+                outbound_bytes: 0,
+                read_paused: false,
+                closed: false,
+            }),
+            set_readiness,
+        }
+    }
+
+    /// Queues a message received from the remote endpoint.
+    pub fn push(&self, message: Vec<u8>) {
+        self.update(|state| {
+            state.inbound_bytes += message.len();
+            state.messages.push_back(message);
+        })
+    }
+
+    /// Returns `true` if the driver may read more messages from the stream. If it may not, the
+    /// next `recv` that makes room wakes the driver with `Command::Resume`.
+    pub fn can_read(&self) -> bool {
+        match self.state.lock() {
+            Ok(mut state) => {
+                state.read_paused = state.inbound_bytes >= MAX_INBOUND_QUEUE_SIZE;
+                !state.read_paused
+            }
+            Err(_) => {
+                error!("QUIC connection state lock was poisoned");
+                false
+            }
+        }
+    }
+
+    /// Releases the given number of bytes of sent messages once they were written to the stream.
+    pub fn release_outbound(&self, bytes: usize) {
+        self.update(|state| {
+            state.outbound_bytes = state.outbound_bytes.saturating_sub(bytes);
+        })
+    }
+
+    /// Marks the connection as closed. Any queued messages may still be received.
+    pub fn close(&self) {
+        self.update(|state| state.closed = true)
+    }
+
+    /// Reserves room for a message of the given size in the outbound queue.
+    ///
+    /// A message is always accepted when nothing is queued, so that a message larger than the
+    /// queue can still be sent.
+    fn reserve_outbound(&self, bytes: usize) -> Result<(), SendError> {
+        let mut state = self.state.lock().map_err(|_| {
+            SendError::ProtocolError("QUIC connection state lock was poisoned".into())
+        })?;
+
+        if state.closed {
+            return Err(SendError::Disconnected);
+        }
+
+        if state.outbound_bytes > 0 && state.outbound_bytes + bytes > MAX_OUTBOUND_QUEUE_SIZE {
+            return Err(SendError::WouldBlock);
+        }
+
+        state.outbound_bytes += bytes;
+        self.set_ready(state.readiness());
+        Ok(())
+    }
+
+    /// Returns the next received message, along with whether the driver must be woken because
+    /// reading was paused and the queue now has room.
+    fn pop(&self) -> Result<(Vec<u8>, bool), RecvError> {
+        let mut state = self.state.lock().map_err(|_| {
+            RecvError::ProtocolError("QUIC connection state lock was poisoned".into())
+        })?;
+
+        match state.messages.pop_front() {
+            Some(message) => {
+                state.inbound_bytes -= message.len();
+                let resume = state.read_paused && state.inbound_bytes < MAX_INBOUND_QUEUE_SIZE;
+                if resume {
+                    state.read_paused = false;
+                }
+                self.set_ready(state.readiness());
+                Ok((message, resume))
+            }
+            None if state.closed => Err(RecvError::Disconnected),
+            None => Err(RecvError::WouldBlock),
+        }
+    }
+
+    fn update<F: FnOnce(&mut State)>(&self, f: F) {
+        match self.state.lock() {
+            Ok(mut state) => {
+                f(&mut state);
+                self.set_ready(state.readiness());
+            }
+            Err(_) => error!("QUIC connection state lock was poisoned"),
+        }
+    }
+
+    fn set_ready(&self, ready: Ready) {
+        if let Err(err) = self.set_readiness.set_readiness(ready) {
+            error!("Unable to set QUIC connection readiness: {}", err);
+        }
+    }
+}
+
+/// A `Connection` backed by a single bidirectional QUIC stream.
+///
+/// The QUIC connection itself is owned by a background thread, which is handed outbound messages
+/// over a channel and queues inbound messages in the shared state. Dropping the connection closes
+/// the underlying QUIC connection.
+pub(super) struct QuicConnection {
+    commands: Sender<Command>,
+    shared: Arc<Shared>,
+    registration: Registration,
+    remote_endpoint: String,
+    local_endpoint: String,
+}
+
+impl QuicConnection {
+    /// Creates a new connection handle, returning it along with the command receiver and the
+    /// shared state to be given to the thread driving the QUIC connection.
+    pub fn new(
+        remote_endpoint: String,
+        local_endpoint: String,
+    ) -> (Self, channel::Receiver<Command>, Arc<Shared>) {
+        let (registration, set_readiness) = Registration::new2();
+        let shared = Arc::new(Shared::new(set_readiness));
+        // A new connection has nothing to receive and room to send
+        shared.set_ready(Ready::writable());
+
+        let (commands, command_receiver) = channel::channel();
+
+        (
+            QuicConnection {
+                commands,
+                shared: shared.clone(),
+                registration,
+                remote_endpoint,
+                local_endpoint,
+            },
+            command_receiver,
+            shared,
+        )
+    }
+}
+
+impl Connection for QuicConnection {
+    fn send(&mut self, message: &[u8]) -> Result<(), SendError> {
+        if message.len() > MAX_MESSAGE_SIZE {
+            return Err(SendError::ProtocolError(format!(
+                "Message of {} bytes is too large to send",
+                message.len()
+            )));
+        }
+
+        let framed_len = message.len() + LENGTH_PREFIX_SIZE;
+        self.shared.reserve_outbound(framed_len)?;
+
+        self.commands
+            .send(Command::Send(message.to_vec()))
+            .map_err(|err| {
+                self.shared.release_outbound(framed_len);
+                match err {
+                    channel::SendError::Io(err) => SendError::from(err),
+                    channel::SendError::Disconnected(_) => SendError::Disconnected,
+                }
+            })
+    }
+
+    fn recv(&mut self) -> Result<Vec<u8>, RecvError> {
+        let (message, resume) = self.shared.pop()?;
+        if resume {
+            // If the driver is gone, the connection is closed and there is nothing left to read
+            let _ = self.commands.send(Command::Resume);
+        }
+        Ok(message)
+    }
+
+    fn remote_endpoint(&self) -> String {
+        self.remote_endpoint.clone()
+    }
+
+    fn local_endpoint(&self) -> String {
+        self.local_endpoint.clone()
+    }
+
+    fn disconnect(&mut self) -> Result<(), DisconnectError> {
+        match self.commands.send(Command::Disconnect) {
+            Ok(()) | Err(channel::SendError::Disconnected(_)) => Ok(()),
+            Err(channel::SendError::Io(err)) => Err(DisconnectError::from(err)),
+        }
+    }
+
+    fn evented(&self) -> &dyn Evented {
+        &self.registration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that sends are refused with `WouldBlock` once the outbound queue is full, and are
+    /// accepted again once the driver has written the queued messages to the stream.
+    #[test]
+    fn outbound_backpressure() {
+        let (mut connection, _commands, shared) =
+            QuicConnection::new("quic://remote".into(), "quic://local".into());
+
+        let message = vec![0; 1024 * 1024];
+        let mut sent = 0;
+        loop {
+            match connection.send(&message) {
+                Ok(()) => sent += 1,
+                Err(SendError::WouldBlock) => break,
+                Err(err) => panic!("Unexpected send error: {}", err),
+            }
+        }
+        assert!(sent > 0);
+
+        shared.release_outbound(message.len() + LENGTH_PREFIX_SIZE);
+        connection
+            .send(&message)
+            .expect("Unable to send after the queue was drained");
+    }
+
+    /// Verify that reading is paused once the inbound queue is full, and that receiving a message
+    /// resumes it.
+    #[test]
+    fn inbound_backpressure() {
+        let (mut connection, commands, shared) =
+            QuicConnection::new("quic://remote".into(), "quic://local".into());
+
+        let message = vec![0; 1024 * 1024];
+        while shared.can_read() {
+            shared.push(message.clone());
+        }
+
+        assert_eq!(message, connection.recv().expect("Unable to receive"));
+        assert!(matches!(commands.try_recv(), Ok(Command::Resume)));
+        assert!(shared.can_read());
+    }
+}
@@ -0,0 +1,434 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The state machine that moves messages between a `QuicConnection` and its QUIC connection.
+//!
+//! Messages are written to the connection's single bidirectional stream, each prefixed with its
+//! length as a big-endian `u32`. The client opens the stream by writing a one byte frame version,
+//! which the server must recognize before it writes anything to the stream.
+
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use mio::net::UdpSocket;
+use mio::{Events, Poll, PollOpt, Ready, Token};
+use mio_extras::channel::{Receiver, TryRecvError};
+use rand::Rng;
+
+use crate::transport::ConnectError;
+
+use super::connection::{Command, QuicConnection, Shared, MAX_MESSAGE_SIZE};
+
+/// The ID of the client-initiated bidirectional stream that carries messages.
+const STREAM_ID: u64 = 0;
+
+/// The version of the message framing written to the stream.
+const FRAME_VERSION: u8 = 1;
+
+pub(super) const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// The maximum size of a UDP datagram sent by either side of a connection.
+pub(super) const MAX_DATAGRAM_SIZE: usize = 1350;
+
+/// The size of the buffers used to receive UDP datagrams and stream data.
+pub(super) const RECV_BUFFER_SIZE: usize = 65535;
+
+const SOCKET_TOKEN: Token = Token(0);
+const COMMANDS_TOKEN: Token = Token(1);
+
+/// Generates a random source connection ID.
+pub(super) fn new_connection_id() -> Vec<u8> {
+    let mut id = vec![0; quiche::MAX_CONN_ID_LEN];
+    rand::thread_rng().fill(&mut id[..]);
+    id
+}
+
+pub(super) struct ConnectionDriver {
+    conn: Pin<Box<quiche::Connection>>,
+    peer_addr: SocketAddr,
+    commands: Receiver<Command>,
+    shared: Arc<Shared>,
+    stream_open: bool,
+    read_buffer: Vec<u8>,
+    write_buffer: Vec<u8>,
+    // The number of bytes at the front of the write buffer that were not reserved by
+    // `QuicConnection::send`, which is the frame version written by the client
+    unreserved_bytes: usize,
+}
+
+impl ConnectionDriver {
+    /// Creates a driver for a connection that was initiated locally, along with its handle.
+    pub fn client(
+        conn: Pin<Box<quiche::Connection>>,
+        peer_addr: SocketAddr,
+        remote_endpoint: String,
+        local_endpoint: String,
+    ) -> (Self, QuicConnection) {
+        let (handle, commands, shared) = QuicConnection::new(remote_endpoint, local_endpoint);
+        (
+            ConnectionDriver {
+                conn,
+                peer_addr,
+                commands,
+                shared,
+                // The client owns the stream, so it may write as soon as the handshake completes
+                stream_open: true,
+                read_buffer: vec![],
+                write_buffer: vec![FRAME_VERSION],
+                unreserved_bytes: 1,
+            },
+            handle,
+        )
+    }
+
+    /// Creates a driver for a connection that was accepted by a listener, along with its handle.
+    pub fn server(
+        conn: Pin<Box<quiche::Connection>>,
+        peer_addr: SocketAddr,
+        remote_endpoint: String,
+        local_endpoint: String,
+    ) -> (Self, QuicConnection) {
+        let (handle, commands, shared) = QuicConnection::new(remote_endpoint, local_endpoint);
+        (
+            ConnectionDriver {
+                conn,
+                peer_addr,
+                commands,
+                shared,
+                stream_open: false,
+                read_buffer: vec![],
+                write_buffer: vec![],
+                unreserved_bytes: 0,
+            },
+            handle,
+        )
+    }
+
+    pub fn peer_addr(&self) -> &SocketAddr {
+        &self.peer_addr
+    }
+
+    pub fn commands(&self) -> &Receiver<Command> {
+        &self.commands
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.conn.timeout()
+    }
+
+    pub fn is_established(&self) -> bool {
+        self.conn.is_established()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.conn.is_closed()
+    }
+
+    /// Processes any expired timers; this is a no-op if none have expired.
+    pub fn on_timeout(&mut self) {
+        self.conn.on_timeout();
+    }
+
+    /// Passes a UDP datagram received from the peer to the QUIC connection.
+    pub fn recv_packet(&mut self, packet: &mut [u8]) {
+        if let Err(err) = self.conn.recv(packet) {
+            debug!(
+                "Unable to process QUIC packet from {}: {}",
+                self.peer_addr, err
+            );
+        }
+    }
+
+    /// Moves queued outbound messages onto the stream and inbound stream data into the queue of
+    /// received messages.
+    pub fn process(&mut self) {
+        self.handle_commands();
+
+        if !self.conn.is_established() {
+            return;
+        }
+
+        if let Err(err) = self.read_stream() {
+            error!("Closing QUIC connection to {}: {}", self.peer_addr, err);
+            self.close(false, err.as_bytes());
+            return;
+        }
+
+        if let Err(err) = self.write_stream() {
+            error!("Closing QUIC connection to {}: {}", self.peer_addr, err);
+            self.close(false, err.as_bytes());
+        }
+    }
+
+    /// Sends all packets the QUIC connection has ready, using the given function to write each
+    /// datagram to the socket.
+    pub fn send_packets<F>(&mut self, send: F, out: &mut [u8])
+    where
+        F: Fn(&[u8]) -> io::Result<usize>,
+    {
+        loop {
+            let written = match self.conn.send(out) {
+                Ok(written) => written,
+                Err(quiche::Error::Done) => break,
+                Err(err) => {
+                    error!(
+                        "Unable to create QUIC packet for {}: {}",
+                        self.peer_addr, err
+                    );
+                    self.close(false, b"internal error");
+                    break;
+                }
+            };
+
+            match send(&out[..written]) {
+                Ok(_) => (),
+                // The packet is dropped; QUIC will retransmit anything that is lost
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    error!("Unable to send QUIC packet to {}: {}", self.peer_addr, err);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Notifies the connection handle that the QUIC connection has closed.
+    pub fn shutdown(&self) {
+        self.shared.close();
+    }
+
+    fn handle_commands(&mut self) {
+        loop {
+            match self.commands.try_recv() {
+                Ok(Command::Send(message)) => {
+                    self.write_buffer
+                        .extend_from_slice(&(message.len() as u32).to_be_bytes());
+                    self.write_buffer.extend_from_slice(&message);
+                }
+                // Nothing to do; the stream is read after the commands are handled
+                Ok(Command::Resume) => (),
+                // The handle was dropped or disconnected, so close the connection
+                Ok(Command::Disconnect) | Err(TryRecvError::Disconnected) => {
+                    self.close(true, b"disconnect");
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+    }
+
+    fn read_stream(&mut self) -> Result<(), String> {
+        // The message stream is the only stream the remote endpoint is allowed to open
+        if !self.conn.readable().any(|stream_id| stream_id == STREAM_ID) {
+            return Ok(());
+        }
+
+        let mut buf = [0; RECV_BUFFER_SIZE];
+
+        // Unread data is left in the stream while the inbound queue is full, so that flow control
+        // stops the remote endpoint from sending more
+        while self.shared.can_read() {
+            match self.conn.stream_recv(STREAM_ID, &mut buf) {
+                Ok((read, fin)) => {
+                    self.read_buffer.extend_from_slice(&buf[..read]);
+                    self.read_messages()?;
+                    if fin {
+                        return Err("remote finished the message stream".into());
+                    }
+                }
+                Err(quiche::Error::Done) => break,
+                Err(err) => return Err(format!("unable to read stream: {}", err)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves each complete message in the read buffer to the queue of received messages.
+    fn read_messages(&mut self) -> Result<(), String> {
+        if !self.stream_open {
+            if self.read_buffer.is_empty() {
+                return Ok(());
+            }
+
+            let version = self.read_buffer.remove(0);
+            if version != FRAME_VERSION {
+                return Err(format!("unsupported frame version {}", version));
+            }
+            self.stream_open = true;
+        }
+
+        while self.read_buffer.len() >= LENGTH_PREFIX_SIZE {
+            let mut length = [0; LENGTH_PREFIX_SIZE];
+            length.copy_from_slice(&self.read_buffer[..LENGTH_PREFIX_SIZE]);
+            let length = u32::from_be_bytes(length) as usize;
+
+            if length > MAX_MESSAGE_SIZE {
+                return Err(format!(
+                    "message of {} bytes exceeds the maximum size of {} bytes",
+                    length, MAX_MESSAGE_SIZE
+                ));
+            }
+
+            let end = LENGTH_PREFIX_SIZE + length;
+            if self.read_buffer.len() < end {
+                break;
+            }
+
+            let message = self.read_buffer[LENGTH_PREFIX_SIZE..end].to_vec();
+            self.read_buffer.drain(..end);
+            self.shared.push(message);
+        }
+
+        Ok(())
+    }
+
+    fn write_stream(&mut self) -> Result<(), String> {
+        if !self.stream_open || self.write_buffer.is_empty() {
+            return Ok(());
+        }
+
+        match self.conn.stream_send(STREAM_ID, &self.write_buffer, false) {
+            Ok(written) => {
+                // Anything not written is retried once the peer extends flow control
+                self.write_buffer.drain(..written);
+
+                let unreserved = std::cmp::min(written, self.unreserved_bytes);
+                self.unreserved_bytes -= unreserved;
+                self.shared.release_outbound(written - unreserved);
+                Ok(())
+            }
+            Err(quiche::Error::Done) => Ok(()),
+            Err(err) => Err(format!("unable to write stream: {}", err)),
+        }
+    }
+
+    fn close(&mut self, app: bool, reason: &[u8]) {
+        match self.conn.close(app, 0, reason) {
+            Ok(()) | Err(quiche::Error::Done) => (),
+            Err(err) => error!(
+                "Unable to close QUIC connection to {}: {}",
+                self.peer_addr, err
+            ),
+        }
+        // The handle may no longer use the connection, even though it takes some time to close
+        self.shared.close();
+    }
+}
+
+/// Starts the thread driving a locally initiated connection and waits for its handshake to
+/// complete.
+pub(super) fn start_client(
+    socket: UdpSocket,
+    driver: ConnectionDriver,
+    handle: QuicConnection,
+) -> Result<QuicConnection, ConnectError> {
+    let (established_tx, established_rx) = mpsc::channel();
+
+    thread::Builder::new()
+        .name(format!("QuicConnection-{}", driver.peer_addr()))
+        .spawn(move || run_client(socket, driver, established_tx))?;
+
+    match established_rx.recv() {
+        Ok(Ok(())) => Ok(handle),
+        Ok(Err(msg)) => Err(ConnectError::ProtocolError(msg)),
+        Err(_) => Err(ConnectError::ProtocolError(
+            "QUIC connection thread exited before the handshake completed".into(),
+        )),
+    }
+}
+
+fn run_client(
+    socket: UdpSocket,
+    mut driver: ConnectionDriver,
+    established_tx: mpsc::Sender<Result<(), String>>,
+) {
+    let mut established_tx = Some(established_tx);
+
+    let poll = match setup_client_poll(&socket, &driver) {
+        Ok(poll) => poll,
+        Err(err) => {
+            if let Some(tx) = established_tx.take() {
+                let _ = tx.send(Err(format!("Unable to set up polling: {}", err)));
+            }
+            return;
+        }
+    };
+
+    let mut events = Events::with_capacity(16);
+    let mut buf = [0; RECV_BUFFER_SIZE];
+    let mut out = [0; MAX_DATAGRAM_SIZE];
+
+    // Send the initial handshake packet
+    driver.send_packets(|packet| socket.send(packet), &mut out);
+
+    loop {
+        if let Err(err) = poll.poll(&mut events, driver.timeout()) {
+            error!("Unable to poll QUIC connection: {}", err);
+            break;
+        }
+
+        driver.on_timeout();
+
+        loop {
+            match socket.recv(&mut buf) {
+                Ok(len) => driver.recv_packet(&mut buf[..len]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    error!("Unable to receive QUIC packet: {}", err);
+                    break;
+                }
+            }
+        }
+
+        if driver.is_established() {
+            if let Some(tx) = established_tx.take() {
+                // If the connecting thread is gone, the dropped handle closes the connection
+                let _ = tx.send(Ok(()));
+            }
+        }
+
+        driver.process();
+        driver.send_packets(|packet| socket.send(packet), &mut out);
+
+        if driver.is_closed() {
+            if let Some(tx) = established_tx.take() {
+                let _ = tx.send(Err(format!(
+                    "Connection to {} closed before the handshake completed",
+                    driver.peer_addr()
+                )));
+            }
+            break;
+        }
+    }
+
+    driver.shutdown();
+}
+
+fn setup_client_poll(socket: &UdpSocket, driver: &ConnectionDriver) -> io::Result<Poll> {
+    let poll = Poll::new()?;
+    poll.register(socket, SOCKET_TOKEN, Ready::readable(), PollOpt::edge())?;
+    poll.register(
+        driver.commands(),
+        COMMANDS_TOKEN,
+        Ready::readable(),
+        PollOpt::edge(),
+    )?;
+    Ok(poll)
+}
@@ -0,0 +1,396 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use mio::net::UdpSocket;
+use mio::{Events, Poll, PollOpt, Ready, Token};
+use mio_extras::channel::{self, TryRecvError};
+
+use crate::transport::{AcceptError, Connection, ListenError, Listener};
+
+use super::connection::QuicConnection;
+use super::driver::{new_connection_id, ConnectionDriver, MAX_DATAGRAM_SIZE, RECV_BUFFER_SIZE};
+use super::retry::RetryTokens;
+use super::transport::PROTOCOL_PREFIX;
+
+const SOCKET_TOKEN: Token = Token(0);
+const LISTENER_TOKEN: Token = Token(1);
+const FIRST_CONNECTION_TOKEN: usize = 2;
+
+/// A `Listener` for QUIC connections.
+///
+/// All connections accepted by a listener share its UDP socket, which is owned by a background
+/// thread. The thread keeps running after the listener is dropped until all of the connections
+/// it accepted have closed.
+///
+/// A client's address is validated with a Retry packet before a connection is created for it.
+/// The server config is shared with the transport, so connections accepted after it is reloaded
+/// use the new certificates and keys.
+pub(super) struct QuicListener {
+    accepted: mpsc::Receiver<QuicConnection>,
+    local_endpoint: String,
+    // Never sent on; the listener thread stops accepting connections once this is dropped
+    _alive: channel::Sender<()>,
+}
+
+impl QuicListener {
+    pub fn new(
+        socket: UdpSocket,
+        config: Arc<Mutex<quiche::Config>>,
+        local_endpoint: String,
+    ) -> Result<Self, ListenError> {
+        let retry_tokens = RetryTokens::new().map_err(|err| {
+            ListenError::ProtocolError(format!("Unable to create retry token key: {}", err))
+        })?;
+
+        let (accepted_tx, accepted) = mpsc::channel();
+        let (alive, alive_rx) = channel::channel();
+
+        let poll =
+            Poll::new().map_err(|err| ListenError::IoError("Unable to create poll".into(), err))?;
+        poll.register(&socket, SOCKET_TOKEN, Ready::readable(), PollOpt::edge())
+            .and_then(|_| {
+                poll.register(
+                    &alive_rx,
+                    LISTENER_TOKEN,
+                    Ready::readable(),
+                    PollOpt::edge(),
+                )
+            })
+            .map_err(|err| ListenError::IoError("Unable to register with poll".into(), err))?;
+
+        let mut driver = ListenerDriver {
+            socket,
+            poll,
+            config,
+            retry_tokens,
+            local_endpoint: local_endpoint.clone(),
+            connections: HashMap::new(),
+            connection_tokens: HashMap::new(),
+            next_token: FIRST_CONNECTION_TOKEN,
+            accepted_tx,
+            alive_rx,
+            accepting: true,
+        };
+
+        thread::Builder::new()
+            .name(format!("QuicListener-{}", local_endpoint))
+            .spawn(move || driver.run())
+            .map_err(|err| ListenError::IoError("Unable to start listener thread".into(), err))?;
+
+        Ok(QuicListener {
+            accepted,
+            local_endpoint,
+            _alive: alive,
+        })
+    }
+}
+
+impl Listener for QuicListener {
+    fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
+        self.accepted
+            .recv()
+            .map(|connection| Box::new(connection) as Box<dyn Connection>)
+            .map_err(|_| AcceptError::ProtocolError("QUIC listener thread has exited".into()))
+    }
+
+    fn endpoint(&self) -> String {
+        self.local_endpoint.clone()
+    }
+}
+
+struct AcceptedConnection {
+    driver: ConnectionDriver,
+    // Handed to the listener once the handshake has completed
+    handle: Option<QuicConnection>,
+    connection_ids: Vec<Vec<u8>>,
+}
+
+struct ListenerDriver {
+    socket: UdpSocket,
+    poll: Poll,
+    config: Arc<Mutex<quiche::Config>>,
+    retry_tokens: RetryTokens,
+    local_endpoint: String,
+    connections: HashMap<Token, AcceptedConnection>,
+    // Maps the connection ID chosen by this listener in its Retry packet to the connection
+    connection_tokens: HashMap<Vec<u8>, Token>,
+    next_token: usize,
+    accepted_tx: mpsc::Sender<QuicConnection>,
+    alive_rx: channel::Receiver<()>,
+    accepting: bool,
+}
+
+impl ListenerDriver {
+    fn run(&mut self) {
+        let mut events = Events::with_capacity(1024);
+        let mut buf = [0; RECV_BUFFER_SIZE];
+        let mut out = [0; MAX_DATAGRAM_SIZE];
+
+        loop {
+            if let Err(err) = self.poll.poll(&mut events, self.timeout()) {
+                error!(
+                    "Unable to poll QUIC listener {}: {}",
+                    self.local_endpoint, err
+                );
+                break;
+            }
+
+            for connection in self.connections.values_mut() {
+                connection.driver.on_timeout();
+            }
+
+            self.recv_packets(&mut buf, &mut out);
+
+            for connection in self.connections.values_mut() {
+                if connection.driver.is_established() {
+                    if let Some(handle) = connection.handle.take() {
+                        // If the listener is gone, the dropped handle closes the connection
+                        let _ = self.accepted_tx.send(handle);
+                    }
+                }
+
+                connection.driver.process();
+
+                let socket = &self.socket;
+                let peer_addr = *connection.driver.peer_addr();
+                connection
+                    .driver
+                    .send_packets(|packet| socket.send_to(packet, &peer_addr), &mut out);
+            }
+
+            self.remove_closed_connections();
+
+            if let Err(TryRecvError::Disconnected) = self.alive_rx.try_recv() {
+                self.accepting = false;
+            }
+
+            if !self.accepting && self.connections.is_empty() {
+                break;
+            }
+        }
+
+        for connection in self.connections.values() {
+            connection.driver.shutdown();
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.connections
+            .values()
+            .filter_map(|connection| connection.driver.timeout())
+            .min()
+    }
+
+    fn recv_packets(&mut self, buf: &mut [u8], out: &mut [u8]) {
+        loop {
+            let (len, from) = match self.socket.recv_from(buf) {
+                Ok(received) => received,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    error!(
+                        "Unable to receive QUIC packet on {}: {}",
+                        self.local_endpoint, err
+                    );
+                    break;
+                }
+            };
+
+            let token = {
+                let header =
+                    match quiche::Header::from_slice(&mut buf[..len], quiche::MAX_CONN_ID_LEN) {
+                        Ok(header) => header,
+                        Err(err) => {
+                            debug!("Ignoring invalid QUIC packet from {}: {}", from, err);
+                            continue;
+                        }
+                    };
+
+                match self.connection_tokens.get(header.dcid.as_ref()) {
+                    Some(token) => *token,
+                    None => match self.new_connection(&header, from, out) {
+                        Some(token) => token,
+                        None => continue,
+                    },
+                }
+            };
+
+            if let Some(connection) = self.connections.get_mut(&token) {
+                connection.driver.recv_packet(&mut buf[..len]);
+            }
+        }
+    }
+
+    /// Creates a connection for the Initial packet with the given header, if it is valid.
+    fn new_connection(
+        &mut self,
+        header: &quiche::Header,
+        from: SocketAddr,
+        out: &mut [u8],
+    ) -> Option<Token> {
+        if header.ty != quiche::Type::Initial || !self.accepting {
+            return None;
+        }
+
+        if !quiche::version_is_supported(header.version) {
+            match quiche::negotiate_version(&header.scid, &header.dcid, out) {
+                Ok(len) => {
+                    if let Err(err) = self.socket.send_to(&out[..len], &from) {
+                        debug!("Unable to send QUIC version negotiation: {}", err);
+                    }
+                }
+                Err(err) => debug!("Unable to create QUIC version negotiation: {}", err),
+            }
+            return None;
+        }
+
+        let retry_token = match header.token.as_deref() {
+            Some(retry_token) if !retry_token.is_empty() => retry_token,
+            _ => {
+                self.send_retry(header, from, out);
+                return None;
+            }
+        };
+
+        let odcid = match self.retry_tokens.validate(&from, retry_token) {
+            Some(odcid) => odcid,
+            None => {
+                debug!("Ignoring QUIC packet from {} with an invalid token", from);
+                return None;
+            }
+        };
+
+        // After a retry the client addresses the listener by the ID sent in the Retry packet
+        let scid = header.dcid.to_vec();
+        if scid.len() != quiche::MAX_CONN_ID_LEN {
+            debug!(
+                "Ignoring QUIC packet from {} with an invalid connection ID",
+                from
+            );
+            return None;
+        }
+
+        let conn = {
+            let mut config = match self.config.lock() {
+                Ok(config) => config,
+                Err(_) => {
+                    error!("QUIC listener config lock was poisoned");
+                    return None;
+                }
+            };
+
+            match quiche::accept(
+                &quiche::ConnectionId::from_ref(&scid),
+                Some(&quiche::ConnectionId::from_ref(&odcid)),
+                &mut config,
+            ) {
+                Ok(conn) => conn,
+                Err(err) => {
+                    error!("Unable to accept QUIC connection from {}: {}", from, err);
+                    return None;
+                }
+            }
+        };
+
+        let token = Token(self.next_token);
+        self.next_token += 1;
+
+        let (driver, handle) = ConnectionDriver::server(
+            conn,
+            from,
+            format!("{}{}", PROTOCOL_PREFIX, from),
+            self.local_endpoint.clone(),
+        );
+
+        if let Err(err) =
+            self.poll
+                .register(driver.commands(), token, Ready::readable(), PollOpt::edge())
+        {
+            error!("Unable to register QUIC connection from {}: {}", from, err);
+            return None;
+        }
+
+        let connection_ids = vec![scid];
+        for id in &connection_ids {
+            self.connection_tokens.insert(id.clone(), token);
+        }
+
+        self.connections.insert(
+            token,
+            AcceptedConnection {
+                driver,
+                handle: Some(handle),
+                connection_ids,
+            },
+        );
+
+        Some(token)
+    }
+
+    /// Answers an Initial packet without a token with a Retry packet, which the client must echo
+    /// to prove that it can receive packets at its address.
+    fn send_retry(&self, header: &quiche::Header, from: SocketAddr, out: &mut [u8]) {
+        let token = match self.retry_tokens.mint(&from, header.dcid.as_ref()) {
+            Ok(token) => token,
+            Err(err) => {
+                error!("Unable to create QUIC retry token for {}: {}", from, err);
+                return;
+            }
+        };
+
+        let new_scid = new_connection_id();
+        match quiche::retry(
+            &header.scid,
+            &header.dcid,
+            &quiche::ConnectionId::from_ref(&new_scid),
+            &token,
+            header.version,
+            out,
+        ) {
+            Ok(len) => {
+                if let Err(err) = self.socket.send_to(&out[..len], &from) {
+                    debug!("Unable to send QUIC retry to {}: {}", from, err);
+                }
+            }
+            Err(err) => debug!("Unable to create QUIC retry for {}: {}", from, err),
+        }
+    }
+
+    fn remove_closed_connections(&mut self) {
+        let closed: Vec<Token> = self
+            .connections
+            .iter()
+            .filter(|(_, connection)| connection.driver.is_closed())
+            .map(|(token, _)| *token)
+            .collect();
+
+        for token in closed {
+            if let Some(connection) = self.connections.remove(&token) {
+                if let Err(err) = self.poll.deregister(connection.driver.commands()) {
+                    debug!("Unable to deregister QUIC connection: {}", err);
+                }
+                for id in &connection.connection_ids {
+                    self.connection_tokens.remove(id);
+                }
+                connection.driver.shutdown();
+            }
+        }
+    }
+}
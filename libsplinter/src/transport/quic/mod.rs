@@ -0,0 +1,161 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A QUIC-based transport implementation.
+//!
+//! The `splinter::transport::quic` module provides a `Transport` implementation on top of QUIC,
+//! which runs over UDP and avoids the head-of-line blocking of TCP-based transports on lossy
+//! links. Each connection is driven by a background thread and carries its messages on a single
+//! bidirectional stream.
+
+mod connection;
+mod driver;
+mod listener;
+mod retry;
+mod transport;
+
+#[cfg(feature = "tls-reload")]
+pub use transport::QuicReloader;
+pub use transport::{QuicInitError, QuicTransport};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::tests;
+    use crate::transport::tls::tests::{make_ca_cert, make_ca_signed_cert};
+    use crate::transport::tls::{TlsConfig, TlsConfigBuilder};
+    use crate::transport::Transport;
+
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    fn write_file(mut temp_dir: PathBuf, file_name: &str, bytes: &[u8]) -> String {
+        temp_dir.push(file_name);
+        let path = temp_dir.to_str().unwrap().to_string();
+        let mut file = File::create(path.to_string()).unwrap();
+        file.write_all(bytes).unwrap();
+
+        path
+    }
+
+    fn create_test_tls_config(temp_dir: &TempDir, insecure: bool) -> TlsConfig {
+        let mut builder = TlsConfigBuilder::new();
+
+        // Generate Certificate Authority keys and certificate
+        let (ca_key, ca_cert) = make_ca_cert();
+
+        let temp_dir_path = temp_dir.path();
+
+        if !insecure {
+            let ca_path_file = write_file(
+                temp_dir_path.to_path_buf(),
+                "ca.cert",
+                &ca_cert.to_pem().unwrap(),
+            );
+            builder = builder.with_ca_certs_file(ca_path_file);
+        }
+
+        // Generate client and server keys and certificates
+        let (client_key, client_cert) = make_ca_signed_cert(&ca_cert, &ca_key);
+        let (server_key, server_cert) = make_ca_signed_cert(&ca_cert, &ca_key);
+
+        let client_cert_file = write_file(
+            temp_dir_path.to_path_buf(),
+            "client.cert",
+            &client_cert.to_pem().unwrap(),
+        );
+
+        let client_key_file = write_file(
+            temp_dir_path.to_path_buf(),
+            "client.key",
+            &client_key.private_key_to_pem_pkcs8().unwrap(),
+        );
+
+        let server_cert_file = write_file(
+            temp_dir_path.to_path_buf(),
+            "server.cert",
+            &server_cert.to_pem().unwrap(),
+        );
+
+        let server_key_file = write_file(
+            temp_dir_path.to_path_buf(),
+            "server.key",
+            &server_key.private_key_to_pem_pkcs8().unwrap(),
+        );
+
+        builder
+            .with_server_cert_file(server_cert_file)
+            .with_server_private_key_file(server_key_file)
+            .with_client_cert_file(client_cert_file)
+            .with_client_private_key_file(client_key_file)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_quic_accepts() {
+        let temp_dir = TempDir::new("test-quic-accepts").unwrap();
+        let config = create_test_tls_config(&temp_dir, false);
+        let transport = QuicTransport::new(&config).unwrap();
+        assert!(transport.accepts("quic://127.0.0.1:18090"));
+        assert!(transport.accepts("quic://somewhere.example.com:18090"));
+        assert!(!transport.accepts("tcps://127.0.0.1:18090"));
+        assert!(!transport.accepts("127.0.0.1:18090"));
+    }
+
+    #[test]
+    fn test_quic_transport() {
+        let temp_dir = TempDir::new("test-quic-transport").unwrap();
+        let config = create_test_tls_config(&temp_dir, false);
+        let transport = QuicTransport::new(&config).unwrap();
+        tests::test_transport(transport, "quic://127.0.0.1:0");
+    }
+
+    #[test]
+    fn test_quic_transport_insecure() {
+        let temp_dir = TempDir::new("test-quic-transport-insecure").unwrap();
+        let config = create_test_tls_config(&temp_dir, true);
+        let transport = QuicTransport::new(&config).unwrap();
+        tests::test_transport(transport, "quic://127.0.0.1:0");
+    }
+
+    #[test]
+    fn test_quic_poll() {
+        let temp_dir = TempDir::new("test-quic-poll").unwrap();
+        let config = create_test_tls_config(&temp_dir, false);
+        let transport = QuicTransport::new(&config).unwrap();
+        tests::test_poll(transport, "quic://127.0.0.1:0");
+    }
+
+    /// Verify that reloading the listener certificates succeeds with valid files, and fails
+    /// without replacing the loaded material when the files are missing.
+    #[cfg(feature = "tls-reload")]
+    #[test]
+    fn test_quic_reload() {
+        let temp_dir = TempDir::new("test-quic-reload").unwrap();
+        let config = create_test_tls_config(&temp_dir, false);
+        let transport = QuicTransport::new(&config).unwrap();
+        let reloader = transport.reloader();
+
+        reloader.reload().expect("Unable to reload");
+
+        std::fs::remove_file(config.server_cert_file()).unwrap();
+        assert!(reloader.reload().is_err());
+
+        tests::test_transport(transport, "quic://127.0.0.1:0");
+    }
+}
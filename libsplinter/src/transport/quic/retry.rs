@@ -0,0 +1,166 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Address validation for QUIC listeners.
+//!
+//! A listener answers each Initial packet without a token with a Retry packet carrying a token
+//! that is bound to the client's address. Only an Initial packet that echoes a valid token creates
+//! a connection, so a spoofed source address can't be used to make the listener send handshake
+//! data to a third party.
+
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use openssl::hash::MessageDigest;
+use openssl::memcmp;
+use openssl::pkey::{PKey, Private};
+use openssl::sign::Signer;
+use rand::Rng;
+
+/// Tokens older than this are rejected; a client uses its token immediately after the retry.
+const TOKEN_LIFETIME: Duration = Duration::from_secs(30);
+
+const TIMESTAMP_SIZE: usize = 8;
+const MAC_SIZE: usize = 32;
+const KEY_SIZE: usize = 32;
+
+/// Mints and validates the retry tokens of a single listener.
+///
+/// Tokens are authenticated with a key generated when the listener is created, so tokens are only
+/// valid for the listener that issued them.
+pub(super) struct RetryTokens {
+    key: PKey<Private>,
+}
+
+impl RetryTokens {
+    pub fn new() -> Result<Self, openssl::error::ErrorStack> {
+        let mut key = [0; KEY_SIZE];
+        rand::thread_rng().fill(&mut key[..]);
+        Ok(RetryTokens {
+            key: PKey::hmac(&key)?,
+        })
+    }
+
+    /// Creates a token for a client at `addr` whose first Initial packet was sent to the original
+    /// destination connection ID `odcid`.
+    pub fn mint(&self, addr: &SocketAddr, odcid: &[u8]) -> Result<Vec<u8>, String> {
+        let issued = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| format!("system time is before the epoch: {}", err))?
+            .as_secs()
+            .to_be_bytes();
+
+        let mac = self
+            .mac(addr, &issued, odcid)
+            .map_err(|err| format!("unable to sign retry token: {}", err))?;
+
+        let mut token = Vec::with_capacity(TIMESTAMP_SIZE + odcid.len() + MAC_SIZE);
+        token.extend_from_slice(&issued);
+        token.extend_from_slice(odcid);
+        token.extend_from_slice(&mac);
+        Ok(token)
+    }
+
+    /// Returns the original destination connection ID stored in the token, or `None` if the token
+    /// was not issued by this listener for a client at `addr`, or has expired.
+    pub fn validate(&self, addr: &SocketAddr, token: &[u8]) -> Option<Vec<u8>> {
+        if token.len() < TIMESTAMP_SIZE + MAC_SIZE {
+            return None;
+        }
+
+        let (issued, rest) = token.split_at(TIMESTAMP_SIZE);
+        let (odcid, mac) = rest.split_at(rest.len() - MAC_SIZE);
+
+        let expected = self.mac(addr, issued, odcid).ok()?;
+        if !memcmp::eq(&expected, mac) {
+            return None;
+        }
+
+        let mut issued_secs = [0; TIMESTAMP_SIZE];
+        issued_secs.copy_from_slice(issued);
+        let issued = UNIX_EPOCH + Duration::from_secs(u64::from_be_bytes(issued_secs));
+        match SystemTime::now().duration_since(issued) {
+            Ok(age) if age <= TOKEN_LIFETIME => Some(odcid.to_vec()),
+            // A token from the future is only possible if the clock went backwards
+            Err(_) => Some(odcid.to_vec()),
+            Ok(_) => None,
+        }
+    }
+
+    fn mac(
+        &self,
+        addr: &SocketAddr,
+        issued: &[u8],
+        odcid: &[u8],
+    ) -> Result<Vec<u8>, openssl::error::ErrorStack> {
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.key)?;
+        signer.update(addr.to_string().as_bytes())?;
+        signer.update(issued)?;
+        signer.update(odcid)?;
+        signer.sign_to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a token is only valid for the address it was minted for, and returns the
+    /// original destination connection ID it was minted with.
+    #[test]
+    fn validate_token() {
+        let tokens = RetryTokens::new().expect("Unable to create retry tokens");
+        let addr: SocketAddr = "127.0.0.1:18090".parse().unwrap();
+        let other_addr: SocketAddr = "127.0.0.1:18091".parse().unwrap();
+
+        let token = tokens.mint(&addr, b"odcid").expect("Unable to mint token");
+
+        assert_eq!(tokens.validate(&addr, &token), Some(b"odcid".to_vec()));
+        assert_eq!(tokens.validate(&other_addr, &token), None);
+    }
+
+    /// Verify that a token is rejected if it was modified or issued by another listener.
+    #[test]
+    fn reject_forged_token() {
+        let tokens = RetryTokens::new().expect("Unable to create retry tokens");
+        let other_tokens = RetryTokens::new().expect("Unable to create retry tokens");
+        let addr: SocketAddr = "127.0.0.1:18090".parse().unwrap();
+
+        let mut token = tokens.mint(&addr, b"odcid").expect("Unable to mint token");
+        assert_eq!(other_tokens.validate(&addr, &token), None);
+
+        token[TIMESTAMP_SIZE] ^= 0xff;
+        assert_eq!(tokens.validate(&addr, &token), None);
+
+        assert_eq!(tokens.validate(&addr, &[]), None);
+    }
+
+    /// Verify that an expired token is rejected.
+    #[test]
+    fn reject_expired_token() {
+        let tokens = RetryTokens::new().expect("Unable to create retry tokens");
+        let addr: SocketAddr = "127.0.0.1:18090".parse().unwrap();
+
+        let issued = (SystemTime::now() - TOKEN_LIFETIME - Duration::from_secs(1))
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .to_be_bytes();
+        let mut token = issued.to_vec();
+        token.extend_from_slice(b"odcid");
+        token.extend_from_slice(&tokens.mac(&addr, &issued, b"odcid").unwrap());
+
+        assert_eq!(tokens.validate(&addr, &token), None);
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+
+use mio::net::UdpSocket;
+use url::{ParseError, Url};
+
+use crate::transport::tls::TlsConfig;
+use crate::transport::{ConnectError, Connection, ListenError, Listener, Transport};
+
+use super::driver::{new_connection_id, start_client, ConnectionDriver};
+use super::listener::QuicListener;
+
+pub(super) const PROTOCOL_PREFIX: &str = "quic://";
+
+/// The ALPN protocol identifier, in wire format (a length-prefixed list).
+const APPLICATION_PROTOCOLS: &[u8] = b"\x08splinter";
+
+/// Connections without any traffic for this long are closed.
+const MAX_IDLE_TIMEOUT_MILLIS: u64 = 60_000;
+
+const INITIAL_MAX_DATA: u64 = 10_000_000;
+const INITIAL_MAX_STREAM_DATA: u64 = 1_000_000;
+
+/// A `Transport` for `quic://` endpoints.
+///
+/// QUIC connections are always encrypted, so the transport is created from the same `TlsConfig`
+/// used by the TLS-based transports. If the config does not include CA certificates, peers are not
+/// verified.
+pub struct QuicTransport {
+    files: QuicFiles,
+    // Shared with every listener, so that a reload applies to connections they accept afterwards
+    server_config: Arc<Mutex<quiche::Config>>,
+}
+
+impl QuicTransport {
+    pub fn new(config: &TlsConfig) -> Result<Self, QuicInitError> {
+        let files = QuicFiles {
+            ca_certs_file: config.ca_certs_file().clone(),
+            server_cert_file: config.server_cert_file().to_string(),
+            server_private_key_file: config.server_private_key_file().to_string(),
+            client_cert_file: config.client_cert_file().to_string(),
+            client_private_key_file: config.client_private_key_file().to_string(),
+        };
+
+        // Client configs are built for each connection, so that they always use the current
+        // files; build one here so that invalid certificates or keys are reported up front
+        files.client_config()?;
+        let server_config = files.server_config()?;
+
+        Ok(QuicTransport {
+            files,
+            server_config: Arc::new(Mutex::new(server_config)),
+        })
+    }
+
+    /// Returns a `QuicReloader` that can be used to reload the certificates and keys used by the
+    /// listeners of this transport from their files.
+    #[cfg(feature = "tls-reload")]
+    pub fn reloader(&self) -> QuicReloader {
+        QuicReloader {
+            files: self.files.clone(),
+            server_config: self.server_config.clone(),
+        }
+    }
+}
+
+/// The paths of the certificate and key files used to build the quiche configs.
+#[derive(Clone)]
+struct QuicFiles {
+    ca_certs_file: Option<String>,
+    server_cert_file: String,
+    server_private_key_file: String,
+    client_cert_file: String,
+    client_private_key_file: String,
+}
+
+impl QuicFiles {
+    fn client_config(&self) -> Result<quiche::Config, quiche::Error> {
+        build_config(
+            self.ca_certs_file.as_deref(),
+            &self.client_cert_file,
+            &self.client_private_key_file,
+        )
+    }
+
+    fn server_config(&self) -> Result<quiche::Config, quiche::Error> {
+        build_config(
+            self.ca_certs_file.as_deref(),
+            &self.server_cert_file,
+            &self.server_private_key_file,
+        )
+    }
+}
+
+/// Reloads the certificates and keys used by the listeners of a `QuicTransport` from their files.
+///
+/// Connections accepted after the reload use the new material. Outbound connections always read
+/// the files when they are made, and existing connections are unaffected.
+#[cfg(feature = "tls-reload")]
+#[derive(Clone)]
+pub struct QuicReloader {
+    files: QuicFiles,
+    server_config: Arc<Mutex<quiche::Config>>,
+}
+
+#[cfg(feature = "tls-reload")]
+impl QuicReloader {
+    /// Reload the certificates and keys.
+    ///
+    /// If any of the files can not be loaded, an error is returned and the listeners continue to
+    /// use the previously loaded material.
+    pub fn reload(&self) -> Result<(), QuicInitError> {
+        let server_config = self.files.server_config()?;
+
+        *self.server_config.lock().map_err(|_| {
+            QuicInitError::ProtocolError("QUIC server config lock was poisoned".into())
+        })? = server_config;
+
+        Ok(())
+    }
+}
+
+fn build_config(
+    ca_certs_file: Option<&str>,
+    cert_file: &str,
+    private_key_file: &str,
+) -> Result<quiche::Config, quiche::Error> {
+    let mut config = quiche::Config::new(quiche::PROTOCOL_VERSION)?;
+
+    config.load_cert_chain_from_pem_file(cert_file)?;
+    config.load_priv_key_from_pem_file(private_key_file)?;
+
+    if let Some(ca_certs_file) = ca_certs_file {
+        config.load_verify_locations_from_file(ca_certs_file)?;
+        config.verify_peer(true);
+    } else {
+        config.verify_peer(false);
+    }
+
+    config.set_application_protos(APPLICATION_PROTOCOLS)?;
+    config.set_max_idle_timeout(MAX_IDLE_TIMEOUT_MILLIS);
+    config.set_initial_max_data(INITIAL_MAX_DATA);
+    config.set_initial_max_stream_data_bidi_local(INITIAL_MAX_STREAM_DATA);
+    config.set_initial_max_stream_data_bidi_remote(INITIAL_MAX_STREAM_DATA);
+    // Messages are carried on a single stream opened by the client
+    config.set_initial_max_streams_bidi(1);
+    config.set_initial_max_streams_uni(0);
+    config.set_disable_active_migration(true);
+
+    Ok(config)
+}
+
+fn endpoint_to_dns_name(endpoint: &str) -> Result<String, ParseError> {
+    let mut address = String::from(PROTOCOL_PREFIX);
+    address.push_str(endpoint);
+    let url = Url::parse(&address)?;
+    let dns_name = match url.domain() {
+        Some(d) if d.parse::<Ipv4Addr>().is_ok() => "localhost",
+        Some(d) if d.parse::<Ipv6Addr>().is_ok() => "localhost",
+        Some(d) => d,
+        None => "localhost",
+    };
+    Ok(String::from(dns_name))
+}
+
+impl Transport for QuicTransport {
+    fn accepts(&self, address: &str) -> bool {
+        address.starts_with(PROTOCOL_PREFIX)
+    }
+
+    fn connect(&mut self, endpoint: &str) -> Result<Box<dyn Connection>, ConnectError> {
+        let address = endpoint.strip_prefix(PROTOCOL_PREFIX).ok_or_else(|| {
+            ConnectError::ProtocolError(format!("Invalid protocol: {}", endpoint))
+        })?;
+
+        let dns_name = endpoint_to_dns_name(address)?;
+
+        let peer_addr = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| ConnectError::ParseError(format!("Unable to resolve {}", address)))?;
+
+        let bind_addr: SocketAddr = if peer_addr.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+
+        // Connect the socket so that its local address reflects the interface used to reach the
+        // peer, and so that it only receives datagrams from the peer
+        let socket = std::net::UdpSocket::bind(bind_addr)?;
+        socket.connect(peer_addr)?;
+        let socket = UdpSocket::from_socket(socket)?;
+
+        let remote_endpoint = format!("{}{}", PROTOCOL_PREFIX, peer_addr);
+        let local_endpoint = format!("{}{}", PROTOCOL_PREFIX, socket.local_addr()?);
+
+        let mut config = self.files.client_config()?;
+        let scid = new_connection_id();
+        let conn = quiche::connect(
+            Some(&dns_name),
+            &quiche::ConnectionId::from_ref(&scid),
+            &mut config,
+        )?;
+
+        let (driver, handle) =
+            ConnectionDriver::client(conn, peer_addr, remote_endpoint, local_endpoint);
+
+        Ok(Box::new(start_client(socket, driver, handle)?))
+    }
+
+    fn listen(&mut self, bind: &str) -> Result<Box<dyn Listener>, ListenError> {
+        let address = bind
+            .strip_prefix(PROTOCOL_PREFIX)
+            .ok_or_else(|| ListenError::ProtocolError(format!("Invalid protocol: {}", bind)))?;
+
+        let socket = std::net::UdpSocket::bind(address)
+            .and_then(UdpSocket::from_socket)
+            .map_err(|err| ListenError::IoError(format!("Failed to bind to {}", address), err))?;
+
+        let local_endpoint = format!(
+            "{}{}",
+            PROTOCOL_PREFIX,
+            socket
+                .local_addr()
+                .map_err(|err| ListenError::IoError("Failed to get local address".into(), err))?
+        );
+
+        Ok(Box::new(QuicListener::new(
+            socket,
+            self.server_config.clone(),
+            local_endpoint,
+        )?))
+    }
+}
+
+impl From<quiche::Error> for ConnectError {
+    fn from(err: quiche::Error) -> Self {
+        ConnectError::ProtocolError(format!("QUIC error: {}", err))
+    }
+}
+
+#[derive(Debug)]
+pub enum QuicInitError {
+    ProtocolError(String),
+}
+
+impl std::error::Error for QuicInitError {}
+
+impl std::fmt::Display for QuicInitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            QuicInitError::ProtocolError(msg) => write!(f, "Unable to initialize QUIC: {}", msg),
+        }
+    }
+}
+
+impl From<quiche::Error> for QuicInitError {
+    fn from(error: quiche::Error) -> Self {
+        QuicInitError::ProtocolError(error.to_string())
+    }
+}
@@ -0,0 +1,290 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compression of the frames sent over socket connections.
+//!
+//! Compression is only available with frame version 2. Once both ends of a connection have agreed
+//! on version 2, the outbound end offers its compression algorithm and the inbound end accepts it
+//! if it is configured with the same algorithm. Otherwise, the connection is not compressed.
+
+use std::io::{Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt};
+
+use super::frame::{FrameError, FrameNegotiation};
+
+/// The default minimum size, in bytes, of a message that will be compressed.
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 1024;
+
+/// The default maximum size, in bytes, of a decompressed message.
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 64 * 1024 * 1024;
+
+const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// Sent in place of an algorithm when compression is not used.
+const NO_COMPRESSION: u8 = 0;
+
+/// A compression algorithm that may be negotiated for a socket connection.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CompressionAlgorithm {
+    Zstd = 1,
+}
+
+impl CompressionAlgorithm {
+    pub(super) fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(CompressionAlgorithm::Zstd),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompressionAlgorithm::Zstd => f.write_str("zstd"),
+        }
+    }
+}
+
+/// Configuration for compressing the messages sent over socket connections.
+///
+/// Messages smaller than the threshold, or that do not get smaller when compressed, are sent
+/// uncompressed. Received messages that decompress to more than the maximum decompressed size
+/// are rejected, so that a small frame cannot expand into an arbitrarily large allocation.
+#[derive(Debug, Clone)]
+pub struct CompressionConfig {
+    algorithm: CompressionAlgorithm,
+    level: i32,
+    threshold: usize,
+    max_decompressed_size: usize,
+}
+
+impl CompressionConfig {
+    /// Creates a config for the given algorithm, using its default level, the default threshold
+    /// and the default maximum decompressed size.
+    pub fn new(algorithm: CompressionAlgorithm) -> Self {
+        let level = match algorithm {
+            CompressionAlgorithm::Zstd => DEFAULT_ZSTD_LEVEL,
+        };
+
+        CompressionConfig {
+            algorithm,
+            level,
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+        }
+    }
+
+    /// Sets the compression level passed to the algorithm.
+    pub fn with_level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets the minimum size, in bytes, of a message that will be compressed.
+    pub fn with_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the maximum size, in bytes, that a received message may decompress to.
+    pub fn with_max_decompressed_size(mut self, max_decompressed_size: usize) -> Self {
+        self.max_decompressed_size = max_decompressed_size;
+        self
+    }
+
+    pub fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+
+    pub fn level(&self) -> i32 {
+        self.level
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn max_decompressed_size(&self) -> usize {
+        self.max_decompressed_size
+    }
+
+    /// Compresses the given message, returning `None` if it should be sent uncompressed.
+    pub(super) fn compress(&self, message: &[u8]) -> Result<Option<Vec<u8>>, FrameError> {
+        if message.len() < self.threshold {
+            return Ok(None);
+        }
+
+        let compressed = match self.algorithm {
+            CompressionAlgorithm::Zstd => zstd::stream::encode_all(message, self.level)
+                .map_err(|err| FrameError::CompressionError(err.to_string()))?,
+        };
+
+        if compressed.len() < message.len() {
+            Ok(Some(compressed))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Decompresses a frame payload that was compressed with the given algorithm, which must be
+    /// the negotiated algorithm.
+    pub(super) fn decompress(
+        &self,
+        algorithm: CompressionAlgorithm,
+        payload: &[u8],
+    ) -> Result<Vec<u8>, FrameError> {
+        if algorithm != self.algorithm {
+            return Err(FrameError::CompressionError(format!(
+                "received a frame compressed with {}, but {} was negotiated",
+                algorithm, self.algorithm
+            )));
+        }
+
+        let max_size = self.max_decompressed_size as u64;
+        let mut message = vec![];
+        match algorithm {
+            CompressionAlgorithm::Zstd => zstd::stream::read::Decoder::new(payload)
+                .and_then(|decoder| decoder.take(max_size + 1).read_to_end(&mut message))
+                .map_err(|err| FrameError::CompressionError(err.to_string()))?,
+        };
+
+        if message.len() as u64 > max_size {
+            return Err(FrameError::CompressionError(format!(
+                "decompressed frame exceeds the maximum size of {} bytes",
+                max_size
+            )));
+        }
+
+        Ok(message)
+    }
+}
+
+/// Negotiates compression as the end that initiated the connection.
+///
+/// The configured algorithm is offered to the remote end. The config is returned if the remote end
+/// accepts it, or `None` if the connection will not be compressed.
+pub(super) fn negotiate_outbound<S: Read + Write>(
+    stream: &mut S,
+    config: &CompressionConfig,
+) -> Result<Option<CompressionConfig>, FrameError> {
+    stream
+        .write_u8(config.algorithm as u8)
+        .map_err(FrameNegotiation::map_io_err)?;
+
+    match stream.read_u8().map_err(FrameNegotiation::map_io_err)? {
+        NO_COMPRESSION => Ok(None),
+        algorithm if algorithm == config.algorithm as u8 => Ok(Some(config.clone())),
+        algorithm => Err(FrameError::CompressionError(format!(
+            "remote accepted unexpected compression algorithm {}",
+            algorithm
+        ))),
+    }
+}
+
+/// Negotiates compression as the end that accepted the connection.
+///
+/// The algorithm offered by the remote end is accepted if it matches the configured algorithm.
+/// The config is returned if it was accepted, or `None` if the connection will not be compressed.
+pub(super) fn negotiate_inbound<S: Read + Write>(
+    stream: &mut S,
+    config: &CompressionConfig,
+) -> Result<Option<CompressionConfig>, FrameError> {
+    let offered = stream.read_u8().map_err(FrameNegotiation::map_io_err)?;
+
+    if offered == config.algorithm as u8 {
+        stream
+            .write_u8(offered)
+            .map_err(FrameNegotiation::map_io_err)?;
+        Ok(Some(config.clone()))
+    } else {
+        stream
+            .write_u8(NO_COMPRESSION)
+            .map_err(FrameNegotiation::map_io_err)?;
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that a message at least as large as the threshold is compressed, and that
+    /// decompressing it produces the original message.
+    #[test]
+    fn compress_round_trip() {
+        let config = CompressionConfig::new(CompressionAlgorithm::Zstd).with_threshold(16);
+        let message = vec![7u8; 4096];
+
+        let compressed = config
+            .compress(&message)
+            .expect("Unable to compress message")
+            .expect("Message should have been compressed");
+        assert!(compressed.len() < message.len());
+
+        let decompressed = config
+            .decompress(CompressionAlgorithm::Zstd, &compressed)
+            .expect("Unable to decompress message");
+        assert_eq!(message, decompressed);
+    }
+
+    /// Test that a message smaller than the threshold is not compressed.
+    #[test]
+    fn below_threshold() {
+        let config = CompressionConfig::new(CompressionAlgorithm::Zstd).with_threshold(1024);
+
+        assert!(config
+            .compress(&[7u8; 1023])
+            .expect("Unable to compress message")
+            .is_none());
+    }
+
+    /// Test that a message that does not get smaller when compressed is sent uncompressed.
+    #[test]
+    fn incompressible_message() {
+        let config = CompressionConfig::new(CompressionAlgorithm::Zstd).with_threshold(0);
+
+        assert!(config
+            .compress(b"abc")
+            .expect("Unable to compress message")
+            .is_none());
+    }
+
+    /// Test that a message that decompresses to more than the maximum decompressed size is
+    /// rejected.
+    #[test]
+    fn exceeds_max_decompressed_size() {
+        let config = CompressionConfig::new(CompressionAlgorithm::Zstd).with_threshold(0);
+        let compressed = config
+            .compress(&[7u8; 4096])
+            .expect("Unable to compress message")
+            .expect("Message should have been compressed");
+
+        let limited = config.clone().with_max_decompressed_size(4095);
+        match limited.decompress(CompressionAlgorithm::Zstd, &compressed) {
+            Err(FrameError::CompressionError(_)) => (),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let exact = config.with_max_decompressed_size(4096);
+        assert_eq!(
+            exact
+                .decompress(CompressionAlgorithm::Zstd, &compressed)
+                .expect("Unable to decompress message")
+                .len(),
+            4096
+        );
+    }
+}
@@ -18,7 +18,16 @@ use std::time::Duration;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
+#[cfg(feature = "transport-compression")]
+use crate::transport::ConnectionStats;
+
+#[cfg(feature = "transport-compression")]
+use super::compression::{self, CompressionAlgorithm, CompressionConfig};
+
 const HEADER_LENGTH: usize = 6;
+/// The version 2 header adds a byte specifying the compression algorithm of the frame data.
+#[cfg(feature = "transport-compression")]
+const V2_HEADER_LENGTH: usize = 7;
 
 /// An error that may be returned during frame-related operations
 #[derive(Debug)]
@@ -28,6 +37,8 @@ pub enum FrameError {
     InvalidHeaderLength(usize),
     UnsupportedVersion,
     HandshakeFailure(String),
+    #[cfg(feature = "transport-compression")]
+    CompressionError(String),
 }
 
 impl std::fmt::Display for FrameError {
@@ -42,6 +53,8 @@ impl std::fmt::Display for FrameError {
             ),
             FrameError::UnsupportedVersion => f.write_str("Unsupported frame version"),
             FrameError::HandshakeFailure(msg) => f.write_str(&msg),
+            #[cfg(feature = "transport-compression")]
+            FrameError::CompressionError(msg) => write!(f, "Compression error: {}", msg),
         }
     }
 }
@@ -54,6 +67,8 @@ impl std::error::Error for FrameError {
             FrameError::InvalidHeaderLength(_) => None,
             FrameError::UnsupportedVersion => None,
             FrameError::HandshakeFailure(_) => None,
+            #[cfg(feature = "transport-compression")]
+            FrameError::CompressionError(_) => None,
         }
     }
 }
@@ -71,6 +86,19 @@ impl From<io::Error> for FrameError {
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum FrameVersion {
     V1 = 1,
+    #[cfg(feature = "transport-compression")]
+    V2 = 2,
+}
+
+impl FrameVersion {
+    fn from_u16(version: u16) -> Option<Self> {
+        match version {
+            1 => Some(FrameVersion::V1),
+            #[cfg(feature = "transport-compression")]
+            2 => Some(FrameVersion::V2),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for FrameVersion {
@@ -84,6 +112,9 @@ impl std::fmt::Display for FrameVersion {
 /// This struct owns the data that has been transmitted.  It is essentially a receiving frame.
 pub struct Frame {
     data: Vec<u8>,
+    // The number of data bytes transmitted, which differs from the data length if compressed
+    #[cfg(feature = "transport-compression")]
+    encoded_length: usize,
 }
 
 impl Frame {
@@ -100,49 +131,105 @@ impl Frame {
     ///
     /// - the header is malformed
     /// - the data length doesn't match the header length
+    /// - the frame is compressed, as no compression has been negotiated
     /// - an IO error occurs
     pub fn read<R: Read>(reader: &mut R) -> Result<Self, FrameError> {
-        let frame_header = loop {
-            match FrameHeader::read(reader) {
-                Err(FrameError::IoError(ref e)) if e.kind() == io::ErrorKind::WouldBlock => {
-                    thread::sleep(Duration::from_millis(100));
-                    continue;
-                }
-                Err(err) => return Err(err),
-                Ok(header) => break header,
-            };
+        #[cfg(not(feature = "transport-compression"))]
+        let frame = match read_header(reader)? {
+            FrameHeader::V1 { length } => Self {
+                data: read_data(reader, length)?,
+            },
         };
+        #[cfg(feature = "transport-compression")]
+        let frame = Self::read_with_compression(reader, None)?;
 
-        match frame_header {
-            FrameHeader::V1 { length } => {
-                let mut buffer = vec![0; length as usize];
-                let mut remaining = &mut buffer[..];
-
-                while !remaining.is_empty() {
-                    match reader.read(remaining) {
-                        Ok(0) => break,
-                        Ok(n) => {
-                            let tmp = remaining;
-                            remaining = &mut tmp[n..];
-                        }
-                        Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
-                        Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                            thread::sleep(Duration::from_millis(100));
-                        }
-                        Err(e) => return Err(FrameError::IoError(e)),
+        Ok(frame)
+    }
+
+    /// Read a frame from the given reader, decompressing its data with the negotiated
+    /// compression config.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors returned by `read`, this function returns an error if the frame
+    /// is compressed with an algorithm other than the negotiated one, or decompresses to more
+    /// than the configured maximum size.
+    #[cfg(feature = "transport-compression")]
+    fn read_with_compression<R: Read>(
+        reader: &mut R,
+        compression: Option<&CompressionConfig>,
+    ) -> Result<Self, FrameError> {
+        match read_header(reader)? {
+            FrameHeader::V1 { length } => Ok(Self {
+                data: read_data(reader, length)?,
+                encoded_length: length as usize,
+            }),
+            FrameHeader::V2 {
+                length,
+                compression: algorithm,
+            } => {
+                let data = read_data(reader, length)?;
+                let data = match (algorithm, compression) {
+                    (None, _) => data,
+                    (Some(algorithm), Some(config)) => config.decompress(algorithm, &data)?,
+                    (Some(algorithm), None) => {
+                        return Err(FrameError::CompressionError(format!(
+                            "received a frame compressed with {}, but compression was not \
+                            negotiated",
+                            algorithm
+                        )))
                     }
-                }
-                if !remaining.is_empty() {
-                    Err(FrameError::IoError(io::Error::new(
-                        io::ErrorKind::UnexpectedEof,
-                        "Could not receive complete frame",
-                    )))
-                } else {
-                    Ok(Self { data: buffer })
-                }
+                };
+
+                Ok(Self {
+                    data,
+                    encoded_length: length as usize,
+                })
+            }
+        }
+    }
+}
+
+/// Read a frame header from the reader, waiting for it to become available.
+fn read_header<R: Read>(reader: &mut R) -> Result<FrameHeader, FrameError> {
+    loop {
+        match FrameHeader::read(reader) {
+            Err(FrameError::IoError(ref e)) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+            result => return result,
+        };
+    }
+}
+
+/// Read the given number of data bytes from the reader.
+fn read_data<R: Read>(reader: &mut R, length: u32) -> Result<Vec<u8>, FrameError> {
+    let mut buffer = vec![0; length as usize];
+    let mut remaining = &mut buffer[..];
+
+    while !remaining.is_empty() {
+        match reader.read(remaining) {
+            Ok(0) => break,
+            Ok(n) => {
+                let tmp = remaining;
+                remaining = &mut tmp[n..];
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(100));
             }
+            Err(e) => return Err(FrameError::IoError(e)),
         }
     }
+    if !remaining.is_empty() {
+        Err(FrameError::IoError(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Could not receive complete frame",
+        )))
+    } else {
+        Ok(buffer)
+    }
 }
 
 /// A Frame of referenced data to be transmitted using a specified version.
@@ -151,23 +238,52 @@ impl Frame {
 pub struct FrameRef<'a> {
     version: FrameVersion,
     data: &'a [u8],
+    #[cfg(feature = "transport-compression")]
+    compression: Option<CompressionAlgorithm>,
 }
 
 impl<'a> FrameRef<'a> {
     /// Construct a FrameRef for the given byte slice, which will be transmitted using the given
     /// frame version.
     pub fn new<'b: 'a>(version: FrameVersion, data: &'b [u8]) -> FrameRef<'a> {
-        Self { version, data }
+        Self {
+            version,
+            data,
+            #[cfg(feature = "transport-compression")]
+            compression: None,
+        }
+    }
+
+    /// Mark the data as compressed with the given algorithm. This is only supported by frame
+    /// versions 2 and above.
+    #[cfg(feature = "transport-compression")]
+    pub fn with_compression(mut self, algorithm: CompressionAlgorithm) -> Self {
+        self.compression = Some(algorithm);
+        self
     }
 
     /// Write the frame to the given writer.
     ///
     /// # Errors
     ///
-    /// Returns a FrameError if an IO error occurs.
+    /// Returns a FrameError if an IO error occurs, or if compressed data is written using a frame
+    /// version that does not support compression.
     pub fn write<W: Write>(self, writer: &mut W) -> Result<(), FrameError> {
         let frame_header = match self.version {
+            #[cfg(not(feature = "transport-compression"))]
             FrameVersion::V1 => FrameHeader::v1(self.data.len() as u32),
+            #[cfg(feature = "transport-compression")]
+            FrameVersion::V1 => {
+                if self.compression.is_some() {
+                    return Err(FrameError::CompressionError(format!(
+                        "frame version {} does not support compression",
+                        self.version
+                    )));
+                }
+                FrameHeader::v1(self.data.len() as u32)
+            }
+            #[cfg(feature = "transport-compression")]
+            FrameVersion::V2 => FrameHeader::v2(self.data.len() as u32, self.compression),
         };
         loop {
             match frame_header.write(writer) {
@@ -207,7 +323,14 @@ impl<'a> FrameRef<'a> {
 /// Each variant corresponds to the implementation for a given version.
 #[derive(Debug, PartialEq)]
 enum FrameHeader {
-    V1 { length: u32 },
+    V1 {
+        length: u32,
+    },
+    #[cfg(feature = "transport-compression")]
+    V2 {
+        length: u32,
+        compression: Option<CompressionAlgorithm>,
+    },
 }
 
 impl FrameHeader {
@@ -216,6 +339,15 @@ impl FrameHeader {
         FrameHeader::V1 { length }
     }
 
+    /// Construct a version 2 frame header.
+    #[cfg(feature = "transport-compression")]
+    fn v2(length: u32, compression: Option<CompressionAlgorithm>) -> Self {
+        FrameHeader::V2 {
+            length,
+            compression,
+        }
+    }
+
     /// Read a FrameHeader from the given reader.
     ///
     /// This function uses the first 2 bytes of the stream to read the version, and constructs the
@@ -252,6 +384,41 @@ impl FrameHeader {
                     length: cursor.read_u32::<BigEndian>()?,
                 })
             }
+            #[cfg(feature = "transport-compression")]
+            2 => {
+                // Header length + checksum byte
+                let mut buffer = [0u8; V2_HEADER_LENGTH + 1];
+                let mut cursor = Cursor::new(&mut buffer[..]);
+                cursor.write_u16::<BigEndian>(2u16)?;
+
+                let n = reader.read(&mut cursor.get_mut()[std::mem::size_of::<u16>()..])?;
+                if n != V2_HEADER_LENGTH + 1 - std::mem::size_of::<u16>() {
+                    return Err(FrameError::InvalidHeaderLength(n));
+                }
+
+                let checksum = compute_checksum(&cursor.get_ref()[..V2_HEADER_LENGTH]);
+                if checksum != cursor.get_ref()[V2_HEADER_LENGTH] {
+                    return Err(FrameError::InvalidChecksum);
+                }
+
+                let length = cursor.read_u32::<BigEndian>()?;
+                let compression = match cursor.read_u8()? {
+                    0 => None,
+                    algorithm => {
+                        Some(CompressionAlgorithm::from_u8(algorithm).ok_or_else(|| {
+                            FrameError::CompressionError(format!(
+                                "unsupported compression algorithm {}",
+                                algorithm
+                            ))
+                        })?)
+                    }
+                };
+
+                Ok(FrameHeader::V2 {
+                    length,
+                    compression,
+                })
+            }
             _ => Err(FrameError::UnsupportedVersion),
         }
     }
@@ -279,6 +446,23 @@ impl FrameHeader {
 
                 writer.write_all(&cursor.into_inner()[..])?;
             }
+            #[cfg(feature = "transport-compression")]
+            FrameHeader::V2 {
+                length,
+                compression,
+            } => {
+                let mut header_bytes = [0u8; V2_HEADER_LENGTH + 1];
+                let mut cursor = Cursor::new(&mut header_bytes[..]);
+
+                cursor.write_u16::<BigEndian>(2)?;
+                cursor.write_u32::<BigEndian>(length)?;
+                cursor.write_u8(compression.map(|algorithm| algorithm as u8).unwrap_or(0))?;
+
+                cursor.get_mut()[V2_HEADER_LENGTH] =
+                    compute_checksum(&cursor.get_ref()[..V2_HEADER_LENGTH]);
+
+                writer.write_all(&cursor.into_inner()[..])?;
+            }
         }
 
         Ok(())
@@ -313,9 +497,12 @@ pub enum FrameNegotiation {
         min: FrameVersion,
         max: FrameVersion,
     },
-    /// The Inbound variant transmits receives the min and max and decides if it should send its
-    /// version or `0`, depending on whether or not it falls in the range.
-    Inbound { version: FrameVersion },
+    /// The Inbound variant receives the remote min and max, and sends the highest version
+    /// supported by both ends, or `0` if the ranges do not overlap.
+    Inbound {
+        min: FrameVersion,
+        max: FrameVersion,
+    },
 }
 
 impl FrameNegotiation {
@@ -326,7 +513,16 @@ impl FrameNegotiation {
 
     /// Construct the inbound side of a negotiation with the given version.
     pub fn inbound(version: FrameVersion) -> Self {
-        FrameNegotiation::Inbound { version }
+        FrameNegotiation::Inbound {
+            min: version,
+            max: version,
+        }
+    }
+
+    /// Construct the inbound side of a negotiation with the given min,max.
+    #[cfg(feature = "transport-compression")]
+    pub fn inbound_range(min: FrameVersion, max: FrameVersion) -> Self {
+        FrameNegotiation::Inbound { min, max }
     }
 
     /// Negotiate frame version to use for future communications over the given stream.
@@ -349,29 +545,39 @@ impl FrameNegotiation {
 
                 let frame_version = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
 
-                match frame_version {
-                    0 => Err(FrameError::UnsupportedVersion),
-                    1 => Ok(FrameVersion::V1),
+                match FrameVersion::from_u16(frame_version) {
+                    Some(version)
+                        if version as u16 >= min as u16 && version as u16 <= max as u16 =>
+                    {
+                        Ok(version)
+                    }
                     _ => Err(FrameError::UnsupportedVersion),
                 }
             }
-            FrameNegotiation::Inbound { version } => {
-                let min = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
-                let max = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
-                if min > version as u16 || max < version as u16 {
-                    stream.write_u16::<BigEndian>(0).map_err(Self::map_io_err)?;
-                    Err(FrameError::UnsupportedVersion)
-                } else {
-                    stream
-                        .write_u16::<BigEndian>(version as u16)
-                        .map_err(Self::map_io_err)?;
-                    Ok(version)
+            FrameNegotiation::Inbound { min, max } => {
+                let remote_min = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
+                let remote_max = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
+
+                let highest = std::cmp::min(remote_max, max as u16);
+                let lowest = std::cmp::max(remote_min, min as u16);
+
+                match FrameVersion::from_u16(highest) {
+                    Some(version) if highest >= lowest => {
+                        stream
+                            .write_u16::<BigEndian>(version as u16)
+                            .map_err(Self::map_io_err)?;
+                        Ok(version)
+                    }
+                    _ => {
+                        stream.write_u16::<BigEndian>(0).map_err(Self::map_io_err)?;
+                        Err(FrameError::UnsupportedVersion)
+                    }
                 }
             }
         }
     }
 
-    fn map_io_err(err: io::Error) -> FrameError {
+    pub(super) fn map_io_err(err: io::Error) -> FrameError {
         use io::ErrorKind::*;
         match err.kind() {
             UnexpectedEof | ConnectionReset | ConnectionAborted | BrokenPipe => {
@@ -384,6 +590,155 @@ impl FrameNegotiation {
     }
 }
 
+/// Reads and writes the frames of a single connection, using the frame version and compression
+/// negotiated for it.
+pub struct FrameCodec {
+    version: FrameVersion,
+    #[cfg(feature = "transport-compression")]
+    compression: Option<CompressionConfig>,
+    #[cfg(feature = "transport-compression")]
+    stats: ConnectionStats,
+}
+
+impl FrameCodec {
+    /// Negotiate the frame version, and compression if it is configured, for a connection
+    /// initiated by this end.
+    ///
+    /// # Errors
+    ///
+    /// Returns a FrameError if either end cannot agree on a version, or an IO error occurs.
+    pub fn outbound<S: Read + Write>(
+        stream: &mut S,
+        #[cfg(feature = "transport-compression")] compression: Option<&CompressionConfig>,
+    ) -> Result<Self, FrameError> {
+        #[cfg(not(feature = "transport-compression"))]
+        let max = FrameVersion::V1;
+        #[cfg(feature = "transport-compression")]
+        let max = if compression.is_some() {
+            FrameVersion::V2
+        } else {
+            FrameVersion::V1
+        };
+
+        let version = FrameNegotiation::outbound(FrameVersion::V1, max).negotiate(stream)?;
+
+        #[cfg(feature = "transport-compression")]
+        let compression = match (version, compression) {
+            (FrameVersion::V2, Some(config)) => compression::negotiate_outbound(stream, config)?,
+            _ => None,
+        };
+
+        Ok(FrameCodec {
+            version,
+            #[cfg(feature = "transport-compression")]
+            compression,
+            #[cfg(feature = "transport-compression")]
+            stats: ConnectionStats::default(),
+        })
+    }
+
+    /// Negotiate the frame version, and compression if it is configured, for a connection
+    /// accepted by this end.
+    ///
+    /// # Errors
+    ///
+    /// Returns a FrameError if either end cannot agree on a version, or an IO error occurs.
+    pub fn inbound<S: Read + Write>(
+        stream: &mut S,
+        #[cfg(feature = "transport-compression")] compression: Option<&CompressionConfig>,
+    ) -> Result<Self, FrameError> {
+        #[cfg(not(feature = "transport-compression"))]
+        let negotiation = FrameNegotiation::inbound(FrameVersion::V1);
+        #[cfg(feature = "transport-compression")]
+        let negotiation = if compression.is_some() {
+            FrameNegotiation::inbound_range(FrameVersion::V1, FrameVersion::V2)
+        } else {
+            FrameNegotiation::inbound(FrameVersion::V1)
+        };
+
+        let version = negotiation.negotiate(stream)?;
+
+        #[cfg(feature = "transport-compression")]
+        let compression = match (version, compression) {
+            (FrameVersion::V2, Some(config)) => compression::negotiate_inbound(stream, config)?,
+            _ => None,
+        };
+
+        Ok(FrameCodec {
+            version,
+            #[cfg(feature = "transport-compression")]
+            compression,
+            #[cfg(feature = "transport-compression")]
+            stats: ConnectionStats::default(),
+        })
+    }
+
+    /// Construct a codec for a connection using the given frame version without negotiation.
+    pub fn with_version(version: FrameVersion) -> Self {
+        FrameCodec {
+            version,
+            #[cfg(feature = "transport-compression")]
+            compression: None,
+            #[cfg(feature = "transport-compression")]
+            stats: ConnectionStats::default(),
+        }
+    }
+
+    /// Write a message to the given writer as a single frame, compressing it if compression was
+    /// negotiated and the message is at least the configured threshold.
+    pub fn write<W: Write>(&mut self, writer: &mut W, message: &[u8]) -> Result<(), FrameError> {
+        #[cfg(not(feature = "transport-compression"))]
+        FrameRef::new(self.version, message).write(writer)?;
+
+        #[cfg(feature = "transport-compression")]
+        {
+            let compressed = match &self.compression {
+                Some(config) => config
+                    .compress(message)?
+                    .map(|data| (config.algorithm(), data)),
+                None => None,
+            };
+
+            let encoded_length = match compressed {
+                Some((algorithm, data)) => {
+                    FrameRef::new(self.version, &data)
+                        .with_compression(algorithm)
+                        .write(writer)?;
+                    data.len()
+                }
+                None => {
+                    FrameRef::new(self.version, message).write(writer)?;
+                    message.len()
+                }
+            };
+
+            self.stats.record_sent(message.len(), encoded_length);
+        }
+
+        Ok(())
+    }
+
+    /// Read a single frame from the given reader, returning its decompressed data.
+    pub fn read<R: Read>(&mut self, reader: &mut R) -> Result<Vec<u8>, FrameError> {
+        #[cfg(not(feature = "transport-compression"))]
+        let frame = Frame::read(reader)?;
+        #[cfg(feature = "transport-compression")]
+        let frame = Frame::read_with_compression(reader, self.compression.as_ref())?;
+
+        #[cfg(feature = "transport-compression")]
+        self.stats
+            .record_received(frame.data.len(), frame.encoded_length);
+
+        Ok(frame.into_inner())
+    }
+
+    /// Returns the counts of message bytes written and read by this codec.
+    #[cfg(feature = "transport-compression")]
+    pub fn stats(&self) -> ConnectionStats {
+        self.stats
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -498,10 +853,29 @@ mod tests {
             .expect("Unable to write frame header");
 
         header_cursor.set_position(0);
-        let FrameHeader::V1 { length } =
-            FrameHeader::read(&mut header_cursor).expect("Unable to read header");
+        let frame_header = FrameHeader::read(&mut header_cursor).expect("Unable to read header");
+
+        assert_eq!(FrameHeader::v1(100), frame_header);
+    }
 
-        assert_eq!(100, length);
+    /// Test a round-trip write and read of a version 2 FrameHeader, with and without a
+    /// compression algorithm.
+    #[cfg(feature = "transport-compression")]
+    #[test]
+    fn round_trip_v2() {
+        for compression in vec![None, Some(CompressionAlgorithm::Zstd)] {
+            let mut header_cursor = Cursor::new(vec![0u8; V2_HEADER_LENGTH + 1]);
+
+            FrameHeader::v2(100, compression)
+                .write(&mut header_cursor)
+                .expect("Unable to write frame header");
+
+            header_cursor.set_position(0);
+            let frame_header =
+                FrameHeader::read(&mut header_cursor).expect("Unable to read header");
+
+            assert_eq!(FrameHeader::v2(100, compression), frame_header);
+        }
     }
 
     /// Test that outbound frame version negotiation works:
@@ -676,6 +1050,68 @@ mod tests {
         assert_eq!(input.to_vec(), frame.data);
     }
 
+    /// Test that a compressed frame is rejected when no compression has been negotiated, and
+    /// decompressed when it has.
+    #[cfg(feature = "transport-compression")]
+    #[test]
+    fn read_compressed_frame() {
+        let config = CompressionConfig::new(CompressionAlgorithm::Zstd).with_threshold(0);
+        let input = vec![7u8; 1024];
+        let compressed = config
+            .compress(&input)
+            .expect("Unable to compress data")
+            .expect("Data should have been compressed");
+
+        let mut cursor = Cursor::new(vec![]);
+        FrameRef::new(FrameVersion::V2, &compressed)
+            .with_compression(CompressionAlgorithm::Zstd)
+            .write(&mut cursor)
+            .expect("Unable to write frame");
+
+        cursor.set_position(0);
+        match Frame::read(&mut cursor) {
+            Err(FrameError::CompressionError(_)) => (),
+            Err(err) => panic!("Unexpected error: {}", err),
+            Ok(_) => panic!("Compressed frame should have been rejected"),
+        }
+
+        cursor.set_position(0);
+        let frame =
+            Frame::read_with_compression(&mut cursor, Some(&config)).expect("Unable to read frame");
+        assert_eq!(input, frame.data);
+    }
+
+    /// Test that an inbound negotiation supporting versions 1 and 2 selects version 1 when the
+    /// outbound end only supports version 1.
+    #[cfg(feature = "transport-compression")]
+    #[test]
+    fn inbound_range_negotiation() {
+        let (mut tx, mut rx) = stream::byte_stream_pair();
+
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let join_handle = thread::spawn(move || {
+            let res = FrameNegotiation::inbound_range(FrameVersion::V1, FrameVersion::V2)
+                .negotiate(&mut rx)
+                .expect("Should have successfully negotiated");
+
+            done_rx.recv().unwrap();
+
+            res
+        });
+
+        let version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::V1)
+            .negotiate(&mut tx)
+            .expect("Unable to negotiate a valid version");
+
+        assert_eq!(FrameVersion::V1, version);
+
+        done_tx.send(1u8).expect("unable to send stop signal");
+
+        let remote_res = join_handle.join().expect("Unable to join thread");
+
+        assert_eq!(FrameVersion::V1, remote_res);
+    }
+
     #[cfg(not(target_os = "unix"))]
     mod stream {
         use std::io::{Error as IoError, Read, Write};
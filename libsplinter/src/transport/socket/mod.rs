@@ -12,12 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "transport-compression")]
+mod compression;
 mod frame;
 mod tcp;
 mod tls;
+#[cfg(feature = "unix-transport")]
+mod unix;
 
+#[cfg(feature = "transport-compression")]
+pub use compression::{
+    CompressionAlgorithm, CompressionConfig, DEFAULT_COMPRESSION_THRESHOLD,
+    DEFAULT_MAX_DECOMPRESSED_SIZE,
+};
 pub use tcp::TcpTransport;
+#[cfg(feature = "tls-reload")]
+pub use tls::{TlsCertWatcher, TlsReloader};
 pub use tls::{TlsConnection, TlsInitError, TlsTransport};
+#[cfg(feature = "unix-transport")]
+pub use unix::{UnixTransport, DEFAULT_SOCKET_PERMISSIONS};
 
 #[cfg(test)]
 pub mod tests {
@@ -16,17 +16,36 @@ use mio::{net::TcpStream as MioTcpStream, Evented};
 
 use std::net::{Shutdown, TcpListener as StdTcpListener, TcpStream};
 
+#[cfg(feature = "transport-compression")]
+use crate::transport::ConnectionStats;
 use crate::transport::{
     AcceptError, ConnectError, Connection, DisconnectError, ListenError, Listener, RecvError,
     SendError, Transport,
 };
 
-use super::frame::{Frame, FrameError, FrameNegotiation, FrameRef, FrameVersion};
+#[cfg(feature = "transport-compression")]
+use super::compression::CompressionConfig;
+use super::frame::{FrameCodec, FrameError, FrameVersion};
 
 const PROTOCOL_PREFIX: &str = "tcp://";
 
 #[derive(Default)]
-pub struct TcpTransport {}
+pub struct TcpTransport {
+    #[cfg(feature = "transport-compression")]
+    compression: Option<CompressionConfig>,
+}
+
+impl TcpTransport {
+    /// Offer compression with the given config on all connections created by this transport.
+    ///
+    /// Compression is only used on a connection if the remote end is configured with the same
+    /// algorithm.
+    #[cfg(feature = "transport-compression")]
+    pub fn with_compression(mut self, config: CompressionConfig) -> Self {
+        self.compression = Some(config);
+        self
+    }
+}
 
 impl Transport for TcpTransport {
     fn accepts(&self, address: &str) -> bool {
@@ -49,19 +68,22 @@ impl Transport for TcpTransport {
         // Connect a std::net::TcpStream to make sure connect() block
         let mut stream = TcpStream::connect(address)?;
 
-        let frame_version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::V1)
-            .negotiate(&mut stream)
-            .map_err(|err| match err {
-                FrameError::UnsupportedVersion => ConnectError::ProtocolError(
-                    "Unable to connect; remote version is not with in range".into(),
-                ),
-                FrameError::IoError(err) => ConnectError::from(err),
-                e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
-            })?;
+        let codec = FrameCodec::outbound(
+            &mut stream,
+            #[cfg(feature = "transport-compression")]
+            self.compression.as_ref(),
+        )
+        .map_err(|err| match err {
+            FrameError::UnsupportedVersion => ConnectError::ProtocolError(
+                "Unable to connect; remote version is not with in range".into(),
+            ),
+            FrameError::IoError(err) => ConnectError::from(err),
+            e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
+        })?;
 
         let mio_stream = MioTcpStream::from_stream(stream)?;
         Ok(Box::new(TcpConnection {
-            frame_version,
+            codec,
             stream: mio_stream,
         }))
     }
@@ -84,32 +106,39 @@ impl Transport for TcpTransport {
             listener: StdTcpListener::bind(address).map_err(|err| {
                 ListenError::IoError(format!("Failed to bind to {}", address), err)
             })?,
+            #[cfg(feature = "transport-compression")]
+            compression: self.compression.clone(),
         }))
     }
 }
 
 struct TcpListener {
     listener: StdTcpListener,
+    #[cfg(feature = "transport-compression")]
+    compression: Option<CompressionConfig>,
 }
 
 impl Listener for TcpListener {
     fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
         let (mut stream, _) = self.listener.accept()?;
 
-        let frame_version = FrameNegotiation::inbound(FrameVersion::V1)
-            .negotiate(&mut stream)
-            .map_err(|err| match err {
-                FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
-                    "Local {} protocol version {} not supported by remote",
-                    PROTOCOL_PREFIX,
-                    FrameVersion::V1
-                )),
-                FrameError::IoError(err) => AcceptError::from(err),
-                err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
-            })?;
+        let codec = FrameCodec::inbound(
+            &mut stream,
+            #[cfg(feature = "transport-compression")]
+            self.compression.as_ref(),
+        )
+        .map_err(|err| match err {
+            FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
+                "Local {} protocol version {} not supported by remote",
+                PROTOCOL_PREFIX,
+                FrameVersion::V1
+            )),
+            FrameError::IoError(err) => AcceptError::from(err),
+            err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
+        })?;
 
         let connection = TcpConnection {
-            frame_version,
+            codec,
             stream: MioTcpStream::from_stream(stream)?,
         };
         Ok(Box::new(connection))
@@ -121,13 +150,13 @@ impl Listener for TcpListener {
 }
 
 struct TcpConnection {
-    frame_version: FrameVersion,
+    codec: FrameCodec,
     stream: MioTcpStream,
 }
 
 impl Connection for TcpConnection {
     fn send(&mut self, message: &[u8]) -> Result<(), SendError> {
-        match FrameRef::new(self.frame_version, message).write(&mut self.stream) {
+        match self.codec.write(&mut self.stream, message) {
             Err(FrameError::IoError(e)) => Err(SendError::from(e)),
             Err(err) => Err(SendError::ProtocolError(err.to_string())),
             Ok(_) => Ok(()),
@@ -135,10 +164,10 @@ impl Connection for TcpConnection {
     }
 
     fn recv(&mut self) -> Result<Vec<u8>, RecvError> {
-        match Frame::read(&mut self.stream) {
+        match self.codec.read(&mut self.stream) {
             Err(FrameError::IoError(e)) => Err(RecvError::from(e)),
             Err(err) => Err(RecvError::ProtocolError(err.to_string())),
-            Ok(frame) => Ok(frame.into_inner()),
+            Ok(data) => Ok(data),
         }
     }
 
@@ -159,6 +188,11 @@ impl Connection for TcpConnection {
     fn evented(&self) -> &dyn Evented {
         &self.stream
     }
+
+    #[cfg(feature = "transport-compression")]
+    fn stats(&self) -> Option<ConnectionStats> {
+        Some(self.codec.stats())
+    }
 }
 
 #[cfg(test)]
@@ -166,6 +200,12 @@ mod tests {
     use super::*;
     use crate::transport::tests;
 
+    #[cfg(feature = "transport-compression")]
+    use std::thread;
+
+    #[cfg(feature = "transport-compression")]
+    use crate::transport::socket::CompressionAlgorithm;
+
     #[test]
     fn test_accepts() {
         let transport = TcpTransport::default();
@@ -196,4 +236,120 @@ mod tests {
         let transport = TcpTransport::default();
         tests::test_poll(transport, "127.0.0.1:0");
     }
+
+    #[cfg(feature = "transport-compression")]
+    #[test]
+    fn test_transport_compression() {
+        let transport = TcpTransport::default()
+            .with_compression(CompressionConfig::new(CompressionAlgorithm::Zstd).with_threshold(0));
+
+        tests::test_transport(transport, "127.0.0.1:0");
+    }
+
+    #[cfg(feature = "transport-compression")]
+    #[test]
+    fn test_poll_compression() {
+        let transport = TcpTransport::default()
+            .with_compression(CompressionConfig::new(CompressionAlgorithm::Zstd).with_threshold(0));
+
+        tests::test_poll(transport, "127.0.0.1:0");
+    }
+
+    /// Test that messages at or above the compression threshold are compressed when both ends
+    /// are configured with compression, and that each end's stats report the raw and compressed
+    /// byte counts:
+    /// 1. Listen with compression enabled, and connect from a thread with compression enabled
+    /// 2. Send a small message and a large, compressible message from the client
+    /// 3. Verify the server receives both messages intact
+    /// 4. Verify the compressed bytes are fewer than the raw bytes, and match across both ends
+    #[cfg(feature = "transport-compression")]
+    #[test]
+    fn test_compression_stats() {
+        let config = CompressionConfig::new(CompressionAlgorithm::Zstd).with_threshold(64);
+        let mut transport = TcpTransport::default().with_compression(config);
+
+        let mut listener = transport.listen("127.0.0.1:0").expect("Unable to listen");
+        let endpoint = listener.endpoint();
+
+        let small = b"hello".to_vec();
+        let large = vec![7u8; 4096];
+
+        let (sent_small, sent_large) = (small.clone(), large.clone());
+        let handle = thread::spawn(move || {
+            let mut client = transport.connect(&endpoint).expect("Unable to connect");
+            client
+                .send(&sent_small)
+                .expect("Unable to send small message");
+            client
+                .send(&sent_large)
+                .expect("Unable to send large message");
+            // Wait for the server to receive both messages before disconnecting
+            client.recv().expect("Unable to receive reply");
+
+            client.stats().expect("Stats should be available")
+        });
+
+        let mut server = listener.accept().expect("Unable to accept");
+        assert_eq!(
+            small,
+            server.recv().expect("Unable to receive small message")
+        );
+        assert_eq!(
+            large,
+            server.recv().expect("Unable to receive large message")
+        );
+        server.send(b"done").expect("Unable to send reply");
+
+        let client_stats = handle.join().expect("Unable to join client thread");
+        let server_stats = server.stats().expect("Stats should be available");
+
+        assert_eq!(
+            (small.len() + large.len()) as u64,
+            client_stats.raw_bytes_sent()
+        );
+        assert!(client_stats.compressed_bytes_sent() < client_stats.raw_bytes_sent());
+        assert_eq!(
+            client_stats.raw_bytes_sent(),
+            server_stats.raw_bytes_received()
+        );
+        assert_eq!(
+            client_stats.compressed_bytes_sent(),
+            server_stats.compressed_bytes_received()
+        );
+        assert_eq!(4, server_stats.raw_bytes_sent());
+        assert_eq!(4, client_stats.raw_bytes_received());
+    }
+
+    /// Test that a connection falls back to uncompressed frames when only one end is configured
+    /// with compression.
+    #[cfg(feature = "transport-compression")]
+    #[test]
+    fn test_compression_one_sided() {
+        let mut transport = TcpTransport::default()
+            .with_compression(CompressionConfig::new(CompressionAlgorithm::Zstd).with_threshold(0));
+        let mut listener = TcpTransport::default()
+            .listen("127.0.0.1:0")
+            .expect("Unable to listen");
+        let endpoint = listener.endpoint();
+
+        let message = vec![7u8; 4096];
+        let sent = message.clone();
+        let handle = thread::spawn(move || {
+            let mut client = transport.connect(&endpoint).expect("Unable to connect");
+            client.send(&sent).expect("Unable to send message");
+            client.recv().expect("Unable to receive reply");
+
+            client.stats().expect("Stats should be available")
+        });
+
+        let mut server = listener.accept().expect("Unable to accept");
+        assert_eq!(message, server.recv().expect("Unable to receive message"));
+        server.send(b"done").expect("Unable to send reply");
+
+        let client_stats = handle.join().expect("Unable to join client thread");
+        assert_eq!(
+            client_stats.raw_bytes_sent(),
+            client_stats.compressed_bytes_sent()
+        );
+    }
 }
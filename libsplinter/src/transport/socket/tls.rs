@@ -26,21 +26,39 @@ use std::io;
 use std::net::{Ipv4Addr, Ipv6Addr, TcpListener, TcpStream};
 use std::os::unix::io::{AsRawFd, RawFd};
 use std::path::Path;
-
+#[cfg(feature = "tls-reload")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "tls-reload")]
+use std::time::{Duration, Instant, SystemTime};
+#[cfg(feature = "tls-reload")]
+use std::{fs, thread};
+
+#[cfg(feature = "tls-reload")]
+use crate::error::InternalError;
+#[cfg(feature = "tls-reload")]
+use crate::threading::lifecycle::ShutdownHandle;
+#[cfg(feature = "transport-compression")]
+use crate::transport::ConnectionStats;
 use crate::transport::{
     AcceptError, ConnectError, Connection, DisconnectError, ListenError, Listener, RecvError,
     SendError, Transport,
 };
 
-use super::frame::{Frame, FrameError, FrameNegotiation, FrameRef, FrameVersion};
+#[cfg(feature = "transport-compression")]
+use super::compression::CompressionConfig;
+use super::frame::{FrameCodec, FrameError, FrameVersion};
 
 /// tls:// is deprecated, tcps:// should be used instead
 const DEPRECATED_PROTOCOL_PREFIX: &str = "tls://";
 const PROTOCOL_PREFIX: &str = "tcps://";
 
 pub struct TlsTransport {
-    connector: SslConnector,
-    acceptor: SslAcceptor,
+    context: Arc<RwLock<TlsContext>>,
+    #[cfg(feature = "tls-reload")]
+    files: TlsFiles,
+    #[cfg(feature = "transport-compression")]
+    compression: Option<CompressionConfig>,
 }
 
 impl TlsTransport {
@@ -51,10 +69,90 @@ impl TlsTransport {
         server_key: String,
         server_cert: String,
     ) -> Result<Self, TlsInitError> {
-        let client_cert_path = Path::new(&client_cert);
-        let client_key_path = Path::new(&client_key);
-        let server_cert_path = Path::new(&server_cert);
-        let server_key_path = Path::new(&server_key);
+        let files = TlsFiles {
+            ca_cert,
+            client_key,
+            client_cert,
+            server_key,
+            server_cert,
+        };
+
+        let context = TlsContext::load(&files)?;
+
+        Ok(TlsTransport {
+            context: Arc::new(RwLock::new(context)),
+            #[cfg(feature = "tls-reload")]
+            files,
+            #[cfg(feature = "transport-compression")]
+            compression: None,
+        })
+    }
+
+    /// Returns a `TlsReloader` that can be used to reload the certificates and keys used by this
+    /// transport, and any listeners it has created, from their files.
+    #[cfg(feature = "tls-reload")]
+    pub fn reloader(&self) -> TlsReloader {
+        TlsReloader {
+            context: self.context.clone(),
+            files: self.files.clone(),
+            transport_reloads: vec![],
+        }
+    }
+
+    /// Offer compression with the given config on all connections created by this transport.
+    ///
+    /// Compression is only used on a connection if the remote end is configured with the same
+    /// algorithm.
+    #[cfg(feature = "transport-compression")]
+    pub fn with_compression(mut self, config: CompressionConfig) -> Self {
+        self.compression = Some(config);
+        self
+    }
+}
+
+/// The paths of the certificate and key files used to build a `TlsContext`.
+#[derive(Clone)]
+struct TlsFiles {
+    ca_cert: Option<String>,
+    client_key: String,
+    client_cert: String,
+    server_key: String,
+    server_cert: String,
+}
+
+#[cfg(feature = "tls-reload")]
+impl TlsFiles {
+    /// Returns the last modification time of each file, or `None` for a file that can't be read.
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        self.ca_cert
+            .iter()
+            .chain(
+                [
+                    &self.client_key,
+                    &self.client_cert,
+                    &self.server_key,
+                    &self.server_cert,
+                ]
+                .iter()
+                .copied(),
+            )
+            .map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+            .collect()
+    }
+}
+
+/// The connector and acceptor used to establish new TLS connections.
+struct TlsContext {
+    connector: SslConnector,
+    acceptor: SslAcceptor,
+}
+
+impl TlsContext {
+    fn load(files: &TlsFiles) -> Result<Self, TlsInitError> {
+        let client_cert_path = Path::new(&files.client_cert);
+        let client_key_path = Path::new(&files.client_key);
+        let server_cert_path = Path::new(&files.server_cert);
+        let server_key_path = Path::new(&files.server_key);
 
         // Build TLS Connector
         let mut connector = SslConnector::builder(SslMethod::tls())?;
@@ -69,28 +167,20 @@ impl TlsTransport {
         acceptor.check_private_key()?;
 
         // if ca_cert is provided set as accept cert, otherwise set verify to none
-        let (acceptor, connector) = {
-            if let Some(ca_cert) = ca_cert {
-                let ca_cert_path = Path::new(&ca_cert);
-                acceptor.set_ca_file(ca_cert_path)?;
-                connector.set_ca_file(ca_cert_path)?;
-                connector.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
-                acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
-                let connector = connector.build();
-                let acceptor = acceptor.build();
-                (acceptor, connector)
-            } else {
-                connector.set_verify(SslVerifyMode::NONE);
-                acceptor.set_verify(SslVerifyMode::NONE);
-                let connector = connector.build();
-                let acceptor = acceptor.build();
-                (acceptor, connector)
-            }
-        };
+        if let Some(ca_cert) = &files.ca_cert {
+            let ca_cert_path = Path::new(ca_cert);
+            acceptor.set_ca_file(ca_cert_path)?;
+            connector.set_ca_file(ca_cert_path)?;
+            connector.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+            acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        } else {
+            connector.set_verify(SslVerifyMode::NONE);
+            acceptor.set_verify(SslVerifyMode::NONE);
+        }
 
-        Ok(TlsTransport {
-            connector,
-            acceptor,
+        Ok(TlsContext {
+            connector: connector.build(),
+            acceptor: acceptor.build(),
         })
     }
 }
@@ -133,22 +223,27 @@ impl Transport for TlsTransport {
 
         let dns_name = endpoint_to_dns_name(address)?;
 
+        // Clone the connector so that a reload is not blocked on the handshake
+        let connector = rwlock_read_unwrap!(self.context).connector.clone();
         let stream = TcpStream::connect(address)?;
-        let mut tls_stream = self.connector.connect(&dns_name, stream)?;
-
-        let frame_version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::V1)
-            .negotiate(&mut tls_stream)
-            .map_err(|err| match err {
-                FrameError::UnsupportedVersion => ConnectError::ProtocolError(
-                    "Unable to connect; remote version is not with in range".into(),
-                ),
-                FrameError::IoError(err) => ConnectError::from(err),
-                e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
-            })?;
+        let mut tls_stream = connector.connect(&dns_name, stream)?;
+
+        let codec = FrameCodec::outbound(
+            &mut tls_stream,
+            #[cfg(feature = "transport-compression")]
+            self.compression.as_ref(),
+        )
+        .map_err(|err| match err {
+            FrameError::UnsupportedVersion => ConnectError::ProtocolError(
+                "Unable to connect; remote version is not with in range".into(),
+            ),
+            FrameError::IoError(err) => ConnectError::from(err),
+            e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
+        })?;
 
         tls_stream.get_ref().set_nonblocking(true)?;
         let connection = TlsConnection {
-            frame_version,
+            codec,
             stream: tls_stream,
         };
         Ok(Box::new(connection))
@@ -174,36 +269,45 @@ impl Transport for TlsTransport {
             listener: TcpListener::bind(address).map_err(|err| {
                 ListenError::IoError(format!("Failed to bind to {}", address), err)
             })?,
-            acceptor: self.acceptor.clone(),
+            context: self.context.clone(),
+            #[cfg(feature = "transport-compression")]
+            compression: self.compression.clone(),
         }))
     }
 }
 
 pub struct TlsListener {
     listener: TcpListener,
-    acceptor: SslAcceptor,
+    context: Arc<RwLock<TlsContext>>,
+    #[cfg(feature = "transport-compression")]
+    compression: Option<CompressionConfig>,
 }
 
 impl Listener for TlsListener {
     fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
         let (stream, _) = self.listener.accept()?;
-        let mut tls_stream = self.acceptor.accept(stream)?;
-
-        let frame_version = FrameNegotiation::inbound(FrameVersion::V1)
-            .negotiate(&mut tls_stream)
-            .map_err(|err| match err {
-                FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
-                    "Local {} protocol version {} not supported by remote",
-                    PROTOCOL_PREFIX,
-                    FrameVersion::V1
-                )),
-                FrameError::IoError(err) => AcceptError::from(err),
-                err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
-            })?;
+        // Clone the acceptor so that a reload is not blocked on the handshake
+        let acceptor = rwlock_read_unwrap!(self.context).acceptor.clone();
+        let mut tls_stream = acceptor.accept(stream)?;
+
+        let codec = FrameCodec::inbound(
+            &mut tls_stream,
+            #[cfg(feature = "transport-compression")]
+            self.compression.as_ref(),
+        )
+        .map_err(|err| match err {
+            FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
+                "Local {} protocol version {} not supported by remote",
+                PROTOCOL_PREFIX,
+                FrameVersion::V1
+            )),
+            FrameError::IoError(err) => AcceptError::from(err),
+            err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
+        })?;
 
         tls_stream.get_ref().set_nonblocking(true)?;
         let connection = TlsConnection {
-            frame_version,
+            codec,
             stream: tls_stream,
         };
         Ok(Box::new(connection))
@@ -215,13 +319,13 @@ impl Listener for TlsListener {
 }
 
 pub struct TlsConnection {
-    frame_version: FrameVersion,
+    codec: FrameCodec,
     stream: SslStream<TcpStream>,
 }
 
 impl Connection for TlsConnection {
     fn send(&mut self, message: &[u8]) -> Result<(), SendError> {
-        match FrameRef::new(self.frame_version, message).write(&mut self.stream) {
+        match self.codec.write(&mut self.stream, message) {
             Err(FrameError::IoError(e)) => Err(SendError::from(e)),
             Err(err) => Err(SendError::ProtocolError(err.to_string())),
             Ok(_) => Ok(()),
@@ -229,10 +333,10 @@ impl Connection for TlsConnection {
     }
 
     fn recv(&mut self) -> Result<Vec<u8>, RecvError> {
-        match Frame::read(&mut self.stream) {
+        match self.codec.read(&mut self.stream) {
             Err(FrameError::IoError(e)) => Err(RecvError::from(e)),
             Err(err) => Err(RecvError::ProtocolError(err.to_string())),
-            Ok(frame) => Ok(frame.into_inner()),
+            Ok(data) => Ok(data),
         }
     }
 
@@ -253,6 +357,11 @@ impl Connection for TlsConnection {
     fn evented(&self) -> &dyn Evented {
         self
     }
+
+    #[cfg(feature = "transport-compression")]
+    fn stats(&self) -> Option<ConnectionStats> {
+        Some(self.codec.stats())
+    }
 }
 
 impl TlsConnection {
@@ -263,7 +372,7 @@ impl TlsConnection {
     )]
     pub fn new(stream: SslStream<TcpStream>) -> Self {
         TlsConnection {
-            frame_version: FrameVersion::V1,
+            codec: FrameCodec::with_version(FrameVersion::V1),
             stream,
         }
     }
@@ -301,6 +410,149 @@ impl Evented for TlsConnection {
     }
 }
 
+/// A reload of another transport that uses the same certificate and key files.
+#[cfg(feature = "tls-reload")]
+type TransportReload = Arc<dyn Fn() -> Result<(), String> + Send + Sync>;
+
+/// Reloads the certificates and keys used by a `TlsTransport` from their files.
+///
+/// New connections, both inbound and outbound, are made using the reloaded material. Existing
+/// connections are unaffected.
+#[cfg(feature = "tls-reload")]
+#[derive(Clone)]
+pub struct TlsReloader {
+    context: Arc<RwLock<TlsContext>>,
+    files: TlsFiles,
+    transport_reloads: Vec<TransportReload>,
+}
+
+#[cfg(feature = "tls-reload")]
+impl TlsReloader {
+    /// Also reload another transport that uses the same files, such as a WebSocket or QUIC
+    /// transport, whenever this reloader reloads; this includes reloads by `watch`.
+    pub fn with_transport_reload<F>(mut self, reload: F) -> Self
+    where
+        F: Fn() -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.transport_reloads.push(Arc::new(reload));
+        self
+    }
+
+    /// Reload the certificates and keys.
+    ///
+    /// If any of the files can not be loaded, an error is returned and the transport continues to
+    /// use the previously loaded material. The other transports are only reloaded once the files
+    /// have loaded; an error is returned if any of them fail to reload.
+    pub fn reload(&self) -> Result<(), TlsInitError> {
+        let context = TlsContext::load(&self.files).map_err(|err| {
+            #[cfg(feature = "metrics")]
+            counter!("splinter.transport.tls.reload_failures", 1);
+            err
+        })?;
+
+        *rwlock_write_unwrap!(self.context) = context;
+
+        let errors = self
+            .transport_reloads
+            .iter()
+            .filter_map(|reload| reload().err())
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            #[cfg(feature = "metrics")]
+            counter!("splinter.transport.tls.reload_failures", 1);
+            return Err(TlsInitError::ProtocolError(errors.join(", ")));
+        }
+
+        info!("Reloaded TLS certificates and keys");
+        #[cfg(feature = "metrics")]
+        counter!("splinter.transport.tls.reloads", 1);
+
+        Ok(())
+    }
+
+    /// Start a thread that checks the certificate and key files for modifications every
+    /// `interval`, reloading them when any have changed.
+    ///
+    /// Returns a `TlsCertWatcher` which must be used to shut down the thread.
+    pub fn watch(&self, interval: Duration) -> Result<TlsCertWatcher, InternalError> {
+        let running = Arc::new(AtomicBool::new(true));
+        let last_modified = self.files.modified();
+
+        let thread_reloader = self.clone();
+        let thread_running = running.clone();
+        let join_handle = thread::Builder::new()
+            .name("TLS Certificate Watcher".into())
+            .spawn(move || watch_loop(interval, last_modified, thread_reloader, thread_running))
+            .map_err(|err| {
+                InternalError::from_source_with_message(
+                    Box::new(err),
+                    "Failed to spawn TLS certificate watcher thread".into(),
+                )
+            })?;
+
+        Ok(TlsCertWatcher {
+            running,
+            join_handle,
+        })
+    }
+}
+
+/// Loop until no longer `running`, reloading the TLS material whenever any of its files have been
+/// modified.
+#[cfg(feature = "tls-reload")]
+fn watch_loop(
+    interval: Duration,
+    mut last_modified: Vec<Option<SystemTime>>,
+    reloader: TlsReloader,
+    running: Arc<AtomicBool>,
+) {
+    loop {
+        // Wait the `interval`, checking for shutdown every second
+        let check_time = Instant::now() + interval;
+        while Instant::now() < check_time {
+            if !running.load(Ordering::SeqCst) {
+                return;
+            }
+            if let Some(time_left) = check_time.checked_duration_since(Instant::now()) {
+                thread::sleep(std::cmp::min(time_left, Duration::from_secs(1)));
+            }
+        }
+
+        let modified = reloader.files.modified();
+        if modified == last_modified {
+            continue;
+        }
+        // Files may be replaced one at a time, so a failure is retried on the next modification
+        // rather than on every interval.
+        last_modified = modified;
+
+        debug!("TLS certificate or key files modified, reloading");
+        if let Err(err) = reloader.reload() {
+            error!("Unable to reload TLS certificates and keys: {}", err);
+        }
+    }
+}
+
+/// Handle for shutting down the thread started by `TlsReloader::watch`.
+#[cfg(feature = "tls-reload")]
+pub struct TlsCertWatcher {
+    running: Arc<AtomicBool>,
+    join_handle: thread::JoinHandle<()>,
+}
+
+#[cfg(feature = "tls-reload")]
+impl ShutdownHandle for TlsCertWatcher {
+    fn signal_shutdown(&mut self) {
+        self.running.store(false, Ordering::SeqCst)
+    }
+
+    fn wait_for_shutdown(self) -> Result<(), InternalError> {
+        self.join_handle.join().map_err(|_| {
+            InternalError::with_message("Unable to shutdown TLS certificate watcher".to_string())
+        })
+    }
+}
+
 #[derive(Debug)]
 pub enum TlsInitError {
     ProtocolError(String),
@@ -451,6 +703,18 @@ pub(crate) mod tests {
         tests::test_poll(transport, "127.0.0.1:0");
     }
 
+    #[cfg(feature = "transport-compression")]
+    #[test]
+    fn test_transport_compression() {
+        let transport = create_test_tls_transport(true).with_compression(
+            crate::transport::socket::CompressionConfig::new(
+                crate::transport::socket::CompressionAlgorithm::Zstd,
+            )
+            .with_threshold(0),
+        );
+        tests::test_transport(transport, "127.0.0.1:0");
+    }
+
     #[test]
     fn test_transport_no_verify() {
         let transport = create_test_tls_transport(false);
@@ -467,4 +731,223 @@ pub(crate) mod tests {
             Ready::readable() | Ready::writable(),
         );
     }
+
+    /// Write a newly generated CA certificate, along with client and server keys and certificates
+    /// signed by it, to `dir`, replacing any existing files.
+    #[cfg(feature = "tls-reload")]
+    fn write_certs(dir: &Path) {
+        let (ca_key, ca_cert) = make_ca_cert();
+        let (client_key, client_cert) = make_ca_signed_cert(&ca_cert, &ca_key);
+        let (server_key, server_cert) = make_ca_signed_cert(&ca_cert, &ca_key);
+
+        write_file(dir.to_path_buf(), "ca.cert", &ca_cert.to_pem().unwrap());
+        write_file(
+            dir.to_path_buf(),
+            "client.key",
+            &client_key.private_key_to_pem_pkcs8().unwrap(),
+        );
+        write_file(
+            dir.to_path_buf(),
+            "client.cert",
+            &client_cert.to_pem().unwrap(),
+        );
+        write_file(
+            dir.to_path_buf(),
+            "server.key",
+            &server_key.private_key_to_pem_pkcs8().unwrap(),
+        );
+        write_file(
+            dir.to_path_buf(),
+            "server.cert",
+            &server_cert.to_pem().unwrap(),
+        );
+    }
+
+    /// Create a transport from the files written by `write_certs` to `dir`.
+    #[cfg(feature = "tls-reload")]
+    fn transport_from_dir(dir: &Path) -> TlsTransport {
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        TlsTransport::new(
+            Some(path("ca.cert")),
+            path("client.key"),
+            path("client.cert"),
+            path("server.key"),
+            path("server.cert"),
+        )
+        .expect("Unable to create transport")
+    }
+
+    /// Copy the files written by `write_certs` from `from` to `to`.
+    #[cfg(feature = "tls-reload")]
+    fn copy_certs(from: &Path, to: &Path) {
+        for name in &[
+            "ca.cert",
+            "client.key",
+            "client.cert",
+            "server.key",
+            "server.cert",
+        ] {
+            std::fs::copy(from.join(name), to.join(name)).expect("Unable to copy file");
+        }
+    }
+
+    /// Connect `client` to `listener`, returning the client transport along with the results of
+    /// both sides of the connection.
+    #[cfg(feature = "tls-reload")]
+    fn connect_to(
+        mut client: TlsTransport,
+        listener: &mut Box<dyn Listener>,
+    ) -> (
+        TlsTransport,
+        Result<Box<dyn Connection>, ConnectError>,
+        Result<Box<dyn Connection>, AcceptError>,
+    ) {
+        let endpoint = listener.endpoint();
+        let handle = std::thread::spawn(move || {
+            let result = client.connect(&endpoint);
+            (client, result)
+        });
+        let accepted = listener.accept();
+        let (client, connected) = handle.join().expect("Unable to join client thread");
+
+        (client, connected, accepted)
+    }
+
+    #[cfg(feature = "tls-reload")]
+    fn blocking_recv(connection: &mut Box<dyn Connection>) -> Vec<u8> {
+        loop {
+            match connection.recv() {
+                Err(RecvError::WouldBlock) => {
+                    std::thread::sleep(std::time::Duration::from_millis(10))
+                }
+                result => return result.expect("Unable to receive message"),
+            }
+        }
+    }
+
+    /// Verify that reloading a transport's certificates causes new connections to use the new
+    /// material, while connections made before the reload remain usable.
+    ///
+    /// 1. Create a transport and listener, and a client with a copy of the same material
+    /// 2. Connect the client to the listener
+    /// 3. Replace the transport's files with material from a new CA and reload
+    /// 4. Verify a client using the new material can connect
+    /// 5. Verify the client using the original material can no longer connect
+    /// 6. Verify the connection from step 2 can still be used
+    #[cfg(feature = "tls-reload")]
+    #[test]
+    fn test_reload() {
+        let server_dir = TempDir::new("tls-reload-server").unwrap();
+        let original_dir = TempDir::new("tls-reload-original").unwrap();
+
+        write_certs(server_dir.path());
+        copy_certs(server_dir.path(), original_dir.path());
+
+        let mut server = transport_from_dir(server_dir.path());
+        let reloader = server.reloader();
+        let mut listener = server.listen("127.0.0.1:0").expect("Unable to listen");
+
+        let original_client = transport_from_dir(original_dir.path());
+        let (original_client, connected, accepted) = connect_to(original_client, &mut listener);
+        let mut existing_client = connected.expect("Unable to connect before reload");
+        let mut existing_server = accepted.expect("Unable to accept before reload");
+
+        write_certs(server_dir.path());
+        reloader.reload().expect("Unable to reload");
+
+        let new_client = transport_from_dir(server_dir.path());
+        let (_, connected, accepted) = connect_to(new_client, &mut listener);
+        assert!(connected.is_ok());
+        assert!(accepted.is_ok());
+
+        let (_, connected, accepted) = connect_to(original_client, &mut listener);
+        assert!(connected.is_err());
+        assert!(accepted.is_err());
+
+        existing_client
+            .send(b"hello")
+            .expect("Unable to send message");
+        assert_eq!(b"hello".to_vec(), blocking_recv(&mut existing_server));
+    }
+
+    /// Verify that a reload which fails leaves the transport using its original material.
+    #[cfg(feature = "tls-reload")]
+    #[test]
+    fn test_reload_invalid_files() {
+        let server_dir = TempDir::new("tls-reload-server").unwrap();
+        let original_dir = TempDir::new("tls-reload-original").unwrap();
+
+        write_certs(server_dir.path());
+        copy_certs(server_dir.path(), original_dir.path());
+
+        let mut server = transport_from_dir(server_dir.path());
+        let reloader = server.reloader();
+        let mut listener = server.listen("127.0.0.1:0").expect("Unable to listen");
+
+        write_file(server_dir.path().to_path_buf(), "server.key", b"not a key");
+        assert!(reloader.reload().is_err());
+
+        let original_client = transport_from_dir(original_dir.path());
+        let (_, connected, accepted) = connect_to(original_client, &mut listener);
+        assert!(connected.is_ok());
+        assert!(accepted.is_ok());
+    }
+
+    /// Verify that a reload also reloads the other transports added to the reloader, and fails if
+    /// any of them fail to reload.
+    #[cfg(feature = "tls-reload")]
+    #[test]
+    fn test_reload_other_transports() {
+        let server_dir = TempDir::new("tls-reload-transports").unwrap();
+        write_certs(server_dir.path());
+
+        let server = transport_from_dir(server_dir.path());
+        let reloads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let thread_reloads = reloads.clone();
+        let reloader = server.reloader().with_transport_reload(move || {
+            thread_reloads.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        reloader.reload().expect("Unable to reload");
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+
+        let failing_reloader = reloader.with_transport_reload(|| Err("failed".into()));
+        assert!(failing_reloader.reload().is_err());
+        assert_eq!(reloads.load(Ordering::SeqCst), 2);
+    }
+
+    /// Verify that a `TlsCertWatcher` reloads the transport's material once its files are
+    /// modified, and that it can be shut down.
+    #[cfg(feature = "tls-reload")]
+    #[test]
+    fn test_watch() {
+        let server_dir = TempDir::new("tls-watch-server").unwrap();
+        write_certs(server_dir.path());
+
+        let mut server = transport_from_dir(server_dir.path());
+        let mut watcher = server
+            .reloader()
+            .watch(std::time::Duration::from_millis(100))
+            .expect("Unable to start watcher");
+        let mut listener = server.listen("127.0.0.1:0").expect("Unable to listen");
+
+        write_certs(server_dir.path());
+
+        let mut reloaded = false;
+        for _ in 0..50 {
+            let new_client = transport_from_dir(server_dir.path());
+            if let (_, Ok(_), Ok(_)) = connect_to(new_client, &mut listener) {
+                reloaded = true;
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        assert!(reloaded, "Watcher did not reload the modified files");
+
+        watcher.signal_shutdown();
+        watcher
+            .wait_for_shutdown()
+            .expect("Unable to shutdown watcher");
+    }
 }
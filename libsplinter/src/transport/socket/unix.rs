@@ -0,0 +1,570 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use mio::{unix::EventedFd, Evented, Poll, PollOpt, Ready, Token};
+
+use std::fs;
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::{UnixListener as StdUnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "transport-compression")]
+use crate::transport::ConnectionStats;
+use crate::transport::{
+    AcceptError, ConnectError, Connection, DisconnectError, ListenError, Listener, RecvError,
+    SendError, Transport,
+};
+
+use super::frame::{FrameCodec, FrameError, FrameVersion};
+
+const PROTOCOL_PREFIX: &str = "unix://";
+
+/// The default permissions of a socket file; only the owner may connect.
+pub const DEFAULT_SOCKET_PERMISSIONS: u32 = 0o600;
+
+/// A `Transport` for `unix://` endpoints, which communicate over Unix domain sockets on the local
+/// host.
+///
+/// The endpoint is the path of the socket file, for example `unix:///var/run/splinter.sock`.
+/// Messages are framed in the same way as the `TcpTransport`.
+///
+/// Access to a listener is controlled in two ways: the socket file is created with the configured
+/// permissions, and, if any user or group IDs are allowed explicitly, connections from processes
+/// whose peer credentials match none of them are rejected.
+pub struct UnixTransport {
+    permissions: u32,
+    allowed_uids: Vec<u32>,
+    allowed_gids: Vec<u32>,
+}
+
+impl Default for UnixTransport {
+    fn default() -> Self {
+        UnixTransport {
+            permissions: DEFAULT_SOCKET_PERMISSIONS,
+            allowed_uids: vec![],
+            allowed_gids: vec![],
+        }
+    }
+}
+
+impl UnixTransport {
+    /// Set the permissions, as a Unix mode such as `0o660`, of the socket files created by
+    /// listeners.
+    pub fn with_permissions(mut self, permissions: u32) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Only accept connections from processes running as one of the given user IDs, or as one of
+    /// the group IDs set with `with_allowed_gids`.
+    pub fn with_allowed_uids(mut self, uids: Vec<u32>) -> Self {
+        self.allowed_uids = uids;
+        self
+    }
+
+    /// Only accept connections from processes running as one of the given group IDs, or as one
+    /// of the user IDs set with `with_allowed_uids`.
+    pub fn with_allowed_gids(mut self, gids: Vec<u32>) -> Self {
+        self.allowed_gids = gids;
+        self
+    }
+}
+
+impl Transport for UnixTransport {
+    fn accepts(&self, address: &str) -> bool {
+        address.starts_with(PROTOCOL_PREFIX)
+    }
+
+    fn connect(&mut self, endpoint: &str) -> Result<Box<dyn Connection>, ConnectError> {
+        let path = endpoint.strip_prefix(PROTOCOL_PREFIX).ok_or_else(|| {
+            ConnectError::ProtocolError(format!("Invalid protocol \"{}\"", endpoint))
+        })?;
+
+        let mut stream = UnixStream::connect(path)?;
+
+        let codec = FrameCodec::outbound(
+            &mut stream,
+            #[cfg(feature = "transport-compression")]
+            None,
+        )
+        .map_err(|err| match err {
+            FrameError::UnsupportedVersion => ConnectError::ProtocolError(
+                "Unable to connect; remote version is not with in range".into(),
+            ),
+            FrameError::IoError(err) => ConnectError::from(err),
+            e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
+        })?;
+
+        stream.set_nonblocking(true)?;
+        Ok(Box::new(UnixConnection {
+            codec,
+            stream,
+            remote_endpoint: endpoint.to_string(),
+            local_endpoint: endpoint.to_string(),
+        }))
+    }
+
+    fn listen(&mut self, bind: &str) -> Result<Box<dyn Listener>, ListenError> {
+        let path = bind
+            .strip_prefix(PROTOCOL_PREFIX)
+            .ok_or_else(|| ListenError::ProtocolError(format!("Invalid protocol \"{}\"", bind)))?;
+        let path = PathBuf::from(path);
+
+        remove_stale_socket(&path)?;
+
+        let listener = bind_with_permissions(&path, self.permissions)?;
+
+        Ok(Box::new(UnixListener {
+            listener,
+            path,
+            allowed_uids: self.allowed_uids.clone(),
+            allowed_gids: self.allowed_gids.clone(),
+        }))
+    }
+}
+
+/// Bind a listener at the given path, with the socket file's permissions set before the socket
+/// can be reached at that path.
+///
+/// The socket is bound in a temporary directory next to the path that only the owner can access,
+/// its permissions are set, and it is then renamed to the path. This leaves no window in which
+/// another process could connect to a socket that still has the default permissions.
+fn bind_with_permissions(path: &Path, permissions: u32) -> Result<StdUnixListener, ListenError> {
+    let file_name = path.file_name().ok_or_else(|| {
+        ListenError::ProtocolError(format!("{} is not a valid socket path", path.display()))
+    })?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut bind_dir_name = std::ffi::OsString::from(".");
+    bind_dir_name.push(file_name);
+    bind_dir_name.push(format!(".{}.tmp", std::process::id()));
+    let bind_dir = parent.join(bind_dir_name);
+
+    // A directory left behind by a previous attempt from a process with the same ID
+    match fs::remove_dir_all(&bind_dir) {
+        Ok(()) => (),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+        Err(err) => {
+            return Err(ListenError::IoError(
+                format!("Failed to remove {}", bind_dir.display()),
+                err,
+            ))
+        }
+    }
+
+    fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&bind_dir)
+        .map_err(|err| {
+            ListenError::IoError(format!("Failed to create {}", bind_dir.display()), err)
+        })?;
+
+    let result = bind_in_dir(&bind_dir, Path::new(file_name), path, permissions);
+
+    if let Err(err) = fs::remove_dir_all(&bind_dir) {
+        warn!("Unable to remove {}: {}", bind_dir.display(), err);
+    }
+
+    result
+}
+
+/// Bind a listener in the given directory, set its permissions, and move it to the path.
+fn bind_in_dir(
+    bind_dir: &Path,
+    file_name: &Path,
+    path: &Path,
+    permissions: u32,
+) -> Result<StdUnixListener, ListenError> {
+    let bind_path = bind_dir.join(file_name);
+
+    let listener = StdUnixListener::bind(&bind_path).map_err(|err| {
+        ListenError::IoError(format!("Failed to bind to {}", path.display()), err)
+    })?;
+
+    fs::set_permissions(&bind_path, fs::Permissions::from_mode(permissions)).map_err(|err| {
+        ListenError::IoError(
+            format!("Failed to set permissions of {}", path.display()),
+            err,
+        )
+    })?;
+
+    fs::rename(&bind_path, path).map_err(|err| {
+        ListenError::IoError(format!("Failed to bind to {}", path.display()), err)
+    })?;
+
+    Ok(listener)
+}
+
+/// Remove a socket file left behind by a listener that was not shut down cleanly.
+///
+/// The file is only removed if it is a socket that no process is listening on.
+fn remove_stale_socket(path: &Path) -> Result<(), ListenError> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(ListenError::IoError(
+                format!("Failed to check {}", path.display()),
+                err,
+            ))
+        }
+    };
+
+    if !metadata.file_type().is_socket() {
+        return Err(ListenError::ProtocolError(format!(
+            "{} exists and is not a socket",
+            path.display()
+        )));
+    }
+
+    match UnixStream::connect(path) {
+        Ok(_) => Err(ListenError::ProtocolError(format!(
+            "{} is already in use",
+            path.display()
+        ))),
+        Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path)
+            .map_err(|err| {
+                ListenError::IoError(format!("Failed to remove {}", path.display()), err)
+            }),
+        Err(err) => Err(ListenError::IoError(
+            format!("Failed to check {}", path.display()),
+            err,
+        )),
+    }
+}
+
+struct UnixListener {
+    listener: StdUnixListener,
+    path: PathBuf,
+    allowed_uids: Vec<u32>,
+    allowed_gids: Vec<u32>,
+}
+
+impl UnixListener {
+    fn is_authorized(&self, credentials: &PeerCredentials) -> bool {
+        (self.allowed_uids.is_empty() && self.allowed_gids.is_empty())
+            || self.allowed_uids.contains(&credentials.uid)
+            || self.allowed_gids.contains(&credentials.gid)
+    }
+}
+
+impl Listener for UnixListener {
+    fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
+        let (mut stream, _) = self.listener.accept()?;
+
+        let credentials = PeerCredentials::from_stream(&stream)?;
+        if !self.is_authorized(&credentials) {
+            return Err(AcceptError::ProtocolError(format!(
+                "Rejected connection to {} from unauthorized peer (uid {}, gid {})",
+                self.endpoint(),
+                credentials.uid,
+                credentials.gid
+            )));
+        }
+
+        let codec = FrameCodec::inbound(
+            &mut stream,
+            #[cfg(feature = "transport-compression")]
+            None,
+        )
+        .map_err(|err| match err {
+            FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
+                "Local {} protocol version {} not supported by remote",
+                PROTOCOL_PREFIX,
+                FrameVersion::V1
+            )),
+            FrameError::IoError(err) => AcceptError::from(err),
+            err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
+        })?;
+
+        stream.set_nonblocking(true)?;
+        Ok(Box::new(UnixConnection {
+            codec,
+            stream,
+            // Peers connect from unnamed sockets, so both ends are identified by the socket path
+            remote_endpoint: self.endpoint(),
+            local_endpoint: self.endpoint(),
+        }))
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{}{}", PROTOCOL_PREFIX, self.path.display())
+    }
+}
+
+impl Drop for UnixListener {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            debug!("Unable to remove socket {}: {}", self.path.display(), err);
+        }
+    }
+}
+
+/// The credentials of the process on the other end of a Unix domain socket.
+struct PeerCredentials {
+    uid: u32,
+    gid: u32,
+}
+
+impl PeerCredentials {
+    #[cfg(target_os = "linux")]
+    fn from_stream(stream: &UnixStream) -> io::Result<Self> {
+        let mut ucred = libc::ucred {
+            pid: 0,
+            uid: 0,
+            gid: 0,
+        };
+        let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+
+        // Safe, as the pointers are valid for the duration of the call and len is the size of
+        // the ucred struct
+        let result = unsafe {
+            libc::getsockopt(
+                stream.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                &mut ucred as *mut libc::ucred as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(PeerCredentials {
+            uid: ucred.uid,
+            gid: ucred.gid,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn from_stream(stream: &UnixStream) -> io::Result<Self> {
+        let mut uid: libc::uid_t = 0;
+        let mut gid: libc::gid_t = 0;
+
+        // Safe, as the pointers are valid for the duration of the call
+        let result = unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(PeerCredentials { uid, gid })
+    }
+}
+
+struct UnixConnection {
+    codec: FrameCodec,
+    stream: UnixStream,
+    remote_endpoint: String,
+    local_endpoint: String,
+}
+
+impl Connection for UnixConnection {
+    fn send(&mut self, message: &[u8]) -> Result<(), SendError> {
+        match self.codec.write(&mut self.stream, message) {
+            Err(FrameError::IoError(e)) => Err(SendError::from(e)),
+            Err(err) => Err(SendError::ProtocolError(err.to_string())),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    fn recv(&mut self) -> Result<Vec<u8>, RecvError> {
+        match self.codec.read(&mut self.stream) {
+            Err(FrameError::IoError(e)) => Err(RecvError::from(e)),
+            Err(err) => Err(RecvError::ProtocolError(err.to_string())),
+            Ok(data) => Ok(data),
+        }
+    }
+
+    fn remote_endpoint(&self) -> String {
+        self.remote_endpoint.clone()
+    }
+
+    fn local_endpoint(&self) -> String {
+        self.local_endpoint.clone()
+    }
+
+    fn disconnect(&mut self) -> Result<(), DisconnectError> {
+        self.stream
+            .shutdown(Shutdown::Both)
+            .map_err(DisconnectError::from)
+    }
+
+    fn evented(&self) -> &dyn Evented {
+        self
+    }
+
+    #[cfg(feature = "transport-compression")]
+    fn stats(&self) -> Option<ConnectionStats> {
+        Some(self.codec.stats())
+    }
+}
+
+impl AsRawFd for UnixConnection {
+    fn as_raw_fd(&self) -> RawFd {
+        self.stream.as_raw_fd()
+    }
+}
+
+impl Evented for UnixConnection {
+    fn register(
+        &self,
+        poll: &Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(poll, token, interest, opts)
+    }
+
+    fn reregister(
+        &self,
+        poll: &Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(poll, token, interest, opts)
+    }
+
+    fn deregister(&self, poll: &Poll) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(poll)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::tests;
+
+    use tempdir::TempDir;
+
+    fn socket_endpoint(temp_dir: &TempDir, name: &str) -> String {
+        format!(
+            "{}{}",
+            PROTOCOL_PREFIX,
+            temp_dir.path().join(name).display()
+        )
+    }
+
+    #[test]
+    fn test_accepts() {
+        let transport = UnixTransport::default();
+        assert!(transport.accepts("unix:///var/run/splinter.sock"));
+
+        assert!(!transport.accepts("/var/run/splinter.sock"));
+        assert!(!transport.accepts("127.0.0.1:0"));
+        assert!(!transport.accepts("tcp://127.0.0.1:0"));
+    }
+
+    #[test]
+    fn test_transport() {
+        let temp_dir = TempDir::new("test_unix_transport").unwrap();
+        let transport = UnixTransport::default();
+
+        tests::test_transport(transport, &socket_endpoint(&temp_dir, "transport.sock"));
+    }
+
+    #[test]
+    fn test_poll() {
+        let temp_dir = TempDir::new("test_unix_poll").unwrap();
+        let transport = UnixTransport::default();
+
+        tests::test_poll(transport, &socket_endpoint(&temp_dir, "poll.sock"));
+    }
+
+    /// Test that the socket file is created with the configured permissions, that the directory
+    /// it was bound in is removed, and that the socket file is removed when the listener is
+    /// dropped.
+    #[test]
+    fn test_socket_permissions() {
+        let temp_dir = TempDir::new("test_unix_permissions").unwrap();
+        let path = temp_dir.path().join("permissions.sock");
+        let mut transport = UnixTransport::default().with_permissions(0o660);
+
+        let listener = transport
+            .listen(&format!("{}{}", PROTOCOL_PREFIX, path.display()))
+            .expect("Unable to listen");
+
+        let mode = fs::metadata(&path)
+            .expect("Unable to read socket metadata")
+            .permissions()
+            .mode();
+        assert_eq!(0o660, mode & 0o777);
+        assert_eq!(
+            vec![path.clone()],
+            fs::read_dir(temp_dir.path())
+                .expect("Unable to read temp dir")
+                .map(|entry| entry.expect("Unable to read dir entry").path())
+                .collect::<Vec<_>>()
+        );
+
+        drop(listener);
+        assert!(!path.exists());
+    }
+
+    /// Test that a stale socket file is replaced, but that a socket with an active listener is
+    /// not.
+    #[test]
+    fn test_stale_socket() {
+        let temp_dir = TempDir::new("test_unix_stale").unwrap();
+        let path = temp_dir.path().join("stale.sock");
+        let endpoint = format!("{}{}", PROTOCOL_PREFIX, path.display());
+
+        // Leave a socket file behind without a listener
+        drop(StdUnixListener::bind(&path).expect("Unable to bind"));
+        assert!(path.exists());
+
+        let mut transport = UnixTransport::default();
+        let _listener = transport.listen(&endpoint).expect("Unable to listen");
+
+        assert!(transport.listen(&endpoint).is_err());
+    }
+
+    /// Test that connections from peers whose credentials are not allowed are rejected, and that
+    /// connections from allowed peers are accepted.
+    #[test]
+    fn test_peer_credentials() {
+        let temp_dir = TempDir::new("test_unix_credentials").unwrap();
+        // Safe, as these calls have no preconditions
+        let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+
+        let endpoint = socket_endpoint(&temp_dir, "allowed.sock");
+        let mut transport = UnixTransport::default().with_allowed_uids(vec![uid]);
+        let mut listener = transport.listen(&endpoint).expect("Unable to listen");
+        // The connecting side blocks on version negotiation, so connect from another thread
+        let handle = std::thread::spawn(move || transport.connect(&endpoint).is_ok());
+        assert!(listener.accept().is_ok());
+        assert!(handle.join().expect("Unable to join thread"));
+
+        let endpoint = socket_endpoint(&temp_dir, "rejected.sock");
+        let mut transport = UnixTransport::default()
+            .with_allowed_uids(vec![uid.wrapping_add(1)])
+            .with_allowed_gids(vec![gid.wrapping_add(1)]);
+        let mut listener = transport.listen(&endpoint).expect("Unable to listen");
+        let handle = std::thread::spawn(move || transport.connect(&endpoint).is_err());
+        match listener.accept() {
+            Err(AcceptError::ProtocolError(_)) => (),
+            Err(err) => panic!("Unexpected error: {}", err),
+            Ok(_) => panic!("Connection should have been rejected"),
+        }
+        assert!(handle.join().expect("Unable to join thread"));
+    }
+}
@@ -13,34 +13,35 @@
 // limitations under the License.
 
 use std::net::TcpListener;
+use std::sync::{Arc, RwLock};
 use std::thread;
 use std::time::Duration;
 
-use openssl::ssl::SslAcceptor;
 use tungstenite::{accept, handshake::HandshakeError};
 
 use crate::transport::{AcceptError, Connection, Listener};
 
 use super::connection::WsConnection;
+use super::transport::TlsInner;
 use super::transport::WSS_PROTOCOL_PREFIX;
 use super::transport::WS_PROTOCOL_PREFIX;
 
 pub(super) struct WsListener {
     listener: TcpListener,
     local_endpoint: String,
-    acceptor: Option<SslAcceptor>,
+    tls_inner: Option<Arc<RwLock<TlsInner>>>,
 }
 
 impl WsListener {
     pub fn new(
         listener: TcpListener,
         local_endpoint: String,
-        acceptor: Option<SslAcceptor>,
+        tls_inner: Option<Arc<RwLock<TlsInner>>>,
     ) -> Self {
         WsListener {
             listener,
             local_endpoint,
-            acceptor,
+            tls_inner,
         }
     }
 }
@@ -49,7 +50,9 @@ impl Listener for WsListener {
     fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
         let (stream, _) = self.listener.accept()?;
 
-        if let Some(acceptor) = &self.acceptor {
+        if let Some(tls_inner) = &self.tls_inner {
+            // Clone the acceptor so that a reload is not blocked on the handshake
+            let acceptor = rwlock_read_unwrap!(tls_inner).acceptor.clone();
             let remote_endpoint = format!("{}{}", WSS_PROTOCOL_PREFIX, stream.peer_addr()?);
 
             let websocket = accept(acceptor.accept(stream)?).map_or_else(
@@ -21,6 +21,8 @@ mod connection;
 mod listener;
 mod transport;
 
+#[cfg(feature = "tls-reload")]
+pub use transport::WsReloader;
 pub use transport::WsTransport;
 
 #[cfg(test)]
@@ -145,4 +147,26 @@ mod tests {
         let transport = WsTransport::new(Some(&config)).unwrap();
         tests::test_poll(transport, "wss://127.0.0.1:18083");
     }
+
+    /// Verify that a transport without TLS has no reloader, and that reloading a TLS transport
+    /// fails without replacing the loaded material when the files are missing.
+    #[cfg(feature = "tls-reload")]
+    #[test]
+    fn test_wss_reload() {
+        assert!(WsTransport::default().reloader().is_none());
+
+        let temp_dir = TempDir::new("test-wss-reload").unwrap();
+        let config = create_test_tls_config(&temp_dir, true);
+        let transport = WsTransport::new(Some(&config)).unwrap();
+        let reloader = transport
+            .reloader()
+            .expect("No reloader for a TLS transport");
+
+        reloader.reload().expect("Unable to reload");
+
+        std::fs::remove_file(config.server_cert_file()).unwrap();
+        assert!(reloader.reload().is_err());
+
+        tests::test_transport(transport, "wss://127.0.0.1:18084");
+    }
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::net::{Ipv4Addr, Ipv6Addr, TcpListener, TcpStream};
+use std::sync::{Arc, RwLock};
 use std::thread;
 use std::time::Duration;
 
@@ -30,9 +31,18 @@ use super::listener::WsListener;
 pub(super) const WS_PROTOCOL_PREFIX: &str = "ws://";
 pub(super) const WSS_PROTOCOL_PREFIX: &str = "wss://";
 
-struct TlsInner {
-    acceptor: SslAcceptor,
-    connector: SslConnector,
+pub(super) struct TlsInner {
+    pub acceptor: SslAcceptor,
+    pub connector: SslConnector,
+}
+
+impl TlsInner {
+    fn load(config: &TlsConfig) -> Result<Self, WsInitError> {
+        Ok(TlsInner {
+            acceptor: build_acceptor(config)?,
+            connector: build_connector(config)?,
+        })
+    }
 }
 
 /// A WebSocket-based `Transport`.
@@ -95,22 +105,61 @@ struct TlsInner {
 /// ```
 #[derive(Default)]
 pub struct WsTransport {
-    tls_inner: Option<TlsInner>,
+    tls_inner: Option<Arc<RwLock<TlsInner>>>,
+    #[cfg(feature = "tls-reload")]
+    tls_config: Option<TlsConfig>,
 }
 
 impl WsTransport {
     pub fn new(config: Option<&TlsConfig>) -> Result<Self, WsInitError> {
         if let Some(conf) = config {
             Ok(WsTransport {
-                tls_inner: Some(TlsInner {
-                    acceptor: build_acceptor(&conf)?,
-                    connector: build_connector(&conf)?,
-                }),
+                tls_inner: Some(Arc::new(RwLock::new(TlsInner::load(conf)?))),
+                #[cfg(feature = "tls-reload")]
+                tls_config: Some(conf.clone()),
             })
         } else {
-            Ok(WsTransport { tls_inner: None })
+            Ok(WsTransport::default())
         }
     }
+
+    /// Returns a `WsReloader` that can be used to reload the certificates and keys used by this
+    /// transport, and any listeners it has created, from their files; `None` if the transport was
+    /// created without TLS.
+    #[cfg(feature = "tls-reload")]
+    pub fn reloader(&self) -> Option<WsReloader> {
+        match (&self.tls_inner, &self.tls_config) {
+            (Some(tls_inner), Some(tls_config)) => Some(WsReloader {
+                tls_inner: tls_inner.clone(),
+                tls_config: tls_config.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Reloads the certificates and keys used by a `WsTransport` from their files.
+///
+/// New `wss://` connections, both inbound and outbound, are made using the reloaded material.
+/// Existing connections are unaffected.
+#[cfg(feature = "tls-reload")]
+#[derive(Clone)]
+pub struct WsReloader {
+    tls_inner: Arc<RwLock<TlsInner>>,
+    tls_config: TlsConfig,
+}
+
+#[cfg(feature = "tls-reload")]
+impl WsReloader {
+    /// Reload the certificates and keys.
+    ///
+    /// If any of the files can not be loaded, an error is returned and the transport continues to
+    /// use the previously loaded material.
+    pub fn reload(&self) -> Result<(), WsInitError> {
+        let tls_inner = TlsInner::load(&self.tls_config)?;
+        *rwlock_write_unwrap!(self.tls_inner) = tls_inner;
+        Ok(())
+    }
 }
 
 fn endpoint_to_dns_name(endpoint: &str) -> Result<String, ParseError> {
@@ -169,17 +218,15 @@ impl Transport for WsTransport {
             let remote_endpoint = format!("{}{}", WSS_PROTOCOL_PREFIX, stream.peer_addr()?);
             let local_endpoint = format!("{}{}", WSS_PROTOCOL_PREFIX, stream.local_addr()?);
 
-            let tls_stream = self
-                .tls_inner
-                .as_ref()
-                .ok_or_else(|| {
-                    ConnectError::ProtocolError(format!(
-                        "Protocol {} requires TLS, which is not configured",
-                        WSS_PROTOCOL_PREFIX
-                    ))
-                })?
-                .connector
-                .connect(&dns_name, stream)?;
+            let tls_inner = self.tls_inner.as_ref().ok_or_else(|| {
+                ConnectError::ProtocolError(format!(
+                    "Protocol {} requires TLS, which is not configured",
+                    WSS_PROTOCOL_PREFIX
+                ))
+            })?;
+            // Clone the connector so that a reload is not blocked on the handshake
+            let connector = rwlock_read_unwrap!(tls_inner).connector.clone();
+            let tls_stream = connector.connect(&dns_name, stream)?;
 
             let (websocket, _) = client(endpoint, tls_stream).map_or_else(
                 {
@@ -231,7 +278,7 @@ impl Transport for WsTransport {
                 None,
             )))
         } else if let Some(address) = bind.strip_prefix(WSS_PROTOCOL_PREFIX) {
-            let inner = self.tls_inner.as_ref().ok_or_else(|| {
+            let tls_inner = self.tls_inner.as_ref().ok_or_else(|| {
                 ListenError::ProtocolError(
                     "TLS support required for the wss:// protocol".to_string(),
                 )
@@ -251,7 +298,7 @@ impl Transport for WsTransport {
             Ok(Box::new(WsListener::new(
                 tcp_listener,
                 local_endpoint,
-                Some(inner.acceptor.clone()),
+                Some(tls_inner.clone()),
             )))
         } else {
             Err(ListenError::ProtocolError(format!(
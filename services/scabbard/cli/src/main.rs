@@ -20,6 +20,8 @@ mod signing;
 
 use std::fs::File;
 use std::io::{BufReader, Read};
+#[cfg(feature = "state-pruning")]
+use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -52,6 +54,12 @@ use signing::{create_cylinder_jwt_auth, load_signer};
 
 const DEFAULT_SPLINTER_REST_API_URL: &str = "http://127.0.0.1:8080";
 const SPLINTER_REST_API_URL_ENV: &str = "SPLINTER_REST_API_URL";
+#[cfg(feature = "state-pruning")]
+const DEFAULT_STATE_DIR: &str = "/var/lib/splinter";
+#[cfg(feature = "state-pruning")]
+const SPLINTER_STATE_DIR_ENV: &str = "SPLINTER_STATE_DIR";
+#[cfg(feature = "state-pruning")]
+const DEFAULT_STATE_DB_SIZE: usize = 1 << 30; // 1024 ** 3
 
 fn main() {
     if let Err(e) = run() {
@@ -546,36 +554,201 @@ fn run() -> Result<(), CliError> {
                                 .default_value("300"),
                         ]),
                 ),
-        )
+        );
+
+    #[allow(unused_mut)]
+    let mut state_subcommand = SubCommand::with_name("state")
+        .about("Get scabbard state information")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
         .subcommand(
-            SubCommand::with_name("state")
-                .about("Get scabbard state information")
+            SubCommand::with_name("root")
+                .about("Get the current state root hash")
+                .args(&[
+                    Arg::with_name("url")
+                        .help("URL to the scabbard REST API")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true),
+                    Arg::with_name("service-id")
+                        .long_help(
+                            "Fully-qualified service ID of the scabbard service (must be \
+                             of the form 'circuit_id::service_id')",
+                        )
+                        .long("service-id")
+                        .takes_value(true)
+                        .required(true),
+                    Arg::with_name("key")
+                        .short("k")
+                        .long("key")
+                        .takes_value(true)
+                        .help("Name or path of private key"),
+                ]),
+        );
+
+    #[cfg(feature = "state-history")]
+    {
+        let common_args = [
+            Arg::with_name("url")
+                .help("URL to the scabbard REST API")
+                .short("U")
+                .long("url")
+                .takes_value(true),
+            Arg::with_name("service-id")
+                .long_help(
+                    "Fully-qualified service ID of the scabbard service (must be of the form \
+                     'circuit_id::service_id')",
+                )
+                .long("service-id")
+                .takes_value(true)
+                .required(true),
+            Arg::with_name("key")
+                .short("k")
+                .long("key")
+                .takes_value(true)
+                .help("Name or path of private key"),
+        ];
+
+        state_subcommand = state_subcommand
+            .subcommand(
+                SubCommand::with_name("get")
+                    .about("Get the value at an address in state, optionally at a past state root")
+                    .args(&common_args)
+                    .args(&[
+                        Arg::with_name("address")
+                            .help("Address of the state entry")
+                            .takes_value(true)
+                            .required(true),
+                        Arg::with_name("state-root")
+                            .help("State root hash to read state at (default: current)")
+                            .long("state-root")
+                            .takes_value(true),
+                    ]),
+            )
+            .subcommand(
+                SubCommand::with_name("list")
+                    .about("List entries in state, optionally at a past state root")
+                    .args(&common_args)
+                    .args(&[
+                        Arg::with_name("prefix")
+                            .help("Only list entries under this address prefix")
+                            .long("prefix")
+                            .takes_value(true),
+                        Arg::with_name("state-root")
+                            .help("State root hash to read state at (default: current)")
+                            .long("state-root")
+                            .takes_value(true),
+                    ]),
+            )
+            .subcommand(
+                SubCommand::with_name("roots")
+                    .about("List recent state root hashes and the batches that produced them")
+                    .args(&common_args)
+                    .arg(
+                        Arg::with_name("limit")
+                            .help("Maximum number of state roots to list")
+                            .long("limit")
+                            .takes_value(true),
+                    ),
+            );
+    }
+
+    #[cfg(feature = "state-pruning")]
+    {
+        state_subcommand = state_subcommand.subcommand(
+            SubCommand::with_name("prune")
+                .about(
+                    "Prune old state roots from the state database of a scabbard service that is \
+                     not running",
+                )
+                .args(&[
+                    Arg::with_name("service-id")
+                        .long_help(
+                            "Fully-qualified service ID of the scabbard service (must be of the \
+                             form 'circuit_id::service_id')",
+                        )
+                        .long("service-id")
+                        .takes_value(true)
+                        .required(true),
+                    Arg::with_name("state-dir")
+                        .help("Directory containing the scabbard state databases")
+                        .long("state-dir")
+                        .takes_value(true),
+                    Arg::with_name("depth")
+                        .help("Number of most recent state roots to retain (must be at least 1)")
+                        .long("depth")
+                        .takes_value(true)
+                        .required(true),
+                    Arg::with_name("state-db-size")
+                        .help("Maximum size of the state database, in bytes")
+                        .long("state-db-size")
+                        .takes_value(true),
+                    Arg::with_name("compact")
+                        .help("Compact the state database after pruning to reclaim disk space")
+                        .long("compact"),
+                ]),
+        );
+    }
+
+    app = app.subcommand(state_subcommand);
+
+    #[cfg(feature = "receipts")]
+    {
+        let common_args = [
+            Arg::with_name("url")
+                .help("URL to the scabbard REST API")
+                .short("U")
+                .long("url")
+                .takes_value(true),
+            Arg::with_name("service-id")
+                .long_help(
+                    "Fully-qualified service ID of the scabbard service (must be of the form \
+                     'circuit_id::service_id')",
+                )
+                .long("service-id")
+                .takes_value(true)
+                .required(true),
+            Arg::with_name("key")
+                .short("k")
+                .long("key")
+                .takes_value(true)
+                .help("Name or path of private key"),
+        ];
+
+        app = app.subcommand(
+            SubCommand::with_name("receipt")
+                .about("Show receipts of committed transactions")
                 .setting(AppSettings::SubcommandRequiredElseHelp)
                 .subcommand(
-                    SubCommand::with_name("root")
-                        .about("Get the current state root hash")
-                        .args(&[
-                            Arg::with_name("url")
-                                .help("URL to the scabbard REST API")
-                                .short("U")
-                                .long("url")
-                                .takes_value(true),
-                            Arg::with_name("service-id")
-                                .long_help(
-                                    "Fully-qualified service ID of the scabbard service (must be \
-                                     of the form 'circuit_id::service_id')",
-                                )
-                                .long("service-id")
+                    SubCommand::with_name("get")
+                        .about("Show the receipt of a committed transaction")
+                        .args(&common_args)
+                        .arg(
+                            Arg::with_name("transaction-id")
+                                .help("ID of the committed transaction")
                                 .takes_value(true)
                                 .required(true),
-                            Arg::with_name("key")
-                                .short("k")
-                                .long("key")
-                                .takes_value(true)
-                                .help("Name or path of private key"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List the receipts of committed transactions, oldest first")
+                        .args(&common_args)
+                        .args(&[
+                            Arg::with_name("since")
+                                .help(
+                                    "Only list receipts of transactions committed after the \
+                                     transaction with this ID",
+                                )
+                                .long("since")
+                                .takes_value(true),
+                            Arg::with_name("limit")
+                                .help("Maximum number of receipts to list")
+                                .long("limit")
+                                .takes_value(true),
                         ]),
                 ),
         );
+    }
 
     #[cfg(feature = "smart-permissions")]
     {
@@ -1459,12 +1632,245 @@ fn run() -> Result<(), CliError> {
 
                 Ok(())
             }
+            #[cfg(feature = "state-history")]
+            ("get", Some(matches)) => {
+                let client = rest_api_client(matches)?;
+                let service_id = service_id_arg(matches)?;
+
+                let address = matches
+                    .value_of("address")
+                    .ok_or_else(|| CliError::MissingArgument("address".into()))?;
+
+                let value = match matches.value_of("state-root") {
+                    Some(state_root) => {
+                        client.get_state_at_address_at_root(&service_id, address, state_root)?
+                    }
+                    None => client.get_state_at_address(&service_id, address)?,
+                };
+
+                match value {
+                    Some(value) => println!("{}", to_hex(&value)),
+                    None => println!("Address not set"),
+                }
+
+                Ok(())
+            }
+            #[cfg(feature = "state-history")]
+            ("list", Some(matches)) => {
+                let client = rest_api_client(matches)?;
+                let service_id = service_id_arg(matches)?;
+
+                let prefix = matches.value_of("prefix");
+                let entries = match matches.value_of("state-root") {
+                    Some(state_root) => {
+                        client.get_state_with_prefix_at_root(&service_id, prefix, state_root)?
+                    }
+                    None => client.get_state_with_prefix(&service_id, prefix)?,
+                };
+
+                for entry in entries {
+                    println!("{} {}", entry.address(), to_hex(entry.value()));
+                }
+
+                Ok(())
+            }
+            #[cfg(feature = "state-history")]
+            ("roots", Some(matches)) => {
+                let client = rest_api_client(matches)?;
+                let service_id = service_id_arg(matches)?;
+
+                let limit = matches
+                    .value_of("limit")
+                    .map(|limit| {
+                        limit.parse::<usize>().map_err(|_| {
+                            CliError::InvalidArgument(
+                                "'limit' argument must be a valid integer".into(),
+                            )
+                        })
+                    })
+                    .transpose()?;
+
+                for state_root in client.list_state_roots(&service_id, limit)? {
+                    println!(
+                        "{} {}",
+                        state_root.state_root(),
+                        state_root.batch_id().unwrap_or("-")
+                    );
+                }
+
+                Ok(())
+            }
+            #[cfg(feature = "state-pruning")]
+            ("prune", Some(matches)) => {
+                let full_service_id = matches
+                    .value_of("service-id")
+                    .ok_or_else(|| CliError::MissingArgument("service-id".into()))?;
+                let service_id = ServiceId::from_string(full_service_id)?;
+
+                let state_dir = matches
+                    .value_of("state-dir")
+                    .map(ToOwned::to_owned)
+                    .or_else(|| std::env::var(SPLINTER_STATE_DIR_ENV).ok())
+                    .unwrap_or_else(|| DEFAULT_STATE_DIR.to_string());
+
+                let depth = matches
+                    .value_of("depth")
+                    .ok_or_else(|| CliError::MissingArgument("depth".into()))?
+                    .parse::<usize>()
+                    .map_err(|_| {
+                        CliError::InvalidArgument("'depth' argument must be a valid integer".into())
+                    })?;
+                if depth == 0 {
+                    return Err(CliError::InvalidArgument(
+                        "'depth' argument must be at least 1".into(),
+                    ));
+                }
+
+                let state_db_size = matches
+                    .value_of("state-db-size")
+                    .map(|size| {
+                        size.parse::<usize>().map_err(|_| {
+                            CliError::InvalidArgument(
+                                "'state-db-size' argument must be a valid integer".into(),
+                            )
+                        })
+                    })
+                    .transpose()?
+                    .unwrap_or(DEFAULT_STATE_DB_SIZE);
+
+                let summary = scabbard::service::prune_service_state(
+                    service_id.service_id(),
+                    service_id.circuit(),
+                    Path::new(&state_dir),
+                    state_db_size,
+                    depth,
+                    matches.is_present("compact"),
+                )
+                .map_err(|err| {
+                    CliError::action_error_with_source("failed to prune state", Box::new(err))
+                })?;
+
+                println!("Pruned {} state root(s)", summary.pruned_state_roots);
+                if let Some((original_size, compacted_size)) = summary.compacted_size {
+                    println!(
+                        "Compacted state database from {} to {} bytes",
+                        original_size, compacted_size
+                    );
+                }
+
+                Ok(())
+            }
+            _ => Err(CliError::InvalidSubcommand),
+        },
+        #[cfg(feature = "receipts")]
+        ("receipt", Some(matches)) => match matches.subcommand() {
+            ("get", Some(matches)) => {
+                let client = rest_api_client(matches)?;
+                let service_id = service_id_arg(matches)?;
+
+                let transaction_id = matches
+                    .value_of("transaction-id")
+                    .ok_or_else(|| CliError::MissingArgument("transaction-id".into()))?;
+
+                match client.get_receipt(&service_id, transaction_id)? {
+                    Some(receipt) => print_receipt(&receipt),
+                    None => println!("Transaction receipt not found"),
+                }
+
+                Ok(())
+            }
+            ("list", Some(matches)) => {
+                let client = rest_api_client(matches)?;
+                let service_id = service_id_arg(matches)?;
+
+                let limit = matches
+                    .value_of("limit")
+                    .map(|limit| {
+                        limit.parse::<usize>().map_err(|_| {
+                            CliError::InvalidArgument(
+                                "'limit' argument must be a valid integer".into(),
+                            )
+                        })
+                    })
+                    .transpose()?;
+
+                let receipts =
+                    client.list_receipts(&service_id, matches.value_of("since"), limit)?;
+                for (i, receipt) in receipts.iter().enumerate() {
+                    if i > 0 {
+                        println!();
+                    }
+                    print_receipt(receipt);
+                }
+
+                Ok(())
+            }
             _ => Err(CliError::InvalidSubcommand),
         },
         _ => Err(CliError::InvalidSubcommand),
     }
 }
 
+#[cfg(feature = "receipts")]
+fn print_receipt(receipt: &scabbard::client::Receipt) {
+    use scabbard::client::ReceiptStateChange;
+
+    println!("Transaction: {}", receipt.transaction_id());
+    println!("State changes:");
+    for change in receipt.state_changes() {
+        match change {
+            ReceiptStateChange::Set { key, value } => println!("    set {} {}", key, to_hex(value)),
+            ReceiptStateChange::Delete { key } => println!("    delete {}", key),
+        }
+    }
+    println!("Events:");
+    for event in receipt.events() {
+        let attributes = event
+            .attributes()
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "    {} [{}] {}",
+            event.event_type(),
+            attributes,
+            to_hex(event.data())
+        );
+    }
+    println!("Data:");
+    for data in receipt.data() {
+        println!("    {}", to_hex(data));
+    }
+}
+
+/// Builds a client for the scabbard REST API from the `url` and `key` arguments of a subcommand.
+#[cfg(any(feature = "receipts", feature = "state-history"))]
+fn rest_api_client(
+    matches: &clap::ArgMatches,
+) -> Result<scabbard::client::ReqwestScabbardClient, CliError> {
+    let url = matches
+        .value_of("url")
+        .map(ToOwned::to_owned)
+        .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+        .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+    let signer = load_signer(matches.value_of("key"))?;
+
+    Ok(ReqwestScabbardClientBuilder::new()
+        .with_url(&url)
+        .with_auth(&create_cylinder_jwt_auth(signer)?)
+        .build()?)
+}
+
+#[cfg(any(feature = "receipts", feature = "state-history"))]
+fn service_id_arg(matches: &clap::ArgMatches) -> Result<ServiceId, CliError> {
+    let full_service_id = matches
+        .value_of("service-id")
+        .ok_or_else(|| CliError::MissingArgument("service-id".into()))?;
+    Ok(ServiceId::from_string(full_service_id)?)
+}
+
 fn setup_logging(log_level: log::LevelFilter) -> Result<(), CliError> {
     let mut log_spec_builder = LogSpecBuilder::new();
     log_spec_builder.default(log_level);
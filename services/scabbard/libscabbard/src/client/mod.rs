@@ -114,6 +114,96 @@ impl StateEntry {
     }
 }
 
+/// Represents a state root committed by a Scabbard service.
+#[cfg(feature = "state-history")]
+#[derive(Debug, PartialEq)]
+pub struct StateRoot {
+    state_root: String,
+    batch_id: Option<String>,
+}
+
+#[cfg(feature = "state-history")]
+impl StateRoot {
+    /// Get the state root hash.
+    pub fn state_root(&self) -> &str {
+        &self.state_root
+    }
+
+    /// Get the ID of the batch whose commit produced the state root, if it was produced by a batch
+    /// rather than a state sync.
+    pub fn batch_id(&self) -> Option<&str> {
+        self.batch_id.as_deref()
+    }
+}
+
+/// Represents the receipt of a transaction committed by a Scabbard service.
+#[cfg(feature = "receipts")]
+#[derive(Debug, PartialEq)]
+pub struct Receipt {
+    transaction_id: String,
+    state_changes: Vec<ReceiptStateChange>,
+    events: Vec<ReceiptEvent>,
+    data: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "receipts")]
+impl Receipt {
+    /// Get the ID of the transaction.
+    pub fn transaction_id(&self) -> &str {
+        &self.transaction_id
+    }
+
+    /// Get the changes the transaction made to state.
+    pub fn state_changes(&self) -> &[ReceiptStateChange] {
+        &self.state_changes
+    }
+
+    /// Get the events emitted by the transaction.
+    pub fn events(&self) -> &[ReceiptEvent] {
+        &self.events
+    }
+
+    /// Get the opaque data returned by the transaction.
+    pub fn data(&self) -> &[Vec<u8>] {
+        &self.data
+    }
+}
+
+/// A change made to a Scabbard service's state by a committed transaction.
+#[cfg(feature = "receipts")]
+#[derive(Debug, PartialEq)]
+pub enum ReceiptStateChange {
+    Set { key: String, value: Vec<u8> },
+    Delete { key: String },
+}
+
+/// An event emitted by a transaction committed by a Scabbard service.
+#[cfg(feature = "receipts")]
+#[derive(Debug, PartialEq)]
+pub struct ReceiptEvent {
+    event_type: String,
+    attributes: Vec<(String, String)>,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "receipts")]
+impl ReceiptEvent {
+    /// Get the type of the event.
+    pub fn event_type(&self) -> &str {
+        &self.event_type
+    }
+
+    /// Get the key/value attributes of the event.
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    /// Get the opaque data of the event.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
 pub trait ScabbardClient {
     /// Submit the given `batches` to the scabbard service with the given `service_id`. If a `wait`
     /// time is specified, wait the given amount of time for the batches to commit.
@@ -170,6 +260,95 @@ pub trait ScabbardClient {
     /// * An internal error based on the underlying implementation
     fn get_current_state_root(&self, service_id: &ServiceId)
         -> Result<String, ScabbardClientError>;
+
+    /// Get the value at the given `address` in state for the scabbard instance with the given
+    /// `service_id`, as of the given `state_root`. Returns `None` if there was no entry at the
+    /// given address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The given address is not a valid hex address
+    /// * The given state root is unknown to the scabbard service
+    /// * An internal server error occurred in the scabbard service
+    /// * An internal error based on the underlying implementation
+    #[cfg(feature = "state-history")]
+    fn get_state_at_address_at_root(
+        &self,
+        service_id: &ServiceId,
+        address: &str,
+        state_root: &str,
+    ) -> Result<Option<Vec<u8>>, ScabbardClientError>;
+
+    /// Get all entries under the given address `prefix` in state for the scabbard instance with
+    /// the given `service_id`, as of the given `state_root`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The given `prefix` is not a valid hex address prefix
+    /// * The given state root is unknown to the scabbard service
+    /// * An internal server error occurred in the scabbard service
+    /// * An internal error based on the underlying implementation
+    #[cfg(feature = "state-history")]
+    fn get_state_with_prefix_at_root(
+        &self,
+        service_id: &ServiceId,
+        prefix: Option<&str>,
+        state_root: &str,
+    ) -> Result<Vec<StateEntry>, ScabbardClientError>;
+
+    /// List the most recent state roots committed by the scabbard instance with the given
+    /// `service_id`, newest first. If a `limit` is provided, at most that many state roots are
+    /// returned; otherwise, the service's default limit applies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * An internal server error occurred in the scabbard service
+    /// * An internal error based on the underlying implementation
+    #[cfg(feature = "state-history")]
+    fn list_state_roots(
+        &self,
+        service_id: &ServiceId,
+        limit: Option<usize>,
+    ) -> Result<Vec<StateRoot>, ScabbardClientError>;
+
+    /// Get the receipt of the transaction with the given `transaction_id` that was committed by
+    /// the scabbard instance with the given `service_id`. Returns `None` if no such transaction
+    /// was committed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * An internal server error occurred in the scabbard service
+    /// * An internal error based on the underlying implementation
+    #[cfg(feature = "receipts")]
+    fn get_receipt(
+        &self,
+        service_id: &ServiceId,
+        transaction_id: &str,
+    ) -> Result<Option<Receipt>, ScabbardClientError>;
+
+    /// List the receipts of the transactions committed by the scabbard instance with the given
+    /// `service_id`, oldest first. If `since` is provided, only the receipts of transactions
+    /// committed after the transaction with that ID are listed. If a `limit` is provided, at most
+    /// that many receipts are returned; otherwise, the service's default limit applies. Further
+    /// receipts can be listed by passing the ID of the last receipt returned as `since`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The transaction given by `since` was not committed by the scabbard service
+    /// * An internal server error occurred in the scabbard service
+    /// * An internal error based on the underlying implementation
+    #[cfg(feature = "receipts")]
+    fn list_receipts(
+        &self,
+        service_id: &ServiceId,
+        since: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Receipt>, ScabbardClientError>;
 }
 
 #[cfg(test)]
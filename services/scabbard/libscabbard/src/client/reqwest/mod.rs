@@ -29,6 +29,10 @@ use crate::protocol::SCABBARD_PROTOCOL_VERSION;
 
 use super::error::ScabbardClientError;
 use super::ScabbardClient;
+#[cfg(feature = "state-history")]
+use super::StateRoot;
+#[cfg(feature = "receipts")]
+use super::{Receipt, ReceiptEvent, ReceiptStateChange};
 use super::{ServiceId, StateEntry};
 
 pub use builder::ReqwestScabbardClientBuilder;
@@ -102,15 +106,37 @@ impl ScabbardClient for ReqwestScabbardClient {
         service_id: &ServiceId,
         address: &str,
     ) -> Result<Option<Vec<u8>>, ScabbardClientError> {
-        parse_hex(address)
-            .map_err(|err| ScabbardClientError::new_with_source("invalid address", err.into()))?;
+        self.fetch_state_at_address(service_id, address, None)
+    }
 
+    /// Get all entries under the given address `prefix` in state for the scabbard instance with
+    /// the given `service_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The client's URL was invalid
+    /// * The given `prefix` is not a valid hex address prefix
+    /// * The REST API request failed
+    /// * An internal server error occurred in the scabbard service
+    fn get_state_with_prefix(
+        &self,
+        service_id: &ServiceId,
+        prefix: Option<&str>,
+    ) -> Result<Vec<StateEntry>, ScabbardClientError> {
+        self.fetch_state_with_prefix(service_id, prefix, None)
+    }
+
+    /// Get the current state root hash of the scabbard instance with the given `service_id`.
+    fn get_current_state_root(
+        &self,
+        service_id: &ServiceId,
+    ) -> Result<String, ScabbardClientError> {
         let url = Url::parse(&format!(
-            "{}/scabbard/{}/{}/state/{}",
+            "{}/scabbard/{}/{}/state_root",
             &self.url,
             service_id.circuit(),
-            service_id.service_id(),
-            address
+            service_id.service_id()
         ))
         .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
 
@@ -122,14 +148,12 @@ impl ScabbardClient for ReqwestScabbardClient {
             .map_err(|err| ScabbardClientError::new_with_source("request failed", err.into()))?;
 
         if response.status().is_success() {
-            Ok(Some(response.json().map_err(|err| {
+            response.json().map_err(|err| {
                 ScabbardClientError::new_with_source(
                     "failed to deserialize response body",
                     err.into(),
                 )
-            })?))
-        } else if response.status().as_u16() == 404 {
-            Ok(None)
+            })
         } else {
             let status = response.status();
             let msg: ErrorResponse = response.json().map_err(|err| {
@@ -139,14 +163,36 @@ impl ScabbardClient for ReqwestScabbardClient {
                 )
             })?;
             Err(ScabbardClientError::new(&format!(
-                "failed to get state at address: {}: {}",
+                "failed to get current state root: {}: {}",
                 status, msg
             )))
         }
     }
 
+    /// Get the value at the given `address` in state for the scabbard instance with the given
+    /// `service_id`, as of the given `state_root`. Returns `None` if there was no entry at the
+    /// given address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The client's URL was invalid
+    /// * The given address is not a valid hex address
+    /// * The REST API request failed
+    /// * The given state root is unknown to the scabbard service
+    /// * An internal server error occurred in the scabbard service
+    #[cfg(feature = "state-history")]
+    fn get_state_at_address_at_root(
+        &self,
+        service_id: &ServiceId,
+        address: &str,
+        state_root: &str,
+    ) -> Result<Option<Vec<u8>>, ScabbardClientError> {
+        self.fetch_state_at_address(service_id, address, Some(state_root))
+    }
+
     /// Get all entries under the given address `prefix` in state for the scabbard instance with
-    /// the given `service_id`.
+    /// the given `service_id`, as of the given `state_root`.
     ///
     /// # Errors
     ///
@@ -154,29 +200,43 @@ impl ScabbardClient for ReqwestScabbardClient {
     /// * The client's URL was invalid
     /// * The given `prefix` is not a valid hex address prefix
     /// * The REST API request failed
+    /// * The given state root is unknown to the scabbard service
     /// * An internal server error occurred in the scabbard service
-    fn get_state_with_prefix(
+    #[cfg(feature = "state-history")]
+    fn get_state_with_prefix_at_root(
         &self,
         service_id: &ServiceId,
         prefix: Option<&str>,
+        state_root: &str,
     ) -> Result<Vec<StateEntry>, ScabbardClientError> {
+        self.fetch_state_with_prefix(service_id, prefix, Some(state_root))
+    }
+
+    /// List the most recent state roots committed by the scabbard instance with the given
+    /// `service_id`, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The client's URL was invalid
+    /// * The REST API request failed
+    /// * An internal server error occurred in the scabbard service
+    #[cfg(feature = "state-history")]
+    fn list_state_roots(
+        &self,
+        service_id: &ServiceId,
+        limit: Option<usize>,
+    ) -> Result<Vec<StateRoot>, ScabbardClientError> {
         let mut url = Url::parse(&format!(
-            "{}/scabbard/{}/{}/state",
+            "{}/scabbard/{}/{}/state_roots",
             &self.url,
             service_id.circuit(),
             service_id.service_id()
         ))
         .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
-        if let Some(prefix) = prefix {
-            parse_hex(prefix).map_err(|err| {
-                ScabbardClientError::new_with_source("invalid prefix", err.into())
-            })?;
-            if prefix.len() > 70 {
-                return Err(ScabbardClientError::new(
-                    "prefix must be less than 70 characters",
-                ));
-            }
-            url.set_query(Some(&format!("prefix={}", prefix)))
+        if let Some(limit) = limit {
+            url.query_pairs_mut()
+                .append_pair("limit", &limit.to_string());
         }
 
         let response = Client::new()
@@ -188,8 +248,8 @@ impl ScabbardClient for ReqwestScabbardClient {
 
         if response.status().is_success() {
             response
-                .json::<Vec<JsonStateEntry>>()
-                .map(|entries| entries.into_iter().map(StateEntry::from).collect())
+                .json::<Vec<JsonStateRoot>>()
+                .map(|state_roots| state_roots.into_iter().map(StateRoot::from).collect())
                 .map_err(|err| {
                     ScabbardClientError::new_with_source(
                         "failed to deserialize response body",
@@ -205,24 +265,103 @@ impl ScabbardClient for ReqwestScabbardClient {
                 )
             })?;
             Err(ScabbardClientError::new(&format!(
-                "failed to get state with prefix: {}: {}",
+                "failed to list state roots: {}: {}",
                 status, msg
             )))
         }
     }
 
-    /// Get the current state root hash of the scabbard instance with the given `service_id`.
-    fn get_current_state_root(
+    /// Get the receipt of the transaction with the given `transaction_id` that was committed by
+    /// the scabbard instance with the given `service_id`. Returns `None` if no such transaction
+    /// was committed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The client's URL was invalid
+    /// * The REST API request failed
+    /// * An internal server error occurred in the scabbard service
+    #[cfg(feature = "receipts")]
+    fn get_receipt(
         &self,
         service_id: &ServiceId,
-    ) -> Result<String, ScabbardClientError> {
+        transaction_id: &str,
+    ) -> Result<Option<Receipt>, ScabbardClientError> {
         let url = Url::parse(&format!(
-            "{}/scabbard/{}/{}/state_root",
+            "{}/scabbard/{}/{}/receipts/{}",
+            &self.url,
+            service_id.circuit(),
+            service_id.service_id(),
+            transaction_id
+        ))
+        .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
+
+        let response = Client::new()
+            .get(url)
+            .header("SplinterProtocolVersion", SCABBARD_PROTOCOL_VERSION)
+            .header("Authorization", &self.auth)
+            .send()
+            .map_err(|err| ScabbardClientError::new_with_source("request failed", err.into()))?;
+
+        if response.status().is_success() {
+            response
+                .json::<JsonReceipt>()
+                .map(|receipt| Some(Receipt::from(receipt)))
+                .map_err(|err| {
+                    ScabbardClientError::new_with_source(
+                        "failed to deserialize response body",
+                        err.into(),
+                    )
+                })
+        } else if response.status().as_u16() == 404 {
+            Ok(None)
+        } else {
+            let status = response.status();
+            let msg: ErrorResponse = response.json().map_err(|err| {
+                ScabbardClientError::new_with_source(
+                    "failed to deserialize error response body",
+                    err.into(),
+                )
+            })?;
+            Err(ScabbardClientError::new(&format!(
+                "failed to get transaction receipt: {}: {}",
+                status, msg
+            )))
+        }
+    }
+
+    /// List the receipts of the transactions committed by the scabbard instance with the given
+    /// `service_id`, oldest first, optionally only those committed after the transaction given by
+    /// `since`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The client's URL was invalid
+    /// * The REST API request failed
+    /// * The transaction given by `since` was not committed by the scabbard service
+    /// * An internal server error occurred in the scabbard service
+    #[cfg(feature = "receipts")]
+    fn list_receipts(
+        &self,
+        service_id: &ServiceId,
+        since: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Receipt>, ScabbardClientError> {
+        let mut url = Url::parse(&format!(
+            "{}/scabbard/{}/{}/receipts",
             &self.url,
             service_id.circuit(),
             service_id.service_id()
         ))
         .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
+        if let Some(since) = since {
+            url.query_pairs_mut().append_pair("since", since);
+        }
+        if let Some(limit) = limit {
+            url.query_pairs_mut()
+                .append_pair("limit", &limit.to_string());
+        }
 
         let response = Client::new()
             .get(url)
@@ -232,12 +371,69 @@ impl ScabbardClient for ReqwestScabbardClient {
             .map_err(|err| ScabbardClientError::new_with_source("request failed", err.into()))?;
 
         if response.status().is_success() {
-            response.json().map_err(|err| {
+            response
+                .json::<JsonReceiptList>()
+                .map(|list| list.data.into_iter().map(Receipt::from).collect())
+                .map_err(|err| {
+                    ScabbardClientError::new_with_source(
+                        "failed to deserialize response body",
+                        err.into(),
+                    )
+                })
+        } else {
+            let status = response.status();
+            let msg: ErrorResponse = response.json().map_err(|err| {
+                ScabbardClientError::new_with_source(
+                    "failed to deserialize error response body",
+                    err.into(),
+                )
+            })?;
+            Err(ScabbardClientError::new(&format!(
+                "failed to list transaction receipts: {}: {}",
+                status, msg
+            )))
+        }
+    }
+}
+
+impl ReqwestScabbardClient {
+    fn fetch_state_at_address(
+        &self,
+        service_id: &ServiceId,
+        address: &str,
+        state_root: Option<&str>,
+    ) -> Result<Option<Vec<u8>>, ScabbardClientError> {
+        parse_hex(address)
+            .map_err(|err| ScabbardClientError::new_with_source("invalid address", err.into()))?;
+
+        let mut url = Url::parse(&format!(
+            "{}/scabbard/{}/{}/state/{}",
+            &self.url,
+            service_id.circuit(),
+            service_id.service_id(),
+            address
+        ))
+        .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
+        if let Some(state_root) = state_root {
+            url.query_pairs_mut().append_pair("state_root", state_root);
+        }
+
+        let response = Client::new()
+            .get(url)
+            .header("SplinterProtocolVersion", SCABBARD_PROTOCOL_VERSION)
+            .header("Authorization", &self.auth)
+            .send()
+            .map_err(|err| ScabbardClientError::new_with_source("request failed", err.into()))?;
+
+        if response.status().is_success() {
+            Ok(Some(response.json().map_err(|err| {
                 ScabbardClientError::new_with_source(
                     "failed to deserialize response body",
                     err.into(),
                 )
-            })
+            })?))
+        } else if response.status().as_u16() == 404 {
+            Ok(None)
         } else {
             let status = response.status();
             let msg: ErrorResponse = response.json().map_err(|err| {
@@ -247,7 +443,67 @@ impl ScabbardClient for ReqwestScabbardClient {
                 )
             })?;
             Err(ScabbardClientError::new(&format!(
-                "failed to get current state root: {}: {}",
+                "failed to get state at address: {}: {}",
+                status, msg
+            )))
+        }
+    }
+
+    fn fetch_state_with_prefix(
+        &self,
+        service_id: &ServiceId,
+        prefix: Option<&str>,
+        state_root: Option<&str>,
+    ) -> Result<Vec<StateEntry>, ScabbardClientError> {
+        let mut url = Url::parse(&format!(
+            "{}/scabbard/{}/{}/state",
+            &self.url,
+            service_id.circuit(),
+            service_id.service_id()
+        ))
+        .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
+        if let Some(prefix) = prefix {
+            parse_hex(prefix).map_err(|err| {
+                ScabbardClientError::new_with_source("invalid prefix", err.into())
+            })?;
+            if prefix.len() > 70 {
+                return Err(ScabbardClientError::new(
+                    "prefix must be less than 70 characters",
+                ));
+            }
+            url.query_pairs_mut().append_pair("prefix", prefix);
+        }
+        if let Some(state_root) = state_root {
+            url.query_pairs_mut().append_pair("state_root", state_root);
+        }
+
+        let response = Client::new()
+            .get(url)
+            .header("SplinterProtocolVersion", SCABBARD_PROTOCOL_VERSION)
+            .header("Authorization", &self.auth)
+            .send()
+            .map_err(|err| ScabbardClientError::new_with_source("request failed", err.into()))?;
+
+        if response.status().is_success() {
+            response
+                .json::<Vec<JsonStateEntry>>()
+                .map(|entries| entries.into_iter().map(StateEntry::from).collect())
+                .map_err(|err| {
+                    ScabbardClientError::new_with_source(
+                        "failed to deserialize response body",
+                        err.into(),
+                    )
+                })
+        } else {
+            let status = response.status();
+            let msg: ErrorResponse = response.json().map_err(|err| {
+                ScabbardClientError::new_with_source(
+                    "failed to deserialize error response body",
+                    err.into(),
+                )
+            })?;
+            Err(ScabbardClientError::new(&format!(
+                "failed to get state with prefix: {}: {}",
                 status, msg
             )))
         }
@@ -375,6 +631,108 @@ impl From<JsonStateEntry> for StateEntry {
     }
 }
 
+/// Used for deserializing `GET /state_roots` responses.
+#[cfg(feature = "state-history")]
+#[derive(Serialize, Deserialize)]
+struct JsonStateRoot {
+    state_root: String,
+    batch_id: Option<String>,
+}
+
+#[cfg(feature = "state-history")]
+impl From<JsonStateRoot> for StateRoot {
+    fn from(json: JsonStateRoot) -> Self {
+        let JsonStateRoot {
+            state_root,
+            batch_id,
+        } = json;
+        Self {
+            state_root,
+            batch_id,
+        }
+    }
+}
+
+/// Used for deserializing `GET /receipts` responses.
+#[cfg(feature = "receipts")]
+#[derive(Serialize, Deserialize)]
+struct JsonReceiptList {
+    data: Vec<JsonReceipt>,
+}
+
+/// Used for deserializing `GET /receipts/{transaction_id}` and `GET /receipts` responses.
+#[cfg(feature = "receipts")]
+#[derive(Serialize, Deserialize)]
+struct JsonReceipt {
+    transaction_id: String,
+    state_changes: Vec<JsonReceiptStateChange>,
+    events: Vec<JsonReceiptEvent>,
+    data: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "receipts")]
+impl From<JsonReceipt> for Receipt {
+    fn from(json: JsonReceipt) -> Self {
+        let JsonReceipt {
+            transaction_id,
+            state_changes,
+            events,
+            data,
+        } = json;
+        Self {
+            transaction_id,
+            state_changes: state_changes
+                .into_iter()
+                .map(ReceiptStateChange::from)
+                .collect(),
+            events: events.into_iter().map(ReceiptEvent::from).collect(),
+            data,
+        }
+    }
+}
+
+#[cfg(feature = "receipts")]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum JsonReceiptStateChange {
+    Set { key: String, value: Vec<u8> },
+    Delete { key: String },
+}
+
+#[cfg(feature = "receipts")]
+impl From<JsonReceiptStateChange> for ReceiptStateChange {
+    fn from(json: JsonReceiptStateChange) -> Self {
+        match json {
+            JsonReceiptStateChange::Set { key, value } => ReceiptStateChange::Set { key, value },
+            JsonReceiptStateChange::Delete { key } => ReceiptStateChange::Delete { key },
+        }
+    }
+}
+
+#[cfg(feature = "receipts")]
+#[derive(Serialize, Deserialize)]
+struct JsonReceiptEvent {
+    event_type: String,
+    attributes: Vec<(String, String)>,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "receipts")]
+impl From<JsonReceiptEvent> for ReceiptEvent {
+    fn from(json: JsonReceiptEvent) -> Self {
+        let JsonReceiptEvent {
+            event_type,
+            attributes,
+            data,
+        } = json;
+        Self {
+            event_type,
+            attributes,
+            data,
+        }
+    }
+}
+
 /// Used for deserializing the batch link provided by the Scabbard REST API.
 #[derive(Debug, Serialize, Deserialize)]
 struct Link {
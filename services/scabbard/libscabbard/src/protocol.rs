@@ -26,3 +26,13 @@ pub(crate) const SCABBARD_GET_STATE_PROTOCOL_MIN: u32 = 1;
 pub(crate) const SCABBARD_LIST_STATE_PROTOCOL_MIN: u32 = 1;
 #[cfg(all(feature = "rest-api", feature = "rest-api-actix"))]
 pub(crate) const SCABBARD_STATE_ROOT_PROTOCOL_MIN: u32 = 1;
+#[cfg(all(
+    feature = "rest-api",
+    feature = "rest-api-actix",
+    feature = "state-history"
+))]
+pub(crate) const SCABBARD_LIST_STATE_ROOTS_PROTOCOL_MIN: u32 = 1;
+#[cfg(all(feature = "rest-api", feature = "rest-api-actix", feature = "receipts"))]
+pub(crate) const SCABBARD_GET_RECEIPT_PROTOCOL_MIN: u32 = 1;
+#[cfg(all(feature = "rest-api", feature = "rest-api-actix", feature = "receipts"))]
+pub(crate) const SCABBARD_LIST_RECEIPTS_PROTOCOL_MIN: u32 = 1;
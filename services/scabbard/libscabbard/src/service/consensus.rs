@@ -187,6 +187,17 @@ impl ProposalManager for ScabbardProposalManager {
             .lock()
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
 
+        // The service's state may be behind its peers' until the state sync is verified
+        #[cfg(feature = "state-sync")]
+        {
+            if shared.state_sync_in_progress() {
+                debug!("Not creating a proposal while state is being synchronized");
+                self.proposal_update_sender
+                    .send(ProposalUpdate::ProposalCreated(None))?;
+                return Ok(());
+            }
+        }
+
         if let Some(batch) = shared
             .pop_batch_from_queue()
             .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?
@@ -256,13 +267,30 @@ impl ProposalManager for ScabbardProposalManager {
     }
 
     fn check_proposal(&self, id: &ProposalId) -> Result<(), ProposalManagerError> {
-        let (proposal, batch) = self
+        let shared = self
             .shared
             .lock()
-            .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
+            .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
+
+        // The service's state may be behind its peers' until the state sync is verified
+        #[cfg(feature = "state-sync")]
+        {
+            if shared.state_sync_in_progress() {
+                warn!(
+                    "Rejecting proposal {} while state is being synchronized",
+                    id
+                );
+                self.proposal_update_sender
+                    .send(ProposalUpdate::ProposalInvalid(id.clone()))?;
+                return Ok(());
+            }
+        }
+
+        let (proposal, batch) = shared
             .get_open_proposal(id)
             .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?
             .clone();
+        drop(shared);
 
         let hash = self
             .state
@@ -276,6 +304,20 @@ impl ProposalManager for ScabbardProposalManager {
 
             self.proposal_update_sender
                 .send(ProposalUpdate::ProposalInvalid(id.clone()))?;
+
+            // The mismatch may be caused by this service's state having fallen behind
+            #[cfg(feature = "state-sync")]
+            {
+                let mut shared = self.shared.lock().map_err(|_| {
+                    ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned))
+                })?;
+                let state = self.state.lock().map_err(|_| {
+                    ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned))
+                })?;
+                if let Err(err) = super::sync::request_state_sync(&mut shared, &state, false) {
+                    warn!("Unable to request state sync: {}", err);
+                }
+            }
         } else {
             self.proposal_update_sender
                 .send(ProposalUpdate::ProposalValid(id.clone()))?;
@@ -0,0 +1,241 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A lightweight key/value transaction family, for workloads that only need to set and delete
+//! state entries.
+
+use openssl::hash::{hash, MessageDigest};
+use protobuf::Message;
+use transact::{
+    handler::{ApplyError, TransactionContext, TransactionHandler},
+    protocol::transaction::TransactionPair,
+};
+
+use crate::hex::to_hex;
+use crate::protos::key_value::KeyValuePayload;
+
+/// The family name of key/value transactions.
+pub const KEY_VALUE_FAMILY_NAME: &str = "key_value";
+/// The family version of key/value transactions.
+pub const KEY_VALUE_FAMILY_VERSION: &str = "1.0";
+
+/// Computes the state address of the given key. Addresses are made up of the first 6 hex
+/// characters of the SHA-512 hash of the family name, followed by the first 64 hex characters of
+/// the SHA-512 hash of the key.
+pub fn compute_key_value_address(key: &str) -> String {
+    let prefix = sha512_hex(KEY_VALUE_FAMILY_NAME.as_bytes());
+    let key_hash = sha512_hex(key.as_bytes());
+    format!("{}{}", &prefix[..6], &key_hash[..64])
+}
+
+fn sha512_hex(bytes: &[u8]) -> String {
+    // The SHA-512 digest is always available, so hashing can't fail
+    to_hex(&hash(MessageDigest::sha512(), bytes).expect("Failed to compute SHA-512 hash"))
+}
+
+/// Applies key/value transactions, whose payloads are `KeyValuePayload` messages.
+pub struct KeyValueTransactionHandler {
+    family_versions: Vec<String>,
+}
+
+impl KeyValueTransactionHandler {
+    pub fn new() -> Self {
+        Self {
+            family_versions: vec![KEY_VALUE_FAMILY_VERSION.into()],
+        }
+    }
+}
+
+impl Default for KeyValueTransactionHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransactionHandler for KeyValueTransactionHandler {
+    fn family_name(&self) -> &str {
+        KEY_VALUE_FAMILY_NAME
+    }
+
+    fn family_versions(&self) -> &[String] {
+        &self.family_versions
+    }
+
+    fn apply(
+        &self,
+        transaction: &TransactionPair,
+        context: &mut dyn TransactionContext,
+    ) -> Result<(), ApplyError> {
+        let payload: KeyValuePayload =
+            Message::parse_from_bytes(transaction.transaction().payload()).map_err(|err| {
+                ApplyError::InvalidTransaction(format!("Invalid payload: {}", err))
+            })?;
+
+        if payload.get_set().is_empty() && payload.get_delete().is_empty() {
+            return Err(ApplyError::InvalidTransaction(
+                "Payload must set or delete at least one key".into(),
+            ));
+        }
+
+        let entries = payload
+            .get_set()
+            .iter()
+            .map(|entry| {
+                if entry.get_key().is_empty() {
+                    return Err(ApplyError::InvalidTransaction(
+                        "Keys must not be empty".into(),
+                    ));
+                }
+                Ok((
+                    compute_key_value_address(entry.get_key()),
+                    entry.get_value().to_vec(),
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !entries.is_empty() {
+            context.set_state_entries(entries).map_err(|err| {
+                ApplyError::InternalError(format!("Unable to set state entries: {}", err))
+            })?;
+        }
+
+        let deleted = payload
+            .get_delete()
+            .iter()
+            .map(|key| compute_key_value_address(key))
+            .collect::<Vec<_>>();
+        if !deleted.is_empty() {
+            context.delete_state_entries(&deleted).map_err(|err| {
+                ApplyError::InternalError(format!("Unable to delete state entries: {}", err))
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cylinder::{secp256k1::Secp256k1Context, Context, Signer};
+    use tempdir::TempDir;
+    use transact::protocol::{
+        batch::{BatchBuilder, BatchPair},
+        transaction::{HashMethod, TransactionBuilder},
+    };
+
+    use crate::protos::key_value::KeyValueEntry;
+    use crate::service::engine::{EngineFactory, KeyValueEngineFactory};
+    use crate::service::state::ScabbardState;
+
+    const TEMP_DB_SIZE: usize = 1 << 30; // 1024 ** 3
+
+    /// Verify that a scabbard state using the key/value engine executes key/value transactions.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState` with the key/value engine's handlers.
+    /// 2. Commit a transaction that sets two keys and verify that both are set.
+    /// 3. Commit a transaction that deletes one of the keys and verify that only it was removed.
+    /// 4. Verify that a transaction with an empty payload is rejected.
+    #[test]
+    fn key_value_engine() {
+        let temp_dir = TempDir::new("key_value_engine").expect("Failed to create temp dir");
+        let mut state = ScabbardState::new_with_handlers(
+            &temp_dir.path().join("state"),
+            TEMP_DB_SIZE,
+            &temp_dir.path().join("receipts"),
+            TEMP_DB_SIZE,
+            vec![],
+            KeyValueEngineFactory.new_handlers(),
+        )
+        .expect("Failed to initialize state");
+
+        let signing_context = Secp256k1Context::new();
+        let signer = signing_context.new_signer(signing_context.new_random_private_key());
+
+        let mut payload = KeyValuePayload::new();
+        payload.set_set(vec![entry("alpha", b"one"), entry("beta", b"two")].into());
+        state
+            .prepare_change(make_batch(&payload, &["alpha", "beta"], &*signer))
+            .expect("Failed to prepare change");
+        state.commit().expect("Failed to commit change");
+
+        assert_eq!(
+            state
+                .get_state_at_address(&compute_key_value_address("alpha"))
+                .expect("Failed to get state"),
+            Some(b"one".to_vec())
+        );
+        assert_eq!(
+            state
+                .get_state_at_address(&compute_key_value_address("beta"))
+                .expect("Failed to get state"),
+            Some(b"two".to_vec())
+        );
+
+        let mut payload = KeyValuePayload::new();
+        payload.set_delete(vec!["alpha".to_string()].into());
+        state
+            .prepare_change(make_batch(&payload, &["alpha"], &*signer))
+            .expect("Failed to prepare change");
+        state.commit().expect("Failed to commit change");
+
+        assert!(state
+            .get_state_at_address(&compute_key_value_address("alpha"))
+            .expect("Failed to get state")
+            .is_none());
+        assert!(state
+            .get_state_at_address(&compute_key_value_address("beta"))
+            .expect("Failed to get state")
+            .is_some());
+
+        assert!(state
+            .prepare_change(make_batch(&KeyValuePayload::new(), &[], &*signer))
+            .is_err());
+    }
+
+    fn entry(key: &str, value: &[u8]) -> KeyValueEntry {
+        let mut entry = KeyValueEntry::new();
+        entry.set_key(key.into());
+        entry.set_value(value.to_vec());
+        entry
+    }
+
+    fn make_batch(payload: &KeyValuePayload, keys: &[&str], signer: &dyn Signer) -> BatchPair {
+        let addresses = keys
+            .iter()
+            .map(|key| {
+                crate::hex::parse_hex(&compute_key_value_address(key)).expect("Invalid address")
+            })
+            .collect::<Vec<_>>();
+
+        let transaction = TransactionBuilder::new()
+            .with_family_name(KEY_VALUE_FAMILY_NAME.into())
+            .with_family_version(KEY_VALUE_FAMILY_VERSION.into())
+            .with_inputs(addresses.clone())
+            .with_outputs(addresses)
+            .with_payload_hash_method(HashMethod::SHA512)
+            .with_payload(
+                payload
+                    .write_to_bytes()
+                    .expect("Failed to serialize payload"),
+            )
+            .build(signer)
+            .expect("Failed to build transaction");
+
+        BatchBuilder::new()
+            .with_transactions(vec![transaction])
+            .build_pair(signer)
+            .expect("Failed to build batch")
+    }
+}
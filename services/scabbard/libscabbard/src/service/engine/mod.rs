@@ -0,0 +1,73 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The engines that scabbard services execute transactions with. An engine is a set of transact
+//! transaction handlers; Sawtooth Sabre is the default engine.
+
+#[cfg(feature = "engines")]
+mod key_value;
+
+use sawtooth_sabre::handler::SabreTransactionHandler;
+use transact::{handler::TransactionHandler, sawtooth::SawtoothToTransactHandlerAdapter};
+
+#[cfg(feature = "engines")]
+pub use key_value::{
+    compute_key_value_address, KeyValueTransactionHandler, KEY_VALUE_FAMILY_NAME,
+    KEY_VALUE_FAMILY_VERSION,
+};
+
+/// The name of the Sawtooth Sabre engine, which is used when no engine is specified.
+pub const SABRE_ENGINE: &str = "sabre";
+/// The name of the built-in key/value engine.
+#[cfg(feature = "engines")]
+pub const KEY_VALUE_ENGINE: &str = "key-value";
+
+/// Creates the transaction handlers of an engine. Each scabbard service is given its own set of
+/// handlers when it is created.
+#[cfg(feature = "engines")]
+pub trait EngineFactory: Send {
+    /// Creates a new set of the engine's transaction handlers.
+    fn new_handlers(&self) -> Vec<Box<dyn TransactionHandler>>;
+}
+
+/// Creates the handlers of the Sawtooth Sabre engine, which runs WebAssembly smart contracts.
+#[cfg(feature = "engines")]
+#[derive(Default)]
+pub struct SabreEngineFactory;
+
+#[cfg(feature = "engines")]
+impl EngineFactory for SabreEngineFactory {
+    fn new_handlers(&self) -> Vec<Box<dyn TransactionHandler>> {
+        sabre_handlers()
+    }
+}
+
+/// Creates the handlers of the built-in key/value engine, which sets and deletes state entries
+/// by key without any contract or permission management.
+#[cfg(feature = "engines")]
+#[derive(Default)]
+pub struct KeyValueEngineFactory;
+
+#[cfg(feature = "engines")]
+impl EngineFactory for KeyValueEngineFactory {
+    fn new_handlers(&self) -> Vec<Box<dyn TransactionHandler>> {
+        vec![Box::new(KeyValueTransactionHandler::new())]
+    }
+}
+
+pub(super) fn sabre_handlers() -> Vec<Box<dyn TransactionHandler>> {
+    vec![Box::new(SawtoothToTransactHandlerAdapter::new(
+        SabreTransactionHandler::new(),
+    ))]
+}
@@ -18,6 +18,8 @@ use std::path::Path;
 use std::time::Duration;
 
 use cylinder::VerifierFactory;
+#[cfg(feature = "backup")]
+use splinter::backup::CommitGate;
 #[cfg(feature = "service-arg-validation")]
 use splinter::service::validation::{ServiceArgValidationError, ServiceArgValidator};
 use splinter::service::{FactoryCreateError, Service, ServiceFactory};
@@ -25,6 +27,15 @@ use splinter::service::{FactoryCreateError, Service, ServiceFactory};
 #[cfg(feature = "service-arg-validation")]
 use crate::hex::parse_hex;
 
+#[cfg(feature = "state-pruning")]
+use super::compute_db_paths;
+use super::engine::SABRE_ENGINE;
+#[cfg(feature = "engines")]
+use super::engine::{EngineFactory, KeyValueEngineFactory, SabreEngineFactory, KEY_VALUE_ENGINE};
+#[cfg(feature = "state-pruning")]
+use super::error::ScabbardError;
+#[cfg(feature = "state-pruning")]
+use super::state::compact_state_db;
 use super::{Scabbard, ScabbardVersion, SERVICE_TYPE};
 
 const DEFAULT_STATE_DB_DIR: &str = "/var/lib/splinter";
@@ -41,6 +52,24 @@ pub struct ScabbardFactoryBuilder {
     receipt_db_dir: Option<String>,
     receipt_db_size: Option<usize>,
     signature_verifier_factory: Option<Box<dyn VerifierFactory>>,
+    #[cfg(feature = "backup")]
+    commit_gate: Option<CommitGate>,
+    #[cfg(feature = "state-history")]
+    state_root_history_size: Option<usize>,
+    #[cfg(feature = "state-pruning")]
+    state_retention_depth: Option<usize>,
+    #[cfg(feature = "state-pruning")]
+    compact_state_on_create: bool,
+    #[cfg(feature = "state-pruning")]
+    batch_history_size: Option<usize>,
+    #[cfg(feature = "state-pruning")]
+    batch_history_max_age: Option<Duration>,
+    #[cfg(feature = "batch-status-store")]
+    batch_status_retention: Option<usize>,
+    #[cfg(feature = "batch-status-store")]
+    batch_status_max_age: Option<Duration>,
+    #[cfg(feature = "engines")]
+    engines: HashMap<String, Box<dyn EngineFactory>>,
 }
 
 #[cfg(feature = "factory-builder")]
@@ -84,11 +113,83 @@ impl ScabbardFactoryBuilder {
         self
     }
 
+    /// Sets the gate that the commits of all created services must pass through.
+    #[cfg(feature = "backup")]
+    pub fn with_commit_gate(mut self, commit_gate: CommitGate) -> Self {
+        self.commit_gate = Some(commit_gate);
+        self
+    }
+
+    /// Sets the number of most recent state roots that created services keep in their state root
+    /// history. If not set, the 10,000 most recent state roots are kept.
+    #[cfg(feature = "state-history")]
+    pub fn with_state_root_history_size(mut self, state_root_history_size: usize) -> Self {
+        self.state_root_history_size = Some(state_root_history_size);
+        self
+    }
+
+    /// Sets the number of most recent state roots that created services retain. Older state roots,
+    /// and the state entries only they reference, are pruned after each commit. If not set, all
+    /// state roots are retained.
+    #[cfg(feature = "state-pruning")]
+    pub fn with_state_retention_depth(mut self, state_retention_depth: usize) -> Self {
+        self.state_retention_depth = Some(state_retention_depth);
+        self
+    }
+
+    /// Sets whether a service's existing state database is compacted when the service is created,
+    /// reclaiming the space freed by pruning. Defaults to `false`.
+    #[cfg(feature = "state-pruning")]
+    pub fn with_state_compaction(mut self, compact_state_on_create: bool) -> Self {
+        self.compact_state_on_create = compact_state_on_create;
+        self
+    }
+
+    /// Sets the maximum number of batches kept in the batch history of created services.
+    #[cfg(feature = "state-pruning")]
+    pub fn with_batch_history_size(mut self, batch_history_size: usize) -> Self {
+        self.batch_history_size = Some(batch_history_size);
+        self
+    }
+
+    /// Sets the maximum amount of time batches are kept in the batch history of created services.
+    #[cfg(feature = "state-pruning")]
+    pub fn with_batch_history_max_age(mut self, batch_history_max_age: Duration) -> Self {
+        self.batch_history_max_age = Some(batch_history_max_age);
+        self
+    }
+
+    /// Sets the maximum number of completed batches that created services persist the statuses of.
+    /// If not set, the statuses of the 10,000 most recently completed batches are persisted.
+    #[cfg(feature = "batch-status-store")]
+    pub fn with_batch_status_retention(mut self, batch_status_retention: usize) -> Self {
+        self.batch_status_retention = Some(batch_status_retention);
+        self
+    }
+
+    /// Sets the maximum amount of time after a batch completes that created services persist its
+    /// status for. If not set, statuses are only pruned by count.
+    #[cfg(feature = "batch-status-store")]
+    pub fn with_batch_status_max_age(mut self, batch_status_max_age: Duration) -> Self {
+        self.batch_status_max_age = Some(batch_status_max_age);
+        self
+    }
+
+    /// Adds an engine that created services may be configured to execute transactions with, using
+    /// the `engine` service argument. The "sabre" and "key-value" engines are always available;
+    /// adding an engine with one of these names replaces the built-in engine.
+    #[cfg(feature = "engines")]
+    pub fn with_engine(mut self, name: String, engine_factory: Box<dyn EngineFactory>) -> Self {
+        self.engines.insert(name, engine_factory);
+        self
+    }
+
     /// Build the final [ScabbardFactory] instance.
     ///
     /// # Errors
     ///
-    /// Returns an InvalidStateError if a signature_verifier_factory has not been set.
+    /// Returns an InvalidStateError if a signature_verifier_factory has not been set, or if the
+    /// state root history size, state retention depth or batch status retention is 0.
     pub fn build(self) -> Result<ScabbardFactory, splinter::error::InvalidStateError> {
         let signature_verifier_factory = self.signature_verifier_factory.ok_or_else(|| {
             splinter::error::InvalidStateError::with_message(
@@ -96,6 +197,27 @@ impl ScabbardFactoryBuilder {
             )
         })?;
 
+        #[cfg(feature = "state-history")]
+        if self.state_root_history_size == Some(0) {
+            return Err(splinter::error::InvalidStateError::with_message(
+                "The state root history size must be at least 1".into(),
+            ));
+        }
+
+        #[cfg(feature = "state-pruning")]
+        if self.state_retention_depth == Some(0) {
+            return Err(splinter::error::InvalidStateError::with_message(
+                "The state retention depth must be at least 1".into(),
+            ));
+        }
+
+        #[cfg(feature = "batch-status-store")]
+        if self.batch_status_retention == Some(0) {
+            return Err(splinter::error::InvalidStateError::with_message(
+                "The batch status retention must be at least 1".into(),
+            ));
+        }
+
         Ok(ScabbardFactory {
             service_types: vec![SERVICE_TYPE.into()],
             state_db_dir: self
@@ -107,6 +229,28 @@ impl ScabbardFactoryBuilder {
                 .unwrap_or_else(|| DEFAULT_RECEIPT_DB_DIR.into()),
             receipt_db_size: self.receipt_db_size.unwrap_or(DEFAULT_RECEIPT_DB_SIZE),
             signature_verifier_factory,
+            #[cfg(feature = "backup")]
+            commit_gate: self.commit_gate,
+            #[cfg(feature = "state-history")]
+            state_root_history_size: self.state_root_history_size,
+            #[cfg(feature = "state-pruning")]
+            state_retention_depth: self.state_retention_depth,
+            #[cfg(feature = "state-pruning")]
+            compact_state_on_create: self.compact_state_on_create,
+            #[cfg(feature = "state-pruning")]
+            batch_history_size: self.batch_history_size,
+            #[cfg(feature = "state-pruning")]
+            batch_history_max_age: self.batch_history_max_age,
+            #[cfg(feature = "batch-status-store")]
+            batch_status_retention: self.batch_status_retention,
+            #[cfg(feature = "batch-status-store")]
+            batch_status_max_age: self.batch_status_max_age,
+            #[cfg(feature = "engines")]
+            engines: {
+                let mut engines = default_engines();
+                engines.extend(self.engines);
+                engines
+            },
         })
     }
 }
@@ -118,6 +262,24 @@ pub struct ScabbardFactory {
     receipt_db_dir: String,
     receipt_db_size: usize,
     signature_verifier_factory: Box<dyn VerifierFactory>,
+    #[cfg(feature = "backup")]
+    commit_gate: Option<CommitGate>,
+    #[cfg(feature = "state-history")]
+    state_root_history_size: Option<usize>,
+    #[cfg(feature = "state-pruning")]
+    state_retention_depth: Option<usize>,
+    #[cfg(feature = "state-pruning")]
+    compact_state_on_create: bool,
+    #[cfg(feature = "state-pruning")]
+    batch_history_size: Option<usize>,
+    #[cfg(feature = "state-pruning")]
+    batch_history_max_age: Option<Duration>,
+    #[cfg(feature = "batch-status-store")]
+    batch_status_retention: Option<usize>,
+    #[cfg(feature = "batch-status-store")]
+    batch_status_max_age: Option<Duration>,
+    #[cfg(feature = "engines")]
+    engines: HashMap<String, Box<dyn EngineFactory>>,
 }
 
 impl ScabbardFactory {
@@ -135,12 +297,52 @@ impl ScabbardFactory {
             receipt_db_dir: receipt_db_dir.unwrap_or_else(|| DEFAULT_RECEIPT_DB_DIR.into()),
             receipt_db_size: receipt_db_size.unwrap_or(DEFAULT_RECEIPT_DB_SIZE),
             signature_verifier_factory,
+            #[cfg(feature = "backup")]
+            commit_gate: None,
+            #[cfg(feature = "state-history")]
+            state_root_history_size: None,
+            #[cfg(feature = "state-pruning")]
+            state_retention_depth: None,
+            #[cfg(feature = "state-pruning")]
+            compact_state_on_create: false,
+            #[cfg(feature = "state-pruning")]
+            batch_history_size: None,
+            #[cfg(feature = "state-pruning")]
+            batch_history_max_age: None,
+            #[cfg(feature = "batch-status-store")]
+            batch_status_retention: None,
+            #[cfg(feature = "batch-status-store")]
+            batch_status_max_age: None,
+            #[cfg(feature = "engines")]
+            engines: default_engines(),
         }
     }
+
+    /// Sets the gate that the commits of all created services must pass through.
+    #[cfg(feature = "backup")]
+    pub fn with_commit_gate(mut self, commit_gate: CommitGate) -> Self {
+        self.commit_gate = Some(commit_gate);
+        self
+    }
+}
+
+#[cfg(feature = "service-arg-validation")]
+#[derive(Default)]
+pub struct ScabbardArgValidator {
+    #[cfg(feature = "engines")]
+    engines: HashSet<String>,
 }
 
 #[cfg(feature = "service-arg-validation")]
-pub struct ScabbardArgValidator;
+impl ScabbardArgValidator {
+    /// Accepts the engine with the given name in the `engine` service argument, in addition to the
+    /// built-in engines. This should be called for each engine added to the scabbard factory.
+    #[cfg(feature = "engines")]
+    pub fn with_engine(mut self, name: String) -> Self {
+        self.engines.insert(name);
+        self
+    }
+}
 
 #[cfg(feature = "service-arg-validation")]
 impl ServiceArgValidator for ScabbardArgValidator {
@@ -177,6 +379,22 @@ impl ServiceArgValidator for ScabbardArgValidator {
             }
         }
 
+        if let Some(engine) = args.get("engine") {
+            #[cfg(feature = "engines")]
+            let supported = engine == SABRE_ENGINE
+                || engine == KEY_VALUE_ENGINE
+                || self.engines.contains(engine);
+            #[cfg(not(feature = "engines"))]
+            let supported = engine == SABRE_ENGINE;
+
+            if !supported {
+                return Err(ServiceArgValidationError(format!(
+                    "unsupported engine: {}",
+                    engine
+                )));
+            }
+        }
+
         Ok(())
     }
 }
@@ -197,6 +415,9 @@ impl ServiceFactory for ScabbardFactory {
     ///   commit a proposal before the coordinator rejects it (if not provided, default is 30
     ///   seconds)
     /// - `version`: the protocol version for scabbard (possible values: "1", "2") (default: "1")
+    /// - `engine`: the name of the engine that the service executes transactions with (possible
+    ///   values: "sabre", "key-value", or any engine added to the factory) (default: "sabre");
+    ///   only "sabre" is supported unless the `engines` feature is enabled
     fn create(
         &self,
         service_id: String,
@@ -244,7 +465,49 @@ impl ServiceFactory for ScabbardFactory {
         let version = ScabbardVersion::try_from(args.get("version").map(String::as_str))
             .map_err(FactoryCreateError::InvalidArguments)?;
 
-        let service = Scabbard::new(
+        #[cfg(feature = "engines")]
+        let handlers = {
+            let engine = args
+                .get("engine")
+                .map(String::as_str)
+                .unwrap_or(SABRE_ENGINE);
+            self.engines
+                .get(engine)
+                .ok_or_else(|| {
+                    FactoryCreateError::InvalidArguments(format!("unsupported engine: {}", engine))
+                })?
+                .new_handlers()
+        };
+        #[cfg(not(feature = "engines"))]
+        let handlers = match args.get("engine").map(String::as_str) {
+            None | Some(SABRE_ENGINE) => super::engine::sabre_handlers(),
+            Some(engine) => {
+                return Err(FactoryCreateError::InvalidArguments(format!(
+                    "unsupported engine: {}",
+                    engine
+                )))
+            }
+        };
+
+        #[cfg(feature = "state-pruning")]
+        if self.compact_state_on_create {
+            let (state_db_path, _) =
+                compute_db_paths(&service_id, circuit_id, &state_db_dir, &receipt_db_dir)
+                    .map_err(|err| FactoryCreateError::CreationFailed(Box::new(err)))?;
+            // A new service has no state database to compact yet
+            if state_db_path.with_extension("lmdb").exists() {
+                let (original_size, compacted_size) =
+                    compact_state_db(&state_db_path, self.state_db_size).map_err(|err| {
+                        FactoryCreateError::CreationFailed(Box::new(ScabbardError::from(err)))
+                    })?;
+                debug!(
+                    "Compacted state of scabbard service {}::{} from {} to {} bytes",
+                    circuit_id, service_id, original_size, compacted_size
+                );
+            }
+        }
+
+        let service = Scabbard::init(
             service_id,
             circuit_id,
             version,
@@ -256,9 +519,54 @@ impl ServiceFactory for ScabbardFactory {
             self.signature_verifier_factory.new_verifier(),
             admin_keys,
             coordinator_timeout,
+            handlers,
         )
         .map_err(|err| FactoryCreateError::CreationFailed(Box::new(err)))?;
 
+        #[cfg(feature = "backup")]
+        if let Some(commit_gate) = &self.commit_gate {
+            service
+                .set_commit_gate(commit_gate.clone())
+                .map_err(|err| FactoryCreateError::CreationFailed(Box::new(err)))?;
+        }
+
+        #[cfg(feature = "state-history")]
+        if let Some(state_root_history_size) = self.state_root_history_size {
+            service
+                .set_state_root_history_size(state_root_history_size)
+                .map_err(|err| FactoryCreateError::CreationFailed(Box::new(err)))?;
+        }
+
+        #[cfg(feature = "state-pruning")]
+        {
+            if let Some(state_retention_depth) = self.state_retention_depth {
+                service
+                    .set_state_retention_depth(state_retention_depth)
+                    .map_err(|err| FactoryCreateError::CreationFailed(Box::new(err)))?;
+            }
+
+            if self.batch_history_size.is_some() || self.batch_history_max_age.is_some() {
+                service
+                    .set_batch_history_retention(
+                        self.batch_history_size
+                            .unwrap_or(super::state::DEFAULT_BATCH_HISTORY_SIZE),
+                        self.batch_history_max_age,
+                    )
+                    .map_err(|err| FactoryCreateError::CreationFailed(Box::new(err)))?;
+            }
+        }
+
+        #[cfg(feature = "batch-status-store")]
+        if self.batch_status_retention.is_some() || self.batch_status_max_age.is_some() {
+            service
+                .set_batch_status_retention(
+                    self.batch_status_retention
+                        .unwrap_or(super::state::DEFAULT_BATCH_STATUS_RETENTION),
+                    self.batch_status_max_age,
+                )
+                .map_err(|err| FactoryCreateError::CreationFailed(Box::new(err)))?;
+        }
+
         Ok(Box::new(service))
     }
 
@@ -272,6 +580,15 @@ impl ServiceFactory for ScabbardFactory {
     /// * `GET /state/{address}` - Get a value from scabbard's state
     /// * `GET /state` - Get multiple scabbard state entries
     /// * `GET /state_root` - Get the current state root hash of scabbard's state
+    /// * `GET /state_roots` - List recently committed state root hashes and the batches that
+    ///   produced them (requires the `state-history` feature)
+    /// * `GET /receipts/{transaction_id}` - Get the receipt of a committed transaction (requires
+    ///   the `receipts` feature)
+    /// * `GET /receipts` - List the receipts of committed transactions, optionally only those
+    ///   committed after a given transaction (requires the `receipts` feature)
+    ///
+    /// With the `state-history` feature, the `GET /state/{address}` and `GET /state` endpoints
+    /// accept a `state_root` query parameter to read state as of an earlier commit.
     ///
     /// These endpoints are only available if the following REST API backend feature is enabled:
     ///
@@ -294,13 +611,31 @@ impl ServiceFactory for ScabbardFactory {
                 actix::state_address::make_get_state_at_address_endpoint(),
                 actix::state::make_get_state_with_prefix_endpoint(),
                 actix::state_root::make_get_state_root_endpoint(),
-            ])
+            ]);
+
+            #[cfg(feature = "state-history")]
+            endpoints.push(actix::state_roots::make_list_state_roots_endpoint());
+
+            #[cfg(feature = "receipts")]
+            endpoints.append(&mut vec![
+                actix::receipts::make_get_receipt_endpoint(),
+                actix::receipts::make_list_receipts_endpoint(),
+            ]);
         }
 
         endpoints
     }
 }
 
+/// Returns the engines that are available to every scabbard factory.
+#[cfg(feature = "engines")]
+fn default_engines() -> HashMap<String, Box<dyn EngineFactory>> {
+    let mut engines: HashMap<String, Box<dyn EngineFactory>> = HashMap::new();
+    engines.insert(SABRE_ENGINE.into(), Box::new(SabreEngineFactory));
+    engines.insert(KEY_VALUE_ENGINE.into(), Box::new(KeyValueEngineFactory));
+    engines
+}
+
 /// Parse a service argument into a list. Check if the argument is in json or csv format
 /// and return the list of strings. An error is returned if json fmt cannot be parsed.
 fn parse_list(values_list: &str) -> Result<Vec<String>, String> {
@@ -402,6 +737,77 @@ mod tests {
         );
     }
 
+    /// Verify that `Scabbard` creation succeeds with a built-in engine and fails when the `engine`
+    /// argument names an engine that the factory does not have.
+    #[cfg(feature = "engines")]
+    #[test]
+    fn create_with_engine() {
+        let factory = get_factory();
+
+        let mut args = get_mock_args();
+        args.insert("engine".into(), KEY_VALUE_ENGINE.into());
+        factory
+            .create("3".into(), "", "1", args)
+            .expect("failed to create service with key-value engine");
+
+        let mut args = get_mock_args();
+        args.insert("engine".into(), "unknown".into());
+        match factory.create("4".into(), "", "1", args) {
+            Err(FactoryCreateError::InvalidArguments(msg)) => {
+                assert_eq!(msg, "unsupported engine: unknown")
+            }
+            Err(err) => panic!("wrong error returned: {}", err),
+            Ok(_) => panic!("creating service with an unknown engine did not fail"),
+        }
+    }
+
+    /// Verify that `Scabbard` creation fails when the `engine` argument names an engine other than
+    /// Sabre and the `engines` feature is not enabled.
+    #[cfg(not(feature = "engines"))]
+    #[test]
+    fn create_with_unsupported_engine() {
+        let factory = get_factory();
+
+        let mut args = get_mock_args();
+        args.insert("engine".into(), SABRE_ENGINE.into());
+        factory
+            .create("3".into(), "", "1", args)
+            .expect("failed to create service with sabre engine");
+
+        let mut args = get_mock_args();
+        args.insert("engine".into(), "key-value".into());
+        match factory.create("4".into(), "", "1", args) {
+            Err(FactoryCreateError::InvalidArguments(msg)) => {
+                assert_eq!(msg, "unsupported engine: key-value")
+            }
+            Err(err) => panic!("wrong error returned: {}", err),
+            Ok(_) => panic!("creating service with an unsupported engine did not fail"),
+        }
+    }
+
+    /// Verify that the argument validator accepts the built-in engines and the engines it was
+    /// given, and rejects any other engine.
+    #[cfg(all(feature = "engines", feature = "service-arg-validation"))]
+    #[test]
+    fn validate_engine() {
+        let validator = ScabbardArgValidator::default().with_engine("custom".into());
+
+        for engine in &[SABRE_ENGINE, KEY_VALUE_ENGINE, "custom"] {
+            let mut args = get_mock_args();
+            args.insert("engine".into(), engine.to_string());
+            validator
+                .validate(&args)
+                .expect("failed to validate supported engine");
+        }
+
+        let mut args = get_mock_args();
+        args.insert("engine".into(), "unknown".into());
+        match validator.validate(&args) {
+            Err(ServiceArgValidationError(msg)) => assert_eq!(msg, "unsupported engine: unknown"),
+            Ok(()) => panic!("validating an unknown engine did not fail"),
+        }
+    }
+
     fn get_factory() -> ScabbardFactory {
         ScabbardFactory::new(
             Some("/tmp".into()),
@@ -17,12 +17,15 @@
 //! transactions.
 
 mod consensus;
+mod engine;
 mod error;
 mod factory;
 #[cfg(feature = "rest-api")]
 mod rest_api;
 mod shared;
 mod state;
+#[cfg(feature = "state-sync")]
+mod sync;
 
 use std::any::Any;
 use std::collections::{HashSet, VecDeque};
@@ -41,7 +44,10 @@ use splinter::{
         ServiceStartError, ServiceStopError,
     },
 };
+#[cfg(feature = "receipts")]
+use transact::protocol::receipt::TransactionReceipt;
 use transact::{
+    handler::TransactionHandler,
     protocol::batch::BatchPair,
     protos::{FromBytes, IntoBytes},
 };
@@ -50,6 +56,12 @@ use super::hex::to_hex;
 use super::protos::scabbard::{ScabbardMessage, ScabbardMessage_Type};
 
 use consensus::ScabbardConsensusManager;
+#[cfg(feature = "engines")]
+pub use engine::{
+    compute_key_value_address, EngineFactory, KeyValueEngineFactory, KeyValueTransactionHandler,
+    SabreEngineFactory, KEY_VALUE_ENGINE, KEY_VALUE_FAMILY_NAME, KEY_VALUE_FAMILY_VERSION,
+    SABRE_ENGINE,
+};
 use error::ScabbardError;
 #[cfg(feature = "service-arg-validation")]
 pub use factory::ScabbardArgValidator;
@@ -57,6 +69,10 @@ pub use factory::ScabbardFactory;
 #[cfg(feature = "factory-builder")]
 pub use factory::ScabbardFactoryBuilder;
 use shared::ScabbardShared;
+#[cfg(feature = "state-pruning")]
+pub use state::StatePruneSummary;
+#[cfg(feature = "state-history")]
+pub use state::StateRootInfo;
 pub use state::{
     BatchInfo, BatchInfoIter, BatchStatus, Events, StateChange, StateChangeEvent, StateIter,
 };
@@ -123,6 +139,90 @@ impl Scabbard {
         // The coordinator timeout for the two-phase commit consensus engine; if `None`, the
         // default value will be used (30 seconds).
         coordinator_timeout: Option<Duration>,
+    ) -> Result<Self, ScabbardError> {
+        Self::init(
+            service_id,
+            circuit_id,
+            version,
+            peer_services,
+            state_db_dir,
+            state_db_size,
+            receipt_db_dir,
+            receipt_db_size,
+            signature_verifier,
+            admin_keys,
+            coordinator_timeout,
+            engine::sabre_handlers(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Generate a new Scabbard service that executes transactions with the given transaction
+    /// handlers, rather than with Sawtooth Sabre.
+    #[cfg(feature = "engines")]
+    pub fn new_with_handlers(
+        service_id: String,
+        circuit_id: &str,
+        // The protocol version for scabbard
+        version: ScabbardVersion,
+        // List of other scabbard services on the same circuit that this service shares state with
+        peer_services: HashSet<String>,
+        // The directory in which to create sabre's LMDB database
+        state_db_dir: &Path,
+        // The size of sabre's LMDB database
+        state_db_size: usize,
+        // The directory in which to create the transaction receipt store's LMDB database
+        receipt_db_dir: &Path,
+        // The size of the transaction receipt store's LMDB database
+        receipt_db_size: usize,
+        signature_verifier: Box<dyn SignatureVerifier>,
+        // The public keys that are authorized to create and manage sabre contracts
+        admin_keys: Vec<String>,
+        // The coordinator timeout for the two-phase commit consensus engine; if `None`, the
+        // default value will be used (30 seconds).
+        coordinator_timeout: Option<Duration>,
+        // The transaction handlers that execute the service's transactions
+        handlers: Vec<Box<dyn TransactionHandler>>,
+    ) -> Result<Self, ScabbardError> {
+        Self::init(
+            service_id,
+            circuit_id,
+            version,
+            peer_services,
+            state_db_dir,
+            state_db_size,
+            receipt_db_dir,
+            receipt_db_size,
+            signature_verifier,
+            admin_keys,
+            coordinator_timeout,
+            handlers,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn init(
+        service_id: String,
+        circuit_id: &str,
+        // The protocol version for scabbard
+        version: ScabbardVersion,
+        // List of other scabbard services on the same circuit that this service shares state with
+        peer_services: HashSet<String>,
+        // The directory in which to create sabre's LMDB database
+        state_db_dir: &Path,
+        // The size of sabre's LMDB database
+        state_db_size: usize,
+        // The directory in which to create the transaction receipt store's LMDB database
+        receipt_db_dir: &Path,
+        // The size of the transaction receipt store's LMDB database
+        receipt_db_size: usize,
+        signature_verifier: Box<dyn SignatureVerifier>,
+        // The public keys that are authorized to create and manage sabre contracts
+        admin_keys: Vec<String>,
+        // The coordinator timeout for the two-phase commit consensus engine; if `None`, the
+        // default value will be used (30 seconds).
+        coordinator_timeout: Option<Duration>,
+        handlers: Vec<Box<dyn TransactionHandler>>,
     ) -> Result<Self, ScabbardError> {
         let shared = ScabbardShared::new(
             VecDeque::new(),
@@ -136,12 +236,13 @@ impl Scabbard {
 
         let (state_db_path, receipt_db_path) =
             compute_db_paths(&service_id, circuit_id, state_db_dir, receipt_db_dir)?;
-        let state = ScabbardState::new(
+        let state = ScabbardState::new_with_handlers(
             &state_db_path,
             state_db_size,
             &receipt_db_path,
             receipt_db_size,
             admin_keys,
+            handlers,
         )
         .map_err(|err| ScabbardError::InitializationFailed(Box::new(err)))?;
 
@@ -159,6 +260,96 @@ impl Scabbard {
         })
     }
 
+    /// Set the gate that the service's commits must pass through, allowing them to be held off
+    /// while a backup is created.
+    #[cfg(feature = "backup")]
+    pub fn set_commit_gate(
+        &self,
+        commit_gate: splinter::backup::CommitGate,
+    ) -> Result<(), ScabbardError> {
+        self.state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .set_commit_gate(commit_gate);
+
+        Ok(())
+    }
+
+    /// Set the number of most recent state roots to retain; older state roots are pruned after
+    /// each commit. The retention depth must be at least 1.
+    #[cfg(feature = "state-pruning")]
+    pub fn set_state_retention_depth(&self, retention_depth: usize) -> Result<(), ScabbardError> {
+        self.state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .set_state_retention_depth(retention_depth);
+
+        Ok(())
+    }
+
+    /// Set the number of most recent state roots that are kept in the service's state root
+    /// history. The history size must be at least 1.
+    #[cfg(feature = "state-history")]
+    pub fn set_state_root_history_size(&self, history_size: usize) -> Result<(), ScabbardError> {
+        self.state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .set_state_root_history_size(history_size);
+
+        Ok(())
+    }
+
+    /// Set the maximum number of batches, and optionally the maximum age of batches, that are
+    /// kept in the service's batch history.
+    #[cfg(feature = "state-pruning")]
+    pub fn set_batch_history_retention(
+        &self,
+        limit: usize,
+        max_age: Option<Duration>,
+    ) -> Result<(), ScabbardError> {
+        let mut state = self.state.lock().map_err(|_| ScabbardError::LockPoisoned)?;
+        let batch_history = state.batch_history();
+        batch_history.set_limit(limit);
+        if let Some(max_age) = max_age {
+            batch_history.set_max_age(max_age);
+        }
+
+        Ok(())
+    }
+
+    /// Set the maximum number of completed batches, and optionally the maximum amount of time after
+    /// completion, that the service persists batch statuses for. Persisted batch statuses can
+    /// still be looked up after the service restarts.
+    #[cfg(feature = "batch-status-store")]
+    pub fn set_batch_status_retention(
+        &self,
+        limit: usize,
+        max_age: Option<Duration>,
+    ) -> Result<(), ScabbardError> {
+        self.state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .batch_history()
+            .set_store_retention(limit, max_age);
+
+        Ok(())
+    }
+
+    /// Request that the service's state be synchronized with its peers. Only the missing
+    /// transactions are fetched, unless `snapshot` is `true`, in which case the service's state is
+    /// replaced by a snapshot of a peer's state. Either way, the service only adopts a state root
+    /// that a majority of its peers report. The service must be started.
+    #[cfg(feature = "state-sync")]
+    pub fn synchronize_state(&self, snapshot: bool) -> Result<(), ScabbardError> {
+        let mut shared = self
+            .shared
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?;
+        let state = self.state.lock().map_err(|_| ScabbardError::LockPoisoned)?;
+
+        sync::request_state_sync(&mut shared, &state, snapshot)
+    }
+
     /// Fetch the value at the given `address` in the scabbard service's state. Returns `None` if
     /// the `address` is not set.
     pub fn get_state_at_address(&self, address: &str) -> Result<Option<Vec<u8>>, ScabbardError> {
@@ -190,6 +381,103 @@ impl Scabbard {
             .to_string())
     }
 
+    /// Determine whether the scabbard service's state can be read at the given state root. A state
+    /// root is unknown if it was never committed by this service, or if it has been pruned.
+    #[cfg(feature = "state-history")]
+    pub fn has_state_root(&self, state_root: &str) -> Result<bool, ScabbardError> {
+        Ok(self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .has_state_root(state_root)?)
+    }
+
+    /// Fetch the value at the given `address` in the scabbard service's state as of the given
+    /// `state_root`. Returns `None` if the `address` was not set at that root.
+    #[cfg(feature = "state-history")]
+    pub fn get_state_at_address_at_root(
+        &self,
+        address: &str,
+        state_root: &str,
+    ) -> Result<Option<Vec<u8>>, ScabbardError> {
+        Ok(self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .get_state_at_address_at_root(address, state_root)?)
+    }
+
+    /// Fetch a list of entries in the scabbard service's state as of the given `state_root`. If a
+    /// `prefix` is provided, only return entries whose addresses are under the given address
+    /// prefix.
+    #[cfg(feature = "state-history")]
+    pub fn get_state_with_prefix_at_root(
+        &self,
+        prefix: Option<&str>,
+        state_root: &str,
+    ) -> Result<StateIter, ScabbardError> {
+        Ok(self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .get_state_with_prefix_at_root(prefix, state_root)?)
+    }
+
+    /// List the state roots committed by the scabbard service, newest first, along with the IDs of
+    /// the batches that produced them. If a `limit` is provided, at most that many state roots are
+    /// returned.
+    #[cfg(feature = "state-history")]
+    pub fn list_state_roots(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<Vec<StateRootInfo>, ScabbardError> {
+        Ok(self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .list_state_roots(limit)?)
+    }
+
+    /// Get the receipt of the transaction with the given ID that was committed by the scabbard
+    /// service, or `None` if no such transaction was committed.
+    #[cfg(feature = "receipts")]
+    pub fn get_receipt(
+        &self,
+        transaction_id: &str,
+    ) -> Result<Option<TransactionReceipt>, ScabbardError> {
+        Ok(self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .get_receipt(transaction_id)?)
+    }
+
+    /// List a page of the transaction receipts committed by the scabbard service, oldest first,
+    /// along with the total number of receipts available. If `since` is provided, only receipts
+    /// committed after the transaction with that ID are listed, and the total only counts up to
+    /// one receipt past the page; `None` is returned if that transaction was never committed.
+    #[cfg(feature = "receipts")]
+    pub fn list_receipts(
+        &self,
+        since: Option<&str>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Option<(Vec<TransactionReceipt>, usize)>, ScabbardError> {
+        // The receipts are read without holding the state lock, so commits aren't held up
+        let transaction_receipt_store = self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .transaction_receipt_store();
+
+        Ok(state::list_receipts(
+            &transaction_receipt_store,
+            since,
+            offset,
+            limit,
+        )?)
+    }
+
     /// Get whether the service is currently accepting batches
     #[cfg(feature = "back-pressure")]
     pub fn accepting_batches(&self) -> Result<bool, ScabbardError> {
@@ -343,6 +631,12 @@ impl Service for Scabbard {
             })?,
         );
 
+        // Commits may have been missed while the service was stopped
+        #[cfg(feature = "state-sync")]
+        if let Err(err) = self.synchronize_state(false) {
+            warn!("Unable to request state sync on startup: {}", err);
+        }
+
         Ok(())
     }
 
@@ -503,6 +797,42 @@ impl Service for Scabbard {
                 }
                 Ok(())
             }
+            #[cfg(feature = "state-sync")]
+            ScabbardMessage_Type::STATE_SYNC_REQUEST => {
+                let mut shared = self
+                    .shared
+                    .lock()
+                    .map_err(|_| ServiceError::PoisonedLock("shared lock poisoned".into()))?;
+                let state = self
+                    .state
+                    .lock()
+                    .map_err(|_| ServiceError::PoisonedLock("state lock poisoned".into()))?;
+
+                sync::handle_state_sync_request(
+                    message.get_state_sync_request(),
+                    &mut shared,
+                    &state,
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
+            }
+            #[cfg(feature = "state-sync")]
+            ScabbardMessage_Type::STATE_SYNC_RESPONSE => {
+                let mut shared = self
+                    .shared
+                    .lock()
+                    .map_err(|_| ServiceError::PoisonedLock("shared lock poisoned".into()))?;
+                let mut state = self
+                    .state
+                    .lock()
+                    .map_err(|_| ServiceError::PoisonedLock("state lock poisoned".into()))?;
+
+                sync::handle_state_sync_response(
+                    message.get_state_sync_response(),
+                    &mut shared,
+                    &mut state,
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
+            }
             _ => Err(ServiceError::InvalidMessageFormat(Box::new(
                 ScabbardError::MessageTypeUnset,
             ))),
@@ -531,6 +861,29 @@ fn compute_db_paths(
     Ok((state_db_path, receipt_db_path))
 }
 
+/// Prunes the state database of a scabbard service, keeping the `retention_depth` most recent state
+/// roots, and optionally compacts the database file to reclaim the freed space.
+///
+/// The service must not be running while its state is pruned.
+#[cfg(feature = "state-pruning")]
+pub fn prune_service_state(
+    service_id: &str,
+    circuit_id: &str,
+    state_db_dir: &Path,
+    state_db_size: usize,
+    retention_depth: usize,
+    compact: bool,
+) -> Result<StatePruneSummary, ScabbardError> {
+    // The receipt database is not affected by pruning, so its directory doesn't matter here
+    let (state_db_path, _) = compute_db_paths(service_id, circuit_id, state_db_dir, state_db_dir)?;
+    Ok(state::prune_state_db(
+        &state_db_path,
+        state_db_size,
+        retention_depth,
+        compact,
+    )?)
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
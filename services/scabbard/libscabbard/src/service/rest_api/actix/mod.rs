@@ -14,7 +14,11 @@
 
 pub mod batch_statuses;
 pub mod batches;
+#[cfg(feature = "receipts")]
+pub mod receipts;
 pub mod state;
 pub mod state_address;
 pub mod state_root;
+#[cfg(feature = "state-history")]
+pub mod state_roots;
 pub mod ws_subscribe;
@@ -0,0 +1,202 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+use futures::IntoFuture;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use splinter::{
+    rest_api::{
+        paging::{get_response_paging_info, DEFAULT_LIMIT, DEFAULT_OFFSET},
+        ErrorResponse, Method, ProtocolVersionRangeGuard,
+    },
+    service::rest_api::ServiceEndpoint,
+};
+
+use crate::protocol;
+#[cfg(feature = "authorization")]
+use crate::service::rest_api::SCABBARD_READ_PERMISSION;
+use crate::service::{
+    rest_api::resources::receipts::{ListReceiptsResponse, ReceiptResponse},
+    Scabbard, SERVICE_TYPE,
+};
+
+pub fn make_get_receipt_endpoint() -> ServiceEndpoint {
+    ServiceEndpoint {
+        service_type: SERVICE_TYPE.into(),
+        route: "/receipts/{transaction_id}".into(),
+        method: Method::Get,
+        handler: Arc::new(move |request, _, service| {
+            let scabbard = match service.as_any().downcast_ref::<Scabbard>() {
+                Some(s) => s,
+                None => {
+                    error!("Failed to downcast to scabbard service");
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+
+            let transaction_id = request
+                .match_info()
+                .get("transaction_id")
+                .expect("transaction_id should not be none");
+
+            Box::new(match scabbard.get_receipt(transaction_id) {
+                Ok(Some(receipt)) => HttpResponse::Ok()
+                    .json(ReceiptResponse::from(&receipt))
+                    .into_future(),
+                Ok(None) => HttpResponse::NotFound()
+                    .json(ErrorResponse::not_found("Transaction receipt not found"))
+                    .into_future(),
+                Err(err) => {
+                    error!("Failed to get transaction receipt: {}", err);
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future()
+                }
+            })
+        }),
+        request_guards: vec![Box::new(ProtocolVersionRangeGuard::new(
+            protocol::SCABBARD_GET_RECEIPT_PROTOCOL_MIN,
+            protocol::SCABBARD_PROTOCOL_VERSION,
+        ))],
+        #[cfg(feature = "authorization")]
+        permission: SCABBARD_READ_PERMISSION,
+    }
+}
+
+pub fn make_list_receipts_endpoint() -> ServiceEndpoint {
+    ServiceEndpoint {
+        service_type: SERVICE_TYPE.into(),
+        route: "/receipts".into(),
+        method: Method::Get,
+        handler: Arc::new(move |request, _, service| {
+            let scabbard = match service.as_any().downcast_ref::<Scabbard>() {
+                Some(s) => s,
+                None => {
+                    error!("Failed to downcast to scabbard service");
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+
+            let query: web::Query<HashMap<String, String>> =
+                if let Ok(q) = web::Query::from_query(request.query_string()) {
+                    q
+                } else {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("Invalid query"))
+                            .into_future(),
+                    );
+                };
+
+            let offset = match query.get("offset") {
+                Some(value) => match value.parse::<usize>() {
+                    Ok(val) => val,
+                    Err(err) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Invalid offset value passed: {}. Error: {}",
+                                    value, err
+                                )))
+                                .into_future(),
+                        )
+                    }
+                },
+                None => DEFAULT_OFFSET,
+            };
+
+            let limit = match query.get("limit") {
+                Some(value) => match value.parse::<usize>() {
+                    Ok(val) if val > 0 => val,
+                    Ok(_) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(
+                                    "Invalid limit value passed: must be greater than 0",
+                                ))
+                                .into_future(),
+                        )
+                    }
+                    Err(err) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Invalid limit value passed: {}. Error: {}",
+                                    value, err
+                                )))
+                                .into_future(),
+                        )
+                    }
+                },
+                None => DEFAULT_LIMIT,
+            };
+
+            let since = query.get("since");
+
+            let link = match since {
+                Some(since) => format!(
+                    "{}?since={}&",
+                    request.uri().path(),
+                    utf8_percent_encode(since, NON_ALPHANUMERIC)
+                ),
+                None => format!("{}?", request.uri().path()),
+            };
+
+            Box::new(
+                match scabbard.list_receipts(since.map(String::as_str), offset, limit) {
+                    Ok(Some((receipts, total))) => HttpResponse::Ok()
+                        .json(ListReceiptsResponse {
+                            data: receipts.iter().map(ReceiptResponse::from).collect(),
+                            paging: get_response_paging_info(
+                                Some(limit),
+                                Some(offset),
+                                &link,
+                                total,
+                            ),
+                        })
+                        .into_future(),
+                    Ok(None) => HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&format!(
+                            "Transaction receipt not found: {}",
+                            since.map(String::as_str).unwrap_or_default()
+                        )))
+                        .into_future(),
+                    Err(err) => {
+                        error!("Failed to list transaction receipts: {}", err);
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future()
+                    }
+                },
+            )
+        }),
+        request_guards: vec![Box::new(ProtocolVersionRangeGuard::new(
+            protocol::SCABBARD_LIST_RECEIPTS_PROTOCOL_MIN,
+            protocol::SCABBARD_PROTOCOL_VERSION,
+        ))],
+        #[cfg(feature = "authorization")]
+        permission: SCABBARD_READ_PERMISSION,
+    }
+}
@@ -22,6 +22,8 @@ use splinter::{
     service::rest_api::ServiceEndpoint,
 };
 
+#[cfg(feature = "state-history")]
+use crate::hex::parse_hex;
 use crate::protocol;
 #[cfg(feature = "authorization")]
 use crate::service::rest_api::SCABBARD_READ_PERMISSION;
@@ -58,7 +60,38 @@ pub fn make_get_state_with_prefix_endpoint() -> ServiceEndpoint {
 
             let prefix = query.get("prefix").map(String::as_str);
 
-            Box::new(match scabbard.get_state_with_prefix(prefix) {
+            #[cfg(feature = "state-history")]
+            let result = match query.get("state_root") {
+                Some(state_root) if parse_hex(&state_root).is_err() => {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Invalid state root: {}",
+                                state_root
+                            )))
+                            .into_future(),
+                    )
+                }
+                Some(state_root) => match scabbard.has_state_root(state_root) {
+                    Ok(true) => scabbard.get_state_with_prefix_at_root(prefix, state_root),
+                    Ok(false) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Unknown state root: {}",
+                                    state_root
+                                )))
+                                .into_future(),
+                        )
+                    }
+                    Err(err) => Err(err),
+                },
+                None => scabbard.get_state_with_prefix(prefix),
+            };
+            #[cfg(not(feature = "state-history"))]
+            let result = scabbard.get_state_with_prefix(prefix);
+
+            Box::new(match result {
                 Ok(state_iter) => {
                     let res = state_iter.collect::<Result<Vec<_>, _>>();
                     match res {
@@ -12,8 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "state-history")]
+use std::collections::HashMap;
 use std::sync::Arc;
 
+#[cfg(feature = "state-history")]
+use actix_web::web;
 use actix_web::HttpResponse;
 use futures::IntoFuture;
 use splinter::{
@@ -21,6 +25,8 @@ use splinter::{
     service::rest_api::ServiceEndpoint,
 };
 
+#[cfg(feature = "state-history")]
+use crate::hex::parse_hex;
 use crate::protocol;
 #[cfg(feature = "authorization")]
 use crate::service::rest_api::SCABBARD_READ_PERMISSION;
@@ -49,7 +55,51 @@ pub fn make_get_state_at_address_endpoint() -> ServiceEndpoint {
                 .get("address")
                 .expect("address should not be none");
 
-            Box::new(match scabbard.get_state_at_address(address) {
+            #[cfg(feature = "state-history")]
+            let state_root =
+                match web::Query::<HashMap<String, String>>::from_query(request.query_string()) {
+                    Ok(query) => query.get("state_root").cloned(),
+                    Err(_) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request("Invalid query"))
+                                .into_future(),
+                        )
+                    }
+                };
+
+            #[cfg(feature = "state-history")]
+            let result = match state_root {
+                Some(state_root) if parse_hex(&state_root).is_err() => {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Invalid state root: {}",
+                                state_root
+                            )))
+                            .into_future(),
+                    )
+                }
+                Some(state_root) => match scabbard.has_state_root(&state_root) {
+                    Ok(true) => scabbard.get_state_at_address_at_root(address, &state_root),
+                    Ok(false) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Unknown state root: {}",
+                                    state_root
+                                )))
+                                .into_future(),
+                        )
+                    }
+                    Err(err) => Err(err),
+                },
+                None => scabbard.get_state_at_address(address),
+            };
+            #[cfg(not(feature = "state-history"))]
+            let result = scabbard.get_state_at_address(address);
+
+            Box::new(match result {
                 Ok(Some(value)) => HttpResponse::Ok().json(value).into_future(),
                 Ok(None) => HttpResponse::NotFound()
                     .json(ErrorResponse::not_found("Address not set"))
@@ -213,6 +263,24 @@ mod tests {
         let response_value: Vec<u8> = resp.json().expect("Failed to deserialize body");
         assert_eq!(response_value, value);
 
+        // Verify that a request with a state root that is not valid hex results in a BAD_REQUEST
+        // response
+        #[cfg(feature = "state-history")]
+        {
+            let url = Url::parse(&format!("{}/{}?state_root=xyz", base_url, address))
+                .expect("Failed to parse URL");
+            let resp = Client::new()
+                .get(url)
+                .header(
+                    "SplinterProtocolVersion",
+                    protocol::SCABBARD_PROTOCOL_VERSION,
+                )
+                .header("Authorization", "test")
+                .send()
+                .expect("Failed to perform request");
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
         shutdown_handle
             .shutdown()
             .expect("Unable to shutdown rest api");
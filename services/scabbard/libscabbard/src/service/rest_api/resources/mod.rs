@@ -14,4 +14,6 @@
 
 pub mod batch_statuses;
 pub mod batches;
+#[cfg(feature = "receipts")]
+pub mod receipts;
 pub mod state;
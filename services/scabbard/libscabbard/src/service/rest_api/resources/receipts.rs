@@ -0,0 +1,90 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use splinter::rest_api::paging::Paging;
+use transact::protocol::receipt::{Event, StateChange, TransactionReceipt, TransactionResult};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListReceiptsResponse<'a> {
+    pub data: Vec<ReceiptResponse<'a>>,
+    pub paging: Paging,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReceiptResponse<'a> {
+    pub transaction_id: &'a str,
+    pub state_changes: Vec<StateChangeResponse<'a>>,
+    pub events: Vec<EventResponse<'a>>,
+    pub data: &'a [Vec<u8>],
+}
+
+impl<'a> From<&'a TransactionReceipt> for ReceiptResponse<'a> {
+    fn from(receipt: &'a TransactionReceipt) -> Self {
+        match &receipt.transaction_result {
+            TransactionResult::Valid {
+                state_changes,
+                events,
+                data,
+            } => Self {
+                transaction_id: &receipt.transaction_id,
+                state_changes: state_changes
+                    .iter()
+                    .map(StateChangeResponse::from)
+                    .collect(),
+                events: events.iter().map(EventResponse::from).collect(),
+                data,
+            },
+            // Only the receipts of valid transactions are committed to the receipt store
+            TransactionResult::Invalid { .. } => Self {
+                transaction_id: &receipt.transaction_id,
+                state_changes: vec![],
+                events: vec![],
+                data: &[],
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum StateChangeResponse<'a> {
+    Set { key: &'a str, value: &'a [u8] },
+    Delete { key: &'a str },
+}
+
+impl<'a> From<&'a StateChange> for StateChangeResponse<'a> {
+    fn from(change: &'a StateChange) -> Self {
+        match change {
+            StateChange::Set { key, value } => StateChangeResponse::Set { key, value },
+            StateChange::Delete { key } => StateChangeResponse::Delete { key },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventResponse<'a> {
+    pub event_type: &'a str,
+    pub attributes: &'a [(String, String)],
+    pub data: &'a [u8],
+}
+
+impl<'a> From<&'a Event> for EventResponse<'a> {
+    fn from(event: &'a Event) -> Self {
+        Self {
+            event_type: &event.event_type,
+            attributes: &event.attributes,
+            data: &event.data,
+        }
+    }
+}
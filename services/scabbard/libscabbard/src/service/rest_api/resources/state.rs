@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "state-history")]
+use crate::service::StateRootInfo;
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct StateEntryResponse<'a> {
     pub address: &'a str,
@@ -26,3 +29,20 @@ impl<'a> From<&'a (String, Vec<u8>)> for StateEntryResponse<'a> {
         }
     }
 }
+
+#[cfg(feature = "state-history")]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StateRootResponse<'a> {
+    pub state_root: &'a str,
+    pub batch_id: Option<&'a str>,
+}
+
+#[cfg(feature = "state-history")]
+impl<'a> From<&'a StateRootInfo> for StateRootResponse<'a> {
+    fn from(info: &'a StateRootInfo) -> Self {
+        Self {
+            state_root: &info.state_root,
+            batch_id: info.batch_id.as_deref(),
+        }
+    }
+}
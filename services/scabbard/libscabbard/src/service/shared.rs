@@ -13,6 +13,8 @@
 // limitations under the License.
 
 use std::collections::{HashMap, HashSet, VecDeque};
+#[cfg(feature = "state-sync")]
+use std::sync::{Arc, Mutex};
 
 use cylinder::{PublicKey, Signature, Verifier as SignatureVerifier};
 use openssl::hash::{hash, MessageDigest};
@@ -32,6 +34,8 @@ use crate::hex::parse_hex;
 use crate::protos::scabbard::{ScabbardMessage, ScabbardMessage_Type};
 
 use super::error::ScabbardError;
+#[cfg(feature = "state-sync")]
+use super::sync::StateSync;
 #[cfg(feature = "back-pressure")]
 use super::ScabbardVersion;
 
@@ -62,6 +66,12 @@ pub struct ScabbardShared {
     accepting_batches: bool,
     #[cfg(feature = "back-pressure")]
     scabbard_version: ScabbardVersion,
+    /// The outstanding state sync request, if there is one
+    #[cfg(feature = "state-sync")]
+    state_sync: Option<StateSync>,
+    /// The services that a snapshot of this service's state is currently being sent to
+    #[cfg(feature = "state-sync")]
+    snapshot_recipients: Arc<Mutex<HashSet<String>>>,
 }
 
 impl ScabbardShared {
@@ -101,6 +111,10 @@ impl ScabbardShared {
             accepting_batches: true,
             #[cfg(feature = "back-pressure")]
             scabbard_version,
+            #[cfg(feature = "state-sync")]
+            state_sync: None,
+            #[cfg(feature = "state-sync")]
+            snapshot_recipients: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
@@ -114,6 +128,55 @@ impl ScabbardShared {
         &self.coordinator_service_id
     }
 
+    /// Gets this service's ID.
+    #[cfg(feature = "state-sync")]
+    pub fn service_id(&self) -> &str {
+        &self.service_id
+    }
+
+    /// Marks the start of a state sync. Returns `false` if a state sync request is already
+    /// outstanding and has not yet timed out, in which case a new request should not be sent.
+    #[cfg(feature = "state-sync")]
+    pub fn start_state_sync(&mut self, state_sync: StateSync) -> bool {
+        if self.state_sync_in_progress() {
+            return false;
+        }
+
+        self.state_sync = Some(state_sync);
+        true
+    }
+
+    /// Determines if a state sync request is outstanding and has not yet timed out. The service
+    /// does not take part in two-phase commit while it is.
+    #[cfg(feature = "state-sync")]
+    pub fn state_sync_in_progress(&self) -> bool {
+        self.state_sync
+            .as_ref()
+            .map(|state_sync| !state_sync.is_timed_out())
+            .unwrap_or(false)
+    }
+
+    /// Gets the outstanding state sync request, unless it has timed out.
+    #[cfg(feature = "state-sync")]
+    pub fn state_sync_mut(&mut self) -> Option<&mut StateSync> {
+        self.state_sync
+            .as_mut()
+            .filter(|state_sync| !state_sync.is_timed_out())
+    }
+
+    /// Marks the outstanding state sync, if any, as finished.
+    #[cfg(feature = "state-sync")]
+    pub fn finish_state_sync(&mut self) {
+        self.state_sync = None;
+    }
+
+    /// Gets the services that a snapshot of this service's state is currently being sent to. A
+    /// snapshot is sent in the background, and the recipient is removed once it has been sent.
+    #[cfg(feature = "state-sync")]
+    pub fn snapshot_recipients(&self) -> Arc<Mutex<HashSet<String>>> {
+        self.snapshot_recipients.clone()
+    }
+
     /// set whether we are accepting new batches
     #[cfg(feature = "back-pressure")]
     pub fn set_accepting_batches(&mut self, accepting: bool) {
@@ -25,13 +25,21 @@ use std::time::{Duration, Instant, SystemTime};
 
 use protobuf::Message;
 use sawtooth::store::{lmdb::LmdbOrderedStore, receipt_store::TransactionReceiptStore};
-use sawtooth_sabre::{
-    handler::SabreTransactionHandler, ADMINISTRATORS_SETTING_ADDRESS, ADMINISTRATORS_SETTING_KEY,
-};
+use sawtooth_sabre::{ADMINISTRATORS_SETTING_ADDRESS, ADMINISTRATORS_SETTING_KEY};
+#[cfg(feature = "backup")]
+use splinter::backup::CommitGate;
 #[cfg(feature = "events")]
 use splinter::events::{ParseBytes, ParseError};
+#[cfg(any(
+    feature = "batch-status-store",
+    feature = "state-history",
+    feature = "state-pruning"
+))]
+use transact::database::DatabaseWriter;
 #[cfg(test)]
 use transact::families::command::CommandTransactionHandler;
+#[cfg(feature = "state-pruning")]
+use transact::state::Prune;
 use transact::{
     context::manager::sync::ContextManager,
     database::{
@@ -39,11 +47,11 @@ use transact::{
         Database,
     },
     execution::{adapter::static_adapter::StaticExecutionAdapter, executor::Executor},
+    handler::TransactionHandler,
     protocol::{
         batch::BatchPair,
         receipt::{TransactionReceipt, TransactionResult},
     },
-    sawtooth::SawtoothToTransactHandlerAdapter,
     scheduler::{serial::SerialScheduler, BatchExecutionResult, Scheduler},
     state::{
         merkle::{MerkleRadixTree, MerkleState, StateDatabaseError, INDEXES},
@@ -60,11 +68,64 @@ const EXECUTION_TIMEOUT: u64 = 300; // five minutes
 const CURRENT_STATE_ROOT_INDEX: &str = "current_state_root";
 const ITER_CACHE_SIZE: usize = 64;
 const COMPLETED_BATCH_INFO_ITER_RETRY_MILLIS: u64 = 100;
-const DEFAULT_BATCH_HISTORY_SIZE: usize = 100;
+pub(super) const DEFAULT_BATCH_HISTORY_SIZE: usize = 100;
+#[cfg(any(feature = "state-history", feature = "state-pruning"))]
+const STATE_ROOT_HISTORY_INDEX: &str = "state_root_history";
+#[cfg(feature = "state-history")]
+pub(super) const DEFAULT_STATE_ROOT_HISTORY_SIZE: usize = 10_000;
+#[cfg(feature = "state-pruning")]
+const COMPACTION_BATCH_SIZE: usize = 10_000;
+#[cfg(feature = "state-sync")]
+const SNAPSHOT_MERGE_BATCH_SIZE: usize = 10_000;
+#[cfg(feature = "batch-status-store")]
+const BATCH_STATUS_INDEX: &str = "batch_status";
+#[cfg(feature = "batch-status-store")]
+const BATCH_STATUS_ORDER_INDEX: &str = "batch_status_order";
+#[cfg(feature = "batch-status-store")]
+pub(super) const DEFAULT_BATCH_STATUS_RETENTION: usize = 10_000;
 
 /// Iterator over entries in a Scabbard service's state
 pub type StateIter = Box<dyn Iterator<Item = Result<(String, Vec<u8>), ScabbardStateError>>>;
 
+/// A snapshot of a peer's state that is being received, along with the state root built from the
+/// chunks received so far. The snapshot's database is not part of a backup, so it's written
+/// without entering the commit gate.
+#[cfg(feature = "state-sync")]
+struct StagedSnapshot {
+    db: Box<dyn Database>,
+    state_root: String,
+}
+
+/// A read-only view of a scabbard service's state at a fixed state root. It can be read from
+/// another thread, without holding the lock on the service's state, for as long as the state root
+/// is not pruned.
+#[cfg(feature = "state-sync")]
+pub struct StateView {
+    db: Box<dyn Database>,
+    state_root: String,
+}
+
+#[cfg(feature = "state-sync")]
+impl StateView {
+    /// Get the state root this view is at.
+    pub fn state_root(&self) -> &str {
+        &self.state_root
+    }
+
+    /// Fetch all of the entries in state at this view's state root.
+    pub fn entries(&self) -> Result<StateIter, ScabbardStateError> {
+        Ok(Box::new(
+            MerkleRadixTree::new(self.db.clone(), Some(&self.state_root))?
+                .leaves(None)
+                .or_else(|err| match err {
+                    StateDatabaseError::NotFound(_) => Ok(Box::new(std::iter::empty())),
+                    err => Err(err),
+                })?
+                .map(|res| res.map_err(ScabbardStateError::from)),
+        ))
+    }
+}
+
 pub struct ScabbardState {
     db: Box<dyn Database>,
     context_manager: ContextManager,
@@ -76,26 +137,59 @@ pub struct ScabbardState {
     batch_history: BatchHistory,
     state_db_file: PathBuf,
     receipt_db_file: PathBuf,
+    #[cfg(feature = "state-sync")]
+    state_db_size: usize,
+    #[cfg(feature = "state-sync")]
+    receipt_db_size: usize,
+    #[cfg(feature = "state-sync")]
+    snapshot: Option<StagedSnapshot>,
+    #[cfg(feature = "backup")]
+    commit_gate: Option<CommitGate>,
+    #[cfg(feature = "state-pruning")]
+    state_retention_depth: Option<usize>,
+    #[cfg(any(feature = "state-history", feature = "state-pruning"))]
+    next_state_root_sequence: u64,
+    #[cfg(feature = "state-history")]
+    state_root_history_size: usize,
 }
 
 impl ScabbardState {
+    /// Creates the state of a scabbard service whose transactions are executed by Sawtooth Sabre.
+    #[cfg(test)]
     pub fn new(
         state_db_path: &Path,
         state_db_size: usize,
         receipt_db_path: &Path,
         receipt_db_size: usize,
         admin_keys: Vec<String>,
+    ) -> Result<Self, ScabbardStateError> {
+        Self::new_with_handlers(
+            state_db_path,
+            state_db_size,
+            receipt_db_path,
+            receipt_db_size,
+            admin_keys,
+            super::engine::sabre_handlers(),
+        )
+    }
+
+    /// Creates the state of a scabbard service whose transactions are executed by the given
+    /// transaction handlers.
+    pub fn new_with_handlers(
+        state_db_path: &Path,
+        state_db_size: usize,
+        receipt_db_path: &Path,
+        receipt_db_size: usize,
+        admin_keys: Vec<String>,
+        handlers: Vec<Box<dyn TransactionHandler>>,
     ) -> Result<Self, ScabbardStateError> {
         // Initialize the database
-        let mut indexes = INDEXES.to_vec();
-        indexes.push(CURRENT_STATE_ROOT_INDEX);
         let state_db_file = state_db_path.to_path_buf();
         let receipt_db_file = receipt_db_path.to_path_buf();
-        let state_db_path = state_db_path.with_extension("lmdb");
         let receipt_db_path = receipt_db_path.with_extension("lmdb");
-        let db = Box::new(LmdbDatabase::new(
-            LmdbContext::new(&state_db_path, indexes.len(), Some(state_db_size))?,
-            &indexes,
+        let db = Box::new(open_state_db(
+            &state_db_path.with_extension("lmdb"),
+            state_db_size,
         )?);
 
         let current_state_root = if let Some(current_state_root) =
@@ -129,15 +223,13 @@ impl ScabbardState {
         };
 
         // Initialize transact
+        #[allow(unused_mut)]
+        let mut handlers = handlers;
+        #[cfg(test)]
+        handlers.push(Box::new(CommandTransactionHandler::new()));
         let context_manager = ContextManager::new(Box::new(MerkleState::new(db.clone())));
         let mut executor = Executor::new(vec![Box::new(StaticExecutionAdapter::new_adapter(
-            vec![
-                Box::new(SawtoothToTransactHandlerAdapter::new(
-                    SabreTransactionHandler::new(),
-                )),
-                #[cfg(test)]
-                Box::new(CommandTransactionHandler::new()),
-            ],
+            handlers,
             context_manager.clone(),
         )?)]);
         executor
@@ -147,25 +239,80 @@ impl ScabbardState {
         // initialize committed_batches metric
         counter!("splinter.scabbard.committed_batches", 0);
 
+        #[cfg(any(feature = "state-history", feature = "state-pruning"))]
+        let next_state_root_sequence = next_sequence(&*db, STATE_ROOT_HISTORY_INDEX)?;
+
+        // Remove the remains of a snapshot that was being received when the service stopped, if any
+        #[cfg(feature = "state-sync")]
+        remove_snapshot_db_files(&state_db_file)?;
+
+        #[allow(unused_mut)]
+        let mut batch_history = BatchHistory::new();
+        #[cfg(feature = "batch-status-store")]
+        batch_history.set_store(BatchStatusStore::new(db.clone())?);
+
         Ok(ScabbardState {
             db,
             context_manager,
             executor,
             current_state_root,
-            transaction_receipt_store: Arc::new(RwLock::new(TransactionReceiptStore::new(
-                Box::new(
-                    LmdbOrderedStore::new(&receipt_db_path, Some(receipt_db_size))
-                        .map_err(|err| ScabbardStateError(err.to_string()))?,
-                ),
-            ))),
+            transaction_receipt_store: Arc::new(RwLock::new(open_receipt_store(
+                &receipt_db_path,
+                receipt_db_size,
+            )?)),
             pending_changes: None,
             event_subscribers: vec![],
-            batch_history: BatchHistory::new(),
+            batch_history,
             state_db_file,
             receipt_db_file,
+            #[cfg(feature = "state-sync")]
+            state_db_size,
+            #[cfg(feature = "state-sync")]
+            receipt_db_size,
+            #[cfg(feature = "state-sync")]
+            snapshot: None,
+            #[cfg(feature = "backup")]
+            commit_gate: None,
+            #[cfg(feature = "state-pruning")]
+            state_retention_depth: None,
+            #[cfg(any(feature = "state-history", feature = "state-pruning"))]
+            next_state_root_sequence,
+            #[cfg(feature = "state-history")]
+            state_root_history_size: DEFAULT_STATE_ROOT_HISTORY_SIZE,
         })
     }
 
+    /// Sets the gate that commits must pass through, allowing them to be held off while a backup
+    /// is created.
+    #[cfg(feature = "backup")]
+    pub fn set_commit_gate(&mut self, commit_gate: CommitGate) {
+        #[cfg(feature = "batch-status-store")]
+        self.batch_history.set_commit_gate(commit_gate.clone());
+        self.commit_gate = Some(commit_gate);
+    }
+
+    /// Sets the number of most recent state roots to retain; older state roots are pruned after
+    /// each commit.
+    #[cfg(feature = "state-pruning")]
+    pub fn set_state_retention_depth(&mut self, retention_depth: usize) {
+        self.state_retention_depth = Some(retention_depth);
+    }
+
+    /// Sets the number of most recent state roots to keep in the state root history; older entries
+    /// are removed from the history after each commit. With state pruning, the history always
+    /// covers at least the retained state roots, so that they can still be pruned later.
+    #[cfg(feature = "state-history")]
+    pub fn set_state_root_history_size(&mut self, history_size: usize) {
+        self.state_root_history_size = history_size;
+    }
+
+    /// Prunes all but the `retention_depth` most recent state roots, removing the Merkle nodes that
+    /// are only referenced by the pruned roots. Returns the number of state roots that were pruned.
+    #[cfg(feature = "state-pruning")]
+    pub fn prune_state_roots(&self, retention_depth: usize) -> Result<usize, ScabbardStateError> {
+        prune_state_roots(&*self.db, retention_depth)
+    }
+
     fn read_current_state_root(db: &dyn Database) -> Result<Option<String>, ScabbardStateError> {
         db.get_reader()
             .and_then(|reader| reader.index_get(CURRENT_STATE_ROOT_INDEX, b"HEAD"))
@@ -173,7 +320,21 @@ impl ScabbardState {
             .map_err(|e| ScabbardStateError(format!("Unable to read HEAD entry: {}", e)))
     }
 
-    fn write_current_state_root(&self) -> Result<(), ScabbardStateError> {
+    /// Writes the current state root as the HEAD entry. The state root history entry and, if the
+    /// state root was produced by committing a batch, the batch's committed status are written in
+    /// the same transaction, so they can't diverge from HEAD.
+    #[cfg_attr(
+        not(any(
+            feature = "batch-status-store",
+            feature = "state-history",
+            feature = "state-pruning"
+        )),
+        allow(unused_variables)
+    )]
+    fn write_current_state_root(
+        &mut self,
+        batch_id: Option<&str>,
+    ) -> Result<(), ScabbardStateError> {
         let current_root_bytes = hex::parse_hex(&self.current_state_root).map_err(|e| {
             ScabbardStateError(format!(
                 "The in-memory current state root is invalid: {}",
@@ -181,6 +342,9 @@ impl ScabbardState {
             ))
         })?;
 
+        #[cfg(any(feature = "state-history", feature = "state-pruning"))]
+        let history_size = self.state_root_history_size();
+
         let mut writer = self.db.get_writer().map_err(|e| {
             ScabbardStateError(format!(
                 "Unable to start write transaction for HEAD entry: {}",
@@ -192,10 +356,29 @@ impl ScabbardState {
             .index_put(CURRENT_STATE_ROOT_INDEX, b"HEAD", &current_root_bytes)
             .map_err(|e| ScabbardStateError(format!("Unable to write HEAD entry: {}", e)))?;
 
+        #[cfg(any(feature = "state-history", feature = "state-pruning"))]
+        record_state_root(
+            &mut *writer,
+            self.next_state_root_sequence,
+            &self.current_state_root,
+            batch_id,
+            history_size,
+        )?;
+
+        #[cfg(feature = "batch-status-store")]
+        if let Some(batch_id) = batch_id {
+            self.batch_history.stage_commit(batch_id, &mut *writer)?;
+        }
+
         writer
             .commit()
             .map_err(|e| ScabbardStateError(format!("Unable to commit HEAD entry: {}", e)))?;
 
+        #[cfg(any(feature = "state-history", feature = "state-pruning"))]
+        {
+            self.next_state_root_sequence += 1;
+        }
+
         Ok(())
     }
 
@@ -204,10 +387,7 @@ impl ScabbardState {
         &self,
         address: &str,
     ) -> Result<Option<Vec<u8>>, ScabbardStateError> {
-        Ok(
-            MerkleRadixTree::new(self.db.clone(), Some(&self.current_state_root))?
-                .get_value(address)?,
-        )
+        self.get_state_at_address_at_root(address, &self.current_state_root)
     }
 
     /// Fetch a list of entries in state. If a `prefix` is provided, only return entries whose
@@ -216,9 +396,50 @@ impl ScabbardState {
     pub fn get_state_with_prefix(
         &self,
         prefix: Option<&str>,
+    ) -> Result<StateIter, ScabbardStateError> {
+        self.get_state_with_prefix_at_root(prefix, &self.current_state_root)
+    }
+
+    /// Determine whether the given state root exists in the state database, meaning state can be
+    /// read at that root. A state root no longer exists once it has been pruned.
+    #[cfg(feature = "state-history")]
+    pub fn has_state_root(&self, state_root: &str) -> Result<bool, ScabbardStateError> {
+        match MerkleRadixTree::new(self.db.clone(), Some(state_root)) {
+            Ok(_) => Ok(true),
+            Err(StateDatabaseError::NotFound(_)) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// List the state roots committed by this service, newest first. If a `limit` is provided,
+    /// at most that many state roots are returned.
+    #[cfg(feature = "state-history")]
+    pub fn list_state_roots(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<Vec<StateRootInfo>, ScabbardStateError> {
+        list_state_roots(&*self.db, limit)
+    }
+
+    /// Fetch the value at the given `address` in state as of the given `state_root`. Returns
+    /// `None` if the `address` was not set at that root.
+    pub fn get_state_at_address_at_root(
+        &self,
+        address: &str,
+        state_root: &str,
+    ) -> Result<Option<Vec<u8>>, ScabbardStateError> {
+        Ok(MerkleRadixTree::new(self.db.clone(), Some(state_root))?.get_value(address)?)
+    }
+
+    /// Fetch a list of entries in state as of the given `state_root`, optionally only those under
+    /// the given address `prefix`.
+    pub fn get_state_with_prefix_at_root(
+        &self,
+        prefix: Option<&str>,
+        state_root: &str,
     ) -> Result<StateIter, ScabbardStateError> {
         Ok(Box::new(
-            MerkleRadixTree::new(self.db.clone(), Some(&self.current_state_root))?
+            MerkleRadixTree::new(self.db.clone(), Some(state_root))?
                 .leaves(prefix)
                 .or_else(|err| match err {
                     StateDatabaseError::NotFound(_) => Ok(Box::new(std::iter::empty())),
@@ -285,13 +506,31 @@ impl ScabbardState {
     }
 
     pub fn commit(&mut self) -> Result<(), ScabbardStateError> {
+        #[cfg(feature = "backup")]
+        let commit_gate = self.commit_gate.clone();
+        #[cfg(feature = "backup")]
+        let _commit_guard = commit_gate.as_ref().map(CommitGate::enter);
+
         match self.pending_changes.take() {
             Some((signature, txn_receipts)) => {
                 let state_changes = receipts_into_transact_state_changes(&txn_receipts)?;
                 self.current_state_root = MerkleState::new(self.db.clone())
                     .commit(&self.current_state_root, &state_changes)?;
 
-                self.write_current_state_root()?;
+                self.write_current_state_root(Some(&signature))?;
+
+                #[cfg(feature = "state-pruning")]
+                {
+                    if let Some(retention_depth) = self.state_retention_depth {
+                        match self.prune_state_roots(retention_depth) {
+                            Ok(0) => (),
+                            Ok(pruned) => debug!("Pruned {} old state root(s)", pruned),
+                            // Pruning failures don't affect the commit itself, the roots will be
+                            // pruned after a later commit
+                            Err(err) => error!("Failed to prune old state roots: {}", err),
+                        }
+                    }
+                }
 
                 info!(
                     "committed {} change(s) for new state root {}",
@@ -299,40 +538,7 @@ impl ScabbardState {
                     self.current_state_root,
                 );
 
-                let events = txn_receipts
-                    .iter()
-                    .cloned()
-                    .map(StateChangeEvent::try_from)
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                self.transaction_receipt_store
-                    .write()
-                    .map_err(|err| {
-                        ScabbardStateError(format!(
-                            "transaction receipt store lock poisoned: {}",
-                            err
-                        ))
-                    })?
-                    .append(txn_receipts)
-                    .map_err(|err| {
-                        ScabbardStateError(format!(
-                            "failed to add transaction receipts to store: {}",
-                            err
-                        ))
-                    })?;
-
-                for event in events {
-                    self.event_subscribers.retain(|subscriber| {
-                        match subscriber.handle_event(event.clone()) {
-                            Ok(()) => true,
-                            Err(StateSubscriberError::Unsubscribe) => false,
-                            Err(err @ StateSubscriberError::UnableToHandleEvent(_)) => {
-                                error!("{}", err);
-                                true
-                            }
-                        }
-                    });
-                }
+                self.store_receipts(txn_receipts)?;
 
                 self.batch_history.commit(&signature);
                 counter!("splinter.scabbard.committed_batches", 1);
@@ -342,6 +548,377 @@ impl ScabbardState {
         }
     }
 
+    /// Adds the receipts of committed transactions to the receipt store and notifies the event
+    /// subscribers of the state changes they contain.
+    fn store_receipts(
+        &mut self,
+        txn_receipts: Vec<TransactionReceipt>,
+    ) -> Result<(), ScabbardStateError> {
+        let events = txn_receipts
+            .iter()
+            .cloned()
+            .map(StateChangeEvent::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.transaction_receipt_store
+            .write()
+            .map_err(|err| {
+                ScabbardStateError(format!("transaction receipt store lock poisoned: {}", err))
+            })?
+            .append(txn_receipts)
+            .map_err(|err| {
+                ScabbardStateError(format!(
+                    "failed to add transaction receipts to store: {}",
+                    err
+                ))
+            })?;
+
+        for event in events {
+            self.event_subscribers.retain(|subscriber| {
+                match subscriber.handle_event(event.clone()) {
+                    Ok(()) => true,
+                    Err(StateSubscriberError::Unsubscribe) => false,
+                    Err(err @ StateSubscriberError::UnableToHandleEvent(_)) => {
+                        error!("{}", err);
+                        true
+                    }
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Get the ID of the last transaction receipt that was committed, or `None` if no
+    /// transactions have been committed.
+    #[cfg(feature = "state-sync")]
+    pub fn last_receipt_id(&self) -> Result<Option<String>, ScabbardStateError> {
+        let transaction_receipt_store = self.transaction_receipt_store.read().map_err(|err| {
+            ScabbardStateError(format!("transaction receipt store lock poisoned: {}", err))
+        })?;
+
+        let count = transaction_receipt_store.count().map_err(|err| {
+            ScabbardStateError(format!("failed to count transaction receipts: {}", err))
+        })?;
+        if count == 0 {
+            return Ok(None);
+        }
+
+        Ok(transaction_receipt_store
+            .get_by_index(count - 1)
+            .map_err(|err| {
+                ScabbardStateError(format!("failed to get last transaction receipt: {}", err))
+            })?
+            .map(|receipt| receipt.transaction_id))
+    }
+
+    /// Get the transaction receipts that were committed after the receipt with the given ID, or
+    /// all receipts if no ID is given. Returns `None` if the receipt with the given ID was never
+    /// committed by this service.
+    #[cfg(feature = "state-sync")]
+    pub fn receipts_since(
+        &self,
+        receipt_id: Option<&str>,
+    ) -> Result<Option<Vec<TransactionReceipt>>, ScabbardStateError> {
+        let transaction_receipt_store = self.transaction_receipt_store.read().map_err(|err| {
+            ScabbardStateError(format!("transaction receipt store lock poisoned: {}", err))
+        })?;
+
+        let receipts = match receipt_id {
+            Some(id) => {
+                let known = transaction_receipt_store
+                    .get_by_id(id.into())
+                    .map_err(|err| {
+                        ScabbardStateError(format!("failed to get transaction receipt: {}", err))
+                    })?
+                    .is_some();
+                if !known {
+                    return Ok(None);
+                }
+                transaction_receipt_store.iter_since_id(id.into())
+            }
+            None => transaction_receipt_store.iter(),
+        }
+        .map_err(|err| {
+            ScabbardStateError(format!(
+                "failed to get transaction receipts from store: {}",
+                err
+            ))
+        })?
+        .collect();
+
+        Ok(Some(receipts))
+    }
+
+    /// Get the receipt of the committed transaction with the given ID, or `None` if no such
+    /// transaction was committed by this service.
+    #[cfg(feature = "receipts")]
+    pub fn get_receipt(
+        &self,
+        transaction_id: &str,
+    ) -> Result<Option<TransactionReceipt>, ScabbardStateError> {
+        self.transaction_receipt_store
+            .read()
+            .map_err(|err| {
+                ScabbardStateError(format!("transaction receipt store lock poisoned: {}", err))
+            })?
+            .get_by_id(transaction_id.into())
+            .map_err(|err| {
+                ScabbardStateError(format!("failed to get transaction receipt: {}", err))
+            })
+    }
+
+    /// Get the store that the receipts of committed transactions are kept in, so they can be read
+    /// without holding on to the state.
+    #[cfg(feature = "receipts")]
+    pub fn transaction_receipt_store(&self) -> Arc<RwLock<TransactionReceiptStore>> {
+        self.transaction_receipt_store.clone()
+    }
+
+    /// Replay the transaction receipts committed by a peer on top of the current state. The
+    /// current state root is only moved if the result matches the peer's `expected_state_root`;
+    /// the receipts are then stored and their events are sent to the subscribers, as if the
+    /// transactions had been committed locally.
+    #[cfg(feature = "state-sync")]
+    pub fn apply_synced_receipts(
+        &mut self,
+        txn_receipts: Vec<TransactionReceipt>,
+        expected_state_root: &str,
+    ) -> Result<(), ScabbardStateError> {
+        #[cfg(feature = "backup")]
+        let commit_gate = self.commit_gate.clone();
+        #[cfg(feature = "backup")]
+        let _commit_guard = commit_gate.as_ref().map(CommitGate::enter);
+
+        let merkle_state = MerkleState::new(self.db.clone());
+        let mut state_root = self.current_state_root.clone();
+        for receipt in &txn_receipts {
+            state_root = merkle_state.commit(
+                &state_root,
+                &receipts_into_transact_state_changes(std::slice::from_ref(receipt))?,
+            )?;
+        }
+
+        if state_root != expected_state_root {
+            return Err(ScabbardStateError(format!(
+                "replaying {} receipt(s) resulted in state root {}, expected {}",
+                txn_receipts.len(),
+                state_root,
+                expected_state_root
+            )));
+        }
+
+        self.set_synced_state_root(state_root)?;
+        self.store_receipts(txn_receipts)
+    }
+
+    /// Start staging a snapshot of a peer's state, discarding the snapshot that was being staged,
+    /// if any. The snapshot is built in a database of its own, so that nothing a peer sends is
+    /// written to the service's state until the snapshot's state root has been verified.
+    #[cfg(feature = "state-sync")]
+    pub fn start_snapshot(&mut self) -> Result<(), ScabbardStateError> {
+        self.discard_snapshot()?;
+
+        let db: Box<dyn Database> = Box::new(open_state_db(
+            &self.state_db_file.with_extension("lmdb-snapshot"),
+            self.state_db_size,
+        )?);
+        let state_root = MerkleRadixTree::new(db.clone(), None)?.get_merkle_root();
+        self.snapshot = Some(StagedSnapshot { db, state_root });
+
+        Ok(())
+    }
+
+    /// Commit a chunk of the staged snapshot on top of the chunks received so far, and return the
+    /// resulting state root. The current state root is not moved.
+    #[cfg(feature = "state-sync")]
+    pub fn apply_snapshot_chunk(
+        &mut self,
+        entries: Vec<(String, Vec<u8>)>,
+    ) -> Result<String, ScabbardStateError> {
+        let snapshot = self
+            .snapshot
+            .as_mut()
+            .ok_or_else(|| ScabbardStateError("no snapshot is being staged".into()))?;
+        let state_changes = entries
+            .into_iter()
+            .map(|(key, value)| TransactStateChange::Set { key, value })
+            .collect::<Vec<_>>();
+
+        snapshot.state_root =
+            MerkleState::new(snapshot.db.clone()).commit(&snapshot.state_root, &state_changes)?;
+        Ok(snapshot.state_root.clone())
+    }
+
+    /// Discard the snapshot that is being staged, if any, and remove its database.
+    #[cfg(feature = "state-sync")]
+    pub fn discard_snapshot(&mut self) -> Result<(), ScabbardStateError> {
+        // The database must be closed before its files are removed
+        self.snapshot = None;
+        remove_snapshot_db_files(&self.state_db_file)
+    }
+
+    /// Replace the current state with the staged snapshot, provided that it resulted in the given
+    /// `state_root`. The snapshot's entries are only then copied into the service's state, and the
+    /// staged snapshot is discarded either way. The receipt store is emptied, since the receipts
+    /// committed so far don't lead to the snapshot's state root and can't be replayed by, or
+    /// served to, peers.
+    #[cfg(feature = "state-sync")]
+    pub fn apply_state_snapshot(&mut self, state_root: &str) -> Result<(), ScabbardStateError> {
+        #[cfg(feature = "backup")]
+        let commit_gate = self.commit_gate.clone();
+        #[cfg(feature = "backup")]
+        let _commit_guard = commit_gate.as_ref().map(CommitGate::enter);
+
+        let snapshot = self
+            .snapshot
+            .take()
+            .ok_or_else(|| ScabbardStateError("no snapshot is being staged".into()))?;
+        let result = self.merge_snapshot(&snapshot, state_root);
+        drop(snapshot);
+        remove_snapshot_db_files(&self.state_db_file)?;
+        result?;
+
+        self.set_synced_state_root(state_root.to_string())?;
+        self.reset_receipt_store()
+    }
+
+    /// Copy the entries of a staged snapshot into the service's state, if the snapshot resulted
+    /// in the given `state_root`.
+    #[cfg(feature = "state-sync")]
+    fn merge_snapshot(
+        &self,
+        snapshot: &StagedSnapshot,
+        state_root: &str,
+    ) -> Result<(), ScabbardStateError> {
+        if snapshot.state_root != state_root {
+            return Err(ScabbardStateError(format!(
+                "snapshot resulted in state root {}, expected {}",
+                snapshot.state_root, state_root
+            )));
+        }
+
+        let mut entries = MerkleRadixTree::new(snapshot.db.clone(), Some(state_root))?
+            .leaves(None)
+            .or_else(|err| match err {
+                StateDatabaseError::NotFound(_) => Ok(Box::new(std::iter::empty())),
+                err => Err(err),
+            })?;
+        let merkle_state = MerkleState::new(self.db.clone());
+        let mut merged_state_root = MerkleRadixTree::new(self.db.clone(), None)?.get_merkle_root();
+        loop {
+            let state_changes = entries
+                .by_ref()
+                .take(SNAPSHOT_MERGE_BATCH_SIZE)
+                .map(|entry| entry.map(|(key, value)| TransactStateChange::Set { key, value }))
+                .collect::<Result<Vec<_>, _>>()?;
+            if state_changes.is_empty() {
+                break;
+            }
+            merged_state_root = merkle_state.commit(&merged_state_root, &state_changes)?;
+        }
+
+        if merged_state_root != state_root {
+            return Err(ScabbardStateError(format!(
+                "merging snapshot resulted in state root {}, expected {}",
+                merged_state_root, state_root
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Empty the receipt store. An LMDB store can't be emptied in place, so an empty store is
+    /// created alongside it and moved into its place, and the store is then reopened. The store
+    /// keeps its current contents if any of this fails, since the open store is only replaced
+    /// once the empty one has been opened at the store's path.
+    #[cfg(feature = "state-sync")]
+    fn reset_receipt_store(&mut self) -> Result<(), ScabbardStateError> {
+        let receipt_db_path = self.receipt_db_file.with_extension("lmdb");
+        let receipt_db_lock_file_path = self.receipt_db_file.with_extension("lmdb-lock");
+        let empty_db_path = self.receipt_db_file.with_extension("lmdb-reset");
+        let empty_db_lock_file_path = self.receipt_db_file.with_extension("lmdb-reset-lock");
+
+        let mut transaction_receipt_store =
+            self.transaction_receipt_store.write().map_err(|err| {
+                ScabbardStateError(format!("transaction receipt store lock poisoned: {}", err))
+            })?;
+
+        // Create the files of an empty store; it's closed again right away
+        for path in &[&empty_db_path, &empty_db_lock_file_path] {
+            if path.exists() {
+                fs::remove_file(path).map_err(|err| {
+                    ScabbardStateError(format!("Unable to remove {}: {}", path.display(), err))
+                })?;
+            }
+        }
+        open_receipt_store(&empty_db_path, self.receipt_db_size)?;
+        fs::remove_file(&empty_db_lock_file_path).map_err(|err| {
+            ScabbardStateError(format!("Unable to remove receipt store LMDB file: {}", err))
+        })?;
+
+        // The open store keeps using the files it has open after they are replaced, and a new lock
+        // file is created when the empty store is opened
+        fs::remove_file(&receipt_db_lock_file_path).map_err(|err| {
+            ScabbardStateError(format!("Unable to remove receipt store LMDB file: {}", err))
+        })?;
+        fs::rename(&empty_db_path, &receipt_db_path).map_err(|err| {
+            ScabbardStateError(format!(
+                "Unable to replace receipt store LMDB file: {}",
+                err
+            ))
+        })?;
+
+        *transaction_receipt_store = open_receipt_store(&receipt_db_path, self.receipt_db_size)?;
+
+        Ok(())
+    }
+
+    /// Get a view of the state at the current state root, which can be read without holding on to
+    /// this `ScabbardState`.
+    #[cfg(feature = "state-sync")]
+    pub fn view(&self) -> StateView {
+        StateView {
+            db: self.db.clone(),
+            state_root: self.current_state_root.clone(),
+        }
+    }
+
+    /// Move the current state root to one that was synchronized from peers. The caller must hold
+    /// the commit gate, if one is set.
+    #[cfg(feature = "state-sync")]
+    fn set_synced_state_root(&mut self, state_root: String) -> Result<(), ScabbardStateError> {
+        // Any pending change was computed against the old state root, so it can't be committed
+        if self.pending_changes.take().is_some() {
+            warn!("Discarding pending change after synchronizing state");
+        }
+
+        info!(
+            "synchronized state from {} to {}",
+            self.current_state_root, state_root
+        );
+        self.current_state_root = state_root;
+        self.write_current_state_root(None)
+    }
+
+    /// Returns the number of most recent state roots to keep in the state root history. When state
+    /// pruning is enabled, the history also covers the retained state roots and the one that
+    /// expires with the next commit, so that it is still in the history when it gets pruned.
+    #[cfg(any(feature = "state-history", feature = "state-pruning"))]
+    fn state_root_history_size(&self) -> usize {
+        #[cfg(feature = "state-history")]
+        #[allow(unused_mut)]
+        let mut history_size = self.state_root_history_size;
+        #[cfg(not(feature = "state-history"))]
+        #[allow(unused_mut)]
+        let mut history_size = 1;
+        #[cfg(feature = "state-pruning")]
+        if let Some(retention_depth) = self.state_retention_depth {
+            history_size = history_size.max(retention_depth + 1);
+        }
+        history_size
+    }
+
     pub fn rollback(&mut self) -> Result<(), ScabbardStateError> {
         match self.pending_changes.take() {
             Some((_, txn_receipts)) => info!(
@@ -394,48 +971,522 @@ impl ScabbardState {
     }
 }
 
-fn receipts_into_transact_state_changes(
-    receipts: &[TransactionReceipt],
-) -> Result<Vec<TransactStateChange>, ScabbardStateError> {
-    Ok(receipts
-        .iter()
-        .cloned()
-        .map(Vec::<TransactStateChange>::try_from)
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|err| ScabbardStateError(err.to_string()))?
-        .into_iter()
-        .flatten()
-        .collect())
-}
+/// Get a page of the transaction receipts in the given store that were committed after the
+/// receipt with the given ID, or of all receipts if no ID is given, along with the total number of
+/// receipts the page was taken from. When an ID is given, the total only counts up to one receipt
+/// past the page, so it is exact on the last page. Returns `None` if the receipt with the given ID
+/// was never committed.
+#[cfg(feature = "receipts")]
+pub(super) fn list_receipts(
+    transaction_receipt_store: &RwLock<TransactionReceiptStore>,
+    since: Option<&str>,
+    offset: usize,
+    limit: usize,
+) -> Result<Option<(Vec<TransactionReceipt>, usize)>, ScabbardStateError> {
+    let transaction_receipt_store = transaction_receipt_store.read().map_err(|err| {
+        ScabbardStateError(format!("transaction receipt store lock poisoned: {}", err))
+    })?;
+
+    match since {
+        Some(id) => {
+            let known = transaction_receipt_store
+                .get_by_id(id.into())
+                .map_err(|err| {
+                    ScabbardStateError(format!("failed to get transaction receipt: {}", err))
+                })?
+                .is_some();
+            if !known {
+                return Ok(None);
+            }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct StateChangeEvent {
-    pub id: String,
-    pub state_changes: Vec<StateChange>,
+            // The store can't count the receipts after a given ID without walking the whole
+            // range, so only one receipt past the page is read to tell whether there are more
+            let mut receipts = transaction_receipt_store
+                .iter_since_id(id.into())
+                .map_err(|err| {
+                    ScabbardStateError(format!(
+                        "failed to get transaction receipts from store: {}",
+                        err
+                    ))
+                })?
+                .skip(offset)
+                .take(limit.saturating_add(1))
+                .collect::<Vec<_>>();
+            let total = offset + receipts.len();
+            receipts.truncate(limit);
+
+            Ok(Some((receipts, total)))
+        }
+        None => {
+            let total = transaction_receipt_store.count().map_err(|err| {
+                ScabbardStateError(format!("failed to count transaction receipts: {}", err))
+            })? as usize;
+            let receipts = transaction_receipt_store
+                .iter()
+                .map_err(|err| {
+                    ScabbardStateError(format!(
+                        "failed to get transaction receipts from store: {}",
+                        err
+                    ))
+                })?
+                .skip(offset)
+                .take(limit)
+                .collect();
+
+            Ok(Some((receipts, total)))
+        }
+    }
 }
 
-#[cfg(feature = "events")]
-impl ParseBytes<StateChangeEvent> for StateChangeEvent {
-    fn from_bytes(bytes: &[u8]) -> Result<StateChangeEvent, ParseError> {
-        serde_json::from_slice(bytes)
-            .map_err(Box::new)
-            .map_err(|err| ParseError::MalformedMessage(err))
+/// Removes the database that a snapshot of a peer's state is staged in, if it exists.
+#[cfg(feature = "state-sync")]
+fn remove_snapshot_db_files(state_db_file: &Path) -> Result<(), ScabbardStateError> {
+    let snapshot_db_path = state_db_file.with_extension("lmdb-snapshot");
+    let snapshot_db_lock_file_path = state_db_file.with_extension("lmdb-snapshot-lock");
+    for path in &[&snapshot_db_path, &snapshot_db_lock_file_path] {
+        if path.exists() {
+            fs::remove_file(path).map_err(|err| {
+                ScabbardStateError(format!("Unable to remove {}: {}", path.display(), err))
+            })?;
+        }
     }
+
+    Ok(())
 }
 
-impl TryFrom<TransactionReceipt> for StateChangeEvent {
-    type Error = ScabbardStateError;
+/// Opens the LMDB store that a scabbard service's transaction receipts are kept in.
+fn open_receipt_store(
+    receipt_db_path: &Path,
+    receipt_db_size: usize,
+) -> Result<TransactionReceiptStore, ScabbardStateError> {
+    Ok(TransactionReceiptStore::new(Box::new(
+        LmdbOrderedStore::new(receipt_db_path, Some(receipt_db_size))
+            .map_err(|err| ScabbardStateError(err.to_string()))?,
+    )))
+}
 
-    fn try_from(receipt: TransactionReceipt) -> Result<Self, Self::Error> {
-        let TransactionReceipt {
-            transaction_id,
-            transaction_result,
-        } = receipt;
+/// Opens the LMDB database that a scabbard service's state is stored in.
+fn open_state_db(
+    state_db_path: &Path,
+    state_db_size: usize,
+) -> Result<LmdbDatabase, ScabbardStateError> {
+    let indexes = state_db_indexes();
+    Ok(LmdbDatabase::new(
+        LmdbContext::new(state_db_path, indexes.len(), Some(state_db_size))?,
+        &indexes,
+    )?)
+}
 
-        match transaction_result {
-            TransactionResult::Valid { state_changes, .. } => {
-                Ok(StateChangeEvent {
-                    id: transaction_id,
+fn state_db_indexes() -> Vec<&'static str> {
+    let mut indexes = INDEXES.to_vec();
+    indexes.push(CURRENT_STATE_ROOT_INDEX);
+    #[cfg(any(feature = "state-history", feature = "state-pruning"))]
+    indexes.push(STATE_ROOT_HISTORY_INDEX);
+    #[cfg(feature = "batch-status-store")]
+    indexes.extend_from_slice(&[BATCH_STATUS_INDEX, BATCH_STATUS_ORDER_INDEX]);
+    indexes
+}
+
+/// Appends the given state root, and the ID of the batch that produced it, to the history of
+/// committed state roots under the given sequence number, and removes the entries that fall
+/// outside of the `history_size` most recent ones. Each root is keyed by its big-endian sequence
+/// number, so the history index is ordered from oldest to newest. The changes are made in the
+/// given transaction, which the caller commits.
+#[cfg(any(feature = "state-history", feature = "state-pruning"))]
+fn record_state_root(
+    writer: &mut dyn DatabaseWriter,
+    sequence: u64,
+    state_root: &str,
+    batch_id: Option<&str>,
+    history_size: usize,
+) -> Result<(), ScabbardStateError> {
+    let record = serde_json::to_vec(&StateRootInfo {
+        state_root: state_root.into(),
+        batch_id: batch_id.map(String::from),
+    })
+    .map_err(|err| ScabbardStateError(format!("Unable to serialize state root: {}", err)))?;
+
+    writer.index_put(STATE_ROOT_HISTORY_INDEX, &sequence.to_be_bytes(), &record)?;
+
+    // The sequence numbers are consecutive, so the expired entries are removed from the newest
+    // one down, until the start of the history is reached
+    let mut expired = sequence.checked_sub(history_size as u64);
+    while let Some(expired_sequence) = expired {
+        let key = expired_sequence.to_be_bytes();
+        if writer
+            .as_reader()
+            .index_get(STATE_ROOT_HISTORY_INDEX, &key)?
+            .is_none()
+        {
+            break;
+        }
+        writer.index_delete(STATE_ROOT_HISTORY_INDEX, &key)?;
+        expired = expired_sequence.checked_sub(1);
+    }
+
+    Ok(())
+}
+
+/// Returns the sequence number that follows the last entry of the given index, whose keys are
+/// big-endian sequence numbers, or 0 if the index is empty.
+#[cfg(any(
+    feature = "batch-status-store",
+    feature = "state-history",
+    feature = "state-pruning"
+))]
+fn next_sequence(db: &dyn Database, index: &str) -> Result<u64, ScabbardStateError> {
+    let reader = db.get_reader()?;
+    let last = reader.index_cursor(index)?.seek_last();
+    match last {
+        Some((key, _)) => Ok(parse_sequence(&key)? + 1),
+        None => Ok(0),
+    }
+}
+
+#[cfg(any(
+    feature = "batch-status-store",
+    feature = "state-history",
+    feature = "state-pruning"
+))]
+fn parse_sequence(key: &[u8]) -> Result<u64, ScabbardStateError> {
+    let mut bytes = [0; 8];
+    if key.len() != bytes.len() {
+        return Err(ScabbardStateError(format!(
+            "Invalid sequence key: {}",
+            hex::to_hex(key)
+        )));
+    }
+    bytes.copy_from_slice(key);
+    Ok(u64::from_be_bytes(bytes))
+}
+
+#[cfg(any(feature = "state-history", feature = "state-pruning"))]
+fn parse_state_root_info(record: &[u8]) -> Result<StateRootInfo, ScabbardStateError> {
+    serde_json::from_slice(record)
+        .map_err(|err| ScabbardStateError(format!("Invalid state root history entry: {}", err)))
+}
+
+/// Lists the state roots in the state root history, newest first. The history is walked backwards
+/// from its last entry, so only the requested entries are read.
+#[cfg(feature = "state-history")]
+fn list_state_roots(
+    db: &dyn Database,
+    limit: Option<usize>,
+) -> Result<Vec<StateRootInfo>, ScabbardStateError> {
+    let limit = limit.unwrap_or(std::usize::MAX);
+    let reader = db.get_reader()?;
+    let last = reader.index_cursor(STATE_ROOT_HISTORY_INDEX)?.seek_last();
+    let mut sequence = match last {
+        Some((key, _)) => Some(parse_sequence(&key)?),
+        None => None,
+    };
+
+    let mut state_roots = vec![];
+    while let Some(current) = sequence {
+        if state_roots.len() >= limit {
+            break;
+        }
+        match reader.index_get(STATE_ROOT_HISTORY_INDEX, &current.to_be_bytes())? {
+            Some(record) => state_roots.push(parse_state_root_info(&record)?),
+            // The sequence numbers are consecutive, so this is the start of the history
+            None => break,
+        }
+        sequence = current.checked_sub(1);
+    }
+
+    Ok(state_roots)
+}
+
+/// Prunes all but the `retention_depth` most recent state roots in the state root history, oldest
+/// first. Pruning a root removes the nodes that its successor replaced, so the remaining roots are
+/// unaffected. Roots that were committed before the history was recorded are never pruned.
+///
+/// Pruned roots are removed from the history, so the expired roots are found by walking back from
+/// the newest expired sequence number until the start of the history; after each commit, that is
+/// a single root.
+#[cfg(feature = "state-pruning")]
+fn prune_state_roots(
+    db: &dyn Database,
+    retention_depth: usize,
+) -> Result<usize, ScabbardStateError> {
+    if retention_depth == 0 {
+        return Err(ScabbardStateError(
+            "State retention depth must be at least 1, to retain the current state root".into(),
+        ));
+    }
+
+    let mut expired = vec![];
+    {
+        let reader = db.get_reader()?;
+        let last = reader.index_cursor(STATE_ROOT_HISTORY_INDEX)?.seek_last();
+        let mut sequence = match last {
+            Some((key, _)) => parse_sequence(&key)?.checked_sub(retention_depth as u64),
+            None => None,
+        };
+        while let Some(current) = sequence {
+            match reader.index_get(STATE_ROOT_HISTORY_INDEX, &current.to_be_bytes())? {
+                Some(record) => expired.push((current, parse_state_root_info(&record)?.state_root)),
+                None => break,
+            }
+            sequence = current.checked_sub(1);
+        }
+    }
+
+    let merkle_state = MerkleState::new(db.clone_box());
+    for (sequence, state_root) in expired.iter().rev() {
+        merkle_state
+            .prune(vec![state_root.clone()])
+            .map_err(|err| ScabbardStateError(format!("Unable to prune state root: {}", err)))?;
+
+        let mut writer = db.get_writer()?;
+        writer.index_delete(STATE_ROOT_HISTORY_INDEX, &sequence.to_be_bytes())?;
+        writer.commit()?;
+    }
+
+    Ok(expired.len())
+}
+
+/// A state root that was committed by a scabbard service.
+#[cfg(any(feature = "state-history", feature = "state-pruning"))]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateRootInfo {
+    /// The state root hash
+    pub state_root: String,
+    /// The ID of the batch whose commit produced the state root; `None` if the state root was
+    /// adopted from a peer by a state sync
+    pub batch_id: Option<String>,
+}
+
+/// The result of pruning a scabbard service's state database with [`prune_state_db`].
+#[cfg(feature = "state-pruning")]
+#[derive(Debug, Default)]
+pub struct StatePruneSummary {
+    /// The number of state roots that were pruned
+    pub pruned_state_roots: usize,
+    /// The size of the state database file, in bytes, before and after it was compacted; `None`
+    /// if compaction was not requested
+    pub compacted_size: Option<(u64, u64)>,
+}
+
+/// Prunes the state database of a scabbard service that is not running, keeping the
+/// `retention_depth` most recent state roots, and optionally compacts the database file.
+#[cfg(feature = "state-pruning")]
+pub(super) fn prune_state_db(
+    state_db_file: &Path,
+    state_db_size: usize,
+    retention_depth: usize,
+    compact: bool,
+) -> Result<StatePruneSummary, ScabbardStateError> {
+    let state_db_path = state_db_file.with_extension("lmdb");
+    if !state_db_path.exists() {
+        return Err(ScabbardStateError(format!(
+            "State database {} does not exist",
+            state_db_path.display()
+        )));
+    }
+    ensure_state_db_closed(state_db_file)?;
+
+    let pruned_state_roots = {
+        let db = open_state_db(&state_db_path, state_db_size)?;
+        prune_state_roots(&db, retention_depth)?
+    };
+
+    let compacted_size = if compact {
+        Some(compact_state_db(state_db_file, state_db_size)?)
+    } else {
+        None
+    };
+
+    Ok(StatePruneSummary {
+        pruned_state_roots,
+        compacted_size,
+    })
+}
+
+/// Compacts the state database of a scabbard service that is not running. LMDB never shrinks its
+/// files, so the entries are copied to a new database file, which then replaces the original.
+/// Returns the size of the file, in bytes, before and after compaction.
+#[cfg(feature = "state-pruning")]
+pub(super) fn compact_state_db(
+    state_db_file: &Path,
+    state_db_size: usize,
+) -> Result<(u64, u64), ScabbardStateError> {
+    let state_db_path = state_db_file.with_extension("lmdb");
+    let compact_db_path = state_db_file.with_extension("lmdb-compact");
+    let compact_db_lock_file_path = state_db_file.with_extension("lmdb-compact-lock");
+
+    // Remove the remains of an interrupted compaction, if any
+    for path in &[&compact_db_path, &compact_db_lock_file_path] {
+        if path.exists() {
+            fs::remove_file(path).map_err(|err| {
+                ScabbardStateError(format!("Unable to remove {}: {}", path.display(), err))
+            })?;
+        }
+    }
+
+    ensure_state_db_closed(state_db_file)?;
+
+    let original_size = file_size(&state_db_path)?;
+
+    {
+        let db = open_state_db(&state_db_path, state_db_size)?;
+        let compact_db = open_state_db(&compact_db_path, state_db_size)?;
+        let reader = db.get_reader()?;
+
+        copy_entries(reader.cursor()?, &compact_db, None)?;
+        for index in state_db_indexes() {
+            copy_entries(reader.index_cursor(index)?, &compact_db, Some(index))?;
+        }
+    }
+
+    fs::rename(&compact_db_path, &state_db_path).map_err(|err| {
+        ScabbardStateError(format!(
+            "Unable to replace state database with compacted database: {}",
+            err
+        ))
+    })?;
+    fs::remove_file(&compact_db_lock_file_path).map_err(|err| {
+        ScabbardStateError(format!(
+            "Unable to remove compacted database lock file: {}",
+            err
+        ))
+    })?;
+
+    Ok((original_size, file_size(&state_db_path)?))
+}
+
+/// Returns an error if the state database is open, for instance because the service is running.
+/// Every process that opens an LMDB environment holds a shared lock on its lock file, and an
+/// open file description lock conflicts with it even when it's held by this process.
+///
+/// The lock file is closed afterwards, which drops this process's locks on it, so this must only
+/// be called before the database is opened by this process.
+#[cfg(feature = "state-pruning")]
+fn ensure_state_db_closed(state_db_file: &Path) -> Result<(), ScabbardStateError> {
+    use std::os::unix::io::AsRawFd;
+
+    #[cfg(target_os = "linux")]
+    const GET_LOCK: libc::c_int = libc::F_OFD_GETLK;
+    #[cfg(not(target_os = "linux"))]
+    const GET_LOCK: libc::c_int = libc::F_GETLK;
+
+    let lock_file_path = state_db_file.with_extension("lmdb-lock");
+    let lock_file = match fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&lock_file_path)
+    {
+        Ok(lock_file) => lock_file,
+        // Without a lock file, the database has never been opened
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(ScabbardStateError(format!(
+                "Unable to open {}: {}",
+                lock_file_path.display(),
+                err
+            )))
+        }
+    };
+
+    // Safe because the lock description is fully initialized and outlives the call
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = libc::F_WRLCK as libc::c_short;
+    lock.l_whence = libc::SEEK_SET as libc::c_short;
+    let result = unsafe { libc::fcntl(lock_file.as_raw_fd(), GET_LOCK, &mut lock) };
+    if result == -1 {
+        return Err(ScabbardStateError(format!(
+            "Unable to check lock on {}: {}",
+            lock_file_path.display(),
+            std::io::Error::last_os_error()
+        )));
+    }
+
+    if lock.l_type != libc::F_UNLCK as libc::c_short {
+        return Err(ScabbardStateError(format!(
+            "State database {} is in use; stop the service before pruning or compacting it",
+            state_db_file.with_extension("lmdb").display()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Writes the given entries to the database, or to the given index of the database, committing
+/// them in batches to keep write transactions small.
+#[cfg(feature = "state-pruning")]
+fn copy_entries(
+    entries: impl Iterator<Item = (Vec<u8>, Vec<u8>)>,
+    db: &dyn Database,
+    index: Option<&str>,
+) -> Result<(), ScabbardStateError> {
+    let mut writer = db.get_writer()?;
+    for (count, (key, value)) in entries.enumerate() {
+        match index {
+            Some(index) => writer.index_put(index, &key, &value)?,
+            None => writer.put(&key, &value)?,
+        }
+
+        if (count + 1) % COMPACTION_BATCH_SIZE == 0 {
+            writer.commit()?;
+            writer = db.get_writer()?;
+        }
+    }
+    writer.commit()?;
+
+    Ok(())
+}
+
+#[cfg(feature = "state-pruning")]
+fn file_size(path: &Path) -> Result<u64, ScabbardStateError> {
+    fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .map_err(|err| {
+            ScabbardStateError(format!("Unable to get size of {}: {}", path.display(), err))
+        })
+}
+
+fn receipts_into_transact_state_changes(
+    receipts: &[TransactionReceipt],
+) -> Result<Vec<TransactStateChange>, ScabbardStateError> {
+    Ok(receipts
+        .iter()
+        .cloned()
+        .map(Vec::<TransactStateChange>::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| ScabbardStateError(err.to_string()))?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateChangeEvent {
+    pub id: String,
+    pub state_changes: Vec<StateChange>,
+}
+
+#[cfg(feature = "events")]
+impl ParseBytes<StateChangeEvent> for StateChangeEvent {
+    fn from_bytes(bytes: &[u8]) -> Result<StateChangeEvent, ParseError> {
+        serde_json::from_slice(bytes)
+            .map_err(Box::new)
+            .map_err(|err| ParseError::MalformedMessage(err))
+    }
+}
+
+impl TryFrom<TransactionReceipt> for StateChangeEvent {
+    type Error = ScabbardStateError;
+
+    fn try_from(receipt: TransactionReceipt) -> Result<Self, Self::Error> {
+        let TransactionReceipt {
+            transaction_id,
+            transaction_result,
+        } = receipt;
+
+        match transaction_result {
+            TransactionResult::Valid { state_changes, .. } => {
+                Ok(StateChangeEvent {
+                    id: transaction_id,
                     state_changes: state_changes.into_iter().map(StateChange::from).collect(),
                 })
             }
@@ -651,7 +1702,13 @@ impl BatchInfo {
 pub struct BatchHistory {
     history: HashMap<String, BatchInfo>,
     limit: usize,
+    #[cfg(feature = "state-pruning")]
+    max_age: Option<Duration>,
     batch_subscribers: Vec<(HashSet<String>, Sender<BatchInfo>)>,
+    #[cfg(feature = "batch-status-store")]
+    store: Option<BatchStatusStore>,
+    #[cfg(all(feature = "backup", feature = "batch-status-store"))]
+    commit_gate: Option<CommitGate>,
 }
 
 impl BatchHistory {
@@ -659,6 +1716,42 @@ impl BatchHistory {
         Self::default()
     }
 
+    /// Sets the maximum number of batches to keep in the history; the oldest batches are evicted
+    /// first.
+    #[cfg(feature = "state-pruning")]
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
+    /// Sets the maximum amount of time a batch is kept in the history after it was submitted.
+    #[cfg(feature = "state-pruning")]
+    pub fn set_max_age(&mut self, max_age: Duration) {
+        self.max_age = Some(max_age);
+    }
+
+    /// Sets the store that the statuses of completed batches are persisted to, so they outlive
+    /// the in-memory history.
+    #[cfg(feature = "batch-status-store")]
+    fn set_store(&mut self, store: BatchStatusStore) {
+        self.store = Some(store);
+    }
+
+    /// Sets the gate that writes of batch statuses outside of a commit must pass through.
+    #[cfg(all(feature = "backup", feature = "batch-status-store"))]
+    fn set_commit_gate(&mut self, commit_gate: CommitGate) {
+        self.commit_gate = Some(commit_gate);
+    }
+
+    /// Sets the maximum number of completed batches, and optionally the maximum amount of time
+    /// after completion, that batch statuses are persisted for.
+    #[cfg(feature = "batch-status-store")]
+    pub fn set_store_retention(&mut self, limit: usize, max_age: Option<Duration>) {
+        if let Some(store) = self.store.as_mut() {
+            store.limit = limit;
+            store.max_age = max_age;
+        }
+    }
+
     pub fn add_batch(&mut self, signature: &str) {
         self.upsert_batch(signature.into(), BatchStatus::Pending);
     }
@@ -668,6 +1761,10 @@ impl BatchHistory {
 
         match batch_info.status {
             BatchStatus::Invalid(_) | BatchStatus::Valid(_) => {
+                #[cfg(feature = "batch-status-store")]
+                if let BatchStatus::Invalid(_) = batch_info.status {
+                    self.persist(&batch_info);
+                }
                 self.send_completed_batch_info_to_subscribers(batch_info)
             }
             _ => {}
@@ -679,6 +1776,10 @@ impl BatchHistory {
             Some(info) => match info.status.clone() {
                 BatchStatus::Valid(txns) => {
                     info.set_status(BatchStatus::Committed(txns));
+                    // The committed status was persisted along with the state root, so only the
+                    // statuses that are no longer retained have to be pruned
+                    #[cfg(feature = "batch-status-store")]
+                    self.prune_store();
                 }
                 _ => {
                     error!(
@@ -711,6 +1812,9 @@ impl BatchHistory {
 
                 self.history.insert(signature, batch_info.clone());
 
+                #[cfg(feature = "state-pruning")]
+                self.evict_expired();
+
                 if self.history.len() > self.limit {
                     self.history
                         .clone()
@@ -724,6 +1828,70 @@ impl BatchHistory {
         }
     }
 
+    /// Persists the status of a completed batch. Failures are only logged, since the batch has
+    /// already completed and its status is still available from the in-memory history.
+    #[cfg(feature = "batch-status-store")]
+    fn persist(&mut self, info: &BatchInfo) {
+        #[cfg(feature = "backup")]
+        let commit_gate = self.commit_gate.clone();
+        #[cfg(feature = "backup")]
+        let _commit_guard = commit_gate.as_ref().map(CommitGate::enter);
+
+        if let Some(store) = self.store.as_mut() {
+            if let Err(err) = store.put(info) {
+                error!("Unable to persist status of batch {}: {}", info.id, err);
+            }
+        }
+    }
+
+    /// Writes the committed status of the given valid batch in the given transaction, which also
+    /// records the state root that the batch produced.
+    #[cfg(feature = "batch-status-store")]
+    fn stage_commit(
+        &mut self,
+        signature: &str,
+        writer: &mut dyn DatabaseWriter,
+    ) -> Result<(), ScabbardStateError> {
+        if let (Some(store), Some(info)) = (self.store.as_mut(), self.history.get(signature)) {
+            if let BatchStatus::Valid(txns) = &info.status {
+                store.stage(
+                    writer,
+                    &BatchInfo {
+                        id: info.id.clone(),
+                        status: BatchStatus::Committed(txns.clone()),
+                        timestamp: info.timestamp,
+                    },
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prunes the persisted statuses that are no longer retained. Failures are only logged, since
+    /// the statuses will be pruned after a later batch completes.
+    #[cfg(feature = "batch-status-store")]
+    fn prune_store(&self) {
+        if let Some(store) = &self.store {
+            if let Err(err) = store.prune() {
+                error!("Unable to prune persisted batch statuses: {}", err);
+            }
+        }
+    }
+
+    #[cfg(feature = "state-pruning")]
+    fn evict_expired(&mut self) {
+        if let Some(max_age) = self.max_age {
+            self.history.retain(|_, info| {
+                info.timestamp
+                    .elapsed()
+                    .map(|age| age <= max_age)
+                    // The system clock moved backwards, so this batch can't have expired yet
+                    .unwrap_or(true)
+            });
+        }
+    }
+
     pub fn get_batch_info(
         &mut self,
         ids: HashSet<String>,
@@ -739,14 +1907,23 @@ impl BatchHistory {
         Box::new(
             ids.iter()
                 .map(|id| {
-                    Ok(if let Some(info) = self.history.get(id) {
-                        info.clone()
-                    } else {
-                        BatchInfo {
-                            id: id.to_string(),
-                            status: BatchStatus::Unknown,
-                            timestamp: SystemTime::now(),
+                    if let Some(info) = self.history.get(id) {
+                        return Ok(info.clone());
+                    }
+
+                    // Batches that completed before the service restarted, or that have been
+                    // evicted from the in-memory history, may still be in the store
+                    #[cfg(feature = "batch-status-store")]
+                    if let Some(store) = &self.store {
+                        if let Some(info) = store.get(id).map_err(|err| err.to_string())? {
+                            return Ok(info);
                         }
+                    }
+
+                    Ok(BatchInfo {
+                        id: id.to_string(),
+                        status: BatchStatus::Unknown,
+                        timestamp: SystemTime::now(),
                     })
                 })
                 .collect::<Vec<_>>()
@@ -809,11 +1986,171 @@ impl Default for BatchHistory {
         Self {
             history: HashMap::new(),
             limit: DEFAULT_BATCH_HISTORY_SIZE,
+            #[cfg(feature = "state-pruning")]
+            max_age: None,
             batch_subscribers: vec![],
+            #[cfg(feature = "batch-status-store")]
+            store: None,
+            #[cfg(all(feature = "backup", feature = "batch-status-store"))]
+            commit_gate: None,
+        }
+    }
+}
+
+/// Persists the statuses of completed batches in the state database, so they can still be looked
+/// up after the service restarts. Statuses are keyed by batch ID, and the batch IDs are also kept
+/// in completion order so the oldest statuses can be pruned once the retention limits are
+/// exceeded.
+#[cfg(feature = "batch-status-store")]
+struct BatchStatusStore {
+    db: Box<dyn Database>,
+    limit: usize,
+    max_age: Option<Duration>,
+    next_sequence: u64,
+}
+
+/// The persisted form of a completed batch's status.
+#[cfg(feature = "batch-status-store")]
+#[derive(Serialize, Deserialize)]
+struct BatchStatusRecord {
+    status: BatchStatus,
+    timestamp: SystemTime,
+    sequence: u64,
+}
+
+#[cfg(feature = "batch-status-store")]
+impl BatchStatusStore {
+    fn new(db: Box<dyn Database>) -> Result<Self, ScabbardStateError> {
+        let next_sequence = next_sequence(&*db, BATCH_STATUS_ORDER_INDEX)?;
+        Ok(Self {
+            db,
+            limit: DEFAULT_BATCH_STATUS_RETENTION,
+            max_age: None,
+            next_sequence,
+        })
+    }
+
+    /// Stores the status of the given batch in its own transaction, and then prunes the statuses
+    /// that are no longer retained.
+    fn put(&mut self, info: &BatchInfo) -> Result<(), ScabbardStateError> {
+        let db = self.db.clone();
+        let mut writer = db.get_writer()?;
+        self.stage(&mut *writer, info)?;
+        writer.commit()?;
+
+        self.prune()?;
+
+        Ok(())
+    }
+
+    /// Writes the status of the given batch in the given transaction, replacing any status
+    /// previously stored for it. The caller commits the transaction.
+    fn stage(
+        &mut self,
+        writer: &mut dyn DatabaseWriter,
+        info: &BatchInfo,
+    ) -> Result<(), ScabbardStateError> {
+        let previous = writer
+            .as_reader()
+            .index_get(BATCH_STATUS_INDEX, info.id.as_bytes())?;
+        if let Some(previous) = previous {
+            let previous = parse_batch_status_record(&previous)?;
+            writer.index_delete(BATCH_STATUS_ORDER_INDEX, &previous.sequence.to_be_bytes())?;
+        }
+
+        let sequence = self.next_sequence;
+        let record = serde_json::to_vec(&BatchStatusRecord {
+            status: info.status.clone(),
+            timestamp: info.timestamp,
+            sequence,
+        })
+        .map_err(|err| ScabbardStateError(format!("Unable to serialize batch status: {}", err)))?;
+
+        writer.index_put(BATCH_STATUS_INDEX, info.id.as_bytes(), &record)?;
+        writer.index_put(
+            BATCH_STATUS_ORDER_INDEX,
+            &sequence.to_be_bytes(),
+            info.id.as_bytes(),
+        )?;
+        self.next_sequence += 1;
+
+        Ok(())
+    }
+
+    fn get(&self, batch_id: &str) -> Result<Option<BatchInfo>, ScabbardStateError> {
+        self.db
+            .get_reader()?
+            .index_get(BATCH_STATUS_INDEX, batch_id.as_bytes())?
+            .map(|record| {
+                let record = parse_batch_status_record(&record)?;
+                Ok(BatchInfo {
+                    id: batch_id.into(),
+                    status: record.status,
+                    timestamp: record.timestamp,
+                })
+            })
+            .transpose()
+    }
+
+    /// Removes the oldest statuses until at most `limit` remain and none is older than `max_age`.
+    /// Returns the number of statuses that were removed.
+    fn prune(&self) -> Result<usize, ScabbardStateError> {
+        let reader = self.db.get_reader()?;
+        let mut excess = reader
+            .index_count(BATCH_STATUS_ORDER_INDEX)?
+            .saturating_sub(self.limit);
+        if excess == 0 && self.max_age.is_none() {
+            return Ok(0);
+        }
+
+        // Statuses are ordered oldest first, so they are only read up to the first one to keep
+        let mut expired = vec![];
+        for (key, batch_id) in reader.index_cursor(BATCH_STATUS_ORDER_INDEX)? {
+            if excess > 0 {
+                excess -= 1;
+            } else {
+                let record = match reader.index_get(BATCH_STATUS_INDEX, &batch_id)? {
+                    Some(record) => parse_batch_status_record(&record)?,
+                    None => continue,
+                };
+                let too_old = match self.max_age {
+                    Some(max_age) => record
+                        .timestamp
+                        .elapsed()
+                        .map(|age| age > max_age)
+                        // The system clock moved backwards, so this status can't have expired yet
+                        .unwrap_or(false),
+                    None => false,
+                };
+                if !too_old {
+                    break;
+                }
+            }
+            expired.push((key, batch_id));
+        }
+        drop(reader);
+
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        let mut writer = self.db.get_writer()?;
+        for (key, batch_id) in &expired {
+            writer.index_delete(BATCH_STATUS_ORDER_INDEX, key)?;
+            writer.index_delete(BATCH_STATUS_INDEX, batch_id)?;
         }
+        writer.commit()?;
+
+        Ok(expired.len())
     }
 }
 
+#[cfg(feature = "batch-status-store")]
+fn parse_batch_status_record(record: &[u8]) -> Result<BatchStatusRecord, ScabbardStateError> {
+    serde_json::from_slice(record)
+        .map_err(|err| ScabbardStateError(format!("Invalid batch status entry: {}", err)))
+}
+
 pub type BatchInfoIter = Box<dyn Iterator<Item = Result<BatchInfo, String>>>;
 
 pub struct ChannelBatchInfoIter {
@@ -1103,6 +2440,795 @@ mod tests {
         assert!(no_entries.is_empty());
     }
 
+    /// Verify that the receipts of committed transactions can be fetched by transaction ID and
+    /// listed in pages, optionally starting after a given transaction.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState`.
+    /// 2. Commit three batches that each contain a single transaction.
+    /// 3. Verify that each receipt can be fetched by its transaction ID and that an unknown ID
+    ///    returns `None`.
+    /// 4. Verify that listing all receipts with an offset and limit returns the expected page and
+    ///    total.
+    /// 5. Verify that listing the receipts since the first transaction returns the other two, and
+    ///    that a page of one of them still counts the other.
+    /// 6. Verify that listing the receipts since an unknown transaction returns `None`.
+    #[cfg(feature = "receipts")]
+    #[test]
+    fn list_receipts() {
+        let paths = StatePaths::new("list_receipts");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        let signing_context = Secp256k1Context::new();
+        let signer = signing_context.new_signer(signing_context.new_random_private_key());
+        let mut transaction_ids = vec![];
+        for value in &[b"one", b"two", b"six"] {
+            let batch = BatchBuilder::new()
+                .with_transactions(vec![
+                    make_command_transaction(
+                        &[Command::SetState(SetState::new(vec![BytesEntry::new(
+                            "abcdef".into(),
+                            value.to_vec(),
+                        )]))],
+                        &*signer,
+                    )
+                    .take()
+                    .0,
+                ])
+                .build_pair(&*signer)
+                .expect("Failed to build batch");
+            transaction_ids.push(
+                batch.batch().transactions()[0]
+                    .header_signature()
+                    .to_string(),
+            );
+            state
+                .prepare_change(batch)
+                .expect("Failed to prepare change");
+            state.commit().expect("Failed to commit change");
+        }
+
+        for transaction_id in &transaction_ids {
+            let receipt = state
+                .get_receipt(transaction_id)
+                .expect("Failed to get receipt")
+                .expect("Receipt not found");
+            assert_eq!(&receipt.transaction_id, transaction_id);
+        }
+        assert!(state
+            .get_receipt("0123456789abcdef")
+            .expect("Failed to get receipt")
+            .is_none());
+
+        let (receipts, total) = super::list_receipts(&state.transaction_receipt_store, None, 1, 1)
+            .expect("Failed to list receipts")
+            .expect("Receipts not found");
+        assert_eq!(total, 3);
+        assert_eq!(
+            receipts
+                .iter()
+                .map(|receipt| receipt.transaction_id.as_str())
+                .collect::<Vec<_>>(),
+            vec![transaction_ids[1].as_str()]
+        );
+
+        let (receipts, total) = super::list_receipts(
+            &state.transaction_receipt_store,
+            Some(&transaction_ids[0]),
+            0,
+            10,
+        )
+        .expect("Failed to list receipts")
+        .expect("Receipts not found");
+        assert_eq!(total, 2);
+        assert_eq!(
+            receipts
+                .iter()
+                .map(|receipt| receipt.transaction_id.as_str())
+                .collect::<Vec<_>>(),
+            vec![transaction_ids[1].as_str(), transaction_ids[2].as_str()]
+        );
+
+        let (receipts, total) = super::list_receipts(
+            &state.transaction_receipt_store,
+            Some(&transaction_ids[0]),
+            0,
+            1,
+        )
+        .expect("Failed to list receipts")
+        .expect("Receipts not found");
+        assert_eq!(total, 2);
+        assert_eq!(
+            receipts
+                .iter()
+                .map(|receipt| receipt.transaction_id.as_str())
+                .collect::<Vec<_>>(),
+            vec![transaction_ids[1].as_str()]
+        );
+
+        assert!(super::list_receipts(
+            &state.transaction_receipt_store,
+            Some("0123456789abcdef"),
+            0,
+            10
+        )
+        .expect("Failed to list receipts")
+        .is_none());
+    }
+
+    /// Verify that state can be read at a past state root and that committed state roots are
+    /// listed, newest first, with the batches that produced them.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState`.
+    /// 2. Commit two batches that each set a different value at the same address, recording the
+    ///    state root and batch ID after each commit.
+    /// 3. Verify that the listed state roots match the recorded ones, newest first, and that the
+    ///    limit is respected.
+    /// 4. Verify that the value and prefix entries at the first root are the original value.
+    /// 5. Verify that an unknown state root is reported as not existing.
+    #[cfg(feature = "state-history")]
+    #[test]
+    fn historical_state_queries() {
+        let paths = StatePaths::new("historical_state_queries");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        let address = "abcdef".to_string();
+        let signing_context = Secp256k1Context::new();
+        let signer = signing_context.new_signer(signing_context.new_random_private_key());
+        let mut committed = vec![];
+        for value in &[b"one", b"two"] {
+            let batch = BatchBuilder::new()
+                .with_transactions(vec![
+                    make_command_transaction(
+                        &[Command::SetState(SetState::new(vec![BytesEntry::new(
+                            address.clone(),
+                            value.to_vec(),
+                        )]))],
+                        &*signer,
+                    )
+                    .take()
+                    .0,
+                ])
+                .build_pair(&*signer)
+                .expect("Failed to build batch");
+            let batch_id = batch.batch().header_signature().to_string();
+            state
+                .prepare_change(batch)
+                .expect("Failed to prepare change");
+            state.commit().expect("Failed to commit change");
+            committed.push(StateRootInfo {
+                state_root: state.current_state_root().to_string(),
+                batch_id: Some(batch_id),
+            });
+        }
+
+        let roots = state
+            .list_state_roots(None)
+            .expect("Failed to list state roots");
+        assert_eq!(roots, vec![committed[1].clone(), committed[0].clone()]);
+        assert_eq!(
+            state
+                .list_state_roots(Some(1))
+                .expect("Failed to list state roots"),
+            vec![committed[1].clone()]
+        );
+
+        let first_root = &committed[0].state_root;
+        assert!(state
+            .has_state_root(first_root)
+            .expect("Failed to check state root"));
+        assert_eq!(
+            state
+                .get_state_at_address_at_root(&address, first_root)
+                .expect("Failed to get state at past root"),
+            Some(b"one".to_vec()),
+        );
+        let entries = state
+            .get_state_with_prefix_at_root(Some("abcd"), first_root)
+            .expect("Failed to get state with prefix at past root")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to read state entries");
+        assert_eq!(entries, vec![(address.clone(), b"one".to_vec())]);
+
+        assert!(!state
+            .has_state_root("abcdef0123456789")
+            .expect("Failed to check unknown state root"));
+    }
+
+    /// Verify that the state root history only keeps the configured number of most recent state
+    /// roots, and that the history continues from its last entry after the state is reopened.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState` with a state root history size of 2.
+    /// 2. Commit three batches that each set a different value at the same address.
+    /// 3. Verify that only the two newest state roots are listed.
+    /// 4. Reopen the state with the same history size and commit another batch.
+    /// 5. Verify that the new state root is listed first, followed by the previous newest root.
+    #[cfg(feature = "state-history")]
+    #[test]
+    fn state_root_history_size() {
+        let paths = StatePaths::new("state_root_history_size");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+        state.set_state_root_history_size(2);
+
+        let address = "abcdef".to_string();
+        let signing_context = Secp256k1Context::new();
+        let signer = signing_context.new_signer(signing_context.new_random_private_key());
+        let make_batch = |value: &[u8]| {
+            BatchBuilder::new()
+                .with_transactions(vec![
+                    make_command_transaction(
+                        &[Command::SetState(SetState::new(vec![BytesEntry::new(
+                            address.clone(),
+                            value.to_vec(),
+                        )]))],
+                        &*signer,
+                    )
+                    .take()
+                    .0,
+                ])
+                .build_pair(&*signer)
+                .expect("Failed to build batch")
+        };
+
+        let mut committed = vec![];
+        for value in &[b"one", b"two", b"six"] {
+            state
+                .prepare_change(make_batch(*value))
+                .expect("Failed to prepare change");
+            state.commit().expect("Failed to commit change");
+            committed.push(state.current_state_root().to_string());
+        }
+
+        let roots = state
+            .list_state_roots(None)
+            .expect("Failed to list state roots")
+            .into_iter()
+            .map(|info| info.state_root)
+            .collect::<Vec<_>>();
+        assert_eq!(roots, vec![committed[2].clone(), committed[1].clone()]);
+
+        drop(state);
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to reopen state");
+        state.set_state_root_history_size(2);
+
+        state
+            .prepare_change(make_batch(b"ten"))
+            .expect("Failed to prepare change");
+        state.commit().expect("Failed to commit change");
+
+        let roots = state
+            .list_state_roots(None)
+            .expect("Failed to list state roots")
+            .into_iter()
+            .map(|info| info.state_root)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            roots,
+            vec![state.current_state_root().to_string(), committed[2].clone()]
+        );
+    }
+
+    /// Verify that old state roots are pruned down to the retention depth, and that the current
+    /// state is still readable afterwards.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState`.
+    /// 2. Commit three batches that each set a different value at the same address.
+    /// 3. Verify that a retention depth of 0 is rejected.
+    /// 4. Prune with a retention depth of 1 and verify that the two older roots were pruned.
+    /// 5. Verify that the latest value is still set and that pruning again is a no-op.
+    #[cfg(feature = "state-pruning")]
+    #[test]
+    fn prune_state_roots() {
+        let paths = StatePaths::new("prune_state_roots");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        let address = "abcdef".to_string();
+        let signing_context = Secp256k1Context::new();
+        let signer = signing_context.new_signer(signing_context.new_random_private_key());
+        for value in &[b"one", b"two", b"six"] {
+            let batch = BatchBuilder::new()
+                .with_transactions(vec![
+                    make_command_transaction(
+                        &[Command::SetState(SetState::new(vec![BytesEntry::new(
+                            address.clone(),
+                            value.to_vec(),
+                        )]))],
+                        &*signer,
+                    )
+                    .take()
+                    .0,
+                ])
+                .build_pair(&*signer)
+                .expect("Failed to build batch");
+            state
+                .prepare_change(batch)
+                .expect("Failed to prepare change");
+            state.commit().expect("Failed to commit change");
+        }
+
+        assert!(state.prune_state_roots(0).is_err());
+        assert_eq!(
+            state
+                .prune_state_roots(1)
+                .expect("Failed to prune state roots"),
+            2
+        );
+
+        assert_eq!(
+            state
+                .get_state_at_address(&address)
+                .expect("Failed to get state for set address"),
+            Some(b"six".to_vec()),
+        );
+        assert_eq!(
+            state
+                .prune_state_roots(1)
+                .expect("Failed to prune state roots"),
+            0
+        );
+    }
+
+    /// Verify that the state database is only compacted while it is not open, and that compaction
+    /// keeps the current state.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState` and commit a batch.
+    /// 2. Verify that compacting the database fails while the state is open.
+    /// 3. Drop the state and compact the database.
+    /// 4. Reopen the state and verify that its state root and value are unchanged.
+    #[cfg(all(feature = "state-pruning", target_os = "linux"))]
+    #[test]
+    fn compact_state_db() {
+        let paths = StatePaths::new("compact_state_db");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        let signing_context = Secp256k1Context::new();
+        let signer = signing_context.new_signer(signing_context.new_random_private_key());
+        let batch = BatchBuilder::new()
+            .with_transactions(vec![
+                make_command_transaction(
+                    &[Command::SetState(SetState::new(vec![BytesEntry::new(
+                        "abcdef".into(),
+                        b"value".to_vec(),
+                    )]))],
+                    &*signer,
+                )
+                .take()
+                .0,
+            ])
+            .build_pair(&*signer)
+            .expect("Failed to build batch");
+        state
+            .prepare_change(batch)
+            .expect("Failed to prepare change");
+        state.commit().expect("Failed to commit change");
+        let state_root = state.current_state_root().to_string();
+
+        assert!(super::compact_state_db(&paths.state_db_path, TEMP_DB_SIZE).is_err());
+
+        drop(state);
+        super::compact_state_db(&paths.state_db_path, TEMP_DB_SIZE)
+            .expect("Failed to compact state db");
+
+        let state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to reopen state");
+        assert_eq!(state.current_state_root(), state_root);
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef")
+                .expect("Failed to get state for set address"),
+            Some(b"value".to_vec()),
+        );
+    }
+
+    /// Verify that the status of a committed batch is still available after the state is
+    /// reopened, as it would be after a restart.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState` and commit a batch.
+    /// 2. Drop the state and reopen it from the same files.
+    /// 3. Verify that the batch is reported as committed, both with and without waiting.
+    /// 4. Verify that an unknown batch is still reported as unknown.
+    #[cfg(feature = "batch-status-store")]
+    #[test]
+    fn batch_status_persisted_across_restart() {
+        let paths = StatePaths::new("batch_status_persisted_across_restart");
+
+        let batch_id = {
+            let mut state = ScabbardState::new(
+                &paths.state_db_path,
+                TEMP_DB_SIZE,
+                &paths.receipt_db_path,
+                TEMP_DB_SIZE,
+                vec![],
+            )
+            .expect("Failed to initialize state");
+
+            let signing_context = Secp256k1Context::new();
+            let signer = signing_context.new_signer(signing_context.new_random_private_key());
+            let batch = BatchBuilder::new()
+                .with_transactions(vec![
+                    make_command_transaction(
+                        &[Command::SetState(SetState::new(vec![BytesEntry::new(
+                            "abcdef".into(),
+                            b"value".to_vec(),
+                        )]))],
+                        &*signer,
+                    )
+                    .take()
+                    .0,
+                ])
+                .build_pair(&*signer)
+                .expect("Failed to build batch");
+            let batch_id = batch.batch().header_signature().to_string();
+            state.batch_history().add_batch(&batch_id);
+            state
+                .prepare_change(batch)
+                .expect("Failed to prepare change");
+            state.commit().expect("Failed to commit change");
+            batch_id
+        };
+
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to reopen state");
+
+        for wait in &[None, Some(Duration::from_millis(100))] {
+            let infos = state
+                .batch_history()
+                .get_batch_info(vec![batch_id.clone()].into_iter().collect(), *wait)
+                .expect("Failed to get batch info")
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Failed to read batch info");
+            assert_eq!(infos.len(), 1);
+            assert_eq!(infos[0].id, batch_id);
+            assert!(matches!(infos[0].status, BatchStatus::Committed(_)));
+        }
+
+        let infos = state
+            .batch_history()
+            .get_batch_info(vec!["unknown".to_string()].into_iter().collect(), None)
+            .expect("Failed to get batch info")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to read batch info");
+        assert_eq!(infos[0].status, BatchStatus::Unknown);
+    }
+
+    /// Verify that the batch status store only retains the most recent statuses, up to its limit
+    /// and maximum age.
+    ///
+    /// 1. Open a batch status store with a limit of 2 and store three statuses.
+    /// 2. Verify that the oldest status was pruned and the other two are retained.
+    /// 3. Set a maximum age, wait for the retained statuses to expire, and store another status.
+    /// 4. Verify that only the new status is retained.
+    #[cfg(feature = "batch-status-store")]
+    #[test]
+    fn batch_status_store_retention() {
+        let paths = StatePaths::new("batch_status_store_retention");
+        let mut store = BatchStatusStore::new(Box::new(
+            open_state_db(&paths.state_db_path.with_extension("lmdb"), TEMP_DB_SIZE)
+                .expect("Failed to open state db"),
+        ))
+        .expect("Failed to open batch status store");
+        store.limit = 2;
+
+        let batch_info = |id: &str| BatchInfo {
+            id: id.into(),
+            status: BatchStatus::Committed(vec![]),
+            timestamp: SystemTime::now(),
+        };
+
+        for id in &["one", "two", "six"] {
+            store.put(&batch_info(id)).expect("Failed to store status");
+        }
+        assert!(store.get("one").expect("Failed to get status").is_none());
+        assert!(store.get("two").expect("Failed to get status").is_some());
+        assert!(store.get("six").expect("Failed to get status").is_some());
+
+        store.max_age = Some(Duration::from_millis(50));
+        std::thread::sleep(Duration::from_millis(100));
+        store
+            .put(&batch_info("ten"))
+            .expect("Failed to store status");
+        assert!(store.get("two").expect("Failed to get status").is_none());
+        assert!(store.get("six").expect("Failed to get status").is_none());
+        assert_eq!(
+            store
+                .get("ten")
+                .expect("Failed to get status")
+                .map(|info| info.status),
+            Some(BatchStatus::Committed(vec![]))
+        );
+    }
+
+    /// Verify that batches older than the batch history's maximum age are evicted when a new batch
+    /// is added.
+    #[cfg(feature = "state-pruning")]
+    #[test]
+    fn batch_history_max_age() {
+        let mut batch_history = BatchHistory::new();
+        batch_history.set_max_age(Duration::from_millis(50));
+
+        batch_history.add_batch("old");
+        std::thread::sleep(Duration::from_millis(100));
+        batch_history.add_batch("new");
+
+        assert!(!batch_history.history.contains_key("old"));
+        assert!(batch_history.history.contains_key("new"));
+    }
+
+    /// Verify that a service can catch up with a peer by replaying the peer's receipts, and that
+    /// the replay is rejected if it doesn't arrive at the peer's state root.
+    ///
+    /// 1. Initialize two new, empty `ScabbardState`s.
+    /// 2. Commit a batch on the first state.
+    /// 3. Verify that replaying the first state's receipts on the second state with an incorrect
+    ///    expected state root fails and leaves the second state as it was.
+    /// 4. Replay the receipts with the correct expected state root and verify that the second state
+    ///    now has the same state root, value and last receipt as the first.
+    #[cfg(feature = "state-sync")]
+    #[test]
+    fn apply_synced_receipts() {
+        let paths = StatePaths::new("apply_synced_receipts");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+        let lagging_paths = StatePaths::new("apply_synced_receipts_lagging");
+        let mut lagging_state = ScabbardState::new(
+            &lagging_paths.state_db_path,
+            TEMP_DB_SIZE,
+            &lagging_paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize lagging state");
+        let initial_state_root = lagging_state.current_state_root().to_string();
+
+        let address = "abcdef".to_string();
+        let value = b"value".to_vec();
+        let signing_context = Secp256k1Context::new();
+        let signer = signing_context.new_signer(signing_context.new_random_private_key());
+        let batch = BatchBuilder::new()
+            .with_transactions(vec![
+                make_command_transaction(
+                    &[Command::SetState(SetState::new(vec![BytesEntry::new(
+                        address.clone(),
+                        value.clone(),
+                    )]))],
+                    &*signer,
+                )
+                .take()
+                .0,
+            ])
+            .build_pair(&*signer)
+            .expect("Failed to build batch");
+        state
+            .prepare_change(batch)
+            .expect("Failed to prepare change");
+        state.commit().expect("Failed to commit change");
+
+        assert_eq!(
+            lagging_state
+                .last_receipt_id()
+                .expect("Failed to get last receipt ID"),
+            None
+        );
+        let receipts = state
+            .receipts_since(None)
+            .expect("Failed to get receipts")
+            .expect("Receipts not found");
+        assert_eq!(receipts.len(), 1);
+
+        assert!(lagging_state
+            .apply_synced_receipts(receipts.clone(), &initial_state_root)
+            .is_err());
+        assert_eq!(lagging_state.current_state_root(), initial_state_root);
+
+        lagging_state
+            .apply_synced_receipts(receipts, state.current_state_root())
+            .expect("Failed to apply receipts");
+        assert_eq!(
+            lagging_state.current_state_root(),
+            state.current_state_root()
+        );
+        assert_eq!(
+            lagging_state
+                .get_state_at_address(&address)
+                .expect("Failed to get state for set address"),
+            Some(value),
+        );
+        assert_eq!(
+            lagging_state
+                .last_receipt_id()
+                .expect("Failed to get last receipt ID"),
+            state
+                .last_receipt_id()
+                .expect("Failed to get last receipt ID"),
+        );
+        assert!(state
+            .receipts_since(Some("unknown"))
+            .expect("Failed to get receipts")
+            .is_none());
+    }
+
+    /// Verify that a service's state can be replaced by a snapshot of a peer's state that is
+    /// received in chunks, that nothing is written to the state until the snapshot is verified,
+    /// and that the receipt store is emptied when the snapshot is applied.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState` and commit a batch on it.
+    /// 2. Stage a snapshot, apply two chunks of it and verify that the resulting state root is the
+    ///    one of the snapshot's entries, while the state root is neither current nor in the state.
+    /// 3. Verify that applying the snapshot with another state root fails, leaves the state as it
+    ///    was and discards the staged snapshot.
+    /// 4. Stage the snapshot again and apply it, and verify that the state now has the snapshot's
+    ///    state root and values, that no receipts are left and that receipts can still be stored.
+    #[cfg(feature = "state-sync")]
+    #[test]
+    fn apply_state_snapshot() {
+        let paths = StatePaths::new("apply_state_snapshot");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        let signing_context = Secp256k1Context::new();
+        let signer = signing_context.new_signer(signing_context.new_random_private_key());
+        let batch = BatchBuilder::new()
+            .with_transactions(vec![
+                make_command_transaction(
+                    &[Command::SetState(SetState::new(vec![BytesEntry::new(
+                        "012345".into(),
+                        b"value".to_vec(),
+                    )]))],
+                    &*signer,
+                )
+                .take()
+                .0,
+            ])
+            .build_pair(&*signer)
+            .expect("Failed to build batch");
+        state
+            .prepare_change(batch)
+            .expect("Failed to prepare change");
+        state.commit().expect("Failed to commit change");
+        let committed_state_root = state.current_state_root().to_string();
+
+        let entries = vec![
+            ("abcdef".to_string(), b"value1".to_vec()),
+            ("abcdef0123".to_string(), b"value2".to_vec()),
+        ];
+        let expected_state_root = {
+            let empty_state_root = MerkleRadixTree::new(state.db.clone(), None)
+                .expect("Failed to create merkle tree")
+                .get_merkle_root();
+            MerkleState::new(state.db.clone())
+                .compute_state_id(
+                    &empty_state_root,
+                    &entries
+                        .iter()
+                        .cloned()
+                        .map(|(key, value)| TransactStateChange::Set { key, value })
+                        .collect::<Vec<_>>(),
+                )
+                .expect("Failed to compute state root")
+        };
+
+        state.start_snapshot().expect("Failed to stage snapshot");
+        state
+            .apply_snapshot_chunk(entries[..1].to_vec())
+            .expect("Failed to apply first chunk");
+        let snapshot_state_root = state
+            .apply_snapshot_chunk(entries[1..].to_vec())
+            .expect("Failed to apply second chunk");
+        assert_eq!(snapshot_state_root, expected_state_root);
+        assert_eq!(state.current_state_root(), committed_state_root);
+        assert!(MerkleRadixTree::new(state.db.clone(), Some(&snapshot_state_root)).is_err());
+
+        assert!(state.apply_state_snapshot(&committed_state_root).is_err());
+        assert_eq!(state.current_state_root(), committed_state_root);
+        assert!(MerkleRadixTree::new(state.db.clone(), Some(&snapshot_state_root)).is_err());
+        assert!(!paths.state_db_path.with_extension("lmdb-snapshot").exists());
+        assert!(state.apply_snapshot_chunk(entries.clone()).is_err());
+
+        state.start_snapshot().expect("Failed to stage snapshot");
+        state
+            .apply_snapshot_chunk(entries)
+            .expect("Failed to apply snapshot chunk");
+        state
+            .apply_state_snapshot(&snapshot_state_root)
+            .expect("Failed to apply snapshot");
+        assert_eq!(state.current_state_root(), expected_state_root);
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef0123")
+                .expect("Failed to get state for set address"),
+            Some(b"value2".to_vec()),
+        );
+        assert_eq!(
+            state
+                .get_state_at_address("012345")
+                .expect("Failed to get state for unset address"),
+            None,
+        );
+        assert_eq!(
+            state
+                .last_receipt_id()
+                .expect("Failed to get last receipt ID"),
+            None
+        );
+        assert!(!paths.state_db_path.with_extension("lmdb-snapshot").exists());
+        assert!(!paths.receipt_db_path.with_extension("lmdb-reset").exists());
+
+        state
+            .store_receipts(vec![mock_transaction_receipt("ab")])
+            .expect("Failed to store receipt");
+        assert_eq!(
+            state
+                .last_receipt_id()
+                .expect("Failed to get last receipt ID"),
+            Some("ab".to_string())
+        );
+    }
+
     struct StatePaths {
         _temp_dir_handle: TempDir,
         pub state_db_path: PathBuf,
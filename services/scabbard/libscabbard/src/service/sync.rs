@@ -0,0 +1,1172 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! State synchronization between scabbard services.
+//!
+//! A service whose state has fallen behind its peers' (for instance, because it was offline when
+//! a proposal was committed) sends a `StateSyncRequest` containing its current state root and the
+//! ID of the last transaction receipt it committed to all of its peers. Each peer responds with
+//! its state root and the receipts it committed after that one, which the requesting service
+//! replays on top of its own state.
+//!
+//! A peer's response can't be trusted on its own, so the requesting service only adopts a state
+//! root that is reported by a majority of its peers, and only if replaying the receipts sent by
+//! one of those peers arrives at it. If none of them does, or if the service was asked to sync
+//! with a snapshot, a snapshot of the state is requested from one of those peers. The peer sends
+//! its snapshot in chunks, from a background thread, and the requesting service stages the chunks
+//! apart from its state until they are verified to arrive at the state root. Until the state sync
+//! is finished or times out, the service does not take part in two-phase commit.
+
+use std::collections::{HashMap, HashSet};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use protobuf::Message;
+use splinter::service::ServiceNetworkSender;
+use transact::protocol::receipt::TransactionReceipt;
+use transact::protos::{FromBytes, IntoBytes};
+
+use crate::protos::scabbard::{
+    ScabbardMessage, ScabbardMessage_Type, StateEntry, StateSyncRequest, StateSyncResponse,
+    StateSyncResponse_Type,
+};
+
+use super::error::ScabbardError;
+use super::shared::ScabbardShared;
+use super::state::{ScabbardState, StateView};
+
+/// How long to wait for the next response to a state sync request before another may be sent
+const STATE_SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+/// The number of bytes of state entries after which a chunk of a snapshot is sent
+const SNAPSHOT_CHUNK_SIZE: usize = 1024 * 1024;
+/// The number of services that snapshots may be sent to at the same time
+const MAX_SNAPSHOT_RECIPIENTS: usize = 2;
+
+/// The progress of an outstanding state sync request.
+pub struct StateSync {
+    /// When the request was sent or a response to it last arrived
+    last_progress: Instant,
+    /// Whether to sync with a snapshot, rather than by replaying receipts
+    snapshot: bool,
+    /// The peer that a snapshot was requested from, if any
+    snapshot_peer: Option<String>,
+    /// The peers that have not finished responding to the request
+    awaiting: HashSet<String>,
+    /// The state root reported by each peer that responded, along with what it sent
+    responses: HashMap<String, (String, SyncPayload)>,
+}
+
+/// What a peer sent in response to a state sync request.
+enum SyncPayload {
+    /// Nothing that can be applied
+    None,
+    /// The receipts the peer committed after the requesting service's last receipt
+    Receipts(Vec<TransactionReceipt>),
+    /// A snapshot that is still being received, along with the index of the next chunk
+    PartialSnapshot(u32),
+    /// A completely received snapshot, which is staged until it is applied
+    Snapshot,
+}
+
+impl StateSync {
+    fn new(peers: &[String], snapshot: bool) -> Self {
+        StateSync {
+            last_progress: Instant::now(),
+            snapshot,
+            snapshot_peer: None,
+            awaiting: peers.iter().cloned().collect(),
+            responses: HashMap::new(),
+        }
+    }
+
+    /// Determines if no response to the request has arrived for too long.
+    pub fn is_timed_out(&self) -> bool {
+        self.last_progress.elapsed() >= STATE_SYNC_TIMEOUT
+    }
+
+    /// Returns the state root reported by a majority of the `peer_count` peers, if there is one.
+    fn quorum_state_root(&self, peer_count: usize) -> Option<String> {
+        let mut votes = HashMap::new();
+        for (state_root, _) in self.responses.values() {
+            *votes.entry(state_root.as_str()).or_insert(0) += 1;
+        }
+
+        votes
+            .into_iter()
+            .find(|(_, count)| *count > peer_count / 2)
+            .map(|(state_root, _)| state_root.to_string())
+    }
+
+    /// Replaces what the given peer sent, keeping the state root it reported.
+    fn set_payload(&mut self, peer: &str, payload: SyncPayload) {
+        if let Some((_, current_payload)) = self.responses.get_mut(peer) {
+            *current_payload = payload;
+        }
+    }
+}
+
+/// Requests a state sync from all of the service's peers, unless a request is already
+/// outstanding. If `snapshot` is `true`, a snapshot is requested from one of the peers once a
+/// majority of them agree on a state root, instead of replaying their receipts.
+pub fn request_state_sync(
+    shared: &mut ScabbardShared,
+    state: &ScabbardState,
+    snapshot: bool,
+) -> Result<(), ScabbardError> {
+    let peers = shared.peer_services().iter().cloned().collect::<Vec<_>>();
+    if peers.is_empty() {
+        debug!("Not requesting state sync; the service has no peers");
+        return Ok(());
+    }
+
+    if !shared.start_state_sync(StateSync::new(&peers, snapshot)) {
+        debug!("Not requesting state sync; a request is already outstanding");
+        return Ok(());
+    }
+
+    let result = send_state_sync_request(shared, state, &peers, false);
+    if result.is_err() {
+        shared.finish_state_sync();
+    }
+
+    result
+}
+
+/// Responds to a peer's state sync request. If the peer has committed a transaction that this
+/// service has not, this service may be the one that is behind, so it tells the peer and requests
+/// a state sync of its own.
+pub fn handle_state_sync_request(
+    request: &StateSyncRequest,
+    shared: &mut ScabbardShared,
+    state: &ScabbardState,
+) -> Result<(), ScabbardError> {
+    let requester = request.get_service_id();
+    if !shared.peer_services().contains(requester) {
+        warn!(
+            "Ignoring state sync request from unknown service {}",
+            requester
+        );
+        return Ok(());
+    }
+
+    if request.get_snapshot() {
+        return send_snapshot(shared, state, requester);
+    }
+
+    let sender = shared.network_sender().ok_or(ScabbardError::NotConnected)?;
+    if request.get_current_state_root() == state.current_state_root() {
+        let response = new_response(
+            shared.service_id(),
+            state.current_state_root(),
+            StateSyncResponse_Type::IN_SYNC,
+        );
+        return send_response(sender, requester, response);
+    }
+
+    let last_receipt_id = Some(request.get_last_receipt_id()).filter(|id| !id.is_empty());
+    match state.receipts_since(last_receipt_id)? {
+        Some(receipts) => {
+            let receipts = receipts
+                .into_iter()
+                .map(|receipt| receipt.into_bytes())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| ScabbardError::Internal(Box::new(err)))?;
+
+            let mut response = new_response(
+                shared.service_id(),
+                state.current_state_root(),
+                StateSyncResponse_Type::RECEIPTS,
+            );
+            response.set_receipts(receipts.into());
+            send_response(sender, requester, response)
+        }
+        None => {
+            let response = new_response(
+                shared.service_id(),
+                state.current_state_root(),
+                StateSyncResponse_Type::UNKNOWN_RECEIPT,
+            );
+            send_response(sender, requester, response)?;
+
+            info!(
+                "Service {} has committed transactions that this service has not; \
+                 requesting state sync",
+                requester
+            );
+            request_state_sync(shared, state, false)
+        }
+    }
+}
+
+/// Records a peer's response to this service's state sync request, and finishes the state sync
+/// once a majority of the peers agree on a state root that the service is able to arrive at.
+/// Responses that arrive while no request is outstanding, from peers that were not asked, or that
+/// are not what the peer was asked for, are ignored.
+pub fn handle_state_sync_response(
+    response: &StateSyncResponse,
+    shared: &mut ScabbardShared,
+    state: &mut ScabbardState,
+) -> Result<(), ScabbardError> {
+    let responder = response.get_service_id();
+    if !shared.peer_services().contains(responder) {
+        warn!(
+            "Ignoring state sync response from unknown service {}",
+            responder
+        );
+        return Ok(());
+    }
+
+    // Only the peer that a snapshot was requested from sends one, and it sends nothing else
+    let is_snapshot = response.get_response_type() == StateSyncResponse_Type::SNAPSHOT;
+    let state_sync = match shared.state_sync_mut() {
+        Some(state_sync)
+            if state_sync.awaiting.contains(responder)
+                && (state_sync.snapshot_peer.as_deref() == Some(responder)) == is_snapshot =>
+        {
+            state_sync
+        }
+        _ => {
+            warn!(
+                "Ignoring unexpected state sync response from service {}",
+                responder
+            );
+            return Ok(());
+        }
+    };
+    state_sync.last_progress = Instant::now();
+
+    let reported_state_root = response.get_state_root().to_string();
+    match response.get_response_type() {
+        StateSyncResponse_Type::IN_SYNC | StateSyncResponse_Type::UNKNOWN_RECEIPT => {
+            state_sync.awaiting.remove(responder);
+            state_sync.responses.insert(
+                responder.to_string(),
+                (reported_state_root, SyncPayload::None),
+            );
+        }
+        StateSyncResponse_Type::RECEIPTS => {
+            let payload = match response
+                .get_receipts()
+                .iter()
+                .map(|bytes| TransactionReceipt::from_bytes(bytes))
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(receipts) => SyncPayload::Receipts(receipts),
+                Err(err) => {
+                    warn!("Invalid receipts from service {}: {}", responder, err);
+                    SyncPayload::None
+                }
+            };
+
+            state_sync.awaiting.remove(responder);
+            state_sync
+                .responses
+                .insert(responder.to_string(), (reported_state_root, payload));
+        }
+        // The snapshot is checked against the state root reported by the majority, rather than
+        // the one in these responses, so that root is not recorded
+        StateSyncResponse_Type::SNAPSHOT => {
+            let chunk = response.get_chunk();
+            let next_chunk = match state_sync.responses.get(responder) {
+                Some((_, SyncPayload::PartialSnapshot(next_chunk))) => *next_chunk,
+                _ => 0,
+            };
+
+            let entries = response
+                .get_entries()
+                .iter()
+                .map(|entry| (entry.get_address().to_string(), entry.get_value().to_vec()))
+                .collect();
+            let result = if chunk != next_chunk {
+                Err(format!("chunk {} arrived out of order", chunk))
+            } else if chunk == 0 {
+                state
+                    .start_snapshot()
+                    .and_then(|_| state.apply_snapshot_chunk(entries))
+                    .map_err(|err| err.to_string())
+            } else {
+                state
+                    .apply_snapshot_chunk(entries)
+                    .map_err(|err| err.to_string())
+            };
+
+            match result {
+                Ok(_) if response.get_last_chunk() => {
+                    state_sync.awaiting.remove(responder);
+                    state_sync.set_payload(responder, SyncPayload::Snapshot);
+                }
+                Ok(_) => state_sync.set_payload(responder, SyncPayload::PartialSnapshot(chunk + 1)),
+                Err(err) => {
+                    warn!("Discarding snapshot from service {}: {}", responder, err);
+                    state_sync.awaiting.remove(responder);
+                    state_sync.set_payload(responder, SyncPayload::None);
+                    state.discard_snapshot()?;
+                }
+            }
+        }
+        StateSyncResponse_Type::UNSET => {
+            state_sync.awaiting.remove(responder);
+            return Err(ScabbardError::MessageTypeUnset);
+        }
+    }
+
+    complete_state_sync(shared, state)
+}
+
+/// Finishes the outstanding state sync if a majority of the peers report a state root that the
+/// service is already at, or that it arrives at by applying what one of those peers sent. If none
+/// of those peers sent receipts that arrive at the state root, a snapshot is requested from one of
+/// them, once.
+fn complete_state_sync(
+    shared: &mut ScabbardShared,
+    state: &mut ScabbardState,
+) -> Result<(), ScabbardError> {
+    let peer_count = shared.peer_services().len();
+    let state_sync = match shared.state_sync_mut() {
+        Some(state_sync) => state_sync,
+        None => return Ok(()),
+    };
+
+    let state_root = match state_sync.quorum_state_root(peer_count) {
+        Some(state_root) => state_root,
+        None => {
+            if state_sync.awaiting.is_empty() {
+                warn!("Unable to sync state; no state root is reported by a majority of peers");
+                return finish_state_sync(shared, state);
+            }
+            return Ok(());
+        }
+    };
+
+    if state_root == state.current_state_root() {
+        debug!("State is in sync with a majority of peers");
+        return finish_state_sync(shared, state);
+    }
+
+    if let Some(peer) = state_sync.snapshot_peer.clone() {
+        // Wait for the rest of the snapshot
+        if state_sync.awaiting.contains(&peer) {
+            return Ok(());
+        }
+
+        if let Some((_, SyncPayload::Snapshot)) = state_sync.responses.get(&peer) {
+            match state.apply_state_snapshot(&state_root) {
+                Ok(()) => return finish_state_sync(shared, state),
+                Err(err) => warn!(
+                    "Unable to sync state with snapshot from service {} ({})",
+                    peer, err
+                ),
+            }
+        }
+
+        warn!(
+            "Unable to sync state; no peer sent state that results in state root {}",
+            state_root
+        );
+        return finish_state_sync(shared, state);
+    }
+
+    let mut agreeing_peers = state_sync
+        .responses
+        .iter()
+        .filter(|(_, (reported_state_root, _))| *reported_state_root == state_root)
+        .map(|(peer, _)| peer.clone())
+        .collect::<Vec<_>>();
+    agreeing_peers.sort();
+
+    if !state_sync.snapshot {
+        for peer in &agreeing_peers {
+            let receipts = match state_sync.responses.get_mut(peer) {
+                Some((_, payload)) => match std::mem::replace(payload, SyncPayload::None) {
+                    SyncPayload::Receipts(receipts) => receipts,
+                    _ => continue,
+                },
+                None => continue,
+            };
+
+            match state.apply_synced_receipts(receipts, &state_root) {
+                Ok(()) => return finish_state_sync(shared, state),
+                Err(err) => warn!(
+                    "Unable to sync state by replaying receipts from service {} ({})",
+                    peer, err
+                ),
+            }
+        }
+    }
+
+    // There is at least one agreeing peer, since a majority reported the state root
+    let peer = agreeing_peers.swap_remove(0);
+    info!("Requesting a state snapshot from service {}", peer);
+    state_sync.snapshot_peer = Some(peer.clone());
+    state_sync.awaiting.insert(peer.clone());
+
+    let result = send_state_sync_request(shared, state, &[peer], true);
+    if result.is_err() {
+        shared.finish_state_sync();
+    }
+
+    result
+}
+
+/// Marks the outstanding state sync as finished and discards any snapshot that was received for
+/// it but not applied.
+fn finish_state_sync(
+    shared: &mut ScabbardShared,
+    state: &mut ScabbardState,
+) -> Result<(), ScabbardError> {
+    shared.finish_state_sync();
+    Ok(state.discard_snapshot()?)
+}
+
+fn send_state_sync_request(
+    shared: &ScabbardShared,
+    state: &ScabbardState,
+    peers: &[String],
+    snapshot: bool,
+) -> Result<(), ScabbardError> {
+    let mut request = StateSyncRequest::new();
+    request.set_service_id(shared.service_id().into());
+    request.set_current_state_root(state.current_state_root().into());
+    if let Some(last_receipt_id) = state.last_receipt_id()? {
+        request.set_last_receipt_id(last_receipt_id);
+    }
+    request.set_snapshot(snapshot);
+
+    let mut msg = ScabbardMessage::new();
+    msg.set_message_type(ScabbardMessage_Type::STATE_SYNC_REQUEST);
+    msg.set_state_sync_request(request);
+    let msg_bytes = msg
+        .write_to_bytes()
+        .map_err(|err| ScabbardError::Internal(Box::new(err)))?;
+
+    let sender = shared.network_sender().ok_or(ScabbardError::NotConnected)?;
+    for peer in peers {
+        sender
+            .send(peer, msg_bytes.as_slice())
+            .map_err(|err| ScabbardError::Internal(Box::new(err)))?;
+    }
+
+    Ok(())
+}
+
+/// Starts sending a snapshot of the service's current state to the requester from a background
+/// thread, so that the service's locks aren't held while the state is read and sent. The request
+/// is ignored if a snapshot is already being sent to the requester, or to too many services.
+fn send_snapshot(
+    shared: &ScabbardShared,
+    state: &ScabbardState,
+    requester: &str,
+) -> Result<(), ScabbardError> {
+    let sender = shared
+        .network_sender()
+        .ok_or(ScabbardError::NotConnected)?
+        .clone_box();
+    let recipients = shared.snapshot_recipients();
+    {
+        let mut recipients = recipients.lock().map_err(|_| ScabbardError::LockPoisoned)?;
+        if recipients.contains(requester) || recipients.len() >= MAX_SNAPSHOT_RECIPIENTS {
+            warn!(
+                "Ignoring snapshot request from service {}; snapshots are already being sent",
+                requester
+            );
+            return Ok(());
+        }
+        recipients.insert(requester.to_string());
+    }
+
+    let service_id = shared.service_id().to_string();
+    let view = state.view();
+    let recipient = requester.to_string();
+    let thread_recipients = recipients.clone();
+    let result = thread::Builder::new()
+        .name(format!("StateSnapshot-{}", requester))
+        .spawn(move || {
+            if let Err(err) = stream_snapshot(&*sender, &service_id, &view, &recipient) {
+                error!(
+                    "Failed to send state snapshot to service {}: {}",
+                    recipient, err
+                );
+            }
+
+            match thread_recipients.lock() {
+                Ok(mut recipients) => {
+                    recipients.remove(&recipient);
+                }
+                Err(_) => error!("Snapshot recipients lock poisoned"),
+            }
+        });
+
+    if let Err(err) = result {
+        if let Ok(mut recipients) = recipients.lock() {
+            recipients.remove(requester);
+        }
+        return Err(ScabbardError::Internal(Box::new(err)));
+    }
+
+    Ok(())
+}
+
+/// Sends all of the state entries in the view to the recipient, in chunks of roughly
+/// `SNAPSHOT_CHUNK_SIZE` bytes so that no single message grows with the size of the state.
+fn stream_snapshot(
+    sender: &dyn ServiceNetworkSender,
+    service_id: &str,
+    view: &StateView,
+    recipient: &str,
+) -> Result<(), ScabbardError> {
+    let mut entries = vec![];
+    let mut chunk_size = 0;
+    let mut chunk = 0;
+    for entry in view.entries()? {
+        let (address, value) = entry?;
+        chunk_size += address.len() + value.len();
+
+        let mut entry = StateEntry::new();
+        entry.set_address(address);
+        entry.set_value(value);
+        entries.push(entry);
+
+        if chunk_size >= SNAPSHOT_CHUNK_SIZE {
+            let mut response = new_response(
+                service_id,
+                view.state_root(),
+                StateSyncResponse_Type::SNAPSHOT,
+            );
+            response.set_entries(std::mem::take(&mut entries).into());
+            response.set_chunk(chunk);
+            send_response(sender, recipient, response)?;
+
+            chunk += 1;
+            chunk_size = 0;
+        }
+    }
+
+    let mut response = new_response(
+        service_id,
+        view.state_root(),
+        StateSyncResponse_Type::SNAPSHOT,
+    );
+    response.set_entries(entries.into());
+    response.set_chunk(chunk);
+    response.set_last_chunk(true);
+    send_response(sender, recipient, response)
+}
+
+fn new_response(
+    service_id: &str,
+    state_root: &str,
+    response_type: StateSyncResponse_Type,
+) -> StateSyncResponse {
+    let mut response = StateSyncResponse::new();
+    response.set_response_type(response_type);
+    response.set_service_id(service_id.into());
+    response.set_state_root(state_root.into());
+    response
+}
+
+fn send_response(
+    sender: &dyn ServiceNetworkSender,
+    recipient: &str,
+    response: StateSyncResponse,
+) -> Result<(), ScabbardError> {
+    let mut msg = ScabbardMessage::new();
+    msg.set_message_type(ScabbardMessage_Type::STATE_SYNC_RESPONSE);
+    msg.set_state_sync_response(response);
+    let msg_bytes = msg
+        .write_to_bytes()
+        .map_err(|err| ScabbardError::Internal(Box::new(err)))?;
+
+    sender
+        .send(recipient, msg_bytes.as_slice())
+        .map_err(|err| ScabbardError::Internal(Box::new(err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::VecDeque;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    use cylinder::{secp256k1::Secp256k1Context, Context, VerifierFactory};
+    use splinter::service::{ServiceMessageContext, ServiceSendError};
+    use tempdir::TempDir;
+    use transact::{
+        families::command::make_command_transaction,
+        protocol::{
+            batch::BatchBuilder,
+            command::{BytesEntry, Command, SetState},
+        },
+    };
+
+    #[cfg(feature = "back-pressure")]
+    use crate::service::ScabbardVersion;
+
+    const TEMP_DB_SIZE: usize = 1 << 30; // 1024 ** 3
+
+    /// Verify that a state root is only adopted once it is reported by a strict majority of the
+    /// peers.
+    #[test]
+    fn quorum_state_root() {
+        let peers = vec!["svc1".to_string(), "svc2".to_string(), "svc3".to_string()];
+        let mut state_sync = StateSync::new(&peers, false);
+        assert_eq!(state_sync.quorum_state_root(3), None);
+
+        state_sync
+            .responses
+            .insert("svc1".into(), ("root1".into(), SyncPayload::None));
+        state_sync
+            .responses
+            .insert("svc2".into(), ("root2".into(), SyncPayload::None));
+        assert_eq!(state_sync.quorum_state_root(3), None);
+
+        state_sync
+            .responses
+            .insert("svc3".into(), ("root1".into(), SyncPayload::None));
+        assert_eq!(state_sync.quorum_state_root(3), Some("root1".into()));
+
+        // Two out of four peers are not a majority
+        assert_eq!(state_sync.quorum_state_root(4), None);
+    }
+
+    /// Verify that a peer's state sync request is answered with the receipts the requester is
+    /// missing, that the peer is told when it's in sync, and that a requester with a receipt this
+    /// service doesn't know about is told so and asked for a state sync in turn.
+    #[test]
+    fn respond_to_state_sync_request() {
+        let temp_dir = TempDir::new("respond_to_state_sync_request").expect("Failed to create dir");
+        let mut state = new_state(temp_dir.path(), "svc0");
+        let initial_state_root = state.current_state_root().to_string();
+        commit_value(&mut state, "abcdef", b"value");
+
+        let sender = MockServiceNetworkSender::new();
+        let mut shared = new_shared(&sender, "svc0", &["svc1", "svc2"]);
+
+        handle_state_sync_request(
+            &new_request("svc1", state.current_state_root(), None),
+            &mut shared,
+            &state,
+        )
+        .expect("Failed to handle request");
+        let (recipient, response) = sender.take_responses().remove(0);
+        assert_eq!(recipient, "svc1");
+        assert_eq!(
+            response.get_response_type(),
+            StateSyncResponse_Type::IN_SYNC
+        );
+
+        handle_state_sync_request(
+            &new_request("svc1", &initial_state_root, None),
+            &mut shared,
+            &state,
+        )
+        .expect("Failed to handle request");
+        let (_, response) = sender.take_responses().remove(0);
+        assert_eq!(
+            response.get_response_type(),
+            StateSyncResponse_Type::RECEIPTS
+        );
+        assert_eq!(response.get_state_root(), state.current_state_root());
+        assert_eq!(response.get_receipts().len(), 1);
+
+        handle_state_sync_request(
+            &new_request("svc1", &initial_state_root, Some("unknown")),
+            &mut shared,
+            &state,
+        )
+        .expect("Failed to handle request");
+        let (_, response) = sender.take_responses().remove(0);
+        assert_eq!(
+            response.get_response_type(),
+            StateSyncResponse_Type::UNKNOWN_RECEIPT
+        );
+        let requests = sender.take_requests();
+        assert_eq!(requests.len(), 2);
+        assert!(shared.state_sync_in_progress());
+    }
+
+    /// Verify that a lagging service only adopts the state root reported by a majority of its
+    /// peers, by replaying the receipts sent by one of them.
+    ///
+    /// 1. Commit a value on a peer's state, which the lagging service's state doesn't have.
+    /// 2. Request a state sync and verify that it is sent to every peer.
+    /// 3. Have one peer report a state root that no other peer reports, and verify that nothing is
+    ///    applied, and that responses from unknown services are ignored.
+    /// 4. Have the two other peers report the peer's state root, and verify that the lagging
+    ///    service has replayed the receipts and is no longer syncing.
+    /// 5. Verify that a response that arrives after the state sync is finished is ignored.
+    #[test]
+    fn sync_with_majority_of_peers() {
+        let temp_dir = TempDir::new("sync_with_majority_of_peers").expect("Failed to create dir");
+        let mut state = new_state(temp_dir.path(), "svc0");
+        let mut peer_state = new_state(temp_dir.path(), "svc1");
+        let mut other_peer_state = new_state(temp_dir.path(), "svc3");
+        commit_value(&mut peer_state, "abcdef", b"value");
+        commit_value(&mut other_peer_state, "abcdef", b"other value");
+        let receipts = peer_state
+            .receipts_since(None)
+            .expect("Failed to get receipts")
+            .expect("Receipts not found");
+        let other_receipts = other_peer_state
+            .receipts_since(None)
+            .expect("Failed to get receipts")
+            .expect("Receipts not found");
+
+        let sender = MockServiceNetworkSender::new();
+        let mut shared = new_shared(&sender, "svc0", &["svc1", "svc2", "svc3"]);
+
+        request_state_sync(&mut shared, &state, false).expect("Failed to request state sync");
+        let mut recipients = sender
+            .take_requests()
+            .into_iter()
+            .map(|(recipient, request)| {
+                assert!(!request.get_snapshot());
+                recipient
+            })
+            .collect::<Vec<_>>();
+        recipients.sort();
+        assert_eq!(recipients, vec!["svc1", "svc2", "svc3"]);
+        assert!(shared.state_sync_in_progress());
+
+        handle_state_sync_response(
+            &new_receipts_response(
+                "svc3",
+                other_peer_state.current_state_root(),
+                &other_receipts,
+            ),
+            &mut shared,
+            &mut state,
+        )
+        .expect("Failed to handle response");
+        handle_state_sync_response(
+            &new_receipts_response("svc4", peer_state.current_state_root(), &receipts),
+            &mut shared,
+            &mut state,
+        )
+        .expect("Failed to handle response");
+        assert!(shared.state_sync_in_progress());
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef")
+                .expect("Failed to get state"),
+            None
+        );
+
+        handle_state_sync_response(
+            &new_receipts_response("svc1", peer_state.current_state_root(), &receipts),
+            &mut shared,
+            &mut state,
+        )
+        .expect("Failed to handle response");
+        assert!(shared.state_sync_in_progress());
+
+        handle_state_sync_response(
+            &new_receipts_response("svc2", peer_state.current_state_root(), &receipts),
+            &mut shared,
+            &mut state,
+        )
+        .expect("Failed to handle response");
+        assert!(!shared.state_sync_in_progress());
+        assert_eq!(state.current_state_root(), peer_state.current_state_root());
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef")
+                .expect("Failed to get state"),
+            Some(b"value".to_vec())
+        );
+
+        handle_state_sync_response(
+            &new_receipts_response(
+                "svc3",
+                other_peer_state.current_state_root(),
+                &other_receipts,
+            ),
+            &mut shared,
+            &mut state,
+        )
+        .expect("Failed to handle response");
+        assert_eq!(state.current_state_root(), peer_state.current_state_root());
+    }
+
+    /// Verify that a snapshot is requested from one of the peers that agree on a state root when
+    /// none of them sent receipts that arrive at it, and that the snapshot sent by that peer is
+    /// applied.
+    ///
+    /// 1. Commit a value on the peers' states, which the lagging service's state doesn't have.
+    /// 2. Request a state sync and have both peers respond that they don't know the lagging
+    ///    service's last receipt.
+    /// 3. Verify that a snapshot is requested from the first of the peers, and that a snapshot
+    ///    from the other peer is ignored.
+    /// 4. Have the first peer send its snapshot and pass it on to the lagging service, and verify
+    ///    that the lagging service now has the peers' state and is no longer syncing.
+    #[test]
+    fn sync_with_snapshot() {
+        let temp_dir = TempDir::new("sync_with_snapshot").expect("Failed to create dir");
+        let mut state = new_state(temp_dir.path(), "svc0");
+        let mut peer_state = new_state(temp_dir.path(), "svc1");
+        commit_value(&mut peer_state, "abcdef", b"value");
+
+        let sender = MockServiceNetworkSender::new();
+        let mut shared = new_shared(&sender, "svc0", &["svc1", "svc2"]);
+        let peer_sender = MockServiceNetworkSender::new();
+        let mut peer_shared = new_shared(&peer_sender, "svc1", &["svc0", "svc2"]);
+
+        request_state_sync(&mut shared, &state, false).expect("Failed to request state sync");
+        sender.take_requests();
+        for peer in &["svc1", "svc2"] {
+            handle_state_sync_response(
+                &new_response(
+                    peer,
+                    peer_state.current_state_root(),
+                    StateSyncResponse_Type::UNKNOWN_RECEIPT,
+                ),
+                &mut shared,
+                &mut state,
+            )
+            .expect("Failed to handle response");
+        }
+
+        let mut requests = sender.take_requests();
+        assert_eq!(requests.len(), 1);
+        let (recipient, request) = requests.remove(0);
+        assert_eq!(recipient, "svc1");
+        assert!(request.get_snapshot());
+
+        let mut unrequested_snapshot = new_response(
+            "svc2",
+            peer_state.current_state_root(),
+            StateSyncResponse_Type::SNAPSHOT,
+        );
+        unrequested_snapshot.set_last_chunk(true);
+        handle_state_sync_response(&unrequested_snapshot, &mut shared, &mut state)
+            .expect("Failed to handle response");
+        assert!(shared.state_sync_in_progress());
+
+        handle_state_sync_request(&request, &mut peer_shared, &peer_state)
+            .expect("Failed to handle request");
+        for (recipient, response) in peer_sender.wait_for_snapshot() {
+            assert_eq!(recipient, "svc0");
+            handle_state_sync_response(&response, &mut shared, &mut state)
+                .expect("Failed to handle response");
+        }
+
+        assert!(!shared.state_sync_in_progress());
+        assert_eq!(state.current_state_root(), peer_state.current_state_root());
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef")
+                .expect("Failed to get state"),
+            Some(b"value".to_vec())
+        );
+    }
+
+    /// Verify that a snapshot whose chunks arrive out of order, or that doesn't arrive at the
+    /// state root the peers agree on, is discarded without changing the service's state.
+    #[test]
+    fn discard_invalid_snapshot() {
+        let temp_dir = TempDir::new("discard_invalid_snapshot").expect("Failed to create dir");
+        let mut state = new_state(temp_dir.path(), "svc0");
+        let initial_state_root = state.current_state_root().to_string();
+        let mut peer_state = new_state(temp_dir.path(), "svc1");
+        commit_value(&mut peer_state, "abcdef", b"value");
+
+        let sender = MockServiceNetworkSender::new();
+        let mut shared = new_shared(&sender, "svc0", &["svc1", "svc2"]);
+
+        // Chunk 1 arrives before chunk 0
+        start_snapshot_sync(
+            &mut shared,
+            &mut state,
+            &sender,
+            peer_state.current_state_root(),
+        );
+        let chunk = new_snapshot_chunk(peer_state.current_state_root(), 1, true);
+        handle_state_sync_response(&chunk, &mut shared, &mut state)
+            .expect("Failed to handle response");
+        assert!(!shared.state_sync_in_progress());
+        assert_eq!(state.current_state_root(), initial_state_root);
+
+        // The snapshot's entries don't arrive at the state root the peers agree on
+        start_snapshot_sync(
+            &mut shared,
+            &mut state,
+            &sender,
+            peer_state.current_state_root(),
+        );
+        let chunk = new_snapshot_chunk(peer_state.current_state_root(), 0, false);
+        handle_state_sync_response(&chunk, &mut shared, &mut state)
+            .expect("Failed to handle response");
+        assert!(shared.state_sync_in_progress());
+        let chunk = new_snapshot_chunk(peer_state.current_state_root(), 1, true);
+        handle_state_sync_response(&chunk, &mut shared, &mut state)
+            .expect("Failed to handle response");
+        assert!(!shared.state_sync_in_progress());
+        assert_eq!(state.current_state_root(), initial_state_root);
+        assert!(!temp_dir.path().join("svc0-state.lmdb-snapshot").exists());
+    }
+
+    /// Verify that a state sync that hasn't made progress for too long no longer keeps the
+    /// service out of two-phase commit, that responses to it are ignored and that a new state
+    /// sync can be requested.
+    #[test]
+    fn state_sync_timeout() {
+        let temp_dir = TempDir::new("state_sync_timeout").expect("Failed to create dir");
+        let mut state = new_state(temp_dir.path(), "svc0");
+        let initial_state_root = state.current_state_root().to_string();
+        let mut peer_state = new_state(temp_dir.path(), "svc1");
+        commit_value(&mut peer_state, "abcdef", b"value");
+        let receipts = peer_state
+            .receipts_since(None)
+            .expect("Failed to get receipts")
+            .expect("Receipts not found");
+
+        let sender = MockServiceNetworkSender::new();
+        let mut shared = new_shared(&sender, "svc0", &["svc1"]);
+
+        request_state_sync(&mut shared, &state, false).expect("Failed to request state sync");
+        assert_eq!(sender.take_requests().len(), 1);
+        request_state_sync(&mut shared, &state, false).expect("Failed to request state sync");
+        assert!(sender.take_requests().is_empty());
+
+        shared
+            .state_sync_mut()
+            .expect("State sync not in progress")
+            .last_progress = Instant::now()
+            .checked_sub(STATE_SYNC_TIMEOUT)
+            .expect("Failed to compute timed out instant");
+        assert!(!shared.state_sync_in_progress());
+
+        handle_state_sync_response(
+            &new_receipts_response("svc1", peer_state.current_state_root(), &receipts),
+            &mut shared,
+            &mut state,
+        )
+        .expect("Failed to handle response");
+        assert_eq!(state.current_state_root(), initial_state_root);
+
+        request_state_sync(&mut shared, &state, false).expect("Failed to request state sync");
+        assert_eq!(sender.take_requests().len(), 1);
+        assert!(shared.state_sync_in_progress());
+    }
+
+    /// Requests a state sync with a snapshot, has both peers report the given state root and
+    /// verifies that a snapshot is requested from the first peer.
+    fn start_snapshot_sync(
+        shared: &mut ScabbardShared,
+        state: &mut ScabbardState,
+        sender: &MockServiceNetworkSender,
+        state_root: &str,
+    ) {
+        request_state_sync(shared, state, true).expect("Failed to request state sync");
+        sender.take_requests();
+        for peer in &["svc1", "svc2"] {
+            handle_state_sync_response(
+                &new_response(peer, state_root, StateSyncResponse_Type::IN_SYNC),
+                shared,
+                state,
+            )
+            .expect("Failed to handle response");
+        }
+
+        let requests = sender.take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].0, "svc1");
+        assert!(requests[0].1.get_snapshot());
+    }
+
+    fn new_state(dir: &Path, service_id: &str) -> ScabbardState {
+        ScabbardState::new(
+            &dir.join(format!("{}-state.lmdb", service_id)),
+            TEMP_DB_SIZE,
+            &dir.join(format!("{}-receipts.lmdb", service_id)),
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state")
+    }
+
+    fn new_shared(
+        sender: &MockServiceNetworkSender,
+        service_id: &str,
+        peers: &[&str],
+    ) -> ScabbardShared {
+        ScabbardShared::new(
+            VecDeque::new(),
+            Some(Box::new(sender.clone())),
+            peers.iter().map(ToString::to_string).collect(),
+            service_id.to_string(),
+            Secp256k1Context::new().new_verifier(),
+            #[cfg(feature = "back-pressure")]
+            ScabbardVersion::V2,
+        )
+    }
+
+    fn commit_value(state: &mut ScabbardState, address: &str, value: &[u8]) {
+        let signing_context = Secp256k1Context::new();
+        let signer = signing_context.new_signer(signing_context.new_random_private_key());
+        let batch = BatchBuilder::new()
+            .with_transactions(vec![
+                make_command_transaction(
+                    &[Command::SetState(SetState::new(vec![BytesEntry::new(
+                        address.to_string(),
+                        value.to_vec(),
+                    )]))],
+                    &*signer,
+                )
+                .take()
+                .0,
+            ])
+            .build_pair(&*signer)
+            .expect("Failed to build batch");
+        state
+            .prepare_change(batch)
+            .expect("Failed to prepare change");
+        state.commit().expect("Failed to commit change");
+    }
+
+    fn new_request(
+        service_id: &str,
+        state_root: &str,
+        last_receipt_id: Option<&str>,
+    ) -> StateSyncRequest {
+        let mut request = StateSyncRequest::new();
+        request.set_service_id(service_id.into());
+        request.set_current_state_root(state_root.into());
+        if let Some(last_receipt_id) = last_receipt_id {
+            request.set_last_receipt_id(last_receipt_id.into());
+        }
+        request
+    }
+
+    fn new_receipts_response(
+        service_id: &str,
+        state_root: &str,
+        receipts: &[TransactionReceipt],
+    ) -> StateSyncResponse {
+        let mut response = new_response(service_id, state_root, StateSyncResponse_Type::RECEIPTS);
+        response.set_receipts(
+            receipts
+                .iter()
+                .cloned()
+                .map(|receipt| receipt.into_bytes().expect("Failed to serialize receipt"))
+                .collect::<Vec<_>>()
+                .into(),
+        );
+        response
+    }
+
+    /// Creates a chunk of a snapshot from the service "svc1", containing a single entry that is
+    /// not in the peers' state.
+    fn new_snapshot_chunk(state_root: &str, chunk: u32, last_chunk: bool) -> StateSyncResponse {
+        let mut entry = StateEntry::new();
+        entry.set_address(format!("01234{}", chunk));
+        entry.set_value(b"bogus".to_vec());
+
+        let mut response = new_response("svc1", state_root, StateSyncResponse_Type::SNAPSHOT);
+        response.set_entries(vec![entry].into());
+        response.set_chunk(chunk);
+        response.set_last_chunk(last_chunk);
+        response
+    }
+
+    /// Records the messages sent by a service, so that they can be passed on to its peers.
+    #[derive(Clone)]
+    struct MockServiceNetworkSender {
+        sent: Arc<Mutex<Vec<(String, ScabbardMessage)>>>,
+    }
+
+    impl MockServiceNetworkSender {
+        fn new() -> Self {
+            MockServiceNetworkSender {
+                sent: Arc::new(Mutex::new(vec![])),
+            }
+        }
+
+        /// Removes the sent messages of the given type and returns them, along with their
+        /// recipients.
+        fn take_sent(&self, message_type: ScabbardMessage_Type) -> Vec<(String, ScabbardMessage)> {
+            let mut sent = self.sent.lock().expect("sent lock poisoned");
+            let (taken, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut *sent)
+                .into_iter()
+                .partition(|(_, msg)| msg.get_message_type() == message_type);
+            *sent = kept;
+            taken
+        }
+
+        fn take_requests(&self) -> Vec<(String, StateSyncRequest)> {
+            self.take_sent(ScabbardMessage_Type::STATE_SYNC_REQUEST)
+                .into_iter()
+                .map(|(recipient, mut msg)| (recipient, msg.take_state_sync_request()))
+                .collect()
+        }
+
+        fn take_responses(&self) -> Vec<(String, StateSyncResponse)> {
+            self.take_sent(ScabbardMessage_Type::STATE_SYNC_RESPONSE)
+                .into_iter()
+                .map(|(recipient, mut msg)| (recipient, msg.take_state_sync_response()))
+                .collect()
+        }
+
+        /// Waits for the last chunk of a snapshot, which is sent from a background thread, and
+        /// returns all of the snapshot's chunks.
+        fn wait_for_snapshot(&self) -> Vec<(String, StateSyncResponse)> {
+            let start = Instant::now();
+            let mut responses = vec![];
+            while !responses
+                .iter()
+                .any(|(_, response): &(String, StateSyncResponse)| response.get_last_chunk())
+            {
+                assert!(
+                    start.elapsed() < Duration::from_secs(10),
+                    "Timed out waiting for snapshot"
+                );
+                thread::sleep(Duration::from_millis(10));
+                responses.extend(self.take_responses());
+            }
+            responses
+        }
+    }
+
+    impl ServiceNetworkSender for MockServiceNetworkSender {
+        fn send(&self, recipient: &str, message: &[u8]) -> Result<(), ServiceSendError> {
+            let msg = ScabbardMessage::parse_from_bytes(message).expect("Failed to parse message");
+            self.sent
+                .lock()
+                .expect("sent lock poisoned")
+                .push((recipient.to_string(), msg));
+            Ok(())
+        }
+
+        fn send_and_await(
+            &self,
+            _recipient: &str,
+            _message: &[u8],
+        ) -> Result<Vec<u8>, ServiceSendError> {
+            unimplemented!()
+        }
+
+        fn reply(
+            &self,
+            _message_origin: &ServiceMessageContext,
+            _message: &[u8],
+        ) -> Result<(), ServiceSendError> {
+            unimplemented!()
+        }
+
+        fn clone_box(&self) -> Box<dyn ServiceNetworkSender> {
+            Box::new(self.clone())
+        }
+    }
+}
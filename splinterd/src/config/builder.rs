@@ -331,6 +331,31 @@ impl ConfigBuilder {
                 .partial_configs
                 .iter()
                 .find_map(|p| p.oauth_openid_scopes().map(|v| (v, p.source()))),
+            #[cfg(feature = "oauth-azure")]
+            oauth_azure_tenant: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.oauth_azure_tenant().map(|v| (v, p.source()))),
+            #[cfg(feature = "rest-api-rate-limit")]
+            rest_api_rate_limit: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.rest_api_rate_limit().map(|v| (v, p.source()))),
+            #[cfg(feature = "rest-api-rate-limit")]
+            rest_api_rate_limit_overrides: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.rest_api_rate_limit_overrides().map(|v| (v, p.source()))),
+            #[cfg(feature = "https-client-auth")]
+            tls_rest_api_client_ca: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.tls_rest_api_client_ca().map(|v| (v, p.source()))),
+            #[cfg(feature = "https-client-auth")]
+            rest_api_client_cert_identity: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.rest_api_client_cert_identity().map(|v| (v, p.source()))),
             strict_ref_counts: self
                 .partial_configs
                 .iter()
@@ -356,6 +381,46 @@ impl ConfigBuilder {
                 .partial_configs
                 .iter()
                 .find_map(|p| p.metrics_password().map(|v| (v, p.source()))),
+            #[cfg(feature = "unix-transport")]
+            unix_socket_permissions: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.unix_socket_permissions().map(|v| (v, p.source()))),
+            #[cfg(feature = "unix-transport")]
+            unix_socket_allowed_uids: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.unix_socket_allowed_uids().map(|v| (v, p.source()))),
+            #[cfg(feature = "unix-transport")]
+            unix_socket_allowed_gids: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.unix_socket_allowed_gids().map(|v| (v, p.source()))),
+            #[cfg(feature = "tls-reload")]
+            tls_reload_interval: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.tls_reload_interval().map(|v| (v, p.source()))),
+            #[cfg(feature = "registry-remote-signing")]
+            registry_public_key: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.registry_public_key().map(|v| (v, p.source()))),
+            #[cfg(feature = "transport-compression")]
+            transport_compression: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.transport_compression().map(|v| (v, p.source()))),
+            #[cfg(feature = "scabbard-batch-status-store")]
+            scabbard_batch_status_retention: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.scabbard_batch_status_retention().map(|v| (v, p.source()))),
+            #[cfg(feature = "scabbard-state-pruning")]
+            scabbard_state_retention_depth: self
+                .partial_configs
+                .iter()
+                .find_map(|p| p.scabbard_state_retention_depth().map(|v| (v, p.source()))),
         })
     }
 }
@@ -33,6 +33,42 @@ fn parse_value(matches: &ArgMatches, arg: &str) -> Result<Option<u64>, ConfigErr
     }
 }
 
+// Parses a Unix file mode, written in octal, from a clap argument.
+#[cfg(feature = "unix-transport")]
+fn parse_mode(matches: &ArgMatches, arg: &str) -> Result<Option<u32>, ConfigError> {
+    matches
+        .value_of(arg)
+        .map(|value| {
+            u32::from_str_radix(value, 8).map_err(|_| {
+                ConfigError::InvalidArgument(format!(
+                    "{} must be an octal file mode, such as 660: {}",
+                    arg, value
+                ))
+            })
+        })
+        .transpose()
+}
+
+// Parses a list of user or group IDs from a clap argument.
+#[cfg(feature = "unix-transport")]
+fn parse_ids(matches: &ArgMatches, arg: &str) -> Result<Option<Vec<u32>>, ConfigError> {
+    matches
+        .values_of(arg)
+        .map(|values| {
+            values
+                .map(|value| {
+                    value.parse::<u32>().map_err(|_| {
+                        ConfigError::InvalidArgument(format!(
+                            "{} must be a numeric ID: {}",
+                            arg, value
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()
+}
+
 impl<'a> ClapPartialConfigBuilder<'a> {
     pub fn new(matches: ArgMatches<'a>) -> Self {
         ClapPartialConfigBuilder { matches }
@@ -171,6 +207,45 @@ impl<'a> PartialConfigBuilder for ClapPartialConfigBuilder<'_> {
                 )
         }
 
+        #[cfg(feature = "oauth-azure")]
+        {
+            partial_config = partial_config.with_oauth_azure_tenant(
+                self.matches
+                    .value_of("oauth_azure_tenant")
+                    .map(String::from),
+            )
+        }
+
+        #[cfg(feature = "rest-api-rate-limit")]
+        {
+            partial_config = partial_config
+                .with_rest_api_rate_limit(
+                    self.matches
+                        .value_of("rest_api_rate_limit")
+                        .map(String::from),
+                )
+                .with_rest_api_rate_limit_overrides(
+                    self.matches
+                        .values_of("rest_api_rate_limit_overrides")
+                        .map(|values| values.map(String::from).collect()),
+                )
+        }
+
+        #[cfg(feature = "https-client-auth")]
+        {
+            partial_config = partial_config
+                .with_tls_rest_api_client_ca(
+                    self.matches
+                        .value_of("tls_rest_api_client_ca")
+                        .map(String::from),
+                )
+                .with_rest_api_client_cert_identity(
+                    self.matches
+                        .value_of("rest_api_client_cert_identity")
+                        .map(String::from),
+                )
+        }
+
         #[cfg(feature = "metrics")]
         {
             partial_config = partial_config
@@ -180,6 +255,62 @@ impl<'a> PartialConfigBuilder for ClapPartialConfigBuilder<'_> {
                 .with_metrics_password(self.matches.value_of("metrics_password").map(String::from))
         }
 
+        #[cfg(feature = "unix-transport")]
+        {
+            partial_config = partial_config
+                .with_unix_socket_permissions(parse_mode(&self.matches, "unix_socket_permissions")?)
+                .with_unix_socket_allowed_uids(parse_ids(
+                    &self.matches,
+                    "unix_socket_allowed_uids",
+                )?)
+                .with_unix_socket_allowed_gids(parse_ids(
+                    &self.matches,
+                    "unix_socket_allowed_gids",
+                )?)
+        }
+
+        #[cfg(feature = "tls-reload")]
+        {
+            partial_config = partial_config
+                .with_tls_reload_interval(parse_value(&self.matches, "tls_reload_interval")?)
+        }
+
+        #[cfg(feature = "registry-remote-signing")]
+        {
+            partial_config = partial_config.with_registry_public_key(
+                self.matches
+                    .value_of("registry_public_key")
+                    .map(String::from),
+            )
+        }
+
+        #[cfg(feature = "transport-compression")]
+        {
+            partial_config = partial_config.with_transport_compression(
+                if self.matches.is_present("transport_compression") {
+                    Some(true)
+                } else {
+                    None
+                },
+            )
+        }
+
+        #[cfg(feature = "scabbard-batch-status-store")]
+        {
+            partial_config = partial_config.with_scabbard_batch_status_retention(
+                parse_value(&self.matches, "scabbard_batch_status_retention")?
+                    .map(|retention| retention as usize),
+            )
+        }
+
+        #[cfg(feature = "scabbard-state-pruning")]
+        {
+            partial_config = partial_config.with_scabbard_state_retention_depth(
+                parse_value(&self.matches, "scabbard_state_retention_depth")?
+                    .map(|depth| depth as usize),
+            )
+        }
+
         Ok(partial_config)
     }
 }
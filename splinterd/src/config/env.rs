@@ -35,6 +35,10 @@ const OAUTH_CLIENT_SECRET_ENV: &str = "OAUTH_CLIENT_SECRET";
 const OAUTH_REDIRECT_URL_ENV: &str = "OAUTH_REDIRECT_URL";
 #[cfg(feature = "oauth")]
 const OAUTH_OPENID_URL_ENV: &str = "OAUTH_OPENID_URL";
+#[cfg(feature = "oauth-azure")]
+const OAUTH_AZURE_TENANT_ENV: &str = "OAUTH_AZURE_TENANT";
+#[cfg(feature = "rest-api-rate-limit")]
+const REST_API_RATE_LIMIT_ENV: &str = "SPLINTER_REST_API_RATE_LIMIT";
 #[cfg(feature = "metrics")]
 const METRICS_DB_ENV: &str = "SPLINTER_METRICS_DB";
 #[cfg(feature = "metrics")]
@@ -125,6 +129,16 @@ impl PartialConfigBuilder for EnvPartialConfigBuilder {
                 .with_oauth_openid_url(env::var(OAUTH_OPENID_URL_ENV).ok());
         }
 
+        #[cfg(feature = "oauth-azure")]
+        {
+            config = config.with_oauth_azure_tenant(env::var(OAUTH_AZURE_TENANT_ENV).ok());
+        }
+
+        #[cfg(feature = "rest-api-rate-limit")]
+        {
+            config = config.with_rest_api_rate_limit(env::var(REST_API_RATE_LIMIT_ENV).ok());
+        }
+
         #[cfg(feature = "metrics")]
         {
             config = config
@@ -87,6 +87,16 @@ pub struct Config {
     oauth_openid_auth_params: Option<(Vec<(String, String)>, ConfigSource)>,
     #[cfg(feature = "oauth")]
     oauth_openid_scopes: Option<(Vec<String>, ConfigSource)>,
+    #[cfg(feature = "oauth-azure")]
+    oauth_azure_tenant: Option<(String, ConfigSource)>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit: Option<(String, ConfigSource)>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit_overrides: Option<(Vec<String>, ConfigSource)>,
+    #[cfg(feature = "https-client-auth")]
+    tls_rest_api_client_ca: Option<(String, ConfigSource)>,
+    #[cfg(feature = "https-client-auth")]
+    rest_api_client_cert_identity: Option<(String, ConfigSource)>,
     strict_ref_counts: (bool, ConfigSource),
     #[cfg(feature = "metrics")]
     metrics_db: Option<(String, ConfigSource)>,
@@ -96,6 +106,22 @@ pub struct Config {
     metrics_username: Option<(String, ConfigSource)>,
     #[cfg(feature = "metrics")]
     metrics_password: Option<(String, ConfigSource)>,
+    #[cfg(feature = "unix-transport")]
+    unix_socket_permissions: Option<(u32, ConfigSource)>,
+    #[cfg(feature = "unix-transport")]
+    unix_socket_allowed_uids: Option<(Vec<u32>, ConfigSource)>,
+    #[cfg(feature = "unix-transport")]
+    unix_socket_allowed_gids: Option<(Vec<u32>, ConfigSource)>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: Option<(u64, ConfigSource)>,
+    #[cfg(feature = "registry-remote-signing")]
+    registry_public_key: Option<(String, ConfigSource)>,
+    #[cfg(feature = "transport-compression")]
+    transport_compression: Option<(bool, ConfigSource)>,
+    #[cfg(feature = "scabbard-batch-status-store")]
+    scabbard_batch_status_retention: Option<(usize, ConfigSource)>,
+    #[cfg(feature = "scabbard-state-pruning")]
+    scabbard_state_retention_depth: Option<(usize, ConfigSource)>,
 }
 
 impl Config {
@@ -295,6 +321,51 @@ impl Config {
         }
     }
 
+    #[cfg(feature = "oauth-azure")]
+    pub fn oauth_azure_tenant(&self) -> Option<&str> {
+        if let Some((tenant, _)) = &self.oauth_azure_tenant {
+            Some(tenant)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    pub fn rest_api_rate_limit(&self) -> Option<&str> {
+        if let Some((limit, _)) = &self.rest_api_rate_limit {
+            Some(limit)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    pub fn rest_api_rate_limit_overrides(&self) -> Option<&[String]> {
+        if let Some((overrides, _)) = &self.rest_api_rate_limit_overrides {
+            Some(overrides)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    pub fn tls_rest_api_client_ca(&self) -> Option<&str> {
+        if let Some((client_ca, _)) = &self.tls_rest_api_client_ca {
+            Some(client_ca)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    pub fn rest_api_client_cert_identity(&self) -> Option<&str> {
+        if let Some((mapping, _)) = &self.rest_api_client_cert_identity {
+            Some(mapping)
+        } else {
+            None
+        }
+    }
+
     pub fn strict_ref_counts(&self) -> bool {
         self.strict_ref_counts.0
     }
@@ -335,6 +406,78 @@ impl Config {
         }
     }
 
+    #[cfg(feature = "unix-transport")]
+    pub fn unix_socket_permissions(&self) -> Option<u32> {
+        if let Some((permissions, _)) = &self.unix_socket_permissions {
+            Some(*permissions)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "unix-transport")]
+    pub fn unix_socket_allowed_uids(&self) -> Option<&[u32]> {
+        if let Some((uids, _)) = &self.unix_socket_allowed_uids {
+            Some(uids)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "unix-transport")]
+    pub fn unix_socket_allowed_gids(&self) -> Option<&[u32]> {
+        if let Some((gids, _)) = &self.unix_socket_allowed_gids {
+            Some(gids)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "tls-reload")]
+    pub fn tls_reload_interval(&self) -> Option<u64> {
+        if let Some((interval, _)) = &self.tls_reload_interval {
+            Some(*interval)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-remote-signing")]
+    pub fn registry_public_key(&self) -> Option<&str> {
+        if let Some((key, _)) = &self.registry_public_key {
+            Some(key)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "transport-compression")]
+    pub fn transport_compression(&self) -> bool {
+        if let Some((enabled, _)) = &self.transport_compression {
+            *enabled
+        } else {
+            false
+        }
+    }
+
+    #[cfg(feature = "scabbard-batch-status-store")]
+    pub fn scabbard_batch_status_retention(&self) -> Option<usize> {
+        if let Some((retention, _)) = &self.scabbard_batch_status_retention {
+            Some(*retention)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "scabbard-state-pruning")]
+    pub fn scabbard_state_retention_depth(&self) -> Option<usize> {
+        if let Some((depth, _)) = &self.scabbard_state_retention_depth {
+            Some(*depth)
+        } else {
+            None
+        }
+    }
+
     pub fn config_dir_source(&self) -> &ConfigSource {
         &self.config_dir.1
     }
@@ -531,6 +674,51 @@ impl Config {
         }
     }
 
+    #[cfg(feature = "oauth-azure")]
+    pub fn oauth_azure_tenant_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.oauth_azure_tenant {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    fn rest_api_rate_limit_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.rest_api_rate_limit {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    fn rest_api_rate_limit_overrides_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.rest_api_rate_limit_overrides {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    fn tls_rest_api_client_ca_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.tls_rest_api_client_ca {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    fn rest_api_client_cert_identity_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.rest_api_client_cert_identity {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
     fn strict_ref_counts_source(&self) -> &ConfigSource {
         &self.strict_ref_counts.1
     }
@@ -571,6 +759,78 @@ impl Config {
         }
     }
 
+    #[cfg(feature = "unix-transport")]
+    pub fn unix_socket_permissions_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.unix_socket_permissions {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "unix-transport")]
+    pub fn unix_socket_allowed_uids_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.unix_socket_allowed_uids {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "unix-transport")]
+    pub fn unix_socket_allowed_gids_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.unix_socket_allowed_gids {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "tls-reload")]
+    pub fn tls_reload_interval_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.tls_reload_interval {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-remote-signing")]
+    pub fn registry_public_key_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.registry_public_key {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "transport-compression")]
+    pub fn transport_compression_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.transport_compression {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "scabbard-batch-status-store")]
+    pub fn scabbard_batch_status_retention_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.scabbard_batch_status_retention {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "scabbard-state-pruning")]
+    pub fn scabbard_state_retention_depth_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.scabbard_state_retention_depth {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
     #[allow(clippy::cognitive_complexity)]
     /// Displays the configuration value along with where the value was sourced from.
     pub fn log_as_debug(&self) {
@@ -769,6 +1029,59 @@ impl Config {
                 debug!("Config: oauth_scopes: {:?} (source: {:?})", scopes, source,);
             }
         }
+        #[cfg(feature = "oauth-azure")]
+        {
+            if let (Some(tenant), Some(source)) =
+                (self.oauth_azure_tenant(), self.oauth_azure_tenant_source())
+            {
+                debug!(
+                    "Config: oauth_azure_tenant: {} (source: {:?})",
+                    tenant, source,
+                );
+            }
+        }
+        #[cfg(feature = "rest-api-rate-limit")]
+        {
+            if let (Some(limit), Some(source)) = (
+                self.rest_api_rate_limit(),
+                self.rest_api_rate_limit_source(),
+            ) {
+                debug!(
+                    "Config: rest_api_rate_limit: {} (source: {:?})",
+                    limit, source,
+                );
+            }
+            if let (Some(overrides), Some(source)) = (
+                self.rest_api_rate_limit_overrides(),
+                self.rest_api_rate_limit_overrides_source(),
+            ) {
+                debug!(
+                    "Config: rest_api_rate_limit_overrides: {:?} (source: {:?})",
+                    overrides, source,
+                );
+            }
+        }
+        #[cfg(feature = "https-client-auth")]
+        {
+            if let (Some(client_ca), Some(source)) = (
+                self.tls_rest_api_client_ca(),
+                self.tls_rest_api_client_ca_source(),
+            ) {
+                debug!(
+                    "Config: tls_rest_api_client_ca: {} (source: {:?})",
+                    client_ca, source,
+                );
+            }
+            if let (Some(mapping), Some(source)) = (
+                self.rest_api_client_cert_identity(),
+                self.rest_api_client_cert_identity_source(),
+            ) {
+                debug!(
+                    "Config: rest_api_client_cert_identity: {} (source: {:?})",
+                    mapping, source,
+                );
+            }
+        }
         debug!(
             "Config: strict_ref_counts: {:?} (source: {:?})",
             self.strict_ref_counts(),
@@ -799,6 +1112,96 @@ impl Config {
                 debug!("Config: metrics_password: <HIDDEN> (source: {:?})", source,);
             }
         }
+        #[cfg(feature = "unix-transport")]
+        {
+            if let (Some(permissions), Some(source)) = (
+                self.unix_socket_permissions(),
+                self.unix_socket_permissions_source(),
+            ) {
+                debug!(
+                    "Config: unix_socket_permissions: {:o} (source: {:?})",
+                    permissions, source,
+                );
+            }
+
+            if let (Some(uids), Some(source)) = (
+                self.unix_socket_allowed_uids(),
+                self.unix_socket_allowed_uids_source(),
+            ) {
+                debug!(
+                    "Config: unix_socket_allowed_uids: {:?} (source: {:?})",
+                    uids, source,
+                );
+            }
+
+            if let (Some(gids), Some(source)) = (
+                self.unix_socket_allowed_gids(),
+                self.unix_socket_allowed_gids_source(),
+            ) {
+                debug!(
+                    "Config: unix_socket_allowed_gids: {:?} (source: {:?})",
+                    gids, source,
+                );
+            }
+        }
+        #[cfg(feature = "tls-reload")]
+        {
+            if let (Some(interval), Some(source)) = (
+                self.tls_reload_interval(),
+                self.tls_reload_interval_source(),
+            ) {
+                debug!(
+                    "Config: tls_reload_interval: {} (source: {:?})",
+                    interval, source,
+                );
+            }
+        }
+        #[cfg(feature = "registry-remote-signing")]
+        {
+            if let (Some(key), Some(source)) = (
+                self.registry_public_key(),
+                self.registry_public_key_source(),
+            ) {
+                debug!(
+                    "Config: registry_public_key: {} (source: {:?})",
+                    key, source,
+                );
+            }
+        }
+        #[cfg(feature = "transport-compression")]
+        {
+            if let Some(source) = self.transport_compression_source() {
+                debug!(
+                    "Config: transport_compression: {} (source: {:?})",
+                    self.transport_compression(),
+                    source,
+                );
+            }
+        }
+        #[cfg(feature = "scabbard-batch-status-store")]
+        {
+            if let (Some(retention), Some(source)) = (
+                self.scabbard_batch_status_retention(),
+                self.scabbard_batch_status_retention_source(),
+            ) {
+                debug!(
+                    "Config: scabbard_batch_status_retention: {} (source: {:?})",
+                    retention, source,
+                );
+            }
+        }
+        #[cfg(feature = "scabbard-state-pruning")]
+        {
+            if let (Some(depth), Some(source)) = (
+                self.scabbard_state_retention_depth(),
+                self.scabbard_state_retention_depth_source(),
+            ) {
+                debug!(
+                    "Config: scabbard_state_retention_depth: {} (source: {:?})",
+                    depth, source,
+                );
+            }
+        }
     }
 
     #[cfg(feature = "rest-api-cors")]
@@ -80,6 +80,16 @@ pub struct PartialConfig {
     oauth_openid_auth_params: Option<Vec<(String, String)>>,
     #[cfg(feature = "oauth")]
     oauth_openid_scopes: Option<Vec<String>>,
+    #[cfg(feature = "oauth-azure")]
+    oauth_azure_tenant: Option<String>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit: Option<String>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit_overrides: Option<Vec<String>>,
+    #[cfg(feature = "https-client-auth")]
+    tls_rest_api_client_ca: Option<String>,
+    #[cfg(feature = "https-client-auth")]
+    rest_api_client_cert_identity: Option<String>,
     strict_ref_counts: Option<bool>,
     #[cfg(feature = "metrics")]
     metrics_db: Option<String>,
@@ -89,6 +99,22 @@ pub struct PartialConfig {
     metrics_username: Option<String>,
     #[cfg(feature = "metrics")]
     metrics_password: Option<String>,
+    #[cfg(feature = "unix-transport")]
+    unix_socket_permissions: Option<u32>,
+    #[cfg(feature = "unix-transport")]
+    unix_socket_allowed_uids: Option<Vec<u32>>,
+    #[cfg(feature = "unix-transport")]
+    unix_socket_allowed_gids: Option<Vec<u32>>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: Option<u64>,
+    #[cfg(feature = "registry-remote-signing")]
+    registry_public_key: Option<String>,
+    #[cfg(feature = "transport-compression")]
+    transport_compression: Option<bool>,
+    #[cfg(feature = "scabbard-batch-status-store")]
+    scabbard_batch_status_retention: Option<usize>,
+    #[cfg(feature = "scabbard-state-pruning")]
+    scabbard_state_retention_depth: Option<usize>,
 }
 
 impl PartialConfig {
@@ -143,6 +169,16 @@ impl PartialConfig {
             oauth_openid_auth_params: None,
             #[cfg(feature = "oauth")]
             oauth_openid_scopes: None,
+            #[cfg(feature = "oauth-azure")]
+            oauth_azure_tenant: None,
+            #[cfg(feature = "rest-api-rate-limit")]
+            rest_api_rate_limit: None,
+            #[cfg(feature = "rest-api-rate-limit")]
+            rest_api_rate_limit_overrides: None,
+            #[cfg(feature = "https-client-auth")]
+            tls_rest_api_client_ca: None,
+            #[cfg(feature = "https-client-auth")]
+            rest_api_client_cert_identity: None,
             strict_ref_counts: None,
             #[cfg(feature = "metrics")]
             metrics_db: None,
@@ -152,6 +188,22 @@ impl PartialConfig {
             metrics_username: None,
             #[cfg(feature = "metrics")]
             metrics_password: None,
+            #[cfg(feature = "unix-transport")]
+            unix_socket_permissions: None,
+            #[cfg(feature = "unix-transport")]
+            unix_socket_allowed_uids: None,
+            #[cfg(feature = "unix-transport")]
+            unix_socket_allowed_gids: None,
+            #[cfg(feature = "tls-reload")]
+            tls_reload_interval: None,
+            #[cfg(feature = "registry-remote-signing")]
+            registry_public_key: None,
+            #[cfg(feature = "transport-compression")]
+            transport_compression: None,
+            #[cfg(feature = "scabbard-batch-status-store")]
+            scabbard_batch_status_retention: None,
+            #[cfg(feature = "scabbard-state-pruning")]
+            scabbard_state_retention_depth: None,
         }
     }
 
@@ -311,6 +363,31 @@ impl PartialConfig {
         self.oauth_openid_scopes.clone()
     }
 
+    #[cfg(feature = "oauth-azure")]
+    pub fn oauth_azure_tenant(&self) -> Option<String> {
+        self.oauth_azure_tenant.clone()
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    pub fn rest_api_rate_limit(&self) -> Option<String> {
+        self.rest_api_rate_limit.clone()
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    pub fn rest_api_rate_limit_overrides(&self) -> Option<Vec<String>> {
+        self.rest_api_rate_limit_overrides.clone()
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    pub fn tls_rest_api_client_ca(&self) -> Option<String> {
+        self.tls_rest_api_client_ca.clone()
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    pub fn rest_api_client_cert_identity(&self) -> Option<String> {
+        self.rest_api_client_cert_identity.clone()
+    }
+
     pub fn strict_ref_counts(&self) -> Option<bool> {
         self.strict_ref_counts
     }
@@ -335,6 +412,46 @@ impl PartialConfig {
         self.metrics_password.clone()
     }
 
+    #[cfg(feature = "unix-transport")]
+    pub fn unix_socket_permissions(&self) -> Option<u32> {
+        self.unix_socket_permissions
+    }
+
+    #[cfg(feature = "unix-transport")]
+    pub fn unix_socket_allowed_uids(&self) -> Option<Vec<u32>> {
+        self.unix_socket_allowed_uids.clone()
+    }
+
+    #[cfg(feature = "unix-transport")]
+    pub fn unix_socket_allowed_gids(&self) -> Option<Vec<u32>> {
+        self.unix_socket_allowed_gids.clone()
+    }
+
+    #[cfg(feature = "tls-reload")]
+    pub fn tls_reload_interval(&self) -> Option<u64> {
+        self.tls_reload_interval
+    }
+
+    #[cfg(feature = "registry-remote-signing")]
+    pub fn registry_public_key(&self) -> Option<String> {
+        self.registry_public_key.clone()
+    }
+
+    #[cfg(feature = "transport-compression")]
+    pub fn transport_compression(&self) -> Option<bool> {
+        self.transport_compression
+    }
+
+    #[cfg(feature = "scabbard-batch-status-store")]
+    pub fn scabbard_batch_status_retention(&self) -> Option<usize> {
+        self.scabbard_batch_status_retention
+    }
+
+    #[cfg(feature = "scabbard-state-pruning")]
+    pub fn scabbard_state_retention_depth(&self) -> Option<usize> {
+        self.scabbard_state_retention_depth
+    }
+
     /// Adds a `config_dir` value to the `PartialConfig` object.
     ///
     /// # Arguments
@@ -742,6 +859,76 @@ impl PartialConfig {
         self
     }
 
+    #[cfg(feature = "oauth-azure")]
+    /// Adds an `oauth_azure_tenant` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `oauth_azure_tenant` - The Azure AD tenant for the REST API OAuth Azure configuration
+    ///
+    pub fn with_oauth_azure_tenant(mut self, oauth_azure_tenant: Option<String>) -> Self {
+        self.oauth_azure_tenant = oauth_azure_tenant;
+        self
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    /// Adds a `rest_api_rate_limit` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `rest_api_rate_limit` - The default rate limit of REST API requests made by each
+    ///   identity to each permission, formatted as `<requests>/<seconds>`
+    ///
+    pub fn with_rest_api_rate_limit(mut self, rest_api_rate_limit: Option<String>) -> Self {
+        self.rest_api_rate_limit = rest_api_rate_limit;
+        self
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    /// Adds a `rest_api_rate_limit_overrides` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `rest_api_rate_limit_overrides` - Rate limits for specific permissions or identities,
+    ///   formatted as `permission:<id>=<requests>/<seconds>` or `<identity>=<requests>/<seconds>`
+    ///
+    pub fn with_rest_api_rate_limit_overrides(
+        mut self,
+        rest_api_rate_limit_overrides: Option<Vec<String>>,
+    ) -> Self {
+        self.rest_api_rate_limit_overrides = rest_api_rate_limit_overrides;
+        self
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    /// Adds a `tls_rest_api_client_ca` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `tls_rest_api_client_ca` - The certificate authority used by the REST API to verify
+    ///   client certificates
+    ///
+    pub fn with_tls_rest_api_client_ca(mut self, tls_rest_api_client_ca: Option<String>) -> Self {
+        self.tls_rest_api_client_ca = tls_rest_api_client_ca;
+        self
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    /// Adds a `rest_api_client_cert_identity` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `rest_api_client_cert_identity` - The part of a client certificate that is used as the
+    ///   client's identity, either `public-key` or `subject`
+    ///
+    pub fn with_rest_api_client_cert_identity(
+        mut self,
+        rest_api_client_cert_identity: Option<String>,
+    ) -> Self {
+        self.rest_api_client_cert_identity = rest_api_client_cert_identity;
+        self
+    }
+
     /// Adds a `strict_ref_counts` value to the `PartialConfig` object.
     ///
     /// # Arguments
@@ -802,4 +989,115 @@ impl PartialConfig {
         self.metrics_password = metrics_password;
         self
     }
+
+    #[cfg(feature = "unix-transport")]
+    /// Adds a `unix_socket_permissions` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `unix_socket_permissions` - The mode of socket files created for `unix://` endpoints
+    ///
+    pub fn with_unix_socket_permissions(mut self, unix_socket_permissions: Option<u32>) -> Self {
+        self.unix_socket_permissions = unix_socket_permissions;
+        self
+    }
+
+    #[cfg(feature = "unix-transport")]
+    /// Adds a `unix_socket_allowed_uids` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `unix_socket_allowed_uids` - The user IDs allowed to connect to `unix://` endpoints
+    ///
+    pub fn with_unix_socket_allowed_uids(
+        mut self,
+        unix_socket_allowed_uids: Option<Vec<u32>>,
+    ) -> Self {
+        self.unix_socket_allowed_uids = unix_socket_allowed_uids;
+        self
+    }
+
+    #[cfg(feature = "unix-transport")]
+    /// Adds a `unix_socket_allowed_gids` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `unix_socket_allowed_gids` - The group IDs allowed to connect to `unix://` endpoints
+    ///
+    pub fn with_unix_socket_allowed_gids(
+        mut self,
+        unix_socket_allowed_gids: Option<Vec<u32>>,
+    ) -> Self {
+        self.unix_socket_allowed_gids = unix_socket_allowed_gids;
+        self
+    }
+
+    #[cfg(feature = "tls-reload")]
+    /// Adds a `tls_reload_interval` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `tls_reload_interval` - How often, in seconds, to check the TLS certificate and key
+    ///    files for changes
+    ///
+    pub fn with_tls_reload_interval(mut self, tls_reload_interval: Option<u64>) -> Self {
+        self.tls_reload_interval = tls_reload_interval;
+        self
+    }
+
+    #[cfg(feature = "registry-remote-signing")]
+    /// Adds a `registry_public_key` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry_public_key` - Hex-encoded public key used to verify remote registry files
+    ///
+    pub fn with_registry_public_key(mut self, registry_public_key: Option<String>) -> Self {
+        self.registry_public_key = registry_public_key;
+        self
+    }
+
+    #[cfg(feature = "transport-compression")]
+    /// Adds a `transport_compression` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport_compression` - Whether to offer compression on TCP and TLS connections
+    ///
+    pub fn with_transport_compression(mut self, transport_compression: Option<bool>) -> Self {
+        self.transport_compression = transport_compression;
+        self
+    }
+
+    #[cfg(feature = "scabbard-batch-status-store")]
+    /// Adds a `scabbard_batch_status_retention` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `scabbard_batch_status_retention` - Number of most recently completed batches whose
+    ///    statuses each scabbard service persists
+    ///
+    pub fn with_scabbard_batch_status_retention(
+        mut self,
+        scabbard_batch_status_retention: Option<usize>,
+    ) -> Self {
+        self.scabbard_batch_status_retention = scabbard_batch_status_retention;
+        self
+    }
+
+    #[cfg(feature = "scabbard-state-pruning")]
+    /// Adds a `scabbard_state_retention_depth` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `scabbard_state_retention_depth` - Number of most recent state roots each scabbard
+    ///    service retains
+    ///
+    pub fn with_scabbard_state_retention_depth(
+        mut self,
+        scabbard_state_retention_depth: Option<usize>,
+    ) -> Self {
+        self.scabbard_state_retention_depth = scabbard_state_retention_depth;
+        self
+    }
 }
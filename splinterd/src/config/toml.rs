@@ -70,6 +70,16 @@ struct TomlConfig {
     oauth_openid_auth_params: Option<Vec<(String, String)>>,
     #[cfg(feature = "oauth")]
     oauth_openid_scopes: Option<Vec<String>>,
+    #[cfg(feature = "oauth-azure")]
+    oauth_azure_tenant: Option<String>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit: Option<String>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit_overrides: Option<Vec<String>>,
+    #[cfg(feature = "https-client-auth")]
+    tls_rest_api_client_ca: Option<String>,
+    #[cfg(feature = "https-client-auth")]
+    rest_api_client_cert_identity: Option<String>,
     #[cfg(feature = "metrics")]
     metrics_db: Option<String>,
     #[cfg(feature = "metrics")]
@@ -78,6 +88,22 @@ struct TomlConfig {
     metrics_username: Option<String>,
     #[cfg(feature = "metrics")]
     metrics_password: Option<String>,
+    #[cfg(feature = "unix-transport")]
+    unix_socket_permissions: Option<u32>,
+    #[cfg(feature = "unix-transport")]
+    unix_socket_allowed_uids: Option<Vec<u32>>,
+    #[cfg(feature = "unix-transport")]
+    unix_socket_allowed_gids: Option<Vec<u32>>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: Option<u64>,
+    #[cfg(feature = "registry-remote-signing")]
+    registry_public_key: Option<String>,
+    #[cfg(feature = "transport-compression")]
+    transport_compression: Option<bool>,
+    #[cfg(feature = "scabbard-batch-status-store")]
+    scabbard_batch_status_retention: Option<usize>,
+    #[cfg(feature = "scabbard-state-pruning")]
+    scabbard_state_retention_depth: Option<usize>,
 
     // Deprecated values
     cert_dir: Option<String>,
@@ -189,6 +215,26 @@ impl PartialConfigBuilder for TomlPartialConfigBuilder {
                 .with_oauth_openid_scopes(self.toml_config.oauth_openid_scopes);
         }
 
+        #[cfg(feature = "oauth-azure")]
+        {
+            partial_config =
+                partial_config.with_oauth_azure_tenant(self.toml_config.oauth_azure_tenant)
+        }
+
+        #[cfg(feature = "rest-api-rate-limit")]
+        {
+            partial_config = partial_config
+                .with_rest_api_rate_limit(self.toml_config.rest_api_rate_limit)
+                .with_rest_api_rate_limit_overrides(self.toml_config.rest_api_rate_limit_overrides)
+        }
+
+        #[cfg(feature = "https-client-auth")]
+        {
+            partial_config = partial_config
+                .with_tls_rest_api_client_ca(self.toml_config.tls_rest_api_client_ca)
+                .with_rest_api_client_cert_identity(self.toml_config.rest_api_client_cert_identity)
+        }
+
         #[cfg(feature = "metrics")]
         {
             partial_config = partial_config
@@ -198,6 +244,46 @@ impl PartialConfigBuilder for TomlPartialConfigBuilder {
                 .with_metrics_password(self.toml_config.metrics_password)
         }
 
+        #[cfg(feature = "unix-transport")]
+        {
+            partial_config = partial_config
+                .with_unix_socket_permissions(self.toml_config.unix_socket_permissions)
+                .with_unix_socket_allowed_uids(self.toml_config.unix_socket_allowed_uids)
+                .with_unix_socket_allowed_gids(self.toml_config.unix_socket_allowed_gids)
+        }
+
+        #[cfg(feature = "tls-reload")]
+        {
+            partial_config =
+                partial_config.with_tls_reload_interval(self.toml_config.tls_reload_interval)
+        }
+
+        #[cfg(feature = "registry-remote-signing")]
+        {
+            partial_config =
+                partial_config.with_registry_public_key(self.toml_config.registry_public_key)
+        }
+
+        #[cfg(feature = "transport-compression")]
+        {
+            partial_config =
+                partial_config.with_transport_compression(self.toml_config.transport_compression)
+        }
+
+        #[cfg(feature = "scabbard-batch-status-store")]
+        {
+            partial_config = partial_config.with_scabbard_batch_status_retention(
+                self.toml_config.scabbard_batch_status_retention,
+            )
+        }
+
+        #[cfg(feature = "scabbard-state-pruning")]
+        {
+            partial_config = partial_config.with_scabbard_state_retention_depth(
+                self.toml_config.scabbard_state_retention_depth,
+            )
+        }
+
         // deprecated values, only set if the current value was not set
         if partial_config.tls_cert_dir().is_none() {
             partial_config = partial_config.with_tls_cert_dir(self.toml_config.cert_dir)
@@ -28,6 +28,8 @@ use std::sync::{mpsc::channel, Arc};
 use std::thread;
 use std::time::Duration;
 
+#[cfg(feature = "registry-remote-signing")]
+use cylinder::PublicKey;
 use cylinder::{secp256k1::Secp256k1Context, Context};
 #[cfg(feature = "challenge-authorization")]
 use cylinder::{Signer, SigningError};
@@ -35,19 +37,46 @@ use cylinder::{Signer, SigningError};
 use health::HealthService;
 #[cfg(feature = "service-arg-validation")]
 use scabbard::service::ScabbardArgValidator;
+#[cfg(not(any(
+    feature = "scabbard-batch-status-store",
+    feature = "scabbard-state-pruning"
+)))]
 use scabbard::service::ScabbardFactory;
+#[cfg(any(
+    feature = "scabbard-batch-status-store",
+    feature = "scabbard-state-pruning"
+))]
+use scabbard::service::ScabbardFactoryBuilder;
+#[cfg(feature = "tls-reload")]
+use signal_hook::{consts::SIGHUP, iterator::Signals};
 use splinter::admin::rest_api::CircuitResourceProvider;
+#[cfg(feature = "circuit-durability")]
+use splinter::admin::rest_api::DurableQueueResourceProvider;
+#[cfg(feature = "biome-notifications")]
+use splinter::admin::service::AdminCommands;
 use splinter::admin::service::{admin_service_id, AdminService, AdminServiceBuilder};
+#[cfg(feature = "audit")]
+use splinter::audit::rest_api::AuditResourceProvider;
+#[cfg(feature = "backup")]
+use splinter::backup::CommitGate;
 #[cfg(feature = "biome-credentials")]
 use splinter::biome::credentials::rest_api::BiomeCredentialsRestResourceProviderBuilder;
 #[cfg(feature = "biome-key-management")]
 use splinter::biome::key_management::rest_api::BiomeKeyManagementRestResourceProvider;
+#[cfg(feature = "biome-notifications")]
+use splinter::biome::notifications::{
+    admin::AdminServiceNotifier, rest_api::BiomeNotificationRestResourceProvider,
+};
 #[cfg(feature = "biome-profile")]
 use splinter::biome::profile::rest_api::BiomeProfileRestResourceProvider;
+#[cfg(feature = "circuit-durability")]
+use splinter::circuit::durable::{DurableMessageReplayer, DurableMessageStore};
 use splinter::circuit::handlers::{
     AdminDirectMessageHandler, CircuitDirectMessageHandler, CircuitErrorHandler,
     CircuitMessageHandler, ServiceConnectRequestHandler, ServiceDisconnectRequestHandler,
 };
+#[cfg(feature = "circuit-durability")]
+use splinter::circuit::handlers::{CircuitDurableMessageAckHandler, CircuitDurableMessageHandler};
 use splinter::circuit::routing::{memory::RoutingTable, RoutingTableReader, RoutingTableWriter};
 use splinter::error::InternalError;
 use splinter::keys::insecure::AllowAllKeyPermissionManager;
@@ -64,11 +93,20 @@ use splinter::orchestrator::ServiceOrchestratorBuilder;
 use splinter::peer::interconnect::NetworkMessageSender;
 use splinter::peer::interconnect::PeerInterconnectBuilder;
 use splinter::peer::PeerManager;
+#[cfg(feature = "circuit-relay")]
+use splinter::peer::PeerManagerConnector;
+#[cfg(feature = "registry-notifications")]
+use splinter::peer::RegistryPeerUpdater;
 use splinter::protos::circuit::CircuitMessageType;
 use splinter::protos::network::NetworkMessageType;
+#[cfg(not(feature = "registry-remote-signing"))]
+use splinter::registry::RemoteYamlRegistry;
 use splinter::registry::{
-    LocalYamlRegistry, RegistryReader, RemoteYamlRegistry, RemoteYamlShutdownHandle, RwRegistry,
-    UnifiedRegistry,
+    LocalYamlRegistry, RegistryReader, RemoteYamlShutdownHandle, RwRegistry, UnifiedRegistry,
+};
+#[cfg(feature = "registry-remote-signing")]
+use splinter::registry::{
+    RemoteRegistryStatusResourceProvider, RemoteYamlRegistryBuilder, RemoteYamlRegistryStatusReader,
 };
 #[cfg(feature = "authorization-handler-allow-keys")]
 use splinter::rest_api::auth::authorization::allow_keys::AllowKeysAuthorizationHandler;
@@ -86,6 +124,13 @@ use splinter::rest_api::auth::authorization::rbac::{
 use splinter::rest_api::auth::authorization::AuthorizationHandler;
 #[cfg(feature = "authorization")]
 use splinter::rest_api::auth::authorization::Permission;
+#[cfg(feature = "https-client-auth")]
+use splinter::rest_api::auth::identity::client_cert::ClientCertificateIdentityMapping;
+#[cfg(feature = "rest-api-rate-limit")]
+use splinter::rest_api::auth::{
+    identity::Identity,
+    rate_limit::{RateLimit, RateLimiter, RateLimiterBuilder},
+};
 #[cfg(feature = "oauth")]
 use splinter::rest_api::OAuthConfig;
 use splinter::rest_api::{
@@ -95,6 +140,8 @@ use splinter::service;
 #[cfg(feature = "service-arg-validation")]
 use splinter::service::validation::ServiceArgValidator;
 use splinter::threading::lifecycle::ShutdownHandle;
+#[cfg(feature = "tls-reload")]
+use splinter::transport::socket::{TlsCertWatcher, TlsReloader};
 use splinter::transport::{
     inproc::InprocTransport, multi::MultiTransport, AcceptError, ConnectError, Connection,
     Incoming, ListenError, Listener, Transport,
@@ -151,10 +198,30 @@ pub struct SplinterDaemon {
     oauth_openid_auth_params: Option<Vec<(String, String)>>,
     #[cfg(feature = "oauth")]
     oauth_openid_scopes: Option<Vec<String>>,
+    #[cfg(feature = "oauth-azure")]
+    oauth_azure_tenant: Option<String>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit: Option<String>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit_overrides: Option<Vec<String>>,
+    #[cfg(feature = "https-client-auth")]
+    rest_api_client_ca: Option<String>,
+    #[cfg(feature = "https-client-auth")]
+    rest_api_client_cert_identity: Option<String>,
     heartbeat: u64,
     strict_ref_counts: bool,
     #[cfg(feature = "challenge-authorization")]
     signers: Vec<Box<dyn Signer>>,
+    #[cfg(feature = "tls-reload")]
+    tls_reloader: Option<TlsReloader>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: Option<Duration>,
+    #[cfg(feature = "registry-remote-signing")]
+    registry_public_key: Option<PublicKey>,
+    #[cfg(feature = "scabbard-batch-status-store")]
+    scabbard_batch_status_retention: Option<usize>,
+    #[cfg(feature = "scabbard-state-pruning")]
+    scabbard_state_retention_depth: Option<usize>,
 }
 
 impl SplinterDaemon {
@@ -367,7 +434,25 @@ impl SplinterDaemon {
                         err
                     ))
                 })?,
+            #[cfg(feature = "circuit-durability")]
+            store_factory.get_durable_message_store(),
+            #[cfg(feature = "circuit-relay")]
+            peer_connector.clone(),
         );
+
+        // Resend queued durable messages when peers reconnect
+        #[cfg(feature = "circuit-durability")]
+        let mut durable_message_replayer = DurableMessageReplayer::start(
+            self.node_id.to_string(),
+            store_factory.get_durable_message_store(),
+            routing_reader.clone(),
+            network_sender.clone(),
+            peer_connector.clone(),
+        )
+        .map_err(|err| {
+            StartError::NetworkError(format!("Unable to start durable message replayer: {}", err))
+        })?;
+
         let mut circuit_dispatch_loop = DispatchLoopBuilder::new()
             .with_dispatcher(circuit_dispatcher)
             .with_thread_name("CircuitDispatchLoop".to_string())
@@ -438,15 +523,51 @@ impl SplinterDaemon {
         let signing_context = Secp256k1Context::new();
         let admin_service_verifier = signing_context.new_verifier();
 
+        #[cfg(not(any(
+            feature = "scabbard-batch-status-store",
+            feature = "scabbard-state-pruning"
+        )))]
+        let scabbard_factory = ScabbardFactory::new(
+            Some(self.state_dir.to_string()),
+            None,
+            Some(self.state_dir.to_string()),
+            None,
+            Box::new(signing_context),
+        );
+
+        #[cfg(any(
+            feature = "scabbard-batch-status-store",
+            feature = "scabbard-state-pruning"
+        ))]
+        let scabbard_factory = {
+            #[allow(unused_mut)]
+            let mut scabbard_factory_builder = ScabbardFactoryBuilder::new()
+                .with_state_db_dir(self.state_dir.to_string())
+                .with_receipt_db_dir(self.state_dir.to_string())
+                .with_signature_verifier_factory(Box::new(signing_context));
+            #[cfg(feature = "scabbard-batch-status-store")]
+            if let Some(retention) = self.scabbard_batch_status_retention {
+                scabbard_factory_builder =
+                    scabbard_factory_builder.with_batch_status_retention(retention);
+            }
+            #[cfg(feature = "scabbard-state-pruning")]
+            if let Some(depth) = self.scabbard_state_retention_depth {
+                scabbard_factory_builder =
+                    scabbard_factory_builder.with_state_retention_depth(depth);
+            }
+            scabbard_factory_builder.build().map_err(|err| {
+                StartError::OrchestratorError(format!("failed to create scabbard factory: {}", err))
+            })?
+        };
+
+        #[cfg(feature = "backup")]
+        let commit_gate = CommitGate::new();
+        #[cfg(feature = "backup")]
+        let scabbard_factory = scabbard_factory.with_commit_gate(commit_gate.clone());
+
         let mut orchestrator = ServiceOrchestratorBuilder::new()
             .with_connection(orchestrator_connection)
-            .with_service_factory(Box::new(ScabbardFactory::new(
-                Some(self.state_dir.to_string()),
-                None,
-                Some(self.state_dir.to_string()),
-                None,
-                Box::new(signing_context),
-            )))
+            .with_service_factory(Box::new(scabbard_factory))
             .build()
             .map_err(|err| {
                 StartError::OrchestratorError(format!("failed to create new orchestrator: {}", err))
@@ -464,14 +585,34 @@ impl SplinterDaemon {
                 )
             })?;
 
+        #[cfg(feature = "registry-remote-signing")]
+        let mut remote_registry_status = vec![];
+
         let (registry, mut registry_shutdown) = create_registry(
             &self.state_dir,
             &self.registries,
             self.registry_auto_refresh,
             self.registry_forced_refresh,
             &*store_factory,
+            #[cfg(feature = "registry-remote-signing")]
+            self.registry_public_key.as_ref(),
+            #[cfg(feature = "registry-remote-signing")]
+            &mut remote_registry_status,
         );
 
+        // Reconnect to peers as soon as their nodes move to new endpoints in the registry
+        #[cfg(feature = "registry-notifications")]
+        {
+            if let Err(err) = registry
+                .add_event_subscriber(Box::new(RegistryPeerUpdater::new(peer_connector.clone())))
+            {
+                warn!(
+                    "Unable to subscribe the peer manager to registry events: {}",
+                    err
+                );
+            }
+        }
+
         let mut admin_service_builder = AdminServiceBuilder::new();
 
         admin_service_builder = admin_service_builder
@@ -490,7 +631,7 @@ impl SplinterDaemon {
         {
             let mut validators: HashMap<String, Box<dyn ServiceArgValidator + Send>> =
                 HashMap::new();
-            validators.insert("scabbard".into(), Box::new(ScabbardArgValidator));
+            validators.insert("scabbard".into(), Box::new(ScabbardArgValidator::default()));
 
             admin_service_builder = admin_service_builder.with_service_arg_validators(validators);
         }
@@ -516,6 +657,29 @@ impl SplinterDaemon {
             StartError::AdminServiceError(format!("unable to create admin service: {}", err))
         })?;
 
+        // Notify Biome users about the circuit proposals and circuits of all management types
+        #[cfg(feature = "biome-notifications")]
+        admin_service
+            .commands()
+            .add_event_subscriber(
+                "*",
+                Box::new(AdminServiceNotifier::new(
+                    self.node_id.clone(),
+                    store_factory.get_biome_notification_store(),
+                    store_factory.get_biome_key_store(),
+                    Box::new(registry.clone_box_as_reader()),
+                )),
+            )
+            .map_err(|err| {
+                StartError::AdminServiceError(format!(
+                    "unable to subscribe to admin service events: {}",
+                    err
+                ))
+            })?;
+
+        #[cfg(feature = "backup")]
+        let admin_quiescer = admin_service.quiescer();
+
         let node_id = self.node_id.clone();
         let display_name = self.display_name.clone();
         #[cfg(feature = "service-endpoint")]
@@ -546,6 +710,21 @@ impl SplinterDaemon {
             .add_resources(orchestrator_resources)
             .add_resources(circuit_resource_provider.resources());
 
+        #[cfg(feature = "circuit-durability")]
+        {
+            rest_api_builder = rest_api_builder.add_resources(
+                DurableQueueResourceProvider::new(store_factory.get_durable_message_store())
+                    .resources(),
+            );
+        }
+
+        #[cfg(feature = "registry-remote-signing")]
+        {
+            rest_api_builder = rest_api_builder.add_resources(
+                RemoteRegistryStatusResourceProvider::new(remote_registry_status).resources(),
+            );
+        }
+
         #[cfg(feature = "authorization")]
         {
             // Allowing unused_mut because authorization_handlers must be mutable if
@@ -583,6 +762,25 @@ impl SplinterDaemon {
                 );
             }
 
+            #[cfg(feature = "audit")]
+            {
+                rest_api_builder = rest_api_builder
+                    .with_audit_store(store_factory.get_audit_store())
+                    .add_resources(
+                        AuditResourceProvider::new(store_factory.get_audit_store()).resources(),
+                    );
+            }
+
+            #[cfg(feature = "rest-api-rate-limit")]
+            {
+                if let Some(rate_limiter) = create_rate_limiter(
+                    self.rest_api_rate_limit.as_deref(),
+                    self.rest_api_rate_limit_overrides.as_deref().unwrap_or(&[]),
+                )? {
+                    rest_api_builder = rest_api_builder.with_rate_limiter(rate_limiter);
+                }
+            }
+
             rest_api_builder = rest_api_builder
                 .with_authorization_handlers(authorization_handlers)
                 .add_resource(Resource::build("/openapi.yaml").add_method(
@@ -625,6 +823,64 @@ impl SplinterDaemon {
                 );
         }
 
+        #[cfg(feature = "tls-reload")]
+        {
+            if let Some(reloader) = self.tls_reloader.clone() {
+                #[cfg(feature = "authorization")]
+                {
+                    rest_api_builder = rest_api_builder.add_resource(
+                        Resource::build("/admin/tls/reload").add_method(
+                            Method::Post,
+                            routes::TLS_RELOAD_PERMISSION,
+                            move |_, _| routes::reload_tls(&reloader),
+                        ),
+                    );
+                }
+                #[cfg(not(feature = "authorization"))]
+                {
+                    rest_api_builder = rest_api_builder.add_resource(
+                        Resource::build("/admin/tls/reload")
+                            .add_method(Method::Post, move |_, _| routes::reload_tls(&reloader)),
+                    );
+                }
+            }
+        }
+
+        #[cfg(feature = "backup")]
+        {
+            let db_url = self.db_url.clone();
+            let state_dir = self.state_dir.clone();
+            #[cfg(feature = "authorization")]
+            {
+                rest_api_builder =
+                    rest_api_builder.add_resource(Resource::build("/admin/backup").add_method(
+                        Method::Post,
+                        routes::BACKUP_PERMISSION,
+                        move |_, _| {
+                            routes::create_node_backup(
+                                &db_url,
+                                &state_dir,
+                                &admin_quiescer,
+                                &commit_gate,
+                            )
+                        },
+                    ));
+            }
+            #[cfg(not(feature = "authorization"))]
+            {
+                rest_api_builder = rest_api_builder.add_resource(
+                    Resource::build("/admin/backup").add_method(Method::Post, move |_, _| {
+                        routes::create_node_backup(
+                            &db_url,
+                            &state_dir,
+                            &admin_quiescer,
+                            &commit_gate,
+                        )
+                    }),
+                );
+            }
+        }
+
         #[cfg(feature = "rest-api-cors")]
         {
             if let Some(list) = &self.whitelist {
@@ -641,6 +897,30 @@ impl SplinterDaemon {
             },
         ];
 
+        // Add API keys as an auth provider
+        #[cfg(feature = "api-keys")]
+        auth_configs.push(AuthConfig::ApiKey {
+            api_key_store: store_factory.get_api_key_store(),
+        });
+
+        // Add TLS client certificates as an auth provider if a client CA is configured
+        #[cfg(feature = "https-client-auth")]
+        if self.rest_api_client_ca.is_some() {
+            let mapping = match self.rest_api_client_cert_identity.as_deref() {
+                Some(mapping) => mapping
+                    .parse::<ClientCertificateIdentityMapping>()
+                    .map_err(|err| {
+                        StartError::RestApiError(format!(
+                            "invalid REST API client certificate identity: {}",
+                            err
+                        ))
+                    })?,
+                None => ClientCertificateIdentityMapping::PublicKey,
+            };
+
+            auth_configs.push(AuthConfig::ClientCertificate { mapping });
+        }
+
         // Add Biome credentials as an auth provider if it's enabled
         #[cfg(feature = "biome-credentials")]
         if self.enable_biome_credentials {
@@ -692,6 +972,7 @@ impl SplinterDaemon {
                     StartError::RestApiError("missing OAuth redirect URL configuration".into())
                 })?;
                 let oauth_config = match oauth_provider {
+                    #[cfg(not(feature = "oauth-azure"))]
                     "azure" => OAuthConfig::Azure {
                         client_id,
                         client_secret,
@@ -703,6 +984,19 @@ impl SplinterDaemon {
                         })?,
                         inflight_request_store: store_factory.get_oauth_inflight_request_store(),
                     },
+                    #[cfg(feature = "oauth-azure")]
+                    "azure" => OAuthConfig::Azure {
+                        client_id,
+                        client_secret,
+                        redirect_url,
+                        tenant_id: self.oauth_azure_tenant.clone().ok_or_else(|| {
+                            StartError::RestApiError(
+                                "missing OAuth Azure tenant configuration".into(),
+                            )
+                        })?,
+                        scopes: self.oauth_openid_scopes.clone(),
+                        inflight_request_store: store_factory.get_oauth_inflight_request_store(),
+                    },
                     "github" => OAuthConfig::GitHub {
                         client_id,
                         client_secret,
@@ -741,6 +1035,10 @@ impl SplinterDaemon {
                     oauth_user_session_store: store_factory.get_biome_oauth_user_session_store(),
                     #[cfg(feature = "biome-profile")]
                     user_profile_store: store_factory.get_biome_user_profile_store(),
+                    #[cfg(feature = "authorization-handler-rbac-groups")]
+                    role_based_authorization_store: Some(
+                        store_factory.get_role_based_authorization_store(),
+                    ),
                 });
             }
         }
@@ -757,6 +1055,16 @@ impl SplinterDaemon {
             );
         }
 
+        #[cfg(feature = "biome-notifications")]
+        {
+            rest_api_builder = rest_api_builder.add_resources(
+                BiomeNotificationRestResourceProvider::new(Arc::new(
+                    store_factory.get_biome_notification_store(),
+                ))
+                .resources(),
+            );
+        }
+
         #[cfg(feature = "biome-profile")]
         {
             rest_api_builder = rest_api_builder.add_resources(
@@ -779,6 +1087,13 @@ impl SplinterDaemon {
 
         let mut admin_shutdown_handle = Self::start_admin_service(admin_connection, admin_service)?;
 
+        #[cfg(feature = "tls-reload")]
+        let tls_reload_shutdown_handle = self
+            .tls_reloader
+            .as_ref()
+            .map(|reloader| start_tls_reload(reloader, self.tls_reload_interval))
+            .transpose()?;
+
         let (shutdown_tx, shutdown_rx) = channel();
         ctrlc::set_handler(move || {
             if shutdown_tx.send(()).is_err() {
@@ -795,6 +1110,14 @@ impl SplinterDaemon {
 
         running.store(false, Ordering::SeqCst);
 
+        #[cfg(feature = "tls-reload")]
+        if let Some(mut tls_reload_shutdown_handle) = tls_reload_shutdown_handle {
+            tls_reload_shutdown_handle.signal_shutdown();
+            if let Err(err) = tls_reload_shutdown_handle.wait_for_shutdown() {
+                error!("Unable to cleanly shut down TLS reloading: {}", err);
+            }
+        }
+
         admin_shutdown_handle.signal_shutdown();
         orchestator_shutdown_handle.signal_shutdown();
         #[cfg(feature = "health-service")]
@@ -826,6 +1149,17 @@ impl SplinterDaemon {
             error!("Unable to cleanly shut down network dispatch loop: {}", err);
         }
 
+        #[cfg(feature = "circuit-durability")]
+        {
+            durable_message_replayer.signal_shutdown();
+            if let Err(err) = durable_message_replayer.wait_for_shutdown() {
+                error!(
+                    "Unable to cleanly shut down durable message replayer: {}",
+                    err
+                );
+            }
+        }
+
         registry_shutdown.signal_shutdown();
         if let Err(err) = registry_shutdown.wait_for_shutdown() {
             error!("Unable to cleanly shut down network dispatch loop: {}", err);
@@ -859,6 +1193,12 @@ impl SplinterDaemon {
     #[cfg(feature = "https-bind")]
     fn build_rest_api_bind(&self) -> Result<splinter::rest_api::BindConfig, StartError> {
         match self.rest_api_endpoint.strip_prefix("http://") {
+            #[cfg(feature = "https-client-auth")]
+            Some(_) if self.rest_api_client_ca.is_some() => Err(StartError::RestApiError(
+                "A REST API client CA has been provided, \
+                but the REST API has not been configured for HTTPS."
+                    .into(),
+            )),
             Some(insecure_endpoint) => Ok(splinter::rest_api::BindConfig::Http(
                 insecure_endpoint.into(),
             )),
@@ -875,6 +1215,8 @@ impl SplinterDaemon {
                             .expect("There should be a value, due to the above or"),
                         cert_path: rest_api_server_cert.clone(),
                         key_path: rest_api_server_key.clone(),
+                        #[cfg(feature = "https-client-auth")]
+                        client_ca_path: self.rest_api_client_ca.clone(),
                     })
                 } else {
                     Err(StartError::RestApiError(
@@ -915,9 +1257,18 @@ impl SplinterDaemon {
 
             #[cfg(feature = "service-endpoint")]
             {
+                let endpoint = external_service_listener.endpoint();
                 for connection_result in external_service_listener.incoming() {
                     let connection = match connection_result {
                         Ok(connection) => connection,
+                        // Rejected or failed handshakes only affect the connecting service
+                        Err(AcceptError::ProtocolError(msg)) => {
+                            warn!(
+                                "Failed to accept service connection on {}: {}",
+                                endpoint, msg
+                            );
+                            continue;
+                        }
                         Err(err) => {
                             return Err(StartError::TransportError(format!(
                                 "Accept Error: {:?}",
@@ -1057,9 +1408,29 @@ pub struct SplinterDaemonBuilder {
     oauth_openid_auth_params: Option<Vec<(String, String)>>,
     #[cfg(feature = "oauth")]
     oauth_openid_scopes: Option<Vec<String>>,
+    #[cfg(feature = "oauth-azure")]
+    oauth_azure_tenant: Option<String>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit: Option<String>,
+    #[cfg(feature = "rest-api-rate-limit")]
+    rest_api_rate_limit_overrides: Option<Vec<String>>,
+    #[cfg(feature = "https-client-auth")]
+    rest_api_client_ca: Option<String>,
+    #[cfg(feature = "https-client-auth")]
+    rest_api_client_cert_identity: Option<String>,
     strict_ref_counts: Option<bool>,
     #[cfg(feature = "challenge-authorization")]
     signers: Option<Vec<Box<dyn Signer>>>,
+    #[cfg(feature = "tls-reload")]
+    tls_reloader: Option<TlsReloader>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: Option<Duration>,
+    #[cfg(feature = "registry-remote-signing")]
+    registry_public_key: Option<PublicKey>,
+    #[cfg(feature = "scabbard-batch-status-store")]
+    scabbard_batch_status_retention: Option<usize>,
+    #[cfg(feature = "scabbard-state-pruning")]
+    scabbard_state_retention_depth: Option<usize>,
 }
 
 impl SplinterDaemonBuilder {
@@ -1210,6 +1581,36 @@ impl SplinterDaemonBuilder {
         self
     }
 
+    #[cfg(feature = "oauth-azure")]
+    pub fn with_oauth_azure_tenant(mut self, value: Option<String>) -> Self {
+        self.oauth_azure_tenant = value;
+        self
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    pub fn with_rest_api_rate_limit(mut self, value: Option<String>) -> Self {
+        self.rest_api_rate_limit = value;
+        self
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    pub fn with_rest_api_rate_limit_overrides(mut self, value: Option<Vec<String>>) -> Self {
+        self.rest_api_rate_limit_overrides = value;
+        self
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    pub fn with_rest_api_client_ca(mut self, value: Option<String>) -> Self {
+        self.rest_api_client_ca = value;
+        self
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    pub fn with_rest_api_client_cert_identity(mut self, value: Option<String>) -> Self {
+        self.rest_api_client_cert_identity = value;
+        self
+    }
+
     pub fn with_strict_ref_counts(mut self, strict_ref_counts: bool) -> Self {
         self.strict_ref_counts = Some(strict_ref_counts);
         self
@@ -1221,6 +1622,36 @@ impl SplinterDaemonBuilder {
         self
     }
 
+    #[cfg(feature = "tls-reload")]
+    pub fn with_tls_reloader(mut self, value: Option<TlsReloader>) -> Self {
+        self.tls_reloader = value;
+        self
+    }
+
+    #[cfg(feature = "tls-reload")]
+    pub fn with_tls_reload_interval(mut self, value: Option<Duration>) -> Self {
+        self.tls_reload_interval = value;
+        self
+    }
+
+    #[cfg(feature = "registry-remote-signing")]
+    pub fn with_registry_public_key(mut self, value: Option<PublicKey>) -> Self {
+        self.registry_public_key = value;
+        self
+    }
+
+    #[cfg(feature = "scabbard-batch-status-store")]
+    pub fn with_scabbard_batch_status_retention(mut self, value: Option<usize>) -> Self {
+        self.scabbard_batch_status_retention = value;
+        self
+    }
+
+    #[cfg(feature = "scabbard-state-pruning")]
+    pub fn with_scabbard_state_retention_depth(mut self, value: Option<usize>) -> Self {
+        self.scabbard_state_retention_depth = value;
+        self
+    }
+
     pub fn build(self) -> Result<SplinterDaemon, CreateError> {
         let heartbeat = self.heartbeat.ok_or_else(|| {
             CreateError::MissingRequiredField("Missing field: heartbeat".to_string())
@@ -1342,10 +1773,30 @@ impl SplinterDaemonBuilder {
             oauth_openid_auth_params: self.oauth_openid_auth_params,
             #[cfg(feature = "oauth")]
             oauth_openid_scopes: self.oauth_openid_scopes,
+            #[cfg(feature = "oauth-azure")]
+            oauth_azure_tenant: self.oauth_azure_tenant,
+            #[cfg(feature = "rest-api-rate-limit")]
+            rest_api_rate_limit: self.rest_api_rate_limit,
+            #[cfg(feature = "rest-api-rate-limit")]
+            rest_api_rate_limit_overrides: self.rest_api_rate_limit_overrides,
+            #[cfg(feature = "https-client-auth")]
+            rest_api_client_ca: self.rest_api_client_ca,
+            #[cfg(feature = "https-client-auth")]
+            rest_api_client_cert_identity: self.rest_api_client_cert_identity,
             heartbeat,
             strict_ref_counts,
             #[cfg(feature = "challenge-authorization")]
             signers,
+            #[cfg(feature = "tls-reload")]
+            tls_reloader: self.tls_reloader,
+            #[cfg(feature = "tls-reload")]
+            tls_reload_interval: self.tls_reload_interval,
+            #[cfg(feature = "registry-remote-signing")]
+            registry_public_key: self.registry_public_key,
+            #[cfg(feature = "scabbard-batch-status-store")]
+            scabbard_batch_status_retention: self.scabbard_batch_status_retention,
+            #[cfg(feature = "scabbard-state-pruning")]
+            scabbard_state_retention_depth: self.scabbard_state_retention_depth,
         })
     }
 }
@@ -1376,6 +1827,8 @@ fn set_up_circuit_dispatcher(
     routing_reader: Box<dyn RoutingTableReader>,
     routing_writer: Box<dyn RoutingTableWriter>,
     #[cfg(feature = "challenge-authorization")] public_keys: Vec<String>,
+    #[cfg(feature = "circuit-durability")] durable_message_store: Box<dyn DurableMessageStore>,
+    #[cfg(feature = "circuit-relay")] peer_connector: PeerManagerConnector,
 ) -> Dispatcher<CircuitMessageType> {
     let mut dispatcher = Dispatcher::<CircuitMessageType>::new(Box::new(network_sender));
 
@@ -1392,8 +1845,30 @@ fn set_up_circuit_dispatcher(
 
     let direct_message_handler =
         CircuitDirectMessageHandler::new(node_id.to_string(), routing_reader.clone());
+    #[cfg(feature = "circuit-durability")]
+    let direct_message_handler =
+        direct_message_handler.with_durable_message_store(durable_message_store.clone());
+    #[cfg(feature = "circuit-relay")]
+    let direct_message_handler = direct_message_handler.with_peer_connector(peer_connector);
     dispatcher.set_handler(Box::new(direct_message_handler));
 
+    #[cfg(feature = "circuit-durability")]
+    {
+        let durable_message_handler = CircuitDurableMessageHandler::new(
+            node_id.to_string(),
+            routing_reader.clone(),
+            durable_message_store.clone(),
+        );
+        dispatcher.set_handler(Box::new(durable_message_handler));
+
+        let durable_message_ack_handler = CircuitDurableMessageAckHandler::new(
+            node_id.to_string(),
+            routing_reader.clone(),
+            durable_message_store,
+        );
+        dispatcher.set_handler(Box::new(durable_message_ack_handler));
+    }
+
     let circuit_error_handler =
         CircuitErrorHandler::new(node_id.to_string(), routing_reader.clone());
     dispatcher.set_handler(Box::new(circuit_error_handler));
@@ -1416,6 +1891,10 @@ fn create_registry(
     auto_refresh_interval: u64,
     forced_refresh_interval: u64,
     store_factory: &dyn splinter::store::StoreFactory,
+    #[cfg(feature = "registry-remote-signing")] registry_public_key: Option<&PublicKey>,
+    #[cfg(feature = "registry-remote-signing")] remote_registry_status: &mut Vec<
+        RemoteYamlRegistryStatusReader,
+    >,
 ) -> (Box<dyn RwRegistry>, RegistryShutdownHandle) {
     let mut registry_shutdown_handle = RegistryShutdownHandle::new();
 
@@ -1456,12 +1935,30 @@ fn create_registry(
                 } else {
                     None
                 };
-                match RemoteYamlRegistry::new(
+                #[cfg(not(feature = "registry-remote-signing"))]
+                let remote_registry = RemoteYamlRegistry::new(
                     registry,
                     state_dir,
                     auto_refresh_interval,
                     forced_refresh_interval,
-                ) {
+                );
+                #[cfg(feature = "registry-remote-signing")]
+                let remote_registry = {
+                    let mut builder = RemoteYamlRegistryBuilder::new()
+                        .with_url(registry.to_string())
+                        .with_cache_dir(state_dir.to_string());
+                    if let Some(interval) = auto_refresh_interval {
+                        builder = builder.with_automatic_refresh_period(interval);
+                    }
+                    if let Some(interval) = forced_refresh_interval {
+                        builder = builder.with_forced_refresh_period(interval);
+                    }
+                    if let Some(public_key) = registry_public_key {
+                        builder = builder.with_signing_key(public_key.clone());
+                    }
+                    builder.build()
+                };
+                match remote_registry {
                     Ok(mut registry) => {
                         // this should alwasy return some
                         if let Some(shutdown_handle) = registry.take_shutdown_handle() {
@@ -1469,6 +1966,9 @@ fn create_registry(
                                 .add_remote_yaml_shutdown_handle(shutdown_handle)
                         }
 
+                        #[cfg(feature = "registry-remote-signing")]
+                        remote_registry_status.push(registry.status_reader());
+
                         Some(Box::new(registry) as Box<dyn RegistryReader>)
                     }
                     Err(err) => {
@@ -1547,6 +2047,29 @@ mod tests {
             ("http", "server/registry.yaml")
         );
     }
+
+    /// Verifies that the REST API rate limiter is only created when limits are configured, and
+    /// that invalid limits and overrides are rejected.
+    #[cfg(feature = "rest-api-rate-limit")]
+    #[test]
+    fn test_create_rate_limiter() {
+        assert!(create_rate_limiter(None, &[]).unwrap().is_none());
+
+        assert!(create_rate_limiter(
+            Some("100/60"),
+            &[
+                "permission:scabbard.write=10/1".into(),
+                "key:0279be667ef9dcbbac55a06295ce870b07029bfc=1000/60".into(),
+                "custom:a=b=5/1".into(),
+            ],
+        )
+        .unwrap()
+        .is_some());
+
+        assert!(create_rate_limiter(Some("100"), &[]).is_err());
+        assert!(create_rate_limiter(None, &["permission:scabbard.write".into()]).is_err());
+        assert!(create_rate_limiter(None, &["node:abc=10/1".into()]).is_err());
+    }
 }
 
 #[derive(Default)]
@@ -1594,6 +2117,79 @@ impl ShutdownHandle for RegistryShutdownHandle {
     }
 }
 
+/// Starts reloading the TLS certificates and keys on SIGHUP and, if an `interval` is given,
+/// whenever the files are modified.
+#[cfg(feature = "tls-reload")]
+fn start_tls_reload(
+    reloader: &TlsReloader,
+    interval: Option<Duration>,
+) -> Result<TlsReloadShutdownHandle, StartError> {
+    let cert_watcher = interval
+        .map(|interval| {
+            debug!("Checking TLS certificates for changes every {:?}", interval);
+            reloader.watch(interval)
+        })
+        .transpose()
+        .map_err(|err| {
+            StartError::TransportError(format!("Unable to start TLS certificate watcher: {}", err))
+        })?;
+
+    let mut signals = Signals::new(&[SIGHUP]).map_err(|err| {
+        StartError::TransportError(format!("Unable to register SIGHUP handler: {}", err))
+    })?;
+    let signals_handle = signals.handle();
+
+    let thread_reloader = reloader.clone();
+    let join_handle = thread::Builder::new()
+        .name("TLS Reload Signal Handler".into())
+        .spawn(move || {
+            for _ in signals.forever() {
+                info!("Received SIGHUP, reloading TLS certificates and keys");
+                if let Err(err) = thread_reloader.reload() {
+                    error!("Unable to reload TLS certificates and keys: {}", err);
+                }
+            }
+        })
+        .map_err(|err| {
+            StartError::TransportError(format!("Unable to start SIGHUP handler thread: {}", err))
+        })?;
+
+    Ok(TlsReloadShutdownHandle {
+        cert_watcher,
+        signals_handle,
+        join_handle,
+    })
+}
+
+#[cfg(feature = "tls-reload")]
+struct TlsReloadShutdownHandle {
+    cert_watcher: Option<TlsCertWatcher>,
+    signals_handle: signal_hook::iterator::Handle,
+    join_handle: thread::JoinHandle<()>,
+}
+
+#[cfg(feature = "tls-reload")]
+impl ShutdownHandle for TlsReloadShutdownHandle {
+    fn signal_shutdown(&mut self) {
+        if let Some(cert_watcher) = self.cert_watcher.as_mut() {
+            cert_watcher.signal_shutdown();
+        }
+        self.signals_handle.close();
+    }
+
+    fn wait_for_shutdown(self) -> Result<(), InternalError> {
+        let watcher_result = match self.cert_watcher {
+            Some(cert_watcher) => cert_watcher.wait_for_shutdown(),
+            None => Ok(()),
+        };
+        let join_result = self.join_handle.join().map_err(|_| {
+            InternalError::with_message("Unable to join SIGHUP handler thread".to_string())
+        });
+
+        watcher_result.and(join_result)
+    }
+}
+
 #[cfg(feature = "authorization-handler-allow-keys")]
 fn create_allow_keys_authorization_handler(
     config_dir: &str,
@@ -1619,6 +2215,65 @@ fn create_allow_keys_authorization_handler(
     ))
 }
 
+/// Creates the REST API rate limiter from the configured default limit and overrides, or returns
+/// `None` if no limits are configured.
+///
+/// Each override is formatted as `permission:<permission_id>=<limit>` or `<identity>=<limit>`,
+/// where the identity is one of `key:<public_key>`, `user:<user_id>` or `custom:<value>` and the
+/// limit is formatted as `<requests>/<seconds>`.
+#[cfg(feature = "rest-api-rate-limit")]
+fn create_rate_limiter(
+    default_limit: Option<&str>,
+    overrides: &[String],
+) -> Result<Option<RateLimiter>, StartError> {
+    if default_limit.is_none() && overrides.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = RateLimiterBuilder::new();
+
+    if let Some(limit) = default_limit {
+        let limit = limit.parse::<RateLimit>().map_err(|err| {
+            StartError::RestApiError(format!("invalid REST API rate limit: {}", err))
+        })?;
+        builder = builder.with_default_limit(limit);
+    }
+
+    for rate_limit_override in overrides {
+        let index = rate_limit_override.rfind('=').ok_or_else(|| {
+            StartError::RestApiError(format!(
+                "invalid REST API rate limit override '{}': must be of the form \
+                 <selector>=<requests>/<seconds>",
+                rate_limit_override
+            ))
+        })?;
+        let selector = &rate_limit_override[..index];
+        let limit = rate_limit_override[index + 1..]
+            .parse::<RateLimit>()
+            .map_err(|err| {
+                StartError::RestApiError(format!(
+                    "invalid REST API rate limit override '{}': {}",
+                    rate_limit_override, err
+                ))
+            })?;
+
+        builder = match selector.strip_prefix("permission:") {
+            Some(permission_id) => builder.with_permission_limit(permission_id.to_string(), limit),
+            None => {
+                let identity = selector.parse::<Identity>().map_err(|err| {
+                    StartError::RestApiError(format!(
+                        "invalid REST API rate limit override '{}': {}",
+                        rate_limit_override, err
+                    ))
+                })?;
+                builder.with_identity_limit(&identity, limit)
+            }
+        };
+    }
+
+    Ok(Some(builder.build()))
+}
+
 #[derive(Debug)]
 pub enum CreateError {
     MissingRequiredField(String),
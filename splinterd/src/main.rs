@@ -25,6 +25,8 @@ mod error;
 mod routes;
 mod transport;
 
+#[cfg(feature = "registry-remote-signing")]
+use cylinder::PublicKey;
 #[cfg(feature = "challenge-authorization")]
 use cylinder::{load_key_from_path, secp256k1::Secp256k1Context, Context, Signer};
 use log4rs::config::{Appender, Logger, Root};
@@ -48,9 +50,32 @@ use std::ffi::OsStr;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
+#[cfg(feature = "tls-reload")]
+use std::time::Duration;
 
 use error::UserError;
-use transport::build_transport;
+use transport::{build_transport, DaemonTransport};
+
+// Parses a hex-encoded public key
+#[cfg(feature = "registry-remote-signing")]
+fn parse_public_key(key: &str) -> Result<PublicKey, UserError> {
+    if key.is_empty() || key.len() % 2 != 0 {
+        return Err(UserError::InvalidArgument(format!(
+            "Invalid registry public key: {}",
+            key
+        )));
+    }
+
+    (0..key.len())
+        .step_by(2)
+        .map(|i| {
+            key.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .map(PublicKey::new)
+        .ok_or_else(|| UserError::InvalidArgument(format!("Invalid registry public key: {}", key)))
+}
 
 fn create_config(_toml_path: Option<&str>, _matches: ArgMatches) -> Result<Config, UserError> {
     let mut builder = ConfigBuilder::new();
@@ -246,7 +271,10 @@ fn main() {
         .arg(
             Arg::with_name("service_endpoint")
                 .long("service-endpoint")
-                .long_help("Endpoint that service will connect to, tcp://ip:port")
+                .long_help(
+                    "Endpoint that service will connect to, tcp://ip:port or \
+                     unix:///path/to/socket (requires unix-transport)",
+                )
                 .takes_value(true)
                 .hidden(!cfg!(feature = "service-endpoint")),
         )
@@ -358,6 +386,28 @@ fn main() {
             .alias("rest-api-key"),
     );
 
+    #[cfg(feature = "https-client-auth")]
+    let app = app
+        .arg(
+            Arg::with_name("tls_rest_api_client_ca")
+                .long("tls-rest-api-client-ca")
+                .help(
+                    "File path to the CA certificate(s) used by the node's REST API to verify \
+                     client certificates",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("rest_api_client_cert_identity")
+                .long("rest-api-client-cert-identity")
+                .long_help(
+                    "The part of a verified client certificate that is used as the client's \
+                     identity: `public-key` (the default) or `subject`",
+                )
+                .possible_values(&["public-key", "subject"])
+                .takes_value(true),
+        );
+
     #[cfg(feature = "rest-api-cors")]
     let app = app.arg(
         Arg::with_name("whitelist")
@@ -431,6 +481,42 @@ fn main() {
                 .multiple(true),
         );
 
+    #[cfg(feature = "oauth-azure")]
+    let app = app.arg(
+        Arg::with_name("oauth_azure_tenant")
+            .long("oauth-azure-tenant")
+            .long_help(
+                "The Azure AD tenant that users sign in with; required by `--oauth-provider \
+                 azure`",
+            )
+            .takes_value(true),
+    );
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    let app = app
+        .arg(
+            Arg::with_name("rest_api_rate_limit")
+                .long("rest-api-rate-limit")
+                .long_help(
+                    "The default limit of REST API requests made by each client identity to each \
+                     permission, formatted as `<requests>/<seconds>`",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("rest_api_rate_limit_overrides")
+                .long("rest-api-rate-limit-overrides")
+                .alias("rest-api-rate-limit-override")
+                .long_help(
+                    "REST API rate limits for specific permissions or client identities, \
+                     formatted as `permission:<id>=<requests>/<seconds>` or \
+                     `<identity>=<requests>/<seconds>`, where the identity is one of \
+                     `key:<public-key>`, `user:<user-id>` or `custom:<value>`",
+                )
+                .takes_value(true)
+                .multiple(true),
+        );
+
     #[cfg(feature = "metrics")]
     let app = app
         .arg(
@@ -458,6 +544,94 @@ fn main() {
                 .takes_value(true),
         );
 
+    #[cfg(feature = "unix-transport")]
+    let app = app
+        .arg(
+            Arg::with_name("unix_socket_permissions")
+                .long("unix-socket-permissions")
+                .long_help(
+                    "The octal file mode of socket files created for unix:// endpoints \
+                     (default: 600)",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("unix_socket_allowed_uids")
+                .long("unix-socket-allowed-uids")
+                .alias("unix-socket-allowed-uid")
+                .long_help(
+                    "User IDs allowed to connect to unix:// endpoints; if no user or group IDs \
+                     are given, any process with access to the socket file may connect",
+                )
+                .takes_value(true)
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name("unix_socket_allowed_gids")
+                .long("unix-socket-allowed-gids")
+                .alias("unix-socket-allowed-gid")
+                .long_help(
+                    "Group IDs allowed to connect to unix:// endpoints; if no user or group IDs \
+                     are given, any process with access to the socket file may connect",
+                )
+                .takes_value(true)
+                .multiple(true),
+        );
+
+    #[cfg(feature = "tls-reload")]
+    let app = app.arg(
+        Arg::with_name("tls_reload_interval")
+            .long("tls-reload-interval")
+            .long_help(
+                "How often, in seconds, to check the TLS certificate and key files for changes; \
+                 if not set, certificates are only reloaded on SIGHUP or through the REST API",
+            )
+            .takes_value(true),
+    );
+
+    #[cfg(feature = "registry-remote-signing")]
+    let app = app.arg(
+        Arg::with_name("registry_public_key")
+            .long("registry-public-key")
+            .long_help(
+                "Hex-encoded public key used to verify signed remote registry files; if set, \
+                 remote registries that are not signed by this key are rejected",
+            )
+            .takes_value(true),
+    );
+
+    #[cfg(feature = "transport-compression")]
+    let app = app.arg(
+        Arg::with_name("transport_compression")
+            .long("transport-compression")
+            .long_help(
+                "Offer zstd compression on TCP and TLS connections; compression is only used \
+                 when the remote node also has it enabled",
+            ),
+    );
+
+    #[cfg(feature = "scabbard-batch-status-store")]
+    let app = app.arg(
+        Arg::with_name("scabbard_batch_status_retention")
+            .long("scabbard-batch-status-retention")
+            .long_help(
+                "Number of most recently completed batches whose statuses each scabbard service \
+                 persists; if not set, the statuses of the 10,000 most recent batches are kept",
+            )
+            .takes_value(true),
+    );
+
+    #[cfg(feature = "scabbard-state-pruning")]
+    let app = app.arg(
+        Arg::with_name("scabbard_state_retention_depth")
+            .long("scabbard-state-retention-depth")
+            .long_help(
+                "Number of most recent state roots each scabbard service retains; older state \
+                 roots are pruned after each commit. If not set, all state roots are retained",
+            )
+            .takes_value(true),
+    );
+
     let matches = app.get_matches();
 
     let log_level = match matches.occurrences_of("verbose") {
@@ -547,6 +721,14 @@ fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
 
     if config.no_tls() {
         for network_endpoint in config.network_endpoints() {
+            #[cfg(feature = "quic-transport")]
+            if network_endpoint.starts_with("quic://") {
+                return Err(UserError::InvalidArgument(format!(
+                    "TLS is disabled, thus endpoint {} is invalid",
+                    network_endpoint,
+                )));
+            }
+
             if network_endpoint.starts_with("tcps://") {
                 return Err(UserError::InvalidArgument(format!(
                     "TLS is disabled, thus endpoint {} is invalid",
@@ -560,7 +742,11 @@ fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
     #[cfg(feature = "metrics")]
     setup_metrics_recorder(&config)?;
 
-    let transport = build_transport(&config)?;
+    let DaemonTransport {
+        transport,
+        #[cfg(feature = "tls-reload")]
+        tls_reloader,
+    } = build_transport(&config)?;
 
     let rest_api_endpoint = config.rest_api_endpoint();
 
@@ -642,6 +828,62 @@ fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
             .with_oauth_openid_scopes(config.oauth_openid_scopes().map(ToOwned::to_owned));
     }
 
+    #[cfg(feature = "oauth-azure")]
+    {
+        daemon_builder = daemon_builder
+            .with_oauth_azure_tenant(config.oauth_azure_tenant().map(ToOwned::to_owned));
+    }
+
+    #[cfg(feature = "rest-api-rate-limit")]
+    {
+        daemon_builder = daemon_builder
+            .with_rest_api_rate_limit(config.rest_api_rate_limit().map(ToOwned::to_owned))
+            .with_rest_api_rate_limit_overrides(
+                config
+                    .rest_api_rate_limit_overrides()
+                    .map(ToOwned::to_owned),
+            );
+    }
+
+    #[cfg(feature = "https-client-auth")]
+    {
+        daemon_builder = daemon_builder
+            .with_rest_api_client_ca(config.tls_rest_api_client_ca().map(ToOwned::to_owned))
+            .with_rest_api_client_cert_identity(
+                config
+                    .rest_api_client_cert_identity()
+                    .map(ToOwned::to_owned),
+            );
+    }
+
+    #[cfg(feature = "registry-remote-signing")]
+    {
+        let registry_public_key = config
+            .registry_public_key()
+            .map(parse_public_key)
+            .transpose()?;
+        daemon_builder = daemon_builder.with_registry_public_key(registry_public_key);
+    }
+
+    #[cfg(feature = "tls-reload")]
+    {
+        daemon_builder = daemon_builder
+            .with_tls_reloader(tls_reloader)
+            .with_tls_reload_interval(config.tls_reload_interval().map(Duration::from_secs));
+    }
+
+    #[cfg(feature = "scabbard-batch-status-store")]
+    {
+        daemon_builder = daemon_builder
+            .with_scabbard_batch_status_retention(config.scabbard_batch_status_retention());
+    }
+
+    #[cfg(feature = "scabbard-state-pruning")]
+    {
+        daemon_builder = daemon_builder
+            .with_scabbard_state_retention_depth(config.scabbard_state_retention_depth());
+    }
+
     #[cfg(feature = "challenge-authorization")]
     {
         let signers = load_signer_keys(config.config_dir())?;
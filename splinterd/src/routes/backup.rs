@@ -0,0 +1,163 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use bytes::Bytes;
+use splinter::actix_web::{error::BlockingError, web, Error, HttpResponse};
+use splinter::admin::service::AdminServiceQuiescer;
+use splinter::backup::{create_backup, CommitGate};
+use splinter::futures::{Async, Future, Poll, Stream};
+#[cfg(feature = "authorization")]
+use splinter::rest_api::auth::authorization::Permission;
+use splinter::store::ConnectionUri;
+use tempdir::TempDir;
+
+#[cfg(feature = "authorization")]
+pub const BACKUP_PERMISSION: Permission = Permission::Check {
+    permission_id: "backup.create",
+    permission_display_name: "Backup create",
+    permission_description:
+        "Allows the client to create a backup of the node's database and service state",
+};
+
+const ARCHIVE_FILE: &str = "backup.tar.gz";
+/// The number of bytes of the archive that are sent in each chunk of the response
+const ARCHIVE_CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Serialize)]
+struct BackupResponse {
+    message: String,
+}
+
+/// Creates a backup archive of the node while the admin service and scabbard commits are paused.
+/// The archive is written to a temporary file on a blocking thread, and it is streamed to the
+/// client once the admin service and scabbard have been resumed.
+pub fn create_node_backup(
+    db_url: &str,
+    state_dir: &str,
+    admin_quiescer: &AdminServiceQuiescer,
+    commit_gate: &CommitGate,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let db_url = db_url.to_string();
+    let state_dir = state_dir.to_string();
+    let admin_quiescer = admin_quiescer.clone();
+    let commit_gate = commit_gate.clone();
+
+    Box::new(
+        web::block(move || backup_node(&db_url, &state_dir, &admin_quiescer, &commit_gate)).then(
+            |result| match result {
+                Ok(archive) => Ok(HttpResponse::Ok()
+                    .content_type("application/gzip")
+                    .streaming(archive)),
+                Err(BlockingError::Error(BackupRouteError::BadRequest(message))) => {
+                    Ok(HttpResponse::BadRequest().json(BackupResponse { message }))
+                }
+                Err(BlockingError::Error(BackupRouteError::Internal(message))) => {
+                    error!("{}", message);
+                    Ok(HttpResponse::InternalServerError().json(BackupResponse { message }))
+                }
+                Err(BlockingError::Canceled) => {
+                    let message = "Unable to create backup: the operation was canceled".to_string();
+                    error!("{}", message);
+                    Ok(HttpResponse::InternalServerError().json(BackupResponse { message }))
+                }
+            },
+        ),
+    )
+}
+
+#[derive(Debug)]
+enum BackupRouteError {
+    BadRequest(String),
+    Internal(String),
+}
+
+fn backup_node(
+    db_url: &str,
+    state_dir: &str,
+    admin_quiescer: &AdminServiceQuiescer,
+    commit_gate: &CommitGate,
+) -> Result<ArchiveStream, BackupRouteError> {
+    let connection_uri = db_url.parse().map_err(|err| {
+        BackupRouteError::Internal(format!("Invalid database URL provided: {}", err))
+    })?;
+    if let ConnectionUri::Memory = connection_uri {
+        return Err(BackupRouteError::BadRequest(
+            "Unable to back up a node that uses an in-memory database".into(),
+        ));
+    }
+    let store_factory = splinter::store::create_store_factory(connection_uri).map_err(|err| {
+        BackupRouteError::Internal(format!("Unable to connect to database: {}", err))
+    })?;
+
+    let temp_dir = TempDir::new("splinter-backup").map_err(|err| {
+        BackupRouteError::Internal(format!("Unable to create temporary directory: {}", err))
+    })?;
+    let archive_path = temp_dir.path().join(ARCHIVE_FILE);
+    let archive_file = File::create(&archive_path).map_err(|err| {
+        BackupRouteError::Internal(format!("Unable to create archive file: {}", err))
+    })?;
+
+    let manifest = {
+        // Pause the admin service before scabbard, as circuit changes may create or remove
+        // services
+        let _admin_guard = admin_quiescer.quiesce().map_err(|err| {
+            BackupRouteError::Internal(format!("Unable to pause admin service: {}", err))
+        })?;
+        let _commit_guard = commit_gate.quiesce();
+
+        create_backup(&*store_factory, Path::new(state_dir), archive_file).map_err(|err| {
+            BackupRouteError::Internal(format!("Unable to create backup: {}", err))
+        })?
+    };
+
+    info!(
+        "Created backup with {} state file(s)",
+        manifest.state_files().len()
+    );
+
+    let file = File::open(&archive_path).map_err(|err| {
+        BackupRouteError::Internal(format!("Unable to open archive file: {}", err))
+    })?;
+
+    Ok(ArchiveStream {
+        file,
+        _temp_dir: temp_dir,
+    })
+}
+
+/// Streams a backup archive from its temporary file, which is removed once the stream is dropped.
+struct ArchiveStream {
+    file: File,
+    _temp_dir: TempDir,
+}
+
+impl Stream for ArchiveStream {
+    type Item = Bytes;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, Error> {
+        let mut chunk = vec![0; ARCHIVE_CHUNK_SIZE];
+        let read = self.file.read(&mut chunk)?;
+        if read == 0 {
+            return Ok(Async::Ready(None));
+        }
+
+        chunk.truncate(read);
+        Ok(Async::Ready(Some(Bytes::from(chunk))))
+    }
+}
@@ -13,6 +13,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "backup")]
+mod backup;
 mod status;
+#[cfg(feature = "tls-reload")]
+mod tls;
 
+#[cfg(feature = "backup")]
+pub use backup::*;
 pub use status::*;
+#[cfg(feature = "tls-reload")]
+pub use tls::*;
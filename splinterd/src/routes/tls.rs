@@ -0,0 +1,47 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use splinter::actix_web::{Error, HttpResponse};
+use splinter::futures::{Future, IntoFuture};
+#[cfg(feature = "authorization")]
+use splinter::rest_api::auth::authorization::Permission;
+use splinter::transport::socket::TlsReloader;
+
+#[cfg(feature = "authorization")]
+pub const TLS_RELOAD_PERMISSION: Permission = Permission::Check {
+    permission_id: "tls.reload",
+    permission_display_name: "TLS reload",
+    permission_description: "Allows the client to reload the node's TLS certificates and keys",
+};
+
+#[derive(Debug, Serialize)]
+struct TlsReloadResponse {
+    message: String,
+}
+
+pub fn reload_tls(reloader: &TlsReloader) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    match reloader.reload() {
+        Ok(()) => Box::new(HttpResponse::Ok().finish().into_future()),
+        Err(err) => {
+            error!("Unable to reload TLS certificates and keys: {}", err);
+            Box::new(
+                HttpResponse::InternalServerError()
+                    .json(TlsReloadResponse {
+                        message: format!("Unable to reload TLS certificates and keys: {}", err),
+                    })
+                    .into_future(),
+            )
+        }
+    }
+}
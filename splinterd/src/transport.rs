@@ -16,8 +16,16 @@ use std::fs;
 use std::path::Path;
 
 use splinter::transport::multi::MultiTransport;
+#[cfg(feature = "quic-transport")]
+use splinter::transport::quic::QuicTransport;
 use splinter::transport::socket::TcpTransport;
+#[cfg(feature = "tls-reload")]
+use splinter::transport::socket::TlsReloader;
 use splinter::transport::socket::TlsTransport;
+#[cfg(feature = "transport-compression")]
+use splinter::transport::socket::{CompressionAlgorithm, CompressionConfig};
+#[cfg(feature = "unix-transport")]
+use splinter::transport::socket::{UnixTransport, DEFAULT_SOCKET_PERMISSIONS};
 use splinter::transport::tls::{TlsConfig, TlsConfigBuilder};
 #[cfg(feature = "ws-transport")]
 use splinter::transport::ws::WsTransport;
@@ -28,41 +36,162 @@ use crate::error::GetTransportError;
 
 type SendableTransport = Box<dyn Transport + Send>;
 
-pub fn build_transport(config: &Config) -> Result<MultiTransport, GetTransportError> {
+/// The transport used by the daemon, along with any handles needed to manage it while running.
+pub struct DaemonTransport {
+    pub transport: MultiTransport,
+    /// Reloads the certificates and keys of the TLS, WebSocket and QUIC transports; `None` if TLS
+    /// is disabled
+    #[cfg(feature = "tls-reload")]
+    pub tls_reloader: Option<TlsReloader>,
+}
+
+pub fn build_transport(config: &Config) -> Result<DaemonTransport, GetTransportError> {
+    #[allow(unused_mut)]
+    let mut tcp_transport = TcpTransport::default();
+
+    #[cfg(feature = "transport-compression")]
+    {
+        if let Some(compression) = build_compression_config(config) {
+            tcp_transport = tcp_transport.with_compression(compression);
+        }
+    }
+
     let mut transports: Vec<SendableTransport> = vec![
         // add tcp transport
         // this will be default for endpoints without a prefix
-        Box::new(TcpTransport::default()),
+        Box::new(tcp_transport),
     ];
 
+    // add unix domain socket transport, for services on the same host
+    #[cfg(feature = "unix-transport")]
+    {
+        if uses_unix_endpoint(config) {
+            transports.push(Box::new(build_unix_transport(config)));
+        }
+    }
+
     // add web socket transport
 
+    #[cfg(feature = "tls-reload")]
+    let mut tls_reloader = None;
+
     // add tls transport
     if !config.no_tls() {
         let tls_config = build_tls_config(&config)?;
         validate_tls_config(&tls_config)?;
         print_tls_config(&tls_config)?;
 
-        transports.push(Box::new(TlsTransport::new(
+        #[allow(unused_mut)]
+        let mut tls_transport = TlsTransport::new(
             tls_config.ca_certs_file().to_owned(),
             tls_config.client_private_key_file().to_string(),
             tls_config.client_cert_file().to_string(),
             tls_config.server_private_key_file().to_string(),
             tls_config.server_cert_file().to_string(),
-        )?));
+        )?;
+
+        #[cfg(feature = "transport-compression")]
+        {
+            if let Some(compression) = build_compression_config(config) {
+                tls_transport = tls_transport.with_compression(compression);
+            }
+        }
+
+        // The WebSocket and QUIC transports use the same files, so they are reloaded along with
+        // the TLS transport
+        #[cfg(feature = "tls-reload")]
+        #[allow(unused_mut)]
+        let mut reloader = tls_transport.reloader();
+
+        transports.push(Box::new(tls_transport));
 
         #[cfg(feature = "ws-transport")]
-        transports.push(Box::new(WsTransport::new(Some(&tls_config)).map_err(
-            |e| {
+        {
+            let ws_transport = WsTransport::new(Some(&tls_config)).map_err(|e| {
                 GetTransportError::CertError(format!("Failed to create WebSocket transport: {}", e))
-            },
-        )?));
+            })?;
+
+            #[cfg(feature = "tls-reload")]
+            {
+                if let Some(ws_reloader) = ws_transport.reloader() {
+                    reloader = reloader.with_transport_reload(move || {
+                        ws_reloader
+                            .reload()
+                            .map_err(|err| format!("WebSocket transport: {}", err))
+                    });
+                }
+            }
+
+            transports.push(Box::new(ws_transport));
+        }
+
+        #[cfg(feature = "quic-transport")]
+        {
+            let quic_transport = QuicTransport::new(&tls_config).map_err(|e| {
+                GetTransportError::CertError(format!("Failed to create QUIC transport: {}", e))
+            })?;
+
+            #[cfg(feature = "tls-reload")]
+            {
+                let quic_reloader = quic_transport.reloader();
+                reloader = reloader.with_transport_reload(move || {
+                    quic_reloader
+                        .reload()
+                        .map_err(|err| format!("QUIC transport: {}", err))
+                });
+            }
+
+            transports.push(Box::new(quic_transport));
+        }
+
+        #[cfg(feature = "tls-reload")]
+        {
+            tls_reloader = Some(reloader);
+        }
     } else {
         #[cfg(feature = "ws-transport")]
         transports.push(Box::new(WsTransport::default()));
     }
 
-    Ok(MultiTransport::new(transports))
+    Ok(DaemonTransport {
+        transport: MultiTransport::new(transports),
+        #[cfg(feature = "tls-reload")]
+        tls_reloader,
+    })
+}
+
+/// Returns `true` if any endpoint the daemon listens on or connects to is a `unix://` endpoint.
+#[cfg(feature = "unix-transport")]
+fn uses_unix_endpoint(config: &Config) -> bool {
+    config
+        .network_endpoints()
+        .iter()
+        .chain(config.peers())
+        .map(String::as_str)
+        .chain(std::iter::once(config.service_endpoint()))
+        .any(|endpoint| endpoint.starts_with("unix://"))
+}
+
+#[cfg(feature = "unix-transport")]
+fn build_unix_transport(config: &Config) -> UnixTransport {
+    UnixTransport::default()
+        .with_permissions(
+            config
+                .unix_socket_permissions()
+                .unwrap_or(DEFAULT_SOCKET_PERMISSIONS),
+        )
+        .with_allowed_uids(config.unix_socket_allowed_uids().unwrap_or(&[]).to_vec())
+        .with_allowed_gids(config.unix_socket_allowed_gids().unwrap_or(&[]).to_vec())
+}
+
+/// Returns the compression offered on TCP and TLS connections, if it is enabled.
+#[cfg(feature = "transport-compression")]
+fn build_compression_config(config: &Config) -> Option<CompressionConfig> {
+    if config.transport_compression() {
+        Some(CompressionConfig::new(CompressionAlgorithm::Zstd))
+    } else {
+        None
+    }
 }
 
 fn build_tls_config(config: &Config) -> Result<TlsConfig, GetTransportError> {